@@ -60,6 +60,12 @@ impl EventStream {
     self.subscribers.add(subscriber)
   }
 
+  /// Adds a subscriber for a specific classifier without replaying buffered events.
+  #[must_use]
+  pub fn subscribe_no_replay_with_key(&mut self, key: ClassifierKey, subscriber: EventStreamSubscriberShared) -> u64 {
+    self.subscribers.add_with_key(key, subscriber)
+  }
+
   /// Removes the subscriber associated with the identifier.
   pub fn unsubscribe(&mut self, id: u64) {
     self.subscribers.remove(id);
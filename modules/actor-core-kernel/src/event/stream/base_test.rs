@@ -308,6 +308,22 @@ fn subscribe_no_replay_skips_buffered_events() {
   assert!(matches!(&recorded[0], EventStreamEvent::Log(event) if event.message() == "live"));
 }
 
+#[test]
+fn subscribe_no_replay_with_key_only_targets_matching_events() {
+  let mut stream = EventStream::default();
+  let events = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let subscriber = subscriber_handle(RecordingSubscriber::new(events));
+
+  let id = stream.subscribe_no_replay_with_key(ClassifierKey::Extension, subscriber);
+
+  let log = EventStreamEvent::Log(LogEvent::new(LogLevel::Info, String::from("log"), Duration::ZERO, None, None));
+  assert!(stream.publish_prepare(log).is_empty());
+  let extension = EventStreamEvent::Extension { name: String::from("cluster"), payload: AnyMessage::new(1_u32) };
+  let targets = stream.publish_prepare(extension);
+  assert_eq!(targets.len(), 1);
+  assert_eq!(targets[0].id(), id);
+}
+
 #[test]
 fn capacity_limits_buffer_size() {
   let stream = EventStreamShared::with_capacity(1);
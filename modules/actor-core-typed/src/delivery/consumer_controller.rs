@@ -104,6 +104,15 @@ impl ConsumerController {
     ConsumerControllerCommand::register_to_producer_controller(producer_controller)
   }
 
+  /// Creates a `SequencedMsg` command from a sequenced message that arrived
+  /// through an intermediary, such as a sharded entity.
+  #[must_use]
+  pub const fn sequenced_message<A>(msg: SequencedMessage<A>) -> ConsumerControllerCommand<A>
+  where
+    A: Clone + Send + Sync + 'static, {
+    ConsumerControllerCommand::sequenced_msg(msg)
+  }
+
   /// Creates a `Confirmed` command.
   #[must_use]
   pub const fn confirmed<A>() -> ConsumerControllerCommand<A>
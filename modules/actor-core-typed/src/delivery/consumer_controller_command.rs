@@ -67,6 +67,18 @@ where
     Self(ConsumerControllerCommandKind::DeliverThenStop)
   }
 
+  /// Returns the wrapped sequenced message when this is a `SequencedMsg` command.
+  ///
+  /// Lets a relay registered as the consumer of a producer controller forward the
+  /// wire-protocol message to a consumer controller it cannot address directly.
+  #[must_use]
+  pub const fn as_sequenced_message(&self) -> Option<&SequencedMessage<A>> {
+    match &self.0 {
+      | ConsumerControllerCommandKind::SequencedMsg(msg) => Some(msg),
+      | _ => None,
+    }
+  }
+
   /// Returns a reference to the command kind.
   pub(crate) const fn kind(&self) -> &ConsumerControllerCommandKind<A> {
     &self.0
@@ -1,9 +1,29 @@
 use alloc::string::String;
 
-use crate::delivery::ConsumerControllerCommand;
+use fraktor_actor_core_kernel_rs::actor::{Pid, actor_ref::NullSender};
+
+use crate::{
+  TypedActorRef,
+  delivery::{ConsumerController, ConsumerControllerCommand, ProducerControllerCommand, SequencedMessage},
+};
 
 #[test]
 fn command_is_clone() {
   fn _assert_clone<T: Clone>() {}
   _assert_clone::<ConsumerControllerCommand<String>>();
 }
+
+#[test]
+fn as_sequenced_message_returns_only_the_wrapped_sequenced_message() {
+  let producer_controller = TypedActorRef::<ProducerControllerCommand<u32>>::from_untyped(
+    crate::test_support::actor_ref_with_sender(Pid::new(1, 0), NullSender),
+  );
+  let sequenced = SequencedMessage::new(String::from("producer"), 3, 7_u32, true, false, producer_controller);
+
+  let command = ConsumerController::sequenced_message(sequenced);
+
+  let unwrapped = command.as_sequenced_message().expect("sequenced message");
+  assert_eq!(unwrapped.seq_nr(), 3);
+  assert_eq!(*unwrapped.message(), 7);
+  assert!(ConsumerController::confirmed::<u32>().as_sequenced_message().is_none());
+}
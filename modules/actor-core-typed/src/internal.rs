@@ -9,6 +9,7 @@ mod behavior_signal_interceptor;
 mod guardian_startup_actor;
 mod guardian_startup_start;
 mod receive_timeout_config;
+mod receptionist_behavior_provider_id;
 mod typed_actor_adapter;
 mod typed_scheduler;
 mod typed_scheduler_guard;
@@ -20,6 +21,7 @@ pub(crate) use behavior_signal_interceptor::BehaviorSignalInterceptor;
 pub(crate) use guardian_startup_actor::GuardianStartupActor;
 pub(crate) use guardian_startup_start::GuardianStartupStart;
 pub(crate) use receive_timeout_config::ReceiveTimeoutConfig;
+pub(crate) use receptionist_behavior_provider_id::ReceptionistBehaviorProviderId;
 pub(crate) use typed_actor_adapter::TypedActorAdapter;
 pub(crate) use typed_scheduler::TypedScheduler;
 pub(crate) use typed_scheduler_guard::TypedSchedulerGuard;
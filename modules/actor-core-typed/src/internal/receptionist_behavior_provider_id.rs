@@ -0,0 +1,25 @@
+//! Identifier used to register the receptionist behavior provider extension.
+
+use fraktor_actor_core_kernel_rs::{actor::extension::ExtensionId, system::ActorSystem};
+
+use crate::receptionist::{Receptionist, ReceptionistBehaviorProvider};
+
+/// Identifier for the [`ReceptionistBehaviorProvider`] extension.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ReceptionistBehaviorProviderId;
+
+impl ReceptionistBehaviorProviderId {
+  /// Creates a new identifier instance.
+  #[must_use]
+  pub(crate) const fn new() -> Self {
+    Self
+  }
+}
+
+impl ExtensionId for ReceptionistBehaviorProviderId {
+  type Ext = ReceptionistBehaviorProvider;
+
+  fn create_extension(&self, _system: &ActorSystem) -> Self::Ext {
+    ReceptionistBehaviorProvider::new(|_system| Receptionist::behavior())
+  }
+}
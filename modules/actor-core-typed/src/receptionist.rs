@@ -3,7 +3,9 @@
 mod deregistered;
 mod extension;
 mod listing;
+mod receptionist_behavior_provider;
 mod receptionist_command;
+mod receptionist_setup;
 mod registered;
 mod service_key;
 #[cfg(test)]
@@ -13,6 +15,8 @@ mod tests;
 pub use deregistered::Deregistered;
 pub use extension::{Receptionist, SYSTEM_RECEPTIONIST_TOP_LEVEL};
 pub use listing::Listing;
+pub use receptionist_behavior_provider::ReceptionistBehaviorProvider;
 pub use receptionist_command::ReceptionistCommand;
+pub use receptionist_setup::ReceptionistSetup;
 pub use registered::Registered;
 pub use service_key::ServiceKey;
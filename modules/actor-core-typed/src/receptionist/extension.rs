@@ -23,7 +23,8 @@ use fraktor_utils_core_rs::sync::{ArcShared, DefaultMutex, SharedLock, shared::S
 
 use super::{Deregistered, Listing, ReceptionistCommand, Registered, ServiceKey};
 use crate::{
-  TypedActorRef, TypedActorSystem, TypedProps, behavior::Behavior, dsl::Behaviors, message_and_signals::BehaviorSignal,
+  TypedActorRef, TypedActorSystem, TypedProps, actor::TypedActorContext, behavior::Behavior, dsl::Behaviors,
+  internal::ReceptionistBehaviorProviderId, message_and_signals::BehaviorSignal,
 };
 
 /// Composite key for internal registry lookups.
@@ -194,6 +195,75 @@ impl Receptionist {
     })
   }
 
+  /// Returns the behavior of the system receptionist top-level actor.
+  ///
+  /// The top-level actor forwards every command to a child running the behavior of the installed
+  /// [`ReceptionistBehaviorProvider`](super::ReceptionistBehaviorProvider), falling back to
+  /// [`Receptionist::behavior`]. The child is watched; when it terminates, the next command is
+  /// handled by a newly spawned child.
+  pub(crate) fn system_behavior() -> Behavior<ReceptionistCommand> {
+    let delegate: SharedLock<Option<TypedActorRef<ReceptionistCommand>>> =
+      SharedLock::new_with_driver::<DefaultMutex<_>>(None);
+    let delegate_for_signal = delegate.clone();
+
+    Behaviors::receive_message(move |ctx, cmd: &ReceptionistCommand| {
+      // 拡張はブートストラップ後にインストールされるため、最初のコマンドで実装を決める
+      let existing = delegate.with_lock(|delegate| delegate.clone());
+      let mut target = match existing {
+        | Some(target) => target,
+        | None => {
+          let target = Self::spawn_delegate(ctx)?;
+          delegate.with_lock(|delegate| *delegate = Some(target.clone()));
+          target
+        },
+      };
+      target.tell(cmd.clone());
+      Ok(Behaviors::same())
+    })
+    .receive_signal(move |ctx, signal| {
+      let BehaviorSignal::Terminated(terminated) = signal else {
+        return Ok(Behaviors::same());
+      };
+      let terminated_pid = terminated.pid();
+      let cleared = delegate_for_signal.with_lock(|delegate| {
+        let matches = delegate.as_ref().is_some_and(|target| target.pid() == terminated_pid);
+        if matches {
+          *delegate = None;
+        }
+        matches
+      });
+      if cleared {
+        // 停止直後に再生成すると即時停止する実装で無限に再生成するため、次のコマンドで再生成する
+        ctx.system().emit_log(
+          LogLevel::Warn,
+          alloc::format!("receptionist delegate {terminated_pid:?} terminated; a new one handles the next command"),
+          Some(ctx.pid()),
+          None,
+        );
+      }
+      Ok(Behaviors::same())
+    })
+  }
+
+  fn spawn_delegate(
+    ctx: &mut TypedActorContext<'_, ReceptionistCommand>,
+  ) -> Result<TypedActorRef<ReceptionistCommand>, ActorError> {
+    let typed_system = ctx.system();
+    let system = typed_system.as_untyped();
+    let behavior = match system.extended().extension(&ReceptionistBehaviorProviderId::new()) {
+      | Some(provider) => provider.behavior(system),
+      | None => Self::behavior(),
+    };
+    let child = ctx
+      .spawn_anonymous(&behavior)
+      .map_err(|error| ActorError::recoverable(alloc::format!("receptionist delegate spawn failed: {:?}", error)))?;
+    let child = child.actor_ref();
+    ctx
+      .watch(&child)
+      .map_err(|error| ActorError::recoverable(alloc::format!("receptionist delegate watch failed: {:?}", error)))?;
+    Ok(child)
+  }
+
   /// Creates a [`Register`](ReceptionistCommand::Register) command from a typed service key.
   #[must_use]
   pub fn register<M>(key: &ServiceKey<M>, actor_ref: TypedActorRef<M>) -> ReceptionistCommand
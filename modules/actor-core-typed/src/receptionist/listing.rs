@@ -18,6 +18,7 @@ pub struct Listing {
   service_id: String,
   type_id: TypeId,
   refs: Vec<ActorRef>,
+  all_refs: Vec<ActorRef>,
   services_were_added_or_removed: bool,
 }
 
@@ -25,7 +26,17 @@ impl Listing {
   /// Creates a new listing.
   #[must_use]
   pub fn new(service_id: impl Into<String>, type_id: TypeId, refs: Vec<ActorRef>) -> Self {
-    Self { service_id: service_id.into(), type_id, refs, services_were_added_or_removed: true }
+    let all_refs = refs.clone();
+    Self { service_id: service_id.into(), type_id, refs, all_refs, services_were_added_or_removed: true }
+  }
+
+  /// Sets every registered reference, including those on unreachable cluster members.
+  ///
+  /// The references passed to [`Listing::new`] are the reachable subset of these.
+  #[must_use]
+  pub fn with_all_refs(mut self, all_refs: Vec<ActorRef>) -> Self {
+    self.all_refs = all_refs;
+    self
   }
 
   /// Sets whether this listing reflects added or removed services.
@@ -53,6 +64,12 @@ impl Listing {
     &self.refs
   }
 
+  /// Returns every registered actor reference, including those on unreachable cluster members.
+  #[must_use]
+  pub fn all_refs(&self) -> &[ActorRef] {
+    &self.all_refs
+  }
+
   /// Returns typed actor references after validating the requested message type.
   ///
   /// # Errors
@@ -101,8 +118,10 @@ impl Listing {
 
   /// Returns all typed actor references for the given service key.
   ///
-  /// In the current non-clustered implementation this is identical to
-  /// [`Self::service_instances`].
+  /// Unlike [`Self::service_instances`], this includes references on unreachable cluster members.
+  /// The node-local receptionist never reports unreachable references, so both are identical there.
+  ///
+  /// Corresponds to Pekko's `Listing.allServiceInstances`.
   ///
   /// # Errors
   ///
@@ -110,7 +129,10 @@ impl Listing {
   pub fn all_service_instances<M>(&self, key: &ServiceKey<M>) -> Result<BTreeSet<TypedActorRef<M>>, ActorError>
   where
     M: Send + Sync + 'static, {
-    self.service_instances(key)
+    if !self.is_for_key(key) {
+      return Err(ActorError::recoverable("listing key mismatch"));
+    }
+    Ok(self.all_refs.iter().map(|r| TypedActorRef::from_untyped(r.clone())).collect())
   }
 
   /// Returns whether the listing reflects added or removed services.
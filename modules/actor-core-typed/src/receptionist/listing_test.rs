@@ -183,3 +183,21 @@ fn services_were_added_or_removed_can_be_set_to_false() {
   let listing = Listing::new("svc", TypeId::of::<u32>(), vec![]).with_services_were_added_or_removed(false);
   assert!(!listing.services_were_added_or_removed());
 }
+
+#[test]
+fn all_service_instances_include_unreachable_refs() {
+  use fraktor_actor_core_kernel_rs::actor::{Pid, actor_ref::NullSender};
+
+  let reachable = crate::test_support::actor_ref_with_sender(Pid::new(1, 0), NullSender);
+  let unreachable = crate::test_support::actor_ref_with_sender(Pid::new(2, 0), NullSender);
+  let key = ServiceKey::<u32>::new("svc");
+  let listing =
+    Listing::new("svc", TypeId::of::<u32>(), vec![reachable.clone()]).with_all_refs(vec![reachable, unreachable]);
+
+  assert_eq!(listing.service_instances(&key).expect("reachable").len(), 1);
+  let all = listing.all_service_instances(&key).expect("all");
+  assert_eq!(all.into_iter().map(|actor_ref| actor_ref.pid()).collect::<Vec<_>>(), vec![
+    Pid::new(1, 0),
+    Pid::new(2, 0)
+  ]);
+}
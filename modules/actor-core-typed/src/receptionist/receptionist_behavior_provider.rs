@@ -0,0 +1,37 @@
+//! Factory for the behavior backing the system receptionist.
+
+use fraktor_actor_core_kernel_rs::{actor::extension::Extension, system::ActorSystem};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::ReceptionistCommand;
+use crate::Behavior;
+
+type ReceptionistBehaviorFactory = dyn Fn(&ActorSystem) -> Behavior<ReceptionistCommand> + Send + Sync + 'static;
+
+/// Creates the behavior that implements the system receptionist.
+///
+/// This is the fraktor equivalent of Pekko's `ReceptionistBehaviorProvider`. Install one through
+/// [`ReceptionistSetup`](super::ReceptionistSetup) to replace the node-local receptionist, e.g.
+/// with a cluster receptionist.
+#[derive(Clone)]
+pub struct ReceptionistBehaviorProvider {
+  factory: ArcShared<ReceptionistBehaviorFactory>,
+}
+
+impl ReceptionistBehaviorProvider {
+  /// Creates a provider from a behavior factory.
+  #[must_use]
+  pub fn new<F>(factory: F) -> Self
+  where
+    F: Fn(&ActorSystem) -> Behavior<ReceptionistCommand> + Send + Sync + 'static, {
+    Self { factory: ArcShared::new(factory) }
+  }
+
+  /// Creates the receptionist behavior for the given actor system.
+  #[must_use]
+  pub fn behavior(&self, system: &ActorSystem) -> Behavior<ReceptionistCommand> {
+    (self.factory)(system)
+  }
+}
+
+impl Extension for ReceptionistBehaviorProvider {}
@@ -19,6 +19,7 @@ use crate::{
 /// The Receptionist maintains a registry of actors indexed by a
 /// `(service_id, TypeId)` pair. Subscribers are notified whenever
 /// the set of registrations for a given key changes.
+#[derive(Clone)]
 pub enum ReceptionistCommand {
   /// Register an actor under a service key.
   Register {
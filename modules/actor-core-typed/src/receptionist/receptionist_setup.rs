@@ -0,0 +1,55 @@
+//! Setup wrapper for replacing the system receptionist implementation.
+
+#[cfg(test)]
+#[path = "receptionist_setup_test.rs"]
+mod tests;
+
+use core::any::TypeId;
+
+use fraktor_actor_core_kernel_rs::{
+  actor::extension::{ExtensionId, ExtensionInstaller},
+  system::{ActorSystem, ActorSystemBuildError},
+};
+
+use super::{ReceptionistBehaviorProvider, ReceptionistCommand};
+use crate::{Behavior, ExtensionSetup, internal::ReceptionistBehaviorProviderId};
+
+/// Replaces the node-local receptionist during actor-system startup.
+///
+/// This is the fraktor equivalent of Pekko's `ReceptionistSetup`. The system receptionist
+/// delegates every [`ReceptionistCommand`] to an actor running the behavior created by the
+/// provider, so [`TypedActorSystem::receptionist`](crate::TypedActorSystem::receptionist) and
+/// [`Receptionist::get`](super::Receptionist::get) keep working unchanged.
+#[derive(Clone)]
+pub struct ReceptionistSetup {
+  inner: ExtensionSetup<ReceptionistBehaviorProviderId>,
+}
+
+impl ReceptionistSetup {
+  /// Creates a new setup with a custom receptionist behavior factory.
+  #[must_use]
+  pub fn new<F>(create_behavior: F) -> Self
+  where
+    F: Fn(&ActorSystem) -> Behavior<ReceptionistCommand> + Send + Sync + 'static, {
+    let provider = ReceptionistBehaviorProvider::new(create_behavior);
+    Self { inner: ExtensionSetup::new(ReceptionistBehaviorProviderId::new(), move |_system| provider.clone()) }
+  }
+}
+
+impl ExtensionId for ReceptionistSetup {
+  type Ext = ReceptionistBehaviorProvider;
+
+  fn create_extension(&self, system: &ActorSystem) -> Self::Ext {
+    self.inner.create_extension(system)
+  }
+
+  fn id(&self) -> TypeId {
+    self.inner.id()
+  }
+}
+
+impl ExtensionInstaller for ReceptionistSetup {
+  fn install(&self, system: &ActorSystem) -> Result<(), ActorSystemBuildError> {
+    self.inner.install(system)
+  }
+}
@@ -0,0 +1,90 @@
+use alloc::{string::String, vec::Vec};
+use core::hint::spin_loop;
+
+use fraktor_actor_core_kernel_rs::actor::{extension::ExtensionInstallers, setup::ActorSystemConfig};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+use crate::{
+  TypedActorSystem, TypedProps,
+  dsl::Behaviors,
+  receptionist::{Listing, Receptionist, ReceptionistCommand, ReceptionistSetup, ServiceKey},
+};
+
+fn wait_until(mut condition: impl FnMut() -> bool) {
+  for _ in 0..10_000 {
+    if condition() {
+      return;
+    }
+    spin_loop();
+  }
+  assert!(condition());
+}
+
+#[test]
+fn system_receptionist_delegates_to_the_installed_behavior() {
+  let received = ArcShared::new(SpinSyncMutex::new(Vec::<String>::new()));
+  let setup = ReceptionistSetup::new({
+    let received = received.clone();
+    move |_system| {
+      let received = received.clone();
+      Behaviors::receive_message(move |_ctx, command: &ReceptionistCommand| {
+        if let ReceptionistCommand::Find { service_id, .. } = command {
+          received.lock().push(service_id.clone());
+        }
+        Ok(Behaviors::same())
+      })
+    }
+  });
+  let installers = ExtensionInstallers::default().with_extension_installer(setup);
+  let config = ActorSystemConfig::new(crate::test_support::test_tick_driver()).with_extension_installers(installers);
+  let guardian_props = TypedProps::<u32>::from_behavior_factory(Behaviors::ignore);
+  let system = TypedActorSystem::<u32>::create_from_props(&guardian_props, config).expect("system");
+
+  let listing_props = TypedProps::<Listing>::from_behavior_factory(Behaviors::ignore);
+  let reply_to = system.as_untyped().actor_of(listing_props.to_untyped()).expect("spawn reply target");
+  let reply_to = crate::TypedActorRef::<Listing>::from_untyped(reply_to.into_actor_ref());
+  let key = ServiceKey::<u32>::new("custom-service");
+  system.receptionist().tell(Receptionist::find(&key, reply_to));
+
+  wait_until(|| received.lock().len() == 1);
+  assert_eq!(received.lock()[0], "custom-service");
+  system.terminate().expect("terminate");
+}
+
+#[test]
+fn system_receptionist_replaces_a_terminated_delegate() {
+  let spawned = ArcShared::new(SpinSyncMutex::new(0_usize));
+  let received = ArcShared::new(SpinSyncMutex::new(Vec::<String>::new()));
+  let setup = ReceptionistSetup::new({
+    let spawned = spawned.clone();
+    let received = received.clone();
+    move |_system| {
+      *spawned.lock() += 1;
+      let received = received.clone();
+      Behaviors::receive_message(move |_ctx, command: &ReceptionistCommand| {
+        let ReceptionistCommand::Find { service_id, .. } = command else {
+          return Ok(Behaviors::same());
+        };
+        received.lock().push(service_id.clone());
+        if service_id == "stop" { Ok(Behaviors::stopped()) } else { Ok(Behaviors::same()) }
+      })
+    }
+  });
+  let installers = ExtensionInstallers::default().with_extension_installer(setup);
+  let config = ActorSystemConfig::new(crate::test_support::test_tick_driver()).with_extension_installers(installers);
+  let guardian_props = TypedProps::<u32>::from_behavior_factory(Behaviors::ignore);
+  let system = TypedActorSystem::<u32>::create_from_props(&guardian_props, config).expect("system");
+  let listing_props = TypedProps::<Listing>::from_behavior_factory(Behaviors::ignore);
+  let reply_to = system.as_untyped().actor_of(listing_props.to_untyped()).expect("spawn reply target");
+  let reply_to = crate::TypedActorRef::<Listing>::from_untyped(reply_to.into_actor_ref());
+
+  system.receptionist().tell(Receptionist::find(&ServiceKey::<u32>::new("stop"), reply_to.clone()));
+  wait_until(|| received.lock().len() == 1);
+  // Terminated の処理前に送ったコマンドは停止した delegate に届かないため、届くまで送り直す
+  wait_until(|| {
+    system.receptionist().tell(Receptionist::find(&ServiceKey::<u32>::new("after-stop"), reply_to.clone()));
+    received.lock().iter().any(|service_id| service_id == "after-stop")
+  });
+  assert_eq!(*spawned.lock(), 2);
+  system.terminate().expect("terminate");
+}
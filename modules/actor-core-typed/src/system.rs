@@ -180,7 +180,7 @@ fn build_event_stream_ref(system: &ActorSystem) -> TypedActorRef<EventStreamComm
 
 fn install_system_receptionist(system: &ActorSystem) -> Result<(), SpawnError> {
   let receptionist_props =
-    TypedProps::<ReceptionistCommand>::from_behavior_factory(Receptionist::behavior).into_untyped();
+    TypedProps::<ReceptionistCommand>::from_behavior_factory(Receptionist::system_behavior).into_untyped();
   let receptionist_props = receptionist_props.with_name(SYSTEM_RECEPTIONIST_TOP_LEVEL);
   system.extended().spawn_system_top_level_actor(&receptionist_props, SYSTEM_RECEPTIONIST_TOP_LEVEL)?;
   Ok(())
@@ -0,0 +1,12 @@
+//! Activation storage adaptors for remembered grains.
+
+mod activation_entry_wire;
+mod durable_state_activation_storage;
+mod local_activation_storage;
+mod remembered_activation_storage;
+mod remembered_grains_store;
+
+pub use durable_state_activation_storage::DurableStateActivationStorage;
+pub use local_activation_storage::LocalActivationStorage;
+pub use remembered_activation_storage::RememberedActivationStorage;
+pub use remembered_grains_store::RememberedGrainsStore;
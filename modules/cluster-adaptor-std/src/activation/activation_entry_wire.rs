@@ -0,0 +1,46 @@
+//! Wire representation of a stored activation entry.
+
+use alloc::{string::String, vec::Vec};
+
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivationEntry, ActivationRecord},
+  grain::GrainKey,
+};
+use serde::{Deserialize, Serialize};
+
+/// Wire representation of an activation entry together with its grain key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ActivationEntryWire {
+  /// Grain key string.
+  pub key:         String,
+  /// Owner authority.
+  pub owner:       String,
+  /// Activated PID string.
+  pub pid:         String,
+  /// Optional snapshot bytes.
+  pub snapshot:    Option<Vec<u8>>,
+  /// Application-level version.
+  pub version:     u64,
+  /// Observation timestamp in seconds.
+  pub observed_at: u64,
+}
+
+impl ActivationEntryWire {
+  /// Builds the wire representation of `entry` stored for `key`.
+  pub(crate) fn from_entry(key: &GrainKey, entry: &ActivationEntry) -> Self {
+    Self {
+      key:         String::from(key.value()),
+      owner:       entry.owner.clone(),
+      pid:         entry.record.pid.clone(),
+      snapshot:    entry.record.snapshot.clone(),
+      version:     entry.record.version,
+      observed_at: entry.observed_at,
+    }
+  }
+
+  /// Converts the wire representation back into a key and entry.
+  pub(crate) fn into_entry(self) -> (GrainKey, ActivationEntry) {
+    let record = ActivationRecord::new(self.pid, self.snapshot, self.version);
+    (GrainKey::new(self.key), ActivationEntry { owner: self.owner, record, observed_at: self.observed_at })
+  }
+}
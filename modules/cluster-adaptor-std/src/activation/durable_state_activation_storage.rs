@@ -0,0 +1,99 @@
+//! Activation storage backed by a persistence durable state store.
+
+#[cfg(test)]
+#[path = "durable_state_activation_storage_test.rs"]
+mod tests;
+
+use alloc::{
+  boxed::Box,
+  collections::BTreeMap,
+  string::{String, ToString},
+  vec::Vec,
+};
+use core::{future::Future, pin::Pin};
+
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivationEntry, ActivationStorage, ActivationStorageError},
+  grain::GrainKey,
+};
+use fraktor_persistence_core_kernel_rs::state::DurableStateStore;
+
+type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ActivationStorageError>> + Send + 'a>>;
+
+/// Activation storage keeping every remembered grain in one durable state object.
+///
+/// The object is read, modified and upserted with its current revision, so concurrent
+/// writers sharing the same persistence identifier are rejected by the store instead of
+/// overwriting each other.
+pub struct DurableStateActivationStorage {
+  store:          Box<dyn DurableStateStore<BTreeMap<GrainKey, ActivationEntry>>>,
+  persistence_id: String,
+}
+
+impl DurableStateActivationStorage {
+  /// Creates a storage writing the durable state object `persistence_id` to `store`.
+  #[must_use]
+  pub fn new(
+    store: Box<dyn DurableStateStore<BTreeMap<GrainKey, ActivationEntry>>>,
+    persistence_id: impl Into<String>,
+  ) -> Self {
+    Self { store, persistence_id: persistence_id.into() }
+  }
+
+  /// Returns the persistence identifier of the durable state object.
+  #[must_use]
+  pub fn persistence_id(&self) -> &str {
+    &self.persistence_id
+  }
+
+  async fn read(&self) -> Result<(BTreeMap<GrainKey, ActivationEntry>, u64), ActivationStorageError> {
+    let result = self.store.get_object(&self.persistence_id).await.map_err(|error| failed(&error))?;
+    let revision = result.revision();
+    Ok((result.into_value().unwrap_or_default(), revision))
+  }
+
+  async fn update<F>(&mut self, apply: F) -> Result<(), ActivationStorageError>
+  where
+    F: FnOnce(&mut BTreeMap<GrainKey, ActivationEntry>) + Send, {
+    let (mut entries, revision) = self.read().await?;
+    apply(&mut entries);
+    self.store.upsert_object(&self.persistence_id, revision, entries, None).await.map_err(|error| failed(&error))
+  }
+}
+
+impl ActivationStorage for DurableStateActivationStorage {
+  type LoadAllFuture<'a> = StorageFuture<'a, Vec<(GrainKey, ActivationEntry)>>;
+  type LoadFuture<'a> = StorageFuture<'a, Option<ActivationEntry>>;
+  type RemoveFuture<'a> = StorageFuture<'a, ()>;
+  type StoreFuture<'a> = StorageFuture<'a, ()>;
+
+  fn load<'a>(&'a mut self, key: GrainKey) -> Self::LoadFuture<'a> {
+    Box::pin(async move {
+      let (mut entries, _) = self.read().await?;
+      Ok(entries.remove(&key))
+    })
+  }
+
+  fn store<'a>(&'a mut self, key: GrainKey, entry: ActivationEntry) -> Self::StoreFuture<'a> {
+    Box::pin(self.update(move |entries| {
+      entries.insert(key, entry);
+    }))
+  }
+
+  fn remove<'a>(&'a mut self, key: GrainKey) -> Self::RemoveFuture<'a> {
+    Box::pin(self.update(move |entries| {
+      entries.remove(&key);
+    }))
+  }
+
+  fn load_all<'a>(&'a mut self) -> Self::LoadAllFuture<'a> {
+    Box::pin(async move {
+      let (entries, _) = self.read().await?;
+      Ok(entries.into_iter().collect())
+    })
+  }
+}
+
+fn failed(error: &impl ToString) -> ActivationStorageError {
+  ActivationStorageError::Failed { reason: error.to_string() }
+}
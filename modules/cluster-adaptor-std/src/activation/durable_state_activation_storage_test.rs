@@ -0,0 +1,96 @@
+use alloc::{
+  boxed::Box,
+  collections::BTreeMap,
+  string::{String, ToString},
+};
+
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivationEntry, ActivationRecord, ActivationStorage},
+  grain::GrainKey,
+};
+use fraktor_persistence_core_kernel_rs::state::{DurableStateError, DurableStateStore, GetObjectResult};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+use super::DurableStateActivationStorage;
+
+type Objects = ArcShared<SpinSyncMutex<BTreeMap<String, (BTreeMap<GrainKey, ActivationEntry>, u64)>>>;
+
+struct TestDurableStateStore {
+  objects: Objects,
+}
+
+impl DurableStateStore<BTreeMap<GrainKey, ActivationEntry>> for TestDurableStateStore {
+  fn get_object<'a>(
+    &'a self,
+    persistence_id: &'a str,
+  ) -> core::pin::Pin<
+    Box<
+      dyn Future<Output = Result<GetObjectResult<BTreeMap<GrainKey, ActivationEntry>>, DurableStateError>> + Send + 'a,
+    >,
+  > {
+    let result = match self.objects.lock().get(persistence_id) {
+      | Some((value, revision)) => GetObjectResult::new(Some(value.clone()), *revision),
+      | None => GetObjectResult::empty(),
+    };
+    Box::pin(async move { Ok(result) })
+  }
+
+  fn upsert_object<'a>(
+    &'a mut self,
+    persistence_id: &'a str,
+    expected_revision: u64,
+    object: BTreeMap<GrainKey, ActivationEntry>,
+    _tag: Option<&'a str>,
+  ) -> core::pin::Pin<Box<dyn Future<Output = Result<(), DurableStateError>> + Send + 'a>> {
+    let mut objects = self.objects.lock();
+    let actual_revision = objects.get(persistence_id).map_or(0, |(_, revision)| *revision);
+    let result = if actual_revision == expected_revision {
+      objects.insert(persistence_id.to_string(), (object, expected_revision + 1));
+      Ok(())
+    } else {
+      Err(DurableStateError::UpsertRevision {
+        persistence_id: persistence_id.to_string(),
+        expected_revision,
+        actual_revision,
+      })
+    };
+    Box::pin(async move { result })
+  }
+
+  fn delete_object<'a>(
+    &'a mut self,
+    persistence_id: &'a str,
+    _expected_revision: u64,
+  ) -> core::pin::Pin<Box<dyn Future<Output = Result<(), DurableStateError>> + Send + 'a>> {
+    self.objects.lock().remove(persistence_id);
+    Box::pin(async { Ok(()) })
+  }
+}
+
+fn storage(objects: &Objects) -> DurableStateActivationStorage {
+  DurableStateActivationStorage::new(Box::new(TestDurableStateStore { objects: objects.clone() }), "remembered-grains")
+}
+
+fn entry(pid: &str) -> ActivationEntry {
+  ActivationEntry {
+    owner:       String::from("node1:8080"),
+    record:      ActivationRecord::new(String::from(pid), None, 0),
+    observed_at: 1,
+  }
+}
+
+#[tokio::test]
+async fn entries_are_shared_through_the_durable_state_object() {
+  let objects: Objects = ArcShared::new(SpinSyncMutex::new(BTreeMap::new()));
+  let a = GrainKey::new(String::from("user/a"));
+  let b = GrainKey::new(String::from("user/b"));
+  let mut writer = storage(&objects);
+  writer.store(a.clone(), entry("a")).await.expect("store a");
+  writer.store(b.clone(), entry("b")).await.expect("store b");
+  writer.remove(a.clone()).await.expect("remove a");
+
+  let mut reader = storage(&objects);
+  assert!(reader.load(a).await.expect("load a").is_none());
+  assert_eq!(reader.load_all().await.expect("load all"), [(b, entry("b"))]);
+  assert_eq!(objects.lock().get("remembered-grains").map(|(_, revision)| *revision), Some(3));
+}
@@ -0,0 +1,156 @@
+//! Filesystem-backed activation storage for remembered grains.
+
+#[cfg(test)]
+#[path = "local_activation_storage_test.rs"]
+mod tests;
+
+use alloc::{format, string::String, vec::Vec};
+use core::future::{Ready, ready};
+use std::{
+  fs::{self, File},
+  io::{ErrorKind, Write},
+  path::{Path, PathBuf},
+};
+
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivationEntry, ActivationStorage, ActivationStorageError},
+  grain::GrainKey,
+};
+use fraktor_utils_core_rs::sync::{ArcShared, DefaultMutex, SharedLock};
+
+use super::activation_entry_wire::ActivationEntryWire;
+
+const ENTRY_FILE_PREFIX: &str = "grain-";
+const TEMP_FILE_SUFFIX: &str = ".tmp";
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Activation storage keeping one atomically replaced file per grain key.
+///
+/// Entries survive a restart of the member, so remembered grains can be restored with
+/// [`ClusterExtension::restore_remembered_grains`](fraktor_cluster_core_kernel_rs::extension::ClusterExtension::restore_remembered_grains).
+/// Clones share the same directory.
+#[derive(Clone)]
+pub struct LocalActivationStorage {
+  directory:  ArcShared<PathBuf>,
+  write_lock: SharedLock<()>,
+}
+
+impl LocalActivationStorage {
+  /// Opens a storage below `directory`, creating the directory when missing.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ActivationStorageError::Failed`] when the directory cannot be prepared.
+  pub fn open(directory: PathBuf) -> Result<Self, ActivationStorageError> {
+    fs::create_dir_all(&directory)
+      .map_err(|error| failed(format!("create activation storage directory {}: {error}", directory.display())))?;
+    Ok(Self { directory: ArcShared::new(directory), write_lock: SharedLock::new_with_driver::<DefaultMutex<_>>(()) })
+  }
+
+  /// Returns the directory entries are stored in.
+  #[must_use]
+  pub fn directory(&self) -> &Path {
+    &self.directory
+  }
+
+  fn entry_path(&self, key: &GrainKey) -> PathBuf {
+    self.directory.join(format!("{ENTRY_FILE_PREFIX}{}", encode_component(key.value())))
+  }
+
+  fn read_entry(path: &Path) -> Result<Option<(GrainKey, ActivationEntry)>, ActivationStorageError> {
+    let bytes = match fs::read(path) {
+      | Ok(bytes) => bytes,
+      | Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+      | Err(error) => return Err(failed(format!("read activation file {}: {error}", path.display()))),
+    };
+    let wire: ActivationEntryWire = postcard::from_bytes(&bytes)
+      .map_err(|error| failed(format!("decode activation file {}: {error}", path.display())))?;
+    Ok(Some(wire.into_entry()))
+  }
+
+  fn write_entry(&self, key: &GrainKey, entry: &ActivationEntry) -> Result<(), ActivationStorageError> {
+    let bytes = postcard::to_allocvec(&ActivationEntryWire::from_entry(key, entry))
+      .map_err(|error| failed(format!("encode activation of {}: {error}", key.value())))?;
+    let path = self.entry_path(key);
+    let mut temp_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    temp_name.push(TEMP_FILE_SUFFIX);
+    let temp_path = path.with_file_name(temp_name);
+    self.write_lock.with_lock(|()| {
+      let mut file = File::create(&temp_path)
+        .map_err(|error| failed(format!("create temp file {}: {error}", temp_path.display())))?;
+      file
+        .write_all(&bytes)
+        .and_then(|()| file.sync_all())
+        .map_err(|error| failed(format!("write temp file {}: {error}", temp_path.display())))?;
+      drop(file);
+      fs::rename(&temp_path, &path)
+        .map_err(|error| failed(format!("replace activation file {}: {error}", path.display())))
+    })
+  }
+
+  fn remove_entry(&self, key: &GrainKey) -> Result<(), ActivationStorageError> {
+    let path = self.entry_path(key);
+    self.write_lock.with_lock(|()| match fs::remove_file(&path) {
+      | Ok(()) => Ok(()),
+      | Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+      | Err(error) => Err(failed(format!("remove activation file {}: {error}", path.display()))),
+    })
+  }
+
+  fn read_all(&self) -> Result<Vec<(GrainKey, ActivationEntry)>, ActivationStorageError> {
+    let entries = fs::read_dir(self.directory.as_path())
+      .map_err(|error| failed(format!("read activation storage directory {}: {error}", self.directory.display())))?;
+    let mut loaded = Vec::new();
+    for entry in entries {
+      let path = entry.map_err(|error| failed(format!("read activation storage entry: {error}")))?.path();
+      let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+      if !name.starts_with(ENTRY_FILE_PREFIX) || name.ends_with(TEMP_FILE_SUFFIX) {
+        continue;
+      }
+      loaded.extend(Self::read_entry(&path)?);
+    }
+    loaded.sort_by(|(left, _), (right, _)| left.cmp(right));
+    Ok(loaded)
+  }
+}
+
+impl ActivationStorage for LocalActivationStorage {
+  type LoadAllFuture<'a> = Ready<Result<Vec<(GrainKey, ActivationEntry)>, ActivationStorageError>>;
+  type LoadFuture<'a> = Ready<Result<Option<ActivationEntry>, ActivationStorageError>>;
+  type RemoveFuture<'a> = Ready<Result<(), ActivationStorageError>>;
+  type StoreFuture<'a> = Ready<Result<(), ActivationStorageError>>;
+
+  fn load<'a>(&'a mut self, key: GrainKey) -> Self::LoadFuture<'a> {
+    ready(Self::read_entry(&self.entry_path(&key)).map(|loaded| loaded.map(|(_, entry)| entry)))
+  }
+
+  fn store<'a>(&'a mut self, key: GrainKey, entry: ActivationEntry) -> Self::StoreFuture<'a> {
+    ready(self.write_entry(&key, &entry))
+  }
+
+  fn remove<'a>(&'a mut self, key: GrainKey) -> Self::RemoveFuture<'a> {
+    ready(self.remove_entry(&key))
+  }
+
+  fn load_all<'a>(&'a mut self) -> Self::LoadAllFuture<'a> {
+    ready(self.read_all())
+  }
+}
+
+const fn failed(reason: String) -> ActivationStorageError {
+  ActivationStorageError::Failed { reason }
+}
+
+fn encode_component(component: &str) -> String {
+  let mut encoded = String::new();
+  for byte in component.bytes() {
+    if byte.is_ascii_alphanumeric() || byte == b'_' {
+      encoded.push(char::from(byte));
+    } else {
+      encoded.push('%');
+      encoded.push(char::from(HEX_DIGITS[(byte >> 4) as usize]));
+      encoded.push(char::from(HEX_DIGITS[(byte & 0x0F) as usize]));
+    }
+  }
+  encoded
+}
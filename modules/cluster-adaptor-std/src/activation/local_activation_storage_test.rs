@@ -0,0 +1,70 @@
+use alloc::{format, string::String, vec};
+use std::{
+  fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivationEntry, ActivationRecord, ActivationStorage},
+  grain::GrainKey,
+};
+
+use super::LocalActivationStorage;
+
+fn unique_storage_dir(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  std::env::temp_dir().join(format!("fraktor-local-activation-storage-{name}-{}-{timestamp}", std::process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => {},
+    | Err(error) if error.kind() == ErrorKind::NotFound => {},
+    | Err(error) => panic!("remove {}: {error}", path.display()),
+  }
+}
+
+fn entry(pid: &str) -> ActivationEntry {
+  ActivationEntry {
+    owner:       String::from("node1:8080"),
+    record:      ActivationRecord::new(String::from(pid), Some(vec![1, 2, 3]), 7),
+    observed_at: 42,
+  }
+}
+
+#[tokio::test]
+async fn entries_survive_reopening_the_directory() {
+  let directory = unique_storage_dir("reopen");
+  let a = GrainKey::new(String::from("user/a b"));
+  let b = GrainKey::new(String::from("user/b"));
+  {
+    let mut storage = LocalActivationStorage::open(directory.clone()).expect("open");
+    storage.store(b.clone(), entry("b")).await.expect("store b");
+    storage.store(a.clone(), entry("a")).await.expect("store a");
+  }
+
+  let mut reopened = LocalActivationStorage::open(directory.clone()).expect("reopen");
+  assert_eq!(reopened.load(a.clone()).await.expect("load a"), Some(entry("a")));
+  assert_eq!(reopened.load_all().await.expect("load all"), [(a, entry("a")), (b, entry("b"))]);
+  remove_dir_if_exists(&directory);
+}
+
+#[tokio::test]
+async fn removed_entries_are_not_loaded() {
+  let directory = unique_storage_dir("remove");
+  let key = GrainKey::new(String::from("user/a"));
+  let mut storage = LocalActivationStorage::open(directory.clone()).expect("open");
+  storage.store(key.clone(), entry("a")).await.expect("store");
+
+  storage.remove(key.clone()).await.expect("remove");
+  storage.remove(key.clone()).await.expect("removing a missing entry is not an error");
+
+  assert!(storage.load(key).await.expect("load").is_none());
+  assert!(storage.load_all().await.expect("load all").is_empty());
+  remove_dir_if_exists(&directory);
+}
@@ -0,0 +1,98 @@
+//! Activation storage selected by the sharding state-store mode.
+
+#[cfg(test)]
+#[path = "remembered_activation_storage_test.rs"]
+mod tests;
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::{future::Future, pin::Pin};
+use std::path::PathBuf;
+
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivationEntry, ActivationStorage, ActivationStorageError, InMemoryActivationStorage},
+  extension::ClusterShardingStateStoreMode,
+  grain::GrainKey,
+};
+
+use super::{DurableStateActivationStorage, LocalActivationStorage};
+
+type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ActivationStorageError>> + Send + 'a>>;
+
+/// Activation storage for remembered grains, chosen from a [`ClusterShardingStateStoreMode`].
+///
+/// - [`ClusterShardingStateStoreMode::DData`] keeps entries in files below a directory, like the
+///   durable store of distributed data, or in memory when no directory is given.
+/// - [`ClusterShardingStateStoreMode::Persistence`] keeps entries in a durable state store.
+pub enum RememberedActivationStorage {
+  /// Entries kept in process memory.
+  InMemory(InMemoryActivationStorage),
+  /// Entries kept in local files.
+  Local(LocalActivationStorage),
+  /// Entries kept in a durable state store.
+  DurableState(DurableStateActivationStorage),
+}
+
+impl RememberedActivationStorage {
+  /// Selects the storage backend for `mode`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ActivationStorageError::Failed`] when the local directory cannot be prepared, or
+  /// when the persistence mode is selected without a durable state storage.
+  pub fn for_mode(
+    mode: ClusterShardingStateStoreMode,
+    directory: Option<PathBuf>,
+    durable_state: Option<DurableStateActivationStorage>,
+  ) -> Result<Self, ActivationStorageError> {
+    match mode {
+      | ClusterShardingStateStoreMode::DData => match directory {
+        | Some(directory) => LocalActivationStorage::open(directory).map(Self::Local),
+        | None => Ok(Self::InMemory(InMemoryActivationStorage::new())),
+      },
+      | ClusterShardingStateStoreMode::Persistence => {
+        durable_state.map(Self::DurableState).ok_or_else(|| ActivationStorageError::Failed {
+          reason: String::from("persistence state-store mode requires a durable state store"),
+        })
+      },
+    }
+  }
+}
+
+impl ActivationStorage for RememberedActivationStorage {
+  type LoadAllFuture<'a> = StorageFuture<'a, Vec<(GrainKey, ActivationEntry)>>;
+  type LoadFuture<'a> = StorageFuture<'a, Option<ActivationEntry>>;
+  type RemoveFuture<'a> = StorageFuture<'a, ()>;
+  type StoreFuture<'a> = StorageFuture<'a, ()>;
+
+  fn load<'a>(&'a mut self, key: GrainKey) -> Self::LoadFuture<'a> {
+    match self {
+      | Self::InMemory(storage) => Box::pin(storage.load(key)),
+      | Self::Local(storage) => Box::pin(storage.load(key)),
+      | Self::DurableState(storage) => storage.load(key),
+    }
+  }
+
+  fn store<'a>(&'a mut self, key: GrainKey, entry: ActivationEntry) -> Self::StoreFuture<'a> {
+    match self {
+      | Self::InMemory(storage) => Box::pin(storage.store(key, entry)),
+      | Self::Local(storage) => Box::pin(storage.store(key, entry)),
+      | Self::DurableState(storage) => storage.store(key, entry),
+    }
+  }
+
+  fn remove<'a>(&'a mut self, key: GrainKey) -> Self::RemoveFuture<'a> {
+    match self {
+      | Self::InMemory(storage) => Box::pin(storage.remove(key)),
+      | Self::Local(storage) => Box::pin(storage.remove(key)),
+      | Self::DurableState(storage) => storage.remove(key),
+    }
+  }
+
+  fn load_all<'a>(&'a mut self) -> Self::LoadAllFuture<'a> {
+    match self {
+      | Self::InMemory(storage) => Box::pin(storage.load_all()),
+      | Self::Local(storage) => Box::pin(storage.load_all()),
+      | Self::DurableState(storage) => storage.load_all(),
+    }
+  }
+}
@@ -0,0 +1,54 @@
+use alloc::{format, string::String};
+use std::{
+  fs,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivationEntry, ActivationRecord, ActivationStorage, ActivationStorageError},
+  extension::ClusterShardingStateStoreMode,
+  grain::GrainKey,
+};
+
+use super::RememberedActivationStorage;
+
+fn entry() -> ActivationEntry {
+  ActivationEntry {
+    owner:       String::from("node1:8080"),
+    record:      ActivationRecord::new(String::from("node1:8080::user/a"), None, 0),
+    observed_at: 1,
+  }
+}
+
+#[tokio::test]
+async fn ddata_mode_without_directory_keeps_entries_in_memory() {
+  let mut storage =
+    RememberedActivationStorage::for_mode(ClusterShardingStateStoreMode::DData, None, None).expect("storage");
+  assert!(matches!(storage, RememberedActivationStorage::InMemory(_)));
+
+  let key = GrainKey::new(String::from("user/a"));
+  storage.store(key.clone(), entry()).await.expect("store");
+  assert_eq!(storage.load_all().await.expect("load all"), [(key, entry())]);
+}
+
+#[test]
+fn ddata_mode_with_directory_uses_local_files() {
+  let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_nanos()).unwrap_or(0);
+  let directory =
+    std::env::temp_dir().join(format!("fraktor-remembered-activation-{}-{timestamp}", std::process::id()));
+
+  let storage =
+    RememberedActivationStorage::for_mode(ClusterShardingStateStoreMode::DData, Some(directory.clone()), None)
+      .expect("storage");
+
+  assert!(matches!(storage, RememberedActivationStorage::Local(_)));
+  assert!(directory.is_dir());
+  fs::remove_dir_all(&directory).expect("remove directory");
+}
+
+#[test]
+fn persistence_mode_requires_a_durable_state_store() {
+  let result = RememberedActivationStorage::for_mode(ClusterShardingStateStoreMode::Persistence, None, None);
+
+  assert!(matches!(result, Err(ActivationStorageError::Failed { .. })));
+}
@@ -0,0 +1,132 @@
+//! Keeps the remembered grains of a member in activation storage.
+
+use alloc::{string::String, vec::Vec};
+use std::path::PathBuf;
+
+use fraktor_actor_core_kernel_rs::{
+  event::stream::{EventStreamEvent, EventStreamSubscriber, EventStreamSubscription, subscriber_handle},
+  system::ActorSystem,
+};
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivationEntry, ActivationStorage, ActivationStorageError},
+  extension::ClusterExtension,
+  grain::{GRAIN_EVENT_STREAM_NAME, GrainEvent, GrainKey},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+use tokio::{
+  runtime::Handle,
+  sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+};
+
+use super::{DurableStateActivationStorage, RememberedActivationStorage};
+
+enum StorageChange {
+  Store { key: GrainKey, entry: ActivationEntry },
+  Remove { key: GrainKey },
+}
+
+struct RememberedGrainsSubscriber {
+  sender: UnboundedSender<StorageChange>,
+}
+
+impl EventStreamSubscriber for RememberedGrainsSubscriber {
+  fn on_event(&mut self, stream_event: &EventStreamEvent) {
+    let EventStreamEvent::Extension { name, payload } = stream_event else {
+      return;
+    };
+    if name != GRAIN_EVENT_STREAM_NAME {
+      return;
+    }
+    let change = match payload.downcast_ref::<GrainEvent>() {
+      | Some(GrainEvent::ActivationRemembered { key, entry }) => {
+        StorageChange::Store { key: key.clone(), entry: entry.clone() }
+      },
+      | Some(GrainEvent::ActivationForgotten { key }) => StorageChange::Remove { key: key.clone() },
+      | Some(GrainEvent::ReactivationFailed { key, error }) => {
+        tracing::warn!(key = key.value(), ?error, "remembered grain could not be reactivated");
+        return;
+      },
+      | _ => return,
+    };
+    if self.sender.send(change).is_err() {
+      tracing::warn!("remembered grain storage writer has stopped");
+    }
+  }
+}
+
+/// Keeps the remembered grains of a cluster member in activation storage.
+///
+/// On start the grains found in the storage are restored through
+/// [`ClusterExtension::restore_remembered_grains`], so grains owned by this member come back
+/// after a restart without waiting for a message. From then on, grains of remembered kinds are
+/// stored when activated and removed when passivated. Storage writes run in order on a tokio
+/// task; failures are logged and do not stop later writes.
+///
+/// Dropping the store stops recording; writes already queued still complete.
+pub struct RememberedGrainsStore {
+  _subscription: EventStreamSubscription,
+}
+
+impl RememberedGrainsStore {
+  /// Starts a store on the backend selected by the cluster's sharding state-store mode.
+  ///
+  /// See [`RememberedActivationStorage::for_mode`] for how `directory` and `durable_state` are
+  /// used.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ActivationStorageError::Failed`] when the cluster extension is not installed,
+  /// the backend cannot be prepared, or the stored grains cannot be loaded.
+  pub async fn start_for_mode(
+    system: &ActorSystem,
+    directory: Option<PathBuf>,
+    durable_state: Option<DurableStateActivationStorage>,
+    handle: &Handle,
+  ) -> Result<Self, ActivationStorageError> {
+    let mode = cluster_extension(system)?.sharding_state_store_mode();
+    let storage = RememberedActivationStorage::for_mode(mode, directory, durable_state)?;
+    Self::start(system, storage, handle).await
+  }
+
+  /// Restores the grains kept in `storage` and records remembered grains there from now on.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ActivationStorageError::Failed`] when the cluster extension is not installed or
+  /// the stored grains cannot be loaded.
+  pub async fn start(
+    system: &ActorSystem,
+    mut storage: RememberedActivationStorage,
+    handle: &Handle,
+  ) -> Result<Self, ActivationStorageError> {
+    let extension = cluster_extension(system)?;
+    // 読み込み中に記録された変更を取りこぼさないよう、先に購読してから復元する
+    let (sender, receiver) = unbounded_channel();
+    let subscriber = subscriber_handle(RememberedGrainsSubscriber { sender });
+    let subscription = system.event_stream().subscribe_no_replay(&subscriber);
+    let keys: Vec<GrainKey> = storage.load_all().await?.into_iter().map(|(key, _)| key).collect();
+    extension.restore_remembered_grains(keys);
+    // 書き込みタスクは購読の解除で送信側が閉じると終了するため、JoinHandle は保持しない
+    drop(handle.spawn(write_changes(storage, receiver)));
+    Ok(Self { _subscription: subscription })
+  }
+}
+
+fn cluster_extension(system: &ActorSystem) -> Result<ArcShared<ClusterExtension>, ActivationStorageError> {
+  system
+    .extended()
+    .extension_by_type::<ClusterExtension>()
+    .ok_or_else(|| ActivationStorageError::Failed { reason: String::from("cluster extension is not installed") })
+}
+
+async fn write_changes(mut storage: RememberedActivationStorage, mut receiver: UnboundedReceiver<StorageChange>) {
+  while let Some(change) = receiver.recv().await {
+    let (key, result) = match change {
+      | StorageChange::Store { key, entry } => (key.clone(), storage.store(key, entry).await),
+      | StorageChange::Remove { key } => (key.clone(), storage.remove(key).await),
+    };
+    if let Err(error) = result {
+      tracing::warn!(key = key.value(), ?error, "failed to update remembered grain storage");
+    }
+  }
+}
@@ -2,10 +2,26 @@
 
 #[cfg(feature = "aws-ecs")]
 mod aws_ecs_cluster_provider;
+/// Cluster bootstrap driver for std runtimes.
+mod cluster_bootstrap;
+mod cluster_bootstrap_probe_message;
+/// Actor answering bootstrap contact point probes.
+mod cluster_bootstrap_responder;
+/// Contact point probing contract used by cluster bootstrap.
+mod contact_point_prober;
 /// Generic discovery backend execution contract.
 mod discovery_backend;
 /// Observable generic discovery backend failure.
 mod discovery_backend_error;
+mod dns_address_record;
+/// DNS SRV / A-record discovery backend.
+mod dns_discovery_backend;
+mod dns_discovery_config;
+mod dns_lookup_mode;
+mod dns_message;
+/// Injectable DNS resolution contract.
+mod dns_resolver;
+mod dns_srv_record;
 #[cfg(feature = "aws-ecs")]
 mod ecs_cluster_config;
 #[cfg(feature = "aws-ecs")]
@@ -14,22 +30,60 @@ mod ecs_poller_error;
 mod ecs_task_discovery;
 /// Generic discovery backend adapter contract.
 mod generic_discovery_adapter;
+mod kubernetes_api_error;
+mod kubernetes_discovery_config;
+/// Pluggable HTTP transport for Kubernetes backends.
+mod kubernetes_http_client;
+mod kubernetes_http_method;
+mod kubernetes_http_request;
+mod kubernetes_http_response;
+mod kubernetes_lease_config;
+/// Lease-majority backend on top of Kubernetes Lease objects.
+mod kubernetes_lease_majority_backend;
+mod kubernetes_micro_time;
+mod kubernetes_pod_discovery_backend;
+mod kubernetes_pod_list;
 mod lease_majority_backend;
 mod local_cluster_provider_ext;
 /// Provider lifecycle bridge for seed and discovery input.
 mod provider_lifecycle_bridge;
+/// Contact point prober using actor remoting.
+mod remoting_contact_point_prober;
 mod split_brain_resolver_provider;
+mod tcp_kubernetes_http_client;
+mod udp_dns_resolver;
 
 #[cfg(feature = "aws-ecs")]
 pub use aws_ecs_cluster_provider::AwsEcsClusterProvider;
+pub use cluster_bootstrap::ClusterBootstrap;
+pub use cluster_bootstrap_responder::ClusterBootstrapResponder;
+pub use contact_point_prober::ContactPointProber;
 pub use discovery_backend::DiscoveryBackend;
 pub use discovery_backend_error::DiscoveryBackendError;
+pub use dns_address_record::DnsAddressRecord;
+pub use dns_discovery_backend::DnsDiscoveryBackend;
+pub use dns_discovery_config::DnsDiscoveryConfig;
+pub use dns_lookup_mode::DnsLookupMode;
+pub use dns_resolver::DnsResolver;
+pub use dns_srv_record::DnsSrvRecord;
 #[cfg(feature = "aws-ecs")]
 pub use ecs_cluster_config::EcsClusterConfig;
 #[cfg(feature = "aws-ecs")]
 pub use ecs_poller_error::EcsPollerError;
 pub use generic_discovery_adapter::GenericDiscoveryAdapter;
+pub use kubernetes_api_error::KubernetesApiError;
+pub use kubernetes_discovery_config::KubernetesDiscoveryConfig;
+pub use kubernetes_http_client::KubernetesHttpClient;
+pub use kubernetes_http_method::KubernetesHttpMethod;
+pub use kubernetes_http_request::KubernetesHttpRequest;
+pub use kubernetes_http_response::KubernetesHttpResponse;
+pub use kubernetes_lease_config::KubernetesLeaseConfig;
+pub use kubernetes_lease_majority_backend::KubernetesLeaseMajorityBackend;
+pub use kubernetes_pod_discovery_backend::KubernetesPodDiscoveryBackend;
 pub use lease_majority_backend::StdLeaseMajorityBackend;
 pub use local_cluster_provider_ext::{subscribe_remoting_events, wrap_local_cluster_provider};
 pub use provider_lifecycle_bridge::ProviderLifecycleBridge;
+pub use remoting_contact_point_prober::RemotingContactPointProber;
 pub use split_brain_resolver_provider::StdSplitBrainResolverProvider;
+pub use tcp_kubernetes_http_client::TcpKubernetesHttpClient;
+pub use udp_dns_resolver::UdpDnsResolver;
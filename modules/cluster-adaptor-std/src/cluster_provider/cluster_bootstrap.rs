@@ -0,0 +1,106 @@
+//! Cluster bootstrap driver for std runtimes.
+
+use std::string::String;
+
+use fraktor_cluster_core_kernel_rs::{
+  cluster_provider::{
+    ClusterBootstrapConfig, ClusterBootstrapDecision, ClusterBootstrapProcess, ClusterProvider,
+    LocalClusterProviderWeak,
+  },
+  extension::ClusterProviderError,
+};
+use fraktor_utils_core_rs::{sync::SharedAccess, time::TimerInstant};
+
+use super::{ContactPointProber, DiscoveryBackend, GenericDiscoveryAdapter};
+
+#[cfg(test)]
+#[path = "cluster_bootstrap_test.rs"]
+mod tests;
+
+/// Forms or joins a cluster from discovered contact points.
+///
+/// Each [`poll`](Self::poll) runs discovery, probes every other contact point and applies the
+/// [`ClusterBootstrapProcess`] decision to the provider. Drivers call it every
+/// [`ClusterBootstrapConfig::probe_interval`] until it returns a join decision.
+pub struct ClusterBootstrap<B, P> {
+  provider:          LocalClusterProviderWeak,
+  discovery_adapter: GenericDiscoveryAdapter<B>,
+  prober:            P,
+  process:           ClusterBootstrapProcess,
+}
+
+impl<B, P> ClusterBootstrap<B, P>
+where
+  B: DiscoveryBackend,
+  P: ContactPointProber,
+{
+  /// Creates a bootstrap driver for the provider advertising `self_authority`.
+  #[must_use]
+  pub fn new(
+    provider: LocalClusterProviderWeak,
+    config: ClusterBootstrapConfig,
+    self_authority: impl Into<String>,
+    mut discovery_adapter: GenericDiscoveryAdapter<B>,
+    prober: P,
+  ) -> Self {
+    discovery_adapter.attach_provider(provider.clone());
+    Self { provider, discovery_adapter, prober, process: ClusterBootstrapProcess::new(config, self_authority) }
+  }
+
+  /// Returns the underlying bootstrap process.
+  #[must_use]
+  pub const fn process(&self) -> &ClusterBootstrapProcess {
+    &self.process
+  }
+
+  /// Runs one discovery and probe round and applies the resulting decision.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ClusterProviderError`] when the provider is gone or rejects the join. The join is
+  /// retried on the next poll.
+  pub fn poll(&mut self, now: TimerInstant) -> Result<ClusterBootstrapDecision, ClusterProviderError> {
+    if self.process.is_completed() {
+      return Ok(ClusterBootstrapDecision::Completed);
+    }
+    if let Some(result) = self.discovery_adapter.poll(now) {
+      if let Some(error) = result.error() {
+        tracing::debug!(?error, "cluster bootstrap discovery failed");
+      }
+      self.process.observe_contact_points(&result, now);
+    }
+    for authority in self.process.contact_points_to_probe() {
+      match self.prober.probe(&authority) {
+        | Ok(seed_nodes) => self.process.record_probe_success(&authority, seed_nodes),
+        | Err(error) => {
+          tracing::debug!(?error, authority = authority.as_str(), "cluster bootstrap probe failed");
+          self.process.record_probe_failure(&authority);
+        },
+      }
+    }
+
+    let decision = self.process.decide(now);
+    match &decision {
+      | ClusterBootstrapDecision::JoinSelf => self.join(&[])?,
+      | ClusterBootstrapDecision::JoinSeedNodes(seed_nodes) => self.join(seed_nodes)?,
+      | ClusterBootstrapDecision::Pending { .. } | ClusterBootstrapDecision::Completed => return Ok(decision),
+    }
+    // 参加に失敗した場合は完了扱いにせず、次の poll で再試行する
+    self.process.mark_joined();
+    Ok(decision)
+  }
+
+  /// Stops bootstrap without joining.
+  pub fn shutdown(&mut self) {
+    self.process.shutdown();
+    self.discovery_adapter.shutdown();
+  }
+
+  fn join(&self, seed_nodes: &[String]) -> Result<(), ClusterProviderError> {
+    let provider = self.provider.upgrade().ok_or_else(|| ClusterProviderError::join("provider unavailable"))?;
+    provider.with_write(|provider| {
+      provider.start_member()?;
+      seed_nodes.iter().try_for_each(|seed_node| provider.join(seed_node))
+    })
+  }
+}
@@ -0,0 +1,50 @@
+//! Text wire format exchanged by bootstrap contact point probes.
+
+use std::{
+  format,
+  string::{String, ToString},
+  vec::Vec,
+};
+
+#[cfg(test)]
+#[path = "cluster_bootstrap_probe_message_test.rs"]
+mod tests;
+
+const REQUEST_TAG: &str = "fraktor.cluster-bootstrap.probe";
+const REPLY_TAG: &str = "fraktor.cluster-bootstrap.seeds";
+
+/// Probe message carried as a `String` so it travels over remoting without extra serializers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ClusterBootstrapProbeMessage {
+  /// Asks a contact point for its seed nodes; the reply is sent to `reply_to`.
+  Request { reply_to: String },
+  /// Seed nodes reported by the contact point `authority`.
+  Reply { authority: String, seed_nodes: Vec<String> },
+}
+
+impl ClusterBootstrapProbeMessage {
+  /// Encodes the message.
+  pub(crate) fn encode(&self) -> String {
+    match self {
+      | Self::Request { reply_to } => format!("{REQUEST_TAG}\n{reply_to}"),
+      | Self::Reply { authority, seed_nodes } => format!("{REPLY_TAG}\n{authority}\n{}", seed_nodes.join(",")),
+    }
+  }
+
+  /// Decodes a message, returning `None` for unrelated text.
+  pub(crate) fn decode(text: &str) -> Option<Self> {
+    let mut lines = text.split('\n');
+    match lines.next()? {
+      | REQUEST_TAG => {
+        let reply_to = lines.next().filter(|reply_to| !reply_to.is_empty())?;
+        Some(Self::Request { reply_to: reply_to.to_string() })
+      },
+      | REPLY_TAG => {
+        let authority = lines.next().filter(|authority| !authority.is_empty())?;
+        let seed_nodes = lines.next()?.split(',').filter(|seed| !seed.is_empty()).map(ToString::to_string).collect();
+        Some(Self::Reply { authority: authority.to_string(), seed_nodes })
+      },
+      | _ => None,
+    }
+  }
+}
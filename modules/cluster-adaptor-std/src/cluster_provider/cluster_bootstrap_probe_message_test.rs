@@ -0,0 +1,30 @@
+use std::{string::String, vec::Vec};
+
+use super::ClusterBootstrapProbeMessage;
+
+#[test]
+fn request_round_trips() {
+  let request =
+    ClusterBootstrapProbeMessage::Request { reply_to: String::from("fraktor.tcp://sys@10.0.0.1:2552/user/$a") };
+
+  assert_eq!(ClusterBootstrapProbeMessage::decode(&request.encode()), Some(request));
+}
+
+#[test]
+fn reply_round_trips_with_and_without_seed_nodes() {
+  let joined = ClusterBootstrapProbeMessage::Reply {
+    authority:  String::from("10.0.0.2:2552"),
+    seed_nodes: Vec::from([String::from("10.0.0.1:2552"), String::from("10.0.0.2:2552")]),
+  };
+  let forming =
+    ClusterBootstrapProbeMessage::Reply { authority: String::from("10.0.0.2:2552"), seed_nodes: Vec::new() };
+
+  assert_eq!(ClusterBootstrapProbeMessage::decode(&joined.encode()), Some(joined));
+  assert_eq!(ClusterBootstrapProbeMessage::decode(&forming.encode()), Some(forming));
+}
+
+#[test]
+fn unrelated_text_is_ignored() {
+  assert_eq!(ClusterBootstrapProbeMessage::decode("hello"), None);
+  assert_eq!(ClusterBootstrapProbeMessage::decode("fraktor.cluster-bootstrap.probe\n"), None);
+}
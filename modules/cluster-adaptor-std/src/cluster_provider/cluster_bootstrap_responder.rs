@@ -0,0 +1,78 @@
+//! Actor answering bootstrap contact point probes.
+
+use std::{string::String, vec::Vec};
+
+use fraktor_actor_core_kernel_rs::actor::{
+  Actor, ActorContext,
+  actor_path::ActorPathParser,
+  error::ActorError,
+  messaging::{AnyMessage, AnyMessageView},
+  props::Props,
+};
+use fraktor_cluster_core_kernel_rs::{cluster_provider::LocalClusterProviderWeak, extension::StartupMode};
+use fraktor_utils_core_rs::sync::SharedAccess;
+
+use super::cluster_bootstrap_probe_message::ClusterBootstrapProbeMessage;
+
+/// Answers probes from bootstrapping nodes with the local seed nodes.
+///
+/// A node started as a cluster member reports itself as a seed node; otherwise it reports none.
+/// Spawn it under [`ClusterBootstrapResponder::NAME`] so remote probers can address it.
+pub struct ClusterBootstrapResponder {
+  provider: LocalClusterProviderWeak,
+}
+
+impl ClusterBootstrapResponder {
+  /// Actor name the responder is spawned under in the user guardian.
+  pub const NAME: &'static str = "cluster-bootstrap";
+
+  /// Creates a responder reporting the state of `provider`.
+  #[must_use]
+  pub const fn new(provider: LocalClusterProviderWeak) -> Self {
+    Self { provider }
+  }
+
+  /// Returns props spawning a responder for `provider`.
+  #[must_use]
+  pub fn props(provider: LocalClusterProviderWeak) -> Props {
+    Props::from_fn(move || Self::new(provider.clone()))
+  }
+
+  fn reply(&self) -> Option<ClusterBootstrapProbeMessage> {
+    let provider = self.provider.upgrade()?;
+    Some(provider.with_read(|provider| {
+      let authority = String::from(provider.advertised_address());
+      let seed_nodes = match provider.startup_mode() {
+        | Some(StartupMode::Member) => Vec::from([authority.clone()]),
+        | _ => Vec::new(),
+      };
+      ClusterBootstrapProbeMessage::Reply { authority, seed_nodes }
+    }))
+  }
+}
+
+impl Actor for ClusterBootstrapResponder {
+  fn receive(&mut self, context: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    let Some(ClusterBootstrapProbeMessage::Request { reply_to }) =
+      message.downcast_ref::<String>().and_then(|text| ClusterBootstrapProbeMessage::decode(text))
+    else {
+      return Ok(());
+    };
+    let Some(reply) = self.reply() else {
+      return Ok(());
+    };
+    // 応答できなくても相手は再プローブするため、失敗はログに留める
+    let resolved = ActorPathParser::parse(&reply_to)
+      .map_err(|error| std::format!("{error:?}"))
+      .and_then(|path| context.system().resolve_actor_ref(path).map_err(|error| std::format!("{error:?}")));
+    match resolved {
+      | Ok(mut reply_to) => {
+        if let Err(error) = reply_to.try_tell(AnyMessage::new(reply.encode())) {
+          tracing::debug!(?error, "cluster bootstrap probe reply could not be sent");
+        }
+      },
+      | Err(error) => tracing::debug!(%error, "cluster bootstrap probe reply path could not be resolved"),
+    }
+    Ok(())
+  }
+}
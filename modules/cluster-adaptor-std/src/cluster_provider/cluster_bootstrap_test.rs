@@ -0,0 +1,166 @@
+use std::{
+  collections::BTreeMap,
+  string::{String, ToString},
+  time::Duration,
+  vec::Vec,
+};
+
+use fraktor_actor_core_kernel_rs::event::stream::EventStreamShared;
+use fraktor_cluster_core_kernel_rs::{
+  cluster_provider::{
+    ClusterBootstrapConfig, ClusterBootstrapDecision, LocalClusterProvider, LocalClusterProviderShared,
+  },
+  extension::{ClusterProviderError, StartupMode},
+  topology::BlockListProvider,
+};
+use fraktor_utils_core_rs::{
+  sync::{ArcShared, SharedAccess},
+  time::TimerInstant,
+};
+
+use super::ClusterBootstrap;
+use crate::cluster_provider::{
+  ContactPointProber, DiscoveryBackend, DiscoveryBackendError, GenericDiscoveryAdapter, wrap_local_cluster_provider,
+};
+
+struct EmptyBlockList;
+
+impl BlockListProvider for EmptyBlockList {
+  fn blocked_members(&self) -> Vec<String> {
+    Vec::new()
+  }
+}
+
+struct StaticBackend {
+  authorities: Vec<String>,
+}
+
+impl DiscoveryBackend for StaticBackend {
+  fn source_identity(&self) -> &str {
+    "static"
+  }
+
+  fn discover(&mut self) -> Result<Vec<String>, DiscoveryBackendError> {
+    Ok(self.authorities.clone())
+  }
+}
+
+#[derive(Default)]
+struct FakeProber {
+  seed_nodes: BTreeMap<String, Vec<String>>,
+  probed:     Vec<String>,
+}
+
+impl ContactPointProber for FakeProber {
+  fn probe(&mut self, authority: &str) -> Result<Vec<String>, ClusterProviderError> {
+    self.probed.push(authority.to_string());
+    self.seed_nodes.get(authority).cloned().ok_or_else(|| ClusterProviderError::join("unreachable"))
+  }
+}
+
+const NODES: [&str; 3] = ["10.0.0.1:2552", "10.0.0.2:2552", "10.0.0.3:2552"];
+
+fn at(seconds: u64) -> TimerInstant {
+  TimerInstant::from_ticks(seconds, Duration::from_secs(1))
+}
+
+fn config() -> ClusterBootstrapConfig {
+  ClusterBootstrapConfig::new().with_stable_margin(Duration::from_secs(2)).with_required_contact_point_count(3)
+}
+
+fn prober(seed_nodes: &[(&str, &[&str])]) -> FakeProber {
+  FakeProber {
+    seed_nodes: seed_nodes
+      .iter()
+      .map(|(authority, seeds)| (authority.to_string(), seeds.iter().map(ToString::to_string).collect()))
+      .collect(),
+    probed:     Vec::new(),
+  }
+}
+
+fn provider(authority: &str) -> LocalClusterProviderShared {
+  wrap_local_cluster_provider(LocalClusterProvider::new(
+    EventStreamShared::default(),
+    ArcShared::new(EmptyBlockList),
+    authority,
+  ))
+}
+
+fn bootstrap(
+  provider: &LocalClusterProviderShared,
+  self_authority: &str,
+  prober: FakeProber,
+) -> ClusterBootstrap<StaticBackend, FakeProber> {
+  let backend = StaticBackend { authorities: NODES.iter().map(ToString::to_string).collect() };
+  ClusterBootstrap::new(provider.downgrade(), config(), self_authority, GenericDiscoveryAdapter::new(backend), prober)
+}
+
+#[test]
+fn lowest_contact_point_starts_as_member_without_joining_anyone() {
+  let provider = provider(NODES[0]);
+  let mut bootstrap = bootstrap(&provider, NODES[0], prober(&[(NODES[1], &[]), (NODES[2], &[])]));
+
+  assert!(matches!(bootstrap.poll(at(0)), Ok(ClusterBootstrapDecision::Pending { .. })));
+  assert!(!provider.with_read(LocalClusterProvider::is_started));
+
+  assert_eq!(bootstrap.poll(at(2)), Ok(ClusterBootstrapDecision::JoinSelf));
+  assert_eq!(provider.with_read(LocalClusterProvider::startup_mode), Some(StartupMode::Member));
+  assert_eq!(provider.with_read(LocalClusterProvider::member_count), 1);
+  assert_eq!(bootstrap.poll(at(3)), Ok(ClusterBootstrapDecision::Completed));
+  assert_eq!(
+    bootstrap.prober.probed,
+    Vec::from([String::from(NODES[1]), String::from(NODES[2]), String::from(NODES[1]), String::from(NODES[2])])
+  );
+}
+
+#[test]
+fn other_contact_points_join_the_reported_seed_nodes() {
+  let provider = provider(NODES[1]);
+  let mut bootstrap = bootstrap(&provider, NODES[1], prober(&[(NODES[0], &[]), (NODES[2], &[])]));
+
+  assert!(matches!(bootstrap.poll(at(5)), Ok(ClusterBootstrapDecision::Pending { .. })));
+
+  bootstrap.prober.seed_nodes.insert(String::from(NODES[0]), Vec::from([String::from(NODES[0])]));
+
+  assert_eq!(bootstrap.poll(at(6)), Ok(ClusterBootstrapDecision::JoinSeedNodes(Vec::from([String::from(NODES[0])]))));
+  assert_eq!(provider.with_read(LocalClusterProvider::startup_mode), Some(StartupMode::Member));
+  assert_eq!(provider.with_read(LocalClusterProvider::member_count), 2);
+}
+
+#[test]
+fn unreachable_contact_points_block_formation() {
+  let provider = provider(NODES[0]);
+  let mut bootstrap = bootstrap(&provider, NODES[0], prober(&[(NODES[1], &[])]));
+  bootstrap.poll(at(0)).expect("initial poll");
+
+  assert_eq!(
+    bootstrap.poll(at(10)),
+    Ok(ClusterBootstrapDecision::Pending { reason: "not all contact points have been probed successfully" })
+  );
+  assert!(!provider.with_read(LocalClusterProvider::is_started));
+}
+
+#[test]
+fn shutdown_prevents_joining_an_existing_cluster() {
+  let provider = provider(NODES[1]);
+  let mut bootstrap = bootstrap(&provider, NODES[1], prober(&[(NODES[0], &[NODES[0]])]));
+
+  bootstrap.shutdown();
+
+  assert_eq!(bootstrap.poll(at(0)), Ok(ClusterBootstrapDecision::Pending { reason: "bootstrap has been shut down" }));
+  assert!(!provider.with_read(LocalClusterProvider::is_started));
+}
+
+#[test]
+fn failed_join_is_retried_on_the_next_poll() {
+  let provider = provider(NODES[0]);
+  let mut bootstrap = bootstrap(&provider, NODES[0], prober(&[(NODES[1], &[]), (NODES[2], &[])]));
+  bootstrap.poll(at(0)).expect("initial poll");
+  // プロバイダが失われていると参加に失敗する
+  drop(provider);
+
+  assert_eq!(bootstrap.poll(at(2)), Err(ClusterProviderError::join("provider unavailable")));
+  assert!(!bootstrap.process().is_completed());
+  assert_eq!(bootstrap.poll(at(3)), Err(ClusterProviderError::join("provider unavailable")));
+  assert!(!bootstrap.process().is_completed());
+}
@@ -0,0 +1,17 @@
+//! Contact point probing contract used by cluster bootstrap.
+
+use std::{string::String, vec::Vec};
+
+use fraktor_cluster_core_kernel_rs::extension::ClusterProviderError;
+
+/// Asks a discovered contact point whether it already belongs to a cluster.
+pub trait ContactPointProber {
+  /// Probes `authority` and returns the seed nodes it reports.
+  ///
+  /// An empty list means the contact point is reachable but has not joined a cluster yet.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ClusterProviderError`] when the contact point did not answer.
+  fn probe(&mut self, authority: &str) -> Result<Vec<String>, ClusterProviderError>;
+}
@@ -0,0 +1,30 @@
+//! DNS A/AAAA record consumed by DNS discovery.
+
+use std::{net::IpAddr, time::Duration};
+
+/// A or AAAA record returned by a [`DnsResolver`](super::DnsResolver).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DnsAddressRecord {
+  address: IpAddr,
+  ttl:     Duration,
+}
+
+impl DnsAddressRecord {
+  /// Creates an address record.
+  #[must_use]
+  pub const fn new(address: IpAddr, ttl: Duration) -> Self {
+    Self { address, ttl }
+  }
+
+  /// Returns the resolved address.
+  #[must_use]
+  pub const fn address(&self) -> IpAddr {
+    self.address
+  }
+
+  /// Returns the record time-to-live.
+  #[must_use]
+  pub const fn ttl(&self) -> Duration {
+    self.ttl
+  }
+}
@@ -0,0 +1,122 @@
+//! DNS SRV / A-record discovery backend.
+
+use std::{
+  format,
+  net::IpAddr,
+  string::{String, ToString},
+  time::{Duration, Instant},
+  vec::Vec,
+};
+
+use super::{DiscoveryBackend, DiscoveryBackendError, DnsDiscoveryConfig, DnsLookupMode, DnsResolver};
+
+#[cfg(test)]
+#[path = "dns_discovery_backend_test.rs"]
+mod tests;
+
+/// Discovery backend that resolves seed authorities from DNS records.
+///
+/// Resolved authorities are cached until the record TTL expires, so the backend can be polled more
+/// often than the records change. Failures are cached for the minimum refresh interval before the
+/// next lookup is attempted.
+pub struct DnsDiscoveryBackend<R> {
+  config:          DnsDiscoveryConfig,
+  resolver:        R,
+  source_identity: String,
+  last_outcome:    Option<Result<Vec<String>, DiscoveryBackendError>>,
+  next_refresh_at: Option<Instant>,
+}
+
+impl<R> DnsDiscoveryBackend<R>
+where
+  R: DnsResolver,
+{
+  /// Creates a DNS discovery backend using the given resolver.
+  #[must_use]
+  pub fn new(config: DnsDiscoveryConfig, resolver: R) -> Self {
+    let source_identity = format!("dns:{}", config.service_name());
+    Self { config, resolver, source_identity, last_outcome: None, next_refresh_at: None }
+  }
+
+  /// Returns the backend configuration.
+  #[must_use]
+  pub const fn config(&self) -> &DnsDiscoveryConfig {
+    &self.config
+  }
+
+  /// Returns how long the current result stays fresh.
+  ///
+  /// Drivers can use this to schedule the next poll. Returns zero when a lookup is due.
+  #[must_use]
+  pub fn next_refresh_in(&self) -> Duration {
+    self.next_refresh_at.map_or(Duration::ZERO, |at| at.saturating_duration_since(Instant::now()))
+  }
+
+  fn discover_at(&mut self, now: Instant) -> Result<Vec<String>, DiscoveryBackendError> {
+    if let (Some(outcome), Some(refresh_at)) = (&self.last_outcome, self.next_refresh_at)
+      && now < refresh_at
+    {
+      return outcome.clone();
+    }
+    let (outcome, refresh_after) = match self.resolve() {
+      | Ok((authorities, Some(ttl))) => (Ok(authorities), self.clamp_refresh(ttl)),
+      // レコードが無い場合は TTL が得られないため、最短間隔で再確認する
+      | Ok((authorities, None)) => (Ok(authorities), self.config.min_refresh_interval()),
+      | Err(error) => (Err(error), self.config.min_refresh_interval()),
+    };
+    self.next_refresh_at = Some(now + refresh_after);
+    self.last_outcome = Some(outcome.clone());
+    outcome
+  }
+
+  fn resolve(&mut self) -> Result<(Vec<String>, Option<Duration>), DiscoveryBackendError> {
+    let name = self.config.service_name().to_string();
+    let (mut authorities, min_ttl) = match self.config.lookup_mode() {
+      | DnsLookupMode::Srv => {
+        let records = self.resolver.resolve_srv(&name)?;
+        let min_ttl = records.iter().map(|record| record.ttl()).min();
+        let authorities = records
+          .iter()
+          .map(|record| (record.target().trim_end_matches('.'), record.port()))
+          // ターゲット "." はサービスが提供されていないことを示す
+          .filter(|(target, _)| !target.is_empty())
+          .map(|(target, port)| format!("{target}:{port}"))
+          .collect::<Vec<_>>();
+        (authorities, min_ttl)
+      },
+      | DnsLookupMode::Address { port } => {
+        let records = self.resolver.resolve_addresses(&name)?;
+        let min_ttl = records.iter().map(|record| record.ttl()).min();
+        let authorities = records.iter().map(|record| Self::address_authority(record.address(), port)).collect();
+        (authorities, min_ttl)
+      },
+    };
+    authorities.sort();
+    authorities.dedup();
+    Ok((authorities, min_ttl))
+  }
+
+  fn clamp_refresh(&self, ttl: Duration) -> Duration {
+    ttl.max(self.config.min_refresh_interval()).min(self.config.max_refresh_interval())
+  }
+
+  fn address_authority(address: IpAddr, port: u16) -> String {
+    match address {
+      | IpAddr::V4(address) => format!("{address}:{port}"),
+      | IpAddr::V6(address) => format!("[{address}]:{port}"),
+    }
+  }
+}
+
+impl<R> DiscoveryBackend for DnsDiscoveryBackend<R>
+where
+  R: DnsResolver,
+{
+  fn source_identity(&self) -> &str {
+    &self.source_identity
+  }
+
+  fn discover(&mut self) -> Result<Vec<String>, DiscoveryBackendError> {
+    self.discover_at(Instant::now())
+  }
+}
@@ -0,0 +1,130 @@
+use std::{
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  string::String,
+  time::{Duration, Instant},
+  vec::Vec,
+};
+
+use super::DnsDiscoveryBackend;
+use crate::cluster_provider::{
+  DiscoveryBackend, DiscoveryBackendError, DnsAddressRecord, DnsDiscoveryConfig, DnsLookupMode, DnsResolver,
+  DnsSrvRecord,
+};
+
+struct FakeResolver {
+  srv_records:     Result<Vec<DnsSrvRecord>, DiscoveryBackendError>,
+  address_records: Result<Vec<DnsAddressRecord>, DiscoveryBackendError>,
+  lookups:         Vec<String>,
+}
+
+impl FakeResolver {
+  fn with_srv(records: Vec<DnsSrvRecord>) -> Self {
+    Self { srv_records: Ok(records), address_records: Ok(Vec::new()), lookups: Vec::new() }
+  }
+
+  fn with_addresses(records: Vec<DnsAddressRecord>) -> Self {
+    Self { srv_records: Ok(Vec::new()), address_records: Ok(records), lookups: Vec::new() }
+  }
+}
+
+impl DnsResolver for FakeResolver {
+  fn resolve_srv(&mut self, name: &str) -> Result<Vec<DnsSrvRecord>, DiscoveryBackendError> {
+    self.lookups.push(String::from(name));
+    self.srv_records.clone()
+  }
+
+  fn resolve_addresses(&mut self, name: &str) -> Result<Vec<DnsAddressRecord>, DiscoveryBackendError> {
+    self.lookups.push(String::from(name));
+    self.address_records.clone()
+  }
+}
+
+const SERVICE: &str = "_fraktor._tcp.cluster.local";
+
+fn srv(target: &str, port: u16, ttl_secs: u64) -> DnsSrvRecord {
+  DnsSrvRecord::new(target, port, 10, 5, Duration::from_secs(ttl_secs))
+}
+
+fn config() -> DnsDiscoveryConfig {
+  DnsDiscoveryConfig::new(SERVICE)
+    .with_min_refresh_interval(Duration::from_secs(5))
+    .with_max_refresh_interval(Duration::from_secs(60))
+}
+
+#[test]
+fn srv_records_become_sorted_unique_authorities() {
+  let resolver = FakeResolver::with_srv(Vec::from([
+    srv("node-b.cluster.local.", 7331, 30),
+    srv("node-a.cluster.local.", 7331, 30),
+    srv("node-a.cluster.local", 7331, 30),
+    srv(".", 0, 30),
+  ]));
+  let mut backend = DnsDiscoveryBackend::new(config(), resolver);
+
+  let authorities = backend.discover().expect("srv discovery");
+
+  assert_eq!(
+    authorities,
+    Vec::from([String::from("node-a.cluster.local:7331"), String::from("node-b.cluster.local:7331")])
+  );
+  assert_eq!(backend.source_identity(), "dns:_fraktor._tcp.cluster.local");
+  assert_eq!(backend.resolver.lookups, Vec::from([String::from(SERVICE)]));
+}
+
+#[test]
+fn address_records_are_paired_with_the_configured_port() {
+  let resolver = FakeResolver::with_addresses(Vec::from([
+    DnsAddressRecord::new(IpAddr::V6(Ipv6Addr::LOCALHOST), Duration::from_secs(30)),
+    DnsAddressRecord::new(IpAddr::V4(Ipv4Addr::new(10, 0, 1, 12)), Duration::from_secs(30)),
+  ]));
+  let config = config().with_lookup_mode(DnsLookupMode::Address { port: 8080 });
+  let mut backend = DnsDiscoveryBackend::new(config, resolver);
+
+  let authorities = backend.discover().expect("address discovery");
+
+  assert_eq!(authorities, Vec::from([String::from("10.0.1.12:8080"), String::from("[::1]:8080")]));
+}
+
+#[test]
+fn results_are_cached_until_the_smallest_ttl_expires() {
+  let resolver = FakeResolver::with_srv(Vec::from([srv("node-a", 7331, 20), srv("node-b", 7331, 40)]));
+  let mut backend = DnsDiscoveryBackend::new(config(), resolver);
+  let start = Instant::now();
+
+  backend.discover_at(start).expect("initial lookup");
+  backend.discover_at(start + Duration::from_secs(19)).expect("cached result");
+  assert_eq!(backend.resolver.lookups.len(), 1);
+
+  backend.discover_at(start + Duration::from_secs(20)).expect("refreshed result");
+  assert_eq!(backend.resolver.lookups.len(), 2);
+}
+
+#[test]
+fn ttl_is_clamped_to_the_refresh_bounds() {
+  let resolver = FakeResolver::with_srv(Vec::from([srv("node-a", 7331, 1)]));
+  let mut backend = DnsDiscoveryBackend::new(config(), resolver);
+  let start = Instant::now();
+
+  backend.discover_at(start).expect("initial lookup");
+  assert_eq!(backend.next_refresh_at, Some(start + Duration::from_secs(5)));
+
+  backend.resolver.srv_records = Ok(Vec::from([srv("node-a", 7331, 3600)]));
+  backend.discover_at(start + Duration::from_secs(5)).expect("refreshed lookup");
+  assert_eq!(backend.next_refresh_at, Some(start + Duration::from_secs(65)));
+}
+
+#[test]
+fn failures_are_reported_and_retried_after_the_minimum_interval() {
+  let mut resolver = FakeResolver::with_srv(Vec::new());
+  resolver.srv_records = Err(DiscoveryBackendError::temporary("dns timeout"));
+  let mut backend = DnsDiscoveryBackend::new(config(), resolver);
+  let start = Instant::now();
+
+  assert_eq!(backend.discover_at(start), Err(DiscoveryBackendError::temporary("dns timeout")));
+  assert_eq!(backend.discover_at(start + Duration::from_secs(1)), Err(DiscoveryBackendError::temporary("dns timeout")));
+  assert_eq!(backend.resolver.lookups.len(), 1);
+
+  backend.resolver.srv_records = Ok(Vec::from([srv("node-a", 7331, 30)]));
+  let authorities = backend.discover_at(start + Duration::from_secs(5)).expect("recovered lookup");
+  assert_eq!(authorities, Vec::from([String::from("node-a:7331")]));
+}
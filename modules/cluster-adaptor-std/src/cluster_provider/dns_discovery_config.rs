@@ -0,0 +1,75 @@
+//! Configuration for DNS discovery.
+
+use std::{string::String, time::Duration};
+
+use super::DnsLookupMode;
+
+/// Configuration for [`DnsDiscoveryBackend`](super::DnsDiscoveryBackend).
+///
+/// Results are cached for the smallest record TTL, clamped between the minimum and maximum refresh
+/// intervals.
+#[derive(Clone, Debug)]
+pub struct DnsDiscoveryConfig {
+  service_name:         String,
+  lookup_mode:          DnsLookupMode,
+  min_refresh_interval: Duration,
+  max_refresh_interval: Duration,
+}
+
+impl DnsDiscoveryConfig {
+  /// Creates a configuration resolving SRV records for `service_name`.
+  #[must_use]
+  pub fn new(service_name: impl Into<String>) -> Self {
+    Self {
+      service_name:         service_name.into(),
+      lookup_mode:          DnsLookupMode::Srv,
+      min_refresh_interval: Duration::from_secs(5),
+      max_refresh_interval: Duration::from_secs(60),
+    }
+  }
+
+  /// Sets the record kind to resolve.
+  #[must_use]
+  pub const fn with_lookup_mode(mut self, lookup_mode: DnsLookupMode) -> Self {
+    self.lookup_mode = lookup_mode;
+    self
+  }
+
+  /// Sets the minimum refresh interval, also used as the retry delay after a failure.
+  #[must_use]
+  pub const fn with_min_refresh_interval(mut self, interval: Duration) -> Self {
+    self.min_refresh_interval = interval;
+    self
+  }
+
+  /// Sets the maximum refresh interval.
+  #[must_use]
+  pub const fn with_max_refresh_interval(mut self, interval: Duration) -> Self {
+    self.max_refresh_interval = interval;
+    self
+  }
+
+  /// Returns the DNS name to resolve.
+  #[must_use]
+  pub fn service_name(&self) -> &str {
+    &self.service_name
+  }
+
+  /// Returns the record kind to resolve.
+  #[must_use]
+  pub const fn lookup_mode(&self) -> DnsLookupMode {
+    self.lookup_mode
+  }
+
+  /// Returns the minimum refresh interval.
+  #[must_use]
+  pub const fn min_refresh_interval(&self) -> Duration {
+    self.min_refresh_interval
+  }
+
+  /// Returns the maximum refresh interval.
+  #[must_use]
+  pub const fn max_refresh_interval(&self) -> Duration {
+    self.max_refresh_interval
+  }
+}
@@ -0,0 +1,13 @@
+//! Record kind queried by DNS discovery.
+
+/// Selects which DNS records DNS discovery resolves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DnsLookupMode {
+  /// Resolves SRV records and uses each target host and port as an authority.
+  Srv,
+  /// Resolves A/AAAA records and pairs each address with a fixed port.
+  Address {
+    /// Port used for cluster communication.
+    port: u16,
+  },
+}
@@ -0,0 +1,209 @@
+//! Minimal DNS wire format codec used by the UDP resolver.
+
+use std::{
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  string::String,
+  time::Duration,
+  vec::Vec,
+};
+
+use super::{DiscoveryBackendError, DnsAddressRecord, DnsSrvRecord};
+
+#[cfg(test)]
+#[path = "dns_message_test.rs"]
+mod tests;
+
+pub(crate) const TYPE_A: u16 = 1;
+pub(crate) const TYPE_AAAA: u16 = 28;
+pub(crate) const TYPE_SRV: u16 = 33;
+pub(crate) const RCODE_NAME_ERROR: u8 = 3;
+/// UDP payload size advertised through EDNS0, matching the resolver's receive buffer.
+pub(crate) const MAX_UDP_PAYLOAD_LEN: u16 = 4096;
+
+const TYPE_OPT: u16 = 41;
+const CLASS_IN: u16 = 1;
+const HEADER_LEN: usize = 12;
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_TRUNCATED: u16 = 0x0200;
+const FLAG_RECURSION_DESIRED: u16 = 0x0100;
+const MAX_LABEL_LEN: usize = 63;
+const MAX_POINTER_JUMPS: usize = 16;
+
+/// Decoded DNS response carrying the answers DNS discovery understands.
+#[derive(Debug, Default)]
+pub(crate) struct DnsMessage {
+  pub(crate) id:              u16,
+  pub(crate) truncated:       bool,
+  pub(crate) rcode:           u8,
+  /// Names and types of the questions the response echoes.
+  pub(crate) questions:       Vec<(String, u16)>,
+  pub(crate) srv_records:     Vec<DnsSrvRecord>,
+  pub(crate) address_records: Vec<DnsAddressRecord>,
+}
+
+impl DnsMessage {
+  /// Encodes a recursive query for `name` and `record_type`.
+  ///
+  /// The query carries an EDNS0 OPT record advertising [`MAX_UDP_PAYLOAD_LEN`], so nameservers
+  /// do not truncate UDP responses at 512 bytes.
+  pub(crate) fn encode_query(id: u16, name: &str, record_type: u16) -> Result<Vec<u8>, DiscoveryBackendError> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + name.len() + 17);
+    bytes.extend_from_slice(&id.to_be_bytes());
+    bytes.extend_from_slice(&FLAG_RECURSION_DESIRED.to_be_bytes());
+    // 質問 1 件と追加セクションの OPT レコード 1 件
+    bytes.extend_from_slice(&1_u16.to_be_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.extend_from_slice(&1_u16.to_be_bytes());
+    for label in name.trim_end_matches('.').split('.') {
+      if label.is_empty() || label.len() > MAX_LABEL_LEN {
+        return Err(DiscoveryBackendError::temporary("invalid dns name"));
+      }
+      // 長さは MAX_LABEL_LEN 以下であることを確認済み
+      bytes.push(label.len() as u8);
+      bytes.extend_from_slice(label.as_bytes());
+    }
+    bytes.push(0);
+    bytes.extend_from_slice(&record_type.to_be_bytes());
+    bytes.extend_from_slice(&CLASS_IN.to_be_bytes());
+    // OPT レコードはルート名を持ち、クラスに受信可能なペイロード長を入れる
+    bytes.push(0);
+    bytes.extend_from_slice(&TYPE_OPT.to_be_bytes());
+    bytes.extend_from_slice(&MAX_UDP_PAYLOAD_LEN.to_be_bytes());
+    bytes.extend_from_slice(&[0; 6]);
+    Ok(bytes)
+  }
+
+  /// Returns whether the response echoes exactly the question for `name` and `record_type`.
+  pub(crate) fn answers_question(&self, name: &str, record_type: u16) -> bool {
+    let name = name.trim_end_matches('.');
+    matches!(
+      self.questions.as_slice(),
+      [(question, question_type)] if *question_type == record_type && question.eq_ignore_ascii_case(name)
+    )
+  }
+
+  /// Decodes a response, skipping answers of unrelated types or classes.
+  pub(crate) fn decode(bytes: &[u8]) -> Result<Self, DiscoveryBackendError> {
+    let mut reader = Reader { bytes, offset: 0 };
+    let id = reader.u16()?;
+    let flags = reader.u16()?;
+    if flags & FLAG_RESPONSE == 0 {
+      return Err(DiscoveryBackendError::temporary("dns message is not a response"));
+    }
+    let question_count = reader.u16()?;
+    let answer_count = reader.u16()?;
+    reader.skip(4)?;
+    let mut message =
+      Self { id, truncated: flags & FLAG_TRUNCATED != 0, rcode: (flags & 0x000F) as u8, ..Self::default() };
+    for _ in 0..question_count {
+      let name = reader.name()?;
+      let record_type = reader.u16()?;
+      if reader.u16()? == CLASS_IN {
+        message.questions.push((name, record_type));
+      }
+    }
+    for _ in 0..answer_count {
+      reader.name()?;
+      let record_type = reader.u16()?;
+      let class = reader.u16()?;
+      let ttl = Duration::from_secs(u64::from(reader.u32()?));
+      let data_len = usize::from(reader.u16()?);
+      let data_start = reader.offset;
+      let data = reader.take(data_len)?;
+      if class != CLASS_IN {
+        continue;
+      }
+      match (record_type, data.len()) {
+        | (TYPE_A, 4) => {
+          let address = Ipv4Addr::new(data[0], data[1], data[2], data[3]);
+          message.address_records.push(DnsAddressRecord::new(IpAddr::V4(address), ttl));
+        },
+        | (TYPE_AAAA, 16) => {
+          let mut octets = [0_u8; 16];
+          octets.copy_from_slice(data);
+          message.address_records.push(DnsAddressRecord::new(IpAddr::V6(Ipv6Addr::from(octets)), ttl));
+        },
+        | (TYPE_SRV, len) if len > 6 => {
+          let mut rdata = Reader { bytes, offset: data_start };
+          let priority = rdata.u16()?;
+          let weight = rdata.u16()?;
+          let port = rdata.u16()?;
+          let target = rdata.name()?;
+          message.srv_records.push(DnsSrvRecord::new(target, port, priority, weight, ttl));
+        },
+        | _ => {},
+      }
+    }
+    Ok(message)
+  }
+}
+
+struct Reader<'a> {
+  bytes:  &'a [u8],
+  offset: usize,
+}
+
+impl<'a> Reader<'a> {
+  fn take(&mut self, len: usize) -> Result<&'a [u8], DiscoveryBackendError> {
+    let end = self.offset.checked_add(len).filter(|end| *end <= self.bytes.len()).ok_or_else(truncated_message)?;
+    let slice = &self.bytes[self.offset..end];
+    self.offset = end;
+    Ok(slice)
+  }
+
+  fn skip(&mut self, len: usize) -> Result<(), DiscoveryBackendError> {
+    self.take(len).map(|_| ())
+  }
+
+  fn u8(&mut self) -> Result<u8, DiscoveryBackendError> {
+    Ok(self.take(1)?[0])
+  }
+
+  fn u16(&mut self) -> Result<u16, DiscoveryBackendError> {
+    let bytes = self.take(2)?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+  }
+
+  fn u32(&mut self) -> Result<u32, DiscoveryBackendError> {
+    let bytes = self.take(4)?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+  }
+
+  /// Reads a possibly compressed name and leaves the cursor after its first occurrence.
+  fn name(&mut self) -> Result<String, DiscoveryBackendError> {
+    let mut name = String::new();
+    let mut cursor = Reader { bytes: self.bytes, offset: self.offset };
+    let mut resume_at = None;
+    let mut jumps = 0;
+    loop {
+      let len = cursor.u8()?;
+      match len & 0xC0 {
+        | 0x00 if len == 0 => break,
+        | 0x00 => {
+          let label = cursor.take(usize::from(len))?;
+          if !name.is_empty() {
+            name.push('.');
+          }
+          name.push_str(&String::from_utf8_lossy(label));
+        },
+        | 0xC0 => {
+          let pointer = (usize::from(len & 0x3F) << 8) | usize::from(cursor.u8()?);
+          resume_at.get_or_insert(cursor.offset);
+          // 圧縮ポインタの循環で無限ループしないように上限を設ける
+          jumps += 1;
+          if jumps > MAX_POINTER_JUMPS {
+            return Err(DiscoveryBackendError::temporary("dns name compression loop"));
+          }
+          cursor.offset = pointer;
+        },
+        | _ => return Err(DiscoveryBackendError::temporary("unsupported dns label type")),
+      }
+    }
+    self.offset = resume_at.unwrap_or(cursor.offset);
+    Ok(name)
+  }
+}
+
+fn truncated_message() -> DiscoveryBackendError {
+  DiscoveryBackendError::temporary("truncated dns message")
+}
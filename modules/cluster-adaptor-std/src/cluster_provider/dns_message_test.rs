@@ -0,0 +1,134 @@
+use std::{
+  net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  time::Duration,
+  vec::Vec,
+};
+
+use super::{DnsMessage, TYPE_A, TYPE_AAAA, TYPE_SRV};
+use crate::cluster_provider::DiscoveryBackendError;
+
+const OPT_RECORD_LEN: usize = 11;
+
+fn response_header(id: u16, flags: u16, answers: u16) -> Vec<u8> {
+  let mut bytes = Vec::new();
+  bytes.extend_from_slice(&id.to_be_bytes());
+  bytes.extend_from_slice(&flags.to_be_bytes());
+  bytes.extend_from_slice(&1_u16.to_be_bytes());
+  bytes.extend_from_slice(&answers.to_be_bytes());
+  bytes.extend_from_slice(&[0; 4]);
+  bytes
+}
+
+fn push_answer(bytes: &mut Vec<u8>, record_type: u16, ttl: u32, data: &[u8]) {
+  // 質問セクションの名前 (オフセット 12) を指す圧縮ポインタ
+  bytes.extend_from_slice(&[0xC0, 0x0C]);
+  bytes.extend_from_slice(&record_type.to_be_bytes());
+  bytes.extend_from_slice(&1_u16.to_be_bytes());
+  bytes.extend_from_slice(&ttl.to_be_bytes());
+  bytes.extend_from_slice(&(data.len() as u16).to_be_bytes());
+  bytes.extend_from_slice(data);
+}
+
+fn response_with_question(name: &str, record_type: u16, answers: u16) -> Vec<u8> {
+  let query = DnsMessage::encode_query(7, name, record_type).expect("query");
+  let mut bytes = response_header(7, 0x8180, answers);
+  // 末尾の OPT レコード (11 バイト) を除いた質問セクションだけを写す
+  bytes.extend_from_slice(&query[12..query.len() - OPT_RECORD_LEN]);
+  bytes
+}
+
+#[test]
+fn encode_query_writes_header_and_labels() {
+  let bytes = DnsMessage::encode_query(0x1234, "seeds.example.", TYPE_A).expect("query");
+
+  assert_eq!(&bytes[..4], &[0x12, 0x34, 0x01, 0x00]);
+  assert_eq!(&bytes[4..6], &[0x00, 0x01]);
+  assert_eq!(&bytes[12..bytes.len() - OPT_RECORD_LEN], b"\x05seeds\x07example\x00\x00\x01\x00\x01");
+}
+
+#[test]
+fn encode_query_advertises_the_udp_payload_size_through_edns() {
+  let bytes = DnsMessage::encode_query(1, "seeds.example", TYPE_SRV).expect("query");
+
+  assert_eq!(&bytes[10..12], &[0x00, 0x01]);
+  assert_eq!(&bytes[bytes.len() - OPT_RECORD_LEN..], &[0, 0x00, 0x29, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn encode_query_rejects_empty_labels() {
+  assert_eq!(
+    DnsMessage::encode_query(1, "seeds..example", TYPE_A).unwrap_err(),
+    DiscoveryBackendError::temporary("invalid dns name")
+  );
+}
+
+#[test]
+fn decode_reads_address_records_with_ttl() {
+  let mut bytes = response_with_question("seeds.example", TYPE_A, 2);
+  push_answer(&mut bytes, TYPE_A, 30, &[10, 0, 1, 12]);
+  push_answer(&mut bytes, TYPE_AAAA, 45, &Ipv6Addr::LOCALHOST.octets());
+
+  let message = DnsMessage::decode(&bytes).expect("response");
+
+  assert_eq!(message.id, 7);
+  assert!(!message.truncated);
+  assert_eq!(message.address_records.len(), 2);
+  assert_eq!(message.address_records[0].address(), IpAddr::V4(Ipv4Addr::new(10, 0, 1, 12)));
+  assert_eq!(message.address_records[0].ttl(), Duration::from_secs(30));
+  assert_eq!(message.address_records[1].address(), IpAddr::V6(Ipv6Addr::LOCALHOST));
+}
+
+#[test]
+fn decode_reads_srv_records_with_compressed_targets() {
+  let mut bytes = response_with_question("_fraktor._tcp.example", TYPE_SRV, 1);
+  let mut data = Vec::from([0, 10, 0, 5, 0x1C, 0xA3]);
+  data.extend_from_slice(b"\x06node-a");
+  // "_fraktor._tcp.example" の "example" (オフセット 12 + 9 + 5) を指す
+  data.extend_from_slice(&[0xC0, 26]);
+  push_answer(&mut bytes, TYPE_SRV, 60, &data);
+
+  let message = DnsMessage::decode(&bytes).expect("response");
+
+  assert_eq!(message.srv_records.len(), 1);
+  let record = &message.srv_records[0];
+  assert_eq!(record.target(), "node-a.example");
+  assert_eq!(record.port(), 7331);
+  assert_eq!(record.priority(), 10);
+  assert_eq!(record.weight(), 5);
+  assert_eq!(record.ttl(), Duration::from_secs(60));
+}
+
+#[test]
+fn decode_reports_truncation_and_response_code() {
+  let bytes = response_with_question("missing.example", TYPE_A, 0);
+  let mut flags_bytes = bytes.clone();
+  flags_bytes[2] = 0x82;
+  flags_bytes[3] = 0x83;
+
+  let message = DnsMessage::decode(&flags_bytes).expect("response");
+
+  assert!(message.truncated);
+  assert_eq!(message.rcode, 3);
+}
+
+#[test]
+fn decode_rejects_malformed_messages() {
+  let mut bytes = response_with_question("seeds.example", TYPE_A, 1);
+  push_answer(&mut bytes, TYPE_A, 30, &[10, 0, 1, 12]);
+  bytes.truncate(bytes.len() - 2);
+
+  assert_eq!(DnsMessage::decode(&bytes).unwrap_err(), DiscoveryBackendError::temporary("truncated dns message"));
+
+  let mut looping = response_header(1, 0x8180, 0);
+  looping.extend_from_slice(&[0xC0, 0x0C]);
+  assert_eq!(DnsMessage::decode(&looping).unwrap_err(), DiscoveryBackendError::temporary("dns name compression loop"));
+}
+
+#[test]
+fn decode_reads_the_echoed_question() {
+  let message = DnsMessage::decode(&response_with_question("Seeds.Example", TYPE_SRV, 0)).expect("response");
+
+  assert!(message.answers_question("seeds.example.", TYPE_SRV));
+  assert!(!message.answers_question("seeds.example", TYPE_A));
+  assert!(!message.answers_question("other.example", TYPE_SRV));
+}
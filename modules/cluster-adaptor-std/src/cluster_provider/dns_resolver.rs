@@ -0,0 +1,24 @@
+//! Injectable DNS resolution contract.
+
+use std::vec::Vec;
+
+use super::{DiscoveryBackendError, DnsAddressRecord, DnsSrvRecord};
+
+/// Resolver used by [`DnsDiscoveryBackend`](super::DnsDiscoveryBackend).
+///
+/// A name that does not exist resolves to an empty record list rather than an error.
+pub trait DnsResolver {
+  /// Resolves SRV records for `name`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`DiscoveryBackendError`] when the lookup cannot be completed.
+  fn resolve_srv(&mut self, name: &str) -> Result<Vec<DnsSrvRecord>, DiscoveryBackendError>;
+
+  /// Resolves A and AAAA records for `name`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`DiscoveryBackendError`] when the lookup cannot be completed.
+  fn resolve_addresses(&mut self, name: &str) -> Result<Vec<DnsAddressRecord>, DiscoveryBackendError>;
+}
@@ -0,0 +1,51 @@
+//! DNS SRV record consumed by DNS discovery.
+
+use std::{string::String, time::Duration};
+
+/// SRV record returned by a [`DnsResolver`](super::DnsResolver).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DnsSrvRecord {
+  target:   String,
+  port:     u16,
+  priority: u16,
+  weight:   u16,
+  ttl:      Duration,
+}
+
+impl DnsSrvRecord {
+  /// Creates an SRV record.
+  #[must_use]
+  pub fn new(target: impl Into<String>, port: u16, priority: u16, weight: u16, ttl: Duration) -> Self {
+    Self { target: target.into(), port, priority, weight, ttl }
+  }
+
+  /// Returns the target host name.
+  #[must_use]
+  pub fn target(&self) -> &str {
+    &self.target
+  }
+
+  /// Returns the target port.
+  #[must_use]
+  pub const fn port(&self) -> u16 {
+    self.port
+  }
+
+  /// Returns the record priority.
+  #[must_use]
+  pub const fn priority(&self) -> u16 {
+    self.priority
+  }
+
+  /// Returns the record weight.
+  #[must_use]
+  pub const fn weight(&self) -> u16 {
+    self.weight
+  }
+
+  /// Returns the record time-to-live.
+  #[must_use]
+  pub const fn ttl(&self) -> Duration {
+    self.ttl
+  }
+}
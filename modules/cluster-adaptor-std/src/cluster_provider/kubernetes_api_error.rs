@@ -0,0 +1,27 @@
+//! Failure talking to the Kubernetes API.
+
+use std::string::{String, ToString};
+
+use thiserror::Error;
+
+use super::DiscoveryBackendError;
+
+/// Error reported by Kubernetes backends and their HTTP clients.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum KubernetesApiError {
+  /// The request could not be delivered or the response could not be read.
+  #[error("kubernetes api transport failed: {0}")]
+  Transport(String),
+  /// The API answered with a status the backend does not handle.
+  #[error("kubernetes api returned unexpected status {0}")]
+  UnexpectedStatus(u16),
+  /// The response body could not be interpreted.
+  #[error("malformed kubernetes api response: {0}")]
+  MalformedResponse(String),
+}
+
+impl From<KubernetesApiError> for DiscoveryBackendError {
+  fn from(error: KubernetesApiError) -> Self {
+    DiscoveryBackendError::temporary(error.to_string())
+  }
+}
@@ -0,0 +1,65 @@
+//! Configuration for Kubernetes pod discovery.
+
+use std::string::String;
+
+/// Configuration for [`KubernetesPodDiscoveryBackend`](super::KubernetesPodDiscoveryBackend).
+#[derive(Clone, Debug)]
+pub struct KubernetesDiscoveryConfig {
+  namespace:      String,
+  label_selector: String,
+  port_name:      Option<String>,
+  port:           u16,
+}
+
+impl KubernetesDiscoveryConfig {
+  /// Creates a configuration listing pods in `namespace` that match `label_selector`.
+  #[must_use]
+  pub fn new(namespace: impl Into<String>, label_selector: impl Into<String>) -> Self {
+    Self {
+      namespace:      namespace.into(),
+      label_selector: label_selector.into(),
+      port_name:      None,
+      port:           8080,
+    }
+  }
+
+  /// Resolves the port from the named container port instead of the fixed port.
+  ///
+  /// Pods that do not declare the named port are skipped.
+  #[must_use]
+  pub fn with_port_name(mut self, port_name: impl Into<String>) -> Self {
+    self.port_name = Some(port_name.into());
+    self
+  }
+
+  /// Sets the fixed port used when no port name is configured.
+  #[must_use]
+  pub const fn with_port(mut self, port: u16) -> Self {
+    self.port = port;
+    self
+  }
+
+  /// Returns the pod namespace.
+  #[must_use]
+  pub fn namespace(&self) -> &str {
+    &self.namespace
+  }
+
+  /// Returns the label selector.
+  #[must_use]
+  pub fn label_selector(&self) -> &str {
+    &self.label_selector
+  }
+
+  /// Returns the named container port.
+  #[must_use]
+  pub fn port_name(&self) -> Option<&str> {
+    self.port_name.as_deref()
+  }
+
+  /// Returns the fixed port.
+  #[must_use]
+  pub const fn port(&self) -> u16 {
+    self.port
+  }
+}
@@ -0,0 +1,16 @@
+//! Pluggable HTTP transport for Kubernetes backends.
+
+use super::{KubernetesApiError, KubernetesHttpRequest, KubernetesHttpResponse};
+
+/// HTTP transport used by Kubernetes discovery and lease backends.
+///
+/// Implementations are responsible for the API server endpoint, TLS and authentication, so tests
+/// can substitute a local fake API server.
+pub trait KubernetesHttpClient: Send + Sync {
+  /// Sends `request` and returns the response regardless of its status code.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`KubernetesApiError::Transport`] when no response could be obtained.
+  fn send(&mut self, request: &KubernetesHttpRequest) -> Result<KubernetesHttpResponse, KubernetesApiError>;
+}
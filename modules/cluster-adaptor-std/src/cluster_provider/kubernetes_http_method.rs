@@ -0,0 +1,24 @@
+//! HTTP methods used against the Kubernetes API.
+
+/// HTTP method issued by Kubernetes backends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KubernetesHttpMethod {
+  /// Reads a resource.
+  Get,
+  /// Creates a resource.
+  Post,
+  /// Replaces a resource.
+  Put,
+}
+
+impl KubernetesHttpMethod {
+  /// Returns the method token used on the wire.
+  #[must_use]
+  pub const fn as_str(self) -> &'static str {
+    match self {
+      | Self::Get => "GET",
+      | Self::Post => "POST",
+      | Self::Put => "PUT",
+    }
+  }
+}
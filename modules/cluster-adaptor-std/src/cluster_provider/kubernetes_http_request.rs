@@ -0,0 +1,46 @@
+//! Request issued to the Kubernetes API.
+
+use std::string::String;
+
+use super::KubernetesHttpMethod;
+
+/// Request issued by Kubernetes backends through a
+/// [`KubernetesHttpClient`](super::KubernetesHttpClient).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KubernetesHttpRequest {
+  method: KubernetesHttpMethod,
+  path:   String,
+  body:   Option<String>,
+}
+
+impl KubernetesHttpRequest {
+  /// Creates a `GET` request for `path`.
+  #[must_use]
+  pub fn get(path: impl Into<String>) -> Self {
+    Self { method: KubernetesHttpMethod::Get, path: path.into(), body: None }
+  }
+
+  /// Creates a request carrying a JSON body.
+  #[must_use]
+  pub fn with_json_body(method: KubernetesHttpMethod, path: impl Into<String>, body: impl Into<String>) -> Self {
+    Self { method, path: path.into(), body: Some(body.into()) }
+  }
+
+  /// Returns the request method.
+  #[must_use]
+  pub const fn method(&self) -> KubernetesHttpMethod {
+    self.method
+  }
+
+  /// Returns the request path including the query string.
+  #[must_use]
+  pub fn path(&self) -> &str {
+    &self.path
+  }
+
+  /// Returns the JSON body, if any.
+  #[must_use]
+  pub fn body(&self) -> Option<&str> {
+    self.body.as_deref()
+  }
+}
@@ -0,0 +1,36 @@
+//! Response returned by the Kubernetes API.
+
+use std::string::String;
+
+/// Response returned by a [`KubernetesHttpClient`](super::KubernetesHttpClient).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KubernetesHttpResponse {
+  status: u16,
+  body:   String,
+}
+
+impl KubernetesHttpResponse {
+  /// Creates a response.
+  #[must_use]
+  pub fn new(status: u16, body: impl Into<String>) -> Self {
+    Self { status, body: body.into() }
+  }
+
+  /// Returns the HTTP status code.
+  #[must_use]
+  pub const fn status(&self) -> u16 {
+    self.status
+  }
+
+  /// Returns the response body.
+  #[must_use]
+  pub fn body(&self) -> &str {
+    &self.body
+  }
+
+  /// Returns true for 2xx status codes.
+  #[must_use]
+  pub const fn is_success(&self) -> bool {
+    self.status >= 200 && self.status < 300
+  }
+}
@@ -0,0 +1,58 @@
+//! Configuration for the Kubernetes lease backend.
+
+use std::{string::String, time::Duration};
+
+/// Configuration for [`KubernetesLeaseMajorityBackend`](super::KubernetesLeaseMajorityBackend).
+#[derive(Clone, Debug)]
+pub struct KubernetesLeaseConfig {
+  namespace:       String,
+  lease_name:      String,
+  holder_identity: String,
+  lease_duration:  Duration,
+}
+
+impl KubernetesLeaseConfig {
+  /// Creates a configuration for the `coordination.k8s.io` Lease `lease_name` in `namespace`.
+  ///
+  /// `holder_identity` must be unique per cluster member.
+  #[must_use]
+  pub fn new(namespace: impl Into<String>, lease_name: impl Into<String>, holder_identity: impl Into<String>) -> Self {
+    Self {
+      namespace:       namespace.into(),
+      lease_name:      lease_name.into(),
+      holder_identity: holder_identity.into(),
+      lease_duration:  Duration::from_secs(120),
+    }
+  }
+
+  /// Sets how long an acquired lease stays valid without renewal.
+  #[must_use]
+  pub const fn with_lease_duration(mut self, lease_duration: Duration) -> Self {
+    self.lease_duration = lease_duration;
+    self
+  }
+
+  /// Returns the lease namespace.
+  #[must_use]
+  pub fn namespace(&self) -> &str {
+    &self.namespace
+  }
+
+  /// Returns the lease name.
+  #[must_use]
+  pub fn lease_name(&self) -> &str {
+    &self.lease_name
+  }
+
+  /// Returns the holder identity written into the lease.
+  #[must_use]
+  pub fn holder_identity(&self) -> &str {
+    &self.holder_identity
+  }
+
+  /// Returns the lease duration.
+  #[must_use]
+  pub const fn lease_duration(&self) -> Duration {
+    self.lease_duration
+  }
+}
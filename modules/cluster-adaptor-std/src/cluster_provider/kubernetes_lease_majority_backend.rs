@@ -0,0 +1,196 @@
+//! Lease-majority backend on top of Kubernetes `coordination.k8s.io` Lease objects.
+
+use std::{
+  format,
+  string::{String, ToString},
+  time::{Duration, SystemTime},
+};
+
+use fraktor_cluster_core_kernel_rs::downing_provider::{DowningDecisionContext, LeaseAcquisitionOutcome};
+use serde_json::{Value, json};
+
+use super::{
+  KubernetesApiError, KubernetesHttpClient, KubernetesHttpMethod, KubernetesHttpRequest, KubernetesLeaseConfig,
+  StdLeaseMajorityBackend,
+  kubernetes_micro_time::{format_micro_time, parse_micro_time},
+};
+
+#[cfg(test)]
+#[path = "kubernetes_lease_majority_backend_test.rs"]
+mod tests;
+
+const HTTP_OK: u16 = 200;
+const HTTP_CREATED: u16 = 201;
+const HTTP_NOT_FOUND: u16 = 404;
+const HTTP_CONFLICT: u16 = 409;
+
+/// [`StdLeaseMajorityBackend`] that takes a Kubernetes Lease before downing the minority side.
+///
+/// The lease is granted when it is free, expired or already held by this member. Updates carry the
+/// observed `resourceVersion`, so concurrent acquirers are resolved by the API server.
+pub struct KubernetesLeaseMajorityBackend<C> {
+  config: KubernetesLeaseConfig,
+  client: C,
+  held:   bool,
+}
+
+impl<C> KubernetesLeaseMajorityBackend<C>
+where
+  C: KubernetesHttpClient,
+{
+  /// Creates a lease backend using the given HTTP client.
+  #[must_use]
+  pub const fn new(config: KubernetesLeaseConfig, client: C) -> Self {
+    Self { config, client, held: false }
+  }
+
+  /// Returns the backend configuration.
+  #[must_use]
+  pub const fn config(&self) -> &KubernetesLeaseConfig {
+    &self.config
+  }
+
+  /// Returns whether the last acquisition granted the lease to this member.
+  #[must_use]
+  pub const fn is_held(&self) -> bool {
+    self.held
+  }
+
+  fn collection_path(&self) -> String {
+    format!("/apis/coordination.k8s.io/v1/namespaces/{}/leases", self.config.namespace())
+  }
+
+  fn lease_path(&self) -> String {
+    format!("{}/{}", self.collection_path(), self.config.lease_name())
+  }
+
+  fn try_acquire(&mut self, now: SystemTime) -> Result<bool, KubernetesApiError> {
+    let response = self.client.send(&KubernetesHttpRequest::get(self.lease_path()))?;
+    let acquired = match response.status() {
+      | HTTP_NOT_FOUND => self.create_lease(now)?,
+      | HTTP_OK => {
+        let mut lease = parse_lease(response.body())?;
+        if self.can_take(&lease, now) { self.renew_lease(&mut lease, now)? } else { false }
+      },
+      | status => return Err(KubernetesApiError::UnexpectedStatus(status)),
+    };
+    self.held = acquired;
+    Ok(acquired)
+  }
+
+  fn can_take(&self, lease: &Value, now: SystemTime) -> bool {
+    let spec = &lease["spec"];
+    match spec["holderIdentity"].as_str() {
+      | None | Some("") => true,
+      | Some(holder) if holder == self.config.holder_identity() => true,
+      | Some(_) => {
+        let renewed_at = spec["renewTime"].as_str().and_then(parse_micro_time);
+        let duration = spec["leaseDurationSeconds"].as_u64().map(Duration::from_secs);
+        // 更新時刻や期間を解釈できないリースは他メンバーのものとして扱う
+        renewed_at.zip(duration).is_some_and(|(renewed_at, duration)| renewed_at + duration <= now)
+      },
+    }
+  }
+
+  fn create_lease(&mut self, now: SystemTime) -> Result<bool, KubernetesApiError> {
+    let timestamp = format_micro_time(now);
+    let lease = json!({
+      "apiVersion": "coordination.k8s.io/v1",
+      "kind": "Lease",
+      "metadata": { "name": self.config.lease_name(), "namespace": self.config.namespace() },
+      "spec": {
+        "holderIdentity": self.config.holder_identity(),
+        "leaseDurationSeconds": self.config.lease_duration().as_secs(),
+        "acquireTime": timestamp,
+        "renewTime": timestamp,
+        "leaseTransitions": 0,
+      },
+    });
+    let request =
+      KubernetesHttpRequest::with_json_body(KubernetesHttpMethod::Post, self.collection_path(), lease.to_string());
+    self.write(&request)
+  }
+
+  fn renew_lease(&mut self, lease: &mut Value, now: SystemTime) -> Result<bool, KubernetesApiError> {
+    let timestamp = format_micro_time(now);
+    let spec = &mut lease["spec"];
+    if spec["holderIdentity"].as_str() != Some(self.config.holder_identity()) {
+      let transitions = spec["leaseTransitions"].as_u64().unwrap_or(0);
+      spec["holderIdentity"] = json!(self.config.holder_identity());
+      spec["acquireTime"] = json!(timestamp);
+      spec["leaseTransitions"] = json!(transitions + 1);
+    }
+    spec["leaseDurationSeconds"] = json!(self.config.lease_duration().as_secs());
+    spec["renewTime"] = json!(timestamp);
+    let request =
+      KubernetesHttpRequest::with_json_body(KubernetesHttpMethod::Put, self.lease_path(), lease.to_string());
+    self.write(&request)
+  }
+
+  fn release(&mut self) -> Result<(), KubernetesApiError> {
+    let response = self.client.send(&KubernetesHttpRequest::get(self.lease_path()))?;
+    if response.status() != HTTP_OK {
+      return Err(KubernetesApiError::UnexpectedStatus(response.status()));
+    }
+    let mut lease = parse_lease(response.body())?;
+    if lease["spec"]["holderIdentity"].as_str() != Some(self.config.holder_identity()) {
+      return Ok(());
+    }
+    lease["spec"]["holderIdentity"] = Value::Null;
+    let request =
+      KubernetesHttpRequest::with_json_body(KubernetesHttpMethod::Put, self.lease_path(), lease.to_string());
+    self.write(&request).map(|_| ())
+  }
+
+  fn write(&mut self, request: &KubernetesHttpRequest) -> Result<bool, KubernetesApiError> {
+    let response = self.client.send(request)?;
+    match response.status() {
+      | HTTP_OK | HTTP_CREATED => Ok(true),
+      // 他メンバーが先に作成・更新した
+      | HTTP_CONFLICT => Ok(false),
+      | status => Err(KubernetesApiError::UnexpectedStatus(status)),
+    }
+  }
+}
+
+impl<C> StdLeaseMajorityBackend for KubernetesLeaseMajorityBackend<C>
+where
+  C: KubernetesHttpClient,
+{
+  fn acquire(&mut self, _context: &DowningDecisionContext) -> LeaseAcquisitionOutcome {
+    match self.try_acquire(SystemTime::now()) {
+      | Ok(true) => LeaseAcquisitionOutcome::Acquired,
+      | Ok(false) => LeaseAcquisitionOutcome::Denied,
+      | Err(error) => {
+        self.held = false;
+        tracing::warn!(%error, lease = self.config.lease_name(), "kubernetes lease acquisition failed");
+        match error {
+          | KubernetesApiError::Transport(_) => LeaseAcquisitionOutcome::Unavailable,
+          | KubernetesApiError::UnexpectedStatus(status) if status >= 500 => LeaseAcquisitionOutcome::Unavailable,
+          | KubernetesApiError::UnexpectedStatus(_) | KubernetesApiError::MalformedResponse(_) => {
+            LeaseAcquisitionOutcome::Unknown
+          },
+        }
+      },
+    }
+  }
+
+  fn close(&mut self) {
+    if !self.held {
+      return;
+    }
+    self.held = false;
+    if let Err(error) = self.release() {
+      tracing::warn!(%error, lease = self.config.lease_name(), "kubernetes lease release failed");
+    }
+  }
+}
+
+fn parse_lease(body: &str) -> Result<Value, KubernetesApiError> {
+  let lease: Value =
+    serde_json::from_str(body).map_err(|error| KubernetesApiError::MalformedResponse(error.to_string()))?;
+  if !lease.is_object() {
+    return Err(KubernetesApiError::MalformedResponse(String::from("lease is not a json object")));
+  }
+  Ok(lease)
+}
@@ -0,0 +1,155 @@
+use std::{
+  collections::VecDeque,
+  string::String,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+  vec::Vec,
+};
+
+use fraktor_cluster_core_kernel_rs::downing_provider::{DowningDecisionContext, LeaseAcquisitionOutcome};
+use fraktor_utils_core_rs::time::TimerInstant;
+use serde_json::Value;
+
+use super::KubernetesLeaseMajorityBackend;
+use crate::cluster_provider::{
+  KubernetesApiError, KubernetesHttpClient, KubernetesHttpMethod, KubernetesHttpRequest, KubernetesHttpResponse,
+  KubernetesLeaseConfig, StdLeaseMajorityBackend,
+};
+
+const LEASE_PATH: &str = "/apis/coordination.k8s.io/v1/namespaces/fraktor/leases/sbr";
+
+struct ScriptedClient {
+  responses: VecDeque<Result<KubernetesHttpResponse, KubernetesApiError>>,
+  requests:  Vec<KubernetesHttpRequest>,
+}
+
+impl ScriptedClient {
+  fn new(responses: Vec<Result<KubernetesHttpResponse, KubernetesApiError>>) -> Self {
+    Self { responses: responses.into(), requests: Vec::new() }
+  }
+}
+
+impl KubernetesHttpClient for ScriptedClient {
+  fn send(&mut self, request: &KubernetesHttpRequest) -> Result<KubernetesHttpResponse, KubernetesApiError> {
+    self.requests.push(request.clone());
+    self.responses.pop_front().expect("scripted response")
+  }
+}
+
+fn ok(status: u16, body: &str) -> Result<KubernetesHttpResponse, KubernetesApiError> {
+  Ok(KubernetesHttpResponse::new(status, body))
+}
+
+fn lease_body(holder: &str, renew_time: &str) -> String {
+  std::format!(
+    r#"{{"metadata":{{"name":"sbr","resourceVersion":"7"}},"spec":{{"holderIdentity":"{holder}","leaseDurationSeconds":10,"renewTime":"{renew_time}","leaseTransitions":2}}}}"#
+  )
+}
+
+fn backend(
+  responses: Vec<Result<KubernetesHttpResponse, KubernetesApiError>>,
+) -> KubernetesLeaseMajorityBackend<ScriptedClient> {
+  let config = KubernetesLeaseConfig::new("fraktor", "sbr", "node-a").with_lease_duration(Duration::from_secs(15));
+  KubernetesLeaseMajorityBackend::new(config, ScriptedClient::new(responses))
+}
+
+fn now() -> SystemTime {
+  // 2024-03-01T00:00:00Z
+  UNIX_EPOCH + Duration::from_secs(1_709_251_200)
+}
+
+fn body_json(request: &KubernetesHttpRequest) -> Value {
+  serde_json::from_str(request.body().expect("request body")).expect("json body")
+}
+
+#[test]
+fn missing_lease_is_created_for_this_holder() {
+  let mut backend = backend(Vec::from([ok(404, "{}"), ok(201, "{}")]));
+
+  assert_eq!(backend.try_acquire(now()), Ok(true));
+  assert!(backend.is_held());
+
+  let create = &backend.client.requests[1];
+  assert_eq!(create.method(), KubernetesHttpMethod::Post);
+  assert_eq!(create.path(), "/apis/coordination.k8s.io/v1/namespaces/fraktor/leases");
+  let lease = body_json(create);
+  assert_eq!(lease["spec"]["holderIdentity"], "node-a");
+  assert_eq!(lease["spec"]["leaseDurationSeconds"], 15);
+  assert_eq!(lease["spec"]["renewTime"], "2024-03-01T00:00:00.000000Z");
+}
+
+#[test]
+fn lease_held_by_another_live_member_is_denied() {
+  let mut backend = backend(Vec::from([ok(200, &lease_body("node-b", "2024-02-29T23:59:55Z"))]));
+
+  assert_eq!(backend.try_acquire(now()), Ok(false));
+  assert_eq!(backend.client.requests.len(), 1);
+}
+
+#[test]
+fn expired_lease_is_taken_over_with_resource_version() {
+  let mut backend = backend(Vec::from([ok(200, &lease_body("node-b", "2024-02-29T23:59:50Z")), ok(200, "{}")]));
+
+  assert_eq!(backend.try_acquire(now()), Ok(true));
+
+  let update = &backend.client.requests[1];
+  assert_eq!(update.method(), KubernetesHttpMethod::Put);
+  assert_eq!(update.path(), LEASE_PATH);
+  let lease = body_json(update);
+  assert_eq!(lease["metadata"]["resourceVersion"], "7");
+  assert_eq!(lease["spec"]["holderIdentity"], "node-a");
+  assert_eq!(lease["spec"]["leaseTransitions"], 3);
+  assert_eq!(lease["spec"]["acquireTime"], "2024-03-01T00:00:00.000000Z");
+}
+
+#[test]
+fn own_lease_is_renewed_without_a_transition() {
+  let mut backend = backend(Vec::from([ok(200, &lease_body("node-a", "2024-02-29T23:59:59Z")), ok(200, "{}")]));
+
+  assert_eq!(backend.try_acquire(now()), Ok(true));
+
+  let lease = body_json(&backend.client.requests[1]);
+  assert_eq!(lease["spec"]["leaseTransitions"], 2);
+  assert_eq!(lease["spec"]["renewTime"], "2024-03-01T00:00:00.000000Z");
+}
+
+#[test]
+fn conflicting_update_is_denied() {
+  let mut backend = backend(Vec::from([ok(200, &lease_body("", "2024-02-29T23:59:59Z")), ok(409, "{}")]));
+
+  assert_eq!(backend.try_acquire(now()), Ok(false));
+  assert!(!backend.is_held());
+}
+
+#[test]
+fn api_failures_map_to_lease_outcomes() {
+  let context =
+    DowningDecisionContext::from_explicit_down("node-b", TimerInstant::from_ticks(1, Duration::from_secs(1)));
+
+  let mut unreachable = backend(Vec::from([Err(KubernetesApiError::Transport(String::from("refused")))]));
+  assert_eq!(unreachable.acquire(&context), LeaseAcquisitionOutcome::Unavailable);
+
+  let mut server_error = backend(Vec::from([ok(503, "")]));
+  assert_eq!(server_error.acquire(&context), LeaseAcquisitionOutcome::Unavailable);
+
+  let mut malformed = backend(Vec::from([ok(200, "[]")]));
+  assert_eq!(malformed.acquire(&context), LeaseAcquisitionOutcome::Unknown);
+}
+
+#[test]
+fn close_releases_a_held_lease() {
+  let mut backend = backend(Vec::from([
+    ok(404, "{}"),
+    ok(201, "{}"),
+    ok(200, &lease_body("node-a", "2024-03-01T00:00:00Z")),
+    ok(200, "{}"),
+  ]));
+  backend.try_acquire(now()).expect("acquire");
+
+  backend.close();
+
+  assert!(!backend.is_held());
+  let release = body_json(&backend.client.requests[3]);
+  assert_eq!(release["spec"]["holderIdentity"], Value::Null);
+  backend.close();
+  assert_eq!(backend.client.requests.len(), 4);
+}
@@ -0,0 +1,79 @@
+//! Kubernetes `MicroTime` (RFC 3339, UTC) conversion.
+
+use std::{
+  format,
+  string::String,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(test)]
+#[path = "kubernetes_micro_time_test.rs"]
+mod tests;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Formats `time` as `YYYY-MM-DDTHH:MM:SS.ffffffZ`.
+pub(crate) fn format_micro_time(time: SystemTime) -> String {
+  let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+  let seconds = since_epoch.as_secs();
+  let (year, month, day) = civil_from_days(seconds / SECONDS_PER_DAY);
+  let seconds_of_day = seconds % SECONDS_PER_DAY;
+  format!(
+    "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:06}Z",
+    seconds_of_day / 3600,
+    (seconds_of_day / 60) % 60,
+    seconds_of_day % 60,
+    since_epoch.subsec_micros()
+  )
+}
+
+/// Parses a UTC RFC 3339 timestamp with optional fractional seconds.
+pub(crate) fn parse_micro_time(value: &str) -> Option<SystemTime> {
+  let value = value.strip_suffix('Z')?;
+  let (date, time) = value.split_once('T')?;
+  let mut date_parts = date.splitn(3, '-');
+  let year = date_parts.next()?.parse::<u64>().ok()?;
+  let month = date_parts.next()?.parse::<u64>().ok()?;
+  let day = date_parts.next()?.parse::<u64>().ok()?;
+  let (clock, fraction) = time.split_once('.').unwrap_or((time, ""));
+  let mut clock_parts = clock.splitn(3, ':');
+  let hour = clock_parts.next()?.parse::<u64>().ok()?;
+  let minute = clock_parts.next()?.parse::<u64>().ok()?;
+  let second = clock_parts.next()?.parse::<u64>().ok()?;
+  if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+    return None;
+  }
+  let nanos = if fraction.is_empty() {
+    0
+  } else {
+    // 小数部はナノ秒精度までに切り詰める
+    let digits = &fraction[..fraction.len().min(9)];
+    digits.parse::<u32>().ok()? * 10_u32.pow(9 - digits.len() as u32)
+  };
+  let seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second;
+  Some(UNIX_EPOCH + Duration::new(seconds, nanos))
+}
+
+// Howard Hinnant の civil calendar アルゴリズム (1970 年以降のみ扱う)
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year / 400;
+  let year_of_era = year - era * 400;
+  let month_index = if month > 2 { month - 3 } else { month + 9 };
+  let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+  let days = days + 719_468;
+  let era = days / 146_097;
+  let day_of_era = days - era * 146_097;
+  let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+  let year = year_of_era + era * 400 + u64::from(month <= 2);
+  (year, month, day)
+}
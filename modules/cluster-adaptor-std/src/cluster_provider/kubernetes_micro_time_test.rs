@@ -0,0 +1,34 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use super::{format_micro_time, parse_micro_time};
+
+#[test]
+fn formats_utc_micro_time() {
+  let time = UNIX_EPOCH + Duration::new(1_709_251_199, 123_456_789);
+
+  assert_eq!(format_micro_time(time), "2024-02-29T23:59:59.123456Z");
+  assert_eq!(format_micro_time(UNIX_EPOCH), "1970-01-01T00:00:00.000000Z");
+}
+
+#[test]
+fn parses_timestamps_with_and_without_fractions() {
+  assert_eq!(
+    parse_micro_time("2024-02-29T23:59:59.123456Z"),
+    Some(UNIX_EPOCH + Duration::new(1_709_251_199, 123_456_000))
+  );
+  assert_eq!(parse_micro_time("2024-03-01T00:00:00Z"), Some(UNIX_EPOCH + Duration::from_secs(1_709_251_200)));
+}
+
+#[test]
+fn round_trips_formatted_time() {
+  let time = UNIX_EPOCH + Duration::new(4_102_444_800, 42_000);
+
+  assert_eq!(parse_micro_time(&format_micro_time(time)), Some(time));
+}
+
+#[test]
+fn rejects_non_utc_or_malformed_timestamps() {
+  assert_eq!(parse_micro_time("2024-03-01T00:00:00+09:00"), None);
+  assert_eq!(parse_micro_time("2024-13-01T00:00:00Z"), None);
+  assert_eq!(parse_micro_time("yesterday"), None);
+}
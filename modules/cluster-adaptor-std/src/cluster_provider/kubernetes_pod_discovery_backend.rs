@@ -0,0 +1,102 @@
+//! Kubernetes API pod discovery backend.
+
+use std::{
+  format,
+  net::IpAddr,
+  string::{String, ToString},
+  vec::Vec,
+};
+
+use super::{
+  DiscoveryBackend, DiscoveryBackendError, KubernetesApiError, KubernetesDiscoveryConfig, KubernetesHttpClient,
+  KubernetesHttpRequest, kubernetes_pod_list::KubernetesPodList,
+};
+
+#[cfg(test)]
+#[path = "kubernetes_pod_discovery_backend_test.rs"]
+mod tests;
+
+/// Discovery backend that lists ready pods by label selector through the Kubernetes API.
+pub struct KubernetesPodDiscoveryBackend<C> {
+  config:          KubernetesDiscoveryConfig,
+  client:          C,
+  source_identity: String,
+}
+
+impl<C> KubernetesPodDiscoveryBackend<C>
+where
+  C: KubernetesHttpClient,
+{
+  /// Creates a pod discovery backend using the given HTTP client.
+  #[must_use]
+  pub fn new(config: KubernetesDiscoveryConfig, client: C) -> Self {
+    let source_identity = format!("kubernetes:{}/{}", config.namespace(), config.label_selector());
+    Self { config, client, source_identity }
+  }
+
+  /// Returns the backend configuration.
+  #[must_use]
+  pub const fn config(&self) -> &KubernetesDiscoveryConfig {
+    &self.config
+  }
+
+  fn pods_path(&self) -> String {
+    format!(
+      "/api/v1/namespaces/{}/pods?labelSelector={}",
+      self.config.namespace(),
+      percent_encode(self.config.label_selector())
+    )
+  }
+
+  fn list_pods(&mut self) -> Result<KubernetesPodList, KubernetesApiError> {
+    let response = self.client.send(&KubernetesHttpRequest::get(self.pods_path()))?;
+    if response.status() != 200 {
+      return Err(KubernetesApiError::UnexpectedStatus(response.status()));
+    }
+    serde_json::from_str(response.body()).map_err(|error| KubernetesApiError::MalformedResponse(error.to_string()))
+  }
+}
+
+impl<C> DiscoveryBackend for KubernetesPodDiscoveryBackend<C>
+where
+  C: KubernetesHttpClient,
+{
+  fn source_identity(&self) -> &str {
+    &self.source_identity
+  }
+
+  fn discover(&mut self) -> Result<Vec<String>, DiscoveryBackendError> {
+    let pods = self.list_pods()?;
+    let mut authorities = pods
+      .items
+      .iter()
+      .filter(|pod| pod.is_ready())
+      .filter_map(|pod| {
+        let address = pod.status.pod_ip.as_deref()?.parse::<IpAddr>().ok()?;
+        let port = match self.config.port_name() {
+          | Some(port_name) => pod.named_port(port_name)?,
+          | None => self.config.port(),
+        };
+        Some(match address {
+          | IpAddr::V4(address) => format!("{address}:{port}"),
+          | IpAddr::V6(address) => format!("[{address}]:{port}"),
+        })
+      })
+      .collect::<Vec<_>>();
+    authorities.sort();
+    authorities.dedup();
+    Ok(authorities)
+  }
+}
+
+fn percent_encode(value: &str) -> String {
+  let mut encoded = String::with_capacity(value.len());
+  for byte in value.bytes() {
+    if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+      encoded.push(char::from(byte));
+    } else {
+      encoded.push_str(&format!("%{byte:02X}"));
+    }
+  }
+  encoded
+}
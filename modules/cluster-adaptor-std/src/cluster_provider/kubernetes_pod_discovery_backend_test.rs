@@ -0,0 +1,111 @@
+use std::{string::String, vec::Vec};
+
+use super::KubernetesPodDiscoveryBackend;
+use crate::cluster_provider::{
+  DiscoveryBackend, DiscoveryBackendError, KubernetesApiError, KubernetesDiscoveryConfig, KubernetesHttpClient,
+  KubernetesHttpMethod, KubernetesHttpRequest, KubernetesHttpResponse,
+};
+
+struct FakeClient {
+  response: Result<KubernetesHttpResponse, KubernetesApiError>,
+  requests: Vec<KubernetesHttpRequest>,
+}
+
+impl FakeClient {
+  fn responding(status: u16, body: &str) -> Self {
+    Self { response: Ok(KubernetesHttpResponse::new(status, body)), requests: Vec::new() }
+  }
+}
+
+impl KubernetesHttpClient for FakeClient {
+  fn send(&mut self, request: &KubernetesHttpRequest) -> Result<KubernetesHttpResponse, KubernetesApiError> {
+    self.requests.push(request.clone());
+    self.response.clone()
+  }
+}
+
+const POD_LIST: &str = r#"{
+  "kind": "PodList",
+  "items": [
+    {
+      "metadata": {"name": "ready-b"},
+      "spec": {"containers": [{"ports": [{"name": "remoting", "containerPort": 25520}]}]},
+      "status": {"phase": "Running", "podIP": "10.0.0.12", "conditions": [{"type": "Ready", "status": "True"}]}
+    },
+    {
+      "metadata": {"name": "ready-a"},
+      "spec": {"containers": [{"ports": [{"name": "http", "containerPort": 80}, {"name": "remoting", "containerPort": 25521}]}]},
+      "status": {"phase": "Running", "podIP": "fd00::1", "conditions": [{"type": "Ready", "status": "True"}]}
+    },
+    {
+      "metadata": {"name": "not-ready"},
+      "spec": {"containers": [{"ports": [{"name": "remoting", "containerPort": 25520}]}]},
+      "status": {"phase": "Running", "podIP": "10.0.0.13", "conditions": [{"type": "Ready", "status": "False"}]}
+    },
+    {
+      "metadata": {"name": "terminating", "deletionTimestamp": "2026-01-01T00:00:00Z"},
+      "spec": {"containers": [{"ports": [{"name": "remoting", "containerPort": 25520}]}]},
+      "status": {"phase": "Running", "podIP": "10.0.0.14", "conditions": [{"type": "Ready", "status": "True"}]}
+    },
+    {
+      "metadata": {"name": "pending"},
+      "status": {"phase": "Pending"}
+    },
+    {
+      "metadata": {"name": "no-named-port"},
+      "spec": {"containers": [{"ports": [{"name": "http", "containerPort": 80}]}]},
+      "status": {"phase": "Running", "podIP": "10.0.0.15", "conditions": [{"type": "Ready", "status": "True"}]}
+    }
+  ]
+}"#;
+
+fn config() -> KubernetesDiscoveryConfig {
+  KubernetesDiscoveryConfig::new("fraktor", "app=seed,tier in (backend)")
+}
+
+#[test]
+fn lists_pods_by_encoded_label_selector() {
+  let mut backend = KubernetesPodDiscoveryBackend::new(config(), FakeClient::responding(200, r#"{"items": []}"#));
+
+  assert_eq!(backend.discover(), Ok(Vec::new()));
+
+  let request = &backend.client.requests[0];
+  assert_eq!(request.method(), KubernetesHttpMethod::Get);
+  assert_eq!(request.path(), "/api/v1/namespaces/fraktor/pods?labelSelector=app%3Dseed%2Ctier%20in%20%28backend%29");
+  assert_eq!(backend.source_identity(), "kubernetes:fraktor/app=seed,tier in (backend)");
+}
+
+#[test]
+fn ready_pods_are_mapped_to_named_port_authorities() {
+  let config = config().with_port_name("remoting");
+  let mut backend = KubernetesPodDiscoveryBackend::new(config, FakeClient::responding(200, POD_LIST));
+
+  let authorities = backend.discover().expect("pod discovery");
+
+  assert_eq!(authorities, Vec::from([String::from("10.0.0.12:25520"), String::from("[fd00::1]:25521")]));
+}
+
+#[test]
+fn ready_pods_use_the_fixed_port_without_a_port_name() {
+  let config = config().with_port(2552);
+  let mut backend = KubernetesPodDiscoveryBackend::new(config, FakeClient::responding(200, POD_LIST));
+
+  let authorities = backend.discover().expect("pod discovery");
+
+  assert_eq!(
+    authorities,
+    Vec::from([String::from("10.0.0.12:2552"), String::from("10.0.0.15:2552"), String::from("[fd00::1]:2552")])
+  );
+}
+
+#[test]
+fn api_failures_are_reported_as_temporary_errors() {
+  let mut forbidden = KubernetesPodDiscoveryBackend::new(config(), FakeClient::responding(403, "{}"));
+  assert_eq!(
+    forbidden.discover(),
+    Err(DiscoveryBackendError::temporary("kubernetes api returned unexpected status 403"))
+  );
+
+  let mut malformed = KubernetesPodDiscoveryBackend::new(config(), FakeClient::responding(200, "not json"));
+  assert!(malformed.discover().unwrap_err().reason().starts_with("malformed kubernetes api response"));
+}
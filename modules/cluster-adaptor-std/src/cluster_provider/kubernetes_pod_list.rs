@@ -0,0 +1,85 @@
+//! Subset of the Kubernetes `PodList` schema read by pod discovery.
+
+use std::{string::String, vec::Vec};
+
+use serde::Deserialize;
+
+/// `PodList` response body.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct KubernetesPodList {
+  #[serde(default)]
+  pub(crate) items: Vec<Pod>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Pod {
+  #[serde(default)]
+  pub(crate) metadata: PodMetadata,
+  #[serde(default)]
+  pub(crate) spec:     PodSpec,
+  #[serde(default)]
+  pub(crate) status:   PodStatus,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PodMetadata {
+  pub(crate) deletion_timestamp: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct PodSpec {
+  #[serde(default)]
+  pub(crate) containers: Vec<Container>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct Container {
+  #[serde(default)]
+  pub(crate) ports: Vec<ContainerPort>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContainerPort {
+  pub(crate) name:           Option<String>,
+  pub(crate) container_port: u16,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PodStatus {
+  pub(crate) phase:      Option<String>,
+  #[serde(rename = "podIP")]
+  pub(crate) pod_ip:     Option<String>,
+  #[serde(default)]
+  pub(crate) conditions: Vec<PodCondition>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct PodCondition {
+  #[serde(rename = "type")]
+  pub(crate) kind:   String,
+  pub(crate) status: String,
+}
+
+impl Pod {
+  /// Returns true when the pod is running, ready and not terminating.
+  pub(crate) fn is_ready(&self) -> bool {
+    self.metadata.deletion_timestamp.is_none()
+      && self.status.phase.as_deref() == Some("Running")
+      && self.status.conditions.iter().any(|condition| condition.kind == "Ready" && condition.status == "True")
+  }
+
+  /// Returns the container port named `name`.
+  pub(crate) fn named_port(&self, name: &str) -> Option<u16> {
+    self
+      .spec
+      .containers
+      .iter()
+      .flat_map(|container| container.ports.iter())
+      .find(|port| port.name.as_deref() == Some(name))
+      .map(|port| port.container_port)
+  }
+}
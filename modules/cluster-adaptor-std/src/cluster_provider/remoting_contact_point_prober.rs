@@ -0,0 +1,128 @@
+//! Contact point prober using actor remoting.
+
+use std::{
+  format,
+  string::String,
+  sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+  time::{Duration, Instant},
+  vec::Vec,
+};
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext,
+    actor_path::{ActorPathParser, ActorPathScheme},
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView},
+    props::Props,
+  },
+  system::ActorSystem,
+};
+use fraktor_cluster_core_kernel_rs::extension::ClusterProviderError;
+
+use super::{
+  ClusterBootstrapResponder, ContactPointProber, cluster_bootstrap_probe_message::ClusterBootstrapProbeMessage,
+};
+
+/// [`ContactPointProber`] that asks the [`ClusterBootstrapResponder`] of each contact point over
+/// remoting.
+///
+/// Contact points are addressed as `fraktor.tcp://<system>@<authority>/user/cluster-bootstrap`, so
+/// every node must use the same actor system name. Probes block the caller until a reply arrives or
+/// the timeout elapses.
+pub struct RemotingContactPointProber {
+  system:          ActorSystem,
+  reply_path:      String,
+  reply_collector: ActorRef,
+  replies:         Receiver<ClusterBootstrapProbeMessage>,
+  timeout:         Duration,
+}
+
+impl RemotingContactPointProber {
+  /// Creates a prober that collects replies in an actor spawned on `system`.
+  ///
+  /// `self_authority` is the remoting authority other nodes use to reach `system`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ClusterProviderError`] when the reply collector cannot spawn.
+  pub fn new(system: &ActorSystem, self_authority: &str, timeout: Duration) -> Result<Self, ClusterProviderError> {
+    let (sender, replies) = mpsc::channel();
+    let props = Props::from_fn(move || ProbeReplyCollector { sender: sender.clone() });
+    let collector = system
+      .actor_of(&props)
+      .map_err(|error| ClusterProviderError::join(format!("failed to spawn probe reply collector: {error:?}")))?;
+    let local_path =
+      collector.actor_ref().path().ok_or_else(|| ClusterProviderError::join("probe reply collector has no path"))?;
+    let reply_path = format!(
+      "{}://{}@{self_authority}{}",
+      ActorPathScheme::FraktorTcp.as_str(),
+      system.name(),
+      local_path.to_relative_string()
+    );
+    Ok(Self { system: system.clone(), reply_path, reply_collector: collector.into_actor_ref(), replies, timeout })
+  }
+
+  fn responder(&self, authority: &str) -> Result<ActorRef, ClusterProviderError> {
+    let path = format!(
+      "{}://{}@{authority}/user/{}",
+      ActorPathScheme::FraktorTcp.as_str(),
+      self.system.name(),
+      ClusterBootstrapResponder::NAME
+    );
+    let path = ActorPathParser::parse(&path)
+      .map_err(|error| ClusterProviderError::join(format!("invalid contact point path {path}: {error:?}")))?;
+    self
+      .system
+      .resolve_actor_ref(path)
+      .map_err(|error| ClusterProviderError::join(format!("contact point {authority} unresolved: {error:?}")))
+  }
+}
+
+impl ContactPointProber for RemotingContactPointProber {
+  fn probe(&mut self, authority: &str) -> Result<Vec<String>, ClusterProviderError> {
+    let mut responder = self.responder(authority)?;
+    // 以前のプローブの遅延応答を捨ててから問い合わせる
+    while self.replies.try_recv().is_ok() {}
+    let request = ClusterBootstrapProbeMessage::Request { reply_to: self.reply_path.clone() };
+    responder
+      .try_tell(AnyMessage::new(request.encode()))
+      .map_err(|error| ClusterProviderError::join(format!("contact point {authority} probe failed: {error:?}")))?;
+
+    let deadline = Instant::now() + self.timeout;
+    loop {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      match self.replies.recv_timeout(remaining) {
+        | Ok(ClusterBootstrapProbeMessage::Reply { authority: replied, seed_nodes }) if replied == authority => {
+          return Ok(seed_nodes);
+        },
+        | Ok(_) => {},
+        | Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {
+          return Err(ClusterProviderError::join(format!("contact point {authority} did not answer the probe")));
+        },
+      }
+    }
+  }
+}
+
+impl Drop for RemotingContactPointProber {
+  fn drop(&mut self) {
+    // システム停止後は送れないが、その場合は回収不要なので無視する
+    drop(self.system.stop(&self.reply_collector));
+  }
+}
+
+struct ProbeReplyCollector {
+  sender: Sender<ClusterBootstrapProbeMessage>,
+}
+
+impl Actor for ProbeReplyCollector {
+  fn receive(&mut self, _context: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(reply) = message.downcast_ref::<String>().and_then(|text| ClusterBootstrapProbeMessage::decode(text)) {
+      // プローバが破棄済みなら応答は不要
+      drop(self.sender.send(reply));
+    }
+    Ok(())
+  }
+}
@@ -4,7 +4,7 @@
 #[path = "split_brain_resolver_provider_test.rs"]
 mod tests;
 
-use alloc::boxed::Box;
+use alloc::{boxed::Box, string::String};
 use core::time::Duration;
 
 use fraktor_cluster_core_kernel_rs::{
@@ -14,6 +14,7 @@ use fraktor_cluster_core_kernel_rs::{
     SplitBrainResolverProviderHook,
   },
   extension::ClusterProviderError,
+  lease::{Lease, LeaseError, LeaseProvider, LeaseSettings},
 };
 use fraktor_utils_core_rs::{sync::ArcShared, time::TimerInstant};
 
@@ -49,6 +50,19 @@ impl StdSplitBrainResolverProvider {
     self
   }
 
+  /// Configures a lease created through `provider` as the lease backend.
+  ///
+  /// A lease of `implementation` is created whenever the provider starts and released when it
+  /// stops. An unregistered implementation is treated as a missing lease backend
+  /// ([`LeaseAcquisitionOutcome::BackendMissing`]).
+  #[must_use]
+  pub fn with_lease_provider(self, provider: LeaseProvider, implementation: &str, settings: LeaseSettings) -> Self {
+    let implementation = String::from(implementation);
+    self.with_lease_backend_factory(move || {
+      Box::new(ProvidedLeaseBackend { lease: provider.get_lease(&implementation, settings.clone()) })
+    })
+  }
+
   /// Returns compatibility metadata for this provider binding.
   #[must_use]
   pub fn compatibility(&self) -> DowningProviderCompatibility {
@@ -179,6 +193,26 @@ impl LeaseMajorityPort for StdLeaseMajorityPort<'_> {
   }
 }
 
+struct ProvidedLeaseBackend {
+  lease: Result<Box<dyn Lease>, LeaseError>,
+}
+
+impl StdLeaseMajorityBackend for ProvidedLeaseBackend {
+  fn acquire(&mut self, context: &DowningDecisionContext) -> LeaseAcquisitionOutcome {
+    match &mut self.lease {
+      | Ok(lease) => lease.acquire_majority(context),
+      | Err(_) => LeaseAcquisitionOutcome::BackendMissing,
+    }
+  }
+
+  fn close(&mut self) {
+    if let Ok(lease) = &mut self.lease {
+      // 解放できなくてもハートビートが途絶えればリースは失効する
+      drop(lease.release());
+    }
+  }
+}
+
 impl StdSplitBrainResolverProvider {
   const fn evaluation_time() -> TimerInstant {
     TimerInstant::zero(Duration::from_millis(1))
@@ -1,5 +1,9 @@
-use alloc::{string::String, vec};
+use alloc::{format, string::String, vec};
 use core::time::Duration;
+use std::{
+  fs,
+  time::{SystemTime, UNIX_EPOCH},
+};
 
 use fraktor_cluster_core_kernel_rs::{
   downing_provider::{
@@ -7,6 +11,7 @@ use fraktor_cluster_core_kernel_rs::{
     SplitBrainResolverConfig, SplitBrainResolverStrategy,
   },
   extension::ClusterProviderError,
+  lease::{LeaseProvider, LeaseSettings},
   membership::{DataCenter, MembershipSnapshot, MembershipVersion, NodeRecord, NodeStatus, ReachabilityMatrix},
 };
 use fraktor_remote_core_rs::address::{Address, UniqueAddress};
@@ -16,6 +21,7 @@ use fraktor_utils_core_rs::{
 };
 
 use super::{StdLeaseMajorityBackend, StdSplitBrainResolverProvider};
+use crate::lease::FileLease;
 
 #[derive(Clone)]
 struct RecordingLeaseBackend {
@@ -293,3 +299,47 @@ fn downing_provider_decide_context_preserves_local_reachability_observer() {
 
   assert_eq!(decision, Ok(DowningDecision::Keep));
 }
+
+#[test]
+fn lease_provider_backend_keeps_only_the_partition_holding_the_file_lease() {
+  let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).expect("system time").as_nanos();
+  let directory = std::env::temp_dir().join(format!("fraktor-sbr-file-lease-{}-{timestamp}", std::process::id()));
+  let leases = LeaseProvider::new().with_implementation(FileLease::IMPLEMENTATION, FileLease::factory(&directory));
+  let mut first = StdSplitBrainResolverProvider::new(lease_majority_config()).with_lease_provider(
+    leases.clone(),
+    FileLease::IMPLEMENTATION,
+    LeaseSettings::new("sbr", "node-a:1"),
+  );
+  let mut second = StdSplitBrainResolverProvider::new(lease_majority_config()).with_lease_provider(
+    leases,
+    FileLease::IMPLEMENTATION,
+    LeaseSettings::new("sbr", "node-b:1"),
+  );
+
+  let first_decision = first.decide_strategy_context(&majority_context()).expect("first decision");
+  let second_decision = second.decide_strategy_context(&majority_context()).expect("second decision");
+
+  assert_eq!(first_decision.trace().lease_outcome(), Some(LeaseAcquisitionOutcome::Acquired));
+  assert_eq!(first_decision.downing_targets(), &[unique("node-c", 3)]);
+  assert_eq!(second_decision.trace().lease_outcome(), Some(LeaseAcquisitionOutcome::Denied));
+  assert!(second_decision.downing_targets().is_empty());
+
+  first.stop().expect("provider stops");
+  let retried = second.decide_strategy_context(&majority_context()).expect("retried decision");
+  assert_eq!(retried.trace().lease_outcome(), Some(LeaseAcquisitionOutcome::Acquired));
+  drop(second);
+  fs::remove_dir_all(&directory).expect("lease directory should be removable");
+}
+
+#[test]
+fn lease_provider_backend_reports_an_unregistered_implementation_as_missing() {
+  let mut provider = StdSplitBrainResolverProvider::new(lease_majority_config()).with_lease_provider(
+    LeaseProvider::new(),
+    FileLease::IMPLEMENTATION,
+    LeaseSettings::new("sbr", "node-a:1"),
+  );
+
+  let decision = provider.decide_strategy_context(&majority_context());
+
+  assert_eq!(decision.err(), Some(ClusterProviderError::down(LeaseAcquisitionOutcome::BackendMissing.trace_reason())));
+}
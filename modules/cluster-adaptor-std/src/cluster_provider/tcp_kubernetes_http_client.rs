@@ -0,0 +1,115 @@
+//! Plain HTTP client for Kubernetes API proxies.
+
+use std::{
+  format,
+  io::{Read, Write},
+  net::{TcpStream, ToSocketAddrs},
+  string::{String, ToString},
+  time::Duration,
+  vec::Vec,
+};
+
+use super::{KubernetesApiError, KubernetesHttpClient, KubernetesHttpRequest, KubernetesHttpResponse};
+
+/// [`KubernetesHttpClient`] speaking plain HTTP to an API server proxy.
+///
+/// Intended for endpoints such as `kubectl proxy` or an authenticating sidecar. Direct access to a
+/// TLS API server requires a custom client.
+pub struct TcpKubernetesHttpClient {
+  authority:    String,
+  bearer_token: Option<String>,
+  timeout:      Duration,
+}
+
+impl TcpKubernetesHttpClient {
+  /// Creates a client connecting to `authority` (`host:port`).
+  #[must_use]
+  pub fn new(authority: impl Into<String>) -> Self {
+    Self { authority: authority.into(), bearer_token: None, timeout: Duration::from_secs(5) }
+  }
+
+  /// Sets the bearer token sent with every request.
+  #[must_use]
+  pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+    self.bearer_token = Some(token.into());
+    self
+  }
+
+  /// Sets the connect, read and write timeout.
+  #[must_use]
+  pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  /// Returns the proxy authority.
+  #[must_use]
+  pub fn authority(&self) -> &str {
+    &self.authority
+  }
+
+  fn connect(&self) -> Result<TcpStream, KubernetesApiError> {
+    let mut last_error = None;
+    for address in self.authority.to_socket_addrs().map_err(transport_error)? {
+      match TcpStream::connect_timeout(&address, self.timeout) {
+        | Ok(stream) => return Ok(stream),
+        | Err(error) => last_error = Some(error),
+      }
+    }
+    Err(last_error.map_or_else(
+      || KubernetesApiError::Transport(format!("no address resolved for {}", self.authority)),
+      transport_error,
+    ))
+  }
+
+  fn encode(&self, request: &KubernetesHttpRequest) -> Vec<u8> {
+    // HTTP/1.0 で送ることで chunked 応答を避け、接続終了までを本文として読める
+    let mut head = format!(
+      "{} {} HTTP/1.0\r\nHost: {}\r\nAccept: application/json\r\n",
+      request.method().as_str(),
+      request.path(),
+      self.authority
+    );
+    if let Some(token) = &self.bearer_token {
+      head.push_str(&format!("Authorization: Bearer {token}\r\n"));
+    }
+    let body = request.body().unwrap_or_default();
+    if request.body().is_some() {
+      head.push_str(&format!("Content-Type: application/json\r\nContent-Length: {}\r\n", body.len()));
+    }
+    head.push_str("\r\n");
+    let mut bytes = head.into_bytes();
+    bytes.extend_from_slice(body.as_bytes());
+    bytes
+  }
+
+  fn decode(bytes: &[u8]) -> Result<KubernetesHttpResponse, KubernetesApiError> {
+    let text = String::from_utf8_lossy(bytes);
+    let (head, body) = text
+      .split_once("\r\n\r\n")
+      .ok_or_else(|| KubernetesApiError::MalformedResponse(String::from("missing http header terminator")))?;
+    let status = head
+      .lines()
+      .next()
+      .and_then(|status_line| status_line.split_whitespace().nth(1))
+      .and_then(|status| status.parse::<u16>().ok())
+      .ok_or_else(|| KubernetesApiError::MalformedResponse(String::from("invalid http status line")))?;
+    Ok(KubernetesHttpResponse::new(status, body))
+  }
+}
+
+impl KubernetesHttpClient for TcpKubernetesHttpClient {
+  fn send(&mut self, request: &KubernetesHttpRequest) -> Result<KubernetesHttpResponse, KubernetesApiError> {
+    let mut stream = self.connect()?;
+    stream.set_read_timeout(Some(self.timeout)).map_err(transport_error)?;
+    stream.set_write_timeout(Some(self.timeout)).map_err(transport_error)?;
+    stream.write_all(&self.encode(request)).map_err(transport_error)?;
+    let mut bytes = Vec::new();
+    stream.read_to_end(&mut bytes).map_err(transport_error)?;
+    Self::decode(&bytes)
+  }
+}
+
+fn transport_error(error: std::io::Error) -> KubernetesApiError {
+  KubernetesApiError::Transport(error.to_string())
+}
@@ -0,0 +1,173 @@
+//! Blocking UDP DNS resolver.
+
+use std::{
+  collections::hash_map::RandomState,
+  format, fs,
+  hash::BuildHasher,
+  io::{Error, ErrorKind, Read, Write},
+  net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, UdpSocket},
+  process,
+  string::ToString,
+  sync::atomic::{AtomicU64, Ordering},
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+  vec,
+  vec::Vec,
+};
+
+use super::{
+  DiscoveryBackendError, DnsAddressRecord, DnsResolver, DnsSrvRecord,
+  dns_message::{DnsMessage, MAX_UDP_PAYLOAD_LEN, RCODE_NAME_ERROR, TYPE_A, TYPE_AAAA, TYPE_SRV},
+};
+
+const DNS_PORT: u16 = 53;
+
+static QUERY_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// [`DnsResolver`] that queries a single nameserver over UDP.
+///
+/// Queries advertise a 4096-byte EDNS0 payload, and responses truncated nonetheless are fetched
+/// again over TCP. Every query uses a random transaction id, and only responses echoing the queried
+/// name and type are accepted.
+pub struct UdpDnsResolver {
+  nameserver: SocketAddr,
+  timeout:    Duration,
+}
+
+impl UdpDnsResolver {
+  /// Creates a resolver querying `nameserver`.
+  #[must_use]
+  pub const fn new(nameserver: SocketAddr) -> Self {
+    Self { nameserver, timeout: Duration::from_secs(2) }
+  }
+
+  /// Creates a resolver querying the first nameserver listed in `/etc/resolv.conf`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`DiscoveryBackendError`] when the file cannot be read or lists no nameserver.
+  pub fn from_system_config() -> Result<Self, DiscoveryBackendError> {
+    let contents = fs::read_to_string("/etc/resolv.conf")
+      .map_err(|error| DiscoveryBackendError::temporary(format!("failed to read resolv.conf: {error}")))?;
+    contents
+      .lines()
+      .filter_map(|line| line.trim().strip_prefix("nameserver"))
+      .filter_map(|address| address.trim().parse::<IpAddr>().ok())
+      .map(|address| Self::new(SocketAddr::new(address, DNS_PORT)))
+      .next()
+      .ok_or_else(|| DiscoveryBackendError::temporary("no nameserver configured in resolv.conf"))
+  }
+
+  /// Sets the timeout for a single query.
+  #[must_use]
+  pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  /// Returns the queried nameserver.
+  #[must_use]
+  pub const fn nameserver(&self) -> SocketAddr {
+    self.nameserver
+  }
+
+  fn query(&self, name: &str, record_type: u16) -> Result<DnsMessage, DiscoveryBackendError> {
+    let mut response = self.query_udp(name, record_type)?;
+    if response.truncated {
+      // UDP に収まらない応答は TCP で問い合わせ直す
+      response = self.query_tcp(name, record_type)?;
+    }
+    match response.rcode {
+      | 0 => Ok(response),
+      | RCODE_NAME_ERROR => Ok(DnsMessage { id: response.id, ..DnsMessage::default() }),
+      | rcode => Err(DiscoveryBackendError::temporary(format!("dns query failed with rcode {rcode}"))),
+    }
+  }
+
+  fn query_udp(&self, name: &str, record_type: u16) -> Result<DnsMessage, DiscoveryBackendError> {
+    let id = random_query_id();
+    let request = DnsMessage::encode_query(id, name, record_type)?;
+    let bind_address = match self.nameserver {
+      | SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+      | SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+    };
+    let socket = UdpSocket::bind(bind_address).map_err(io_error)?;
+    socket.connect(self.nameserver).map_err(io_error)?;
+    socket.send(&request).map_err(io_error)?;
+
+    let deadline = Instant::now() + self.timeout;
+    let mut buffer = [0_u8; MAX_UDP_PAYLOAD_LEN as usize];
+    loop {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      if remaining.is_zero() {
+        return Err(timed_out());
+      }
+      socket.set_read_timeout(Some(remaining)).map_err(io_error)?;
+      let len = socket.recv(&mut buffer).map_err(io_error)?;
+      // 別の問い合わせへの応答や壊れたパケット、偽装された応答は読み捨てて待ち続ける
+      let Ok(response) = DnsMessage::decode(&buffer[..len]) else {
+        continue;
+      };
+      if response.id == id && response.answers_question(name, record_type) {
+        return Ok(response);
+      }
+    }
+  }
+
+  fn query_tcp(&self, name: &str, record_type: u16) -> Result<DnsMessage, DiscoveryBackendError> {
+    let id = random_query_id();
+    let request = DnsMessage::encode_query(id, name, record_type)?;
+    let request_len =
+      u16::try_from(request.len()).map_err(|_| DiscoveryBackendError::temporary("dns query is too long"))?;
+    let mut stream = TcpStream::connect_timeout(&self.nameserver, self.timeout).map_err(io_error)?;
+    stream.set_read_timeout(Some(self.timeout)).map_err(io_error)?;
+    stream.set_write_timeout(Some(self.timeout)).map_err(io_error)?;
+    // TCP では各メッセージの前に 2 バイトの長さを置く
+    stream.write_all(&request_len.to_be_bytes()).map_err(io_error)?;
+    stream.write_all(&request).map_err(io_error)?;
+    let mut len_bytes = [0_u8; 2];
+    stream.read_exact(&mut len_bytes).map_err(io_error)?;
+    let mut buffer = vec![0_u8; usize::from(u16::from_be_bytes(len_bytes))];
+    stream.read_exact(&mut buffer).map_err(io_error)?;
+    let response = DnsMessage::decode(&buffer)?;
+    if response.id != id || !response.answers_question(name, record_type) {
+      return Err(DiscoveryBackendError::temporary("dns response does not match the query"));
+    }
+    if response.truncated {
+      return Err(DiscoveryBackendError::temporary("truncated dns response"));
+    }
+    Ok(response)
+  }
+}
+
+impl DnsResolver for UdpDnsResolver {
+  fn resolve_srv(&mut self, name: &str) -> Result<Vec<DnsSrvRecord>, DiscoveryBackendError> {
+    Ok(self.query(name, TYPE_SRV)?.srv_records)
+  }
+
+  fn resolve_addresses(&mut self, name: &str) -> Result<Vec<DnsAddressRecord>, DiscoveryBackendError> {
+    let mut records = self.query(name, TYPE_A)?.address_records;
+    records.extend(self.query(name, TYPE_AAAA)?.address_records);
+    Ok(records)
+  }
+}
+
+/// Returns an unpredictable transaction id, so off-path senders cannot forge responses.
+fn random_query_id() -> u16 {
+  // RandomState
+  // の鍵はプロセスごとに乱数で初期化されるため、時刻と連番のハッシュは外部から推測できない
+  let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos());
+  let sequence = QUERY_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+  let hash = RandomState::new().hash_one((nanos, process::id(), sequence));
+  (hash & 0xFFFF) as u16
+}
+
+fn timed_out() -> DiscoveryBackendError {
+  DiscoveryBackendError::temporary("dns query timed out")
+}
+
+fn io_error(error: Error) -> DiscoveryBackendError {
+  match error.kind() {
+    | ErrorKind::WouldBlock | ErrorKind::TimedOut => timed_out(),
+    | _ => DiscoveryBackendError::temporary(error.to_string()),
+  }
+}
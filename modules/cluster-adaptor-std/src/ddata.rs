@@ -0,0 +1,7 @@
+//! Durable storage adaptors for distributed data.
+
+mod local_durable_store;
+mod local_durable_store_config;
+
+pub use local_durable_store::LocalDurableStore;
+pub use local_durable_store_config::LocalDurableStoreConfig;
@@ -0,0 +1,215 @@
+//! Filesystem-backed durable store for replicated data.
+
+#[cfg(test)]
+#[path = "local_durable_store_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::BTreeMap,
+  format,
+  string::{String, ToString},
+  vec::Vec,
+};
+use core::ops::Deref;
+use std::{
+  fs::{self, File},
+  io::{ErrorKind, Write},
+  path::{Path, PathBuf},
+};
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  SerializationDelegator, SerializedMessage, serialization_registry::SerializationRegistry,
+};
+use fraktor_cluster_core_kernel_rs::ddata::{DurableDataEnvelope, DurableStore, DurableStoreError};
+use fraktor_utils_core_rs::sync::{ArcShared, DefaultMutex, SharedLock};
+
+use super::{LocalDurableStoreConfig, local_durable_store_config::DataRestorer};
+
+const DATA_FILE_PREFIX: &str = "data-";
+const TEMP_FILE_SUFFIX: &str = ".tmp";
+const DELETED_MARKER: u8 = 0;
+const PRESENT_MARKER: u8 = 1;
+const PERCENT_ENCODING_MARKER: char = '%';
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Embedded durable store keeping one atomically replaced file per key.
+///
+/// Each value is written to a synced temporary file and renamed into place, so a crash leaves
+/// either the previous or the new value of a key on disk. Clones share the same directory.
+#[derive(Clone)]
+pub struct LocalDurableStore {
+  directory:     ArcShared<PathBuf>,
+  serialization: ArcShared<SerializationRegistry>,
+  restorers:     ArcShared<Vec<DataRestorer>>,
+  write_lock:    SharedLock<()>,
+}
+
+impl LocalDurableStore {
+  /// Opens a local durable store, creating its directory when missing.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`DurableStoreError::LoadFailed`] when the directory cannot be prepared.
+  pub fn open(config: LocalDurableStoreConfig) -> Result<Self, DurableStoreError> {
+    let (directory, serialization, restorers) = config.into_parts();
+    fs::create_dir_all(&directory)
+      .map_err(|error| load_failed(format!("create durable store directory {}: {error}", directory.display())))?;
+    Ok(Self {
+      directory: ArcShared::new(directory),
+      serialization,
+      restorers: ArcShared::new(restorers),
+      write_lock: SharedLock::new_with_driver::<DefaultMutex<_>>(()),
+    })
+  }
+
+  fn encode(&self, key: &str, envelope: &DurableDataEnvelope) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    write_bytes(&mut bytes, key.as_bytes())?;
+    match (envelope.data(), envelope.type_name()) {
+      | (Some(data), Some(type_name)) => {
+        let delegator = SerializationDelegator::new(self.serialization.deref());
+        let serialized =
+          delegator.serialize(data, type_name).map_err(|error| format!("serialize value of key {key}: {error}"))?;
+        bytes.push(PRESENT_MARKER);
+        bytes.extend_from_slice(&serialized.encode());
+      },
+      | _ => bytes.push(DELETED_MARKER),
+    }
+    Ok(bytes)
+  }
+
+  fn decode(&self, path: &Path, bytes: &[u8]) -> Result<(String, DurableDataEnvelope), String> {
+    let corrupt = || format!("corrupt durable data file {}", path.display());
+    let (key, rest) = read_bytes(bytes).ok_or_else(corrupt)?;
+    let key = String::from_utf8(key.to_vec()).map_err(|_| corrupt())?;
+    let (&marker, payload) = rest.split_first().ok_or_else(corrupt)?;
+    let envelope = match marker {
+      | DELETED_MARKER if payload.is_empty() => DurableDataEnvelope::deleted(),
+      | PRESENT_MARKER => self.restore(&key, payload)?,
+      | _ => return Err(corrupt()),
+    };
+    Ok((key, envelope))
+  }
+
+  fn restore(&self, key: &str, payload: &[u8]) -> Result<DurableDataEnvelope, String> {
+    let serialized =
+      SerializedMessage::decode(payload).map_err(|error| format!("decode value of key {key}: {error}"))?;
+    let delegator = SerializationDelegator::new(self.serialization.deref());
+    let mut value =
+      delegator.deserialize(&serialized, None).map_err(|error| format!("deserialize value of key {key}: {error}"))?;
+    for restorer in self.restorers.iter() {
+      match restorer(value) {
+        | Ok(envelope) => return Ok(envelope),
+        | Err(other) => value = other,
+      }
+    }
+    Err(format!("value of key {key} has no registered data type"))
+  }
+
+  fn data_path(&self, key: &str) -> PathBuf {
+    self.directory.join(format!("{DATA_FILE_PREFIX}{}", encode_component(key)))
+  }
+
+  fn replace_file(&self, path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut temp_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    temp_name.push(TEMP_FILE_SUFFIX);
+    let temp_path = path.with_file_name(temp_name);
+    let mut file =
+      File::create(&temp_path).map_err(|error| format!("create temp file {}: {error}", temp_path.display()))?;
+    file
+      .write_all(bytes)
+      .and_then(|()| file.sync_all())
+      .map_err(|error| format!("write temp file {}: {error}", temp_path.display()))?;
+    drop(file);
+    fs::rename(&temp_path, path).map_err(|error| format!("replace durable data file {}: {error}", path.display()))
+  }
+
+  fn sync_directory(&self) -> Result<(), String> {
+    #[cfg(not(windows))]
+    File::open(self.directory.as_path())
+      .and_then(|directory| directory.sync_all())
+      .map_err(|error| format!("sync durable store directory {}: {error}", self.directory.display()))?;
+    Ok(())
+  }
+}
+
+impl DurableStore for LocalDurableStore {
+  fn load_all(&self) -> Result<BTreeMap<String, DurableDataEnvelope>, DurableStoreError> {
+    let entries = fs::read_dir(self.directory.as_path())
+      .map_err(|error| load_failed(format!("read durable store directory {}: {error}", self.directory.display())))?;
+    let mut loaded = BTreeMap::new();
+    for entry in entries {
+      let path = entry.map_err(|error| load_failed(format!("read durable store entry: {error}")))?.path();
+      let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_string();
+      if !name.starts_with(DATA_FILE_PREFIX) {
+        continue;
+      }
+      if name.ends_with(TEMP_FILE_SUFFIX) {
+        // 置き換え前に中断された書き込みの残骸は捨てる。
+        fs::remove_file(&path).map_err(|error| load_failed(format!("remove temp file {}: {error}", path.display())))?;
+        continue;
+      }
+      let bytes =
+        fs::read(&path).map_err(|error| load_failed(format!("read durable data file {}: {error}", path.display())))?;
+      let (key, envelope) = self.decode(&path, &bytes).map_err(load_failed)?;
+      loaded.insert(key, envelope);
+    }
+    Ok(loaded)
+  }
+
+  fn load(&self, key: &str) -> Result<Option<DurableDataEnvelope>, DurableStoreError> {
+    let path = self.data_path(key);
+    let bytes = match fs::read(&path) {
+      | Ok(bytes) => bytes,
+      | Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+      | Err(error) => return Err(load_failed(format!("read durable data file {}: {error}", path.display()))),
+    };
+    let (_, envelope) = self.decode(&path, &bytes).map_err(load_failed)?;
+    Ok(Some(envelope))
+  }
+
+  fn store(&self, entries: &BTreeMap<String, DurableDataEnvelope>) -> Result<(), DurableStoreError> {
+    self
+      .write_lock
+      .with_lock(|()| {
+        for (key, envelope) in entries {
+          let bytes = self.encode(key, envelope)?;
+          self.replace_file(&self.data_path(key), &bytes)?;
+        }
+        self.sync_directory()
+      })
+      .map_err(|reason| DurableStoreError::StoreFailed { reason })
+  }
+}
+
+const fn load_failed(reason: String) -> DurableStoreError {
+  DurableStoreError::LoadFailed { reason }
+}
+
+fn write_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) -> Result<(), String> {
+  let len =
+    u32::try_from(bytes.len()).map_err(|_| format!("durable data field of {} bytes is too large", bytes.len()))?;
+  buffer.extend_from_slice(&len.to_le_bytes());
+  buffer.extend_from_slice(bytes);
+  Ok(())
+}
+
+fn read_bytes(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+  let (len, rest) = bytes.split_first_chunk::<4>()?;
+  let len = u32::from_le_bytes(*len) as usize;
+  (rest.len() >= len).then(|| rest.split_at(len))
+}
+
+fn encode_component(component: &str) -> String {
+  let mut encoded = String::new();
+  for byte in component.bytes() {
+    if byte.is_ascii_alphanumeric() || byte == b'_' {
+      encoded.push(char::from(byte));
+    } else {
+      encoded.push(PERCENT_ENCODING_MARKER);
+      encoded.push(char::from(HEX_DIGITS[(byte >> 4) as usize]));
+      encoded.push(char::from(HEX_DIGITS[(byte & 0x0F) as usize]));
+    }
+  }
+  encoded
+}
@@ -0,0 +1,66 @@
+//! Local durable store configuration.
+
+#[cfg(test)]
+#[path = "local_durable_store_config_test.rs"]
+mod tests;
+
+use alloc::{boxed::Box, vec::Vec};
+use core::any::Any;
+use std::path::{Path, PathBuf};
+
+use fraktor_actor_core_kernel_rs::serialization::serialization_registry::SerializationRegistry;
+use fraktor_cluster_core_kernel_rs::ddata::{DurableDataEnvelope, ReplicatorData};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+/// Restores a deserialized value of one registered type, handing other values back.
+pub(crate) type DataRestorer =
+  fn(Box<dyn Any + Send + Sync>) -> Result<DurableDataEnvelope, Box<dyn Any + Send + Sync>>;
+
+/// Configuration for [`LocalDurableStore`](super::LocalDurableStore).
+///
+/// Stored values are encoded with the serializer bound to their type in the serialization
+/// registry, and only the types registered with
+/// [`with_data_type`](LocalDurableStoreConfig::with_data_type) can be loaded back.
+#[derive(Clone)]
+pub struct LocalDurableStoreConfig {
+  directory:     PathBuf,
+  serialization: ArcShared<SerializationRegistry>,
+  restorers:     Vec<DataRestorer>,
+}
+
+impl LocalDurableStoreConfig {
+  /// Creates a configuration storing values below `directory`.
+  #[must_use]
+  pub const fn new(directory: PathBuf, serialization: ArcShared<SerializationRegistry>) -> Self {
+    Self { directory, serialization, restorers: Vec::new() }
+  }
+
+  /// Returns the configuration able to load stored values of type `D`.
+  #[must_use]
+  pub fn with_data_type<D: ReplicatorData>(mut self) -> Self {
+    self.restorers.push(restore::<D>);
+    self
+  }
+
+  /// Returns the directory values are stored in.
+  #[must_use]
+  pub fn directory(&self) -> &Path {
+    &self.directory
+  }
+
+  /// Returns the serialization registry used to encode stored values.
+  #[must_use]
+  pub const fn serialization(&self) -> &ArcShared<SerializationRegistry> {
+    &self.serialization
+  }
+
+  pub(crate) fn into_parts(self) -> (PathBuf, ArcShared<SerializationRegistry>, Vec<DataRestorer>) {
+    (self.directory, self.serialization, self.restorers)
+  }
+}
+
+fn restore<D: ReplicatorData>(
+  value: Box<dyn Any + Send + Sync>,
+) -> Result<DurableDataEnvelope, Box<dyn Any + Send + Sync>> {
+  value.downcast::<D>().map(|data| DurableDataEnvelope::new(*data))
+}
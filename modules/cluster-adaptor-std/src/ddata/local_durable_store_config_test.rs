@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  default_serialization_setup, serialization_registry::SerializationRegistry,
+};
+use fraktor_cluster_core_kernel_rs::ddata::{Flag, GCounter};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::LocalDurableStoreConfig;
+
+#[test]
+fn config_exposes_directory_registry_and_data_types() {
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&default_serialization_setup()));
+  let config = LocalDurableStoreConfig::new(PathBuf::from("/tmp/ddata"), registry.clone())
+    .with_data_type::<Flag>()
+    .with_data_type::<GCounter>();
+
+  assert_eq!(config.directory(), PathBuf::from("/tmp/ddata").as_path());
+  assert!(ArcShared::ptr_eq(config.serialization(), &registry));
+  let (_, _, restorers) = config.into_parts();
+  assert_eq!(restorers.len(), 2);
+}
@@ -0,0 +1,164 @@
+use alloc::{boxed::Box, collections::BTreeMap, format, string::String, vec, vec::Vec};
+use core::any::{Any, TypeId};
+use std::{
+  fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  SerializationError, Serializer, SerializerId, builtin, default_serialization_setup,
+  serialization_registry::SerializationRegistry,
+};
+use fraktor_cluster_core_kernel_rs::ddata::{DurableDataEnvelope, DurableStore, DurableStoreError, Flag, GCounter};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::LocalDurableStore;
+use crate::ddata::LocalDurableStoreConfig;
+
+const FLAG_SERIALIZER_ID: SerializerId = SerializerId::from_raw(4_100);
+
+struct FlagSerializer;
+
+impl Serializer for FlagSerializer {
+  fn identifier(&self) -> SerializerId {
+    FLAG_SERIALIZER_ID
+  }
+
+  fn to_binary(&self, message: &(dyn Any + Send + Sync)) -> Result<Vec<u8>, SerializationError> {
+    let flag = message.downcast_ref::<Flag>().ok_or(SerializationError::InvalidFormat)?;
+    Ok(vec![u8::from(flag.is_enabled())])
+  }
+
+  fn from_binary(
+    &self,
+    bytes: &[u8],
+    _type_hint: Option<TypeId>,
+  ) -> Result<Box<dyn Any + Send + Sync>, SerializationError> {
+    match bytes {
+      | [0] => Ok(Box::new(Flag::disabled())),
+      | [1] => Ok(Box::new(Flag::disabled().switch_on())),
+      | _ => Err(SerializationError::InvalidFormat),
+    }
+  }
+
+  fn as_any(&self) -> &(dyn Any + Send + Sync) {
+    self
+  }
+}
+
+fn serialization_registry() -> ArcShared<SerializationRegistry> {
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&default_serialization_setup()));
+  builtin::register_defaults(&registry, |name, id| panic!("unexpected serializer collision for {name}: {id:?}"))
+    .expect("register builtin serializers");
+  assert!(registry.register_serializer(FLAG_SERIALIZER_ID, ArcShared::new(FlagSerializer)));
+  registry.register_binding(TypeId::of::<Flag>(), "Flag", FLAG_SERIALIZER_ID).expect("bind flag serializer");
+  registry
+}
+
+fn unique_store_dir(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  std::env::temp_dir().join(format!("fraktor-local-durable-store-{name}-{}-{timestamp}", std::process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("durable store test directory should be removable: {error}"),
+  }
+}
+
+fn open_store(directory: &Path) -> LocalDurableStore {
+  let config = LocalDurableStoreConfig::new(directory.to_path_buf(), serialization_registry()).with_data_type::<Flag>();
+  LocalDurableStore::open(config).expect("open local durable store")
+}
+
+fn flag_enabled(envelope: &DurableDataEnvelope) -> bool {
+  envelope.data_as::<Flag>().expect("stored flag").is_enabled()
+}
+
+#[test]
+fn stored_values_and_tombstones_survive_reopen() {
+  let directory = unique_store_dir("reopen");
+  remove_dir_if_exists(&directory);
+  let store = open_store(&directory);
+  let batch = BTreeMap::from([
+    (String::from("feature/on"), DurableDataEnvelope::new(Flag::disabled().switch_on())),
+    (String::from("removed"), DurableDataEnvelope::deleted()),
+  ]);
+  store.store(&batch).expect("store batch");
+  drop(store);
+
+  let store = open_store(&directory);
+  let loaded = store.load_all().expect("load all");
+  assert_eq!(loaded.keys().map(String::as_str).collect::<Vec<_>>(), ["feature/on", "removed"]);
+  assert!(flag_enabled(&loaded["feature/on"]));
+  assert!(loaded["removed"].is_deleted());
+  assert!(flag_enabled(&store.load("feature/on").expect("load key").expect("stored key")));
+  assert!(store.load("missing").expect("load missing key").is_none());
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn later_stores_replace_previous_values() {
+  let directory = unique_store_dir("replace");
+  remove_dir_if_exists(&directory);
+  let store = open_store(&directory);
+
+  store.store(&BTreeMap::from([(String::from("flag"), DurableDataEnvelope::new(Flag::disabled()))])).expect("store");
+  store
+    .store(&BTreeMap::from([(String::from("flag"), DurableDataEnvelope::new(Flag::disabled().switch_on()))]))
+    .expect("store again");
+
+  assert!(flag_enabled(&store.load("flag").expect("load key").expect("stored key")));
+  assert_eq!(store.load_all().expect("load all").len(), 1);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn interrupted_writes_are_discarded_on_load() {
+  let directory = unique_store_dir("temp");
+  remove_dir_if_exists(&directory);
+  let store = open_store(&directory);
+  fs::write(directory.join("data-flag.tmp"), b"torn").expect("write temp file");
+
+  assert!(store.load_all().expect("load all").is_empty());
+  assert!(!directory.join("data-flag.tmp").exists());
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn values_of_unregistered_types_fail_to_load() {
+  let directory = unique_store_dir("unregistered");
+  remove_dir_if_exists(&directory);
+  open_store(&directory)
+    .store(&BTreeMap::from([(String::from("flag"), DurableDataEnvelope::new(Flag::disabled()))]))
+    .expect("store");
+  let config = LocalDurableStoreConfig::new(directory.clone(), serialization_registry());
+  let store = LocalDurableStore::open(config).expect("open local durable store");
+
+  assert!(matches!(store.load_all(), Err(DurableStoreError::LoadFailed { .. })));
+  assert!(matches!(store.load("flag"), Err(DurableStoreError::LoadFailed { .. })));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn values_without_a_serializer_fail_to_store() {
+  let directory = unique_store_dir("unserializable");
+  remove_dir_if_exists(&directory);
+  let store = open_store(&directory);
+
+  let result = store.store(&BTreeMap::from([(String::from("counter"), DurableDataEnvelope::new(GCounter::new()))]));
+
+  let Err(DurableStoreError::StoreFailed { reason }) = result else {
+    panic!("storing an unserializable value must fail");
+  };
+  assert!(reason.contains("counter"), "unexpected reason {reason}");
+  assert!(store.load("counter").expect("load key").is_none());
+  remove_dir_if_exists(&directory);
+}
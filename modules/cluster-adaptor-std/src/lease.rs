@@ -0,0 +1,5 @@
+//! Lease backends for std runtimes.
+
+mod file_lease;
+
+pub use file_lease::FileLease;
@@ -0,0 +1,293 @@
+//! Lease backed by lock files in a shared directory.
+
+#[cfg(test)]
+#[path = "file_lease_test.rs"]
+mod tests;
+
+use alloc::{
+  boxed::Box,
+  format,
+  string::{String, ToString},
+};
+use core::{
+  sync::atomic::{AtomicU64, Ordering},
+  time::Duration,
+};
+use std::{
+  fs::{self, File},
+  io::{ErrorKind, Write},
+  path::{Path, PathBuf},
+  process,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_cluster_core_kernel_rs::lease::{Lease, LeaseError, LeaseLostCallback, LeaseSettings};
+
+const LOCK_FILE_SUFFIX: &str = ".lock";
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+static TEMP_FILE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// [`Lease`] held through a lock file in a directory shared by all competing owners.
+///
+/// The lock file `<lease-name>.lock` records the owner and its last heartbeat. It is created
+/// atomically by hard-linking a fully written temporary file, so competitors never observe a
+/// partially written lock. A lock whose heartbeat is older than the heartbeat timeout is taken
+/// over by renaming it aside first, so only one competitor replaces it. Heartbeats compare wall
+/// clock time, so the clocks of the owners must be roughly synchronized.
+pub struct FileLease {
+  settings:   LeaseSettings,
+  path:       PathBuf,
+  renewed_at: Option<SystemTime>,
+  on_lost:    Option<LeaseLostCallback>,
+}
+
+impl FileLease {
+  /// Implementation name under which [`FileLease::factory`] is usually registered.
+  pub const IMPLEMENTATION: &'static str = "file";
+
+  /// Creates a lease whose lock file lives in `directory`.
+  #[must_use]
+  pub fn new(settings: LeaseSettings, directory: impl Into<PathBuf>) -> Self {
+    let file_name = format!("{}{LOCK_FILE_SUFFIX}", encode_component(settings.lease_name()));
+    let path = directory.into().join(file_name);
+    Self { settings, path, renewed_at: None, on_lost: None }
+  }
+
+  /// Returns a factory for [`LeaseProvider`](fraktor_cluster_core_kernel_rs::lease::LeaseProvider)
+  /// creating file leases in `directory`.
+  pub fn factory(directory: impl Into<PathBuf>) -> impl Fn(LeaseSettings) -> Box<dyn Lease> + Send + Sync + 'static {
+    let directory = directory.into();
+    move |settings| Box::new(Self::new(settings, directory.clone()))
+  }
+
+  /// Returns the path of the lock file.
+  #[must_use]
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  fn owner(&self) -> &str {
+    self.settings.owner_name()
+  }
+
+  fn read_record(&self) -> Result<Option<LockRecord>, LeaseError> {
+    match fs::read_to_string(&self.path) {
+      | Ok(text) => Ok(Some(
+        LockRecord::decode(&text).ok_or_else(|| unavailable(format!("corrupt lock file {}", self.path.display())))?,
+      )),
+      | Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+      | Err(error) => Err(unavailable(format!("read lock file {}: {error}", self.path.display()))),
+    }
+  }
+
+  fn write_temp(&self, now: SystemTime) -> Result<PathBuf, LeaseError> {
+    let sequence = TEMP_FILE_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let mut temp_name = self.path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    temp_name.push(format!(".{}-{sequence}.tmp", process::id()));
+    let temp_path = self.path.with_file_name(temp_name);
+    let record = LockRecord { owner: self.owner().to_string(), heartbeat_millis: epoch_millis(now) };
+    let written = File::create(&temp_path).and_then(|mut file| {
+      file.write_all(record.encode().as_bytes())?;
+      file.sync_all()
+    });
+    if let Err(error) = written {
+      // 一時ファイルはロックとして参照されないので、削除に失敗しても残骸が残るだけで済む
+      drop(fs::remove_file(&temp_path));
+      return Err(unavailable(format!("write temp lock file {}: {error}", temp_path.display())));
+    }
+    Ok(temp_path)
+  }
+
+  /// Creates the lock file, returning false when another owner created it first.
+  fn create(&self, now: SystemTime) -> Result<bool, LeaseError> {
+    if let Some(directory) = self.path.parent() {
+      fs::create_dir_all(directory)
+        .map_err(|error| unavailable(format!("create lease directory {}: {error}", directory.display())))?;
+    }
+    let temp_path = self.write_temp(now)?;
+    // ハードリンクは既存ファイルを上書きしないので、作成できた 1 者だけが所有者になる
+    let linked = fs::hard_link(&temp_path, &self.path);
+    // リンク後の一時ファイルはロックの別名でしかないので、削除に失敗しても所有者の判定は変わらない
+    drop(fs::remove_file(&temp_path));
+    match linked {
+      | Ok(()) => Ok(true),
+      | Err(error) if error.kind() == ErrorKind::AlreadyExists => Ok(false),
+      | Err(error) => Err(unavailable(format!("create lock file {}: {error}", self.path.display()))),
+    }
+  }
+
+  /// Replaces the heartbeat of the lock file held by this owner.
+  fn heartbeat(&self, now: SystemTime) -> Result<(), LeaseError> {
+    let temp_path = self.write_temp(now)?;
+    fs::rename(&temp_path, &self.path).map_err(|error| {
+      // 置き換えに失敗した一時ファイルはロックとして参照されないので、削除は best-effort でよい
+      drop(fs::remove_file(&temp_path));
+      unavailable(format!("replace lock file {}: {error}", self.path.display()))
+    })
+  }
+
+  /// Removes the expired lock `stale`, leaving a lock that was renewed meanwhile in place.
+  fn take_over(&self, stale: &LockRecord) -> Result<(), LeaseError> {
+    let sequence = TEMP_FILE_SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let mut aside_name = self.path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    aside_name.push(format!(".{}-{sequence}.stale", process::id()));
+    let aside_path = self.path.with_file_name(aside_name);
+    match fs::rename(&self.path, &aside_path) {
+      | Ok(()) => {},
+      | Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+      | Err(error) => return Err(unavailable(format!("move stale lock file {}: {error}", self.path.display()))),
+    }
+    let moved = fs::read_to_string(&aside_path).ok().and_then(|text| LockRecord::decode(&text));
+    if moved.as_ref() != Some(stale) {
+      // 読み取り後に更新されたロックを退避してしまったので元に戻す
+      match fs::hard_link(&aside_path, &self.path) {
+        | Ok(()) => {},
+        // 別の競合者が既に新しいロックを作成しており、そのロックが有効になる
+        | Err(error) if error.kind() == ErrorKind::AlreadyExists => {},
+        // 退避したロックを削除すると生存中の所有者のロックが失われるため残しておく
+        | Err(error) => {
+          return Err(unavailable(format!(
+            "restore lock file {} from {}: {error}",
+            self.path.display(),
+            aside_path.display()
+          )));
+        },
+      }
+    }
+    // 退避したファイルは期限切れか復元済みのロックの別名なので、削除に失敗しても残骸が残るだけで済む
+    drop(fs::remove_file(&aside_path));
+    Ok(())
+  }
+
+  fn expired(&self, renewed_at: SystemTime, now: SystemTime) -> bool {
+    now.duration_since(renewed_at).unwrap_or(Duration::ZERO) > self.settings.heartbeat_timeout()
+  }
+
+  fn lost(&mut self, reason: String) -> LeaseError {
+    self.renewed_at = None;
+    let error = LeaseError::Lost { reason };
+    if let Some(on_lost) = self.on_lost.take() {
+      on_lost(&error);
+    }
+    error
+  }
+}
+
+impl Lease for FileLease {
+  fn settings(&self) -> &LeaseSettings {
+    &self.settings
+  }
+
+  fn acquire(&mut self, on_lost: Option<LeaseLostCallback>) -> Result<bool, LeaseError> {
+    let now = SystemTime::now();
+    let mut granted = false;
+    // 期限切れロックを退避した後は、改めて作成を試みる
+    for _ in 0..2 {
+      match self.read_record()? {
+        | None => {
+          granted = self.create(now)?;
+          break;
+        },
+        | Some(record) if record.owner == self.owner() => {
+          self.heartbeat(now)?;
+          granted = true;
+          break;
+        },
+        | Some(record) if self.expired(record.heartbeat(), now) => self.take_over(&record)?,
+        | Some(_) => break,
+      }
+    }
+    if !granted {
+      self.renewed_at = None;
+      return Ok(false);
+    }
+    self.renewed_at = Some(now);
+    self.on_lost = on_lost;
+    Ok(true)
+  }
+
+  fn renew(&mut self) -> Result<(), LeaseError> {
+    let Some(renewed_at) = self.renewed_at else {
+      return Err(LeaseError::Lost { reason: String::from("lease is not held") });
+    };
+    let now = SystemTime::now();
+    // 心拍が途絶えていた間に他の所有者が奪取した可能性があるので書き込まない
+    if self.expired(renewed_at, now) {
+      return Err(self.lost(String::from("heartbeat timed out")));
+    }
+    match self.read_record()? {
+      | Some(record) if record.owner == self.owner() => {},
+      | Some(record) => return Err(self.lost(format!("taken over by {}", record.owner))),
+      | None => return Err(self.lost(String::from("lock file was removed"))),
+    }
+    self.heartbeat(now)?;
+    self.renewed_at = Some(now);
+    Ok(())
+  }
+
+  fn release(&mut self) -> Result<bool, LeaseError> {
+    if self.renewed_at.take().is_none() {
+      return Ok(false);
+    }
+    self.on_lost = None;
+    match self.read_record()? {
+      | Some(record) if record.owner == self.owner() => match fs::remove_file(&self.path) {
+        | Ok(()) => Ok(true),
+        | Err(error) if error.kind() == ErrorKind::NotFound => Ok(false),
+        | Err(error) => Err(unavailable(format!("remove lock file {}: {error}", self.path.display()))),
+      },
+      | _ => Ok(false),
+    }
+  }
+
+  fn check_lease(&self) -> bool {
+    self.renewed_at.is_some_and(|renewed_at| !self.expired(renewed_at, SystemTime::now()))
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LockRecord {
+  owner:            String,
+  heartbeat_millis: u64,
+}
+
+impl LockRecord {
+  fn encode(&self) -> String {
+    format!("{}\n{}\n", self.owner, self.heartbeat_millis)
+  }
+
+  fn decode(text: &str) -> Option<Self> {
+    let mut lines = text.lines();
+    let owner = lines.next()?.to_string();
+    let heartbeat_millis = lines.next()?.parse().ok()?;
+    Some(Self { owner, heartbeat_millis })
+  }
+
+  fn heartbeat(&self) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(self.heartbeat_millis)
+  }
+}
+
+fn epoch_millis(time: SystemTime) -> u64 {
+  let millis = time.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_millis();
+  u64::try_from(millis).unwrap_or(u64::MAX)
+}
+
+const fn unavailable(reason: String) -> LeaseError {
+  LeaseError::Unavailable { reason }
+}
+
+fn encode_component(value: &str) -> String {
+  let mut encoded = String::with_capacity(value.len());
+  for byte in value.bytes() {
+    if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.') {
+      encoded.push(char::from(byte));
+    } else {
+      encoded.push('%');
+      encoded.push(char::from(HEX_DIGITS[usize::from(byte >> 4)]));
+      encoded.push(char::from(HEX_DIGITS[usize::from(byte & 0x0F)]));
+    }
+  }
+  encoded
+}
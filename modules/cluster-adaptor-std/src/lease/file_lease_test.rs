@@ -0,0 +1,196 @@
+use alloc::{format, string::String, vec::Vec};
+use core::{
+  sync::atomic::{AtomicU32, Ordering},
+  time::Duration,
+};
+use std::{
+  fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  sync::Barrier,
+  thread,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_cluster_core_kernel_rs::lease::{Lease, LeaseError, LeaseProvider, LeaseSettings};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+use super::{FileLease, LockRecord};
+
+fn unique_lease_dir(name: &str) -> PathBuf {
+  let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).expect("system time").as_nanos();
+  std::env::temp_dir().join(format!("fraktor-file-lease-{name}-{}-{timestamp}", std::process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("file lease test directory should be removable: {error}"),
+  }
+}
+
+fn lease(directory: &Path, owner: &str) -> FileLease {
+  FileLease::new(LeaseSettings::new("sbr", owner), directory)
+}
+
+fn short_lived(directory: &Path, owner: &str) -> FileLease {
+  let settings = LeaseSettings::new("sbr", owner).with_heartbeat_timeout(Duration::from_millis(50));
+  FileLease::new(settings, directory)
+}
+
+#[test]
+fn first_owner_acquires_and_others_are_denied() {
+  let directory = unique_lease_dir("exclusive");
+  let mut first = lease(&directory, "a:1");
+  let mut second = lease(&directory, "b:1");
+
+  assert_eq!(first.acquire(None), Ok(true));
+  assert_eq!(second.acquire(None), Ok(false));
+  assert_eq!(first.acquire(None), Ok(true));
+
+  assert!(first.check_lease());
+  assert!(!second.check_lease());
+  assert_eq!(fs::read_to_string(first.path()).expect("lock file").lines().next(), Some("a:1"));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn release_lets_another_owner_acquire() {
+  let directory = unique_lease_dir("release");
+  let mut first = lease(&directory, "a:1");
+  let mut second = lease(&directory, "b:1");
+  assert_eq!(first.acquire(None), Ok(true));
+
+  assert_eq!(second.release(), Ok(false));
+  assert_eq!(first.release(), Ok(true));
+  assert!(!first.path().exists());
+
+  assert!(!first.check_lease());
+  assert_eq!(second.acquire(None), Ok(true));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn renew_keeps_the_lease_held() {
+  let directory = unique_lease_dir("renew");
+  let mut first = short_lived(&directory, "a:1");
+  let mut second = short_lived(&directory, "b:1");
+  assert_eq!(first.acquire(None), Ok(true));
+
+  for _ in 0..4 {
+    thread::sleep(Duration::from_millis(20));
+    assert_eq!(first.renew(), Ok(()));
+  }
+
+  assert_eq!(second.acquire(None), Ok(false));
+  assert!(first.check_lease());
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn expired_lease_is_taken_over_and_the_previous_owner_is_told_it_is_lost() {
+  let directory = unique_lease_dir("takeover");
+  let mut first = short_lived(&directory, "a:1");
+  let mut second = short_lived(&directory, "b:1");
+  let lost: ArcShared<SpinSyncMutex<Vec<LeaseError>>> = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let on_lost = {
+    let lost = lost.clone();
+    Box::new(move |error: &LeaseError| lost.lock().push(error.clone()))
+  };
+  assert_eq!(first.acquire(Some(on_lost)), Ok(true));
+
+  thread::sleep(Duration::from_millis(100));
+  assert!(!first.check_lease());
+  assert_eq!(second.acquire(None), Ok(true));
+
+  assert!(matches!(first.renew(), Err(LeaseError::Lost { .. })));
+  assert!(matches!(first.renew(), Err(LeaseError::Lost { .. })));
+  assert_eq!(lost.lock().len(), 1);
+  assert_eq!(second.renew(), Ok(()));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn taken_over_lease_is_lost_on_renew() {
+  let directory = unique_lease_dir("renew-lost");
+  let settings = LeaseSettings::new("sbr", "a:1").with_heartbeat_timeout(Duration::from_secs(60));
+  let mut first = FileLease::new(settings, &directory);
+  assert_eq!(first.acquire(None), Ok(true));
+
+  fs::write(first.path(), "b:1\n0\n").expect("overwrite lock file");
+
+  assert_eq!(first.renew(), Err(LeaseError::Lost { reason: String::from("taken over by b:1") }));
+  assert!(!first.check_lease());
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn take_over_restores_a_lock_renewed_after_it_was_read() {
+  let directory = unique_lease_dir("restore");
+  let mut first = lease(&directory, "a:1");
+  let second = lease(&directory, "b:1");
+  assert_eq!(first.acquire(None), Ok(true));
+  let renewed = fs::read_to_string(first.path()).expect("lock file");
+
+  // 読み取った期限切れの記録と異なる、更新済みのロックを退避してしまう場合
+  let stale = LockRecord { owner: String::from("a:1"), heartbeat_millis: 0 };
+  assert_eq!(second.take_over(&stale), Ok(()));
+
+  assert_eq!(fs::read_to_string(first.path()).expect("restored lock file"), renewed);
+  let leftovers: Vec<String> = fs::read_dir(&directory)
+    .expect("lease directory")
+    .map(|entry| entry.expect("directory entry").file_name().to_string_lossy().into_owned())
+    .filter(|name| name.ends_with(".stale"))
+    .collect();
+  assert!(leftovers.is_empty(), "unexpected aside files {leftovers:?}");
+  assert_eq!(first.renew(), Ok(()));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn concurrent_acquirers_grant_the_lease_once() {
+  let directory = unique_lease_dir("concurrent");
+  let contenders = 8;
+  let barrier = Barrier::new(contenders);
+  let granted = AtomicU32::new(0);
+
+  thread::scope(|scope| {
+    for index in 0..contenders {
+      let (directory, barrier, granted) = (&directory, &barrier, &granted);
+      scope.spawn(move || {
+        let mut lease = lease(directory, &format!("node-{index}:1"));
+        barrier.wait();
+        if lease.acquire(None).expect("acquire") {
+          granted.fetch_add(1, Ordering::AcqRel);
+        }
+      });
+    }
+  });
+
+  assert_eq!(granted.load(Ordering::Acquire), 1);
+  let leftovers: Vec<_> = fs::read_dir(&directory).expect("lease directory").collect();
+  assert_eq!(leftovers.len(), 1);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn lease_names_are_encoded_into_the_lock_file_name() {
+  let directory = unique_lease_dir("encode");
+  let lease = FileLease::new(LeaseSettings::new("singleton/app a", "a:1"), &directory);
+
+  assert_eq!(lease.path(), directory.join("singleton%2Fapp%20a.lock"));
+}
+
+#[test]
+fn factory_registers_file_leases_in_a_provider() {
+  let directory = unique_lease_dir("provider");
+  let provider = LeaseProvider::new().with_implementation(FileLease::IMPLEMENTATION, FileLease::factory(&directory));
+
+  let mut first = provider.get_lease(FileLease::IMPLEMENTATION, LeaseSettings::new("sbr", "a:1")).expect("lease");
+  let mut second = provider.get_lease(FileLease::IMPLEMENTATION, LeaseSettings::new("sbr", "b:1")).expect("lease");
+
+  assert_eq!(first.acquire(None), Ok(true));
+  assert_eq!(second.acquire(None), Ok(false));
+  remove_dir_if_exists(&directory);
+}
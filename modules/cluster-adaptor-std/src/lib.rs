@@ -7,13 +7,19 @@
 
 extern crate alloc;
 
+/// Activation storage adaptors for remembered grains.
+pub mod activation;
 /// Cluster provider adaptors for std runtimes.
 pub mod cluster_provider;
+/// Durable storage adaptors for distributed data.
+pub mod ddata;
 /// ActorSystem integration for AWS ECS cluster extensions.
 #[cfg(feature = "aws-ecs")]
 pub mod extension;
 /// Std helpers for virtual actor grain APIs.
 pub mod grain;
+/// Lease backends for std runtimes.
+pub mod lease;
 /// Tokio-backed membership and gossip adaptors.
 pub mod membership;
 /// Cluster message wire frame adaptors.
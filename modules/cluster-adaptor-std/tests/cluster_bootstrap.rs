@@ -0,0 +1,159 @@
+//! Cluster bootstrap over real remoting: the lowest contact point forms the cluster, the others
+//! join it.
+
+use std::{
+  format,
+  net::TcpListener,
+  string::String,
+  thread,
+  time::{Duration, Instant},
+  vec::Vec,
+};
+
+use fraktor_actor_adaptor_std_rs::{system::std_actor_system_config, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{actor::extension::ExtensionInstallers, system::ActorSystem};
+use fraktor_cluster_adaptor_std_rs::cluster_provider::{
+  ClusterBootstrap, ClusterBootstrapResponder, DiscoveryBackend, DiscoveryBackendError, GenericDiscoveryAdapter,
+  RemotingContactPointProber, wrap_local_cluster_provider,
+};
+use fraktor_cluster_core_kernel_rs::{
+  cluster_provider::{
+    ClusterBootstrapConfig, ClusterBootstrapDecision, LocalClusterProvider, LocalClusterProviderShared,
+  },
+  extension::StartupMode,
+  topology::BlockListProvider,
+};
+use fraktor_remote_adaptor_std_rs::{
+  extension_installer::RemotingExtensionInstaller, provider::StdRemoteActorRefProviderInstaller,
+  transport::tcp::TcpRemoteTransport,
+};
+use fraktor_remote_core_rs::{
+  address::{Address, UniqueAddress},
+  config::RemoteConfig,
+};
+use fraktor_utils_core_rs::{
+  sync::{ArcShared, SharedAccess},
+  time::TimerInstant,
+};
+
+const SYSTEM_NAME: &str = "cluster-bootstrap-e2e";
+
+struct EmptyBlockList;
+
+impl BlockListProvider for EmptyBlockList {
+  fn blocked_members(&self) -> Vec<String> {
+    Vec::new()
+  }
+}
+
+struct StaticBackend {
+  authorities: Vec<String>,
+}
+
+impl DiscoveryBackend for StaticBackend {
+  fn source_identity(&self) -> &str {
+    "static"
+  }
+
+  fn discover(&mut self) -> Result<Vec<String>, DiscoveryBackendError> {
+    Ok(self.authorities.clone())
+  }
+}
+
+struct BootstrapNode {
+  system:    ActorSystem,
+  authority: String,
+  provider:  LocalClusterProviderShared,
+}
+
+fn reserve_port() -> u16 {
+  let listener = TcpListener::bind("127.0.0.1:0").expect("reserve tcp port");
+  listener.local_addr().expect("reserved local addr").port()
+}
+
+fn build_node(port: u16, uid: u64) -> BootstrapNode {
+  let address = Address::new(SYSTEM_NAME, "127.0.0.1", port);
+  let transport = TcpRemoteTransport::new(format!("127.0.0.1:{port}"), vec![address.clone()]);
+  let remote_config = RemoteConfig::new("127.0.0.1").with_allowed_remote_host("127.0.0.1");
+  let installer = ArcShared::new(RemotingExtensionInstaller::new(transport, remote_config));
+  let provider_installer = StdRemoteActorRefProviderInstaller::from_remoting_extension_installer(
+    UniqueAddress::new(address, uid),
+    installer.clone(),
+  );
+  let config = std_actor_system_config(TestTickDriver::default())
+    .with_system_name(SYSTEM_NAME)
+    .with_extension_installers(ExtensionInstallers::default().with_shared_extension_installer(installer))
+    .with_actor_ref_provider_installer(provider_installer);
+  let system = ActorSystem::create_with_noop_guardian(config).expect("actor system should build");
+
+  let authority = format!("127.0.0.1:{port}");
+  let provider = wrap_local_cluster_provider(LocalClusterProvider::new(
+    system.event_stream(),
+    ArcShared::new(EmptyBlockList),
+    authority.clone(),
+  ));
+  system
+    .actor_of_named(&ClusterBootstrapResponder::props(provider.downgrade()), ClusterBootstrapResponder::NAME)
+    .expect("bootstrap responder should spawn");
+  BootstrapNode { system, authority, provider }
+}
+
+/// Polls bootstrap until it decides to join, returning the join decision.
+fn run_bootstrap(node: &BootstrapNode, contact_points: Vec<String>) -> ClusterBootstrapDecision {
+  let config = ClusterBootstrapConfig::new()
+    .with_stable_margin(Duration::from_millis(200))
+    .with_required_contact_point_count(contact_points.len())
+    .with_probe_interval(Duration::from_millis(50));
+  let prober =
+    RemotingContactPointProber::new(&node.system, &node.authority, Duration::from_millis(500)).expect("prober");
+  let adapter = GenericDiscoveryAdapter::new(StaticBackend { authorities: contact_points });
+  let mut bootstrap = ClusterBootstrap::new(node.provider.downgrade(), config, node.authority.clone(), adapter, prober);
+
+  let started = Instant::now();
+  let deadline = started + Duration::from_secs(20);
+  loop {
+    let elapsed = started.elapsed();
+    let now = TimerInstant::from_ticks(u64::try_from(elapsed.as_millis()).expect("millis"), Duration::from_millis(1));
+    match bootstrap.poll(now).expect("bootstrap poll") {
+      | ClusterBootstrapDecision::Pending { reason } => {
+        assert!(Instant::now() < deadline, "bootstrap of {} did not decide: {reason}", node.authority);
+        thread::sleep(config.probe_interval());
+      },
+      | decision => return decision,
+    }
+  }
+}
+
+#[test]
+fn lowest_contact_point_forms_the_cluster_and_the_others_join_it() {
+  // リモーティングの TCP トランスポートは tokio ランタイム上で動作する
+  let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().expect("tokio runtime");
+  let _guard = runtime.enter();
+  let mut ports = [reserve_port(), reserve_port(), reserve_port()];
+  ports.sort_unstable();
+  let nodes: Vec<BootstrapNode> = ports.iter().zip(1..).map(|(port, uid)| build_node(*port, uid)).collect();
+  let contact_points: Vec<String> = nodes.iter().map(|node| node.authority.clone()).collect();
+
+  let decisions: Vec<ClusterBootstrapDecision> = thread::scope(|scope| {
+    let handles: Vec<_> =
+      nodes.iter().map(|node| scope.spawn(|| run_bootstrap(node, contact_points.clone()))).collect();
+    handles.into_iter().map(|handle| handle.join().expect("bootstrap thread")).collect()
+  });
+
+  assert_eq!(decisions[0], ClusterBootstrapDecision::JoinSelf);
+  for decision in &decisions[1..] {
+    // 先に参加したノードもシードとして報告され得るため、最小ノードを含むことだけを確認する
+    let ClusterBootstrapDecision::JoinSeedNodes(seeds) = decision else {
+      panic!("expected JoinSeedNodes, got {decision:?}");
+    };
+    assert!(seeds.contains(&contact_points[0]), "seeds {seeds:?} should contain the lowest contact point");
+    assert!(seeds.iter().all(|seed| contact_points.contains(seed)));
+  }
+  for node in &nodes {
+    assert_eq!(node.provider.with_read(LocalClusterProvider::startup_mode), Some(StartupMode::Member));
+  }
+
+  for node in nodes {
+    node.system.terminate().expect("system should terminate");
+  }
+}
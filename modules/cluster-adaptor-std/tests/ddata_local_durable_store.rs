@@ -0,0 +1,202 @@
+//! Durable distributed-data keys surviving a restart of the Replicator's actor system.
+
+use std::{
+  any::{Any, TypeId},
+  fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  thread,
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext,
+    actor_ref::ActorRef,
+    error::ActorError,
+    extension::ExtensionInstallers,
+    messaging::{AnyMessage, AnyMessageView},
+    props::Props,
+    scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  serialization::{
+    SerializationError, Serializer, SerializerId, builtin, default_serialization_setup,
+    serialization_registry::SerializationRegistry,
+  },
+  system::ActorSystem,
+};
+use fraktor_cluster_adaptor_std_rs::ddata::{LocalDurableStore, LocalDurableStoreConfig};
+use fraktor_cluster_core_kernel_rs::ddata::{
+  DurableStore, Flag, FlagKey, Get, GetResponse, ReadConsistency, ReplicatorCommand, ReplicatorExtension,
+  ReplicatorExtensionInstaller, ReplicatorSettings, SelfUniqueAddress, Update, UpdateResponse, WriteConsistency,
+};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+const FLAG_SERIALIZER_ID: SerializerId = SerializerId::from_raw(4_100);
+
+struct FlagSerializer;
+
+impl Serializer for FlagSerializer {
+  fn identifier(&self) -> SerializerId {
+    FLAG_SERIALIZER_ID
+  }
+
+  fn to_binary(&self, message: &(dyn Any + Send + Sync)) -> Result<Vec<u8>, SerializationError> {
+    let flag = message.downcast_ref::<Flag>().ok_or(SerializationError::InvalidFormat)?;
+    Ok(vec![u8::from(flag.is_enabled())])
+  }
+
+  fn from_binary(
+    &self,
+    bytes: &[u8],
+    _type_hint: Option<TypeId>,
+  ) -> Result<Box<dyn Any + Send + Sync>, SerializationError> {
+    match bytes {
+      | [0] => Ok(Box::new(Flag::disabled())),
+      | [1] => Ok(Box::new(Flag::disabled().switch_on())),
+      | _ => Err(SerializationError::InvalidFormat),
+    }
+  }
+
+  fn as_any(&self) -> &(dyn Any + Send + Sync) {
+    self
+  }
+}
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+type Received = ArcShared<SpinSyncMutex<Vec<Box<dyn Any + Send + Sync>>>>;
+
+struct ProbeActor {
+  received: Received,
+}
+
+impl Actor for ProbeActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    let captured: Option<Box<dyn Any + Send + Sync>> =
+      if let Some(response) = message.downcast_ref::<UpdateResponse<Flag>>() {
+        Some(Box::new(response.clone()))
+      } else {
+        message.downcast_ref::<GetResponse<Flag>>().map(|response| Box::new(response.clone()) as _)
+      };
+    if let Some(captured) = captured {
+      self.received.lock().push(captured);
+    }
+    Ok(())
+  }
+}
+
+struct Node {
+  _system:    ActorSystem,
+  replicator: ActorRef,
+  probe:      ActorRef,
+  received:   Received,
+}
+
+impl Node {
+  fn start(directory: &Path) -> Self {
+    let address = SelfUniqueAddress::new(UniqueAddress::new(Address::new("sys", "node-a", 2552), 1));
+    let config =
+      LocalDurableStoreConfig::new(directory.to_path_buf(), serialization_registry()).with_data_type::<Flag>();
+    let store = LocalDurableStore::open(config).expect("open local durable store");
+    let installer = ReplicatorExtensionInstaller::new(address)
+      .with_settings(ReplicatorSettings::new().with_durable_keys(["durable-*"]))
+      .with_durable_store(ArcShared::new(store) as ArcShared<dyn DurableStore>);
+    let config = ActorSystemConfig::new(TestTickDriver::default())
+      .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+      .with_extension_installers(ExtensionInstallers::default().with_extension_installer(installer));
+    let system =
+      ActorSystem::create_from_props(&Props::from_fn(|| GuardianActor), config).expect("system should build");
+    let replicator =
+      system.extended().extension_by_type::<ReplicatorExtension>().expect("replicator is installed").replicator();
+    let received = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+    let props = {
+      let received = received.clone();
+      Props::from_fn(move || ProbeActor { received: received.clone() }).with_name("probe")
+    };
+    let probe = system.extended().spawn_system_actor(&props).expect("spawn probe").into_actor_ref();
+    Self { _system: system, replicator, probe, received }
+  }
+
+  fn expect<T: Clone + 'static>(&self) -> T {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+      {
+        let mut received = self.received.lock();
+        if let Some(index) = received.iter().position(|message| message.is::<T>()) {
+          return received.remove(index).downcast_ref::<T>().cloned().expect("message type was checked");
+        }
+      }
+      assert!(Instant::now() < deadline, "expected message did not arrive");
+      thread::yield_now();
+    }
+  }
+
+  fn switch_on(&self, key: &str) -> UpdateResponse<Flag> {
+    let update = Update::<Flag>::new(FlagKey::new(key), WriteConsistency::Local);
+    let command = ReplicatorCommand::update(
+      update,
+      |current: Option<&Flag>| Ok(current.cloned().unwrap_or_default().switch_on()),
+      self.probe.clone(),
+    );
+    self.replicator.clone().tell(AnyMessage::new(command));
+    self.expect::<UpdateResponse<Flag>>()
+  }
+
+  fn read(&self, key: &str) -> GetResponse<Flag> {
+    let get = Get::<Flag>::new(FlagKey::new(key), ReadConsistency::Local);
+    self.replicator.clone().tell(AnyMessage::new(ReplicatorCommand::get(get, self.probe.clone())));
+    self.expect::<GetResponse<Flag>>()
+  }
+}
+
+fn serialization_registry() -> ArcShared<SerializationRegistry> {
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&default_serialization_setup()));
+  builtin::register_defaults(&registry, |name, id| panic!("unexpected serializer collision for {name}: {id:?}"))
+    .expect("register builtin serializers");
+  assert!(registry.register_serializer(FLAG_SERIALIZER_ID, ArcShared::new(FlagSerializer)));
+  registry.register_binding(TypeId::of::<Flag>(), "Flag", FLAG_SERIALIZER_ID).expect("bind flag serializer");
+  registry
+}
+
+fn unique_store_dir(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  std::env::temp_dir().join(format!("fraktor-ddata-durable-{name}-{}-{timestamp}", std::process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("durable store test directory should be removable: {error}"),
+  }
+}
+
+#[test]
+fn durable_keys_survive_a_restart_and_volatile_keys_do_not() {
+  let directory = unique_store_dir("restart");
+  remove_dir_if_exists(&directory);
+
+  let node = Node::start(&directory);
+  assert!(matches!(node.switch_on("durable-feature"), UpdateResponse::Success { .. }));
+  assert!(matches!(node.switch_on("volatile-feature"), UpdateResponse::Success { .. }));
+  drop(node);
+
+  let restarted = Node::start(&directory);
+  let durable = restarted.read("durable-feature");
+  assert!(durable.data().expect("durable key is restored").is_enabled());
+  assert!(matches!(restarted.read("volatile-feature"), GetResponse::NotFound { .. }));
+  remove_dir_if_exists(&directory);
+}
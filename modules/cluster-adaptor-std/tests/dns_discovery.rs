@@ -0,0 +1,248 @@
+use core::time::Duration;
+use std::{
+  io::{Read, Write},
+  net::{Ipv4Addr, SocketAddr, TcpListener, UdpSocket},
+  thread::{self, JoinHandle},
+};
+
+use fraktor_cluster_adaptor_std_rs::cluster_provider::{
+  DnsDiscoveryBackend, DnsDiscoveryConfig, DnsLookupMode, DnsResolver, GenericDiscoveryAdapter, UdpDnsResolver,
+};
+use fraktor_cluster_core_kernel_rs::{cluster_provider::DiscoveryTopologyMapper, topology::BlockListProvider};
+use fraktor_utils_core_rs::{sync::ArcShared, time::TimerInstant};
+
+const SRV_NAME: &str = "_fraktor._tcp.cluster.local";
+const ADDRESS_NAME: &str = "seeds.cluster.local";
+const TYPE_A: u16 = 1;
+const TYPE_SRV: u16 = 33;
+
+struct EmptyBlockList;
+
+impl BlockListProvider for EmptyBlockList {
+  fn blocked_members(&self) -> Vec<String> {
+    Vec::new()
+  }
+}
+
+fn encode_name(bytes: &mut Vec<u8>, name: &str) {
+  for label in name.split('.') {
+    bytes.push(label.len() as u8);
+    bytes.extend_from_slice(label.as_bytes());
+  }
+  bytes.push(0);
+}
+
+fn read_question(request: &[u8]) -> (String, u16, usize) {
+  let mut offset = 12;
+  let mut labels = Vec::new();
+  while request[offset] != 0 {
+    let len = usize::from(request[offset]);
+    labels.push(String::from_utf8_lossy(&request[offset + 1..offset + 1 + len]).into_owned());
+    offset += len + 1;
+  }
+  let record_type = u16::from_be_bytes([request[offset + 1], request[offset + 2]]);
+  (labels.join("."), record_type, offset + 5)
+}
+
+fn answers_for(name: &str, record_type: u16) -> Vec<(u16, Vec<u8>)> {
+  match (name, record_type) {
+    | (SRV_NAME, TYPE_SRV) => ["node-b.cluster.local", "node-a.cluster.local"]
+      .iter()
+      .map(|target| {
+        let mut data = Vec::from([0, 10, 0, 5, 0x1C, 0xA3]);
+        encode_name(&mut data, target);
+        (TYPE_SRV, data)
+      })
+      .collect(),
+    | (ADDRESS_NAME, TYPE_A) => Vec::from([(TYPE_A, Vec::from([10, 0, 1, 12])), (TYPE_A, Vec::from([10, 0, 1, 13]))]),
+    | _ => Vec::new(),
+  }
+}
+
+fn respond(request: &[u8]) -> Vec<u8> {
+  let (name, record_type, _) = read_question(request);
+  respond_with(request, 0x8180, answers_for(&name, record_type))
+}
+
+fn respond_with(request: &[u8], flags: u16, answers: Vec<(u16, Vec<u8>)>) -> Vec<u8> {
+  let (_, _, question_end) = read_question(request);
+  let mut response = Vec::from(&request[..2]);
+  response.extend_from_slice(&flags.to_be_bytes());
+  response.extend_from_slice(&[0, 1]);
+  response.extend_from_slice(&(answers.len() as u16).to_be_bytes());
+  response.extend_from_slice(&[0, 0, 0, 0]);
+  response.extend_from_slice(&request[12..question_end]);
+  for (answer_type, data) in answers {
+    response.extend_from_slice(&[0xC0, 0x0C]);
+    response.extend_from_slice(&answer_type.to_be_bytes());
+    response.extend_from_slice(&1_u16.to_be_bytes());
+    response.extend_from_slice(&30_u32.to_be_bytes());
+    response.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    response.extend_from_slice(&data);
+  }
+  response
+}
+
+/// Answers `expected_queries` DNS queries from an in-process UDP stand-in.
+fn spawn_dns_stand_in(expected_queries: usize) -> (SocketAddr, JoinHandle<()>) {
+  let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind dns stand-in");
+  socket.set_read_timeout(Some(Duration::from_secs(5))).expect("read timeout");
+  let address = socket.local_addr().expect("local address");
+  let handle = thread::spawn(move || {
+    let mut buffer = [0_u8; 512];
+    for _ in 0..expected_queries {
+      let (len, peer) = socket.recv_from(&mut buffer).expect("dns query");
+      socket.send_to(&respond(&buffer[..len]), peer).expect("dns response");
+    }
+  });
+  (address, handle)
+}
+
+/// Binds a UDP socket and a TCP listener on the same loopback port.
+fn bind_udp_and_tcp() -> (UdpSocket, TcpListener) {
+  for _ in 0..16 {
+    let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind dns stand-in");
+    socket.set_read_timeout(Some(Duration::from_secs(5))).expect("read timeout");
+    let port = socket.local_addr().expect("local address").port();
+    if let Ok(listener) = TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+      return (socket, listener);
+    }
+  }
+  panic!("no loopback port is free for both udp and tcp");
+}
+
+fn srv_targets(resolver: &mut UdpDnsResolver) -> Vec<String> {
+  let mut targets: Vec<String> =
+    resolver.resolve_srv(SRV_NAME).expect("srv records").iter().map(|record| String::from(record.target())).collect();
+  targets.sort();
+  targets
+}
+
+fn observed_at(ticks: u64) -> TimerInstant {
+  TimerInstant::from_ticks(ticks, Duration::from_secs(1))
+}
+
+#[test]
+fn srv_records_from_dns_join_the_cluster_topology() {
+  let (nameserver, stand_in) = spawn_dns_stand_in(1);
+  let resolver = UdpDnsResolver::new(nameserver).with_timeout(Duration::from_secs(2));
+  let backend = DnsDiscoveryBackend::new(DnsDiscoveryConfig::new(SRV_NAME), resolver);
+  let mut adapter = GenericDiscoveryAdapter::new(backend);
+  let mut mapper = DiscoveryTopologyMapper::new(ArcShared::new(EmptyBlockList));
+
+  let result = adapter.discover(observed_at(1));
+  let update = mapper.apply(&result).expect("dns discovery should publish topology");
+
+  assert_eq!(update.joined, vec![String::from("node-a.cluster.local:7331"), String::from("node-b.cluster.local:7331")]);
+  assert_eq!(result.authorities()[0].source_identity(), "dns:_fraktor._tcp.cluster.local");
+
+  // TTL 内の再ポーリングはキャッシュから返るため、スタンドインへの問い合わせは増えない
+  let cached = adapter.discover(observed_at(2));
+  assert_eq!(cached.authorities().len(), 2);
+  assert!(mapper.apply(&cached).is_none());
+  stand_in.join().expect("dns stand-in");
+}
+
+#[test]
+fn address_records_from_dns_join_the_cluster_topology() {
+  // A と AAAA の 2 回問い合わせる
+  let (nameserver, stand_in) = spawn_dns_stand_in(2);
+  let config = DnsDiscoveryConfig::new(ADDRESS_NAME).with_lookup_mode(DnsLookupMode::Address { port: 8080 });
+  let backend = DnsDiscoveryBackend::new(config, UdpDnsResolver::new(nameserver));
+  let mut adapter = GenericDiscoveryAdapter::new(backend);
+  let mut mapper = DiscoveryTopologyMapper::new(ArcShared::new(EmptyBlockList));
+
+  let update = mapper.apply(&adapter.discover(observed_at(1))).expect("dns discovery should publish topology");
+
+  assert_eq!(update.joined, vec![String::from("10.0.1.12:8080"), String::from("10.0.1.13:8080")]);
+  stand_in.join().expect("dns stand-in");
+}
+
+#[test]
+fn unanswered_queries_are_reported_as_discovery_failures() {
+  let silent = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind silent nameserver");
+  let resolver =
+    UdpDnsResolver::new(silent.local_addr().expect("local address")).with_timeout(Duration::from_millis(100));
+  let backend = DnsDiscoveryBackend::new(DnsDiscoveryConfig::new(SRV_NAME), resolver);
+  let mut adapter = GenericDiscoveryAdapter::new(backend);
+
+  let result = adapter.discover(observed_at(1));
+
+  assert!(result.authorities().is_empty());
+  assert!(result.is_failed());
+}
+
+#[test]
+fn queries_advertise_a_large_udp_payload_through_edns() {
+  let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind dns stand-in");
+  socket.set_read_timeout(Some(Duration::from_secs(5))).expect("read timeout");
+  let nameserver = socket.local_addr().expect("local address");
+  let stand_in = thread::spawn(move || {
+    let mut buffer = [0_u8; 512];
+    let (len, peer) = socket.recv_from(&mut buffer).expect("dns query");
+    socket.send_to(&respond(&buffer[..len]), peer).expect("dns response");
+    Vec::from(&buffer[..len])
+  });
+
+  srv_targets(&mut UdpDnsResolver::new(nameserver));
+
+  let request = stand_in.join().expect("dns stand-in");
+  let (_, _, question_end) = read_question(&request);
+  // 追加セクションに 4096 バイトを広告する OPT レコードが 1 件ある
+  assert_eq!(&request[10..12], &[0, 1]);
+  assert_eq!(&request[question_end..question_end + 5], &[0, 0, 41, 0x10, 0x00]);
+}
+
+#[test]
+fn truncated_udp_responses_are_retried_over_tcp() {
+  let (socket, listener) = bind_udp_and_tcp();
+  let nameserver = socket.local_addr().expect("local address");
+  let udp = thread::spawn(move || {
+    let mut buffer = [0_u8; 512];
+    let (len, peer) = socket.recv_from(&mut buffer).expect("dns query");
+    // TC ビットを立て、回答を載せずに返す
+    socket.send_to(&respond_with(&buffer[..len], 0x8380, Vec::new()), peer).expect("truncated dns response");
+  });
+  let tcp = thread::spawn(move || {
+    let (mut stream, _) = listener.accept().expect("dns tcp connection");
+    let mut len_bytes = [0_u8; 2];
+    stream.read_exact(&mut len_bytes).expect("dns query length");
+    let mut request = vec![0_u8; usize::from(u16::from_be_bytes(len_bytes))];
+    stream.read_exact(&mut request).expect("dns query");
+    let response = respond(&request);
+    stream.write_all(&(response.len() as u16).to_be_bytes()).expect("dns response length");
+    stream.write_all(&response).expect("dns response");
+  });
+
+  let targets = srv_targets(&mut UdpDnsResolver::new(nameserver));
+
+  assert_eq!(targets, vec![String::from("node-a.cluster.local"), String::from("node-b.cluster.local")]);
+  udp.join().expect("udp stand-in");
+  tcp.join().expect("tcp stand-in");
+}
+
+#[test]
+fn responses_for_another_question_are_ignored() {
+  let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind dns stand-in");
+  socket.set_read_timeout(Some(Duration::from_secs(5))).expect("read timeout");
+  let nameserver = socket.local_addr().expect("local address");
+  let stand_in = thread::spawn(move || {
+    let mut buffer = [0_u8; 512];
+    let (len, peer) = socket.recv_from(&mut buffer).expect("dns query");
+    let request = &buffer[..len];
+    // 同じ ID でも別の名前への応答は偽装とみなされる
+    let mut forged_question = Vec::from(&request[..12]);
+    encode_name(&mut forged_question, "_fraktor._tcp.attacker.example");
+    forged_question.extend_from_slice(&[0, 33, 0, 1]);
+    let mut forged_srv = Vec::from([0, 10, 0, 5, 0x1C, 0xA3]);
+    encode_name(&mut forged_srv, "attacker.example");
+    let forged = respond_with(&forged_question, 0x8180, vec![(TYPE_SRV, forged_srv)]);
+    socket.send_to(&forged, peer).expect("forged dns response");
+    socket.send_to(&respond(request), peer).expect("dns response");
+  });
+
+  let targets = srv_targets(&mut UdpDnsResolver::new(nameserver));
+
+  assert_eq!(targets, vec![String::from("node-a.cluster.local"), String::from("node-b.cluster.local")]);
+  stand_in.join().expect("dns stand-in");
+}
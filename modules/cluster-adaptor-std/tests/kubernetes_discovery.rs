@@ -0,0 +1,192 @@
+use core::time::Duration;
+use std::{
+  io::{BufRead, BufReader, Read, Write},
+  net::{Ipv4Addr, TcpListener, TcpStream},
+  sync::{Arc, Mutex},
+  thread,
+};
+
+use fraktor_cluster_adaptor_std_rs::cluster_provider::{
+  GenericDiscoveryAdapter, KubernetesDiscoveryConfig, KubernetesLeaseConfig, KubernetesLeaseMajorityBackend,
+  KubernetesPodDiscoveryBackend, StdLeaseMajorityBackend, TcpKubernetesHttpClient,
+};
+use fraktor_cluster_core_kernel_rs::{
+  cluster_provider::DiscoveryTopologyMapper,
+  downing_provider::{DowningDecisionContext, LeaseAcquisitionOutcome},
+  topology::BlockListProvider,
+};
+use fraktor_utils_core_rs::{sync::ArcShared, time::TimerInstant};
+use serde_json::{Value, json};
+
+const PODS_PATH: &str = "/api/v1/namespaces/fraktor/pods?labelSelector=app%3Dseed";
+const LEASES_PATH: &str = "/apis/coordination.k8s.io/v1/namespaces/fraktor/leases";
+const LEASE_PATH: &str = "/apis/coordination.k8s.io/v1/namespaces/fraktor/leases/fraktor-sbr";
+
+struct EmptyBlockList;
+
+impl BlockListProvider for EmptyBlockList {
+  fn blocked_members(&self) -> Vec<String> {
+    Vec::new()
+  }
+}
+
+/// In-process stand-in for the Kubernetes API server covering pods and a single Lease.
+#[derive(Clone, Default)]
+struct FakeApiServer {
+  lease: Arc<Mutex<Option<Value>>>,
+}
+
+impl FakeApiServer {
+  fn spawn(&self) -> String {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind fake api server");
+    let authority = listener.local_addr().expect("local address").to_string();
+    let server = self.clone();
+    thread::spawn(move || {
+      for stream in listener.incoming() {
+        server.serve(stream.expect("accept"));
+      }
+    });
+    authority
+  }
+
+  fn serve(&self, stream: TcpStream) {
+    let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).expect("request line");
+    let mut content_length = 0;
+    loop {
+      let mut header = String::new();
+      reader.read_line(&mut header).expect("header");
+      if header == "\r\n" {
+        break;
+      }
+      if let Some(length) = header.to_ascii_lowercase().strip_prefix("content-length:") {
+        content_length = length.trim().parse().expect("content length");
+      }
+    }
+    let mut body = vec![0_u8; content_length];
+    reader.read_exact(&mut body).expect("body");
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().expect("method");
+    let path = parts.next().expect("path");
+    let body = String::from_utf8(body).expect("utf8 body");
+
+    let (status, response) = self.route(method, path, &body);
+    let mut stream = stream;
+    write!(
+      stream,
+      "HTTP/1.1 {status} Fake\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+      response.len()
+    )
+    .expect("write response");
+  }
+
+  fn route(&self, method: &str, path: &str, body: &str) -> (u16, String) {
+    let mut lease = self.lease.lock().expect("lease lock");
+    match (method, path) {
+      | ("GET", PODS_PATH) => (200, pod_list().to_string()),
+      | ("GET", LEASE_PATH) => match lease.as_ref() {
+        | Some(current) => (200, current.to_string()),
+        | None => (404, json!({"kind": "Status", "code": 404}).to_string()),
+      },
+      | ("POST", LEASES_PATH) if lease.is_some() => (409, String::from("{}")),
+      | ("POST", LEASES_PATH) => {
+        let mut created: Value = serde_json::from_str(body).expect("lease body");
+        created["metadata"]["resourceVersion"] = json!("1");
+        *lease = Some(created.clone());
+        (201, created.to_string())
+      },
+      | ("PUT", LEASE_PATH) => {
+        let mut updated: Value = serde_json::from_str(body).expect("lease body");
+        let current_version = lease.as_ref().map(|current| current["metadata"]["resourceVersion"].clone());
+        if current_version != Some(updated["metadata"]["resourceVersion"].clone()) {
+          return (409, String::from("{}"));
+        }
+        let next = updated["metadata"]["resourceVersion"].as_str().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0) + 1;
+        updated["metadata"]["resourceVersion"] = json!(next.to_string());
+        *lease = Some(updated.clone());
+        (200, updated.to_string())
+      },
+      | _ => (404, String::from("{}")),
+    }
+  }
+
+  fn holder(&self) -> Option<String> {
+    let lease = self.lease.lock().expect("lease lock");
+    lease.as_ref().and_then(|lease| lease["spec"]["holderIdentity"].as_str().map(String::from))
+  }
+}
+
+fn pod(name: &str, ip: &str, ready: bool) -> Value {
+  json!({
+    "metadata": { "name": name, "labels": { "app": "seed" } },
+    "spec": { "containers": [{ "name": "app", "ports": [{ "name": "remoting", "containerPort": 25520 }] }] },
+    "status": {
+      "phase": "Running",
+      "podIP": ip,
+      "conditions": [{ "type": "Ready", "status": if ready { "True" } else { "False" } }],
+    },
+  })
+}
+
+fn pod_list() -> Value {
+  json!({
+    "kind": "PodList",
+    "items": [pod("seed-1", "10.1.0.11", true), pod("seed-0", "10.1.0.10", true), pod("seed-2", "10.1.0.12", false)],
+  })
+}
+
+fn client(authority: &str) -> TcpKubernetesHttpClient {
+  TcpKubernetesHttpClient::new(authority).with_bearer_token("test-token").with_timeout(Duration::from_secs(2))
+}
+
+fn context() -> DowningDecisionContext {
+  DowningDecisionContext::from_explicit_down("10.1.0.12:25520", TimerInstant::from_ticks(1, Duration::from_secs(1)))
+}
+
+#[test]
+fn ready_pods_from_the_api_server_join_the_cluster_topology() {
+  let authority = FakeApiServer::default().spawn();
+  let config = KubernetesDiscoveryConfig::new("fraktor", "app=seed").with_port_name("remoting");
+  let mut adapter = GenericDiscoveryAdapter::new(KubernetesPodDiscoveryBackend::new(config, client(&authority)));
+  let mut mapper = DiscoveryTopologyMapper::new(ArcShared::new(EmptyBlockList));
+
+  let result = adapter.discover(TimerInstant::from_ticks(1, Duration::from_secs(1)));
+  let update = mapper.apply(&result).expect("kubernetes discovery should publish topology");
+
+  assert_eq!(update.joined, vec![String::from("10.1.0.10:25520"), String::from("10.1.0.11:25520")]);
+  assert_eq!(result.authorities()[0].source_identity(), "kubernetes:fraktor/app=seed");
+}
+
+#[test]
+fn only_one_member_holds_the_lease_until_it_is_released() {
+  let server = FakeApiServer::default();
+  let authority = server.spawn();
+  let lease_config = |holder: &str| KubernetesLeaseConfig::new("fraktor", "fraktor-sbr", holder);
+  let mut node_a = KubernetesLeaseMajorityBackend::new(lease_config("node-a"), client(&authority));
+  let mut node_b = KubernetesLeaseMajorityBackend::new(lease_config("node-b"), client(&authority));
+
+  assert_eq!(node_a.acquire(&context()), LeaseAcquisitionOutcome::Acquired);
+  assert_eq!(node_b.acquire(&context()), LeaseAcquisitionOutcome::Denied);
+  assert_eq!(node_a.acquire(&context()), LeaseAcquisitionOutcome::Acquired);
+  assert_eq!(server.holder().as_deref(), Some("node-a"));
+
+  node_a.close();
+  assert_eq!(server.holder(), None);
+
+  assert_eq!(node_b.acquire(&context()), LeaseAcquisitionOutcome::Acquired);
+  assert_eq!(server.holder().as_deref(), Some("node-b"));
+}
+
+#[test]
+fn unreachable_api_server_makes_the_lease_unavailable() {
+  let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind");
+  let authority = listener.local_addr().expect("local address").to_string();
+  drop(listener);
+  let mut backend = KubernetesLeaseMajorityBackend::new(
+    KubernetesLeaseConfig::new("fraktor", "fraktor-sbr", "node-a"),
+    client(&authority),
+  );
+
+  assert_eq!(backend.acquire(&context()), LeaseAcquisitionOutcome::Unavailable);
+}
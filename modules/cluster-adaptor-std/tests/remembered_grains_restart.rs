@@ -0,0 +1,132 @@
+//! Remembered grains coming back after a restart of the member's actor system.
+
+use std::{
+  fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext, error::ActorError, extension::ExtensionInstallers, messaging::AnyMessageView, props::Props,
+    scheduler::SchedulerConfig, setup::ActorSystemConfig,
+  },
+  system::ActorSystem,
+};
+use fraktor_cluster_adaptor_std_rs::activation::{LocalActivationStorage, RememberedGrainsStore};
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivatedKind, ActivationStorage, ClusterIdentity, PartitionIdentityLookup},
+  cluster_provider::NoopClusterProvider,
+  extension::{ClusterApi, ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+  topology::{ClusterTopology, TopologyUpdate},
+};
+use fraktor_utils_core_rs::{sync::ArcShared, time::TimerInstant};
+use tokio::runtime::Handle;
+
+const AUTHORITY: &str = "node-a:2552";
+const KIND: &str = "counter";
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+fn start_member() -> (ActorSystem, ArcShared<ClusterExtension>) {
+  let config = ClusterExtensionConfig::new().with_advertised_address(AUTHORITY);
+  let installer =
+    ClusterExtensionInstaller::new(config, |_event_stream, _block_list, _address| Box::new(NoopClusterProvider::new()))
+      .with_identity_lookup_factory(|| Box::new(PartitionIdentityLookup::with_defaults()));
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(ExtensionInstallers::default().with_extension_installer(installer));
+  let system = ActorSystem::create_from_props(&Props::from_fn(|| GuardianActor), config).expect("system should build");
+  let extension = system.extended().extension_by_type::<ClusterExtension>().expect("cluster extension is installed");
+  extension.setup_member_kinds(vec![ActivatedKind::new(KIND).with_remember_entities(true)]).expect("register kind");
+  extension.start_member().expect("start member");
+  let members = vec![String::from(AUTHORITY)];
+  extension.on_topology(&TopologyUpdate::new(
+    ClusterTopology::new(1, members.clone(), Vec::new(), Vec::new()),
+    members.clone(),
+    members,
+    Vec::new(),
+    Vec::new(),
+    Vec::new(),
+    TimerInstant::from_ticks(1, Duration::from_secs(1)),
+  ));
+  (system, extension)
+}
+
+fn local_entity_ids(system: &ActorSystem) -> Vec<String> {
+  let state = ClusterApi::try_from_system(system).expect("cluster api").shard_region_state(KIND);
+  state.shards.into_iter().flat_map(|shard| shard.entity_ids).collect()
+}
+
+async fn wait_until(mut condition: impl FnMut() -> bool, description: &str) {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while !condition() {
+    assert!(Instant::now() < deadline, "timed out waiting until {description}");
+    tokio::time::sleep(Duration::from_millis(5)).await;
+  }
+}
+
+async fn stored_ids(directory: &Path) -> Vec<String> {
+  let mut storage = LocalActivationStorage::open(directory.to_path_buf()).expect("open storage");
+  let entries = storage.load_all().await.expect("load stored grains");
+  entries.into_iter().map(|(key, _)| String::from(key.value())).collect()
+}
+
+fn unique_storage_dir(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  std::env::temp_dir().join(format!("fraktor-remembered-grains-{name}-{}-{timestamp}", std::process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("remembered grains test directory should be removable: {error}"),
+  }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn remembered_grain_is_reactivated_after_the_member_restarts() {
+  let directory = unique_storage_dir("restart");
+  remove_dir_if_exists(&directory);
+
+  let (system, extension) = start_member();
+  let store = RememberedGrainsStore::start_for_mode(&system, Some(directory.clone()), None, &Handle::current())
+    .await
+    .expect("start remembered grains store");
+  let identity = ClusterIdentity::new(KIND, "a").expect("identity");
+  ClusterApi::try_from_system(&system).expect("cluster api").placement_of(&identity).expect("activate grain");
+  let mut stored = Vec::new();
+  for _ in 0..1_000 {
+    stored = stored_ids(&directory).await;
+    if !stored.is_empty() {
+      break;
+    }
+    tokio::time::sleep(Duration::from_millis(5)).await;
+  }
+  assert_eq!(stored, vec![String::from("counter/a")]);
+  drop(store);
+  extension.shutdown(true).expect("shutdown cluster");
+  system.terminate().expect("terminate first system");
+
+  let (system, _extension) = start_member();
+  assert!(local_entity_ids(&system).is_empty());
+  let _store = RememberedGrainsStore::start_for_mode(&system, Some(directory.clone()), None, &Handle::current())
+    .await
+    .expect("restart remembered grains store");
+  wait_until(|| local_entity_ids(&system) == vec![String::from("a")], "the remembered grain is reactivated").await;
+
+  system.terminate().expect("terminate second system");
+  remove_dir_if_exists(&directory);
+}
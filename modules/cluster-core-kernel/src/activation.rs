@@ -14,10 +14,17 @@ mod identity_lookup;
 mod identity_lookup_shared;
 mod identity_setup_error;
 mod identity_table;
+mod in_memory_activation_storage;
+mod least_frequently_used_policy;
+mod least_recently_used_policy;
 mod lookup_error;
+mod most_recently_used_policy;
 mod noop_identity_lookup;
 mod partition_identity_lookup;
 mod partition_identity_lookup_config;
+mod passivation_policy;
+mod passivation_reason;
+mod passivation_strategy;
 mod pid_cache;
 mod pid_cache_event;
 mod placement_command;
@@ -37,9 +44,11 @@ mod placement_lock_error;
 mod placement_request_id;
 mod placement_resolution;
 mod placement_snapshot;
+mod recency_list;
 mod rendezvous_hasher;
 mod resolve_error;
 mod resolve_result;
+mod segmented_least_recently_used_policy;
 mod virtual_actor_event;
 mod virtual_actor_registry;
 
@@ -57,10 +66,13 @@ pub use identity_lookup::IdentityLookup;
 pub use identity_lookup_shared::IdentityLookupShared;
 pub use identity_setup_error::IdentitySetupError;
 pub use identity_table::IdentityTable;
+pub use in_memory_activation_storage::InMemoryActivationStorage;
 pub use lookup_error::LookupError;
 pub use noop_identity_lookup::NoopIdentityLookup;
 pub use partition_identity_lookup::PartitionIdentityLookup;
 pub use partition_identity_lookup_config::PartitionIdentityLookupConfig;
+pub use passivation_reason::PassivationReason;
+pub use passivation_strategy::PassivationStrategy;
 pub use pid_cache::PidCache;
 pub use pid_cache_event::PidCacheEvent;
 pub use placement_command::PlacementCommand;
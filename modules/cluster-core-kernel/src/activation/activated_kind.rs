@@ -2,17 +2,40 @@
 
 use alloc::string::String;
 
+use super::PassivationStrategy;
+
 /// Describes a cluster kind that can be activated.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ActivatedKind {
-  name: String,
+  name:                 String,
+  remember_entities:    bool,
+  passivation_strategy: Option<PassivationStrategy>,
 }
 
 impl ActivatedKind {
   /// Creates a new activated kind with the provided name.
   #[must_use]
   pub fn new(name: impl Into<String>) -> Self {
-    Self { name: name.into() }
+    Self { name: name.into(), remember_entities: false, passivation_strategy: None }
+  }
+
+  /// Enables or disables remembered entities for this kind.
+  ///
+  /// Grains of a remembered kind are reactivated on their new owner after a rebalance or
+  /// restart instead of waiting for the next message.
+  #[must_use]
+  pub const fn with_remember_entities(mut self, enabled: bool) -> Self {
+    self.remember_entities = enabled;
+    self
+  }
+
+  /// Sets how grains of this kind are passivated on each member.
+  ///
+  /// Without a strategy, grains are passivated after the cluster-wide idle threshold.
+  #[must_use]
+  pub const fn with_passivation_strategy(mut self, strategy: PassivationStrategy) -> Self {
+    self.passivation_strategy = Some(strategy);
+    self
   }
 
   /// Returns the kind name.
@@ -21,4 +44,16 @@ impl ActivatedKind {
   pub fn name(&self) -> &str {
     &self.name
   }
+
+  /// Returns `true` when grains of this kind are remembered.
+  #[must_use]
+  pub const fn remember_entities(&self) -> bool {
+    self.remember_entities
+  }
+
+  /// Returns the passivation strategy of this kind, if one is set.
+  #[must_use]
+  pub const fn passivation_strategy(&self) -> Option<PassivationStrategy> {
+    self.passivation_strategy
+  }
 }
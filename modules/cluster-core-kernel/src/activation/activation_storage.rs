@@ -1,5 +1,6 @@
 //! Activation storage trait used by placement coordination.
 
+use alloc::vec::Vec;
 use core::future::Future;
 
 use super::{ActivationEntry, ActivationStorageError};
@@ -22,6 +23,11 @@ pub trait ActivationStorage {
   where
     Self: 'a;
 
+  /// Future returned by [`ActivationStorage::load_all`].
+  type LoadAllFuture<'a>: Future<Output = Result<Vec<(GrainKey, ActivationEntry)>, ActivationStorageError>> + Send + 'a
+  where
+    Self: 'a;
+
   /// Loads activation entry for the given key.
   fn load<'a>(&'a mut self, key: GrainKey) -> Self::LoadFuture<'a>;
 
@@ -30,4 +36,9 @@ pub trait ActivationStorage {
 
   /// Removes activation entry for the given key.
   fn remove<'a>(&'a mut self, key: GrainKey) -> Self::RemoveFuture<'a>;
+
+  /// Loads every stored activation entry, ordered by key.
+  ///
+  /// Used to restore remembered grains after a restart or rebalance.
+  fn load_all<'a>(&'a mut self) -> Self::LoadAllFuture<'a>;
 }
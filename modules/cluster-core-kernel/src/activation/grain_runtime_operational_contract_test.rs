@@ -1,10 +1,10 @@
-use alloc::{format, string::ToString};
+use alloc::{format, string::ToString, vec};
 
 use crate::{
   activation::{
-    ActivationRecord, IdentityLookup, LookupError, PartitionIdentityLookup, PidCacheEvent, PlacementCommand,
-    PlacementCommandResult, PlacementCoordinatorOutcome, PlacementEvent, PlacementLease, PlacementLocality,
-    PlacementRequestId, PlacementResolution, RendezvousHasher,
+    ActivatedKind, ActivationRecord, IdentityLookup, LookupError, PartitionIdentityLookup, PidCacheEvent,
+    PlacementCommand, PlacementCommandResult, PlacementCoordinatorOutcome, PlacementEvent, PlacementLease,
+    PlacementLocality, PlacementRequestId, PlacementResolution, RendezvousHasher,
   },
   grain::GrainKey,
 };
@@ -489,3 +489,30 @@ fn rolling_update_prevents_stale_authority_reuse_without_rebalance_guarantees()
   // この contract は stale authority invalidation と re-resolution に意図的に限定する。
   // rebalance、remembered entity recovery、request draining は後続 change で扱う。
 }
+
+#[test]
+fn remembered_grain_is_activated_from_drained_commands_without_an_incoming_message() {
+  let mut lookup = PartitionIdentityLookup::with_defaults();
+  lookup.setup_member(&[ActivatedKind::new("user").with_remember_entities(true)]).expect("setup member");
+  lookup.set_local_authority("node1:8080".to_string());
+  lookup.set_distributed_activation(true);
+  let key = grain_key("user/remembered");
+
+  lookup.restore_remembered(vec![key.clone()]);
+  lookup.update_topology(vec!["node1:8080".to_string()]);
+  lookup.reactivate_remembered_at(5, 5_000_000_000);
+
+  // resolve を一度も呼ばず、drain したコマンドだけで活性化を完了できる。
+  let commands = lookup.drain_commands();
+  let PlacementCommand::TryAcquire { request_id, key: command_key, owner, .. } =
+    only_command(&commands, "reactivation command")
+  else {
+    panic!("expected TryAcquire for remembered grain, got {commands:?}");
+  };
+  assert_eq!(command_key, &key);
+  let resolution = complete_pending_activation(&mut lookup, *request_id, &key, owner, "node1:8080::user/remembered", 6);
+
+  assert_eq!(resolution.decision.key, key);
+  assert_eq!(resolution.locality, PlacementLocality::Local);
+  assert!(lookup.drain_commands().is_empty());
+}
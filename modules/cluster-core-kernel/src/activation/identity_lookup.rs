@@ -4,8 +4,12 @@ use alloc::{string::String, vec::Vec};
 
 use super::{identity_setup_error::IdentitySetupError, lookup_error::LookupError, pid_cache_event::PidCacheEvent};
 use crate::{
-  activation::{ActivatedKind, PlacementCoordinatorState, PlacementEvent, PlacementResolution},
+  activation::{
+    ActivatedKind, PlacementCommand, PlacementCommandResult, PlacementCoordinatorError, PlacementCoordinatorState,
+    PlacementEvent, PlacementResolution, PlacementSnapshot,
+  },
   grain::GrainKey,
+  sharding::{ShardAllocationTable, ShardRegionRequests, ShardRegionState, ShardRegionStats},
 };
 
 /// Provides identity resolution setup and lookup operations.
@@ -29,6 +33,13 @@ pub trait IdentityLookup: Send + Sync {
   /// Returns an error if identity lookup setup fails for client mode.
   fn setup_client(&mut self, kinds: &[ActivatedKind]) -> Result<(), IdentitySetupError>;
 
+  /// Sets the authority of the local member.
+  ///
+  /// Implementations that do not tell members apart ignore the authority.
+  fn set_local_authority(&mut self, authority: &str) {
+    let _ = authority;
+  }
+
   /// Resolves placement for a grain key.
   ///
   /// Returns `Ok(resolution)` when placement can be resolved, or
@@ -67,6 +78,16 @@ pub trait IdentityLookup: Send + Sync {
     self.resolve(key, now_secs)
   }
 
+  /// Returns the authority owning `key` without activating it.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when no owner can be selected yet.
+  fn owner_of(&mut self, key: &GrainKey) -> Result<String, LookupError> {
+    let _ = key;
+    Err(LookupError::NotReady)
+  }
+
   /// Removes a PID from the registry and cache.
   ///
   /// # Arguments
@@ -88,6 +109,72 @@ pub trait IdentityLookup: Send + Sync {
     let _ = authorities;
   }
 
+  /// Restores remembered grain keys loaded from activation storage.
+  ///
+  /// Implementations without remembered-entities support ignore the keys.
+  fn restore_remembered(&mut self, keys: Vec<GrainKey>) {
+    let _ = keys;
+  }
+
+  /// Reactivates remembered grains that became owned by the local member.
+  ///
+  /// Called after topology changes and restoration so remembered grains come back
+  /// without waiting for a message. Placement commands required by the reactivation are
+  /// queued for [`Self::drain_commands`].
+  fn reactivate_remembered_at(&mut self, now_secs: u64, idle_now_nanos: u64) {
+    let _ = (now_secs, idle_now_nanos);
+  }
+
+  /// Runs one shard rebalance round.
+  ///
+  /// Implementations without shard allocation ignore the call.
+  fn rebalance_at(&mut self, now_secs: u64, idle_now_nanos: u64) {
+    let _ = (now_secs, idle_now_nanos);
+  }
+
+  /// Returns the kinds placed by shard.
+  fn sharded_kinds(&self) -> Vec<String> {
+    Vec::new()
+  }
+
+  /// Returns the shard allocations of `kind` when the local member coordinates shards.
+  ///
+  /// The table is replicated to the other members, which apply it with
+  /// [`Self::apply_shard_allocations`].
+  fn shard_allocations(&self, kind: &str) -> Option<ShardAllocationTable> {
+    let _ = kind;
+    None
+  }
+
+  /// Returns the requests of the local member to the member coordinating the shards of `kind`.
+  fn shard_region_requests(&self, kind: &str) -> Option<ShardRegionRequests> {
+    let _ = kind;
+    None
+  }
+
+  /// Applies shard allocations of `kind` replicated from the coordinating member.
+  ///
+  /// Placement commands required by the resulting reactivations are queued for
+  /// [`Self::drain_commands`].
+  fn apply_shard_allocations(&mut self, kind: &str, table: ShardAllocationTable, now_secs: u64, idle_now_nanos: u64) {
+    let _ = (kind, table, now_secs, idle_now_nanos);
+  }
+
+  /// Handles the requests of the member at `authority` for the shards of `kind`.
+  ///
+  /// Only the coordinating member acts on requests; placement commands required by the
+  /// resulting reactivations are queued for [`Self::drain_commands`].
+  fn handle_shard_region_requests(
+    &mut self,
+    kind: &str,
+    authority: &str,
+    requests: &ShardRegionRequests,
+    now_secs: u64,
+    idle_now_nanos: u64,
+  ) {
+    let _ = (kind, authority, requests, now_secs, idle_now_nanos);
+  }
+
   /// Handles a member leaving the cluster.
   ///
   /// Invalidates all activations and cache entries for the given authority.
@@ -122,6 +209,24 @@ pub trait IdentityLookup: Send + Sync {
     Vec::new()
   }
 
+  /// Drains placement commands queued for the caller to execute.
+  fn drain_commands(&mut self) -> Vec<PlacementCommand> {
+    Vec::new()
+  }
+
+  /// Applies the result of a command returned by [`Self::drain_commands`].
+  ///
+  /// Follow-up commands are queued for [`Self::drain_commands`].
+  ///
+  /// # Errors
+  ///
+  /// The default implementation has no placement coordinator and rejects every result with
+  /// [`PlacementCoordinatorError::InvalidState`].
+  fn apply_command_result(&mut self, result: PlacementCommandResult) -> Result<(), PlacementCoordinatorError> {
+    let _ = result;
+    Err(PlacementCoordinatorError::InvalidState { state: self.placement_state() })
+  }
+
   /// Drains pending PID cache events.
   fn drain_cache_events(&mut self) -> Vec<PidCacheEvent> {
     Vec::new()
@@ -135,4 +240,23 @@ pub trait IdentityLookup: Send + Sync {
   fn placement_state(&self) -> PlacementCoordinatorState {
     PlacementCoordinatorState::NotReady
   }
+
+  /// Returns a snapshot of the placement coordinator, if the lookup is backed by one.
+  fn placement_snapshot(&self) -> Option<PlacementSnapshot> {
+    None
+  }
+
+  /// Returns the shards of `kind` with grains active on the local member.
+  ///
+  /// Lookups that do not track activations report no shards.
+  fn shard_region_state(&self, kind: &str) -> ShardRegionState {
+    ShardRegionState::empty(kind)
+  }
+
+  /// Returns the number of grains active on the local member per kind.
+  ///
+  /// Lookups that do not track activations report no grains.
+  fn shard_region_stats(&self) -> ShardRegionStats {
+    ShardRegionStats::default()
+  }
 }
@@ -0,0 +1,65 @@
+//! In-memory activation storage.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::future::{Ready, ready};
+
+use super::{ActivationEntry, ActivationStorage, ActivationStorageError};
+use crate::grain::GrainKey;
+
+#[cfg(test)]
+#[path = "in_memory_activation_storage_test.rs"]
+mod tests;
+
+/// Activation storage that keeps entries in process memory.
+///
+/// Entries are lost when the process stops, so this storage only remembers grains across
+/// rebalances within a running member.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryActivationStorage {
+  entries: BTreeMap<GrainKey, ActivationEntry>,
+}
+
+impl InMemoryActivationStorage {
+  /// Creates an empty storage.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self { entries: BTreeMap::new() }
+  }
+
+  /// Returns the number of stored entries.
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  /// Returns `true` when no entry is stored.
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+}
+
+impl ActivationStorage for InMemoryActivationStorage {
+  type LoadAllFuture<'a> = Ready<Result<Vec<(GrainKey, ActivationEntry)>, ActivationStorageError>>;
+  type LoadFuture<'a> = Ready<Result<Option<ActivationEntry>, ActivationStorageError>>;
+  type RemoveFuture<'a> = Ready<Result<(), ActivationStorageError>>;
+  type StoreFuture<'a> = Ready<Result<(), ActivationStorageError>>;
+
+  fn load<'a>(&'a mut self, key: GrainKey) -> Self::LoadFuture<'a> {
+    ready(Ok(self.entries.get(&key).cloned()))
+  }
+
+  fn store<'a>(&'a mut self, key: GrainKey, entry: ActivationEntry) -> Self::StoreFuture<'a> {
+    self.entries.insert(key, entry);
+    ready(Ok(()))
+  }
+
+  fn remove<'a>(&'a mut self, key: GrainKey) -> Self::RemoveFuture<'a> {
+    self.entries.remove(&key);
+    ready(Ok(()))
+  }
+
+  fn load_all<'a>(&'a mut self) -> Self::LoadAllFuture<'a> {
+    ready(Ok(self.entries.iter().map(|(key, entry)| (key.clone(), entry.clone())).collect()))
+  }
+}
@@ -0,0 +1,44 @@
+use alloc::string::String;
+
+use super::InMemoryActivationStorage;
+use crate::{
+  activation::{ActivationEntry, ActivationRecord, ActivationStorage},
+  grain::GrainKey,
+};
+
+fn entry(owner: &str, pid: &str) -> ActivationEntry {
+  ActivationEntry {
+    owner:       String::from(owner),
+    record:      ActivationRecord::new(String::from(pid), None, 0),
+    observed_at: 1,
+  }
+}
+
+#[test]
+fn stored_entries_are_loaded_by_key() {
+  let mut storage = InMemoryActivationStorage::new();
+  let key = GrainKey::new(String::from("user/a"));
+
+  storage.store(key.clone(), entry("node1:8080", "node1:8080::user/a")).into_inner().expect("store");
+
+  let loaded = storage.load(key).into_inner().expect("load");
+  assert_eq!(loaded, Some(entry("node1:8080", "node1:8080::user/a")));
+  assert!(storage.load(GrainKey::new(String::from("user/b"))).into_inner().expect("load").is_none());
+}
+
+#[test]
+fn load_all_returns_entries_ordered_by_key_and_skips_removed_ones() {
+  let mut storage = InMemoryActivationStorage::new();
+  let a = GrainKey::new(String::from("user/a"));
+  let b = GrainKey::new(String::from("user/b"));
+  let c = GrainKey::new(String::from("user/c"));
+  storage.store(c.clone(), entry("node1:8080", "c")).into_inner().expect("store c");
+  storage.store(a.clone(), entry("node1:8080", "a")).into_inner().expect("store a");
+  storage.store(b.clone(), entry("node2:8080", "b")).into_inner().expect("store b");
+
+  storage.remove(b).into_inner().expect("remove");
+
+  let all = storage.load_all().into_inner().expect("load all");
+  assert_eq!(all, [(a, entry("node1:8080", "a")), (c, entry("node1:8080", "c"))]);
+  assert_eq!(storage.len(), 2);
+}
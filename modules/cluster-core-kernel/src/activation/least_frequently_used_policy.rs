@@ -0,0 +1,67 @@
+//! Least-frequently-used passivation policy.
+
+#[cfg(test)]
+#[path = "least_frequently_used_policy_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  vec::Vec,
+};
+
+use super::passivation_policy::PassivationPolicy;
+use crate::grain::GrainKey;
+
+/// Passivates the least frequently used activations over the limit.
+///
+/// Among activations used equally often, the least recently used one is passivated first.
+pub(crate) struct LeastFrequentlyUsedPolicy {
+  limit:   usize,
+  entries: BTreeMap<GrainKey, (u64, u64)>,
+  order:   BTreeSet<(u64, u64, GrainKey)>,
+  next:    u64,
+}
+
+impl LeastFrequentlyUsedPolicy {
+  pub(crate) const fn new(limit: usize) -> Self {
+    Self { limit, entries: BTreeMap::new(), order: BTreeSet::new(), next: 0 }
+  }
+
+  fn record(&mut self, key: &GrainKey, uses: u64) {
+    if let Some((previous_uses, tick)) = self.entries.remove(key) {
+      self.order.remove(&(previous_uses, tick, key.clone()));
+    }
+    self.entries.insert(key.clone(), (uses, self.next));
+    self.order.insert((uses, self.next, key.clone()));
+    self.next += 1;
+  }
+}
+
+impl PassivationPolicy for LeastFrequentlyUsedPolicy {
+  fn activated(&mut self, key: &GrainKey) -> Vec<GrainKey> {
+    self.record(key, 1);
+    let mut evicted = Vec::new();
+    while self.entries.len() > self.limit {
+      let Some(victim) =
+        self.order.iter().map(|(_, _, candidate)| candidate).find(|candidate| *candidate != key).cloned()
+      else {
+        break;
+      };
+      self.removed(&victim);
+      evicted.push(victim);
+    }
+    evicted
+  }
+
+  fn accessed(&mut self, key: &GrainKey) {
+    if let Some((uses, _)) = self.entries.get(key).copied() {
+      self.record(key, uses.saturating_add(1));
+    }
+  }
+
+  fn removed(&mut self, key: &GrainKey) {
+    if let Some((uses, tick)) = self.entries.remove(key) {
+      self.order.remove(&(uses, tick, key.clone()));
+    }
+  }
+}
@@ -0,0 +1,30 @@
+use alloc::{string::ToString, vec};
+
+use super::LeastFrequentlyUsedPolicy;
+use crate::{activation::passivation_policy::PassivationPolicy, grain::GrainKey};
+
+fn key(value: &str) -> GrainKey {
+  GrainKey::new(value.to_string())
+}
+
+#[test]
+fn evicts_the_least_frequently_used_grain_over_the_limit() {
+  let mut policy = LeastFrequentlyUsedPolicy::new(2);
+  assert!(policy.activated(&key("user/a")).is_empty());
+  assert!(policy.activated(&key("user/b")).is_empty());
+  policy.accessed(&key("user/b"));
+  policy.accessed(&key("user/b"));
+
+  assert_eq!(policy.activated(&key("user/c")), vec![key("user/a")]);
+}
+
+#[test]
+fn ties_are_broken_by_recency_and_the_new_grain_is_kept() {
+  let mut policy = LeastFrequentlyUsedPolicy::new(2);
+  assert!(policy.activated(&key("user/a")).is_empty());
+  assert!(policy.activated(&key("user/b")).is_empty());
+  policy.accessed(&key("user/a"));
+  policy.accessed(&key("user/b"));
+
+  assert_eq!(policy.activated(&key("user/c")), vec![key("user/a")]);
+}
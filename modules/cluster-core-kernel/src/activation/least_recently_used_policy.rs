@@ -0,0 +1,45 @@
+//! Least-recently-used passivation policy.
+
+#[cfg(test)]
+#[path = "least_recently_used_policy_test.rs"]
+mod tests;
+
+use alloc::vec::Vec;
+
+use super::{passivation_policy::PassivationPolicy, recency_list::RecencyList};
+use crate::grain::GrainKey;
+
+/// Passivates the least recently used activations over the limit.
+pub(crate) struct LeastRecentlyUsedPolicy {
+  limit:   usize,
+  recency: RecencyList,
+}
+
+impl LeastRecentlyUsedPolicy {
+  pub(crate) const fn new(limit: usize) -> Self {
+    Self { limit, recency: RecencyList::new() }
+  }
+}
+
+impl PassivationPolicy for LeastRecentlyUsedPolicy {
+  fn activated(&mut self, key: &GrainKey) -> Vec<GrainKey> {
+    self.recency.touch(key);
+    let mut evicted = Vec::new();
+    while self.recency.len() > self.limit
+      && let Some(victim) = self.recency.pop_least_recent()
+    {
+      evicted.push(victim);
+    }
+    evicted
+  }
+
+  fn accessed(&mut self, key: &GrainKey) {
+    if self.recency.contains(key) {
+      self.recency.touch(key);
+    }
+  }
+
+  fn removed(&mut self, key: &GrainKey) {
+    self.recency.remove(key);
+  }
+}
@@ -0,0 +1,27 @@
+use alloc::{string::ToString, vec};
+
+use super::LeastRecentlyUsedPolicy;
+use crate::{activation::passivation_policy::PassivationPolicy, grain::GrainKey};
+
+fn key(value: &str) -> GrainKey {
+  GrainKey::new(value.to_string())
+}
+
+#[test]
+fn evicts_the_least_recently_used_grain_over_the_limit() {
+  let mut policy = LeastRecentlyUsedPolicy::new(2);
+  assert!(policy.activated(&key("user/a")).is_empty());
+  assert!(policy.activated(&key("user/b")).is_empty());
+  policy.accessed(&key("user/a"));
+
+  assert_eq!(policy.activated(&key("user/c")), vec![key("user/b")]);
+}
+
+#[test]
+fn removed_grains_free_their_slot() {
+  let mut policy = LeastRecentlyUsedPolicy::new(1);
+  assert!(policy.activated(&key("user/a")).is_empty());
+  policy.removed(&key("user/a"));
+
+  assert!(policy.activated(&key("user/b")).is_empty());
+}
@@ -0,0 +1,48 @@
+//! Most-recently-used passivation policy.
+
+#[cfg(test)]
+#[path = "most_recently_used_policy_test.rs"]
+mod tests;
+
+use alloc::vec::Vec;
+
+use super::{passivation_policy::PassivationPolicy, recency_list::RecencyList};
+use crate::grain::GrainKey;
+
+/// Passivates the most recently used activations, other than the new one, over the limit.
+///
+/// Suited to cyclic access patterns where the grain used last is the one needed latest.
+pub(crate) struct MostRecentlyUsedPolicy {
+  limit:   usize,
+  recency: RecencyList,
+}
+
+impl MostRecentlyUsedPolicy {
+  pub(crate) const fn new(limit: usize) -> Self {
+    Self { limit, recency: RecencyList::new() }
+  }
+}
+
+impl PassivationPolicy for MostRecentlyUsedPolicy {
+  fn activated(&mut self, key: &GrainKey) -> Vec<GrainKey> {
+    self.recency.touch(key);
+    let mut evicted = Vec::new();
+    while self.recency.len() > self.limit
+      && let Some(victim) = self.recency.most_recent_except(key).cloned()
+    {
+      self.recency.remove(&victim);
+      evicted.push(victim);
+    }
+    evicted
+  }
+
+  fn accessed(&mut self, key: &GrainKey) {
+    if self.recency.contains(key) {
+      self.recency.touch(key);
+    }
+  }
+
+  fn removed(&mut self, key: &GrainKey) {
+    self.recency.remove(key);
+  }
+}
@@ -0,0 +1,18 @@
+use alloc::{string::ToString, vec};
+
+use super::MostRecentlyUsedPolicy;
+use crate::{activation::passivation_policy::PassivationPolicy, grain::GrainKey};
+
+fn key(value: &str) -> GrainKey {
+  GrainKey::new(value.to_string())
+}
+
+#[test]
+fn evicts_the_most_recently_used_grain_other_than_the_new_one() {
+  let mut policy = MostRecentlyUsedPolicy::new(2);
+  assert!(policy.activated(&key("user/a")).is_empty());
+  assert!(policy.activated(&key("user/b")).is_empty());
+  policy.accessed(&key("user/a"));
+
+  assert_eq!(policy.activated(&key("user/c")), vec![key("user/a")]);
+}
@@ -1,6 +1,6 @@
 //! Partition-based identity lookup using distributed hashing.
 
-use alloc::{format, string::String, vec::Vec};
+use alloc::{boxed::Box, format, string::String, vec::Vec};
 
 use super::{
   identity_lookup::IdentityLookup, identity_setup_error::IdentitySetupError, lookup_error::LookupError,
@@ -8,10 +8,14 @@ use super::{
 };
 use crate::{
   activation::{
-    ActivatedKind, PlacementCommandResult, PlacementCoordinatorCore, PlacementCoordinatorError,
-    PlacementCoordinatorOutcome, PlacementCoordinatorState, PlacementEvent, PlacementResolution,
+    ActivatedKind, PlacementCommand, PlacementCommandResult, PlacementCoordinatorCore, PlacementCoordinatorError,
+    PlacementCoordinatorOutcome, PlacementCoordinatorState, PlacementEvent, PlacementResolution, PlacementSnapshot,
   },
   grain::GrainKey,
+  sharding::{
+    ShardAllocationConfig, ShardAllocationStrategy, ShardAllocationTable, ShardCoordinator, ShardId,
+    ShardRegionRequests, ShardRegionState, ShardRegionStats,
+  },
 };
 
 #[cfg(test)]
@@ -33,6 +37,9 @@ pub struct PartitionIdentityLookup {
   client_kinds: Vec<ActivatedKind>,
   /// Configuration parameters.
   config:       PartitionIdentityLookupConfig,
+  /// Placement commands queued by resolution, reactivation and command results for the caller to
+  /// execute.
+  commands:     Vec<PlacementCommand>,
 }
 
 impl PartitionIdentityLookup {
@@ -44,6 +51,7 @@ impl PartitionIdentityLookup {
       member_kinds: Vec::new(),
       client_kinds: Vec::new(),
       config,
+      commands: Vec::new(),
     }
   }
 
@@ -53,6 +61,25 @@ impl PartitionIdentityLookup {
     Self::new(PartitionIdentityLookupConfig::default())
   }
 
+  /// Places grains of `kind` by shard, allocating shards with `strategy`.
+  ///
+  /// Kinds without a strategy keep rendezvous-hash placement.
+  #[must_use]
+  pub fn with_shard_allocation(
+    mut self,
+    kind: impl Into<String>,
+    config: ShardAllocationConfig,
+    strategy: Box<dyn ShardAllocationStrategy>,
+  ) -> Self {
+    self.coordinator.set_shard_coordinator(kind, ShardCoordinator::new(config, strategy));
+    self
+  }
+
+  /// Completes the hand-off of `shard` of `kind` once its previous owner stopped its grains.
+  pub fn complete_hand_off(&mut self, kind: &str, shard: ShardId, observed_at: u64) {
+    self.coordinator.complete_hand_off(kind, shard, observed_at);
+  }
+
   /// Returns the current authority list.
   #[must_use]
   #[allow(clippy::missing_const_for_fn)]
@@ -106,6 +133,12 @@ impl PartitionIdentityLookup {
 impl IdentityLookup for PartitionIdentityLookup {
   fn setup_member(&mut self, kinds: &[ActivatedKind]) -> Result<(), IdentitySetupError> {
     self.member_kinds = kinds.to_vec();
+    let remembered =
+      kinds.iter().filter(|kind| kind.remember_entities()).map(|kind| String::from(kind.name())).collect();
+    self.coordinator.set_remembered_kinds(remembered);
+    for kind in kinds {
+      self.coordinator.set_passivation_strategy(kind.name(), kind.passivation_strategy());
+    }
     self.coordinator.start_member().map_err(|error| IdentitySetupError::Provider(format!("{error:?}")))?;
     Ok(())
   }
@@ -116,8 +149,13 @@ impl IdentityLookup for PartitionIdentityLookup {
     Ok(())
   }
 
+  fn set_local_authority(&mut self, authority: &str) {
+    self.coordinator.set_local_authority(authority);
+  }
+
   fn resolve(&mut self, key: &GrainKey, now: u64) -> Result<PlacementResolution, LookupError> {
     let outcome = self.coordinator.resolve(key, now)?;
+    self.commands.extend(outcome.commands);
     if let Some(resolution) = outcome.resolution {
       return Ok(resolution);
     }
@@ -131,12 +169,17 @@ impl IdentityLookup for PartitionIdentityLookup {
     idle_now_nanos: u64,
   ) -> Result<PlacementResolution, LookupError> {
     let outcome = self.coordinator.resolve_at(key, now_secs, idle_now_nanos)?;
+    self.commands.extend(outcome.commands);
     if let Some(resolution) = outcome.resolution {
       return Ok(resolution);
     }
     Err(LookupError::Pending)
   }
 
+  fn owner_of(&mut self, key: &GrainKey) -> Result<String, LookupError> {
+    self.coordinator.owner_of(key)
+  }
+
   fn remove_pid(&mut self, key: &GrainKey) {
     self.coordinator.remove_pid(key);
   }
@@ -145,6 +188,49 @@ impl IdentityLookup for PartitionIdentityLookup {
     self.coordinator.update_topology(authorities);
   }
 
+  fn restore_remembered(&mut self, keys: Vec<GrainKey>) {
+    self.coordinator.restore_remembered(keys);
+  }
+
+  fn reactivate_remembered_at(&mut self, now_secs: u64, idle_now_nanos: u64) {
+    let outcomes = self.coordinator.reactivate_remembered_at(now_secs, idle_now_nanos);
+    self.commands.extend(outcomes.into_iter().flat_map(|outcome| outcome.commands));
+  }
+
+  fn rebalance_at(&mut self, now_secs: u64, idle_now_nanos: u64) {
+    let outcomes = self.coordinator.rebalance_at(now_secs, idle_now_nanos);
+    self.commands.extend(outcomes.into_iter().flat_map(|outcome| outcome.commands));
+  }
+
+  fn sharded_kinds(&self) -> Vec<String> {
+    self.coordinator.sharded_kinds()
+  }
+
+  fn shard_allocations(&self, kind: &str) -> Option<ShardAllocationTable> {
+    self.coordinator.shard_allocations(kind)
+  }
+
+  fn shard_region_requests(&self, kind: &str) -> Option<ShardRegionRequests> {
+    self.coordinator.shard_region_requests(kind)
+  }
+
+  fn apply_shard_allocations(&mut self, kind: &str, table: ShardAllocationTable, now_secs: u64, idle_now_nanos: u64) {
+    self.coordinator.apply_shard_allocations(kind, table, now_secs);
+    self.reactivate_remembered_at(now_secs, idle_now_nanos);
+  }
+
+  fn handle_shard_region_requests(
+    &mut self,
+    kind: &str,
+    authority: &str,
+    requests: &ShardRegionRequests,
+    now_secs: u64,
+    idle_now_nanos: u64,
+  ) {
+    self.coordinator.handle_shard_region_requests(kind, authority, requests, now_secs);
+    self.reactivate_remembered_at(now_secs, idle_now_nanos);
+  }
+
   fn on_member_left(&mut self, authority: &str) {
     self.coordinator.invalidate_authority(authority);
   }
@@ -161,6 +247,16 @@ impl IdentityLookup for PartitionIdentityLookup {
     self.coordinator.drain_events()
   }
 
+  fn drain_commands(&mut self) -> Vec<PlacementCommand> {
+    core::mem::take(&mut self.commands)
+  }
+
+  fn apply_command_result(&mut self, result: PlacementCommandResult) -> Result<(), PlacementCoordinatorError> {
+    let outcome = self.coordinator.handle_command_result(result)?;
+    self.commands.extend(outcome.commands);
+    Ok(())
+  }
+
   fn drain_cache_events(&mut self) -> Vec<PidCacheEvent> {
     self.coordinator.drain_cache_events()
   }
@@ -168,4 +264,16 @@ impl IdentityLookup for PartitionIdentityLookup {
   fn placement_state(&self) -> PlacementCoordinatorState {
     self.coordinator.state()
   }
+
+  fn placement_snapshot(&self) -> Option<PlacementSnapshot> {
+    Some(self.coordinator.snapshot())
+  }
+
+  fn shard_region_state(&self, kind: &str) -> ShardRegionState {
+    self.coordinator.shard_region_state(kind)
+  }
+
+  fn shard_region_stats(&self) -> ShardRegionStats {
+    self.coordinator.shard_region_stats()
+  }
 }
@@ -637,3 +637,21 @@ fn default_placement_state_reports_not_ready() {
   let lookup = DefaultPlacementLookup;
   assert_eq!(lookup.placement_state(), PlacementCoordinatorState::NotReady);
 }
+
+#[test]
+fn test_restored_grains_of_remembered_kind_are_reactivated_after_topology_change() {
+  // remember_entities を有効にした kind は、復元後のトポロジー変化で即座に再活性化される
+  let mut lookup = PartitionIdentityLookup::with_defaults();
+  let kinds = vec![ActivatedKind::new("user").with_remember_entities(true), ActivatedKind::new("order")];
+  lookup.setup_member(&kinds).expect("setup member");
+  let key = GrainKey::new("user/restored".to_string());
+
+  lookup.restore_remembered(vec![key.clone()]);
+  lookup.update_topology(vec!["node1:8080".to_string()]);
+  lookup.reactivate_remembered_at(5, 5_000_000_000);
+
+  let events = lookup.drain_events();
+  assert!(
+    events.iter().any(|event| matches!(event, PlacementEvent::Activated { key: activated, .. } if *activated == key))
+  );
+}
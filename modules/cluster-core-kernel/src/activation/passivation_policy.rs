@@ -0,0 +1,19 @@
+//! Active-entity limit policies applied by the virtual actor registry.
+
+use alloc::vec::Vec;
+
+use crate::grain::GrainKey;
+
+/// Tracks the activations of one kind and selects the ones to passivate over the limit.
+pub(crate) trait PassivationPolicy: Send + Sync {
+  /// Records a new activation and returns the activations to passivate to stay within the limit.
+  ///
+  /// The returned keys never include `key` itself, except for admission policies that refuse it.
+  fn activated(&mut self, key: &GrainKey) -> Vec<GrainKey>;
+
+  /// Records a use of an existing activation.
+  fn accessed(&mut self, key: &GrainKey);
+
+  /// Forgets an activation passivated or removed for any reason.
+  fn removed(&mut self, key: &GrainKey);
+}
@@ -0,0 +1,36 @@
+//! Reasons a grain activation was passivated.
+
+/// Why a grain activation was passivated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PassivationReason {
+  /// The grain was idle for longer than its idle timeout.
+  IdleTimeout,
+  /// Evicted as the least recently used grain of its kind over the active-entity limit.
+  LeastRecentlyUsed,
+  /// Evicted as the most recently used grain of its kind over the active-entity limit.
+  MostRecentlyUsed,
+  /// Evicted as the least frequently used grain of its kind over the active-entity limit.
+  LeastFrequentlyUsed,
+  /// Evicted, or refused admission, by the segmented least-recently-used policy of its kind.
+  SegmentedLeastRecentlyUsed,
+  /// Removed explicitly, with its owner, or while its shard was handed off.
+  Removed,
+}
+
+impl PassivationReason {
+  /// Number of passivation reasons.
+  pub const COUNT: usize = 6;
+
+  /// Returns a dense index in `0..Self::COUNT`, used for per-reason counters.
+  #[must_use]
+  pub const fn index(self) -> usize {
+    match self {
+      | Self::IdleTimeout => 0,
+      | Self::LeastRecentlyUsed => 1,
+      | Self::MostRecentlyUsed => 2,
+      | Self::LeastFrequentlyUsed => 3,
+      | Self::SegmentedLeastRecentlyUsed => 4,
+      | Self::Removed => 5,
+    }
+  }
+}
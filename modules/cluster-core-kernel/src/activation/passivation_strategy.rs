@@ -0,0 +1,104 @@
+//! Per-kind passivation strategy settings.
+
+#[cfg(test)]
+#[path = "passivation_strategy_test.rs"]
+mod tests;
+
+use alloc::boxed::Box;
+use core::time::Duration;
+
+use super::{
+  PassivationReason, least_frequently_used_policy::LeastFrequentlyUsedPolicy,
+  least_recently_used_policy::LeastRecentlyUsedPolicy, most_recently_used_policy::MostRecentlyUsedPolicy,
+  passivation_policy::PassivationPolicy, segmented_least_recently_used_policy::SegmentedLeastRecentlyUsedPolicy,
+};
+
+/// How the grains of one kind are passivated on each member.
+///
+/// Kinds without a strategy are passivated after the cluster-wide grain idle threshold.
+/// Limit-based strategies keep at most `limit` activations of the kind per member and are not
+/// subject to idle passivation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PassivationStrategy {
+  /// Passivates grains idle for longer than `timeout`.
+  IdleTimeout {
+    /// Idle duration after which a grain is passivated.
+    timeout: Duration,
+  },
+  /// Passivates the least recently used grain when the limit is exceeded.
+  LeastRecentlyUsed {
+    /// Maximum number of active grains of the kind per member.
+    limit: usize,
+  },
+  /// Passivates the most recently used grain, other than the one just activated, when the
+  /// limit is exceeded.
+  MostRecentlyUsed {
+    /// Maximum number of active grains of the kind per member.
+    limit: usize,
+  },
+  /// Passivates the least frequently used grain, oldest first among equals, when the limit is
+  /// exceeded.
+  LeastFrequentlyUsed {
+    /// Maximum number of active grains of the kind per member.
+    limit: usize,
+  },
+  /// Segmented least-recently-used policy with an admission window and frequency filter.
+  ///
+  /// New grains enter a small recency window. Grains leaving the window are admitted to the main
+  /// area, split into probationary and protected segments, only when they were used more often
+  /// than the grain they would replace. A `window_percent` of zero admits every grain.
+  SegmentedLeastRecentlyUsed {
+    /// Maximum number of active grains of the kind per member.
+    limit:          usize,
+    /// Share of the limit used by the admission window, in percent.
+    window_percent: u8,
+  },
+}
+
+impl PassivationStrategy {
+  /// Returns the reason reported for grains passivated by this strategy.
+  #[must_use]
+  pub const fn reason(&self) -> PassivationReason {
+    match self {
+      | Self::IdleTimeout { .. } => PassivationReason::IdleTimeout,
+      | Self::LeastRecentlyUsed { .. } => PassivationReason::LeastRecentlyUsed,
+      | Self::MostRecentlyUsed { .. } => PassivationReason::MostRecentlyUsed,
+      | Self::LeastFrequentlyUsed { .. } => PassivationReason::LeastFrequentlyUsed,
+      | Self::SegmentedLeastRecentlyUsed { .. } => PassivationReason::SegmentedLeastRecentlyUsed,
+    }
+  }
+
+  /// Returns the active-entity limit of limit-based strategies.
+  #[must_use]
+  pub const fn limit(&self) -> Option<usize> {
+    match self {
+      | Self::IdleTimeout { .. } => None,
+      | Self::LeastRecentlyUsed { limit }
+      | Self::MostRecentlyUsed { limit }
+      | Self::LeastFrequentlyUsed { limit }
+      | Self::SegmentedLeastRecentlyUsed { limit, .. } => Some(*limit),
+    }
+  }
+
+  /// Returns the idle timeout of the idle strategy.
+  #[must_use]
+  pub const fn idle_timeout(&self) -> Option<Duration> {
+    match self {
+      | Self::IdleTimeout { timeout } => Some(*timeout),
+      | _ => None,
+    }
+  }
+
+  pub(crate) fn policy(&self) -> Option<Box<dyn PassivationPolicy>> {
+    // 0 件上限では直前に活性化した grain も残せないため 1 件に切り上げる
+    match *self {
+      | Self::IdleTimeout { .. } => None,
+      | Self::LeastRecentlyUsed { limit } => Some(Box::new(LeastRecentlyUsedPolicy::new(limit.max(1)))),
+      | Self::MostRecentlyUsed { limit } => Some(Box::new(MostRecentlyUsedPolicy::new(limit.max(1)))),
+      | Self::LeastFrequentlyUsed { limit } => Some(Box::new(LeastFrequentlyUsedPolicy::new(limit.max(1)))),
+      | Self::SegmentedLeastRecentlyUsed { limit, window_percent } => {
+        Some(Box::new(SegmentedLeastRecentlyUsedPolicy::new(limit.max(1), window_percent.min(100))))
+      },
+    }
+  }
+}
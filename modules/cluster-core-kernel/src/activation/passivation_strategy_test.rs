@@ -0,0 +1,37 @@
+use alloc::string::ToString;
+use core::time::Duration;
+
+use crate::{
+  activation::{PassivationReason, PassivationStrategy},
+  grain::GrainKey,
+};
+
+#[test]
+fn strategies_report_their_reason_limit_and_timeout() {
+  let idle = PassivationStrategy::IdleTimeout { timeout: Duration::from_secs(30) };
+  let lru = PassivationStrategy::LeastRecentlyUsed { limit: 10 };
+  let slru = PassivationStrategy::SegmentedLeastRecentlyUsed { limit: 20, window_percent: 1 };
+
+  assert_eq!(idle.reason(), PassivationReason::IdleTimeout);
+  assert_eq!(idle.limit(), None);
+  assert_eq!(idle.idle_timeout(), Some(Duration::from_secs(30)));
+  assert_eq!(lru.reason(), PassivationReason::LeastRecentlyUsed);
+  assert_eq!(lru.limit(), Some(10));
+  assert_eq!(lru.idle_timeout(), None);
+  assert_eq!(slru.reason(), PassivationReason::SegmentedLeastRecentlyUsed);
+  assert_eq!(slru.limit(), Some(20));
+}
+
+#[test]
+fn only_limit_based_strategies_build_a_policy() {
+  assert!(PassivationStrategy::IdleTimeout { timeout: Duration::from_secs(1) }.policy().is_none());
+  assert!(PassivationStrategy::MostRecentlyUsed { limit: 1 }.policy().is_some());
+  assert!(PassivationStrategy::LeastFrequentlyUsed { limit: 1 }.policy().is_some());
+}
+
+#[test]
+fn zero_limit_still_keeps_the_grain_just_activated() {
+  let mut policy = PassivationStrategy::LeastRecentlyUsed { limit: 0 }.policy().expect("policy");
+
+  assert!(policy.activated(&GrainKey::new("user/a".to_string())).is_empty());
+}
@@ -1,14 +1,26 @@
 //! Placement coordinator core logic.
 
-use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  format,
+  string::String,
+  vec,
+  vec::Vec,
+};
 
 use super::{
-  ActivationEntry, ActivationError, ActivationRecord, LookupError, PidCacheEvent, PlacementCommand,
-  PlacementCommandResult, PlacementCoordinatorError, PlacementCoordinatorOutcome, PlacementCoordinatorState,
-  PlacementDecision, PlacementEvent, PlacementLease, PlacementLocality, PlacementRequestId, PlacementResolution,
-  PlacementSnapshot, RendezvousHasher, VirtualActorEvent, VirtualActorRegistry,
+  ActivationEntry, ActivationError, ActivationRecord, LookupError, PassivationStrategy, PidCacheEvent,
+  PlacementCommand, PlacementCommandResult, PlacementCoordinatorError, PlacementCoordinatorOutcome,
+  PlacementCoordinatorState, PlacementDecision, PlacementEvent, PlacementLease, PlacementLocality, PlacementRequestId,
+  PlacementResolution, PlacementSnapshot, RendezvousHasher, VirtualActorEvent, VirtualActorRegistry,
+};
+use crate::{
+  grain::GrainKey,
+  sharding::{
+    ShardAllocationConfig, ShardAllocationTable, ShardCoordinator, ShardHome, ShardId, ShardRegionRequests,
+    ShardRegionState, ShardRegionStats, ShardState,
+  },
 };
-use crate::grain::GrainKey;
 
 #[cfg(test)]
 #[path = "placement_coordinator_test.rs"]
@@ -34,6 +46,10 @@ pub struct PlacementCoordinatorCore {
   pending:                BTreeMap<PlacementRequestId, PendingRequest>,
   next_request_id:        u64,
   events:                 Vec<PlacementEvent>,
+  remembered_kinds:       Vec<String>,
+  remembered:             BTreeSet<GrainKey>,
+  reactivations:          Vec<GrainKey>,
+  shard_coordinators:     BTreeMap<String, ShardCoordinator>,
 }
 
 impl PlacementCoordinatorCore {
@@ -49,6 +65,10 @@ impl PlacementCoordinatorCore {
       pending:                BTreeMap::new(),
       next_request_id:        0,
       events:                 Vec::new(),
+      remembered_kinds:       Vec::new(),
+      remembered:             BTreeSet::new(),
+      reactivations:          Vec::new(),
+      shard_coordinators:     BTreeMap::new(),
     }
   }
 
@@ -75,6 +95,211 @@ impl PlacementCoordinatorCore {
     self.distributed_activation = enabled;
   }
 
+  /// Places grains of `kind` by shard using `coordinator` instead of rendezvous hashing.
+  ///
+  /// Remembered grains of `kind` are scheduled for reactivation on their shard owner.
+  pub fn set_shard_coordinator(&mut self, kind: impl Into<String>, mut coordinator: ShardCoordinator) {
+    let kind = kind.into();
+    let mut released = coordinator.update_members(&self.authorities);
+    self.shard_coordinators.insert(kind.clone(), coordinator);
+    released.extend(self.remembered.iter().filter(|key| key.kind() == kind).cloned());
+    self.schedule_reactivations(released);
+  }
+
+  /// Returns the kinds placed by shard.
+  #[must_use]
+  pub fn sharded_kinds(&self) -> Vec<String> {
+    self.shard_coordinators.keys().cloned().collect()
+  }
+
+  /// Returns the authority of the member allocating and rebalancing shards.
+  ///
+  /// The member with the lowest authority coordinates, so every member with the same topology
+  /// agrees on it. Returns `None` without authorities.
+  #[must_use]
+  pub fn shard_coordinator_authority(&self) -> Option<&str> {
+    self.authorities.iter().min().map(String::as_str)
+  }
+
+  /// Returns the allocations of `kind` to replicate from the coordinating member.
+  ///
+  /// Returns `None` when the local member does not coordinate or `kind` is not placed by shard.
+  #[must_use]
+  pub fn shard_allocations(&self, kind: &str) -> Option<ShardAllocationTable> {
+    if !self.is_shard_coordinator() {
+      return None;
+    }
+    self.shard_coordinators.get(kind).map(ShardCoordinator::table)
+  }
+
+  /// Returns the requests of the local member to the coordinating member for `kind`.
+  #[must_use]
+  pub fn shard_region_requests(&self, kind: &str) -> Option<ShardRegionRequests> {
+    self.shard_coordinators.get(kind).map(ShardCoordinator::requests)
+  }
+
+  /// Applies the allocations of `kind` replicated from the coordinating member.
+  ///
+  /// Shards the table hands off from the local member stop their local grains and are
+  /// acknowledged in [`Self::shard_region_requests`]. Grains buffered by completed hand-offs and
+  /// remembered grains of the kind are scheduled for reactivation.
+  pub fn apply_shard_allocations(&mut self, kind: &str, table: ShardAllocationTable, observed_at: u64) {
+    let Some(coordinator) = self.shard_coordinators.get_mut(kind) else {
+      return;
+    };
+    let mut released = coordinator.apply_table(table);
+    if let Some(local) = self.local_authority.clone() {
+      let stopping = coordinator.unacknowledged_hand_offs(&local);
+      for shard in stopping {
+        self.events.push(PlacementEvent::ShardHandOffStarted {
+          kind: String::from(kind),
+          shard,
+          from: local.clone(),
+          observed_at,
+        });
+        self.stop_local_shard(kind, shard, observed_at);
+        if let Some(coordinator) = self.shard_coordinators.get_mut(kind) {
+          coordinator.acknowledge_hand_off(shard);
+        }
+      }
+    }
+    released.extend(self.remembered.iter().filter(|key| key.kind() == kind).cloned());
+    self.schedule_reactivations(released);
+  }
+
+  /// Handles the requests of the member at `authority` for `kind` on the coordinating member.
+  ///
+  /// Acknowledged hand-offs complete through [`Self::complete_hand_off`], and unallocated
+  /// shards are allocated on behalf of the requesting member. Other members ignore requests.
+  pub fn handle_shard_region_requests(
+    &mut self,
+    kind: &str,
+    authority: &str,
+    requests: &ShardRegionRequests,
+    observed_at: u64,
+  ) {
+    if !self.is_shard_coordinator() {
+      return;
+    }
+    let Some(coordinator) = self.shard_coordinators.get_mut(kind) else {
+      return;
+    };
+    let acknowledged: Vec<ShardId> = requests
+      .hand_offs
+      .iter()
+      .filter(|(shard, revision)| coordinator.is_acknowledged(**shard, authority, **revision))
+      .map(|(shard, _)| *shard)
+      .collect();
+    for shard in &requests.home {
+      // 割り当てられない shard は要求元が次の要求で再試行する
+      drop(coordinator.allocate(*shard, authority));
+    }
+    for shard in acknowledged {
+      self.complete_hand_off(kind, shard, observed_at);
+    }
+  }
+
+  /// Returns the shard coordinator of `kind`, if the kind is placed by shard.
+  #[must_use]
+  pub fn shard_coordinator(&self, kind: &str) -> Option<&ShardCoordinator> {
+    self.shard_coordinators.get(kind)
+  }
+
+  /// Runs one rebalance round for every kind placed by shard.
+  ///
+  /// Only the coordinating member starts hand-offs. Hand-offs from the local authority stop the
+  /// local grains of the shard and complete at once; hand-offs from other members complete once
+  /// the member acknowledges them through [`Self::handle_shard_region_requests`], or when the
+  /// member leaves. Grains buffered during a hand-off and remembered grains of the shard are
+  /// then reactivated if they moved to the local authority.
+  pub fn rebalance_at(&mut self, now_secs: u64, idle_now_nanos: u64) -> Vec<PlacementCoordinatorOutcome> {
+    let kinds: Vec<String> =
+      if self.is_shard_coordinator() { self.shard_coordinators.keys().cloned().collect() } else { Vec::new() };
+    for kind in kinds {
+      let hand_offs = self.shard_coordinators.get_mut(&kind).map(ShardCoordinator::start_rebalance).unwrap_or_default();
+      for hand_off in hand_offs {
+        self.events.push(PlacementEvent::ShardHandOffStarted {
+          kind:        kind.clone(),
+          shard:       hand_off.shard,
+          from:        hand_off.from.clone(),
+          observed_at: now_secs,
+        });
+        if !self.is_remote(&hand_off.from) {
+          self.stop_local_shard(&kind, hand_off.shard, now_secs);
+          self.complete_hand_off(&kind, hand_off.shard, now_secs);
+        }
+      }
+    }
+    self.reactivate_remembered_at(now_secs, idle_now_nanos)
+  }
+
+  /// Completes the hand-off of `shard` of `kind` after its previous owner stopped its grains.
+  ///
+  /// Buffered and remembered grains of the shard are scheduled for reactivation when their new
+  /// owner is the local authority. Unknown hand-offs, and calls on a member that does not
+  /// coordinate shards, are ignored.
+  pub fn complete_hand_off(&mut self, kind: &str, shard: ShardId, observed_at: u64) {
+    if !self.is_shard_coordinator() {
+      return;
+    }
+    let Some(coordinator) = self.shard_coordinators.get_mut(kind) else {
+      return;
+    };
+    if !coordinator.in_progress().contains(&shard) {
+      return;
+    }
+    let buffered = coordinator.complete_hand_off(shard);
+    let remembered: Vec<GrainKey> =
+      self.remembered.iter().filter(|key| key.kind() == kind && coordinator.shard_of(key) == shard).cloned().collect();
+    self.events.push(PlacementEvent::ShardHandOffCompleted { kind: String::from(kind), shard, observed_at });
+    self.schedule_reactivations(buffered.into_iter().chain(remembered).collect());
+  }
+
+  /// Sets the passivation strategy of `kind`; `None` restores idle passivation.
+  pub fn set_passivation_strategy(&mut self, kind: &str, strategy: Option<PassivationStrategy>) {
+    self.registry.set_passivation_strategy(kind, strategy);
+  }
+
+  /// Sets the kinds whose grains are remembered and reactivated after topology changes.
+  pub fn set_remembered_kinds(&mut self, kinds: Vec<String>) {
+    self.remembered_kinds = kinds;
+  }
+
+  /// Returns the remembered grain keys in key order.
+  #[must_use]
+  pub fn remembered_keys(&self) -> Vec<GrainKey> {
+    self.remembered.iter().cloned().collect()
+  }
+
+  /// Restores remembered grain keys, typically loaded from activation storage.
+  ///
+  /// Keys owned by the local authority and not yet activated are scheduled for
+  /// [`Self::reactivate_remembered_at`].
+  pub fn restore_remembered(&mut self, keys: Vec<GrainKey>) {
+    for key in &keys {
+      self.remembered.insert(key.clone());
+    }
+    self.schedule_reactivations(keys);
+  }
+
+  /// Reactivates grains scheduled by topology changes, restoration or completed hand-offs.
+  ///
+  /// Keys that cannot be resolved yet stay remembered and are scheduled again on the next
+  /// topology change. Each failure is reported as [`PlacementEvent::ReactivationFailed`].
+  pub fn reactivate_remembered_at(&mut self, now_secs: u64, idle_now_nanos: u64) -> Vec<PlacementCoordinatorOutcome> {
+    let keys = core::mem::take(&mut self.reactivations);
+    let mut outcomes = Vec::with_capacity(keys.len());
+    for key in keys {
+      match self.resolve_at(&key, now_secs, idle_now_nanos) {
+        | Ok(outcome) => outcomes.push(outcome),
+        // ハンドオフ中のキーはバッファされ、完了時に再スケジュールされるため失敗ではない
+        | Err(LookupError::Pending) => {},
+        | Err(error) => self.events.push(PlacementEvent::ReactivationFailed { key, error, observed_at: now_secs }),
+      }
+    }
+    outcomes
+  }
+
   /// Starts in member mode.
   ///
   /// # Errors
@@ -103,6 +328,7 @@ impl PlacementCoordinatorCore {
   pub fn stop(&mut self) -> Result<(), PlacementCoordinatorError> {
     self.state = PlacementCoordinatorState::Stopped;
     self.pending.clear();
+    self.reactivations.clear();
     Ok(())
   }
 
@@ -111,7 +337,13 @@ impl PlacementCoordinatorCore {
     self.registry.invalidate_absent_authorities(&authorities);
     let events = self.collect_registry_events(0);
     self.events.extend(events);
+    let mut released = Vec::new();
+    for coordinator in self.shard_coordinators.values_mut() {
+      released.extend(coordinator.update_members(&authorities));
+    }
     self.authorities = authorities;
+    released.extend(self.remembered_keys());
+    self.schedule_reactivations(released);
   }
 
   /// Invalidates activations for a departed authority.
@@ -126,6 +358,7 @@ impl PlacementCoordinatorCore {
     self.registry.remove_activation(key);
     let events = self.collect_registry_events(0);
     self.events.extend(events);
+    self.forget(key, 0);
   }
 
   /// Passivates idle activations.
@@ -138,7 +371,17 @@ impl PlacementCoordinatorCore {
     self.registry.passivate_idle_at(now_nanos, idle_ttl_nanos);
     let observed_at = now_nanos.div_ceil(1_000_000_000);
     let events = self.collect_registry_events(observed_at);
+    let passivated: Vec<GrainKey> = events
+      .iter()
+      .filter_map(|event| match event {
+        | PlacementEvent::Passivated { key, .. } => Some(key.clone()),
+        | _ => None,
+      })
+      .collect();
     self.events.extend(events);
+    for key in &passivated {
+      self.forget(key, observed_at);
+    }
   }
 
   /// Drains PID cache events.
@@ -161,6 +404,35 @@ impl PlacementCoordinatorCore {
     }
   }
 
+  /// Returns the shards of `kind` with grains active on the local member.
+  ///
+  /// Kinds placed by rendezvous hashing are grouped into the default number of shards.
+  #[must_use]
+  pub fn shard_region_state(&self, kind: &str) -> ShardRegionState {
+    let number_of_shards =
+      self.shard_coordinators.get(kind).map_or(ShardAllocationConfig::default().number_of_shards(), |coordinator| {
+        coordinator.config().number_of_shards()
+      });
+    let mut shards: BTreeMap<ShardId, Vec<String>> = BTreeMap::new();
+    for key in self.local_activation_keys().into_iter().filter(|key| key.kind() == kind) {
+      shards.entry(ShardId::for_key(&key, number_of_shards)).or_default().push(String::from(key.identity()));
+    }
+    ShardRegionState {
+      kind:   String::from(kind),
+      shards: shards.into_iter().map(|(shard, entity_ids)| ShardState { shard, entity_ids }).collect(),
+    }
+  }
+
+  /// Returns the number of grains active on the local member per kind.
+  #[must_use]
+  pub fn shard_region_stats(&self) -> ShardRegionStats {
+    let mut stats = ShardRegionStats::default();
+    for key in self.local_activation_keys() {
+      *stats.entity_counts.entry(String::from(key.kind())).or_default() += 1;
+    }
+    stats
+  }
+
   /// Resolves placement for a grain key.
   ///
   /// # Errors
@@ -170,6 +442,18 @@ impl PlacementCoordinatorCore {
     self.resolve_at(key, now, now.saturating_mul(1_000_000_000))
   }
 
+  /// Returns the authority owning `key` without activating it.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when the coordinator is not ready or no owner can be selected.
+  pub fn owner_of(&mut self, key: &GrainKey) -> Result<String, LookupError> {
+    if matches!(self.state, PlacementCoordinatorState::Stopped | PlacementCoordinatorState::NotReady) {
+      return Err(LookupError::NotReady);
+    }
+    self.select_owner(key)
+  }
+
   /// Resolves placement and records an exact monotonic time for idle tracking.
   ///
   /// # Errors
@@ -185,9 +469,7 @@ impl PlacementCoordinatorCore {
       return Err(LookupError::NotReady);
     }
 
-    let Some(owner) = RendezvousHasher::select(&self.authorities, key).cloned() else {
-      return Err(LookupError::NoAuthority);
-    };
+    let owner = self.select_owner(key)?;
 
     if let Some((pid, cached_authority)) = self.registry.cached_pid_with_authority(key, now_secs)
       && self.authorities.contains(&cached_authority)
@@ -361,7 +643,8 @@ impl PlacementCoordinatorCore {
     decision: PlacementDecision,
     events: Vec<PlacementEvent>,
   ) -> Result<PlacementCoordinatorOutcome, LookupError> {
-    match self.registry.ensure_activation_at(key, &self.authorities, now_secs, idle_now_nanos, false, None) {
+    let owner = core::slice::from_ref(&decision.authority);
+    match self.registry.ensure_activation_at(key, owner, now_secs, idle_now_nanos, false, None) {
       | Ok(pid) => {
         let resolution = PlacementResolution { decision, locality: PlacementLocality::Local, pid };
         Ok(PlacementCoordinatorOutcome { resolution: Some(resolution), commands: Vec::new(), events })
@@ -371,6 +654,87 @@ impl PlacementCoordinatorCore {
     }
   }
 
+  fn select_owner(&mut self, key: &GrainKey) -> Result<String, LookupError> {
+    let Some(coordinator) = self.shard_coordinators.get_mut(key.kind()) else {
+      return RendezvousHasher::select(&self.authorities, key).cloned().ok_or(LookupError::NoAuthority);
+    };
+    let Some(coordinator_authority) = self.authorities.iter().min() else {
+      return Err(LookupError::NoAuthority);
+    };
+    let coordinates = self.local_authority.as_ref().is_none_or(|local| local == coordinator_authority);
+    if !coordinates {
+      // 割り当ては調整役のメンバーだけが行い、未割り当ての shard は要求として調整役へ送る
+      return match coordinator.lookup(key) {
+        | ShardHome::Owner(owner) => Ok(owner),
+        | ShardHome::Buffered | ShardHome::Unallocated => Err(LookupError::Pending),
+      };
+    }
+    match coordinator.home(key, coordinator_authority) {
+      | ShardHome::Owner(owner) => Ok(owner),
+      | ShardHome::Buffered => Err(LookupError::Pending),
+      | ShardHome::Unallocated => Err(LookupError::NoAuthority),
+    }
+  }
+
+  fn is_shard_coordinator(&self) -> bool {
+    match (&self.local_authority, self.shard_coordinator_authority()) {
+      | (Some(local), Some(coordinator)) => local == coordinator,
+      | _ => true,
+    }
+  }
+
+  fn stop_local_shard(&mut self, kind: &str, shard: ShardId, observed_at: u64) {
+    let Some(coordinator) = self.shard_coordinators.get(kind) else {
+      return;
+    };
+    let keys: Vec<GrainKey> = self
+      .registry
+      .activation_keys()
+      .into_iter()
+      .filter(|key| key.kind() == kind && coordinator.shard_of(key) == shard)
+      .collect();
+    for key in &keys {
+      self.registry.remove_activation(key);
+    }
+    let events = self.collect_registry_events(observed_at);
+    self.events.extend(events);
+  }
+
+  fn local_activation_keys(&self) -> Vec<GrainKey> {
+    self
+      .registry
+      .activation_keys()
+      .into_iter()
+      .filter(|key| self.registry.activation_authority(key).is_some_and(|authority| !self.is_remote(authority)))
+      .collect()
+  }
+
+  fn is_remembered_kind(&self, key: &GrainKey) -> bool {
+    let kind = key.kind();
+    self.remembered_kinds.iter().any(|remembered| remembered == kind)
+  }
+
+  fn forget(&mut self, key: &GrainKey, observed_at: u64) {
+    if self.remembered.remove(key) {
+      self.events.push(PlacementEvent::Forgotten { key: key.clone(), observed_at });
+    }
+  }
+
+  fn schedule_reactivations(&mut self, keys: Vec<GrainKey>) {
+    for key in keys {
+      let Ok(owner) = self.select_owner(&key) else {
+        continue;
+      };
+      // 他ノードが所有するキー、既に所有者で稼働中のキーは再活性化しない
+      if self.is_remote(&owner) || self.registry.activation_authority(&key) == Some(owner.as_str()) {
+        continue;
+      }
+      if !self.reactivations.contains(&key) {
+        self.reactivations.push(key);
+      }
+    }
+  }
+
   fn is_remote(&self, owner: &str) -> bool {
     match &self.local_authority {
       | Some(local) => local != owner,
@@ -432,13 +796,21 @@ impl PlacementCoordinatorCore {
     let mut events = Vec::new();
     for event in self.registry.drain_events() {
       match event {
-        | VirtualActorEvent::Activated { key, pid, .. }
-        | VirtualActorEvent::Hit { key, pid }
-        | VirtualActorEvent::Reactivated { key, pid, .. } => {
+        | VirtualActorEvent::Activated { key, pid, authority }
+        | VirtualActorEvent::Reactivated { key, pid, authority } => {
+          if self.is_remembered_kind(&key) {
+            self.remembered.insert(key.clone());
+            let record = ActivationRecord::new(pid.clone(), None, 0);
+            let entry = ActivationEntry { owner: authority, record, observed_at: now };
+            events.push(PlacementEvent::Remembered { key: key.clone(), entry });
+          }
+          events.push(PlacementEvent::Activated { key, pid, observed_at: now });
+        },
+        | VirtualActorEvent::Hit { key, pid } => {
           events.push(PlacementEvent::Activated { key, pid, observed_at: now });
         },
-        | VirtualActorEvent::Passivated { key } => {
-          events.push(PlacementEvent::Passivated { key, observed_at: now });
+        | VirtualActorEvent::Passivated { key, reason } => {
+          events.push(PlacementEvent::Passivated { key, reason, observed_at: now });
         },
         | VirtualActorEvent::SnapshotMissing { .. } => {},
       }
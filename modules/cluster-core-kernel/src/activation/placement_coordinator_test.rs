@@ -1,12 +1,23 @@
-use alloc::vec;
+use alloc::{
+  boxed::Box,
+  collections::{BTreeMap, BTreeSet},
+  format,
+  string::String,
+  vec,
+  vec::Vec,
+};
 
 use crate::{
   activation::{
-    ActivationRecord, PlacementCommand, PlacementCommandResult, PlacementCoordinatorCore, PlacementCoordinatorError,
-    PlacementEvent, PlacementLease, PlacementLocality, PlacementRequestId, RendezvousHasher,
+    ActivationRecord, LookupError, PlacementCommand, PlacementCommandResult, PlacementCoordinatorCore,
+    PlacementCoordinatorError, PlacementEvent, PlacementLease, PlacementLocality, PlacementRequestId, RendezvousHasher,
     placement_lock_error::PlacementLockError,
   },
   grain::GrainKey,
+  sharding::{
+    ExternalShardAllocation, ExternalShardAllocationStrategy, LeastShardAllocationStrategy, ShardAllocationConfig,
+    ShardAllocationStrategy, ShardCoordinator, ShardId, ShardState,
+  },
 };
 
 #[test]
@@ -175,3 +186,333 @@ fn distributed_activation_uses_completion_time_for_idle_passivation() {
       .any(|event| matches!(event, PlacementEvent::Passivated { key: passivated, .. } if *passivated == key))
   );
 }
+
+fn key_owned_by(authorities: &[String], owner: &str) -> GrainKey {
+  (0..)
+    .map(|index| GrainKey::new(format!("user/{index}")))
+    .find(|key| RendezvousHasher::select(authorities, key).is_some_and(|selected| selected == owner))
+    .expect("key owned by authority")
+}
+
+#[test]
+fn remembered_grain_is_reactivated_on_new_owner_after_topology_change() {
+  let mut coordinator = PlacementCoordinatorCore::new(128, 60);
+  coordinator.start_member().expect("start");
+  coordinator.set_local_authority("node2:8080".to_string());
+  coordinator.set_remembered_kinds(vec!["user".to_string()]);
+  let authorities = vec!["node1:8080".to_string(), "node2:8080".to_string()];
+  let key = key_owned_by(&authorities, "node1:8080");
+  coordinator.update_topology(authorities);
+
+  coordinator.restore_remembered(vec![key.clone()]);
+  assert!(coordinator.reactivate_remembered_at(10, 10_000_000_000).is_empty());
+
+  coordinator.update_topology(vec!["node2:8080".to_string()]);
+  let outcomes = coordinator.reactivate_remembered_at(11, 11_000_000_000);
+
+  assert_eq!(outcomes.len(), 1);
+  let resolution = outcomes[0].resolution.as_ref().expect("resolution");
+  assert_eq!(resolution.decision.key, key);
+  assert_eq!(resolution.decision.authority, "node2:8080");
+  let events = coordinator.drain_events();
+  assert!(
+    events.iter().any(|event| matches!(event, PlacementEvent::Activated { key: activated, .. } if *activated == key))
+  );
+  assert!(events.iter().any(
+    |event| matches!(event, PlacementEvent::Remembered { key: remembered, entry } if *remembered == key && entry.owner == "node2:8080")
+  ));
+}
+
+#[test]
+fn activated_grain_of_remembered_kind_is_remembered_until_passivated() {
+  let mut coordinator = PlacementCoordinatorCore::new(128, 60);
+  coordinator.start_member().expect("start");
+  coordinator.set_remembered_kinds(vec!["user".to_string()]);
+  coordinator.update_topology(vec!["node1:8080".to_string()]);
+  let remembered = GrainKey::new("user/a".to_string());
+  let transient = GrainKey::new("order/a".to_string());
+
+  let _ = coordinator.resolve(&remembered, 0).expect("activate remembered");
+  let _ = coordinator.resolve(&transient, 0).expect("activate transient");
+  assert_eq!(coordinator.remembered_keys(), vec![remembered.clone()]);
+  let _ = coordinator.drain_events();
+
+  coordinator.passivate_idle(10, 10);
+
+  assert!(coordinator.remembered_keys().is_empty());
+  let events = coordinator.drain_events();
+  assert!(events.iter().any(|event| matches!(event, PlacementEvent::Forgotten { key, .. } if *key == remembered)));
+  assert!(!events.iter().any(|event| matches!(event, PlacementEvent::Forgotten { key, .. } if *key == transient)));
+}
+
+#[test]
+fn remembered_grain_survives_owner_loss_but_not_removal() {
+  let mut coordinator = PlacementCoordinatorCore::new(128, 60);
+  coordinator.start_member().expect("start");
+  coordinator.set_remembered_kinds(vec!["user".to_string()]);
+  coordinator.update_topology(vec!["node1:8080".to_string()]);
+  let key = GrainKey::new("user/a".to_string());
+  let _ = coordinator.resolve(&key, 0).expect("activate");
+
+  coordinator.invalidate_authority("node1:8080");
+  assert_eq!(coordinator.remembered_keys(), vec![key.clone()]);
+
+  coordinator.remove_pid(&key);
+  assert!(coordinator.remembered_keys().is_empty());
+  coordinator.update_topology(vec!["node1:8080".to_string(), "node2:8080".to_string()]);
+  assert!(coordinator.reactivate_remembered_at(1, 1_000_000_000).is_empty());
+}
+
+struct NeverAllocate;
+
+impl ShardAllocationStrategy for NeverAllocate {
+  fn allocate_shard(
+    &mut self,
+    _requester: &str,
+    _shard: ShardId,
+    _allocations: &BTreeMap<String, Vec<ShardId>>,
+  ) -> Option<String> {
+    None
+  }
+
+  fn rebalance(
+    &mut self,
+    _allocations: &BTreeMap<String, Vec<ShardId>>,
+    _in_progress: &BTreeSet<ShardId>,
+  ) -> Vec<ShardId> {
+    Vec::new()
+  }
+}
+
+#[test]
+fn failed_reactivation_is_reported_per_key() {
+  let mut coordinator = PlacementCoordinatorCore::new(128, 60);
+  coordinator.start_member().expect("start");
+  coordinator.set_local_authority("node1:8080".to_string());
+  coordinator.set_remembered_kinds(vec!["user".to_string()]);
+  coordinator.update_topology(vec!["node1:8080".to_string()]);
+  let failing = GrainKey::new("user/a".to_string());
+  let reactivated = GrainKey::new("order/a".to_string());
+  coordinator.restore_remembered(vec![failing.clone(), reactivated.clone()]);
+  coordinator
+    .set_shard_coordinator("user", ShardCoordinator::new(ShardAllocationConfig::new(), Box::new(NeverAllocate)));
+
+  let outcomes = coordinator.reactivate_remembered_at(5, 5_000_000_000);
+
+  assert_eq!(outcomes.len(), 1);
+  assert_eq!(outcomes[0].resolution.as_ref().expect("resolution").decision.key, reactivated);
+  let failures: Vec<PlacementEvent> = coordinator
+    .drain_events()
+    .into_iter()
+    .filter(|event| matches!(event, PlacementEvent::ReactivationFailed { .. }))
+    .collect();
+  assert_eq!(failures, vec![PlacementEvent::ReactivationFailed {
+    key:         failing.clone(),
+    error:       LookupError::NoAuthority,
+    observed_at: 5,
+  }]);
+  assert!(coordinator.remembered_keys().contains(&failing));
+}
+
+fn keys_in_distinct_shards(count: usize, number_of_shards: u32) -> Vec<GrainKey> {
+  let mut keys: Vec<GrainKey> = Vec::new();
+  for index in 0.. {
+    let key = GrainKey::new(format!("user/{index}"));
+    let shard = ShardId::for_key(&key, number_of_shards);
+    if keys.iter().all(|existing| ShardId::for_key(existing, number_of_shards) != shard) {
+      keys.push(key);
+    }
+    if keys.len() == count {
+      break;
+    }
+  }
+  keys
+}
+
+#[test]
+fn rebalance_hands_off_local_shard_to_joining_member() {
+  let config = ShardAllocationConfig::new().with_number_of_shards(8);
+  let mut coordinator = PlacementCoordinatorCore::new(128, 60);
+  coordinator.start_member().expect("start");
+  coordinator.set_local_authority("node1:8080".to_string());
+  coordinator.update_topology(vec!["node1:8080".to_string()]);
+  coordinator
+    .set_shard_coordinator("user", ShardCoordinator::new(config, Box::new(LeastShardAllocationStrategy::new(1, 1.0))));
+  let keys = keys_in_distinct_shards(2, 8);
+  for key in &keys {
+    let _ = coordinator.resolve(key, 0).expect("activate");
+  }
+  let _ = coordinator.drain_events();
+
+  coordinator.update_topology(vec!["node1:8080".to_string(), "node2:8080".to_string()]);
+  let _ = coordinator.rebalance_at(10, 10_000_000_000);
+
+  let events = coordinator.drain_events();
+  let shard = events
+    .iter()
+    .find_map(|event| match event {
+      | PlacementEvent::ShardHandOffStarted { shard, from, .. } if from == "node1:8080" => Some(*shard),
+      | _ => None,
+    })
+    .expect("hand-off started");
+  assert!(events.iter().any(
+    |event| matches!(event, PlacementEvent::ShardHandOffCompleted { shard: completed, .. } if *completed == shard)
+  ));
+  let moved = keys.iter().find(|key| ShardId::for_key(key, 8) == shard).expect("moved key");
+  assert!(events.iter().any(|event| matches!(event, PlacementEvent::Passivated { key, .. } if key == moved)));
+
+  let outcome = coordinator.resolve(moved, 11).expect("resolve moved");
+  let resolution = outcome.resolution.expect("resolution");
+  assert_eq!(resolution.decision.authority, "node2:8080");
+  assert_eq!(resolution.locality, PlacementLocality::Remote);
+}
+
+fn sharded_member(local: &str, strategy: Box<dyn ShardAllocationStrategy>) -> PlacementCoordinatorCore {
+  let mut coordinator = PlacementCoordinatorCore::new(128, 60);
+  coordinator.start_member().expect("start");
+  coordinator.set_local_authority(local.to_string());
+  coordinator.update_topology(vec!["node1:8080".to_string(), "node2:8080".to_string()]);
+  coordinator.set_shard_coordinator(
+    "user",
+    ShardCoordinator::new(ShardAllocationConfig::new().with_number_of_shards(8), strategy),
+  );
+  coordinator
+}
+
+/// Replicates the requests of `member` to `coordinator` and the resulting table back.
+fn exchange(coordinator: &mut PlacementCoordinatorCore, member: &mut PlacementCoordinatorCore, observed_at: u64) {
+  let requests = member.shard_region_requests("user").expect("requests");
+  coordinator.handle_shard_region_requests("user", "node2:8080", &requests, observed_at);
+  let table = coordinator.shard_allocations("user").expect("coordinator table");
+  member.apply_shard_allocations("user", table, observed_at);
+}
+
+#[test]
+fn only_the_lowest_authority_allocates_so_members_agree_on_every_shard_owner() {
+  let mut node1 = sharded_member("node1:8080", Box::new(LeastShardAllocationStrategy::new(1, 1.0)));
+  let mut node2 = sharded_member("node2:8080", Box::new(LeastShardAllocationStrategy::new(1, 1.0)));
+  let keys = keys_in_distinct_shards(6, 8);
+  assert_eq!(node1.shard_coordinator_authority(), Some("node1:8080"));
+  assert!(node2.shard_allocations("user").is_none());
+
+  // 両メンバーが異なる順序で問い合わせても、割り当ては調整役だけが決める
+  for key in keys.iter().rev() {
+    assert_eq!(node2.owner_of(key), Err(LookupError::Pending));
+  }
+  for key in &keys[..3] {
+    node1.owner_of(key).expect("coordinator allocates");
+  }
+  exchange(&mut node1, &mut node2, 1);
+
+  let owners: Vec<String> = keys.iter().map(|key| node1.owner_of(key).expect("owner on node1")).collect();
+  for (key, owner) in keys.iter().zip(&owners) {
+    assert_eq!(&node2.owner_of(key).expect("owner on node2"), owner);
+  }
+  assert!(owners.iter().any(|owner| owner == "node1:8080"));
+  assert!(owners.iter().any(|owner| owner == "node2:8080"));
+  assert!(node2.shard_region_requests("user").expect("requests").home.is_empty());
+}
+
+#[test]
+fn remote_hand_off_completes_once_the_previous_owner_acknowledges_it() {
+  let allocation = ExternalShardAllocation::new();
+  let key = GrainKey::new("user/a".to_string());
+  let shard = ShardId::for_key(&key, 8);
+  allocation.update_shard_location(shard, "node2:8080");
+  let mut node1 = sharded_member("node1:8080", Box::new(ExternalShardAllocationStrategy::new(allocation.clone())));
+  let mut node2 = sharded_member("node2:8080", Box::new(ExternalShardAllocationStrategy::new(allocation.clone())));
+  assert!(matches!(node2.resolve(&key, 0), Err(LookupError::Pending)));
+  exchange(&mut node1, &mut node2, 0);
+  let outcome = node2.resolve(&key, 0).expect("activate on node2");
+  assert_eq!(outcome.resolution.expect("resolution").locality, PlacementLocality::Local);
+
+  allocation.update_shard_location(shard, "node1:8080");
+  assert!(node1.rebalance_at(1, 1_000_000_000).is_empty());
+  assert!(matches!(node1.resolve(&key, 2), Err(LookupError::Pending)));
+  let _ = node1.drain_events();
+
+  // node2 が shard を停止して確認応答するまで、ハンドオフは完了しない
+  let table = node1.shard_allocations("user").expect("coordinator table");
+  node2.apply_shard_allocations("user", table, 3);
+  let events = node2.drain_events();
+  assert!(
+    events.iter().any(|event| matches!(event, PlacementEvent::Passivated { key: stopped, .. } if *stopped == key))
+  );
+  assert!(matches!(node1.resolve(&key, 3), Err(LookupError::Pending)));
+  assert_eq!(
+    node2.shard_region_requests("user").expect("requests").hand_offs.keys().copied().collect::<Vec<_>>(),
+    vec![shard]
+  );
+
+  exchange(&mut node1, &mut node2, 4);
+
+  assert!(node1.drain_events().iter().any(
+    |event| matches!(event, PlacementEvent::ShardHandOffCompleted { shard: completed, .. } if *completed == shard)
+  ));
+  let outcomes = node1.reactivate_remembered_at(4, 4_000_000_000);
+  assert_eq!(outcomes.len(), 1);
+  let resolution = outcomes[0].resolution.as_ref().expect("resolution");
+  assert_eq!(resolution.decision.authority, "node1:8080");
+  assert_eq!(resolution.locality, PlacementLocality::Local);
+  let resolution = node2.resolve(&key, 5).expect("resolve on node2").resolution.expect("resolution");
+  assert_eq!(resolution.decision.authority, "node1:8080");
+  assert_eq!(resolution.locality, PlacementLocality::Remote);
+  assert!(node2.shard_region_requests("user").expect("requests").hand_offs.is_empty());
+}
+
+#[test]
+fn members_that_do_not_coordinate_ignore_hand_off_completion() {
+  let mut node2 = sharded_member("node2:8080", Box::new(LeastShardAllocationStrategy::new(1, 1.0)));
+
+  node2.complete_hand_off("user", ShardId(0), 1);
+  assert!(node2.rebalance_at(1, 1_000_000_000).is_empty());
+
+  assert!(node2.drain_events().is_empty());
+}
+
+#[test]
+fn shard_region_state_and_stats_only_report_local_activations() {
+  let authorities = vec!["node1:8080".to_string(), "node2:8080".to_string()];
+  let local = key_owned_by(&authorities, "node1:8080");
+  let remote = key_owned_by(&authorities, "node2:8080");
+  let mut coordinator = PlacementCoordinatorCore::new(128, 60);
+  coordinator.start_member().expect("start");
+  coordinator.set_local_authority("node1:8080".to_string());
+  coordinator.update_topology(authorities);
+  let _ = coordinator.resolve(&local, 0).expect("activate local");
+  let _ = coordinator.resolve(&remote, 0).expect("resolve remote");
+
+  let state = coordinator.shard_region_state("user");
+
+  assert_eq!(state.kind, "user");
+  assert_eq!(state.shards, vec![ShardState {
+    shard:      ShardId::for_key(&local, ShardAllocationConfig::new().number_of_shards()),
+    entity_ids: vec![local.identity().to_string()],
+  }]);
+  let stats = coordinator.shard_region_stats();
+  assert_eq!(stats.entity_counts.get("user"), Some(&1));
+  assert_eq!(stats.total(), 1);
+}
+
+#[test]
+fn shard_region_state_groups_grains_by_allocated_shard() {
+  let config = ShardAllocationConfig::new().with_number_of_shards(8);
+  let mut coordinator = PlacementCoordinatorCore::new(128, 60);
+  coordinator.start_member().expect("start");
+  coordinator.set_local_authority("node1:8080".to_string());
+  coordinator.update_topology(vec!["node1:8080".to_string()]);
+  coordinator
+    .set_shard_coordinator("user", ShardCoordinator::new(config, Box::new(LeastShardAllocationStrategy::new(1, 1.0))));
+  let keys = keys_in_distinct_shards(2, 8);
+  for key in &keys {
+    let _ = coordinator.resolve(key, 0).expect("activate");
+  }
+
+  let state = coordinator.shard_region_state("user");
+
+  let mut expected: Vec<ShardId> = keys.iter().map(|key| ShardId::for_key(key, 8)).collect();
+  expected.sort();
+  assert_eq!(state.shards.iter().map(|shard| shard.shard).collect::<Vec<_>>(), expected);
+  assert!(state.shards.iter().all(|shard| shard.entity_ids.len() == 1));
+  assert_eq!(coordinator.shard_region_stats().total(), 2);
+}
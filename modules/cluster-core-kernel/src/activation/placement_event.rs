@@ -2,7 +2,8 @@
 
 use alloc::string::String;
 
-use crate::grain::GrainKey;
+use super::{ActivationEntry, LookupError, PassivationReason};
+use crate::{grain::GrainKey, sharding::ShardId};
 
 /// Events emitted during placement resolution and activation.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -38,6 +39,51 @@ pub enum PlacementEvent {
   Passivated {
     /// Target grain key.
     key:         GrainKey,
+    /// Why the activation was passivated.
+    reason:      PassivationReason,
+    /// Observation timestamp in seconds.
+    observed_at: u64,
+  },
+  /// Grain of a remembered kind was activated and should be stored.
+  Remembered {
+    /// Target grain key.
+    key:   GrainKey,
+    /// Activation entry to store.
+    entry: ActivationEntry,
+  },
+  /// Remembered grain was passivated or removed and should be dropped from storage.
+  Forgotten {
+    /// Target grain key.
+    key:         GrainKey,
+    /// Observation timestamp in seconds.
+    observed_at: u64,
+  },
+  /// Remembered grain could not be reactivated on the local member.
+  ReactivationFailed {
+    /// Target grain key.
+    key:         GrainKey,
+    /// Why placement failed.
+    error:       LookupError,
+    /// Observation timestamp in seconds.
+    observed_at: u64,
+  },
+  /// Rebalance started handing off a shard.
+  ShardHandOffStarted {
+    /// Grain kind of the shard.
+    kind:        String,
+    /// Shard being handed off.
+    shard:       ShardId,
+    /// Authority owning the shard until the hand-off completes.
+    from:        String,
+    /// Observation timestamp in seconds.
+    observed_at: u64,
+  },
+  /// Shard hand-off completed; the shard is allocated again on the next lookup.
+  ShardHandOffCompleted {
+    /// Grain kind of the shard.
+    kind:        String,
+    /// Shard that was handed off.
+    shard:       ShardId,
     /// Observation timestamp in seconds.
     observed_at: u64,
   },
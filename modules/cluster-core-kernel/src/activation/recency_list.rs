@@ -0,0 +1,68 @@
+//! Grain keys ordered by their last use.
+
+#[cfg(test)]
+#[path = "recency_list_test.rs"]
+mod tests;
+
+use alloc::collections::BTreeMap;
+
+use crate::grain::GrainKey;
+
+/// Grain keys ordered from least to most recently used.
+pub(crate) struct RecencyList {
+  order:     BTreeMap<u64, GrainKey>,
+  positions: BTreeMap<GrainKey, u64>,
+  next:      u64,
+}
+
+impl RecencyList {
+  pub(crate) const fn new() -> Self {
+    Self { order: BTreeMap::new(), positions: BTreeMap::new(), next: 0 }
+  }
+
+  /// Moves `key` to the most recently used end, inserting it when absent.
+  pub(crate) fn touch(&mut self, key: &GrainKey) {
+    if let Some(position) = self.positions.remove(key) {
+      self.order.remove(&position);
+    }
+    self.order.insert(self.next, key.clone());
+    self.positions.insert(key.clone(), self.next);
+    self.next += 1;
+  }
+
+  /// Removes `key`, returning `true` when it was present.
+  pub(crate) fn remove(&mut self, key: &GrainKey) -> bool {
+    match self.positions.remove(key) {
+      | Some(position) => {
+        self.order.remove(&position);
+        true
+      },
+      | None => false,
+    }
+  }
+
+  /// Removes and returns the least recently used key.
+  pub(crate) fn pop_least_recent(&mut self) -> Option<GrainKey> {
+    let (_, key) = self.order.pop_first()?;
+    self.positions.remove(&key);
+    Some(key)
+  }
+
+  /// Returns the least recently used key.
+  pub(crate) fn least_recent(&self) -> Option<&GrainKey> {
+    self.order.values().next()
+  }
+
+  /// Returns the most recently used key other than `except`.
+  pub(crate) fn most_recent_except(&self, except: &GrainKey) -> Option<&GrainKey> {
+    self.order.values().rev().find(|key| *key != except)
+  }
+
+  pub(crate) fn contains(&self, key: &GrainKey) -> bool {
+    self.positions.contains_key(key)
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.positions.len()
+  }
+}
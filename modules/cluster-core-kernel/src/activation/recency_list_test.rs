@@ -0,0 +1,32 @@
+use alloc::string::ToString;
+
+use super::RecencyList;
+use crate::grain::GrainKey;
+
+fn key(value: &str) -> GrainKey {
+  GrainKey::new(value.to_string())
+}
+
+#[test]
+fn touch_moves_keys_to_the_most_recent_end() {
+  let mut list = RecencyList::new();
+  list.touch(&key("user/a"));
+  list.touch(&key("user/b"));
+  list.touch(&key("user/a"));
+
+  assert_eq!(list.len(), 2);
+  assert_eq!(list.least_recent(), Some(&key("user/b")));
+  assert_eq!(list.most_recent_except(&key("user/b")), Some(&key("user/a")));
+  assert_eq!(list.pop_least_recent(), Some(key("user/b")));
+  assert!(!list.contains(&key("user/b")));
+}
+
+#[test]
+fn remove_reports_whether_the_key_was_present() {
+  let mut list = RecencyList::new();
+  list.touch(&key("user/a"));
+
+  assert!(list.remove(&key("user/a")));
+  assert!(!list.remove(&key("user/a")));
+  assert_eq!(list.pop_least_recent(), None);
+}
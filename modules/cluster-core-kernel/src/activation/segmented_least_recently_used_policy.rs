@@ -0,0 +1,147 @@
+//! Segmented least-recently-used passivation policy with an admission window and filter.
+
+#[cfg(test)]
+#[path = "segmented_least_recently_used_policy_test.rs"]
+mod tests;
+
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+use super::{passivation_policy::PassivationPolicy, recency_list::RecencyList};
+use crate::grain::GrainKey;
+
+const PROTECTED_PERCENT: usize = 80;
+const SAMPLE_SIZE_FACTOR: usize = 10;
+
+/// Segmented LRU main area behind an LRU admission window, filtered by use frequency.
+///
+/// New activations enter the window. The grain leaving a full window competes with the
+/// least recently used grain of the main area and is admitted only when it was used more often;
+/// the loser is passivated. In the main area, grains used again are promoted from the
+/// probationary to the protected segment, and the protected segment overflows back into the
+/// probationary one. Use counts are halved every `10 × limit` uses so that old popularity fades.
+pub(crate) struct SegmentedLeastRecentlyUsedPolicy {
+  window_capacity:    usize,
+  main_capacity:      usize,
+  protected_capacity: usize,
+  window:             RecencyList,
+  probation:          RecencyList,
+  protected:          RecencyList,
+  frequencies:        BTreeMap<GrainKey, u32>,
+  samples:            usize,
+  sample_size:        usize,
+}
+
+impl SegmentedLeastRecentlyUsedPolicy {
+  pub(crate) fn new(limit: usize, window_percent: u8) -> Self {
+    let window_capacity =
+      if window_percent == 0 { 0 } else { (limit * usize::from(window_percent) / 100).clamp(1, limit) };
+    let main_capacity = limit - window_capacity;
+    Self {
+      window_capacity,
+      main_capacity,
+      protected_capacity: main_capacity * PROTECTED_PERCENT / 100,
+      window: RecencyList::new(),
+      probation: RecencyList::new(),
+      protected: RecencyList::new(),
+      frequencies: BTreeMap::new(),
+      samples: 0,
+      sample_size: limit.saturating_mul(SAMPLE_SIZE_FACTOR),
+    }
+  }
+
+  fn record_use(&mut self, key: &GrainKey) {
+    let frequency = self.frequencies.entry(key.clone()).or_insert(0);
+    *frequency = frequency.saturating_add(1);
+    self.samples += 1;
+    if self.samples >= self.sample_size {
+      // 古い人気を減衰させるため、全頻度を半減させる
+      self.frequencies.retain(|_, frequency| {
+        *frequency /= 2;
+        *frequency > 0
+      });
+      self.samples /= 2;
+    }
+  }
+
+  fn frequency(&self, key: &GrainKey) -> u32 {
+    self.frequencies.get(key).copied().unwrap_or(0)
+  }
+
+  fn main_len(&self) -> usize {
+    self.probation.len() + self.protected.len()
+  }
+
+  fn main_victim(&self, except: Option<&GrainKey>) -> Option<GrainKey> {
+    self.probation.least_recent().filter(|key| Some(*key) != except).or(self.protected.least_recent()).cloned()
+  }
+
+  fn admit(&mut self, candidate: GrainKey) -> Vec<GrainKey> {
+    if self.main_len() < self.main_capacity {
+      self.probation.touch(&candidate);
+      return Vec::new();
+    }
+    let Some(victim) = self.main_victim(None) else {
+      return vec![candidate];
+    };
+    if self.frequency(&candidate) > self.frequency(&victim) {
+      self.remove_from_main(&victim);
+      self.probation.touch(&candidate);
+      vec![victim]
+    } else {
+      vec![candidate]
+    }
+  }
+
+  fn remove_from_main(&mut self, key: &GrainKey) {
+    if !self.probation.remove(key) {
+      self.protected.remove(key);
+    }
+  }
+}
+
+impl PassivationPolicy for SegmentedLeastRecentlyUsedPolicy {
+  fn activated(&mut self, key: &GrainKey) -> Vec<GrainKey> {
+    self.record_use(key);
+    if self.window_capacity == 0 {
+      self.probation.touch(key);
+      let mut evicted = Vec::new();
+      while self.main_len() > self.main_capacity
+        && let Some(victim) = self.main_victim(Some(key))
+      {
+        self.remove_from_main(&victim);
+        evicted.push(victim);
+      }
+      return evicted;
+    }
+    self.window.touch(key);
+    if self.window.len() <= self.window_capacity {
+      return Vec::new();
+    }
+    match self.window.pop_least_recent() {
+      | Some(candidate) => self.admit(candidate),
+      | None => Vec::new(),
+    }
+  }
+
+  fn accessed(&mut self, key: &GrainKey) {
+    self.record_use(key);
+    if self.window.contains(key) {
+      self.window.touch(key);
+    } else if self.probation.remove(key) {
+      self.protected.touch(key);
+      while self.protected.len() > self.protected_capacity
+        && let Some(demoted) = self.protected.pop_least_recent()
+      {
+        self.probation.touch(&demoted);
+      }
+    } else if self.protected.contains(key) {
+      self.protected.touch(key);
+    }
+  }
+
+  fn removed(&mut self, key: &GrainKey) {
+    if !self.window.remove(key) {
+      self.remove_from_main(key);
+    }
+  }
+}
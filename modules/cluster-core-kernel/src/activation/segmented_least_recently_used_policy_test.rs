@@ -0,0 +1,35 @@
+use alloc::{string::ToString, vec};
+
+use super::SegmentedLeastRecentlyUsedPolicy;
+use crate::{activation::passivation_policy::PassivationPolicy, grain::GrainKey};
+
+fn key(value: &str) -> GrainKey {
+  GrainKey::new(value.to_string())
+}
+
+#[test]
+fn without_a_window_grains_used_again_are_protected_from_eviction() {
+  let mut policy = SegmentedLeastRecentlyUsedPolicy::new(3, 0);
+  assert!(policy.activated(&key("user/a")).is_empty());
+  assert!(policy.activated(&key("user/b")).is_empty());
+  assert!(policy.activated(&key("user/c")).is_empty());
+  policy.accessed(&key("user/a"));
+
+  assert_eq!(policy.activated(&key("user/d")), vec![key("user/b")]);
+}
+
+#[test]
+fn admission_filter_refuses_rare_grains_and_admits_popular_ones() {
+  let mut policy = SegmentedLeastRecentlyUsedPolicy::new(4, 25);
+  for name in ["user/a", "user/b", "user/c", "user/d"] {
+    assert!(policy.activated(&key(name)).is_empty());
+  }
+  policy.accessed(&key("user/a"));
+
+  // 窓から押し出された d は主領域の b より使用頻度が高くないため拒否される
+  assert_eq!(policy.activated(&key("user/e")), vec![key("user/d")]);
+
+  policy.accessed(&key("user/e"));
+  policy.accessed(&key("user/e"));
+  assert_eq!(policy.activated(&key("user/f")), vec![key("user/b")]);
+}
@@ -2,6 +2,7 @@
 
 use alloc::string::String;
 
+use super::PassivationReason;
 use crate::grain::GrainKey;
 
 #[cfg(test)]
@@ -36,10 +37,12 @@ pub enum VirtualActorEvent {
     /// New authority.
     authority: String,
   },
-  /// Activation was passivated due to idleness.
+  /// Activation was passivated.
   Passivated {
     /// Grain key.
-    key: GrainKey,
+    key:    GrainKey,
+    /// Why the activation was passivated.
+    reason: PassivationReason,
   },
   /// Activation was dropped because snapshot missing.
   SnapshotMissing {
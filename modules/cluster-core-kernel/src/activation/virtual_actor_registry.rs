@@ -1,13 +1,17 @@
 //! Manages virtual actor activations and passivation.
 
 use alloc::{
+  boxed::Box,
   collections::BTreeMap,
   format,
   string::{String, ToString},
   vec::Vec,
 };
 
-use super::{ActivationError, ActivationRecord, PidCache, PidCacheEvent, RendezvousHasher, VirtualActorEvent};
+use super::{
+  ActivationError, ActivationRecord, PassivationReason, PassivationStrategy, PidCache, PidCacheEvent, RendezvousHasher,
+  VirtualActorEvent, passivation_policy::PassivationPolicy,
+};
 use crate::grain::GrainKey;
 
 #[cfg(test)]
@@ -21,18 +25,50 @@ struct ActivationEntry {
 }
 
 /// Registry that keeps track of active grains.
+///
+/// Activations are passivated per kind by their [`PassivationStrategy`]: limit-based
+/// strategies evict activations as new ones are created, the others are passivated by
+/// [`Self::passivate_idle`].
 pub struct VirtualActorRegistry {
-  activations:  BTreeMap<GrainKey, ActivationEntry>,
-  pid_cache:    PidCache,
-  pid_ttl_secs: u64,
-  events:       Vec<VirtualActorEvent>,
+  activations:   BTreeMap<GrainKey, ActivationEntry>,
+  pid_cache:     PidCache,
+  pid_ttl_secs:  u64,
+  events:        Vec<VirtualActorEvent>,
+  idle_timeouts: BTreeMap<String, u64>,
+  policies:      BTreeMap<String, (PassivationReason, Box<dyn PassivationPolicy>)>,
 }
 
 impl VirtualActorRegistry {
   /// Creates a new registry.
   #[must_use]
   pub const fn new(cache_capacity: usize, pid_ttl_secs: u64) -> Self {
-    Self { activations: BTreeMap::new(), pid_cache: PidCache::new(cache_capacity), pid_ttl_secs, events: Vec::new() }
+    Self {
+      activations: BTreeMap::new(),
+      pid_cache: PidCache::new(cache_capacity),
+      pid_ttl_secs,
+      events: Vec::new(),
+      idle_timeouts: BTreeMap::new(),
+      policies: BTreeMap::new(),
+    }
+  }
+
+  /// Sets the passivation strategy of `kind`, or restores idle passivation with the default
+  /// threshold when `strategy` is `None`.
+  ///
+  /// Activations of the kind that already exist are tracked by the new strategy from their
+  /// next use on.
+  pub fn set_passivation_strategy(&mut self, kind: &str, strategy: Option<PassivationStrategy>) {
+    self.idle_timeouts.remove(kind);
+    self.policies.remove(kind);
+    let Some(strategy) = strategy else {
+      return;
+    };
+    if let Some(timeout) = strategy.idle_timeout() {
+      self.idle_timeouts.insert(String::from(kind), u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX));
+    }
+    if let Some(policy) = strategy.policy() {
+      self.policies.insert(String::from(kind), (strategy.reason(), policy));
+    }
   }
 
   /// Ensures an activation exists and returns its PID.
@@ -74,9 +110,11 @@ impl VirtualActorRegistry {
       && entry.authority == *owner
     {
       entry.last_seen_nanos = idle_now_nanos;
-      self.events.push(VirtualActorEvent::Hit { key: key.clone(), pid: entry.record.pid.clone() });
-      self.pid_cache.put(key.clone(), entry.record.pid.clone(), owner.clone(), now_secs, self.pid_ttl_secs);
-      return Ok(entry.record.pid.clone());
+      let pid = entry.record.pid.clone();
+      self.events.push(VirtualActorEvent::Hit { key: key.clone(), pid: pid.clone() });
+      self.pid_cache.put(key.clone(), pid.clone(), owner.clone(), now_secs, self.pid_ttl_secs);
+      self.policy_accessed(key);
+      return Ok(pid);
     }
 
     let pid = format!("{}::{}", owner, key.value());
@@ -91,12 +129,14 @@ impl VirtualActorRegistry {
         pid:       pid.clone(),
         authority: owner.clone(),
       });
+      self.policy_accessed(key);
     } else {
       self.events.push(VirtualActorEvent::Activated {
         key:       key.clone(),
         pid:       pid.clone(),
         authority: owner.clone(),
       });
+      self.policy_activated(key);
     }
 
     Ok(pid)
@@ -122,8 +162,7 @@ impl VirtualActorRegistry {
     let to_drop: Vec<_> =
       self.activations.iter().filter(|(_, entry)| entry.authority == authority).map(|(key, _)| key.clone()).collect();
     for key in to_drop {
-      self.activations.remove(&key);
-      self.events.push(VirtualActorEvent::Passivated { key });
+      self.drop_activation(&key, PassivationReason::Removed);
     }
   }
 
@@ -137,8 +176,7 @@ impl VirtualActorRegistry {
       .map(|(key, _)| key.clone())
       .collect();
     for key in to_drop {
-      self.activations.remove(&key);
-      self.events.push(VirtualActorEvent::Passivated { key });
+      self.drop_activation(&key, PassivationReason::Removed);
     }
   }
 
@@ -151,14 +189,19 @@ impl VirtualActorRegistry {
     let to_passivate: Vec<_> = self
       .activations
       .iter()
-      .filter(|(_, entry)| now_nanos.saturating_sub(entry.last_seen_nanos) >= idle_ttl_nanos)
+      .filter(|(key, entry)| {
+        let kind = key.kind();
+        // 上限方式の kind は放置時間では非活性化しない
+        !self.policies.contains_key(kind)
+          && now_nanos.saturating_sub(entry.last_seen_nanos)
+            >= self.idle_timeouts.get(kind).copied().unwrap_or(idle_ttl_nanos)
+      })
       .map(|(key, _)| key.clone())
       .collect();
 
     for key in to_passivate {
-      self.activations.remove(&key);
       self.pid_cache.invalidate_key(&key);
-      self.events.push(VirtualActorEvent::Passivated { key });
+      self.drop_activation(&key, PassivationReason::IdleTimeout);
     }
   }
 
@@ -168,11 +211,11 @@ impl VirtualActorRegistry {
   /// If the key does not exist, this method does nothing.
   pub fn remove_activation(&mut self, key: &GrainKey) {
     // アクティベーションが存在する場合のみ削除処理を実行
-    if self.activations.remove(key).is_some() {
+    if self.activations.contains_key(key) {
       // 対応するキャッシュエントリも削除
       self.pid_cache.invalidate_key(key);
       // Passivated イベントを生成
-      self.events.push(VirtualActorEvent::Passivated { key: key.clone() });
+      self.drop_activation(key, PassivationReason::Removed);
     }
   }
 
@@ -210,17 +253,53 @@ impl VirtualActorRegistry {
     let replaced = self.activations.insert(key.clone(), entry);
     self.pid_cache.put(key.clone(), pid.clone(), authority.to_string(), now_secs, self.pid_ttl_secs);
 
-    let event = if replaced.is_some() {
-      VirtualActorEvent::Reactivated { key: key.clone(), pid, authority: authority.to_string() }
+    if replaced.is_some() {
+      self.events.push(VirtualActorEvent::Reactivated { key: key.clone(), pid, authority: authority.to_string() });
+      self.policy_accessed(key);
     } else {
-      VirtualActorEvent::Activated { key: key.clone(), pid, authority: authority.to_string() }
-    };
-    self.events.push(event);
+      self.events.push(VirtualActorEvent::Activated { key: key.clone(), pid, authority: authority.to_string() });
+      self.policy_activated(key);
+    }
+  }
+
+  pub(crate) fn activation_keys(&self) -> Vec<GrainKey> {
+    self.activations.keys().cloned().collect()
+  }
+
+  pub(crate) fn activation_authority(&self, key: &GrainKey) -> Option<&str> {
+    self.activations.get(key).map(|entry| entry.authority.as_str())
   }
 
   pub(crate) fn touch_activation(&mut self, key: &GrainKey, idle_now_nanos: u64) {
     if let Some(entry) = self.activations.get_mut(key) {
       entry.last_seen_nanos = idle_now_nanos;
+      self.policy_accessed(key);
+    }
+  }
+
+  fn policy_activated(&mut self, key: &GrainKey) {
+    let Some((reason, policy)) = self.policies.get_mut(key.kind()) else {
+      return;
+    };
+    let reason = *reason;
+    for evicted in policy.activated(key) {
+      self.pid_cache.invalidate_key(&evicted);
+      self.drop_activation(&evicted, reason);
+    }
+  }
+
+  fn policy_accessed(&mut self, key: &GrainKey) {
+    if let Some((_, policy)) = self.policies.get_mut(key.kind()) {
+      policy.accessed(key);
+    }
+  }
+
+  fn drop_activation(&mut self, key: &GrainKey, reason: PassivationReason) {
+    if let Some((_, policy)) = self.policies.get_mut(key.kind()) {
+      policy.removed(key);
+    }
+    if self.activations.remove(key).is_some() {
+      self.events.push(VirtualActorEvent::Passivated { key: key.clone(), reason });
     }
   }
 }
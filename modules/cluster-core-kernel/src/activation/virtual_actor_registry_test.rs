@@ -1,7 +1,10 @@
-use alloc::string::ToString;
+use alloc::{string::ToString, vec::Vec};
+use core::time::Duration;
 
 use crate::{
-  activation::{ActivationError, PidCacheEvent, VirtualActorEvent, VirtualActorRegistry},
+  activation::{
+    ActivationError, PassivationReason, PassivationStrategy, PidCacheEvent, VirtualActorEvent, VirtualActorRegistry,
+  },
   grain::GrainKey,
 };
 
@@ -65,7 +68,7 @@ fn cached_pid_access_refreshes_last_seen_for_idle_passivation() {
   registry.passivate_idle(10, 10);
 
   let events = registry.drain_events();
-  assert!(!events.iter().any(|event| matches!(event, VirtualActorEvent::Passivated { key } if *key == k)));
+  assert!(!events.iter().any(|event| matches!(event, VirtualActorEvent::Passivated { key, .. } if *key == k)));
   assert!(registry.cached_pid(&k, 10).is_some());
 }
 
@@ -106,7 +109,7 @@ fn remove_activation_removes_activation_and_cache() {
   // Passivated イベントが生成されたことを確認
   let events = registry.drain_events();
   assert!(
-    events.iter().any(|e| matches!(e, VirtualActorEvent::Passivated { key } if *key == k)),
+    events.iter().any(|e| matches!(e, VirtualActorEvent::Passivated { key, .. } if *key == k)),
     "Passivated イベントが生成されるべき"
   );
 }
@@ -234,3 +237,75 @@ fn drain_cache_events_captures_ttl_expiration() {
     "TTL 期限切れ時に Dropped イベントが生成されるべき"
   );
 }
+
+fn passivations(registry: &mut VirtualActorRegistry) -> Vec<(GrainKey, PassivationReason)> {
+  registry
+    .drain_events()
+    .into_iter()
+    .filter_map(|event| match event {
+      | VirtualActorEvent::Passivated { key, reason } => Some((key, reason)),
+      | _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn least_recently_used_strategy_evicts_beyond_limit_with_reason() {
+  let mut registry = VirtualActorRegistry::new(8, 60);
+  registry.set_passivation_strategy("user", Some(PassivationStrategy::LeastRecentlyUsed { limit: 2 }));
+  let authorities = vec!["a1:4000".to_string()];
+
+  registry.ensure_activation(&key("user/1"), &authorities, 0, false, None).expect("activation");
+  registry.ensure_activation(&key("user/2"), &authorities, 1, false, None).expect("activation");
+  // user/1 を再利用して user/2 を最も古い利用にする
+  registry.ensure_activation(&key("user/1"), &authorities, 2, false, None).expect("activation");
+  registry.ensure_activation(&key("order/1"), &authorities, 3, false, None).expect("activation");
+  registry.drain_events();
+
+  registry.ensure_activation(&key("user/3"), &authorities, 4, false, None).expect("activation");
+
+  assert_eq!(passivations(&mut registry), vec![(key("user/2"), PassivationReason::LeastRecentlyUsed)]);
+  assert!(registry.cached_pid(&key("user/2"), 5).is_none());
+  assert!(registry.cached_pid(&key("user/1"), 5).is_some());
+  assert!(registry.cached_pid(&key("order/1"), 5).is_some());
+}
+
+#[test]
+fn limit_strategies_are_exempt_from_idle_passivation() {
+  let mut registry = VirtualActorRegistry::new(8, 60);
+  registry.set_passivation_strategy("user", Some(PassivationStrategy::LeastFrequentlyUsed { limit: 4 }));
+  let authorities = vec!["a1:4000".to_string()];
+  registry.ensure_activation(&key("user/1"), &authorities, 0, false, None).expect("activation");
+  registry.ensure_activation(&key("order/1"), &authorities, 0, false, None).expect("activation");
+  registry.drain_events();
+
+  registry.passivate_idle(30, 10);
+
+  assert_eq!(passivations(&mut registry), vec![(key("order/1"), PassivationReason::IdleTimeout)]);
+}
+
+#[test]
+fn idle_timeout_strategy_overrides_default_threshold() {
+  let mut registry = VirtualActorRegistry::new(8, 60);
+  registry.set_passivation_strategy("user", Some(PassivationStrategy::IdleTimeout { timeout: Duration::from_secs(5) }));
+  let authorities = vec!["a1:4000".to_string()];
+  registry.ensure_activation(&key("user/1"), &authorities, 0, false, None).expect("activation");
+  registry.ensure_activation(&key("order/1"), &authorities, 0, false, None).expect("activation");
+  registry.drain_events();
+
+  registry.passivate_idle(6, 10);
+
+  assert_eq!(passivations(&mut registry), vec![(key("user/1"), PassivationReason::IdleTimeout)]);
+}
+
+#[test]
+fn remove_activation_reports_removed_reason() {
+  let mut registry = VirtualActorRegistry::new(8, 60);
+  let k = key("user/1");
+  registry.ensure_activation(&k, &["a1:4000".to_string()], 0, false, None).expect("activation");
+  registry.drain_events();
+
+  registry.remove_activation(&k);
+
+  assert_eq!(passivations(&mut registry), vec![(k, PassivationReason::Removed)]);
+}
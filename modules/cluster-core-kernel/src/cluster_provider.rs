@@ -5,6 +5,12 @@
 
 use crate::extension::ClusterProviderError;
 
+/// Configuration for cluster bootstrap.
+mod cluster_bootstrap_config;
+/// Cluster bootstrap decision vocabulary.
+mod cluster_bootstrap_decision;
+/// Seed node formation from discovered contact points.
+mod cluster_bootstrap_process;
 /// Provider-neutral discovered authority value.
 mod discovered_authority;
 /// Provider-neutral discovery outcome value.
@@ -26,6 +32,9 @@ mod seed_node_process;
 /// Static cluster provider for predetermined topology scenarios.
 mod static_cluster_provider;
 
+pub use cluster_bootstrap_config::ClusterBootstrapConfig;
+pub use cluster_bootstrap_decision::ClusterBootstrapDecision;
+pub use cluster_bootstrap_process::ClusterBootstrapProcess;
 pub use discovered_authority::DiscoveredAuthority;
 pub use discovery_result::DiscoveryResult;
 pub use discovery_topology_mapper::DiscoveryTopologyMapper;
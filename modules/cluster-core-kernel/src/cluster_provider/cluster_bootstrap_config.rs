@@ -0,0 +1,70 @@
+//! Configuration for cluster bootstrap.
+
+use core::time::Duration;
+
+/// Configuration for [`ClusterBootstrapProcess`](super::ClusterBootstrapProcess).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClusterBootstrapConfig {
+  stable_margin:                Duration,
+  required_contact_point_count: usize,
+  probe_interval:               Duration,
+}
+
+impl Default for ClusterBootstrapConfig {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl ClusterBootstrapConfig {
+  /// Creates a bootstrap configuration with default values.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self {
+      stable_margin:                Duration::from_secs(5),
+      required_contact_point_count: 2,
+      probe_interval:               Duration::from_secs(1),
+    }
+  }
+
+  /// Sets how long the contact point set must stay unchanged before a new cluster is formed.
+  #[must_use]
+  pub const fn with_stable_margin(mut self, stable_margin: Duration) -> Self {
+    self.stable_margin = stable_margin;
+    self
+  }
+
+  /// Sets how many contact points, including the local node, must be discovered before forming.
+  ///
+  /// This prevents partially rolled out deployments from forming separate clusters.
+  #[must_use]
+  pub const fn with_required_contact_point_count(mut self, count: usize) -> Self {
+    self.required_contact_point_count = count;
+    self
+  }
+
+  /// Sets how often drivers re-run discovery and contact point probes.
+  #[must_use]
+  pub const fn with_probe_interval(mut self, probe_interval: Duration) -> Self {
+    self.probe_interval = probe_interval;
+    self
+  }
+
+  /// Returns the stable margin.
+  #[must_use]
+  pub const fn stable_margin(&self) -> Duration {
+    self.stable_margin
+  }
+
+  /// Returns the required contact point count.
+  #[must_use]
+  pub const fn required_contact_point_count(&self) -> usize {
+    self.required_contact_point_count
+  }
+
+  /// Returns the probe interval.
+  #[must_use]
+  pub const fn probe_interval(&self) -> Duration {
+    self.probe_interval
+  }
+}
@@ -0,0 +1,19 @@
+//! Cluster bootstrap decision vocabulary.
+
+use alloc::{string::String, vec::Vec};
+
+/// Decision produced by [`ClusterBootstrapProcess`](super::ClusterBootstrapProcess).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClusterBootstrapDecision {
+  /// Bootstrap cannot decide yet.
+  Pending {
+    /// Human readable reason used for observability.
+    reason: &'static str,
+  },
+  /// The local node has the lowest contact point address and forms a new cluster by joining itself.
+  JoinSelf,
+  /// An existing cluster was found; the local node joins the listed seed nodes.
+  JoinSeedNodes(Vec<String>),
+  /// The local node already joined the cluster.
+  Completed,
+}
@@ -0,0 +1,192 @@
+//! Seed node formation from discovered contact points.
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::{cmp::Ordering, net::IpAddr, time::Duration};
+
+use fraktor_utils_core_rs::time::TimerInstant;
+
+use super::{ClusterBootstrapConfig, ClusterBootstrapDecision, DiscoveryResult};
+
+#[cfg(test)]
+#[path = "cluster_bootstrap_process_test.rs"]
+mod tests;
+
+const INSUFFICIENT_CONTACT_POINTS: &str = "fewer contact points than required";
+const SELF_NOT_DISCOVERED: &str = "local node is not among the discovered contact points";
+const NOT_STABLE: &str = "contact points have not been stable for the stable margin";
+const NOT_ALL_PROBED: &str = "not all contact points have been probed successfully";
+const WAITING_FOR_LOWEST: &str = "waiting for the lowest address contact point to form the cluster";
+const SHUT_DOWN: &str = "bootstrap has been shut down";
+
+/// Decides whether the local node forms a new cluster or joins an existing one.
+///
+/// Drivers feed discovery results and contact point probe outcomes, then ask for a decision. When a
+/// probed contact point already belongs to a cluster, its seed nodes are joined. Otherwise, once
+/// the required number of contact points has been stable for the configured margin and every
+/// contact point answered, only the node with the lowest address joins itself; the others join it
+/// after it reports itself as a seed node.
+#[derive(Debug, Clone)]
+pub struct ClusterBootstrapProcess {
+  config:         ClusterBootstrapConfig,
+  self_authority: String,
+  contact_points: Vec<String>,
+  stable_since:   Option<TimerInstant>,
+  // 応答したコンタクトポイントと、そのノードが報告した seed ノード
+  probed:         BTreeMap<String, Vec<String>>,
+  is_completed:   bool,
+  is_shutdown:    bool,
+}
+
+impl ClusterBootstrapProcess {
+  /// Creates a bootstrap process for the local advertised authority.
+  #[must_use]
+  pub fn new(config: ClusterBootstrapConfig, self_authority: impl Into<String>) -> Self {
+    Self {
+      config,
+      self_authority: self_authority.into(),
+      contact_points: Vec::new(),
+      stable_since: None,
+      probed: BTreeMap::new(),
+      is_completed: false,
+      is_shutdown: false,
+    }
+  }
+
+  /// Returns the bootstrap configuration.
+  #[must_use]
+  pub const fn config(&self) -> &ClusterBootstrapConfig {
+    &self.config
+  }
+
+  /// Returns the currently discovered contact points ordered by address.
+  #[must_use]
+  pub const fn contact_points(&self) -> &[String] {
+    self.contact_points.as_slice()
+  }
+
+  /// Returns whether the local node has joined the cluster.
+  #[must_use]
+  pub const fn is_completed(&self) -> bool {
+    self.is_completed
+  }
+
+  /// Records a discovery result observed at `now`.
+  ///
+  /// Failed results keep the previous contact points. A changed contact point set restarts the
+  /// stable margin and forgets probe outcomes for contact points that disappeared.
+  pub fn observe_contact_points(&mut self, result: &DiscoveryResult, now: TimerInstant) {
+    if result.is_failed() {
+      return;
+    }
+    let mut contact_points = result.to_authorities();
+    contact_points.sort_by(|left, right| compare_authorities(left, right));
+    contact_points.dedup();
+    if self.stable_since.is_some() && contact_points == self.contact_points {
+      return;
+    }
+    self.probed.retain(|authority, _| contact_points.contains(authority));
+    self.contact_points = contact_points;
+    self.stable_since = Some(now);
+  }
+
+  /// Returns contact points other than the local node that should be probed.
+  #[must_use]
+  pub fn contact_points_to_probe(&self) -> Vec<String> {
+    self.contact_points.iter().filter(|authority| **authority != self.self_authority).cloned().collect()
+  }
+
+  /// Records that `authority` answered a probe with its current seed nodes.
+  ///
+  /// An empty list means the contact point has not joined a cluster yet.
+  pub fn record_probe_success(&mut self, authority: &str, seed_nodes: Vec<String>) {
+    if self.contact_points.iter().any(|contact_point| contact_point == authority) {
+      self.probed.insert(String::from(authority), seed_nodes);
+    }
+  }
+
+  /// Records that probing `authority` failed.
+  pub fn record_probe_failure(&mut self, authority: &str) {
+    self.probed.remove(authority);
+  }
+
+  /// Returns the bootstrap decision at `now`.
+  ///
+  /// A join decision is returned again on every call until [`Self::mark_joined`] records that it
+  /// was applied; later calls return [`ClusterBootstrapDecision::Completed`].
+  pub fn decide(&mut self, now: TimerInstant) -> ClusterBootstrapDecision {
+    if self.is_shutdown {
+      return ClusterBootstrapDecision::Pending { reason: SHUT_DOWN };
+    }
+    if self.is_completed {
+      return ClusterBootstrapDecision::Completed;
+    }
+    self.evaluate(now)
+  }
+
+  /// Records that the local node joined the cluster as decided.
+  ///
+  /// Drivers call this only once the join succeeded, so a failed join is retried on the next
+  /// decision.
+  pub const fn mark_joined(&mut self) {
+    self.is_completed = true;
+  }
+
+  /// Stops producing join decisions.
+  pub const fn shutdown(&mut self) {
+    self.is_shutdown = true;
+  }
+
+  fn evaluate(&self, now: TimerInstant) -> ClusterBootstrapDecision {
+    let mut seed_nodes: Vec<String> =
+      self.probed.values().flatten().filter(|authority| **authority != self.self_authority).cloned().collect();
+    if !seed_nodes.is_empty() {
+      seed_nodes.sort_by(|left, right| compare_authorities(left, right));
+      seed_nodes.dedup();
+      return ClusterBootstrapDecision::JoinSeedNodes(seed_nodes);
+    }
+    if self.contact_points.len() < self.config.required_contact_point_count() {
+      return ClusterBootstrapDecision::Pending { reason: INSUFFICIENT_CONTACT_POINTS };
+    }
+    if !self.contact_points.contains(&self.self_authority) {
+      return ClusterBootstrapDecision::Pending { reason: SELF_NOT_DISCOVERED };
+    }
+    let stable_for = self.stable_since.map_or(Duration::ZERO, |since| elapsed(since, now));
+    if stable_for < self.config.stable_margin() {
+      return ClusterBootstrapDecision::Pending { reason: NOT_STABLE };
+    }
+    if self.contact_points_to_probe().iter().any(|authority| !self.probed.contains_key(authority)) {
+      return ClusterBootstrapDecision::Pending { reason: NOT_ALL_PROBED };
+    }
+    if self.contact_points.first() == Some(&self.self_authority) {
+      ClusterBootstrapDecision::JoinSelf
+    } else {
+      ClusterBootstrapDecision::Pending { reason: WAITING_FOR_LOWEST }
+    }
+  }
+}
+
+/// Orders authorities by IP address and port, falling back to host name ordering.
+fn compare_authorities(left: &str, right: &str) -> Ordering {
+  authority_key(left).cmp(&authority_key(right)).then_with(|| left.cmp(right))
+}
+
+fn authority_key(authority: &str) -> (bool, Option<IpAddr>, &str, u16) {
+  let (host, port) = match authority.rsplit_once(':') {
+    | Some((host, port)) => (host, port.parse::<u16>().unwrap_or(0)),
+    | None => (authority, 0),
+  };
+  let host = host.trim_start_matches('[').trim_end_matches(']');
+  // IP アドレスをホスト名より前に並べる
+  match host.parse::<IpAddr>() {
+    | Ok(address) => (false, Some(address), "", port),
+    | Err(_) => (true, None, host, port),
+  }
+}
+
+fn elapsed(since: TimerInstant, now: TimerInstant) -> Duration {
+  let to_duration = |instant: TimerInstant| {
+    let nanos = instant.resolution().as_nanos().saturating_mul(u128::from(instant.ticks()));
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+  };
+  to_duration(now).saturating_sub(to_duration(since))
+}
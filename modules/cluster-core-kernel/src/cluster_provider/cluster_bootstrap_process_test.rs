@@ -0,0 +1,160 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::time::Duration;
+
+use fraktor_utils_core_rs::time::TimerInstant;
+
+use crate::{
+  ClusterProviderError,
+  cluster_provider::{
+    ClusterBootstrapConfig, ClusterBootstrapDecision, ClusterBootstrapProcess, DiscoveredAuthority, DiscoveryResult,
+  },
+};
+
+fn at(seconds: u64) -> TimerInstant {
+  TimerInstant::from_ticks(seconds, Duration::from_secs(1))
+}
+
+fn discovered(authorities: &[&str], seconds: u64) -> DiscoveryResult {
+  DiscoveryResult::discovered(
+    authorities
+      .iter()
+      .map(|authority| DiscoveredAuthority::new(String::from(*authority), String::from("test"), at(seconds)))
+      .collect(),
+  )
+}
+
+fn process(self_authority: &str) -> ClusterBootstrapProcess {
+  let config =
+    ClusterBootstrapConfig::new().with_stable_margin(Duration::from_secs(5)).with_required_contact_point_count(3);
+  ClusterBootstrapProcess::new(config, self_authority)
+}
+
+fn pending(reason: &'static str) -> ClusterBootstrapDecision {
+  ClusterBootstrapDecision::Pending { reason }
+}
+
+fn confirm_all(process: &mut ClusterBootstrapProcess) {
+  for authority in process.contact_points_to_probe() {
+    process.record_probe_success(&authority, Vec::new());
+  }
+}
+
+#[test]
+fn contact_points_are_ordered_by_numeric_address() {
+  let mut process = process("10.0.0.9:2552");
+
+  process.observe_contact_points(&discovered(&["seed.local:2552", "10.0.0.10:2552", "10.0.0.9:2552"], 0), at(0));
+
+  assert_eq!(process.contact_points(), &[
+    String::from("10.0.0.9:2552"),
+    String::from("10.0.0.10:2552"),
+    String::from("seed.local:2552")
+  ]);
+  assert_eq!(process.contact_points_to_probe(), vec![String::from("10.0.0.10:2552"), String::from("seed.local:2552")]);
+}
+
+#[test]
+fn lowest_address_joins_itself_after_stable_margin_and_probes() {
+  let mut process = process("10.0.0.1:2552");
+  process.observe_contact_points(&discovered(&["10.0.0.2:2552", "10.0.0.1:2552", "10.0.0.3:2552"], 0), at(0));
+
+  assert_eq!(process.decide(at(1)), pending("contact points have not been stable for the stable margin"));
+  assert_eq!(process.decide(at(5)), pending("not all contact points have been probed successfully"));
+
+  confirm_all(&mut process);
+
+  assert_eq!(process.decide(at(5)), ClusterBootstrapDecision::JoinSelf);
+  process.mark_joined();
+  assert_eq!(process.decide(at(6)), ClusterBootstrapDecision::Completed);
+  assert!(process.is_completed());
+}
+
+#[test]
+fn join_decision_is_repeated_until_the_join_is_recorded() {
+  let mut process = process("10.0.0.1:2552");
+  process.observe_contact_points(&discovered(&["10.0.0.2:2552", "10.0.0.1:2552", "10.0.0.3:2552"], 0), at(0));
+  confirm_all(&mut process);
+
+  assert_eq!(process.decide(at(5)), ClusterBootstrapDecision::JoinSelf);
+  assert_eq!(process.decide(at(6)), ClusterBootstrapDecision::JoinSelf);
+  assert!(!process.is_completed());
+}
+
+#[test]
+fn other_nodes_wait_for_the_lowest_address_and_then_join_it() {
+  let mut process = process("10.0.0.2:2552");
+  process.observe_contact_points(&discovered(&["10.0.0.1:2552", "10.0.0.2:2552", "10.0.0.3:2552"], 0), at(0));
+  confirm_all(&mut process);
+
+  assert_eq!(process.decide(at(10)), pending("waiting for the lowest address contact point to form the cluster"));
+
+  process.record_probe_success("10.0.0.1:2552", vec![String::from("10.0.0.1:2552")]);
+
+  assert_eq!(process.decide(at(11)), ClusterBootstrapDecision::JoinSeedNodes(vec![String::from("10.0.0.1:2552")]));
+}
+
+#[test]
+fn existing_cluster_is_joined_without_waiting_for_stability() {
+  let mut process = process("10.0.0.5:2552");
+  process.observe_contact_points(&discovered(&["10.0.0.5:2552", "10.0.0.4:2552"], 0), at(0));
+
+  process.record_probe_success("10.0.0.4:2552", vec![String::from("10.0.0.4:2552"), String::from("10.0.0.1:2552")]);
+
+  assert_eq!(
+    process.decide(at(0)),
+    ClusterBootstrapDecision::JoinSeedNodes(vec![String::from("10.0.0.1:2552"), String::from("10.0.0.4:2552")])
+  );
+}
+
+#[test]
+fn required_contact_point_count_prevents_partial_formation() {
+  let mut process = process("10.0.0.1:2552");
+  process.observe_contact_points(&discovered(&["10.0.0.1:2552", "10.0.0.2:2552"], 0), at(0));
+  confirm_all(&mut process);
+
+  assert_eq!(process.decide(at(60)), pending("fewer contact points than required"));
+}
+
+#[test]
+fn changed_contact_points_restart_the_stable_margin() {
+  let mut process = process("10.0.0.1:2552");
+  process.observe_contact_points(&discovered(&["10.0.0.1:2552", "10.0.0.2:2552", "10.0.0.3:2552"], 0), at(0));
+  confirm_all(&mut process);
+
+  process.observe_contact_points(&discovered(&["10.0.0.1:2552", "10.0.0.2:2552", "10.0.0.3:2552"], 3), at(3));
+  process.observe_contact_points(&discovered(&["10.0.0.1:2552", "10.0.0.2:2552", "10.0.0.4:2552"], 4), at(4));
+  process.observe_contact_points(
+    &DiscoveryResult::failed(String::from("test"), at(5), ClusterProviderError::join("timeout")),
+    at(5),
+  );
+
+  assert_eq!(process.decide(at(8)), pending("contact points have not been stable for the stable margin"));
+  assert_eq!(process.decide(at(9)), pending("not all contact points have been probed successfully"));
+
+  process.record_probe_success("10.0.0.4:2552", Vec::new());
+  process.record_probe_failure("10.0.0.2:2552");
+  assert_eq!(process.decide(at(9)), pending("not all contact points have been probed successfully"));
+
+  process.record_probe_success("10.0.0.2:2552", Vec::new());
+  assert_eq!(process.decide(at(9)), ClusterBootstrapDecision::JoinSelf);
+}
+
+#[test]
+fn local_node_must_be_discovered_before_forming() {
+  let mut process = process("10.0.0.1:2552");
+  process.observe_contact_points(&discovered(&["10.0.0.2:2552", "10.0.0.3:2552", "10.0.0.4:2552"], 0), at(0));
+  confirm_all(&mut process);
+
+  assert_eq!(process.decide(at(10)), pending("local node is not among the discovered contact points"));
+}
+
+#[test]
+fn shutdown_stops_join_decisions() {
+  let mut process = process("10.0.0.5:2552");
+  process.observe_contact_points(&discovered(&["10.0.0.5:2552", "10.0.0.4:2552"], 0), at(0));
+  process.record_probe_success("10.0.0.4:2552", vec![String::from("10.0.0.4:2552")]);
+
+  process.shutdown();
+
+  assert_eq!(process.decide(at(0)), pending("bootstrap has been shut down"));
+}
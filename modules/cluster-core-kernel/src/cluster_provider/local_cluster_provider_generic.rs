@@ -197,6 +197,12 @@ impl LocalClusterProvider {
     self.startup_mode.is_some()
   }
 
+  /// Returns the mode the provider was started in.
+  #[must_use]
+  pub const fn startup_mode(&self) -> Option<StartupMode> {
+    self.startup_mode
+  }
+
   /// Returns the event stream reference.
   #[must_use]
   #[allow(clippy::missing_const_for_fn)]
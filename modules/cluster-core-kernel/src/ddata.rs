@@ -1,10 +1,15 @@
-//! Distributed Data CRDT base types and protocol vocabulary.
+//! Distributed Data CRDT base types, protocol vocabulary and the Replicator runtime.
 
 mod counter_arithmetic_error;
+mod data_envelope;
 mod delete;
 mod delete_response;
 mod delete_write_outcome;
 mod delta_replicated_data;
+mod durable_data_envelope;
+mod durable_store;
+mod durable_store_error;
+mod erased_replicator_data;
 mod flag;
 mod flush_changes;
 mod g_counter;
@@ -19,12 +24,23 @@ mod or_multimap;
 mod or_set;
 mod pn_counter;
 mod pn_counter_map;
+mod pruning_state;
 mod read_consistency;
 mod removed_node_pruning;
 mod replica_count;
 mod replicated_data;
 mod replicated_delta;
+mod replicator;
+mod replicator_command;
+mod replicator_data;
 mod replicator_entry;
+mod replicator_extension;
+mod replicator_extension_id;
+mod replicator_extension_installer;
+mod replicator_operation;
+mod replicator_peer_message;
+mod replicator_settings;
+mod replicator_tick;
 mod requires_causal_delivery_of_deltas;
 mod self_unique_address;
 mod subscribe;
@@ -38,10 +54,15 @@ mod version_vector_ordering;
 mod write_consistency;
 
 pub use counter_arithmetic_error::CounterArithmeticError;
+pub(crate) use data_envelope::DataEnvelope;
 pub use delete::Delete;
 pub use delete_response::DeleteResponse;
 pub use delete_write_outcome::DeleteWriteOutcome;
 pub use delta_replicated_data::DeltaReplicatedData;
+pub use durable_data_envelope::DurableDataEnvelope;
+pub use durable_store::DurableStore;
+pub use durable_store_error::DurableStoreError;
+pub(crate) use erased_replicator_data::ErasedReplicatorData;
 pub use flag::Flag;
 pub use flush_changes::FlushChanges;
 pub use g_counter::GCounter;
@@ -59,12 +80,23 @@ pub use or_multimap::ORMultiMap;
 pub use or_set::ORSet;
 pub use pn_counter::PNCounter;
 pub use pn_counter_map::PNCounterMap;
+pub(crate) use pruning_state::PruningState;
 pub use read_consistency::ReadConsistency;
 pub use removed_node_pruning::RemovedNodePruning;
 pub use replica_count::ReplicaCount;
 pub use replicated_data::ReplicatedData;
 pub use replicated_delta::ReplicatedDelta;
+pub use replicator::Replicator;
+pub use replicator_command::ReplicatorCommand;
+pub use replicator_data::ReplicatorData;
 pub use replicator_entry::ReplicatorEntry;
+pub use replicator_extension::ReplicatorExtension;
+pub use replicator_extension_id::ReplicatorExtensionId;
+pub use replicator_extension_installer::ReplicatorExtensionInstaller;
+pub(crate) use replicator_operation::ReplicatorOperation;
+pub(crate) use replicator_peer_message::ReplicatorPeerMessage;
+pub use replicator_settings::ReplicatorSettings;
+pub(crate) use replicator_tick::ReplicatorTick;
 pub use requires_causal_delivery_of_deltas::RequiresCausalDeliveryOfDeltas;
 pub use self_unique_address::SelfUniqueAddress;
 pub use subscribe::Subscribe;
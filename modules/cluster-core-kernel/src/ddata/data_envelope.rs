@@ -0,0 +1,185 @@
+//! Replicated value of one key together with its pruning markers.
+
+#[cfg(test)]
+#[path = "data_envelope_test.rs"]
+mod tests;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+
+use fraktor_remote_core_rs::address::UniqueAddress;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{ErasedReplicatorData, PruningState, ReplicatorEntry};
+
+/// Value of one key as exchanged between Replicators.
+///
+/// An envelope without data is a tombstone: deletion wins every merge and is never undone.
+#[derive(Clone)]
+pub(crate) struct DataEnvelope {
+  data:    Option<ArcShared<dyn ErasedReplicatorData>>,
+  pruning: BTreeMap<UniqueAddress, PruningState>,
+}
+
+impl DataEnvelope {
+  /// Creates an envelope holding `data` without pruning markers.
+  #[must_use]
+  pub(crate) const fn new(data: ArcShared<dyn ErasedReplicatorData>) -> Self {
+    Self { data: Some(data), pruning: BTreeMap::new() }
+  }
+
+  /// Creates a tombstone envelope.
+  #[must_use]
+  pub(crate) const fn deleted() -> Self {
+    Self { data: None, pruning: BTreeMap::new() }
+  }
+
+  /// Returns the stored value, or `None` for a tombstone.
+  #[must_use]
+  pub(crate) fn data(&self) -> Option<&dyn ErasedReplicatorData> {
+    self.data.as_deref()
+  }
+
+  /// Returns a shared handle to the stored value, or `None` for a tombstone.
+  #[must_use]
+  pub(crate) fn data_shared(&self) -> Option<ArcShared<dyn ErasedReplicatorData>> {
+    self.data.clone()
+  }
+
+  /// Returns true when this envelope is a tombstone.
+  #[must_use]
+  pub(crate) const fn is_deleted(&self) -> bool {
+    self.data.is_none()
+  }
+
+  /// Returns the pruning markers keyed by removed node.
+  #[must_use]
+  pub(crate) const fn pruning(&self) -> &BTreeMap<UniqueAddress, PruningState> {
+    &self.pruning
+  }
+
+  /// Returns this envelope with its value replaced by `data`, keeping the pruning markers.
+  ///
+  /// Residual contribution of nodes whose pruning has been performed is removed from `data`.
+  #[must_use]
+  pub(crate) fn with_data(&self, data: ArcShared<dyn ErasedReplicatorData>) -> Self {
+    Self { data: Some(cleanup_performed(data, &self.pruning)), pruning: self.pruning.clone() }
+  }
+
+  /// Returns the typed entry of `envelope`, or `None` when it holds a value of another type.
+  #[must_use]
+  pub(crate) fn entry<D: Clone + 'static>(envelope: Option<&Self>) -> Option<ReplicatorEntry<D>> {
+    let Some(envelope) = envelope else {
+      return Some(ReplicatorEntry::Missing);
+    };
+    match envelope.data() {
+      | None => Some(ReplicatorEntry::Deleted),
+      | Some(data) => data.as_any().downcast_ref::<D>().map(|data| ReplicatorEntry::Present(data.clone())),
+    }
+  }
+
+  /// Returns the converged envelope of `self` and `other`.
+  ///
+  /// Residual contribution of nodes whose pruning has been performed is removed from the merged
+  /// value. Returns `None` when the envelopes hold values of different types.
+  #[must_use]
+  pub(crate) fn merge(&self, other: &Self) -> Option<Self> {
+    let (Some(data), Some(other_data)) = (&self.data, &other.data) else {
+      return Some(Self::deleted());
+    };
+    let mut pruning = self.pruning.clone();
+    for (removed_node, state) in &other.pruning {
+      let merged = match pruning.get(removed_node) {
+        | Some(current) => current.merge(state),
+        | None => state.clone(),
+      };
+      pruning.insert(removed_node.clone(), merged);
+    }
+    let merged = data.merge_erased(&**other_data)?;
+    Some(Self { data: Some(cleanup_performed(merged, &pruning)), pruning })
+  }
+
+  /// Returns true when both envelopes hold equal values or are both tombstones.
+  #[must_use]
+  pub(crate) fn same_value(&self, other: &Self) -> bool {
+    match (&self.data, &other.data) {
+      | (None, None) => true,
+      | (Some(data), Some(other_data)) => data.eq_erased(&**other_data),
+      | _ => false,
+    }
+  }
+
+  /// Returns the removed nodes in `removed_nodes` that still contribute to the value and have no
+  /// pruning marker yet.
+  #[must_use]
+  pub(crate) fn needs_pruning(&self, removed_nodes: &BTreeSet<UniqueAddress>) -> BTreeSet<UniqueAddress> {
+    let Some(data) = &self.data else {
+      return BTreeSet::new();
+    };
+    data
+      .pruning_nodes_erased()
+      .into_iter()
+      .filter(|node| removed_nodes.contains(node) && !self.pruning.contains_key(node))
+      .collect()
+  }
+
+  /// Adds an initialized marker for `removed_node` owned by `owner`.
+  #[must_use]
+  pub(crate) fn initialize_pruning(&self, removed_node: &UniqueAddress, owner: &UniqueAddress) -> Self {
+    let mut pruning = self.pruning.clone();
+    let seen = BTreeSet::from([owner.clone()]);
+    pruning.insert(removed_node.clone(), PruningState::Initialized { owner: owner.clone(), seen });
+    Self { data: self.data.clone(), pruning }
+  }
+
+  /// Records that `node` has observed every initialized marker of this envelope.
+  #[must_use]
+  pub(crate) fn add_seen(&self, node: &UniqueAddress) -> Self {
+    let pruning = self
+      .pruning
+      .iter()
+      .map(|(removed_node, state)| {
+        let state = match state {
+          | PruningState::Initialized { owner, seen } if !seen.contains(node) => {
+            let mut seen = seen.clone();
+            seen.insert(node.clone());
+            PruningState::Initialized { owner: owner.clone(), seen }
+          },
+          | _ => state.clone(),
+        };
+        (removed_node.clone(), state)
+      })
+      .collect();
+    Self { data: self.data.clone(), pruning }
+  }
+
+  /// Collapses the contribution of `removed_node` into `collapse_into` and marks it performed.
+  ///
+  /// Returns `None` when the value cannot be pruned.
+  #[must_use]
+  pub(crate) fn prune(&self, removed_node: &UniqueAddress, collapse_into: &UniqueAddress) -> Option<Self> {
+    let data = self.data.as_ref()?.prune_node_erased(removed_node, collapse_into)?;
+    let mut pruning = self.pruning.clone();
+    pruning.insert(removed_node.clone(), PruningState::Performed);
+    Some(Self { data: Some(data), pruning })
+  }
+
+  /// Drops the pruning marker of `removed_node`.
+  #[must_use]
+  pub(crate) fn without_marker(&self, removed_node: &UniqueAddress) -> Self {
+    let mut pruning = self.pruning.clone();
+    pruning.remove(removed_node);
+    Self { data: self.data.clone(), pruning }
+  }
+}
+
+fn cleanup_performed(
+  mut data: ArcShared<dyn ErasedReplicatorData>,
+  pruning: &BTreeMap<UniqueAddress, PruningState>,
+) -> ArcShared<dyn ErasedReplicatorData> {
+  for (removed_node, state) in pruning {
+    if matches!(state, PruningState::Performed) {
+      data = data.cleanup_node_erased(removed_node);
+    }
+  }
+  data
+}
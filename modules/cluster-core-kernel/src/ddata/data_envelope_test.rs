@@ -0,0 +1,129 @@
+use alloc::collections::BTreeSet;
+
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::DataEnvelope;
+use crate::ddata::{ErasedReplicatorData, Flag, GCounter, PruningState, ReplicatorEntry, SelfUniqueAddress};
+
+fn node(name: &str, uid: u64) -> UniqueAddress {
+  UniqueAddress::new(Address::new("sys", name, 2552), uid)
+}
+
+fn counter(increments: &[(&UniqueAddress, u64)]) -> GCounter {
+  let mut counter = GCounter::new();
+  for (node, amount) in increments {
+    counter = counter.increment(&SelfUniqueAddress::new((*node).clone()), *amount).expect("small increments fit");
+  }
+  counter
+}
+
+fn envelope(counter: GCounter) -> DataEnvelope {
+  DataEnvelope::new(ArcShared::new(counter) as ArcShared<dyn ErasedReplicatorData>)
+}
+
+fn counter_value(envelope: &DataEnvelope) -> u128 {
+  match DataEnvelope::entry::<GCounter>(Some(envelope)) {
+    | Some(ReplicatorEntry::Present(counter)) => counter.value().expect("value fits"),
+    | _ => panic!("envelope must hold a counter"),
+  }
+}
+
+#[test]
+fn entry_maps_missing_deleted_and_present_values() {
+  let a = node("a", 1);
+
+  assert!(matches!(DataEnvelope::entry::<GCounter>(None), Some(ReplicatorEntry::Missing)));
+  assert!(matches!(DataEnvelope::entry::<GCounter>(Some(&DataEnvelope::deleted())), Some(ReplicatorEntry::Deleted)));
+  assert_eq!(counter_value(&envelope(counter(&[(&a, 3)]))), 3);
+}
+
+#[test]
+fn entry_of_another_type_is_none() {
+  let flag = DataEnvelope::new(ArcShared::new(Flag::disabled()) as ArcShared<dyn ErasedReplicatorData>);
+
+  assert!(DataEnvelope::entry::<GCounter>(Some(&flag)).is_none());
+}
+
+#[test]
+fn merge_converges_values_and_deletion_wins() {
+  let a = node("a", 1);
+  let b = node("b", 2);
+  let left = envelope(counter(&[(&a, 2)]));
+  let right = envelope(counter(&[(&b, 5)]));
+
+  let merged = left.merge(&right).expect("same type merges");
+  assert_eq!(counter_value(&merged), 7);
+  assert!(left.merge(&DataEnvelope::deleted()).expect("deletion merges").is_deleted());
+  assert!(DataEnvelope::deleted().merge(&right).expect("deletion merges").is_deleted());
+}
+
+#[test]
+fn merge_of_different_types_is_none() {
+  let flag = DataEnvelope::new(ArcShared::new(Flag::disabled()) as ArcShared<dyn ErasedReplicatorData>);
+
+  assert!(envelope(GCounter::new()).merge(&flag).is_none());
+}
+
+#[test]
+fn same_value_compares_data_and_tombstones() {
+  let a = node("a", 1);
+
+  assert!(envelope(counter(&[(&a, 1)])).same_value(&envelope(counter(&[(&a, 1)]))));
+  assert!(!envelope(counter(&[(&a, 1)])).same_value(&envelope(counter(&[(&a, 2)]))));
+  assert!(DataEnvelope::deleted().same_value(&DataEnvelope::deleted()));
+  assert!(!DataEnvelope::deleted().same_value(&envelope(GCounter::new())));
+}
+
+#[test]
+fn needs_pruning_skips_nodes_with_markers() {
+  let a = node("a", 1);
+  let removed = node("z", 9);
+  let value = envelope(counter(&[(&a, 1), (&removed, 4)]));
+  let removed_nodes = BTreeSet::from([removed.clone()]);
+
+  assert_eq!(value.needs_pruning(&removed_nodes), removed_nodes);
+  assert!(value.initialize_pruning(&removed, &a).needs_pruning(&removed_nodes).is_empty());
+}
+
+#[test]
+fn initialized_markers_collect_seen_nodes_across_merges() {
+  let a = node("a", 1);
+  let b = node("b", 2);
+  let removed = node("z", 9);
+  let value = envelope(counter(&[(&removed, 4)])).initialize_pruning(&removed, &a);
+
+  let merged = value.merge(&value.add_seen(&b)).expect("same type merges");
+
+  let expected = PruningState::Initialized { owner: a.clone(), seen: BTreeSet::from([a, b]) };
+  assert_eq!(merged.pruning().get(&removed), Some(&expected));
+}
+
+#[test]
+fn prune_collapses_contribution_and_later_merges_drop_residue() {
+  let a = node("a", 1);
+  let removed = node("z", 9);
+  let value = envelope(counter(&[(&a, 1), (&removed, 4)])).initialize_pruning(&removed, &a);
+
+  let pruned = value.prune(&removed, &a).expect("counter prunes");
+  assert_eq!(pruned.pruning().get(&removed), Some(&PruningState::Performed));
+  assert_eq!(counter_value(&pruned), 5);
+
+  let stale = envelope(counter(&[(&removed, 6)]));
+  let merged = pruned.merge(&stale).expect("same type merges");
+  assert_eq!(counter_value(&merged), 5);
+  assert_eq!(counter_value(&pruned.with_data(ArcShared::new(counter(&[(&removed, 8)])))), 0);
+}
+
+#[test]
+fn without_marker_drops_only_the_given_node() {
+  let a = node("a", 1);
+  let first = node("y", 8);
+  let second = node("z", 9);
+  let value = envelope(GCounter::new()).initialize_pruning(&first, &a).initialize_pruning(&second, &a);
+
+  let remaining = value.without_marker(&first);
+
+  assert!(!remaining.pruning().contains_key(&first));
+  assert!(remaining.pruning().contains_key(&second));
+}
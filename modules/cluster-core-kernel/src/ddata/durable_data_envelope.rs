@@ -0,0 +1,68 @@
+//! Value of one durable key as written to and loaded from a durable store.
+
+#[cfg(test)]
+#[path = "durable_data_envelope_test.rs"]
+mod tests;
+
+use core::any::Any;
+
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{DataEnvelope, ErasedReplicatorData, ReplicatorData};
+
+/// Stored value of a durable key, or the tombstone of a deleted key.
+///
+/// Pruning markers are not stored; the Replicator re-establishes them after loading.
+#[derive(Clone)]
+pub struct DurableDataEnvelope {
+  data: Option<ArcShared<dyn ErasedReplicatorData>>,
+}
+
+impl DurableDataEnvelope {
+  /// Creates an envelope holding `data`.
+  #[must_use]
+  pub fn new<D: ReplicatorData>(data: D) -> Self {
+    Self { data: Some(ArcShared::new(data)) }
+  }
+
+  /// Creates the tombstone of a deleted key.
+  #[must_use]
+  pub const fn deleted() -> Self {
+    Self { data: None }
+  }
+
+  /// Returns true when this envelope is the tombstone of a deleted key.
+  #[must_use]
+  pub const fn is_deleted(&self) -> bool {
+    self.data.is_none()
+  }
+
+  /// Returns the stored value for serialization, or `None` for a tombstone.
+  #[must_use]
+  pub fn data(&self) -> Option<&(dyn Any + Send + Sync)> {
+    self.data.as_deref().map(ErasedReplicatorData::as_any)
+  }
+
+  /// Returns the type name of the stored value, or `None` for a tombstone.
+  #[must_use]
+  pub fn type_name(&self) -> Option<&'static str> {
+    self.data.as_deref().map(ErasedReplicatorData::type_name)
+  }
+
+  /// Returns the stored value when it has type `D`.
+  #[must_use]
+  pub fn data_as<D: 'static>(&self) -> Option<&D> {
+    self.data()?.downcast_ref::<D>()
+  }
+
+  pub(crate) fn from_envelope(envelope: &DataEnvelope) -> Self {
+    Self { data: envelope.data_shared() }
+  }
+
+  pub(crate) fn into_envelope(self) -> DataEnvelope {
+    match self.data {
+      | Some(data) => DataEnvelope::new(data),
+      | None => DataEnvelope::deleted(),
+    }
+  }
+}
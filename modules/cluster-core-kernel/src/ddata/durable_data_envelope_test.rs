@@ -0,0 +1,35 @@
+use core::any::type_name;
+
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::DurableDataEnvelope;
+use crate::ddata::{DataEnvelope, ErasedReplicatorData, Flag, GCounter};
+
+#[test]
+fn stored_values_are_exposed_by_type() {
+  let envelope = DurableDataEnvelope::new(Flag::disabled().switch_on());
+
+  assert!(!envelope.is_deleted());
+  assert!(envelope.data_as::<Flag>().expect("flag value").is_enabled());
+  assert!(envelope.data_as::<GCounter>().is_none());
+  assert_eq!(envelope.type_name(), Some(type_name::<Flag>()));
+  assert!(envelope.data().is_some_and(|data| data.is::<Flag>()));
+}
+
+#[test]
+fn tombstones_have_no_value() {
+  let envelope = DurableDataEnvelope::deleted();
+
+  assert!(envelope.is_deleted());
+  assert!(envelope.data().is_none());
+  assert!(envelope.type_name().is_none());
+}
+
+#[test]
+fn conversion_keeps_values_and_tombstones() {
+  let value = DataEnvelope::new(ArcShared::new(Flag::disabled().switch_on()) as ArcShared<dyn ErasedReplicatorData>);
+
+  let restored = DurableDataEnvelope::from_envelope(&value).into_envelope();
+  assert!(restored.same_value(&value));
+  assert!(DurableDataEnvelope::from_envelope(&DataEnvelope::deleted()).into_envelope().is_deleted());
+}
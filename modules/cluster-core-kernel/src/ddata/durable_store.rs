@@ -0,0 +1,35 @@
+//! Storage port keeping durable keys across full-cluster restarts.
+
+use alloc::{collections::BTreeMap, string::String};
+
+use super::{DurableDataEnvelope, DurableStoreError};
+
+/// Durable storage of the keys selected by
+/// [`ReplicatorSettings::with_durable_keys`](super::ReplicatorSettings::with_durable_keys).
+///
+/// The Replicator loads every stored key before it serves its first command, and stores the
+/// latest value of each changed durable key, batched on the configured write-behind interval.
+/// Implementations are shared by every incarnation of the Replicator and are called from its
+/// message handling, so each call should complete promptly.
+pub trait DurableStore: Send + Sync {
+  /// Loads every stored key.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`DurableStoreError::LoadFailed`] when the stored values cannot be read.
+  fn load_all(&self) -> Result<BTreeMap<String, DurableDataEnvelope>, DurableStoreError>;
+
+  /// Loads the stored value of `key`, or `None` when it has not been stored.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`DurableStoreError::LoadFailed`] when the stored value cannot be read.
+  fn load(&self, key: &str) -> Result<Option<DurableDataEnvelope>, DurableStoreError>;
+
+  /// Stores one batch of values, replacing the previously stored value of each key.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`DurableStoreError::StoreFailed`] when the batch cannot be stored.
+  fn store(&self, entries: &BTreeMap<String, DurableDataEnvelope>) -> Result<(), DurableStoreError>;
+}
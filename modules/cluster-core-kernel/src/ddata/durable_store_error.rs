@@ -0,0 +1,18 @@
+//! Errors returned by durable stores of replicated data.
+
+use alloc::string::String;
+
+/// Failure of a [`DurableStore`](super::DurableStore) operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DurableStoreError {
+  /// Stored values could not be loaded.
+  LoadFailed {
+    /// Failure reason.
+    reason: String,
+  },
+  /// Values could not be stored.
+  StoreFailed {
+    /// Failure reason.
+    reason: String,
+  },
+}
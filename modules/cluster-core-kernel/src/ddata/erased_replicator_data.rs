@@ -0,0 +1,95 @@
+//! Type-erased view of values stored by the Replicator.
+
+use alloc::collections::BTreeSet;
+use core::any::{Any, type_name};
+
+use fraktor_remote_core_rs::address::UniqueAddress;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::ReplicatorData;
+
+/// Object-safe wrapper over [`ReplicatorData`] so one Replicator can hold values of any type.
+///
+/// Operations combining two values return `None` when the values have different concrete types.
+pub(crate) trait ErasedReplicatorData: Send + Sync {
+  fn as_any(&self) -> &(dyn Any + Send + Sync);
+
+  fn type_name(&self) -> &'static str;
+
+  fn merge_erased(&self, other: &dyn ErasedReplicatorData) -> Option<ArcShared<dyn ErasedReplicatorData>>;
+
+  fn merge_delta_erased(&self, delta: &dyn ErasedReplicatorData) -> Option<ArcShared<dyn ErasedReplicatorData>>;
+
+  fn delta_zero_erased(&self) -> ArcShared<dyn ErasedReplicatorData>;
+
+  #[allow(clippy::type_complexity)]
+  fn split_delta_erased(&self) -> Option<(ArcShared<dyn ErasedReplicatorData>, ArcShared<dyn ErasedReplicatorData>)>;
+
+  fn requires_causal_delivery_erased(&self) -> bool;
+
+  fn pruning_nodes_erased(&self) -> BTreeSet<UniqueAddress>;
+
+  fn prune_node_erased(
+    &self,
+    removed_node: &UniqueAddress,
+    collapse_into: &UniqueAddress,
+  ) -> Option<ArcShared<dyn ErasedReplicatorData>>;
+
+  fn cleanup_node_erased(&self, removed_node: &UniqueAddress) -> ArcShared<dyn ErasedReplicatorData>;
+
+  fn eq_erased(&self, other: &dyn ErasedReplicatorData) -> bool;
+}
+
+impl<D: ReplicatorData> ErasedReplicatorData for D {
+  fn as_any(&self) -> &(dyn Any + Send + Sync) {
+    self
+  }
+
+  fn type_name(&self) -> &'static str {
+    type_name::<D>()
+  }
+
+  fn merge_erased(&self, other: &dyn ErasedReplicatorData) -> Option<ArcShared<dyn ErasedReplicatorData>> {
+    let other = other.as_any().downcast_ref::<D>()?;
+    Some(ArcShared::new(self.merge(other)))
+  }
+
+  fn merge_delta_erased(&self, delta: &dyn ErasedReplicatorData) -> Option<ArcShared<dyn ErasedReplicatorData>> {
+    let delta = delta.as_any().downcast_ref::<D>()?;
+    Some(ArcShared::new(self.merge_delta_from(delta)))
+  }
+
+  fn delta_zero_erased(&self) -> ArcShared<dyn ErasedReplicatorData> {
+    ArcShared::new(self.delta_zero())
+  }
+
+  fn split_delta_erased(&self) -> Option<(ArcShared<dyn ErasedReplicatorData>, ArcShared<dyn ErasedReplicatorData>)> {
+    let (delta, reset) = self.split_delta()?;
+    Some((ArcShared::new(delta), ArcShared::new(reset)))
+  }
+
+  fn requires_causal_delivery_erased(&self) -> bool {
+    self.requires_causal_delivery()
+  }
+
+  fn pruning_nodes_erased(&self) -> BTreeSet<UniqueAddress> {
+    self.pruning_nodes()
+  }
+
+  fn prune_node_erased(
+    &self,
+    removed_node: &UniqueAddress,
+    collapse_into: &UniqueAddress,
+  ) -> Option<ArcShared<dyn ErasedReplicatorData>> {
+    let pruned = self.prune_node(removed_node, collapse_into)?;
+    Some(ArcShared::new(pruned))
+  }
+
+  fn cleanup_node_erased(&self, removed_node: &UniqueAddress) -> ArcShared<dyn ErasedReplicatorData> {
+    ArcShared::new(self.cleanup_node(removed_node))
+  }
+
+  fn eq_erased(&self, other: &dyn ErasedReplicatorData) -> bool {
+    other.as_any().downcast_ref::<D>().is_some_and(|other| self == other)
+  }
+}
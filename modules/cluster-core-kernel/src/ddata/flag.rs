@@ -4,7 +4,7 @@
 #[path = "flag_test.rs"]
 mod tests;
 
-use super::ReplicatedData;
+use super::{ReplicatedData, ReplicatorData};
 
 /// Boolean CRDT that can only move from disabled to enabled.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -38,6 +38,8 @@ impl ReplicatedData for Flag {
   }
 }
 
+impl ReplicatorData for Flag {}
+
 impl Default for Flag {
   fn default() -> Self {
     Self::disabled()
@@ -9,7 +9,8 @@ use alloc::collections::{BTreeMap, BTreeSet};
 use fraktor_remote_core_rs::address::UniqueAddress;
 
 use super::{
-  CounterArithmeticError, DeltaReplicatedData, RemovedNodePruning, ReplicatedData, ReplicatedDelta, SelfUniqueAddress,
+  CounterArithmeticError, DeltaReplicatedData, RemovedNodePruning, ReplicatedData, ReplicatedDelta, ReplicatorData,
+  SelfUniqueAddress,
 };
 
 /// Grow-only counter CRDT with per-node slots.
@@ -180,6 +181,32 @@ impl RemovedNodePruning for GCounter {
   }
 }
 
+impl ReplicatorData for GCounter {
+  fn split_delta(&self) -> Option<(Self, Self)> {
+    self.delta().map(|delta| (delta, self.reset_delta()))
+  }
+
+  fn merge_delta_from(&self, delta: &Self) -> Self {
+    self.merge_delta(delta)
+  }
+
+  fn delta_zero(&self) -> Self {
+    self.zero()
+  }
+
+  fn pruning_nodes(&self) -> BTreeSet<UniqueAddress> {
+    self.modified_by_nodes()
+  }
+
+  fn prune_node(&self, removed_node: &UniqueAddress, collapse_into: &UniqueAddress) -> Option<Self> {
+    self.prune(removed_node, collapse_into).ok()
+  }
+
+  fn cleanup_node(&self, removed_node: &UniqueAddress) -> Self {
+    self.pruning_cleanup(removed_node)
+  }
+}
+
 impl Default for GCounter {
   fn default() -> Self {
     Self::new()
@@ -10,7 +10,7 @@ use fraktor_remote_core_rs::address::UniqueAddress;
 
 use super::{
   CounterArithmeticError, DeltaReplicatedData, LWWRegister, ORMap, RemovedNodePruning, ReplicatedData, ReplicatedDelta,
-  RequiresCausalDeliveryOfDeltas, SelfUniqueAddress,
+  ReplicatorData, RequiresCausalDeliveryOfDeltas, SelfUniqueAddress,
 };
 
 /// Observed-remove map CRDT whose per-key values follow last-writer-wins semantics.
@@ -166,6 +166,40 @@ where
   }
 }
 
+impl<A, B> ReplicatorData for LWWMap<A, B>
+where
+  A: Clone + Ord + Send + Sync + 'static,
+  B: Clone + PartialEq + Send + Sync + 'static,
+{
+  fn split_delta(&self) -> Option<(Self, Self)> {
+    self.delta().map(|delta| (delta, self.reset_delta()))
+  }
+
+  fn merge_delta_from(&self, delta: &Self) -> Self {
+    self.merge_delta(delta)
+  }
+
+  fn delta_zero(&self) -> Self {
+    self.zero()
+  }
+
+  fn requires_causal_delivery(&self) -> bool {
+    true
+  }
+
+  fn pruning_nodes(&self) -> BTreeSet<UniqueAddress> {
+    self.modified_by_nodes()
+  }
+
+  fn prune_node(&self, removed_node: &UniqueAddress, collapse_into: &UniqueAddress) -> Option<Self> {
+    self.prune(removed_node, collapse_into).ok()
+  }
+
+  fn cleanup_node(&self, removed_node: &UniqueAddress) -> Self {
+    self.pruning_cleanup(removed_node)
+  }
+}
+
 impl<A, B> Default for LWWMap<A, B>
 where
   A: Clone + Ord,
@@ -8,7 +8,7 @@ use alloc::{collections::BTreeSet, format};
 
 use fraktor_remote_core_rs::address::{Address, UniqueAddress};
 
-use crate::ddata::{CounterArithmeticError, RemovedNodePruning, ReplicatedData, SelfUniqueAddress};
+use crate::ddata::{CounterArithmeticError, RemovedNodePruning, ReplicatedData, ReplicatorData, SelfUniqueAddress};
 
 /// Last-writer-wins register CRDT using timestamp and node ordering.
 ///
@@ -160,6 +160,23 @@ where
   }
 }
 
+impl<T> ReplicatorData for LWWRegister<T>
+where
+  T: Clone + PartialEq + Send + Sync + 'static,
+{
+  fn pruning_nodes(&self) -> BTreeSet<UniqueAddress> {
+    self.modified_by_nodes()
+  }
+
+  fn prune_node(&self, removed_node: &UniqueAddress, collapse_into: &UniqueAddress) -> Option<Self> {
+    self.prune(removed_node, collapse_into).ok()
+  }
+
+  fn cleanup_node(&self, removed_node: &UniqueAddress) -> Self {
+    self.pruning_cleanup(removed_node)
+  }
+}
+
 fn pruning_node_for(removed_node: &UniqueAddress) -> UniqueAddress {
   let address = removed_node.address();
   UniqueAddress::new(
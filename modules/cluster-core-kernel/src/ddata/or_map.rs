@@ -9,8 +9,8 @@ use alloc::collections::{BTreeMap, BTreeSet};
 use fraktor_remote_core_rs::address::UniqueAddress;
 
 use super::{
-  DeltaReplicatedData, ORSet, RemovedNodePruning, ReplicatedData, ReplicatedDelta, RequiresCausalDeliveryOfDeltas,
-  SelfUniqueAddress, VersionVector, or_set::pruning_node_for,
+  DeltaReplicatedData, ORSet, RemovedNodePruning, ReplicatedData, ReplicatedDelta, ReplicatorData,
+  RequiresCausalDeliveryOfDeltas, SelfUniqueAddress, VersionVector, or_set::pruning_node_for,
 };
 
 /// Observed-remove map CRDT, also known as OR-Map.
@@ -263,6 +263,40 @@ where
   }
 }
 
+impl<A, B> ReplicatorData for ORMap<A, B>
+where
+  A: Clone + Ord + Send + Sync + 'static,
+  B: RemovedNodePruning + PartialEq + Send + Sync + 'static,
+{
+  fn split_delta(&self) -> Option<(Self, Self)> {
+    self.delta().map(|delta| (delta, self.reset_delta()))
+  }
+
+  fn merge_delta_from(&self, delta: &Self) -> Self {
+    self.merge_delta(delta)
+  }
+
+  fn delta_zero(&self) -> Self {
+    self.zero()
+  }
+
+  fn requires_causal_delivery(&self) -> bool {
+    true
+  }
+
+  fn pruning_nodes(&self) -> BTreeSet<UniqueAddress> {
+    self.modified_by_nodes()
+  }
+
+  fn prune_node(&self, removed_node: &UniqueAddress, collapse_into: &UniqueAddress) -> Option<Self> {
+    self.prune(removed_node, collapse_into).ok()
+  }
+
+  fn cleanup_node(&self, removed_node: &UniqueAddress) -> Self {
+    self.pruning_cleanup(removed_node)
+  }
+}
+
 impl<A, B> Default for ORMap<A, B>
 where
   A: Clone + Ord,
@@ -14,8 +14,8 @@ use core::convert::Infallible;
 use fraktor_remote_core_rs::address::{Address, UniqueAddress};
 
 use super::{
-  DeltaReplicatedData, RemovedNodePruning, ReplicatedData, ReplicatedDelta, RequiresCausalDeliveryOfDeltas,
-  SelfUniqueAddress, VersionVector,
+  DeltaReplicatedData, RemovedNodePruning, ReplicatedData, ReplicatedDelta, ReplicatorData,
+  RequiresCausalDeliveryOfDeltas, SelfUniqueAddress, VersionVector,
 };
 
 /// Observed-remove set CRDT, also known as ORSWOT, where a concurrent add wins over a remove.
@@ -284,6 +284,39 @@ where
   }
 }
 
+impl<A> ReplicatorData for ORSet<A>
+where
+  A: Clone + Ord + Send + Sync + 'static,
+{
+  fn split_delta(&self) -> Option<(Self, Self)> {
+    self.delta().map(|delta| (delta, self.reset_delta()))
+  }
+
+  fn merge_delta_from(&self, delta: &Self) -> Self {
+    self.merge_delta(delta)
+  }
+
+  fn delta_zero(&self) -> Self {
+    self.zero()
+  }
+
+  fn requires_causal_delivery(&self) -> bool {
+    true
+  }
+
+  fn pruning_nodes(&self) -> BTreeSet<UniqueAddress> {
+    self.modified_by_nodes()
+  }
+
+  fn prune_node(&self, removed_node: &UniqueAddress, collapse_into: &UniqueAddress) -> Option<Self> {
+    self.prune(removed_node, collapse_into).ok()
+  }
+
+  fn cleanup_node(&self, removed_node: &UniqueAddress) -> Self {
+    self.pruning_cleanup(removed_node)
+  }
+}
+
 impl<A> Default for ORSet<A>
 where
   A: Clone + Ord,
@@ -11,7 +11,7 @@ use fraktor_remote_core_rs::address::UniqueAddress;
 
 use super::{
   CounterArithmeticError, DeltaReplicatedData, GCounter, RemovedNodePruning, ReplicatedData, ReplicatedDelta,
-  SelfUniqueAddress,
+  ReplicatorData, SelfUniqueAddress,
 };
 
 const I128_MIN_ABS: u128 = 1_u128 << 127;
@@ -237,6 +237,32 @@ impl RemovedNodePruning for PNCounter {
   }
 }
 
+impl ReplicatorData for PNCounter {
+  fn split_delta(&self) -> Option<(Self, Self)> {
+    self.delta().map(|delta| (delta, self.reset_delta()))
+  }
+
+  fn merge_delta_from(&self, delta: &Self) -> Self {
+    self.merge_delta(delta)
+  }
+
+  fn delta_zero(&self) -> Self {
+    self.zero()
+  }
+
+  fn pruning_nodes(&self) -> BTreeSet<UniqueAddress> {
+    self.modified_by_nodes()
+  }
+
+  fn prune_node(&self, removed_node: &UniqueAddress, collapse_into: &UniqueAddress) -> Option<Self> {
+    self.prune(removed_node, collapse_into).ok()
+  }
+
+  fn cleanup_node(&self, removed_node: &UniqueAddress) -> Self {
+    self.pruning_cleanup(removed_node)
+  }
+}
+
 impl Default for PNCounter {
   fn default() -> Self {
     Self::new()
@@ -0,0 +1,41 @@
+//! Replicated progress of removing one departed node from a value.
+
+use alloc::collections::BTreeSet;
+
+use fraktor_remote_core_rs::address::UniqueAddress;
+
+/// Pruning marker stored with a value for one removed node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PruningState {
+  /// `owner` will collapse the removed node's contribution once every member in `seen` knows it.
+  Initialized {
+    /// Node that performs the pruning.
+    owner: UniqueAddress,
+    /// Nodes that have observed this marker.
+    seen:  BTreeSet<UniqueAddress>,
+  },
+  /// The contribution has been collapsed; replicas drop any residual contribution they merge.
+  Performed,
+}
+
+impl PruningState {
+  /// Returns the converged marker of `self` and `other`.
+  ///
+  /// A performed marker wins; initialized markers of the same owner union their observers, and
+  /// otherwise the lower owner wins so that exactly one node performs the pruning.
+  #[must_use]
+  pub(crate) fn merge(&self, other: &Self) -> Self {
+    match (self, other) {
+      | (Self::Performed, _) | (_, Self::Performed) => Self::Performed,
+      | (Self::Initialized { owner, seen }, Self::Initialized { owner: other_owner, seen: other_seen }) => {
+        if owner == other_owner {
+          Self::Initialized { owner: owner.clone(), seen: seen.union(other_seen).cloned().collect() }
+        } else if owner < other_owner {
+          self.clone()
+        } else {
+          other.clone()
+        }
+      },
+    }
+  }
+}
@@ -1,4 +1,4 @@
-//! Read consistency vocabulary for future Replicator operations.
+//! Read consistency vocabulary for Replicator operations.
 
 #[cfg(test)]
 #[path = "read_consistency_test.rs"]
@@ -40,3 +40,31 @@ pub enum ReadConsistency {
     timeout: Duration,
   },
 }
+
+impl ReadConsistency {
+  /// Returns how many of `replicas` replicas, including the local one, must take part.
+  #[must_use]
+  pub(crate) fn required_replicas(&self, replicas: usize) -> usize {
+    let majority = replicas / 2 + 1;
+    let required = match self {
+      | Self::Local => 1,
+      | Self::From { n, .. } => n.get(),
+      | Self::Majority { min_cap, .. } => majority.max(*min_cap),
+      | Self::MajorityPlus { additional, min_cap, .. } => majority.saturating_add(additional.get()).max(*min_cap),
+      | Self::All { .. } => replicas,
+    };
+    required.clamp(1, replicas.max(1))
+  }
+
+  /// Returns the time to wait for remote replicas.
+  #[must_use]
+  pub(crate) const fn timeout(&self) -> Duration {
+    match self {
+      | Self::Local => Duration::ZERO,
+      | Self::From { timeout, .. }
+      | Self::Majority { timeout, .. }
+      | Self::MajorityPlus { timeout, .. }
+      | Self::All { timeout } => *timeout,
+    }
+  }
+}
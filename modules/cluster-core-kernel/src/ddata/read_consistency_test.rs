@@ -17,3 +17,19 @@ fn read_consistency_variants_keep_parameters() {
   });
   assert_eq!(ReadConsistency::All { timeout }, ReadConsistency::All { timeout });
 }
+
+#[test]
+fn required_replicas_follow_consistency_level() {
+  let timeout = Duration::from_secs(1);
+  let two = NonZeroUsize::new(2).expect("2 is non-zero");
+
+  assert_eq!(ReadConsistency::Local.required_replicas(5), 1);
+  assert_eq!(ReadConsistency::From { n: two, timeout }.required_replicas(5), 2);
+  assert_eq!(ReadConsistency::Majority { timeout, min_cap: 0 }.required_replicas(5), 3);
+  assert_eq!(ReadConsistency::Majority { timeout, min_cap: 4 }.required_replicas(5), 4);
+  assert_eq!(ReadConsistency::MajorityPlus { timeout, additional: two, min_cap: 0 }.required_replicas(4), 4);
+  assert_eq!(ReadConsistency::All { timeout }.required_replicas(3), 3);
+  assert_eq!(ReadConsistency::From { n: two, timeout }.required_replicas(1), 1);
+  assert_eq!(ReadConsistency::Majority { timeout, min_cap: 0 }.timeout(), timeout);
+  assert_eq!(ReadConsistency::Local.timeout(), Duration::ZERO);
+}
@@ -0,0 +1,714 @@
+//! Actor that stores and replicates CRDT values between members.
+
+#[cfg(test)]
+#[path = "replicator_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  format,
+  string::String,
+  vec::Vec,
+};
+use core::time::Duration;
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext, Pid,
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView},
+  },
+  event::logging::LogLevel,
+};
+use fraktor_remote_core_rs::address::UniqueAddress;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{
+  DataEnvelope, DeleteWriteOutcome, DurableDataEnvelope, DurableStore, DurableStoreError, ErasedReplicatorData,
+  FlushChanges, GetReplicaCount, PruningState, ReadConsistency, ReplicaCount, ReplicatorCommand, ReplicatorOperation,
+  ReplicatorPeerMessage, ReplicatorSettings, ReplicatorTick, SelfUniqueAddress, UpdateWriteOutcome, WriteConsistency,
+  replicator_operation::{ReadResponder, SubscriberNotifier},
+  replicator_peer_message::DeltaEntry,
+};
+
+const GOSSIP_TIMER: &str = "ddata-gossip";
+const NOTIFY_TIMER: &str = "ddata-notify";
+const DELTA_TIMER: &str = "ddata-delta";
+const PRUNING_TIMER: &str = "ddata-pruning";
+const DURABLE_TIMER: &str = "ddata-durable";
+
+struct PendingRead {
+  key:       String,
+  respond:   ReadResponder,
+  failure:   AnyMessage,
+  reply_to:  ActorRef,
+  remaining: usize,
+  merged:    Option<DataEnvelope>,
+}
+
+struct WriteReply {
+  success:  AnyMessage,
+  timeout:  AnyMessage,
+  reply_to: ActorRef,
+}
+
+struct PendingWrite {
+  remaining: usize,
+  reply:     WriteReply,
+}
+
+struct Subscription {
+  subscriber: ActorRef,
+  notify:     SubscriberNotifier,
+}
+
+/// Actor holding the local replica of every distributed-data key.
+///
+/// The Replicator answers [`ReplicatorCommand`]s against its local replica and contacts the
+/// Replicators of other members as the requested read or write consistency demands. Members are
+/// added with [`ReplicatorCommand::member_up`] and removed with
+/// [`ReplicatorCommand::member_removed`]; the replica count is the number of known members plus
+/// the local one.
+///
+/// Replicas converge through three channels:
+///
+/// - the writes of updates and deletes with a consistency above local,
+/// - deltas of [`DeltaReplicatedData`](super::DeltaReplicatedData) values, sent to every member on
+///   the delta propagation interval, and
+/// - full-state gossip with one member per gossip interval, answered with the member's state.
+///
+/// Deltas of values requiring causal delivery are dropped when one was missed, and the full state
+/// is exchanged with their origin instead.
+///
+/// The member with the lowest address prunes the contribution of removed members once every
+/// member has seen its pruning marker, and the other replicas drop any residual contribution
+/// they later merge.
+///
+/// Keys selected by [`ReplicatorSettings::with_durable_keys`] are kept in the
+/// [`DurableStore`] given with [`Replicator::with_durable_store`]. They are loaded before the first
+/// command is served, and each change is stored either before the next message is handled or
+/// batched on the configured write-behind interval. The Replicator fails fatally when the stored
+/// keys cannot be loaded.
+pub struct Replicator {
+  self_address:      UniqueAddress,
+  settings:          ReplicatorSettings,
+  entries:           BTreeMap<String, DataEnvelope>,
+  peers:             BTreeMap<UniqueAddress, ActorRef>,
+  removed_nodes:     BTreeSet<UniqueAddress>,
+  subscriptions:     BTreeMap<String, Vec<Subscription>>,
+  changed:           BTreeSet<String>,
+  pending_deltas:    BTreeMap<String, ArcShared<dyn ErasedReplicatorData>>,
+  delta_sequences:   BTreeMap<String, u64>,
+  received_deltas:   BTreeMap<(UniqueAddress, String), u64>,
+  pending_reads:     BTreeMap<u64, PendingRead>,
+  pending_writes:    BTreeMap<u64, PendingWrite>,
+  next_request_id:   u64,
+  gossip_cursor:     usize,
+  pruning_ticks:     u64,
+  performed_markers: BTreeMap<(String, UniqueAddress), u64>,
+  durable_store:     Option<ArcShared<dyn DurableStore>>,
+  durable_pending:   BTreeSet<String>,
+}
+
+impl Replicator {
+  /// Creates a Replicator for the local member `self_address`.
+  #[must_use]
+  pub fn new(self_address: &SelfUniqueAddress, settings: ReplicatorSettings) -> Self {
+    Self {
+      self_address: self_address.unique_address().clone(),
+      settings,
+      entries: BTreeMap::new(),
+      peers: BTreeMap::new(),
+      removed_nodes: BTreeSet::new(),
+      subscriptions: BTreeMap::new(),
+      changed: BTreeSet::new(),
+      pending_deltas: BTreeMap::new(),
+      delta_sequences: BTreeMap::new(),
+      received_deltas: BTreeMap::new(),
+      pending_reads: BTreeMap::new(),
+      pending_writes: BTreeMap::new(),
+      next_request_id: 0,
+      gossip_cursor: 0,
+      pruning_ticks: 0,
+      performed_markers: BTreeMap::new(),
+      durable_store: None,
+      durable_pending: BTreeSet::new(),
+    }
+  }
+
+  /// Returns this Replicator keeping its durable keys in `store`.
+  #[must_use]
+  pub fn with_durable_store(mut self, store: ArcShared<dyn DurableStore>) -> Self {
+    self.durable_store = Some(store);
+    self
+  }
+
+  fn replica_count(&self) -> usize {
+    self.peers.len() + 1
+  }
+
+  const fn next_request_id(&mut self) -> u64 {
+    self.next_request_id = self.next_request_id.wrapping_add(1);
+    self.next_request_id
+  }
+
+  fn handle_operation(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    operation: &ReplicatorOperation,
+  ) -> Result<(), ActorError> {
+    match operation {
+      | ReplicatorOperation::Get { key, consistency, respond, failure, reply_to } => {
+        self.handle_get(ctx, key, *consistency, respond, failure, reply_to)
+      },
+      | ReplicatorOperation::Update { key, consistency, modify, respond, reply_to } => {
+        let next = match modify(self.entries.get(key)) {
+          | Ok(data) => data,
+          | Err(rejection) => {
+            reply_to.clone().tell(rejection);
+            return Ok(());
+          },
+        };
+        let stored = match next.split_delta_erased() {
+          | Some((delta, reset)) => {
+            self.add_pending_delta(key, delta);
+            reset
+          },
+          | None => next,
+        };
+        let envelope = match self.entries.get(key) {
+          | Some(current) => current.with_data(stored),
+          | None => DataEnvelope::new(stored),
+        };
+        self.set_local(key, envelope.clone());
+        if self.writes_through(key) && self.flush_durable().is_err() {
+          // 値はローカルに反映済みで、ゴシップで他のレプリカへも広がる。
+          reply_to.clone().tell(respond(UpdateWriteOutcome::StoreFailure));
+          return Ok(());
+        }
+        let reply = WriteReply {
+          success:  respond(UpdateWriteOutcome::Success),
+          timeout:  respond(UpdateWriteOutcome::Timeout),
+          reply_to: reply_to.clone(),
+        };
+        self.write(ctx, key, envelope, *consistency, reply)
+      },
+      | ReplicatorOperation::Delete { key, consistency, respond, data_deleted, reply_to } => {
+        if self.entries.get(key).is_some_and(DataEnvelope::is_deleted) {
+          reply_to.clone().tell(data_deleted.clone());
+          return Ok(());
+        }
+        self.pending_deltas.remove(key);
+        self.set_local(key, DataEnvelope::deleted());
+        if self.writes_through(key) && self.flush_durable().is_err() {
+          reply_to.clone().tell(respond(DeleteWriteOutcome::StoreFailure));
+          return Ok(());
+        }
+        let reply = WriteReply {
+          success:  respond(DeleteWriteOutcome::Success),
+          timeout:  respond(DeleteWriteOutcome::Timeout),
+          reply_to: reply_to.clone(),
+        };
+        self.write(ctx, key, DataEnvelope::deleted(), *consistency, reply)
+      },
+      | ReplicatorOperation::Subscribe { key, subscriber, notify } => {
+        // 監視の登録に失敗しても購読は続け、停止時の掃除ができないだけに留める。
+        if let Err(error) = ctx.watch(subscriber) {
+          ctx.log(LogLevel::Warn, format!("replicator failed to watch subscriber {:?}: {error:?}", subscriber.pid()));
+        }
+        if let Some(message) = self.entries.get(key).and_then(&**notify) {
+          subscriber.clone().tell(message);
+        }
+        let subscription = Subscription { subscriber: subscriber.clone(), notify: notify.clone() };
+        self.subscriptions.entry(key.clone()).or_default().push(subscription);
+        Ok(())
+      },
+      | ReplicatorOperation::Unsubscribe { key, subscriber } => {
+        if let Some(subscriptions) = self.subscriptions.get_mut(key) {
+          subscriptions.retain(|subscription| subscription.subscriber != *subscriber);
+          if subscriptions.is_empty() {
+            self.subscriptions.remove(key);
+          }
+        }
+        let still_subscribed = self
+          .subscriptions
+          .values()
+          .any(|subscriptions| subscriptions.iter().any(|subscription| subscription.subscriber == *subscriber));
+        if !still_subscribed && let Err(error) = ctx.unwatch(subscriber) {
+          ctx.log(LogLevel::Warn, format!("replicator failed to unwatch subscriber {:?}: {error:?}", subscriber.pid()));
+        }
+        Ok(())
+      },
+      | ReplicatorOperation::MemberUp { node, replicator } => {
+        if *node != self.self_address {
+          self.peers.insert(node.clone(), replicator.clone());
+        }
+        Ok(())
+      },
+      | ReplicatorOperation::MemberRemoved { node } => {
+        if *node != self.self_address {
+          self.peers.remove(node);
+          self.removed_nodes.insert(node.clone());
+        }
+        Ok(())
+      },
+    }
+  }
+
+  fn handle_get(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    key: &str,
+    consistency: ReadConsistency,
+    respond: &ReadResponder,
+    failure: &AnyMessage,
+    reply_to: &ActorRef,
+  ) -> Result<(), ActorError> {
+    let required = consistency.required_replicas(self.replica_count());
+    if required <= 1 {
+      reply_to.clone().tell(respond(self.entries.get(key)));
+      return Ok(());
+    }
+    let request_id = self.next_request_id();
+    self.send_to_peers(ctx, &ReplicatorPeerMessage::Read { key: String::from(key), request_id });
+    self.pending_reads.insert(request_id, PendingRead {
+      key:       String::from(key),
+      respond:   respond.clone(),
+      failure:   failure.clone(),
+      reply_to:  reply_to.clone(),
+      remaining: required - 1,
+      merged:    self.entries.get(key).cloned(),
+    });
+    start_timeout(ctx, &read_timer(request_id), ReplicatorTick::ReadTimeout(request_id), consistency.timeout())
+  }
+
+  fn write(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    key: &str,
+    envelope: DataEnvelope,
+    consistency: WriteConsistency,
+    mut reply: WriteReply,
+  ) -> Result<(), ActorError> {
+    let required = consistency.required_replicas(self.replica_count());
+    if required <= 1 {
+      reply.reply_to.tell(reply.success);
+      return Ok(());
+    }
+    let request_id = self.next_request_id();
+    self.send_to_peers(ctx, &ReplicatorPeerMessage::Write { key: String::from(key), envelope, request_id });
+    self.pending_writes.insert(request_id, PendingWrite { remaining: required - 1, reply });
+    start_timeout(ctx, &write_timer(request_id), ReplicatorTick::WriteTimeout(request_id), consistency.timeout())
+  }
+
+  fn handle_peer(&mut self, ctx: &mut ActorContext<'_>, message: &ReplicatorPeerMessage) -> Result<(), ActorError> {
+    match message {
+      | ReplicatorPeerMessage::Gossip { entries, send_back } => {
+        for (key, envelope) in entries {
+          self.merge_into_local(key, envelope);
+        }
+        if *send_back {
+          reply(ctx, ReplicatorPeerMessage::Gossip { entries: self.gossip_entries(), send_back: false })?;
+        }
+      },
+      | ReplicatorPeerMessage::DeltaPropagation { from, deltas } => {
+        if self.apply_deltas(from, deltas) {
+          // 因果順の欠落したデルタは捨て、送信元と全状態を交換して取り戻す。
+          reply(ctx, ReplicatorPeerMessage::Gossip { entries: self.gossip_entries(), send_back: true })?;
+        }
+      },
+      | ReplicatorPeerMessage::Read { key, request_id } => {
+        let envelope = self.entries.get(key).cloned();
+        reply(ctx, ReplicatorPeerMessage::ReadResult { request_id: *request_id, envelope })?;
+      },
+      | ReplicatorPeerMessage::ReadResult { request_id, envelope } => {
+        self.handle_read_result(ctx, *request_id, envelope.as_ref())
+      },
+      | ReplicatorPeerMessage::Write { key, envelope, request_id } => {
+        if self.merge_into_local(key, envelope) {
+          reply(ctx, ReplicatorPeerMessage::WriteAck { request_id: *request_id })?;
+        }
+      },
+      | ReplicatorPeerMessage::WriteAck { request_id } => {
+        let Some(pending) = self.pending_writes.get_mut(request_id) else {
+          return Ok(());
+        };
+        pending.remaining = pending.remaining.saturating_sub(1);
+        if pending.remaining == 0
+          && let Some(mut pending) = self.pending_writes.remove(request_id)
+        {
+          // must-ignore: 保留中の書き込みは削除済みなので、取り消せなかったタイムアウトは届いても無視される。
+          drop(ctx.timers().cancel(&write_timer(*request_id)));
+          pending.reply.reply_to.tell(pending.reply.success);
+        }
+      },
+    }
+    Ok(())
+  }
+
+  fn handle_read_result(&mut self, ctx: &ActorContext<'_>, request_id: u64, envelope: Option<&DataEnvelope>) {
+    let Some(pending) = self.pending_reads.get_mut(&request_id) else {
+      return;
+    };
+    if let Some(envelope) = envelope {
+      pending.merged = match &pending.merged {
+        | Some(merged) => merged.merge(envelope).or_else(|| Some(merged.clone())),
+        | None => Some(envelope.clone()),
+      };
+    }
+    pending.remaining = pending.remaining.saturating_sub(1);
+    if pending.remaining > 0 {
+      return;
+    }
+    let Some(mut pending) = self.pending_reads.remove(&request_id) else {
+      return;
+    };
+    // must-ignore: 保留中の読み取りは削除済みなので、取り消せなかったタイムアウトは届いても無視される。
+    drop(ctx.timers().cancel(&read_timer(request_id)));
+    if let Some(merged) = &pending.merged {
+      // 読み取りで得た値をローカルにも反映する。
+      self.merge_into_local(&pending.key, merged);
+    }
+    let response = (pending.respond)(self.entries.get(&pending.key));
+    pending.reply_to.tell(response);
+  }
+
+  fn handle_tick(&mut self, ctx: &mut ActorContext<'_>, tick: ReplicatorTick) {
+    match tick {
+      | ReplicatorTick::Gossip => self.gossip(ctx),
+      | ReplicatorTick::NotifySubscribers => self.notify_subscribers(),
+      | ReplicatorTick::DeltaPropagation => self.propagate_deltas(ctx),
+      | ReplicatorTick::Pruning => self.prune(),
+      | ReplicatorTick::DurableFlush => self.flush_durable_or_log(ctx),
+      | ReplicatorTick::ReadTimeout(request_id) => {
+        if let Some(mut pending) = self.pending_reads.remove(&request_id) {
+          pending.reply_to.tell(pending.failure);
+        }
+      },
+      | ReplicatorTick::WriteTimeout(request_id) => {
+        if let Some(mut pending) = self.pending_writes.remove(&request_id) {
+          pending.reply.reply_to.tell(pending.reply.timeout);
+        }
+      },
+    }
+  }
+
+  fn gossip(&mut self, ctx: &ActorContext<'_>) {
+    if self.peers.is_empty() || self.entries.is_empty() {
+      return;
+    }
+    self.gossip_cursor = (self.gossip_cursor + 1) % self.peers.len();
+    let Some(peer) = self.peers.values().nth(self.gossip_cursor) else {
+      return;
+    };
+    let message = ReplicatorPeerMessage::Gossip { entries: self.gossip_entries(), send_back: true };
+    peer.clone().tell(AnyMessage::new(message).with_sender(ctx.self_ref()));
+  }
+
+  fn gossip_entries(&self) -> Vec<(String, DataEnvelope)> {
+    self.entries.iter().map(|(key, envelope)| (key.clone(), envelope.clone())).collect()
+  }
+
+  fn add_pending_delta(&mut self, key: &str, delta: ArcShared<dyn ErasedReplicatorData>) {
+    if !self.settings.delta_crdt_enabled() {
+      return;
+    }
+    let combined = match self.pending_deltas.get(key) {
+      | Some(pending) => pending.merge_delta_erased(&*delta).unwrap_or(delta),
+      | None => delta,
+    };
+    self.pending_deltas.insert(String::from(key), combined);
+  }
+
+  fn propagate_deltas(&mut self, ctx: &ActorContext<'_>) {
+    if self.pending_deltas.is_empty() {
+      return;
+    }
+    let pending = core::mem::take(&mut self.pending_deltas);
+    if self.peers.is_empty() {
+      return;
+    }
+    let deltas = pending
+      .into_iter()
+      .map(|(key, delta)| {
+        let seq = self.delta_sequences.entry(key.clone()).or_insert(0);
+        *seq += 1;
+        DeltaEntry { key, seq: *seq, delta }
+      })
+      .collect();
+    let message = ReplicatorPeerMessage::DeltaPropagation { from: self.self_address.clone(), deltas };
+    self.send_to_peers(ctx, &message);
+  }
+
+  /// Applies received deltas and returns true when a causal delta was missed.
+  fn apply_deltas(&mut self, from: &UniqueAddress, deltas: &[DeltaEntry]) -> bool {
+    let mut missed = false;
+    for entry in deltas {
+      let origin = (from.clone(), entry.key.clone());
+      let last = self.received_deltas.get(&origin).copied().unwrap_or(0);
+      if entry.seq <= last {
+        continue;
+      }
+      self.received_deltas.insert(origin, entry.seq);
+      if entry.delta.requires_causal_delivery_erased() && entry.seq != last + 1 {
+        missed = true;
+        continue;
+      }
+      let envelope = match self.entries.get(&entry.key) {
+        | Some(current) if current.is_deleted() => continue,
+        | Some(current) => match current.data().and_then(|data| data.merge_delta_erased(&*entry.delta)) {
+          | Some(data) => current.with_data(data),
+          | None => continue,
+        },
+        | None => match entry.delta.delta_zero_erased().merge_delta_erased(&*entry.delta) {
+          | Some(data) => DataEnvelope::new(data),
+          | None => continue,
+        },
+      };
+      self.set_local(&entry.key, envelope);
+    }
+    missed
+  }
+
+  /// Merges `incoming` into the local envelope and returns false when the value types differ.
+  fn merge_into_local(&mut self, key: &str, incoming: &DataEnvelope) -> bool {
+    let merged = match self.entries.get(key) {
+      | Some(current) => match current.merge(incoming) {
+        | Some(merged) => merged,
+        | None => return false,
+      },
+      | None => incoming.clone(),
+    };
+    let merged = merged.add_seen(&self.self_address);
+    self.set_local(key, merged);
+    true
+  }
+
+  fn set_local(&mut self, key: &str, envelope: DataEnvelope) {
+    let changed = self.entries.get(key).is_none_or(|current| !current.same_value(&envelope));
+    if changed {
+      self.changed.insert(String::from(key));
+      if self.is_durable(key) {
+        self.durable_pending.insert(String::from(key));
+      }
+    }
+    self.entries.insert(String::from(key), envelope);
+  }
+
+  fn is_durable(&self, key: &str) -> bool {
+    self.durable_store.is_some() && self.settings.is_durable(key)
+  }
+
+  /// Returns true when changes of `key` are stored before the change is acknowledged.
+  fn writes_through(&self, key: &str) -> bool {
+    self.is_durable(key) && self.settings.durable_write_behind_interval().is_zero()
+  }
+
+  fn load_durable(&mut self) -> Result<(), ActorError> {
+    let Some(store) = self.durable_store.clone() else {
+      return Ok(());
+    };
+    let loaded =
+      store.load_all().map_err(|error| ActorError::fatal(format!("replicator durable load failed: {error:?}")))?;
+    for (key, envelope) in loaded {
+      if self.settings.is_durable(&key) {
+        self.merge_into_local(&key, &envelope.into_envelope());
+      }
+    }
+    // 読み込んだ値はストアと同じなので書き戻さない。
+    self.durable_pending.clear();
+    Ok(())
+  }
+
+  /// Stores every changed durable key in one batch, keeping them pending when the store fails.
+  fn flush_durable(&mut self) -> Result<(), DurableStoreError> {
+    let Some(store) = &self.durable_store else {
+      return Ok(());
+    };
+    if self.durable_pending.is_empty() {
+      return Ok(());
+    }
+    let batch = self
+      .durable_pending
+      .iter()
+      .filter_map(|key| {
+        self.entries.get(key).map(|envelope| (key.clone(), DurableDataEnvelope::from_envelope(envelope)))
+      })
+      .collect();
+    store.store(&batch)?;
+    self.durable_pending.clear();
+    Ok(())
+  }
+
+  /// Flushes pending durable changes, logging a failure and keeping the batch for the next attempt.
+  fn flush_durable_or_log(&mut self, ctx: &ActorContext<'_>) {
+    if let Err(error) = self.flush_durable() {
+      ctx.log(
+        LogLevel::Warn,
+        format!("replicator durable flush failed, keeping {} keys pending: {error:?}", self.durable_pending.len()),
+      );
+    }
+  }
+
+  fn dispatch(&mut self, ctx: &mut ActorContext<'_>, message: &AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(command) = message.downcast_ref::<ReplicatorCommand>() {
+      return self.handle_operation(ctx, command.operation());
+    }
+    if let Some(peer_message) = message.downcast_ref::<ReplicatorPeerMessage>() {
+      return self.handle_peer(ctx, peer_message);
+    }
+    if let Some(tick) = message.downcast_ref::<ReplicatorTick>() {
+      self.handle_tick(ctx, *tick);
+      return Ok(());
+    }
+    if message.downcast_ref::<FlushChanges>().is_some() {
+      self.notify_subscribers();
+      return Ok(());
+    }
+    if message.downcast_ref::<GetReplicaCount>().is_some()
+      && let Err(error) = ctx.reply(AnyMessage::new(ReplicaCount::new(self.replica_count())))
+    {
+      ctx.log(LogLevel::Warn, format!("replicator failed to reply replica count: {error:?}"));
+    }
+    Ok(())
+  }
+
+  fn notify_subscribers(&mut self) {
+    for key in core::mem::take(&mut self.changed) {
+      let (Some(subscriptions), Some(envelope)) = (self.subscriptions.get(&key), self.entries.get(&key)) else {
+        continue;
+      };
+      for subscription in subscriptions {
+        if let Some(message) = (subscription.notify)(envelope) {
+          subscription.subscriber.clone().tell(message);
+        }
+      }
+    }
+  }
+
+  fn prune(&mut self) {
+    self.pruning_ticks += 1;
+    let mut members: BTreeSet<UniqueAddress> = self.peers.keys().cloned().collect();
+    members.insert(self.self_address.clone());
+    let is_leader = members.first() == Some(&self.self_address);
+    let marker_ticks = self.settings.pruning_marker_ticks();
+    let keys: Vec<String> = self.entries.keys().cloned().collect();
+    for key in keys {
+      let Some(current) = self.entries.get(&key) else {
+        continue;
+      };
+      let mut envelope = current.clone();
+      if is_leader {
+        for removed_node in envelope.needs_pruning(&self.removed_nodes) {
+          envelope = envelope.initialize_pruning(&removed_node, &self.self_address);
+        }
+      }
+      envelope = envelope.add_seen(&self.self_address);
+      for (removed_node, state) in envelope.pruning().clone() {
+        match state {
+          | PruningState::Initialized { owner, seen } => {
+            if !members.contains(&owner) {
+              // 担当ノードが離脱したマーカーは破棄し、次のリーダーに初期化し直させる。
+              envelope = envelope.without_marker(&removed_node);
+            } else if owner == self.self_address
+              && members.is_subset(&seen)
+              && let Some(pruned) = envelope.prune(&removed_node, &self.self_address)
+            {
+              envelope = pruned;
+            }
+          },
+          | PruningState::Performed => {
+            let marker = (key.clone(), removed_node.clone());
+            let first_seen = *self.performed_markers.entry(marker.clone()).or_insert(self.pruning_ticks);
+            if self.pruning_ticks.saturating_sub(first_seen) >= marker_ticks {
+              envelope = envelope.without_marker(&removed_node);
+              self.performed_markers.remove(&marker);
+            }
+          },
+        }
+      }
+      self.set_local(&key, envelope);
+    }
+  }
+
+  fn send_to_peers(&self, ctx: &ActorContext<'_>, message: &ReplicatorPeerMessage) {
+    for peer in self.peers.values() {
+      peer.clone().tell(AnyMessage::new(message.clone()).with_sender(ctx.self_ref()));
+    }
+  }
+
+  fn start_periodic(&self, ctx: &ActorContext<'_>) -> Result<(), ActorError> {
+    start_periodic(ctx, GOSSIP_TIMER, ReplicatorTick::Gossip, self.settings.gossip_interval())?;
+    start_periodic(ctx, NOTIFY_TIMER, ReplicatorTick::NotifySubscribers, self.settings.notify_subscribers_interval())?;
+    if self.settings.delta_crdt_enabled() {
+      start_periodic(ctx, DELTA_TIMER, ReplicatorTick::DeltaPropagation, self.settings.delta_propagation_interval())?;
+    }
+    let write_behind = self.settings.durable_write_behind_interval();
+    if self.durable_store.is_some() && !write_behind.is_zero() {
+      start_periodic(ctx, DURABLE_TIMER, ReplicatorTick::DurableFlush, write_behind)?;
+    }
+    start_periodic(ctx, PRUNING_TIMER, ReplicatorTick::Pruning, self.settings.pruning_interval())
+  }
+}
+
+impl Actor for Replicator {
+  fn pre_start(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    self.load_durable()?;
+    self.start_periodic(ctx)
+  }
+
+  fn receive(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    let result = self.dispatch(ctx, &message);
+    if self.settings.durable_write_behind_interval().is_zero() {
+      // ゴシップやデルタによる変更もここで保存する。失敗した分は次のメッセージ後に再試行される。
+      self.flush_durable_or_log(ctx);
+    }
+    result
+  }
+
+  fn post_stop(&mut self, _ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    self.flush_durable().map_err(|error| ActorError::recoverable(format!("replicator durable flush failed: {error:?}")))
+  }
+
+  fn on_terminated(&mut self, _ctx: &mut ActorContext<'_>, terminated: Pid) -> Result<(), ActorError> {
+    self.subscriptions.retain(|_, subscriptions| {
+      subscriptions.retain(|subscription| subscription.subscriber.pid() != terminated);
+      !subscriptions.is_empty()
+    });
+    Ok(())
+  }
+}
+
+fn reply(ctx: &mut ActorContext<'_>, message: ReplicatorPeerMessage) -> Result<(), ActorError> {
+  ctx
+    .reply(AnyMessage::new(message).with_sender(ctx.self_ref()))
+    .map_err(|error| ActorError::recoverable(format!("replicator reply failed: {error:?}")))
+}
+
+fn start_periodic(
+  ctx: &ActorContext<'_>,
+  key: &str,
+  tick: ReplicatorTick,
+  interval: Duration,
+) -> Result<(), ActorError> {
+  ctx
+    .timers()
+    .start_timer_with_fixed_delay(key, AnyMessage::new(tick), interval)
+    .map_err(|error| ActorError::recoverable(format!("replicator timer {key} failed: {error:?}")))
+}
+
+fn start_timeout(ctx: &ActorContext<'_>, key: &str, tick: ReplicatorTick, timeout: Duration) -> Result<(), ActorError> {
+  ctx
+    .timers()
+    .start_single_timer(key, AnyMessage::new(tick), timeout)
+    .map_err(|error| ActorError::recoverable(format!("replicator timer {key} failed: {error:?}")))
+}
+
+fn read_timer(request_id: u64) -> String {
+  format!("ddata-read-{request_id}")
+}
+
+fn write_timer(request_id: u64) -> String {
+  format!("ddata-write-{request_id}")
+}
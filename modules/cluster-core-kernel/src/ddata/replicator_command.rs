@@ -0,0 +1,152 @@
+//! Commands accepted by the Replicator actor.
+
+#[cfg(test)]
+#[path = "replicator_command_test.rs"]
+mod tests;
+
+use alloc::string::String;
+
+use fraktor_actor_core_kernel_rs::actor::{actor_ref::ActorRef, messaging::AnyMessage};
+use fraktor_remote_core_rs::address::UniqueAddress;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{
+  DataEnvelope, Delete, DeleteWriteOutcome, ErasedReplicatorData, Get, ReplicatorData, ReplicatorEntry,
+  ReplicatorOperation, Subscribe, Unsubscribe, Update, UpdateResponse, UpdateWriteOutcome,
+  replicator_operation::{DeleteResponder, ReadResponder, SubscriberNotifier, UpdateModifier, UpdateResponder},
+};
+
+const TYPE_MISMATCH: &str = "key holds a value of another data type";
+
+/// Message sent to the Replicator actor.
+///
+/// Replies are sent to the `reply_to` reference given to the constructor: a
+/// [`GetResponse`](super::GetResponse) for [`get`](Self::get), an
+/// [`UpdateResponse`](super::UpdateResponse) for [`update`](Self::update), and a
+/// [`DeleteResponse`](super::DeleteResponse) for [`delete`](Self::delete), each typed with the
+/// data and request context of the command. Subscribers receive
+/// [`SubscribeResponse`](super::SubscribeResponse) notifications.
+///
+/// [`FlushChanges`](super::FlushChanges) and [`GetReplicaCount`](super::GetReplicaCount) are sent
+/// to the Replicator as they are; the latter is answered with a
+/// [`ReplicaCount`](super::ReplicaCount) to the sender.
+#[derive(Clone)]
+pub struct ReplicatorCommand {
+  operation: ReplicatorOperation,
+}
+
+impl ReplicatorCommand {
+  /// Reads the value of a key with the consistency of `get`.
+  #[must_use]
+  pub fn get<D, C>(get: Get<D, C>, reply_to: ActorRef) -> Self
+  where
+    D: ReplicatorData,
+    C: Clone + Send + Sync + 'static, {
+    let key = String::from(get.key().id());
+    let consistency = get.consistency();
+    let failure = AnyMessage::new(get.failure());
+    let respond: ReadResponder =
+      ArcShared::new(move |envelope: Option<&DataEnvelope>| match DataEnvelope::entry::<D>(envelope) {
+        | Some(entry) => AnyMessage::new(get.respond_from(&entry)),
+        | None => AnyMessage::new(get.failure()),
+      });
+    Self { operation: ReplicatorOperation::Get { key, consistency, respond, failure, reply_to } }
+  }
+
+  /// Applies `modify` to the value of a key and writes the result with the consistency of
+  /// `update`.
+  ///
+  /// `modify` receives `None` when the key has no value. It runs inside the Replicator and must
+  /// not block.
+  #[must_use]
+  pub fn update<D, C, F>(update: Update<D, C>, modify: F, reply_to: ActorRef) -> Self
+  where
+    D: ReplicatorData,
+    C: Clone + Send + Sync + 'static,
+    F: Fn(Option<&D>) -> Result<D, String> + Send + Sync + 'static, {
+    let key = String::from(update.key().id());
+    let consistency = update.consistency();
+    let update = ArcShared::new(update);
+    let modifier: UpdateModifier = {
+      let update = update.clone();
+      ArcShared::new(move |envelope: Option<&DataEnvelope>| {
+        let Some(entry) = DataEnvelope::entry::<D>(envelope) else {
+          return Err(AnyMessage::new(UpdateResponse::ModifyFailure {
+            key:     update.key().clone(),
+            message: String::from(TYPE_MISMATCH),
+            request: update.request().cloned(),
+          }));
+        };
+        match update.evaluate(&entry, &modify, UpdateWriteOutcome::Success) {
+          | (ReplicatorEntry::Present(data), response) if response.is_locally_applied() => {
+            Ok(ArcShared::new(data) as ArcShared<dyn ErasedReplicatorData>)
+          },
+          | (_, response) => Err(AnyMessage::new(response)),
+        }
+      })
+    };
+    let respond: UpdateResponder =
+      ArcShared::new(move |outcome: UpdateWriteOutcome| AnyMessage::new(update.write_response(outcome)));
+    Self { operation: ReplicatorOperation::Update { key, consistency, modify: modifier, respond, reply_to } }
+  }
+
+  /// Deletes a key with the consistency of `delete`.
+  ///
+  /// A deleted key cannot be used again; later updates are answered with `DataDeleted`.
+  #[must_use]
+  pub fn delete<D, C>(delete: Delete<D, C>, reply_to: ActorRef) -> Self
+  where
+    D: ReplicatorData,
+    C: Clone + Send + Sync + 'static, {
+    let key = String::from(delete.key().id());
+    let consistency = delete.consistency();
+    let (_, already_deleted) = delete.evaluate(&ReplicatorEntry::Deleted, DeleteWriteOutcome::Success);
+    let data_deleted = AnyMessage::new(already_deleted);
+    let respond: DeleteResponder = ArcShared::new(move |outcome: DeleteWriteOutcome| {
+      let (_, response) = delete.evaluate(&ReplicatorEntry::Missing, outcome);
+      AnyMessage::new(response)
+    });
+    Self { operation: ReplicatorOperation::Delete { key, consistency, respond, data_deleted, reply_to } }
+  }
+
+  /// Registers the subscriber of `subscribe` for change notifications of its key.
+  ///
+  /// The subscriber receives the current value right away when the key has one, and is removed
+  /// when it terminates.
+  #[must_use]
+  pub fn subscribe<D: ReplicatorData>(subscribe: Subscribe<D, ActorRef>) -> Self {
+    let key = String::from(subscribe.key().id());
+    let subscriber = subscribe.subscriber().clone();
+    let notify: SubscriberNotifier =
+      ArcShared::new(move |envelope: &DataEnvelope| match DataEnvelope::entry::<D>(Some(envelope))? {
+        | ReplicatorEntry::Present(data) => Some(AnyMessage::new(subscribe.changed(data))),
+        | ReplicatorEntry::Deleted => Some(AnyMessage::new(subscribe.deleted())),
+        | ReplicatorEntry::Missing => None,
+      });
+    Self { operation: ReplicatorOperation::Subscribe { key, subscriber, notify } }
+  }
+
+  /// Removes the subscriber of `unsubscribe` from its key.
+  #[must_use]
+  pub fn unsubscribe<D: ReplicatorData>(unsubscribe: &Unsubscribe<D, ActorRef>) -> Self {
+    let key = String::from(unsubscribe.key().id());
+    let subscriber = unsubscribe.subscriber().clone();
+    Self { operation: ReplicatorOperation::Unsubscribe { key, subscriber } }
+  }
+
+  /// Adds the Replicator of a member that became reachable as a replica.
+  #[must_use]
+  pub const fn member_up(node: UniqueAddress, replicator: ActorRef) -> Self {
+    Self { operation: ReplicatorOperation::MemberUp { node, replicator } }
+  }
+
+  /// Removes a member as a replica and schedules pruning of its contribution.
+  #[must_use]
+  pub const fn member_removed(node: UniqueAddress) -> Self {
+    Self { operation: ReplicatorOperation::MemberRemoved { node } }
+  }
+
+  pub(crate) const fn operation(&self) -> &ReplicatorOperation {
+    &self.operation
+  }
+}
@@ -0,0 +1,137 @@
+use alloc::string::String;
+
+use fraktor_actor_core_kernel_rs::actor::{actor_ref::ActorRef, messaging::AnyMessage};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::ReplicatorCommand;
+use crate::ddata::{
+  DataEnvelope, Delete, DeleteResponse, DeleteWriteOutcome, ErasedReplicatorData, Flag, FlagKey, GCounter, Get,
+  GetResponse, ReadConsistency, ReplicatorOperation, Subscribe, SubscribeResponse, Unsubscribe, Update, UpdateResponse,
+  UpdateWriteOutcome, WriteConsistency,
+};
+
+fn flag_envelope(flag: Flag) -> DataEnvelope {
+  DataEnvelope::new(ArcShared::new(flag) as ArcShared<dyn ErasedReplicatorData>)
+}
+
+fn get_response(message: &AnyMessage) -> &GetResponse<Flag, u64> {
+  message.downcast_ref::<GetResponse<Flag, u64>>().expect("get response")
+}
+
+#[test]
+fn get_responds_from_the_observed_envelope() {
+  let get = Get::<Flag, u64>::new(FlagKey::new("flag"), ReadConsistency::Local).with_request(7);
+  let command = ReplicatorCommand::get(get, ActorRef::null());
+  let ReplicatorOperation::Get { key, respond, failure, .. } = command.operation() else {
+    panic!("get operation expected");
+  };
+
+  assert_eq!(key, "flag");
+  assert!(matches!(get_response(&respond(None)), GetResponse::NotFound { .. }));
+  let present = respond(Some(&flag_envelope(Flag::disabled().switch_on())));
+  assert!(get_response(&present).data().expect("present value").is_enabled());
+  assert_eq!(get_response(&present).request(), Some(&7));
+  assert!(matches!(get_response(&respond(Some(&DataEnvelope::deleted()))), GetResponse::DataDeleted { .. }));
+  assert!(matches!(get_response(failure), GetResponse::Failure { .. }));
+}
+
+#[test]
+fn get_of_a_value_with_another_type_fails() {
+  let get = Get::<Flag, u64>::new(FlagKey::new("flag"), ReadConsistency::Local);
+  let command = ReplicatorCommand::get(get, ActorRef::null());
+  let ReplicatorOperation::Get { respond, .. } = command.operation() else {
+    panic!("get operation expected");
+  };
+  let counter = DataEnvelope::new(ArcShared::new(GCounter::new()) as ArcShared<dyn ErasedReplicatorData>);
+
+  assert!(matches!(get_response(&respond(Some(&counter))), GetResponse::Failure { .. }));
+}
+
+#[test]
+fn update_modifies_the_current_value_and_maps_write_outcomes() {
+  let update = Update::<Flag, u64>::new(FlagKey::new("flag"), WriteConsistency::Local).with_request(3);
+  let command = ReplicatorCommand::update(
+    update,
+    |current: Option<&Flag>| Ok(current.cloned().unwrap_or_default().switch_on()),
+    ActorRef::null(),
+  );
+  let ReplicatorOperation::Update { modify, respond, .. } = command.operation() else {
+    panic!("update operation expected");
+  };
+
+  let data = modify(None).unwrap_or_else(|_| panic!("modification succeeds"));
+  assert!(data.as_any().downcast_ref::<Flag>().expect("flag value").is_enabled());
+  let timeout = respond(UpdateWriteOutcome::Timeout);
+  let response = timeout.downcast_ref::<UpdateResponse<Flag, u64>>().expect("update response");
+  assert!(matches!(response, UpdateResponse::Timeout { .. }));
+  assert_eq!(response.request(), Some(&3));
+}
+
+#[test]
+fn update_rejections_are_replied_without_a_new_value() {
+  let update = Update::<Flag>::new(FlagKey::new("flag"), WriteConsistency::Local);
+  let command = ReplicatorCommand::update(update, |_: Option<&Flag>| Err(String::from("rejected")), ActorRef::null());
+  let ReplicatorOperation::Update { modify, .. } = command.operation() else {
+    panic!("update operation expected");
+  };
+
+  let rejection = modify(None).err().expect("modification fails");
+  let response = rejection.downcast_ref::<UpdateResponse<Flag>>().expect("update response");
+  assert_eq!(response.message(), Some("rejected"));
+
+  let rejection = modify(Some(&DataEnvelope::deleted())).err().expect("deleted key is rejected");
+  let response = rejection.downcast_ref::<UpdateResponse<Flag>>().expect("update response");
+  assert!(matches!(response, UpdateResponse::DataDeleted { .. }));
+
+  let counter = DataEnvelope::new(ArcShared::new(GCounter::new()) as ArcShared<dyn ErasedReplicatorData>);
+  let rejection = modify(Some(&counter)).err().expect("type mismatch is rejected");
+  let response = rejection.downcast_ref::<UpdateResponse<Flag>>().expect("update response");
+  assert!(matches!(response, UpdateResponse::ModifyFailure { .. }));
+}
+
+#[test]
+fn delete_maps_write_outcomes_and_repeated_deletes() {
+  let delete = Delete::<Flag, u64>::new(FlagKey::new("flag"), WriteConsistency::Local).with_request(5);
+  let command = ReplicatorCommand::delete(delete, ActorRef::null());
+  let ReplicatorOperation::Delete { respond, data_deleted, .. } = command.operation() else {
+    panic!("delete operation expected");
+  };
+
+  let success = respond(DeleteWriteOutcome::Success);
+  let success = success.downcast_ref::<DeleteResponse<Flag, u64>>().expect("delete response");
+  assert!(matches!(success, DeleteResponse::Success { .. }));
+  assert_eq!(success.request(), Some(&5));
+  let deleted = data_deleted.downcast_ref::<DeleteResponse<Flag, u64>>().expect("delete response");
+  assert!(matches!(deleted, DeleteResponse::DataDeleted { .. }));
+}
+
+#[test]
+fn subscribe_notifies_changes_and_deletions_of_its_type() {
+  let command = ReplicatorCommand::subscribe(Subscribe::new(FlagKey::new("flag"), ActorRef::null()));
+  let ReplicatorOperation::Subscribe { key, notify, .. } = command.operation() else {
+    panic!("subscribe operation expected");
+  };
+
+  assert_eq!(key, "flag");
+  let changed = notify(&flag_envelope(Flag::disabled().switch_on())).expect("change notification");
+  let changed = changed.downcast_ref::<SubscribeResponse<Flag>>().expect("subscribe response");
+  assert!(changed.data().expect("changed value").is_enabled());
+  let deleted = notify(&DataEnvelope::deleted()).expect("deletion notification");
+  assert!(matches!(deleted.downcast_ref::<SubscribeResponse<Flag>>(), Some(SubscribeResponse::Deleted { .. })));
+  let counter = DataEnvelope::new(ArcShared::new(GCounter::new()) as ArcShared<dyn ErasedReplicatorData>);
+  assert!(notify(&counter).is_none());
+}
+
+#[test]
+fn unsubscribe_and_membership_commands_carry_their_targets() {
+  let command =
+    ReplicatorCommand::unsubscribe(&Unsubscribe::<Flag, ActorRef>::new(FlagKey::new("flag"), ActorRef::null()));
+  assert!(matches!(command.operation(), ReplicatorOperation::Unsubscribe { key, .. } if key == "flag"));
+
+  let node = UniqueAddress::new(Address::new("sys", "node-b", 2553), 2);
+  let command = ReplicatorCommand::member_up(node.clone(), ActorRef::null());
+  assert!(matches!(command.operation(), ReplicatorOperation::MemberUp { node: up, .. } if *up == node));
+  let command = ReplicatorCommand::member_removed(node.clone());
+  assert!(matches!(command.operation(), ReplicatorOperation::MemberRemoved { node: removed } if *removed == node));
+}
@@ -0,0 +1,62 @@
+//! Runtime contract for CRDT values managed by the Replicator.
+
+use alloc::collections::BTreeSet;
+
+use fraktor_remote_core_rs::address::UniqueAddress;
+
+use super::ReplicatedData;
+
+/// CRDT value that the Replicator can store, replicate, and prune.
+///
+/// The delta and pruning hooks default to full-state-only replication without pruning, so plain
+/// [`ReplicatedData`] values only need an empty implementation. Values implementing
+/// [`DeltaReplicatedData`](super::DeltaReplicatedData) or
+/// [`RemovedNodePruning`](super::RemovedNodePruning) forward to those contracts.
+pub trait ReplicatorData: ReplicatedData + PartialEq + Send + Sync + 'static {
+  /// Splits the accumulated delta off this value.
+  ///
+  /// Returns the delta together with this value with its delta cleared, or `None` when there is
+  /// nothing to propagate.
+  #[must_use]
+  fn split_delta(&self) -> Option<(Self, Self)> {
+    None
+  }
+
+  /// Applies a delta split off another replica of this value.
+  #[must_use]
+  fn merge_delta_from(&self, delta: &Self) -> Self {
+    self.merge(delta)
+  }
+
+  /// Returns the empty value a delta is applied to when the key has no local value.
+  #[must_use]
+  fn delta_zero(&self) -> Self {
+    self.clone()
+  }
+
+  /// Returns true when deltas of this value must be applied in the order they were produced.
+  #[must_use]
+  fn requires_causal_delivery(&self) -> bool {
+    false
+  }
+
+  /// Returns the nodes that have contributed to this value.
+  #[must_use]
+  fn pruning_nodes(&self) -> BTreeSet<UniqueAddress> {
+    BTreeSet::new()
+  }
+
+  /// Moves the contribution of `removed_node` into `collapse_into`.
+  ///
+  /// Returns `None` when the value cannot be pruned.
+  #[must_use]
+  fn prune_node(&self, _removed_node: &UniqueAddress, _collapse_into: &UniqueAddress) -> Option<Self> {
+    None
+  }
+
+  /// Removes residual contribution of `removed_node` after it has been pruned elsewhere.
+  #[must_use]
+  fn cleanup_node(&self, _removed_node: &UniqueAddress) -> Self {
+    self.clone()
+  }
+}
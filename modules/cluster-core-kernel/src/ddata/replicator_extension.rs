@@ -0,0 +1,73 @@
+//! Extension running the Replicator of an actor system.
+
+#[cfg(test)]
+#[path = "replicator_extension_test.rs"]
+mod tests;
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{actor_ref::ActorRef, extension::Extension, props::Props, spawn::SpawnError},
+  system::ActorSystem,
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{DurableStore, Replicator, ReplicatorSettings, SelfUniqueAddress};
+
+/// Extension giving access to the [`Replicator`] system actor of the local member.
+#[derive(Clone)]
+pub struct ReplicatorExtension {
+  replicator:   ActorRef,
+  self_address: SelfUniqueAddress,
+  settings:     ReplicatorSettings,
+}
+
+impl ReplicatorExtension {
+  /// Spawns the Replicator of `system` as a system actor named after `settings`.
+  ///
+  /// The durable keys of `settings` are kept in `durable_store` when one is given.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`SpawnError`] when the system actor cannot be spawned.
+  pub fn new(
+    system: &ActorSystem,
+    self_address: SelfUniqueAddress,
+    settings: ReplicatorSettings,
+    durable_store: Option<ArcShared<dyn DurableStore>>,
+  ) -> Result<Self, SpawnError> {
+    let props = {
+      let self_address = self_address.clone();
+      let actor_settings = settings.clone();
+      Props::from_fn(move || {
+        let replicator = Replicator::new(&self_address, actor_settings.clone());
+        match &durable_store {
+          | Some(store) => replicator.with_durable_store(store.clone()),
+          | None => replicator,
+        }
+      })
+      .with_name(settings.name())
+    };
+    let replicator = system.extended().spawn_system_actor(&props)?.into_actor_ref();
+    Ok(Self { replicator, self_address, settings })
+  }
+
+  /// Returns the Replicator actor that accepts
+  /// [`ReplicatorCommand`](super::ReplicatorCommand)s.
+  #[must_use]
+  pub fn replicator(&self) -> ActorRef {
+    self.replicator.clone()
+  }
+
+  /// Returns the address of the local member used by node-local CRDT updates.
+  #[must_use]
+  pub const fn self_unique_address(&self) -> &SelfUniqueAddress {
+    &self.self_address
+  }
+
+  /// Returns the settings the Replicator runs with.
+  #[must_use]
+  pub const fn settings(&self) -> &ReplicatorSettings {
+    &self.settings
+  }
+}
+
+impl Extension for ReplicatorExtension {}
@@ -0,0 +1,42 @@
+//! Extension identifier for the Replicator.
+
+use fraktor_actor_core_kernel_rs::{actor::extension::ExtensionId, system::ActorSystem};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{DurableStore, ReplicatorExtension, ReplicatorSettings, SelfUniqueAddress};
+
+/// Registers and instantiates the [`ReplicatorExtension`].
+pub struct ReplicatorExtensionId {
+  self_address:  SelfUniqueAddress,
+  settings:      ReplicatorSettings,
+  durable_store: Option<ArcShared<dyn DurableStore>>,
+}
+
+impl ReplicatorExtensionId {
+  /// Creates an identifier for the Replicator of the local member `self_address`.
+  #[must_use]
+  pub const fn new(self_address: SelfUniqueAddress, settings: ReplicatorSettings) -> Self {
+    Self { self_address, settings, durable_store: None }
+  }
+
+  /// Returns the identifier keeping the durable keys in `store`.
+  #[must_use]
+  pub fn with_durable_store(mut self, store: ArcShared<dyn DurableStore>) -> Self {
+    self.durable_store = Some(store);
+    self
+  }
+}
+
+impl ExtensionId for ReplicatorExtensionId {
+  type Ext = ReplicatorExtension;
+
+  fn create_extension(&self, system: &ActorSystem) -> Self::Ext {
+    match ReplicatorExtension::new(system, self.self_address.clone(), self.settings.clone(), self.durable_store.clone())
+    {
+      | Ok(extension) => extension,
+      | Err(error) => {
+        panic!("replicator extension bootstrap failed: {error:?}");
+      },
+    }
+  }
+}
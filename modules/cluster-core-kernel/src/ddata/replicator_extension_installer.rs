@@ -0,0 +1,49 @@
+//! Installer for the Replicator extension.
+
+use fraktor_actor_core_kernel_rs::{
+  actor::extension::{ExtensionInstaller, install_extension_id},
+  system::{ActorSystem, ActorSystemBuildError},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{DurableStore, ReplicatorExtensionId, ReplicatorSettings, SelfUniqueAddress};
+
+/// Installs the [`ReplicatorExtension`](super::ReplicatorExtension) into the actor system.
+pub struct ReplicatorExtensionInstaller {
+  self_address:  SelfUniqueAddress,
+  settings:      ReplicatorSettings,
+  durable_store: Option<ArcShared<dyn DurableStore>>,
+}
+
+impl ReplicatorExtensionInstaller {
+  /// Creates an installer for the local member `self_address` with default settings.
+  #[must_use]
+  pub fn new(self_address: SelfUniqueAddress) -> Self {
+    Self { self_address, settings: ReplicatorSettings::default(), durable_store: None }
+  }
+
+  /// Returns the installer with the Replicator settings replaced.
+  #[must_use]
+  pub fn with_settings(mut self, settings: ReplicatorSettings) -> Self {
+    self.settings = settings;
+    self
+  }
+
+  /// Returns the installer keeping the durable keys of the settings in `store`.
+  #[must_use]
+  pub fn with_durable_store(mut self, store: ArcShared<dyn DurableStore>) -> Self {
+    self.durable_store = Some(store);
+    self
+  }
+}
+
+impl ExtensionInstaller for ReplicatorExtensionInstaller {
+  fn install(&self, system: &ActorSystem) -> Result<(), ActorSystemBuildError> {
+    let mut extension_id = ReplicatorExtensionId::new(self.self_address.clone(), self.settings.clone());
+    if let Some(store) = &self.durable_store {
+      extension_id = extension_id.with_durable_store(store.clone());
+    }
+    install_extension_id(system, &extension_id);
+    Ok(())
+  }
+}
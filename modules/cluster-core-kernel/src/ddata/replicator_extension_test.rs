@@ -0,0 +1,41 @@
+use alloc::string::ToString;
+use core::time::Duration;
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext, error::ActorError, extension::ExtensionInstallers, messaging::AnyMessageView, props::Props,
+    scheduler::SchedulerConfig, setup::ActorSystemConfig,
+  },
+  system::ActorSystem,
+};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+
+use super::ReplicatorExtension;
+use crate::ddata::{ReplicatorExtensionInstaller, ReplicatorSettings, SelfUniqueAddress};
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+#[test]
+fn installer_spawns_the_replicator_with_the_configured_settings() {
+  let self_address = SelfUniqueAddress::new(UniqueAddress::new(Address::new("sys", "node-a", 2552), 1));
+  let settings = ReplicatorSettings::new().with_name("replicator").with_gossip_interval(Duration::from_millis(100));
+  let installer = ReplicatorExtensionInstaller::new(self_address.clone()).with_settings(settings.clone());
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(ExtensionInstallers::default().with_extension_installer(installer));
+  let system = ActorSystem::create_from_props(&Props::from_fn(|| GuardianActor), config).expect("system should build");
+
+  let extension = system.extended().extension_by_type::<ReplicatorExtension>().expect("extension is installed");
+
+  assert_eq!(extension.self_unique_address(), &self_address);
+  assert_eq!(extension.settings(), &settings);
+  let path = extension.replicator().path().expect("replicator has a path");
+  assert!(path.to_string().ends_with("/replicator"), "unexpected path {path}");
+}
@@ -0,0 +1,69 @@
+//! Type-erased operations carried by Replicator commands.
+
+use alloc::string::String;
+
+use fraktor_actor_core_kernel_rs::actor::{actor_ref::ActorRef, messaging::AnyMessage};
+use fraktor_remote_core_rs::address::UniqueAddress;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{
+  DataEnvelope, DeleteWriteOutcome, ErasedReplicatorData, ReadConsistency, UpdateWriteOutcome, WriteConsistency,
+};
+
+/// Builds the reply of a read from the envelope it observed.
+pub(crate) type ReadResponder = ArcShared<dyn Fn(Option<&DataEnvelope>) -> AnyMessage + Send + Sync>;
+
+/// Computes the new value of an update, or the reply rejecting it.
+pub(crate) type UpdateModifier =
+  ArcShared<dyn Fn(Option<&DataEnvelope>) -> Result<ArcShared<dyn ErasedReplicatorData>, AnyMessage> + Send + Sync>;
+
+/// Builds the reply of an accepted update once its write outcome is known.
+pub(crate) type UpdateResponder = ArcShared<dyn Fn(UpdateWriteOutcome) -> AnyMessage + Send + Sync>;
+
+/// Builds the reply of an accepted delete once its write outcome is known.
+pub(crate) type DeleteResponder = ArcShared<dyn Fn(DeleteWriteOutcome) -> AnyMessage + Send + Sync>;
+
+/// Builds the change notification of a subscriber, or `None` for a value of another type.
+pub(crate) type SubscriberNotifier = ArcShared<dyn Fn(&DataEnvelope) -> Option<AnyMessage> + Send + Sync>;
+
+/// Operation requested from the Replicator through a `ReplicatorCommand`.
+#[derive(Clone)]
+pub(crate) enum ReplicatorOperation {
+  Get {
+    key:         String,
+    consistency: ReadConsistency,
+    respond:     ReadResponder,
+    failure:     AnyMessage,
+    reply_to:    ActorRef,
+  },
+  Update {
+    key:         String,
+    consistency: WriteConsistency,
+    modify:      UpdateModifier,
+    respond:     UpdateResponder,
+    reply_to:    ActorRef,
+  },
+  Delete {
+    key:          String,
+    consistency:  WriteConsistency,
+    respond:      DeleteResponder,
+    data_deleted: AnyMessage,
+    reply_to:     ActorRef,
+  },
+  Subscribe {
+    key:        String,
+    subscriber: ActorRef,
+    notify:     SubscriberNotifier,
+  },
+  Unsubscribe {
+    key:        String,
+    subscriber: ActorRef,
+  },
+  MemberUp {
+    node:       UniqueAddress,
+    replicator: ActorRef,
+  },
+  MemberRemoved {
+    node: UniqueAddress,
+  },
+}
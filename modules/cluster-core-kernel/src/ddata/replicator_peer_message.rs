@@ -0,0 +1,36 @@
+//! Messages exchanged between the Replicators of different members.
+
+use alloc::{string::String, vec::Vec};
+
+use fraktor_remote_core_rs::address::UniqueAddress;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{DataEnvelope, ErasedReplicatorData};
+
+/// Delta of one key numbered by its origin Replicator.
+#[derive(Clone)]
+pub(crate) struct DeltaEntry {
+  pub(crate) key:   String,
+  pub(crate) seq:   u64,
+  pub(crate) delta: ArcShared<dyn ErasedReplicatorData>,
+}
+
+/// Replicator-to-Replicator protocol.
+///
+/// Messages expecting an answer are sent with the requesting Replicator as sender, and the answer
+/// is sent back to that sender.
+#[derive(Clone)]
+pub(crate) enum ReplicatorPeerMessage {
+  /// Full state of the sender; answered with the receiver's full state when `send_back` is set.
+  Gossip { entries: Vec<(String, DataEnvelope)>, send_back: bool },
+  /// Deltas accumulated by `from` since its previous propagation.
+  DeltaPropagation { from: UniqueAddress, deltas: Vec<DeltaEntry> },
+  /// Requests the receiver's envelope of `key`.
+  Read { key: String, request_id: u64 },
+  /// Answers a read; `envelope` is `None` when the receiver has no value.
+  ReadResult { request_id: u64, envelope: Option<DataEnvelope> },
+  /// Asks the receiver to merge `envelope` into its value of `key`.
+  Write { key: String, envelope: DataEnvelope, request_id: u64 },
+  /// Acknowledges a merged write.
+  WriteAck { request_id: u64 },
+}
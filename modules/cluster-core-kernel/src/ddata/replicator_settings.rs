@@ -0,0 +1,196 @@
+//! Replicator runtime configuration.
+
+#[cfg(test)]
+#[path = "replicator_settings_test.rs"]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use core::time::Duration;
+
+const DEFAULT_NAME: &str = "ddataReplicator";
+const DURABLE_KEY_WILDCARD: char = '*';
+
+/// Timing and naming configuration of the Replicator actor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplicatorSettings {
+  name: String,
+  gossip_interval: Duration,
+  notify_subscribers_interval: Duration,
+  delta_propagation_interval: Duration,
+  pruning_interval: Duration,
+  max_pruning_dissemination: Duration,
+  delta_crdt_enabled: bool,
+  durable_keys: Vec<String>,
+  durable_write_behind_interval: Duration,
+}
+
+impl ReplicatorSettings {
+  /// Creates the default settings.
+  #[must_use]
+  pub fn new() -> Self {
+    Self {
+      name: String::from(DEFAULT_NAME),
+      gossip_interval: Duration::from_secs(2),
+      notify_subscribers_interval: Duration::from_millis(500),
+      delta_propagation_interval: Duration::from_millis(200),
+      pruning_interval: Duration::from_secs(120),
+      max_pruning_dissemination: Duration::from_secs(300),
+      delta_crdt_enabled: true,
+      durable_keys: Vec::new(),
+      durable_write_behind_interval: Duration::ZERO,
+    }
+  }
+
+  /// Returns settings with the system actor name of the Replicator replaced.
+  #[must_use]
+  pub fn with_name(mut self, name: impl Into<String>) -> Self {
+    self.name = name.into();
+    self
+  }
+
+  /// Returns settings with the full-state gossip interval replaced.
+  #[must_use]
+  pub const fn with_gossip_interval(mut self, interval: Duration) -> Self {
+    self.gossip_interval = interval;
+    self
+  }
+
+  /// Returns settings with the subscriber notification interval replaced.
+  #[must_use]
+  pub const fn with_notify_subscribers_interval(mut self, interval: Duration) -> Self {
+    self.notify_subscribers_interval = interval;
+    self
+  }
+
+  /// Returns settings with the delta propagation interval replaced.
+  #[must_use]
+  pub const fn with_delta_propagation_interval(mut self, interval: Duration) -> Self {
+    self.delta_propagation_interval = interval;
+    self
+  }
+
+  /// Returns settings with the removed-node pruning interval replaced.
+  #[must_use]
+  pub const fn with_pruning_interval(mut self, interval: Duration) -> Self {
+    self.pruning_interval = interval;
+    self
+  }
+
+  /// Returns settings with the time a performed pruning marker is kept replicated replaced.
+  #[must_use]
+  pub const fn with_max_pruning_dissemination(mut self, duration: Duration) -> Self {
+    self.max_pruning_dissemination = duration;
+    self
+  }
+
+  /// Returns settings with delta propagation enabled or disabled.
+  ///
+  /// Without delta propagation, updates reach other replicas through full-state gossip and the
+  /// writes of the requested consistency level only.
+  #[must_use]
+  pub const fn with_delta_crdt_enabled(mut self, enabled: bool) -> Self {
+    self.delta_crdt_enabled = enabled;
+    self
+  }
+
+  /// Returns settings with the patterns selecting durable keys replaced.
+  ///
+  /// A pattern matches the key with the same name, or every key starting with the prefix before a
+  /// trailing `*`; `"*"` alone selects every key. Durable keys are kept in the
+  /// [`DurableStore`](super::DurableStore) given to the Replicator.
+  #[must_use]
+  pub fn with_durable_keys<I, K>(mut self, patterns: I) -> Self
+  where
+    I: IntoIterator<Item = K>,
+    K: Into<String>, {
+    self.durable_keys = patterns.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Returns settings with the interval changed durable keys are batched over replaced.
+  ///
+  /// With a zero interval each change is stored before the Replicator handles its next message,
+  /// and updates and deletes of durable keys are answered only after their value is stored.
+  #[must_use]
+  pub const fn with_durable_write_behind_interval(mut self, interval: Duration) -> Self {
+    self.durable_write_behind_interval = interval;
+    self
+  }
+
+  /// Returns the system actor name of the Replicator.
+  #[must_use]
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Returns the full-state gossip interval.
+  #[must_use]
+  pub const fn gossip_interval(&self) -> Duration {
+    self.gossip_interval
+  }
+
+  /// Returns the subscriber notification interval.
+  #[must_use]
+  pub const fn notify_subscribers_interval(&self) -> Duration {
+    self.notify_subscribers_interval
+  }
+
+  /// Returns the delta propagation interval.
+  #[must_use]
+  pub const fn delta_propagation_interval(&self) -> Duration {
+    self.delta_propagation_interval
+  }
+
+  /// Returns the removed-node pruning interval.
+  #[must_use]
+  pub const fn pruning_interval(&self) -> Duration {
+    self.pruning_interval
+  }
+
+  /// Returns the time a performed pruning marker is kept replicated.
+  #[must_use]
+  pub const fn max_pruning_dissemination(&self) -> Duration {
+    self.max_pruning_dissemination
+  }
+
+  /// Returns true when deltas are propagated between replicas.
+  #[must_use]
+  pub const fn delta_crdt_enabled(&self) -> bool {
+    self.delta_crdt_enabled
+  }
+
+  /// Returns the patterns selecting durable keys.
+  #[must_use]
+  pub fn durable_keys(&self) -> &[String] {
+    &self.durable_keys
+  }
+
+  /// Returns the interval changed durable keys are batched over.
+  #[must_use]
+  pub const fn durable_write_behind_interval(&self) -> Duration {
+    self.durable_write_behind_interval
+  }
+
+  /// Returns true when `key` is selected by one of the durable key patterns.
+  #[must_use]
+  pub fn is_durable(&self, key: &str) -> bool {
+    self.durable_keys.iter().any(|pattern| match pattern.strip_suffix(DURABLE_KEY_WILDCARD) {
+      | Some(prefix) => key.starts_with(prefix),
+      | None => pattern == key,
+    })
+  }
+
+  /// Returns the number of pruning ticks a performed marker is kept before it is dropped.
+  #[must_use]
+  pub(crate) fn pruning_marker_ticks(&self) -> u64 {
+    let interval = self.pruning_interval.as_nanos().max(1);
+    let ticks = self.max_pruning_dissemination.as_nanos().div_ceil(interval);
+    u64::try_from(ticks).unwrap_or(u64::MAX).max(1)
+  }
+}
+
+impl Default for ReplicatorSettings {
+  fn default() -> Self {
+    Self::new()
+  }
+}
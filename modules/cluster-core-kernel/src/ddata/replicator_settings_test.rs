@@ -0,0 +1,69 @@
+use core::time::Duration;
+
+use super::ReplicatorSettings;
+
+#[test]
+fn default_settings_use_documented_values() {
+  let settings = ReplicatorSettings::default();
+
+  assert_eq!(settings.name(), "ddataReplicator");
+  assert_eq!(settings.gossip_interval(), Duration::from_secs(2));
+  assert_eq!(settings.notify_subscribers_interval(), Duration::from_millis(500));
+  assert_eq!(settings.delta_propagation_interval(), Duration::from_millis(200));
+  assert_eq!(settings.pruning_interval(), Duration::from_secs(120));
+  assert_eq!(settings.max_pruning_dissemination(), Duration::from_secs(300));
+  assert!(settings.delta_crdt_enabled());
+  assert!(settings.durable_keys().is_empty());
+  assert_eq!(settings.durable_write_behind_interval(), Duration::ZERO);
+}
+
+#[test]
+fn builders_replace_each_setting() {
+  let settings = ReplicatorSettings::new()
+    .with_name("replicator")
+    .with_gossip_interval(Duration::from_millis(10))
+    .with_notify_subscribers_interval(Duration::from_millis(20))
+    .with_delta_propagation_interval(Duration::from_millis(30))
+    .with_pruning_interval(Duration::from_millis(40))
+    .with_max_pruning_dissemination(Duration::from_millis(50))
+    .with_delta_crdt_enabled(false)
+    .with_durable_keys(["counter"])
+    .with_durable_write_behind_interval(Duration::from_millis(60));
+
+  assert_eq!(settings.name(), "replicator");
+  assert_eq!(settings.gossip_interval(), Duration::from_millis(10));
+  assert_eq!(settings.notify_subscribers_interval(), Duration::from_millis(20));
+  assert_eq!(settings.delta_propagation_interval(), Duration::from_millis(30));
+  assert_eq!(settings.pruning_interval(), Duration::from_millis(40));
+  assert_eq!(settings.max_pruning_dissemination(), Duration::from_millis(50));
+  assert!(!settings.delta_crdt_enabled());
+  assert_eq!(settings.durable_keys(), ["counter"]);
+  assert_eq!(settings.durable_write_behind_interval(), Duration::from_millis(60));
+}
+
+#[test]
+fn durable_key_patterns_match_names_and_prefixes() {
+  let settings = ReplicatorSettings::new().with_durable_keys(["counter", "cart-*"]);
+
+  assert!(settings.is_durable("counter"));
+  assert!(!settings.is_durable("counter-2"));
+  assert!(settings.is_durable("cart-"));
+  assert!(settings.is_durable("cart-42"));
+  assert!(!settings.is_durable("carts"));
+  assert!(!ReplicatorSettings::new().is_durable("counter"));
+  assert!(ReplicatorSettings::new().with_durable_keys(["*"]).is_durable("anything"));
+}
+
+#[test]
+fn pruning_marker_ticks_round_up_and_keep_at_least_one_tick() {
+  let settings = ReplicatorSettings::new()
+    .with_pruning_interval(Duration::from_secs(120))
+    .with_max_pruning_dissemination(Duration::from_secs(300));
+  assert_eq!(settings.pruning_marker_ticks(), 3);
+
+  let settings = settings.with_max_pruning_dissemination(Duration::ZERO);
+  assert_eq!(settings.pruning_marker_ticks(), 1);
+
+  let settings = settings.with_pruning_interval(Duration::ZERO).with_max_pruning_dissemination(Duration::from_nanos(5));
+  assert_eq!(settings.pruning_marker_ticks(), 5);
+}
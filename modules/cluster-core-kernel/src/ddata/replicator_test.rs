@@ -0,0 +1,424 @@
+use alloc::{boxed::Box, collections::BTreeMap, format, string::String, vec::Vec};
+use core::{
+  any::Any,
+  sync::atomic::{AtomicBool, Ordering},
+  time::Duration,
+};
+use std::{thread, time::Instant};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext,
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView},
+    props::Props,
+    scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  system::ActorSystem,
+};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+use super::Replicator;
+use crate::ddata::{
+  Delete, DeleteResponse, DurableDataEnvelope, DurableStore, DurableStoreError, Flag, FlagKey, FlushChanges, GCounter,
+  GCounterKey, Get, GetReplicaCount, GetResponse, ReadConsistency, ReplicaCount, ReplicatorCommand, ReplicatorSettings,
+  SelfUniqueAddress, Subscribe, SubscribeResponse, Unsubscribe, Update, UpdateResponse, WriteConsistency,
+};
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+type Received = ArcShared<SpinSyncMutex<Vec<Box<dyn Any + Send + Sync>>>>;
+
+type Capture = fn(&AnyMessageView<'_>) -> Option<Box<dyn Any + Send + Sync>>;
+
+const CAPTURES: &[Capture] = &[
+  capture::<GetResponse<GCounter>>,
+  capture::<GetResponse<GCounter, String>>,
+  capture::<UpdateResponse<GCounter>>,
+  capture::<UpdateResponse<Flag>>,
+  capture::<DeleteResponse<GCounter>>,
+  capture::<DeleteResponse<Flag>>,
+  capture::<SubscribeResponse<Flag>>,
+  capture::<ReplicaCount>,
+];
+
+fn capture<T: Clone + Send + Sync + 'static>(message: &AnyMessageView<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+  message.downcast_ref::<T>().map(|message| Box::new(message.clone()) as Box<dyn Any + Send + Sync>)
+}
+
+struct ProbeActor {
+  received: Received,
+}
+
+impl Actor for ProbeActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(captured) = CAPTURES.iter().find_map(|capture| capture(&message)) {
+      self.received.lock().push(captured);
+    }
+    Ok(())
+  }
+}
+
+struct Probe {
+  actor_ref: ActorRef,
+  received:  Received,
+}
+
+impl Probe {
+  fn spawn(system: &ActorSystem, name: &str) -> Self {
+    let received = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+    let props = {
+      let received = received.clone();
+      Props::from_fn(move || ProbeActor { received: received.clone() }).with_name(name)
+    };
+    let actor_ref = system.extended().spawn_system_actor(&props).expect("spawn probe").into_actor_ref();
+    Self { actor_ref, received }
+  }
+
+  fn expect<T: Clone + 'static>(&self) -> T {
+    let deadline = Instant::now() + Duration::from_secs(3);
+    loop {
+      {
+        let mut received = self.received.lock();
+        if let Some(index) = received.iter().position(|message| message.is::<T>()) {
+          let message = received.remove(index);
+          return message.downcast_ref::<T>().cloned().expect("message type was checked");
+        }
+      }
+      assert!(Instant::now() < deadline, "expected message did not arrive");
+      thread::yield_now();
+    }
+  }
+
+  fn count<T: 'static>(&self) -> usize {
+    self.received.lock().iter().filter(|message| message.is::<T>()).count()
+  }
+}
+
+struct MemoryDurableStore {
+  entries: SpinSyncMutex<BTreeMap<String, DurableDataEnvelope>>,
+  batches: SpinSyncMutex<Vec<Vec<String>>>,
+  failing: AtomicBool,
+}
+
+impl MemoryDurableStore {
+  fn new() -> Self {
+    Self {
+      entries: SpinSyncMutex::new(BTreeMap::new()),
+      batches: SpinSyncMutex::new(Vec::new()),
+      failing: AtomicBool::new(false),
+    }
+  }
+
+  fn counter(&self, key: &str) -> Option<u128> {
+    let entries = self.entries.lock();
+    entries.get(key)?.data_as::<GCounter>().map(|counter| counter.value().expect("value fits"))
+  }
+}
+
+impl DurableStore for MemoryDurableStore {
+  fn load_all(&self) -> Result<BTreeMap<String, DurableDataEnvelope>, DurableStoreError> {
+    Ok(self.entries.lock().clone())
+  }
+
+  fn load(&self, key: &str) -> Result<Option<DurableDataEnvelope>, DurableStoreError> {
+    Ok(self.entries.lock().get(key).cloned())
+  }
+
+  fn store(&self, entries: &BTreeMap<String, DurableDataEnvelope>) -> Result<(), DurableStoreError> {
+    if self.failing.load(Ordering::Acquire) {
+      return Err(DurableStoreError::StoreFailed { reason: String::from("disk full") });
+    }
+    self.entries.lock().extend(entries.iter().map(|(key, envelope)| (key.clone(), envelope.clone())));
+    self.batches.lock().push(entries.keys().cloned().collect());
+    Ok(())
+  }
+}
+
+fn self_address() -> SelfUniqueAddress {
+  SelfUniqueAddress::new(UniqueAddress::new(Address::new("sys", "node-a", 2552), 1))
+}
+
+fn spawn_replicator() -> (ActorSystem, ActorRef) {
+  spawn_replicator_with(|settings| Replicator::new(&self_address(), settings))
+}
+
+fn spawn_durable_replicator(
+  settings: ReplicatorSettings,
+  store: &ArcShared<MemoryDurableStore>,
+) -> (ActorSystem, ActorRef) {
+  let store = store.clone() as ArcShared<dyn DurableStore>;
+  spawn_replicator_with(move |defaults| {
+    let settings = settings.clone().with_notify_subscribers_interval(defaults.notify_subscribers_interval());
+    Replicator::new(&self_address(), settings).with_durable_store(store.clone())
+  })
+}
+
+fn spawn_replicator_with(
+  create: impl Fn(ReplicatorSettings) -> Replicator + Send + Sync + 'static,
+) -> (ActorSystem, ActorRef) {
+  let props = Props::from_fn(|| GuardianActor);
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  let config = ActorSystemConfig::new(TestTickDriver::default()).with_scheduler_config(scheduler);
+  let system = ActorSystem::create_from_props(&props, config).expect("system should build");
+  let settings = ReplicatorSettings::new().with_notify_subscribers_interval(Duration::from_secs(60));
+  let replicator_props = Props::from_fn(move || create(settings.clone())).with_name("ddata");
+  let replicator = system.extended().spawn_system_actor(&replicator_props).expect("spawn replicator").into_actor_ref();
+  (system, replicator)
+}
+
+fn increment(replicator: &mut ActorRef, probe: &Probe, amount: u64) {
+  increment_key(replicator, probe, "counter", amount);
+}
+
+fn increment_key(replicator: &mut ActorRef, probe: &Probe, key: &str, amount: u64) {
+  let update = Update::<GCounter>::new(GCounterKey::new(key), WriteConsistency::Local);
+  let command = ReplicatorCommand::update(
+    update,
+    move |current: Option<&GCounter>| {
+      current.cloned().unwrap_or_default().increment(&self_address(), amount).map_err(|error| format!("{error:?}"))
+    },
+    probe.actor_ref.clone(),
+  );
+  replicator.tell(AnyMessage::new(command));
+}
+
+#[test]
+fn local_updates_are_visible_to_local_reads() {
+  let (system, mut replicator) = spawn_replicator();
+  let probe = Probe::spawn(&system, "probe");
+  let get = || Get::<GCounter>::new(GCounterKey::new("counter"), ReadConsistency::Local);
+
+  replicator.tell(AnyMessage::new(ReplicatorCommand::get(get(), probe.actor_ref.clone())));
+  assert!(matches!(probe.expect::<GetResponse<GCounter>>(), GetResponse::NotFound { .. }));
+
+  increment(&mut replicator, &probe, 2);
+  increment(&mut replicator, &probe, 3);
+  assert!(matches!(probe.expect::<UpdateResponse<GCounter>>(), UpdateResponse::Success { .. }));
+  assert!(matches!(probe.expect::<UpdateResponse<GCounter>>(), UpdateResponse::Success { .. }));
+
+  replicator.tell(AnyMessage::new(ReplicatorCommand::get(get(), probe.actor_ref.clone())));
+  let response = probe.expect::<GetResponse<GCounter>>();
+  assert_eq!(response.data().expect("counter is present").value().expect("value fits"), 5);
+}
+
+#[test]
+fn deleted_keys_reject_later_updates_and_deletes() {
+  let (system, mut replicator) = spawn_replicator();
+  let probe = Probe::spawn(&system, "probe");
+  let delete = || Delete::<GCounter>::new(GCounterKey::new("counter"), WriteConsistency::Local);
+
+  increment(&mut replicator, &probe, 1);
+  probe.expect::<UpdateResponse<GCounter>>();
+  replicator.tell(AnyMessage::new(ReplicatorCommand::delete(delete(), probe.actor_ref.clone())));
+  assert!(matches!(probe.expect::<DeleteResponse<GCounter>>(), DeleteResponse::Success { .. }));
+
+  increment(&mut replicator, &probe, 1);
+  assert!(matches!(probe.expect::<UpdateResponse<GCounter>>(), UpdateResponse::DataDeleted { .. }));
+  replicator.tell(AnyMessage::new(ReplicatorCommand::delete(delete(), probe.actor_ref.clone())));
+  assert!(matches!(probe.expect::<DeleteResponse<GCounter>>(), DeleteResponse::DataDeleted { .. }));
+}
+
+#[test]
+fn subscribers_receive_current_value_and_flushed_changes() {
+  let (system, mut replicator) = spawn_replicator();
+  let probe = Probe::spawn(&system, "probe");
+  let subscriber = Probe::spawn(&system, "subscriber");
+  let key = FlagKey::new("flag");
+  let switch_on = || {
+    let update = Update::<Flag>::new(FlagKey::new("flag"), WriteConsistency::Local);
+    ReplicatorCommand::update(
+      update,
+      |current: Option<&Flag>| Ok(current.cloned().unwrap_or_default().switch_on()),
+      probe.actor_ref.clone(),
+    )
+  };
+
+  replicator.tell(AnyMessage::new(switch_on()));
+  probe.expect::<UpdateResponse<Flag>>();
+  replicator
+    .tell(AnyMessage::new(ReplicatorCommand::subscribe(Subscribe::new(key.clone(), subscriber.actor_ref.clone()))));
+  let current = subscriber.expect::<SubscribeResponse<Flag>>();
+  assert!(current.data().expect("current value").is_enabled());
+
+  replicator.tell(AnyMessage::new(ReplicatorCommand::delete(
+    Delete::<Flag>::new(key.clone(), WriteConsistency::Local),
+    probe.actor_ref.clone(),
+  )));
+  probe.expect::<DeleteResponse<Flag>>();
+  replicator.tell(AnyMessage::new(FlushChanges));
+  assert!(matches!(subscriber.expect::<SubscribeResponse<Flag>>(), SubscribeResponse::Deleted { .. }));
+
+  replicator
+    .tell(AnyMessage::new(ReplicatorCommand::unsubscribe(&Unsubscribe::new(key, subscriber.actor_ref.clone()))));
+  replicator.tell(AnyMessage::new(FlushChanges));
+  replicator.tell(AnyMessage::new(GetReplicaCount).with_sender(probe.actor_ref.clone()));
+  probe.expect::<ReplicaCount>();
+  assert_eq!(subscriber.count::<SubscribeResponse<Flag>>(), 0);
+}
+
+#[test]
+fn replica_count_includes_known_members() {
+  let (system, mut replicator) = spawn_replicator();
+  let probe = Probe::spawn(&system, "probe");
+  let peer = UniqueAddress::new(Address::new("sys", "node-b", 2553), 2);
+
+  replicator.tell(AnyMessage::new(GetReplicaCount).with_sender(probe.actor_ref.clone()));
+  assert_eq!(probe.expect::<ReplicaCount>().get(), 1);
+
+  replicator.tell(AnyMessage::new(ReplicatorCommand::member_up(peer.clone(), probe.actor_ref.clone())));
+  replicator.tell(AnyMessage::new(GetReplicaCount).with_sender(probe.actor_ref.clone()));
+  assert_eq!(probe.expect::<ReplicaCount>().get(), 2);
+
+  replicator.tell(AnyMessage::new(ReplicatorCommand::member_removed(peer)));
+  replicator.tell(AnyMessage::new(GetReplicaCount).with_sender(probe.actor_ref.clone()));
+  assert_eq!(probe.expect::<ReplicaCount>().get(), 1);
+}
+
+#[test]
+fn reads_above_local_fail_when_peers_do_not_answer() {
+  let (system, mut replicator) = spawn_replicator();
+  let probe = Probe::spawn(&system, "probe");
+  let silent_peer = Probe::spawn(&system, "silent-peer");
+  let peer = UniqueAddress::new(Address::new("sys", "node-b", 2553), 2);
+  replicator.tell(AnyMessage::new(ReplicatorCommand::member_up(peer, silent_peer.actor_ref.clone())));
+
+  let get = Get::<GCounter, String>::new(GCounterKey::new("counter"), ReadConsistency::All {
+    timeout: Duration::from_millis(50),
+  })
+  .with_request(String::from("read"));
+  replicator.tell(AnyMessage::new(ReplicatorCommand::get(get, probe.actor_ref.clone())));
+
+  let response = probe.expect::<GetResponse<GCounter, String>>();
+  assert!(matches!(response, GetResponse::Failure { .. }));
+  assert_eq!(response.request().map(String::as_str), Some("read"));
+}
+
+fn read_counter(replicator: &mut ActorRef, probe: &Probe, key: &str) -> GetResponse<GCounter> {
+  let get = Get::<GCounter>::new(GCounterKey::new(key), ReadConsistency::Local);
+  replicator.tell(AnyMessage::new(ReplicatorCommand::get(get, probe.actor_ref.clone())));
+  probe.expect::<GetResponse<GCounter>>()
+}
+
+fn stored_counter(amount: u64) -> DurableDataEnvelope {
+  DurableDataEnvelope::new(GCounter::new().increment(&self_address(), amount).expect("small increments fit"))
+}
+
+#[test]
+fn durable_keys_are_loaded_before_the_first_command() {
+  let store = ArcShared::new(MemoryDurableStore::new());
+  store
+    .entries
+    .lock()
+    .extend([(String::from("counter"), stored_counter(4)), (String::from("volatile"), stored_counter(9))]);
+  let settings = ReplicatorSettings::new().with_durable_keys(["counter"]);
+  let (system, mut replicator) = spawn_durable_replicator(settings, &store);
+  let probe = Probe::spawn(&system, "probe");
+
+  let loaded = read_counter(&mut replicator, &probe, "counter");
+  assert_eq!(loaded.data().expect("loaded counter").value().expect("value fits"), 4);
+  assert!(matches!(read_counter(&mut replicator, &probe, "volatile"), GetResponse::NotFound { .. }));
+  assert!(store.batches.lock().is_empty());
+}
+
+#[test]
+fn changes_of_durable_keys_are_written_through() {
+  let store = ArcShared::new(MemoryDurableStore::new());
+  let settings = ReplicatorSettings::new().with_durable_keys(["counter"]);
+  let (system, mut replicator) = spawn_durable_replicator(settings, &store);
+  let probe = Probe::spawn(&system, "probe");
+
+  increment(&mut replicator, &probe, 2);
+  assert!(matches!(probe.expect::<UpdateResponse<GCounter>>(), UpdateResponse::Success { .. }));
+  assert_eq!(store.counter("counter"), Some(2));
+
+  increment_key(&mut replicator, &probe, "volatile", 1);
+  probe.expect::<UpdateResponse<GCounter>>();
+  assert!(store.entries.lock().get("volatile").is_none());
+
+  let delete = Delete::<GCounter>::new(GCounterKey::new("counter"), WriteConsistency::Local);
+  replicator.tell(AnyMessage::new(ReplicatorCommand::delete(delete, probe.actor_ref.clone())));
+  probe.expect::<DeleteResponse<GCounter>>();
+  assert!(store.entries.lock().get("counter").is_some_and(DurableDataEnvelope::is_deleted));
+}
+
+#[test]
+fn store_failures_are_reported_to_writers() {
+  let store = ArcShared::new(MemoryDurableStore::new());
+  store.failing.store(true, Ordering::Release);
+  let settings = ReplicatorSettings::new().with_durable_keys(["*"]);
+  let (system, mut replicator) = spawn_durable_replicator(settings, &store);
+  let probe = Probe::spawn(&system, "probe");
+
+  increment(&mut replicator, &probe, 1);
+  assert!(matches!(probe.expect::<UpdateResponse<GCounter>>(), UpdateResponse::StoreFailure { .. }));
+  let delete = Delete::<GCounter>::new(GCounterKey::new("counter"), WriteConsistency::Local);
+  replicator.tell(AnyMessage::new(ReplicatorCommand::delete(delete, probe.actor_ref.clone())));
+  assert!(matches!(probe.expect::<DeleteResponse<GCounter>>(), DeleteResponse::StoreFailure { .. }));
+
+  // 失敗した変更は保留に残り、ストアの回復後に保存される。
+  store.failing.store(false, Ordering::Release);
+  increment_key(&mut replicator, &probe, "other", 1);
+  probe.expect::<UpdateResponse<GCounter>>();
+  assert!(store.entries.lock().get("counter").is_some_and(DurableDataEnvelope::is_deleted));
+}
+
+#[test]
+fn write_behind_keeps_failed_batches_dirty_and_retries_on_the_next_interval() {
+  let store = ArcShared::new(MemoryDurableStore::new());
+  store.failing.store(true, Ordering::Release);
+  let settings = ReplicatorSettings::new()
+    .with_durable_keys(["counter"])
+    .with_durable_write_behind_interval(Duration::from_millis(50));
+  let (system, mut replicator) = spawn_durable_replicator(settings, &store);
+  let probe = Probe::spawn(&system, "probe");
+
+  increment(&mut replicator, &probe, 4);
+  assert!(matches!(probe.expect::<UpdateResponse<GCounter>>(), UpdateResponse::Success { .. }));
+  thread::sleep(Duration::from_millis(200));
+  assert_eq!(store.counter("counter"), None);
+
+  store.failing.store(false, Ordering::Release);
+  let deadline = Instant::now() + Duration::from_secs(3);
+  while store.counter("counter").is_none() {
+    assert!(Instant::now() < deadline, "failed write-behind batch was not retried");
+    thread::yield_now();
+  }
+  assert_eq!(store.counter("counter"), Some(4));
+}
+
+#[test]
+fn write_behind_batches_changes_on_the_interval() {
+  let store = ArcShared::new(MemoryDurableStore::new());
+  let settings = ReplicatorSettings::new()
+    .with_durable_keys(["counter", "other"])
+    .with_durable_write_behind_interval(Duration::from_millis(200));
+  let (system, mut replicator) = spawn_durable_replicator(settings, &store);
+  let probe = Probe::spawn(&system, "probe");
+
+  increment(&mut replicator, &probe, 2);
+  increment(&mut replicator, &probe, 3);
+  increment_key(&mut replicator, &probe, "other", 1);
+  for _ in 0..3 {
+    assert!(matches!(probe.expect::<UpdateResponse<GCounter>>(), UpdateResponse::Success { .. }));
+  }
+  assert!(store.batches.lock().is_empty());
+
+  let deadline = Instant::now() + Duration::from_secs(3);
+  while store.batches.lock().is_empty() {
+    assert!(Instant::now() < deadline, "write-behind batch was not stored");
+    thread::yield_now();
+  }
+  assert_eq!(store.batches.lock()[0], [String::from("counter"), String::from("other")]);
+  assert_eq!(store.counter("counter"), Some(5));
+}
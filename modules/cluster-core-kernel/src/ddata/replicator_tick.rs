@@ -0,0 +1,20 @@
+//! Timer messages the Replicator schedules for itself.
+
+/// Periodic and timeout ticks of the Replicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReplicatorTick {
+  /// Pushes the full state to the next peer.
+  Gossip,
+  /// Notifies subscribers of the keys changed since the previous notification.
+  NotifySubscribers,
+  /// Sends accumulated deltas to every peer.
+  DeltaPropagation,
+  /// Advances removed-node pruning.
+  Pruning,
+  /// Stores the durable keys changed since the previous flush.
+  DurableFlush,
+  /// Fails the read with the given request id.
+  ReadTimeout(u64),
+  /// Times out the write with the given request id.
+  WriteTimeout(u64),
+}
@@ -69,20 +69,7 @@ impl<D: ReplicatedData, C: Clone> Update<D, C> {
         request: self.request.clone(),
       }),
       | ReplicatorEntry::Missing | ReplicatorEntry::Present(_) => match modify(entry.data()) {
-        | Ok(data) => {
-          let response = match outcome {
-            | UpdateWriteOutcome::Success => {
-              UpdateResponse::Success { key: self.key.clone(), request: self.request.clone() }
-            },
-            | UpdateWriteOutcome::Timeout => {
-              UpdateResponse::Timeout { key: self.key.clone(), request: self.request.clone() }
-            },
-            | UpdateWriteOutcome::StoreFailure => {
-              UpdateResponse::StoreFailure { key: self.key.clone(), request: self.request.clone() }
-            },
-          };
-          (ReplicatorEntry::Present(data), response)
-        },
+        | Ok(data) => (ReplicatorEntry::Present(data), self.write_response(outcome)),
         | Err(message) => (entry.clone(), UpdateResponse::ModifyFailure {
           key: self.key.clone(),
           message,
@@ -91,4 +78,20 @@ impl<D: ReplicatedData, C: Clone> Update<D, C> {
       },
     }
   }
+
+  /// Builds the response of an accepted update for the outcome of its write policy.
+  #[must_use]
+  pub fn write_response(&self, outcome: UpdateWriteOutcome) -> UpdateResponse<D, C> {
+    match outcome {
+      | UpdateWriteOutcome::Success => {
+        UpdateResponse::Success { key: self.key.clone(), request: self.request.clone() }
+      },
+      | UpdateWriteOutcome::Timeout => {
+        UpdateResponse::Timeout { key: self.key.clone(), request: self.request.clone() }
+      },
+      | UpdateWriteOutcome::StoreFailure => {
+        UpdateResponse::StoreFailure { key: self.key.clone(), request: self.request.clone() }
+      },
+    }
+  }
 }
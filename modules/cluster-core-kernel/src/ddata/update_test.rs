@@ -87,3 +87,16 @@ fn update_store_failure_still_applies_local_entry() {
   assert!(matches!(response, UpdateResponse::StoreFailure { .. }));
   assert!(response.is_locally_applied());
 }
+
+#[test]
+fn write_response_maps_each_outcome_with_request_context() {
+  let command = Update::<Flag, u32>::new(flag_key(), WriteConsistency::Local).with_request(7);
+
+  let success = command.write_response(UpdateWriteOutcome::Success);
+  let timeout = command.write_response(UpdateWriteOutcome::Timeout);
+
+  assert!(matches!(success, UpdateResponse::Success { .. }));
+  assert!(matches!(timeout, UpdateResponse::Timeout { .. }));
+  assert_eq!(timeout.request(), Some(&7));
+  assert_eq!(timeout.key(), &flag_key());
+}
@@ -8,7 +8,9 @@ use alloc::collections::{BTreeMap, BTreeSet};
 
 use fraktor_remote_core_rs::address::UniqueAddress;
 
-use super::{CounterArithmeticError, RemovedNodePruning, ReplicatedData, SelfUniqueAddress, VersionVectorOrdering};
+use super::{
+  CounterArithmeticError, RemovedNodePruning, ReplicatedData, ReplicatorData, SelfUniqueAddress, VersionVectorOrdering,
+};
 
 /// Version vector CRDT keyed by unique cluster node identity.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -209,6 +211,20 @@ impl RemovedNodePruning for VersionVector {
   }
 }
 
+impl ReplicatorData for VersionVector {
+  fn pruning_nodes(&self) -> BTreeSet<UniqueAddress> {
+    self.modified_by_nodes()
+  }
+
+  fn prune_node(&self, removed_node: &UniqueAddress, collapse_into: &UniqueAddress) -> Option<Self> {
+    self.prune(removed_node, collapse_into).ok()
+  }
+
+  fn cleanup_node(&self, removed_node: &UniqueAddress) -> Self {
+    self.pruning_cleanup(removed_node)
+  }
+}
+
 impl Default for VersionVector {
   fn default() -> Self {
     Self::new()
@@ -4,10 +4,28 @@
 #[path = "lease_majority_port_test.rs"]
 mod tests;
 
+use alloc::boxed::Box;
+
 use super::{DowningDecisionContext, LeaseAcquisitionOutcome};
+use crate::lease::{Lease, LeaseError};
 
 /// Port used by core SBR evaluation to observe a lease acquisition result.
 pub trait LeaseMajorityPort {
   /// Acquires or observes majority lease ownership for the current decision context.
   fn acquire_majority(&mut self, context: &DowningDecisionContext) -> LeaseAcquisitionOutcome;
 }
+
+impl LeaseMajorityPort for Box<dyn Lease> {
+  fn acquire_majority(&mut self, _context: &DowningDecisionContext) -> LeaseAcquisitionOutcome {
+    // 保持中ならハートビートを更新し、失っていれば改めて取得を試みる
+    if self.check_lease() && self.renew().is_ok() {
+      return LeaseAcquisitionOutcome::Acquired;
+    }
+    match self.acquire(None) {
+      | Ok(true) => LeaseAcquisitionOutcome::Acquired,
+      | Ok(false) | Err(LeaseError::Lost { .. }) => LeaseAcquisitionOutcome::Denied,
+      | Err(LeaseError::Unavailable { .. }) => LeaseAcquisitionOutcome::Unavailable,
+      | Err(LeaseError::UnknownImplementation { .. }) => LeaseAcquisitionOutcome::BackendMissing,
+    }
+  }
+}
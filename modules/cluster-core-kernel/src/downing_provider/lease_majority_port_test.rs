@@ -1,14 +1,72 @@
-use alloc::{string::String, vec};
+use alloc::{boxed::Box, string::String, vec};
 use core::time::Duration;
 
 use fraktor_remote_core_rs::address::{Address, UniqueAddress};
-use fraktor_utils_core_rs::time::TimerInstant;
+use fraktor_utils_core_rs::{
+  sync::{ArcShared, SpinSyncMutex},
+  time::TimerInstant,
+};
 
 use crate::{
   downing_provider::{DowningDecisionContext, LeaseAcquisitionOutcome, LeaseMajorityPort},
+  lease::{Lease, LeaseError, LeaseLostCallback, LeaseSettings},
   membership::{DataCenter, MembershipSnapshot, MembershipVersion, NodeRecord, NodeStatus},
 };
 
+type Holder = ArcShared<SpinSyncMutex<Option<String>>>;
+
+struct SharedLease {
+  settings:  LeaseSettings,
+  holder:    Holder,
+  reachable: bool,
+}
+
+impl SharedLease {
+  fn boxed(owner: &str, holder: &Holder) -> Box<dyn Lease> {
+    Box::new(Self { settings: LeaseSettings::new("sbr", owner), holder: holder.clone(), reachable: true })
+  }
+
+  fn unreachable(owner: &str, holder: &Holder) -> Box<dyn Lease> {
+    Box::new(Self { settings: LeaseSettings::new("sbr", owner), holder: holder.clone(), reachable: false })
+  }
+
+  fn check_reachable(&self) -> Result<(), LeaseError> {
+    if self.reachable { Ok(()) } else { Err(LeaseError::Unavailable { reason: String::from("offline") }) }
+  }
+}
+
+impl Lease for SharedLease {
+  fn settings(&self) -> &LeaseSettings {
+    &self.settings
+  }
+
+  fn acquire(&mut self, _on_lost: Option<LeaseLostCallback>) -> Result<bool, LeaseError> {
+    self.check_reachable()?;
+    let mut holder = self.holder.lock();
+    let owner = holder.get_or_insert_with(|| String::from(self.settings.owner_name()));
+    Ok(owner == self.settings.owner_name())
+  }
+
+  fn renew(&mut self) -> Result<(), LeaseError> {
+    self.check_reachable()?;
+    if self.check_lease() { Ok(()) } else { Err(LeaseError::Lost { reason: String::from("taken over") }) }
+  }
+
+  fn release(&mut self) -> Result<bool, LeaseError> {
+    self.check_reachable()?;
+    let mut holder = self.holder.lock();
+    let held = holder.as_deref() == Some(self.settings.owner_name());
+    if held {
+      *holder = None;
+    }
+    Ok(held)
+  }
+
+  fn check_lease(&self) -> bool {
+    self.holder.lock().as_deref() == Some(self.settings.owner_name())
+  }
+}
+
 struct RecordingLeasePort {
   outcome: LeaseAcquisitionOutcome,
   calls:   usize,
@@ -51,3 +109,25 @@ fn lease_majority_port_contract_uses_context_and_returns_outcome() {
   assert_eq!(outcome, LeaseAcquisitionOutcome::Unavailable);
   assert_eq!(port.calls, 1);
 }
+
+#[test]
+fn lease_port_grants_the_majority_to_one_owner() {
+  let holder: Holder = ArcShared::new(SpinSyncMutex::new(None));
+  let mut first = SharedLease::boxed("a:1", &holder);
+  let mut second = SharedLease::boxed("b:1", &holder);
+
+  assert_eq!(first.acquire_majority(&context()), LeaseAcquisitionOutcome::Acquired);
+  assert_eq!(second.acquire_majority(&context()), LeaseAcquisitionOutcome::Denied);
+  assert_eq!(first.acquire_majority(&context()), LeaseAcquisitionOutcome::Acquired);
+
+  assert_eq!(first.release(), Ok(true));
+  assert_eq!(second.acquire_majority(&context()), LeaseAcquisitionOutcome::Acquired);
+}
+
+#[test]
+fn lease_port_reports_an_unreachable_backend_as_unavailable() {
+  let holder: Holder = ArcShared::new(SpinSyncMutex::new(None));
+  let mut lease = SharedLease::unreachable("a:1", &holder);
+
+  assert_eq!(lease.acquire_majority(&context()), LeaseAcquisitionOutcome::Unavailable);
+}
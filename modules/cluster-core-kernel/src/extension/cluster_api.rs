@@ -8,6 +8,7 @@ use alloc::{
   collections::BTreeSet,
   format,
   string::{String, ToString},
+  vec::Vec,
 };
 use core::time::Duration;
 
@@ -19,7 +20,8 @@ use fraktor_actor_core_kernel_rs::{
     scheduler::{ExecutionBatch, SchedulerCommand, SchedulerRunnable},
   },
   event::stream::{
-    EventStreamEvent, EventStreamSubscriber, EventStreamSubscriberShared, EventStreamSubscription, subscriber_handle,
+    ClassifierKey, EventStreamEvent, EventStreamSubscriber, EventStreamSubscriberShared, EventStreamSubscription,
+    subscriber_handle,
   },
   support::futures::ActorFutureShared,
   system::ActorSystem,
@@ -29,16 +31,19 @@ use fraktor_utils_core_rs::sync::{ArcShared, SharedAccess};
 use crate::{
   ClusterApiError, ClusterError, ClusterEvent, ClusterEventType, ClusterExtension, ClusterRequestError,
   ClusterResolveError, ClusterSubscriptionInitialStateMode,
-  activation::{ClusterIdentity, LookupError},
+  activation::{ClusterIdentity, LookupError, PlacementResolution},
   extension::ClusterIdentityResolver,
   grain::GrainMetricsShared,
   membership::CurrentClusterState,
+  sharding::{ShardRegionState, ShardRegionStats},
 };
 
 const CLUSTER_EVENT_STREAM_NAME: &str = "cluster";
 const REMOTE_ACTOR_PATH_SCHEME: &str = "fraktor.tcp";
 const CANONICAL_PATH_UNAVAILABLE_REASON: &str = "canonical path is unavailable";
 
+// 購読者へのアクター送信はメールボックスのメトリクスイベントを発行するため、
+// 拡張イベントだけを購読して通知中の購読者への再入を避ける
 struct ClusterEventFilterSubscriber {
   subscriber:  EventStreamSubscriberShared,
   event_types: BTreeSet<ClusterEventType>,
@@ -105,6 +110,72 @@ impl ClusterApi {
     self.resolve_actor_ref(identity)
   }
 
+  /// Resolves where the grain of `identity` is placed, activating it on its owner.
+  ///
+  /// Unlike [`Self::get`], no actor reference is resolved, so callers can act on the
+  /// placement locality themselves.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the cluster is not started, the kind is not registered, or PID
+  /// lookup fails or is pending.
+  pub fn placement_of(&self, identity: &ClusterIdentity) -> Result<PlacementResolution, ClusterResolveError> {
+    let key = identity.key();
+    let (resolution, placement_events, placement_commands) = {
+      let core = self.extension.core_shared();
+      core.with_lock(|guard| {
+        if guard.mode().is_none() {
+          return Err(ClusterResolveError::ClusterNotStarted);
+        }
+        if !guard.is_kind_registered(identity.kind()) {
+          return Err(ClusterResolveError::KindNotRegistered { kind: identity.kind().to_string() });
+        }
+        let idle_now_nanos = self.system.state().scheduler().current_time_nanos();
+        let now_secs = pid_cache_time_secs(idle_now_nanos);
+        let resolution = guard.resolve_pid_at(&key, now_secs, idle_now_nanos).map_err(|error| match error {
+          | LookupError::Pending => ClusterResolveError::LookupPending,
+          | _ => ClusterResolveError::LookupFailed,
+        });
+        let events = guard.drain_placement_events();
+        let commands = guard.drain_placement_commands();
+        Ok((resolution, events, commands))
+      })?
+    };
+    if !placement_events.is_empty() || !placement_commands.is_empty() {
+      let extension = self.extension.clone();
+      self.system.state().scheduler().run_after_write(move || {
+        extension.publish_activation_events(placement_events);
+        extension.publish_placement_commands(placement_commands);
+      });
+    }
+    resolution
+  }
+
+  /// Returns the authority of the member that owns the grain of `identity`.
+  ///
+  /// Unlike [`Self::placement_of`], the grain is not activated, so callers that run the grain's
+  /// work themselves can check ownership without registering an activation.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the cluster is not started, the kind is not registered, or no owner
+  /// can be selected yet.
+  pub fn owner_of(&self, identity: &ClusterIdentity) -> Result<String, ClusterResolveError> {
+    let key = identity.key();
+    self.extension.core_shared().with_lock(|guard| {
+      if guard.mode().is_none() {
+        return Err(ClusterResolveError::ClusterNotStarted);
+      }
+      if !guard.is_kind_registered(identity.kind()) {
+        return Err(ClusterResolveError::KindNotRegistered { kind: identity.kind().to_string() });
+      }
+      guard.owner_of(&key).map_err(|error| match error {
+        | LookupError::Pending => ClusterResolveError::LookupPending,
+        | _ => ClusterResolveError::LookupFailed,
+      })
+    })
+  }
+
   /// Sends a request and returns the ask response handle.
   ///
   /// # Errors
@@ -214,6 +285,32 @@ impl ClusterApi {
     state
   }
 
+  /// Returns the shards of `kind` with grains active on the local member.
+  #[must_use]
+  pub fn shard_region_state(&self, kind: &str) -> ShardRegionState {
+    self.extension.core_shared().with_lock(|core| core.shard_region_state(kind))
+  }
+
+  /// Returns the number of grains active on the local member per kind.
+  #[must_use]
+  pub fn shard_region_stats(&self) -> ShardRegionStats {
+    self.extension.core_shared().with_lock(|core| core.shard_region_stats())
+  }
+
+  /// Returns the authorities grains are currently placed on.
+  ///
+  /// The list comes from the placement coordinator and is empty when the identity lookup
+  /// does not coordinate placement or the cluster has not started.
+  #[must_use]
+  pub fn current_regions(&self) -> Vec<String> {
+    self
+      .extension
+      .core_shared()
+      .with_lock(|core| core.placement_snapshot())
+      .map(|snapshot| snapshot.authorities)
+      .unwrap_or_default()
+  }
+
   /// Returns the authority advertised by the local cluster member.
   #[must_use]
   pub fn self_authority(&self) -> String {
@@ -245,7 +342,8 @@ impl ClusterApi {
 
     match initial_state_mode {
       | ClusterSubscriptionInitialStateMode::AsEvents => {
-        let (subscription_id, snapshot) = event_stream.with_write(|stream| stream.subscribe(filtered.clone()));
+        let (subscription_id, snapshot) =
+          event_stream.with_write(|stream| stream.subscribe_with_key(ClassifierKey::Extension, filtered.clone()));
         for event in &snapshot {
           filtered.notify(event);
         }
@@ -253,7 +351,8 @@ impl ClusterApi {
       },
       | ClusterSubscriptionInitialStateMode::AsSnapshot => {
         // Subscribe first to avoid event gap between snapshot and registration.
-        let subscription_id = event_stream.with_write(|stream| stream.subscribe_no_replay(filtered));
+        let subscription_id =
+          event_stream.with_write(|stream| stream.subscribe_no_replay_with_key(ClassifierKey::Extension, filtered));
         let initial_event = {
           let core = self.extension.core_shared();
           let (state, observed_at) = core.with_lock(|core| core.current_cluster_state_snapshot());
@@ -285,7 +384,8 @@ impl ClusterApi {
     let filtered =
       subscriber_handle(ClusterEventFilterSubscriber::new(subscriber.clone(), to_event_type_set(event_types)));
     let event_stream = self.system.event_stream();
-    let subscription_id = event_stream.with_write(|stream| stream.subscribe_no_replay(filtered));
+    let subscription_id =
+      event_stream.with_write(|stream| stream.subscribe_no_replay_with_key(ClassifierKey::Extension, filtered));
     EventStreamSubscription::new(event_stream, subscription_id)
   }
 
@@ -295,34 +395,7 @@ impl ClusterApi {
   }
 
   fn resolve_actor_ref(&self, identity: &ClusterIdentity) -> Result<ActorRef, ClusterResolveError> {
-    let key = identity.key();
-    let (pid_result, placement_events) = {
-      let core = self.extension.core_shared();
-      core.with_lock(|guard| {
-        if guard.mode().is_none() {
-          return Err(ClusterResolveError::ClusterNotStarted);
-        }
-        if !guard.is_kind_registered(identity.kind()) {
-          return Err(ClusterResolveError::KindNotRegistered { kind: identity.kind().to_string() });
-        }
-        let idle_now_nanos = self.system.state().scheduler().current_time_nanos();
-        let now_secs = pid_cache_time_secs(idle_now_nanos);
-        let resolution = guard.resolve_pid_at(&key, now_secs, idle_now_nanos).map_err(|error| match error {
-          | LookupError::Pending => ClusterResolveError::LookupPending,
-          | _ => ClusterResolveError::LookupFailed,
-        });
-        let events = guard.drain_placement_events();
-        Ok((resolution.map(|value| value.pid), events))
-      })?
-    };
-    if !placement_events.is_empty() {
-      let extension = self.extension.clone();
-      self.system.state().scheduler().run_after_write(move || {
-        extension.publish_activation_events(placement_events);
-      });
-    }
-    let pid = pid_result?;
-
+    let pid = self.placement_of(identity)?.pid;
     let (authority, path) = split_pid(&pid)?;
     let system_name = self.system.state().system_name();
     let canonical = format!("fraktor.tcp://{system_name}@{authority}/{path}");
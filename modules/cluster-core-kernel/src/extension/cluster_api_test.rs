@@ -32,7 +32,9 @@ use crate::{
   MetricsError, TopologyUpdate,
   activation::{
     ActivatedKind, ClusterIdentity, IdentityLookup, IdentitySetupError, LookupError, NoopIdentityLookup,
-    PartitionIdentityLookup, PlacementDecision, PlacementEvent, PlacementLocality, PlacementResolution,
+    PartitionIdentityLookup, PassivationReason, PassivationStrategy, PlacementCommand, PlacementCommandResult,
+    PlacementCoordinatorError, PlacementDecision, PlacementEvent, PlacementLease, PlacementLocality,
+    PlacementLockError, PlacementRequestId, PlacementResolution,
   },
   cluster_provider::{ClusterProvider, NoopClusterProvider},
   downing_provider::{DowningDecision, DowningInput, DowningProvider, DowningProviderCompatibility},
@@ -243,9 +245,11 @@ fn configured_idle_passivation_runs_from_install_and_start_to_events_and_metrics
   assert_eq!(run_scheduler(&system, Duration::from_secs(1)), 1);
 
   let events = recorder.events();
-  assert!(events.iter().any(|event| matches!(event, GrainEvent::ActivationPassivated { key } if *key == idle.key())));
   assert!(
-    !events.iter().any(|event| matches!(event, GrainEvent::ActivationPassivated { key } if *key == recent.key()))
+    events.iter().any(|event| matches!(event, GrainEvent::ActivationPassivated { key, .. } if *key == idle.key()))
+  );
+  assert!(
+    !events.iter().any(|event| matches!(event, GrainEvent::ActivationPassivated { key, .. } if *key == recent.key()))
   );
   assert_eq!(ext.grain_metrics().expect("metrics").activations_passivated(), 1);
 }
@@ -272,8 +276,150 @@ fn subsecond_access_is_not_passivated_at_the_next_second_boundary() {
     !recorder
       .events()
       .iter()
-      .any(|event| matches!(event, GrainEvent::ActivationPassivated { key } if *key == identity.key()))
+      .any(|event| matches!(event, GrainEvent::ActivationPassivated { key, .. } if *key == identity.key()))
+  );
+}
+
+#[test]
+fn least_recently_used_strategy_passivates_with_reason_and_counts_per_policy() {
+  let (system, ext) = build_system_with_extension_config(|| Box::new(PartitionIdentityLookup::with_defaults()), true);
+  ext.start_member().expect("start member");
+  let kind = ActivatedKind::new("user").with_passivation_strategy(PassivationStrategy::LeastRecentlyUsed { limit: 1 });
+  ext.setup_member_kinds(vec![kind]).expect("setup kinds");
+  ext.on_topology(&build_topology_update(1, Vec::new(), Vec::new()));
+  let (recorder, _subscription) = subscribe_grain_events(&system.event_stream());
+  let api = ClusterApi::try_from_system(&system).expect("cluster api");
+  let first = ClusterIdentity::new("user", "first").expect("first identity");
+  let second = ClusterIdentity::new("user", "second").expect("second identity");
+
+  let _ = api.get(&first).expect("activate first Grain");
+  let _ = api.get(&second).expect("activate second Grain");
+
+  assert!(recorder.events().iter().any(|event| matches!(
+    event,
+    GrainEvent::ActivationPassivated { key, reason: PassivationReason::LeastRecentlyUsed } if *key == first.key()
+  )));
+  let metrics = ext.grain_metrics().expect("metrics");
+  assert_eq!(metrics.activations_passivated(), 1);
+  assert_eq!(metrics.passivations(PassivationReason::LeastRecentlyUsed), 1);
+  assert_eq!(metrics.passivations(PassivationReason::IdleTimeout), 0);
+}
+
+#[test]
+fn placement_of_reports_locality_without_resolving_an_actor_ref() {
+  let (system, ext) = build_system_with_extension(|| Box::new(PartitionIdentityLookup::with_defaults()));
+  ext.start_member().expect("start member");
+  ext.setup_member_kinds(vec![ActivatedKind::new("user")]).expect("setup kinds");
+  ext.on_topology(&build_topology_update(1, Vec::new(), Vec::new()));
+  let api = ClusterApi::try_from_system(&system).expect("cluster api");
+  let identity = ClusterIdentity::new("user", "worker").expect("identity");
+
+  let resolution = api.placement_of(&identity).expect("placement");
+
+  assert_eq!(resolution.decision.key, identity.key());
+  assert_eq!(resolution.decision.authority, "node1:8080");
+  assert_eq!(resolution.locality, PlacementLocality::Local);
+}
+
+#[test]
+fn owner_of_selects_the_owner_without_activating_the_grain() {
+  let (system, ext) = build_system_with_extension(|| Box::new(PartitionIdentityLookup::with_defaults()));
+  ext.start_member().expect("start member");
+  ext.setup_member_kinds(vec![ActivatedKind::new("user")]).expect("setup kinds");
+  ext.on_topology(&build_topology_update(1, Vec::new(), Vec::new()));
+  let api = ClusterApi::try_from_system(&system).expect("cluster api");
+
+  let owner = api.owner_of(&ClusterIdentity::new("user", "worker").expect("identity")).expect("owner");
+
+  assert_eq!(owner, "node1:8080");
+  assert_eq!(api.shard_region_stats().entity_counts.get("user"), None);
+}
+
+#[test]
+fn activating_a_remembered_kind_publishes_the_remembered_grain() {
+  let (system, ext) = build_system_with_extension(|| Box::new(PartitionIdentityLookup::with_defaults()));
+  ext.start_member().expect("start member");
+  ext.setup_member_kinds(vec![ActivatedKind::new("user").with_remember_entities(true)]).expect("setup kinds");
+  ext.on_topology(&build_topology_update(1, Vec::new(), Vec::new()));
+  let (recorder, _subscription) = subscribe_grain_events(&system.event_stream());
+  let api = ClusterApi::try_from_system(&system).expect("cluster api");
+
+  api.placement_of(&ClusterIdentity::new("user", "worker").expect("identity")).expect("placement");
+
+  assert!(recorder.events().iter().any(|event| matches!(
+    event,
+    GrainEvent::ActivationRemembered { key, entry } if key.value() == "user/worker" && entry.owner == "node1:8080"
+  )));
+}
+
+#[test]
+fn distributed_activation_publishes_placement_commands_and_applies_their_results() {
+  let (system, ext) = build_system_with_extension(|| {
+    let mut lookup = PartitionIdentityLookup::with_defaults();
+    lookup.set_distributed_activation(true);
+    Box::new(lookup)
+  });
+  ext.start_member().expect("start member");
+  ext.setup_member_kinds(vec![ActivatedKind::new("user")]).expect("setup kinds");
+  ext.on_topology(&build_topology_update(1, Vec::new(), Vec::new()));
+  let (recorder, _subscription) = subscribe_placement_commands(&system.event_stream());
+  let api = ClusterApi::try_from_system(&system).expect("cluster api");
+  let identity = ClusterIdentity::new("user", "worker").expect("identity");
+
+  assert_eq!(api.placement_of(&identity), Err(ClusterResolveError::LookupPending));
+  let commands = recorder.commands();
+  let [PlacementCommand::TryAcquire { request_id, key, owner, .. }] = commands.as_slice() else {
+    panic!("expected a single TryAcquire command, got {commands:?}");
+  };
+  let lease = PlacementLease { key: key.clone(), owner: owner.clone(), expires_at: u64::MAX };
+  ext
+    .handle_placement_command_result(PlacementCommandResult::LockAcquired {
+      request_id: *request_id,
+      result:     Ok(lease),
+    })
+    .expect("lock acquired");
+
+  let commands = recorder.commands();
+  assert!(
+    matches!(commands.last(), Some(PlacementCommand::LoadActivation { request_id: loaded, .. }) if loaded == request_id)
   );
+  let error = ext
+    .handle_placement_command_result(PlacementCommandResult::LockAcquired {
+      request_id: PlacementRequestId(999),
+      result:     Err(PlacementLockError::Failed { reason: String::from("missing") }),
+    })
+    .expect_err("unknown request");
+  assert!(matches!(error, PlacementCoordinatorError::UnknownRequest { .. }));
+}
+
+#[test]
+fn region_queries_report_local_grains_and_placement_authorities() {
+  let (system, ext) = build_system_with_extension(|| Box::new(PartitionIdentityLookup::with_defaults()));
+  ext.start_member().expect("start member");
+  ext.setup_member_kinds(vec![ActivatedKind::new("user")]).expect("setup kinds");
+  ext.on_topology(&build_topology_update(1, Vec::new(), Vec::new()));
+  let api = ClusterApi::try_from_system(&system).expect("cluster api");
+  for id in ["a", "b"] {
+    let _ = api.placement_of(&ClusterIdentity::new("user", id).expect("identity")).expect("placement");
+  }
+
+  let state = api.shard_region_state("user");
+  let mut entity_ids: Vec<String> = state.shards.into_iter().flat_map(|shard| shard.entity_ids).collect();
+  entity_ids.sort();
+
+  assert_eq!(entity_ids, vec![String::from("a"), String::from("b")]);
+  assert_eq!(api.shard_region_stats().entity_counts.get("user"), Some(&2));
+  assert_eq!(api.current_regions(), vec![String::from("node1:8080")]);
+}
+
+#[test]
+fn region_queries_are_empty_without_placement_coordinator() {
+  let (system, _ext) = build_system_with_extension(|| Box::new(StaticIdentityLookup::new("node1:8080")));
+  let api = ClusterApi::try_from_system(&system).expect("cluster api");
+
+  assert!(api.shard_region_state("user").shards.is_empty());
+  assert_eq!(api.shard_region_stats().total(), 0);
+  assert!(api.current_regions().is_empty());
 }
 
 #[test]
@@ -944,6 +1090,41 @@ fn subscribe_grain_events(event_stream: &EventStreamShared) -> (RecordingGrainEv
   (recorder, subscription)
 }
 
+#[derive(Clone)]
+struct RecordingPlacementCommands {
+  commands: ArcShared<SpinSyncMutex<Vec<PlacementCommand>>>,
+}
+
+impl RecordingPlacementCommands {
+  fn new() -> Self {
+    Self { commands: ArcShared::new(SpinSyncMutex::new(Vec::new())) }
+  }
+
+  fn commands(&self) -> Vec<PlacementCommand> {
+    self.commands.lock().clone()
+  }
+}
+
+impl EventStreamSubscriber for RecordingPlacementCommands {
+  fn on_event(&mut self, event: &EventStreamEvent) {
+    if let EventStreamEvent::Extension { name, payload } = event
+      && name == "cluster"
+      && let Some(command) = payload.payload().downcast_ref::<PlacementCommand>()
+    {
+      self.commands.lock().push(command.clone());
+    }
+  }
+}
+
+fn subscribe_placement_commands(
+  event_stream: &EventStreamShared,
+) -> (RecordingPlacementCommands, EventStreamSubscription) {
+  let recorder = RecordingPlacementCommands::new();
+  let subscriber = test_subscriber_handle(recorder.clone());
+  let subscription = event_stream.subscribe(&subscriber);
+  (recorder, subscription)
+}
+
 #[derive(Clone)]
 struct SchedulingGrainSubscriber {
   scheduler: SchedulerShared,
@@ -1116,7 +1297,11 @@ impl IdentityLookup for EventfulIdentityLookup {
       pid:         pid.clone(),
       observed_at: now,
     });
-    self.events.push(PlacementEvent::Passivated { key: key.clone(), observed_at: now });
+    self.events.push(PlacementEvent::Passivated {
+      key:         key.clone(),
+      reason:      PassivationReason::IdleTimeout,
+      observed_at: now,
+    });
     Ok(PlacementResolution {
       decision: PlacementDecision { key: key.clone(), authority: self.authority.clone(), observed_at: now },
       locality: PlacementLocality::Remote,
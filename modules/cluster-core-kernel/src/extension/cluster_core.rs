@@ -24,16 +24,18 @@ use fraktor_utils_core_rs::{
 
 use crate::{
   BlockListProvider, ClusterError, ClusterEvent, ClusterExtensionConfig, ClusterExtensionConfigError, ClusterMetrics,
-  ClusterMetricsSnapshot, ClusterProviderError, ClusterProviderShared, MetricsError, StartupMode, TopologyApplyError,
-  TopologyUpdate,
+  ClusterMetricsSnapshot, ClusterProviderError, ClusterProviderShared, ClusterShardingStateStoreMode, MetricsError,
+  StartupMode, TopologyApplyError, TopologyUpdate,
   activation::{
-    ActivatedKind, IdentityLookupShared, IdentitySetupError, LookupError, PidCache, PlacementEvent, PlacementResolution,
+    ActivatedKind, IdentityLookupShared, IdentitySetupError, LookupError, PidCache, PlacementCommand,
+    PlacementCommandResult, PlacementCoordinatorError, PlacementEvent, PlacementResolution, PlacementSnapshot,
   },
   downing_provider::{DowningDecision, DowningInput, DowningProvider},
   failure_detector::FailureDetectorConfig,
   grain::{GrainKey, GrainReadinessSnapshot, KindRegistry},
   membership::{CurrentClusterState, GossiperShared, MembershipVersion, NodeRecord, NodeStatus},
   pub_sub::ClusterPubSubShared,
+  sharding::{ShardAllocationTable, ShardRegionRequests, ShardRegionState, ShardRegionStats},
 };
 
 /// Aggregates configuration and shared dependencies for cluster runtime flows.
@@ -46,6 +48,7 @@ pub struct ClusterCore {
   pub_sub: ClusterPubSubShared,
   failure_detector_config: FailureDetectorConfig,
   grain_idle_passivation_threshold: Duration,
+  sharding_state_store_mode: ClusterShardingStateStoreMode,
   startup_state: ClusterStartupState,
   metrics_enabled: bool,
   kind_registry: KindRegistry,
@@ -97,6 +100,7 @@ impl ClusterCore {
       pub_sub: pubsub,
       failure_detector_config: *config.failure_detector_config(),
       grain_idle_passivation_threshold: config.grain_idle_passivation_threshold(),
+      sharding_state_store_mode: config.sharding_state_store_mode(),
       startup_state,
       metrics_enabled,
       kind_registry,
@@ -160,7 +164,14 @@ impl ClusterCore {
     self.kind_registry.register_all(kinds);
     self.virtual_actor_count = self.kind_registry.virtual_actor_count();
     let snapshot = self.kind_registry.all();
-    self.identity_lookup.with_write(|lookup| lookup.setup_member(&snapshot))?;
+    let address = self.startup_address();
+    self.identity_lookup.with_write(|lookup| {
+      // メンバーは自身の authority を知らないと、シャード調整役かどうかを判定できない
+      if !address.is_empty() {
+        lookup.set_local_authority(&address);
+      }
+      lookup.setup_member(&snapshot)
+    })?;
     Ok(())
   }
 
@@ -200,18 +211,113 @@ impl ClusterCore {
     self.identity_lookup.with_write(|lookup| lookup.resolve_at(key, now_secs, idle_now_nanos))
   }
 
+  /// Returns the authority owning the grain key without activating it.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when no owner can be selected yet.
+  pub(crate) fn owner_of(&mut self, key: &GrainKey) -> Result<String, LookupError> {
+    self.identity_lookup.with_write(|lookup| lookup.owner_of(key))
+  }
+
   /// Passivates activations that exceeded the configured idle threshold.
   pub(crate) fn passivate_idle_at(&mut self, now_nanos: u64) {
     let idle_ttl_nanos = u64::try_from(self.grain_idle_passivation_threshold.as_nanos()).unwrap_or(u64::MAX);
     self.identity_lookup.with_write(|lookup| lookup.passivate_idle_at(now_nanos, idle_ttl_nanos));
   }
 
+  /// Restores remembered grain keys and reactivates those owned by the local member.
+  pub(crate) fn restore_remembered_grains(&mut self, keys: Vec<GrainKey>, now_nanos: u64) {
+    self.identity_lookup.with_write(|lookup| {
+      lookup.restore_remembered(keys);
+      lookup.reactivate_remembered_at(now_nanos / 1_000_000_000, now_nanos);
+    });
+  }
+
+  /// Runs one shard rebalance round of the identity lookup.
+  pub(crate) fn rebalance_shards_at(&mut self, now_nanos: u64) {
+    self.identity_lookup.with_write(|lookup| lookup.rebalance_at(now_nanos / 1_000_000_000, now_nanos));
+  }
+
+  /// Returns the kinds placed by shard.
+  pub(crate) fn sharded_kinds(&self) -> Vec<String> {
+    self.identity_lookup.with_read(|lookup| lookup.sharded_kinds())
+  }
+
+  /// Returns the shard allocations of `kind` when the local member coordinates shards.
+  pub(crate) fn shard_allocations(&self, kind: &str) -> Option<ShardAllocationTable> {
+    self.identity_lookup.with_read(|lookup| lookup.shard_allocations(kind))
+  }
+
+  /// Returns the requests of the local member to the coordinating member for `kind`.
+  pub(crate) fn shard_region_requests(&self, kind: &str) -> Option<ShardRegionRequests> {
+    self.identity_lookup.with_read(|lookup| lookup.shard_region_requests(kind))
+  }
+
+  /// Applies shard allocations of `kind` replicated from the coordinating member.
+  pub(crate) fn apply_shard_allocations_at(&mut self, kind: &str, table: ShardAllocationTable, now_nanos: u64) {
+    self
+      .identity_lookup
+      .with_write(|lookup| lookup.apply_shard_allocations(kind, table, now_nanos / 1_000_000_000, now_nanos));
+  }
+
+  /// Handles the shard requests of the member at `authority` for `kind`.
+  pub(crate) fn handle_shard_region_requests_at(
+    &mut self,
+    kind: &str,
+    authority: &str,
+    requests: &ShardRegionRequests,
+    now_nanos: u64,
+  ) {
+    self.identity_lookup.with_write(|lookup| {
+      lookup.handle_shard_region_requests(kind, authority, requests, now_nanos / 1_000_000_000, now_nanos);
+    });
+  }
+
   /// Drains placement events emitted by identity lookup.
   #[must_use]
   pub(crate) fn drain_placement_events(&mut self) -> Vec<PlacementEvent> {
     self.identity_lookup.with_write(|lookup| lookup.drain_events())
   }
 
+  /// Drains placement commands queued by identity lookup for execution.
+  #[must_use]
+  pub(crate) fn drain_placement_commands(&mut self) -> Vec<PlacementCommand> {
+    self.identity_lookup.with_write(|lookup| lookup.drain_commands())
+  }
+
+  /// Applies the result of an executed placement command to identity lookup.
+  pub(crate) fn apply_placement_command_result(
+    &mut self,
+    result: PlacementCommandResult,
+  ) -> Result<(), PlacementCoordinatorError> {
+    self.identity_lookup.with_write(|lookup| lookup.apply_command_result(result))
+  }
+
+  /// Returns the state-store mode remembered grains are kept in.
+  #[must_use]
+  pub const fn sharding_state_store_mode(&self) -> ClusterShardingStateStoreMode {
+    self.sharding_state_store_mode
+  }
+
+  /// Returns a snapshot of the placement coordinator, if the identity lookup has one.
+  #[must_use]
+  pub fn placement_snapshot(&self) -> Option<PlacementSnapshot> {
+    self.identity_lookup.with_read(|lookup| lookup.placement_snapshot())
+  }
+
+  /// Returns the shards of `kind` with grains active on the local member.
+  #[must_use]
+  pub fn shard_region_state(&self, kind: &str) -> ShardRegionState {
+    self.identity_lookup.with_read(|lookup| lookup.shard_region_state(kind))
+  }
+
+  /// Returns the number of grains active on the local member per kind.
+  #[must_use]
+  pub fn shard_region_stats(&self) -> ShardRegionStats {
+    self.identity_lookup.with_read(|lookup| lookup.shard_region_stats())
+  }
+
   /// Returns the aggregated virtual actor count.
   #[must_use]
   pub const fn virtual_actor_count(&self) -> i64 {
@@ -631,11 +737,17 @@ impl ClusterCore {
     let members = update.members.clone();
     let left = update.left.clone();
     let dead = update.dead.clone();
+    let observed_at = update.observed_at;
+    let idle_now_nanos =
+      u64::try_from(observed_at.resolution().as_nanos().saturating_mul(u128::from(observed_at.ticks())))
+        .unwrap_or(u64::MAX);
     self.identity_lookup.with_write(|identity_lookup| {
       identity_lookup.update_topology(members);
       for authority in left.iter().chain(dead.iter()) {
         identity_lookup.on_member_left(authority);
       }
+      identity_lookup.reactivate_remembered_at(idle_now_nanos / 1_000_000_000, idle_now_nanos);
+      identity_lookup.rebalance_at(idle_now_nanos / 1_000_000_000, idle_now_nanos);
     });
 
     self.pub_sub.with_write(|pub_sub| pub_sub.on_topology(update));
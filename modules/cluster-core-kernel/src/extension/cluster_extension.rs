@@ -27,13 +27,19 @@ use fraktor_utils_core_rs::sync::{ArcShared, DefaultMutex, SharedAccess, SharedL
 
 use super::grain_idle_passivation_actor::GrainIdlePassivationActor;
 use crate::{
-  ClusterCore, ClusterError, ClusterEvent, ClusterMetricsSnapshot, MetricsError, StartupMode, TopologyUpdate,
-  activation::{ActivatedKind, IdentitySetupError, PlacementEvent},
+  ClusterCore, ClusterError, ClusterEvent, ClusterMetricsSnapshot, ClusterShardingStateStoreMode, MetricsError,
+  StartupMode, TopologyApplyError, TopologyUpdate,
+  activation::{
+    ActivatedKind, IdentitySetupError, PlacementCommand, PlacementCommandResult, PlacementCoordinatorError,
+    PlacementEvent,
+  },
   grain::{
-    GRAIN_EVENT_STREAM_NAME, GrainEvent, GrainMetrics, GrainMetricsShared, GrainMetricsSnapshot, GrainReadinessSnapshot,
+    GRAIN_EVENT_STREAM_NAME, GrainEvent, GrainKey, GrainMetrics, GrainMetricsShared, GrainMetricsSnapshot,
+    GrainReadinessSnapshot,
   },
   membership::NodeStatus,
   pub_sub::ClusterPubSubShared,
+  sharding::{ShardAllocationTable, ShardRegionRequests},
 };
 
 const CLUSTER_EVENT_STREAM_NAME: &str = "cluster";
@@ -46,6 +52,7 @@ fn report_idle_passivation_delivery_failure(error: &SendError) {
 struct ClusterTopologySubscriber {
   core: SharedLock<ClusterCore>,
   event_stream: EventStreamShared,
+  grain_metrics: Option<GrainMetricsShared>,
   self_address: String,
   self_status: SharedLock<Option<SelfMemberStatus>>,
   self_identity: SharedLock<Option<SelfMemberIdentity>>,
@@ -54,16 +61,27 @@ struct ClusterTopologySubscriber {
 }
 
 impl ClusterTopologySubscriber {
+  #[allow(clippy::too_many_arguments)]
   const fn new(
     core: SharedLock<ClusterCore>,
     event_stream: EventStreamShared,
+    grain_metrics: Option<GrainMetricsShared>,
     self_address: String,
     self_status: SharedLock<Option<SelfMemberStatus>>,
     self_identity: SharedLock<Option<SelfMemberIdentity>>,
     starting_identity: SharedLock<Option<SelfMemberIdentity>>,
     topology_absent_identities: SharedLock<Vec<SelfMemberIdentity>>,
   ) -> Self {
-    Self { core, event_stream, self_address, self_status, self_identity, starting_identity, topology_absent_identities }
+    Self {
+      core,
+      event_stream,
+      grain_metrics,
+      self_address,
+      self_status,
+      self_identity,
+      starting_identity,
+      topology_absent_identities,
+    }
   }
 }
 
@@ -75,7 +93,9 @@ impl EventStreamSubscriber for ClusterTopologySubscriber {
       && name == CLUSTER_EVENT_STREAM_NAME
       && let Some(ClusterEvent::TopologyUpdated { update }) = payload.payload().downcast_ref::<ClusterEvent>()
     {
-      let result = self.core.with_lock(|core| core.try_apply_topology(update));
+      let (result, events, commands) = self.core.with_lock(|core| apply_topology_and_drain(core, update));
+      publish_activation_events(&self.event_stream, &self.grain_metrics, events);
+      publish_placement_commands(&self.event_stream, commands);
       if matches!(result.as_ref(), Ok(Some(_))) {
         clear_self_observation_if_absent(
           update,
@@ -603,6 +623,10 @@ impl ClusterExtension {
     publish_activation_events(&self.event_stream, &self.grain_metrics, events);
   }
 
+  pub(crate) fn publish_placement_commands(&self, commands: Vec<PlacementCommand>) {
+    publish_placement_commands(&self.event_stream, commands);
+  }
+
   fn prepare_idle_passivation_task(&self) -> Result<bool, ClusterError> {
     self.core.with_lock(|core| core.validate_configuration()).map_err(ClusterError::from)?;
     let interval = self.core.with_lock(|core| core.grain_idle_passivation_threshold());
@@ -734,6 +758,7 @@ impl ClusterExtension {
     let subscriber: ClusterTopologySubscriber = ClusterTopologySubscriber::new(
       self.core.clone(),
       self.event_stream.clone(),
+      self.grain_metrics.clone(),
       self_address,
       self.self_member_status.clone(),
       self.self_member_identity.clone(),
@@ -915,6 +940,125 @@ impl ClusterExtension {
     self.core.with_lock(|core| core.setup_member_kinds(kinds))
   }
 
+  /// Restores remembered grains loaded from activation storage.
+  ///
+  /// Keys owned by the local member are reactivated immediately; the others are reactivated
+  /// by their owners or after the next topology change moves them here.
+  pub fn restore_remembered_grains(&self, keys: Vec<GrainKey>) {
+    let Some(system) = self._system.upgrade() else {
+      return;
+    };
+    let now_nanos = system.state().scheduler().current_time_nanos();
+    let (events, commands) = self.core.with_lock(|core| {
+      core.restore_remembered_grains(keys, now_nanos);
+      (core.drain_placement_events(), core.drain_placement_commands())
+    });
+    self.publish_activation_events(events);
+    self.publish_placement_commands(commands);
+  }
+
+  /// Applies the result of executing a published [`PlacementCommand`].
+  ///
+  /// With distributed activation enabled, placement publishes the commands it needs executed to
+  /// the event stream; the executor reports each outcome here, and follow-up commands are
+  /// published the same way.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when the identity lookup rejects the result, for example because the
+  /// request is unknown.
+  pub fn handle_placement_command_result(
+    &self,
+    result: PlacementCommandResult,
+  ) -> Result<(), PlacementCoordinatorError> {
+    let (events, commands) = self.core.with_lock(|core| {
+      core.apply_placement_command_result(result)?;
+      Ok::<_, PlacementCoordinatorError>((core.drain_placement_events(), core.drain_placement_commands()))
+    })?;
+    self.publish_activation_events(events);
+    self.publish_placement_commands(commands);
+    Ok(())
+  }
+
+  /// Returns the state-store mode remembered grains are kept in.
+  #[must_use]
+  pub fn sharding_state_store_mode(&self) -> ClusterShardingStateStoreMode {
+    self.core.with_lock(|core| core.sharding_state_store_mode())
+  }
+
+  /// Runs one shard rebalance round for the kinds placed by shard.
+  ///
+  /// Rounds also run after every topology change and on each grain maintenance sweep.
+  pub fn rebalance_shards(&self) {
+    let Some(system) = self._system.upgrade() else {
+      return;
+    };
+    let now_nanos = system.state().scheduler().current_time_nanos();
+    let (events, commands) = self.core.with_lock(|core| {
+      core.rebalance_shards_at(now_nanos);
+      (core.drain_placement_events(), core.drain_placement_commands())
+    });
+    self.publish_activation_events(events);
+    self.publish_placement_commands(commands);
+  }
+
+  /// Returns the kinds placed by shard.
+  #[must_use]
+  pub fn sharded_kinds(&self) -> Vec<String> {
+    self.core.with_lock(|core| core.sharded_kinds())
+  }
+
+  /// Returns the shard allocations of `kind` to replicate to the other members.
+  ///
+  /// Only the member coordinating shards, the member with the lowest authority, returns a
+  /// table; the other members apply it with [`Self::apply_shard_allocations`].
+  #[must_use]
+  pub fn shard_allocations(&self, kind: &str) -> Option<ShardAllocationTable> {
+    self.core.with_lock(|core| core.shard_allocations(kind))
+  }
+
+  /// Returns the shard requests of the local member to replicate to the coordinating member.
+  ///
+  /// The coordinating member handles them with [`Self::handle_shard_region_requests`].
+  #[must_use]
+  pub fn shard_region_requests(&self, kind: &str) -> Option<ShardRegionRequests> {
+    self.core.with_lock(|core| core.shard_region_requests(kind))
+  }
+
+  /// Applies shard allocations of `kind` replicated from the coordinating member.
+  ///
+  /// Grains of shards handed off from the local member are stopped, and grains whose shard
+  /// moved to the local member are reactivated.
+  pub fn apply_shard_allocations(&self, kind: &str, table: ShardAllocationTable) {
+    let Some(system) = self._system.upgrade() else {
+      return;
+    };
+    let now_nanos = system.state().scheduler().current_time_nanos();
+    let (events, commands) = self.core.with_lock(|core| {
+      core.apply_shard_allocations_at(kind, table, now_nanos);
+      (core.drain_placement_events(), core.drain_placement_commands())
+    });
+    self.publish_activation_events(events);
+    self.publish_placement_commands(commands);
+  }
+
+  /// Handles shard requests of the member at `authority` replicated to the coordinating member.
+  ///
+  /// Acknowledged hand-offs complete and requested shards are allocated; members that do not
+  /// coordinate shards ignore the requests.
+  pub fn handle_shard_region_requests(&self, kind: &str, authority: &str, requests: &ShardRegionRequests) {
+    let Some(system) = self._system.upgrade() else {
+      return;
+    };
+    let now_nanos = system.state().scheduler().current_time_nanos();
+    let (events, commands) = self.core.with_lock(|core| {
+      core.handle_shard_region_requests_at(kind, authority, requests, now_nanos);
+      (core.drain_placement_events(), core.drain_placement_commands())
+    });
+    self.publish_activation_events(events);
+    self.publish_placement_commands(commands);
+  }
+
   /// Registers kinds for client mode.
   ///
   /// # Errors
@@ -931,7 +1075,9 @@ impl ClusterExtension {
   pub fn on_topology(&self, update: &TopologyUpdate) {
     // ロックを保持したまま publish するとデッドロックするため、
     // イベントを取得してからロックを解放し、その後に publish する
-    let result = self.core.with_lock(|core| core.try_apply_topology(update));
+    let (result, events, commands) = self.core.with_lock(|core| apply_topology_and_drain(core, update));
+    self.publish_activation_events(events);
+    self.publish_placement_commands(commands);
     if matches!(result.as_ref(), Ok(Some(_))) {
       let self_address = self.core.with_lock(|core| core.startup_address());
       clear_self_observation_if_absent(
@@ -1115,17 +1261,48 @@ pub(super) fn publish_activation_events(
           metrics.with_write(|inner| inner.record_activation_created());
         }
       },
-      | PlacementEvent::Passivated { key, .. } => {
-        publish_grain_event(event_stream, GrainEvent::ActivationPassivated { key });
+      | PlacementEvent::Passivated { key, reason, .. } => {
+        publish_grain_event(event_stream, GrainEvent::ActivationPassivated { key, reason });
         if let Some(metrics) = metrics {
-          metrics.with_write(|inner| inner.record_activation_passivated());
+          metrics.with_write(|inner| inner.record_passivation(reason));
         }
       },
-      | _ => {},
+      | PlacementEvent::Remembered { key, entry } => {
+        publish_grain_event(event_stream, GrainEvent::ActivationRemembered { key, entry });
+      },
+      | PlacementEvent::Forgotten { key, .. } => {
+        publish_grain_event(event_stream, GrainEvent::ActivationForgotten { key });
+      },
+      | PlacementEvent::ReactivationFailed { key, error, .. } => {
+        publish_grain_event(event_stream, GrainEvent::ReactivationFailed { key, error });
+      },
+      // 解決・ロック・ハンドオフの経過はアクティベーションの増減を伴わない
+      | PlacementEvent::Resolved { .. }
+      | PlacementEvent::LockDenied { .. }
+      | PlacementEvent::ShardHandOffStarted { .. }
+      | PlacementEvent::ShardHandOffCompleted { .. } => {},
     }
   }
 }
 
+/// Publishes placement commands for the executor of distributed activation.
+pub(super) fn publish_placement_commands(event_stream: &EventStreamShared, commands: Vec<PlacementCommand>) {
+  for command in commands {
+    let payload = AnyMessage::new(command);
+    let extension_event = EventStreamEvent::Extension { name: String::from(CLUSTER_EVENT_STREAM_NAME), payload };
+    event_stream.publish(&extension_event);
+  }
+}
+
+/// Applies a topology update and drains the placement output it produced.
+fn apply_topology_and_drain(
+  core: &mut ClusterCore,
+  update: &TopologyUpdate,
+) -> (Result<Option<ClusterEvent>, TopologyApplyError>, Vec<PlacementEvent>, Vec<PlacementCommand>) {
+  let result = core.try_apply_topology(update);
+  (result, core.drain_placement_events(), core.drain_placement_commands())
+}
+
 fn publish_grain_event(event_stream: &EventStreamShared, event: GrainEvent) {
   let payload = AnyMessage::new(event);
   let extension_event = EventStreamEvent::Extension { name: String::from(GRAIN_EVENT_STREAM_NAME), payload };
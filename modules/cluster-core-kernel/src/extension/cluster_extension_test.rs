@@ -22,11 +22,12 @@ use crate::{
   BlockListProvider, ClusterError, ClusterEvent, ClusterExtension, ClusterExtensionConfig, ClusterExtensionId,
   ClusterProviderError, ClusterTopology, StartupMode, TopologyUpdate,
   activation::{
-    ActivatedKind, IdentityLookup, IdentitySetupError, LookupError, PartitionIdentityLookup, PlacementResolution,
+    ActivatedKind, IdentityLookup, IdentitySetupError, LookupError, PartitionIdentityLookup, PlacementEvent,
+    PlacementResolution,
   },
   cluster_provider::{ClusterProvider, StaticClusterProvider},
   downing_provider::NoopDowningProvider,
-  grain::{GrainKey, GrainReadiness, GrainUnreadyReason},
+  grain::{GRAIN_EVENT_STREAM_NAME, GrainEvent, GrainKey, GrainReadiness, GrainUnreadyReason},
   membership::{Gossiper, NodeStatus},
   pub_sub::{PubSubError, PubSubSubscriber, PubSubTopic, PublishAck, PublishRequest, cluster_pub_sub::ClusterPubSub},
 };
@@ -2720,3 +2721,42 @@ fn phase2_integration_shutdown_resets_metrics_and_emits_event() {
   // 9. blocked_members がクリアされていることを確認
   assert!(ext_shared.blocked_members().is_empty(), "blocked_members should be cleared after shutdown");
 }
+
+#[derive(Clone)]
+struct RecordingGrainEvents {
+  events: ArcShared<SpinSyncMutex<Vec<GrainEvent>>>,
+}
+
+impl EventStreamSubscriber for RecordingGrainEvents {
+  fn on_event(&mut self, event: &EventStreamEvent) {
+    if let EventStreamEvent::Extension { name, payload } = event
+      && name == GRAIN_EVENT_STREAM_NAME
+      && let Some(grain_event) = payload.payload().downcast_ref::<GrainEvent>()
+    {
+      self.events.lock().push(grain_event.clone());
+    }
+  }
+}
+
+#[test]
+fn forgotten_and_failed_reactivations_are_published_as_grain_events() {
+  let system = create_noop_actor_system();
+  let event_stream = system.event_stream();
+  let recorder = RecordingGrainEvents { events: ArcShared::new(SpinSyncMutex::new(Vec::new())) };
+  let _subscription = event_stream.subscribe(&test_subscriber_handle(recorder.clone()));
+  let key = GrainKey::new(String::from("user/a"));
+
+  super::publish_activation_events(&event_stream, &None, vec![
+    PlacementEvent::Forgotten { key: key.clone(), observed_at: 1 },
+    PlacementEvent::ReactivationFailed {
+      key:         key.clone(),
+      error:       LookupError::NoAuthority,
+      observed_at: 2,
+    },
+  ]);
+
+  assert_eq!(recorder.events.lock().clone(), vec![
+    GrainEvent::ActivationForgotten { key: key.clone() },
+    GrainEvent::ReactivationFailed { key, error: LookupError::NoAuthority },
+  ]);
+}
@@ -8,7 +8,7 @@ use fraktor_actor_core_kernel_rs::{
 };
 use fraktor_utils_core_rs::sync::{SharedAccess, SharedLock};
 
-use super::cluster_extension::publish_activation_events;
+use super::cluster_extension::{publish_activation_events, publish_placement_commands};
 use crate::{ClusterCore, grain::GrainMetricsShared};
 
 pub(super) struct GrainIdlePassivationActor {
@@ -32,14 +32,16 @@ impl Actor for GrainIdlePassivationActor {
     let Some(now) = message.downcast_ref::<u64>().copied() else {
       return Ok(());
     };
-    let events = self.core.with_write(|core| {
+    let (events, commands) = self.core.with_write(|core| {
       if core.mode().is_none() {
-        return Vec::new();
+        return (Vec::new(), Vec::new());
       }
       core.passivate_idle_at(now);
-      core.drain_placement_events()
+      core.rebalance_shards_at(now);
+      (core.drain_placement_events(), core.drain_placement_commands())
     });
     publish_activation_events(&self.event_stream, &self.grain_metrics, events);
+    publish_placement_commands(&self.event_stream, commands);
     Ok(())
   }
 }
@@ -3,7 +3,7 @@
 use alloc::string::String;
 
 use super::GrainKey;
-use crate::activation::ClusterIdentity;
+use crate::activation::{ActivationEntry, ClusterIdentity, LookupError, PassivationReason};
 
 /// EventStream extension name used for grain events.
 pub const GRAIN_EVENT_STREAM_NAME: &str = "cluster-grain";
@@ -39,7 +39,28 @@ pub enum GrainEvent {
   },
   /// Activation was passivated.
   ActivationPassivated {
+    /// Target grain key.
+    key:    GrainKey,
+    /// Why the activation was passivated.
+    reason: PassivationReason,
+  },
+  /// Activation of a remembered kind should be kept in activation storage.
+  ActivationRemembered {
+    /// Target grain key.
+    key:   GrainKey,
+    /// Activation entry to store.
+    entry: ActivationEntry,
+  },
+  /// Remembered activation ended and should be removed from activation storage.
+  ActivationForgotten {
     /// Target grain key.
     key: GrainKey,
   },
+  /// Remembered grain could not be reactivated.
+  ReactivationFailed {
+    /// Target grain key.
+    key:   GrainKey,
+    /// Why placement failed.
+    error: LookupError,
+  },
 }
@@ -25,4 +25,14 @@ impl GrainKey {
   pub fn value(&self) -> &str {
     &self.value
   }
+
+  /// Returns the kind part of a `kind/identity` key, or the whole key when it has no kind.
+  pub(crate) fn kind(&self) -> &str {
+    self.value.split_once('/').map_or(self.value.as_str(), |(kind, _)| kind)
+  }
+
+  /// Returns the identity part of a `kind/identity` key, or the whole key when it has no kind.
+  pub(crate) fn identity(&self) -> &str {
+    self.value.split_once('/').map_or(self.value.as_str(), |(_, identity)| identity)
+  }
 }
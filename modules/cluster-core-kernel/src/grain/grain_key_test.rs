@@ -5,3 +5,15 @@ fn value_is_preserved() {
   let key = GrainKey::new("user:1".to_string());
   assert_eq!(key.value(), "user:1");
 }
+
+#[test]
+fn kind_is_the_part_before_the_first_slash() {
+  assert_eq!(GrainKey::new("user/a/b".to_string()).kind(), "user");
+  assert_eq!(GrainKey::new("user".to_string()).kind(), "user");
+}
+
+#[test]
+fn identity_is_the_part_after_the_first_slash() {
+  assert_eq!(GrainKey::new("user/a/b".to_string()).identity(), "a/b");
+  assert_eq!(GrainKey::new("user".to_string()).identity(), "user");
+}
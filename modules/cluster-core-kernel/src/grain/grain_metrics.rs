@@ -1,6 +1,7 @@
 //! Grain metrics state for call/activation observability.
 
 use super::GrainMetricsSnapshot;
+use crate::activation::PassivationReason;
 
 /// Metrics state when enabled.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -10,6 +11,7 @@ pub struct GrainMetrics {
   call_retries:           u64,
   activations_created:    u64,
   activations_passivated: u64,
+  passivations_by_reason: [u64; PassivationReason::COUNT],
 }
 
 impl GrainMetrics {
@@ -22,6 +24,7 @@ impl GrainMetrics {
       call_retries:           0,
       activations_created:    0,
       activations_passivated: 0,
+      passivations_by_reason: [0; PassivationReason::COUNT],
     }
   }
 
@@ -50,6 +53,13 @@ impl GrainMetrics {
     self.activations_passivated = self.activations_passivated.saturating_add(1);
   }
 
+  /// Records an activation passivation and counts it under `reason`.
+  pub const fn record_passivation(&mut self, reason: PassivationReason) {
+    self.record_activation_passivated();
+    let count = &mut self.passivations_by_reason[reason.index()];
+    *count = count.saturating_add(1);
+  }
+
   /// Returns a snapshot of the current metrics.
   #[must_use]
   pub const fn snapshot(&self) -> GrainMetricsSnapshot {
@@ -59,6 +69,7 @@ impl GrainMetrics {
       self.call_retries,
       self.activations_created,
       self.activations_passivated,
+      self.passivations_by_reason,
     )
   }
 }
@@ -1,5 +1,7 @@
 //! Immutable snapshot of collected grain metrics.
 
+use crate::activation::PassivationReason;
+
 /// Read-only grain metrics snapshot.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct GrainMetricsSnapshot {
@@ -8,6 +10,7 @@ pub struct GrainMetricsSnapshot {
   call_retries:           u64,
   activations_created:    u64,
   activations_passivated: u64,
+  passivations_by_reason: [u64; PassivationReason::COUNT],
 }
 
 impl GrainMetricsSnapshot {
@@ -18,8 +21,16 @@ impl GrainMetricsSnapshot {
     call_retries: u64,
     activations_created: u64,
     activations_passivated: u64,
+    passivations_by_reason: [u64; PassivationReason::COUNT],
   ) -> Self {
-    Self { call_failures, call_timeouts, call_retries, activations_created, activations_passivated }
+    Self {
+      call_failures,
+      call_timeouts,
+      call_retries,
+      activations_created,
+      activations_passivated,
+      passivations_by_reason,
+    }
   }
 
   /// Failed call count.
@@ -51,4 +62,10 @@ impl GrainMetricsSnapshot {
   pub const fn activations_passivated(&self) -> u64 {
     self.activations_passivated
   }
+
+  /// Activation passivated count for one passivation reason.
+  #[must_use]
+  pub const fn passivations(&self, reason: PassivationReason) -> u64 {
+    self.passivations_by_reason[reason.index()]
+  }
 }
@@ -0,0 +1,17 @@
+//! Lease abstraction granting one owner exclusive use of a named resource.
+//!
+//! Backends implement [`Lease`]; consumers such as the cluster singleton and the split brain
+//! resolver look them up through a [`LeaseProvider`] by implementation name.
+
+#[allow(clippy::module_inception)]
+mod lease;
+mod lease_error;
+mod lease_lost_callback;
+mod lease_provider;
+mod lease_settings;
+
+pub use lease::Lease;
+pub use lease_error::LeaseError;
+pub use lease_lost_callback::LeaseLostCallback;
+pub use lease_provider::LeaseProvider;
+pub use lease_settings::LeaseSettings;
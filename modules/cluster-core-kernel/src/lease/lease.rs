@@ -0,0 +1,40 @@
+//! Lease trait implemented by lease backends.
+
+use super::{LeaseError, LeaseLostCallback, LeaseSettings};
+
+/// Exclusive, time-bounded ownership of a named resource.
+///
+/// The holder keeps the lease by calling [`renew`](Self::renew) more often than the heartbeat
+/// timeout of its [`LeaseSettings`]; other owners may take over a lease whose heartbeat timed out.
+pub trait Lease: Send + Sync {
+  /// Returns the settings this lease was created with.
+  fn settings(&self) -> &LeaseSettings;
+
+  /// Tries to acquire the lease, returning `true` when this owner holds it afterwards.
+  ///
+  /// Acquiring a lease that is already held by this owner succeeds. `on_lost` is invoked once when
+  /// a later [`renew`](Self::renew) finds that the lease is no longer held.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LeaseError::Unavailable`] when the backend cannot be reached.
+  fn acquire(&mut self, on_lost: Option<LeaseLostCallback>) -> Result<bool, LeaseError>;
+
+  /// Renews the heartbeat of a held lease.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LeaseError::Lost`] after invoking the lost callback when the lease is no longer
+  /// held, or [`LeaseError::Unavailable`] when the backend cannot be reached.
+  fn renew(&mut self) -> Result<(), LeaseError>;
+
+  /// Releases the lease, returning `true` when this owner held it.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LeaseError::Unavailable`] when the backend cannot be reached.
+  fn release(&mut self) -> Result<bool, LeaseError>;
+
+  /// Returns whether this owner believes it holds the lease.
+  fn check_lease(&self) -> bool;
+}
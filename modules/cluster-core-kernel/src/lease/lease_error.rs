@@ -0,0 +1,43 @@
+//! Errors returned by lease operations.
+
+use alloc::string::String;
+use core::{
+  error::Error,
+  fmt::{self, Formatter, Result as FmtResult},
+};
+
+#[cfg(test)]
+#[path = "lease_error_test.rs"]
+mod tests;
+
+/// Failure of a [`Lease`](super::Lease) operation or lookup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeaseError {
+  /// The lease backend could not be reached or returned an unusable answer.
+  Unavailable {
+    /// Failure reason.
+    reason: String,
+  },
+  /// The lease was held but has been taken over or has expired.
+  Lost {
+    /// Failure reason.
+    reason: String,
+  },
+  /// No lease implementation is registered under the requested name.
+  UnknownImplementation {
+    /// The requested implementation name.
+    name: String,
+  },
+}
+
+impl fmt::Display for LeaseError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      | Self::Unavailable { reason } => write!(f, "lease backend unavailable: {reason}"),
+      | Self::Lost { reason } => write!(f, "lease lost: {reason}"),
+      | Self::UnknownImplementation { name } => write!(f, "lease implementation {name} is not registered"),
+    }
+  }
+}
+
+impl Error for LeaseError {}
@@ -0,0 +1,14 @@
+use alloc::{format, string::String};
+
+use super::LeaseError;
+
+#[test]
+fn display_names_the_cause() {
+  let unavailable = LeaseError::Unavailable { reason: String::from("io") };
+  let lost = LeaseError::Lost { reason: String::from("taken over") };
+  let unknown = LeaseError::UnknownImplementation { name: String::from("etcd") };
+
+  assert_eq!(format!("{unavailable}"), "lease backend unavailable: io");
+  assert_eq!(format!("{lost}"), "lease lost: taken over");
+  assert_eq!(format!("{unknown}"), "lease implementation etcd is not registered");
+}
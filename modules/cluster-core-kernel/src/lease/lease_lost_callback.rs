@@ -0,0 +1,8 @@
+//! Callback invoked when a held lease is lost.
+
+use alloc::boxed::Box;
+
+use super::LeaseError;
+
+/// Callback a [`Lease`](super::Lease) invokes with the cause when it loses a held lease.
+pub type LeaseLostCallback = Box<dyn Fn(&LeaseError) + Send + Sync>;
@@ -0,0 +1,67 @@
+//! Registry creating leases by implementation name.
+
+use alloc::{boxed::Box, collections::BTreeMap, string::String};
+
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{Lease, LeaseError, LeaseSettings};
+
+#[cfg(test)]
+#[path = "lease_provider_test.rs"]
+mod tests;
+
+type LeaseFactory = ArcShared<dyn Fn(LeaseSettings) -> Box<dyn Lease> + Send + Sync>;
+
+/// Creates [`Lease`]s from factories registered under implementation names.
+///
+/// Consumers refer to a lease backend by name, for example through
+/// [`LeaseUsageConfig`](crate::singleton::LeaseUsageConfig), so the backend can be chosen by
+/// configuration. Clones share the registered factories.
+#[derive(Clone, Default)]
+pub struct LeaseProvider {
+  factories: BTreeMap<String, LeaseFactory>,
+}
+
+impl LeaseProvider {
+  /// Creates a provider without registered implementations.
+  #[must_use]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Registers `factory` under `implementation`, replacing a previous registration.
+  #[must_use]
+  pub fn with_implementation<F>(mut self, implementation: &str, factory: F) -> Self
+  where
+    F: Fn(LeaseSettings) -> Box<dyn Lease> + Send + Sync + 'static, {
+    self.register(implementation, factory);
+    self
+  }
+
+  /// Registers `factory` under `implementation`, replacing a previous registration.
+  pub fn register<F>(&mut self, implementation: &str, factory: F)
+  where
+    F: Fn(LeaseSettings) -> Box<dyn Lease> + Send + Sync + 'static, {
+    let factory: LeaseFactory = ArcShared::new(factory);
+    self.factories.insert(String::from(implementation), factory);
+  }
+
+  /// Returns whether an implementation is registered under `implementation`.
+  #[must_use]
+  pub fn contains(&self, implementation: &str) -> bool {
+    self.factories.contains_key(implementation)
+  }
+
+  /// Creates a lease of the implementation registered under `implementation`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LeaseError::UnknownImplementation`] when no factory is registered under the name.
+  pub fn get_lease(&self, implementation: &str, settings: LeaseSettings) -> Result<Box<dyn Lease>, LeaseError> {
+    let factory = self
+      .factories
+      .get(implementation)
+      .ok_or_else(|| LeaseError::UnknownImplementation { name: String::from(implementation) })?;
+    Ok(factory(settings))
+  }
+}
@@ -0,0 +1,69 @@
+use alloc::{boxed::Box, string::String};
+
+use super::LeaseProvider;
+use crate::lease::{Lease, LeaseError, LeaseLostCallback, LeaseSettings};
+
+struct GrantingLease {
+  settings: LeaseSettings,
+  held:     bool,
+}
+
+impl Lease for GrantingLease {
+  fn settings(&self) -> &LeaseSettings {
+    &self.settings
+  }
+
+  fn acquire(&mut self, _on_lost: Option<LeaseLostCallback>) -> Result<bool, LeaseError> {
+    self.held = true;
+    Ok(true)
+  }
+
+  fn renew(&mut self) -> Result<(), LeaseError> {
+    Ok(())
+  }
+
+  fn release(&mut self) -> Result<bool, LeaseError> {
+    Ok(core::mem::replace(&mut self.held, false))
+  }
+
+  fn check_lease(&self) -> bool {
+    self.held
+  }
+}
+
+fn granting(settings: LeaseSettings) -> Box<dyn Lease> {
+  Box::new(GrantingLease { settings, held: false })
+}
+
+#[test]
+fn get_lease_creates_the_registered_implementation() {
+  let provider = LeaseProvider::new().with_implementation("granting", granting);
+
+  let mut lease = provider.get_lease("granting", LeaseSettings::new("singleton", "a:1")).expect("lease");
+
+  assert!(provider.contains("granting"));
+  assert_eq!(lease.settings().lease_name(), "singleton");
+  assert_eq!(lease.settings().owner_name(), "a:1");
+  assert_eq!(lease.acquire(None), Ok(true));
+  assert!(lease.check_lease());
+}
+
+#[test]
+fn get_lease_rejects_an_unknown_implementation() {
+  let provider = LeaseProvider::new().with_implementation("granting", granting);
+
+  let result = provider.get_lease("etcd", LeaseSettings::new("singleton", "a:1"));
+
+  assert!(!provider.contains("etcd"));
+  assert_eq!(result.err(), Some(LeaseError::UnknownImplementation { name: String::from("etcd") }));
+}
+
+#[test]
+fn clones_share_registered_implementations() {
+  let mut provider = LeaseProvider::new();
+  provider.register("granting", granting);
+
+  let cloned = provider.clone();
+
+  assert!(cloned.get_lease("granting", LeaseSettings::new("sbr", "b:1")).is_ok());
+}
@@ -0,0 +1,74 @@
+//! Settings identifying a lease and its owner.
+
+use alloc::string::String;
+use core::time::Duration;
+
+#[cfg(test)]
+#[path = "lease_settings_test.rs"]
+mod tests;
+
+const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(12);
+const DEFAULT_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Name, owner and heartbeat timing of a [`Lease`](super::Lease).
+///
+/// The heartbeat timeout bounds how long a lease stays held without renewal; it should be several
+/// heartbeat intervals so that one missed renewal does not lose the lease.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaseSettings {
+  lease_name:         String,
+  owner_name:         String,
+  heartbeat_interval: Duration,
+  heartbeat_timeout:  Duration,
+}
+
+impl LeaseSettings {
+  /// Creates settings for `owner_name` competing for `lease_name` with default heartbeat timing.
+  #[must_use]
+  pub fn new(lease_name: &str, owner_name: &str) -> Self {
+    Self {
+      lease_name:         String::from(lease_name),
+      owner_name:         String::from(owner_name),
+      heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+      heartbeat_timeout:  DEFAULT_HEARTBEAT_TIMEOUT,
+    }
+  }
+
+  /// Sets how often the holder renews the lease.
+  #[must_use]
+  pub const fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+    self.heartbeat_interval = interval;
+    self
+  }
+
+  /// Sets how long a lease stays held without renewal.
+  #[must_use]
+  pub const fn with_heartbeat_timeout(mut self, timeout: Duration) -> Self {
+    self.heartbeat_timeout = timeout;
+    self
+  }
+
+  /// Returns the lease name.
+  #[must_use]
+  pub fn lease_name(&self) -> &str {
+    &self.lease_name
+  }
+
+  /// Returns the owner name.
+  #[must_use]
+  pub fn owner_name(&self) -> &str {
+    &self.owner_name
+  }
+
+  /// Returns how often the holder renews the lease.
+  #[must_use]
+  pub const fn heartbeat_interval(&self) -> Duration {
+    self.heartbeat_interval
+  }
+
+  /// Returns how long a lease stays held without renewal.
+  #[must_use]
+  pub const fn heartbeat_timeout(&self) -> Duration {
+    self.heartbeat_timeout
+  }
+}
@@ -0,0 +1,23 @@
+use core::time::Duration;
+
+use super::LeaseSettings;
+
+#[test]
+fn new_uses_default_heartbeat_timing() {
+  let settings = LeaseSettings::new("sbr", "a:1");
+
+  assert_eq!(settings.lease_name(), "sbr");
+  assert_eq!(settings.owner_name(), "a:1");
+  assert_eq!(settings.heartbeat_interval(), Duration::from_secs(12));
+  assert_eq!(settings.heartbeat_timeout(), Duration::from_secs(120));
+}
+
+#[test]
+fn builders_override_heartbeat_timing() {
+  let settings = LeaseSettings::new("sbr", "a:1")
+    .with_heartbeat_interval(Duration::from_millis(100))
+    .with_heartbeat_timeout(Duration::from_secs(1));
+
+  assert_eq!(settings.heartbeat_interval(), Duration::from_millis(100));
+  assert_eq!(settings.heartbeat_timeout(), Duration::from_secs(1));
+}
@@ -68,6 +68,8 @@ pub mod extension;
 pub mod failure_detector;
 /// Virtual actor (grain) API, RPC routing, and codec abstraction.
 pub mod grain;
+/// Lease abstraction granting exclusive ownership of a named resource to one member.
+pub mod lease;
 /// Membership management, gossip dissemination, and node lifecycle.
 pub mod membership;
 /// Cluster message serialization contracts.
@@ -76,6 +78,8 @@ pub mod message_serialization;
 pub mod outbound;
 /// Cluster-wide publish/subscribe messaging coordination.
 pub mod pub_sub;
+/// Shard allocation strategies and rebalancing for grain placement.
+pub mod sharding;
 /// Cluster Singleton settings, validation, and error vocabulary.
 pub mod singleton;
 /// Observed cluster topology and topology-change contracts.
@@ -0,0 +1,31 @@
+//! Shard allocation strategies and rebalancing for grain placement.
+
+mod external_shard_allocation;
+mod external_shard_allocation_strategy;
+mod least_shard_allocation_strategy;
+mod shard_allocation_config;
+mod shard_allocation_strategy;
+mod shard_allocation_table;
+mod shard_coordinator;
+mod shard_hand_off;
+mod shard_home;
+mod shard_id;
+mod shard_region_requests;
+mod shard_region_state;
+mod shard_region_stats;
+mod shard_state;
+
+pub use external_shard_allocation::ExternalShardAllocation;
+pub use external_shard_allocation_strategy::ExternalShardAllocationStrategy;
+pub use least_shard_allocation_strategy::LeastShardAllocationStrategy;
+pub use shard_allocation_config::ShardAllocationConfig;
+pub use shard_allocation_strategy::ShardAllocationStrategy;
+pub use shard_allocation_table::ShardAllocationTable;
+pub use shard_coordinator::ShardCoordinator;
+pub use shard_hand_off::ShardHandOff;
+pub use shard_home::ShardHome;
+pub use shard_id::ShardId;
+pub use shard_region_requests::ShardRegionRequests;
+pub use shard_region_state::ShardRegionState;
+pub use shard_region_stats::ShardRegionStats;
+pub use shard_state::ShardState;
@@ -0,0 +1,63 @@
+//! Operator API pinning shards to members.
+
+use alloc::{collections::BTreeMap, string::String};
+
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedAccess, SharedLock};
+
+use super::ShardId;
+
+/// Shared table of shard locations chosen by an operator.
+///
+/// Clones share the same table, so an operator keeps one handle while an
+/// [`ExternalShardAllocationStrategy`](super::ExternalShardAllocationStrategy) reads the locations
+/// on allocation and rebalance. Pinned shards move on the next rebalance round.
+#[derive(Clone)]
+pub struct ExternalShardAllocation {
+  locations: SharedLock<BTreeMap<ShardId, String>>,
+}
+
+impl ExternalShardAllocation {
+  /// Creates an empty location table.
+  #[must_use]
+  pub fn new() -> Self {
+    Self { locations: SharedLock::new_with_driver::<DefaultMutex<_>>(BTreeMap::new()) }
+  }
+
+  /// Pins `shard` to the member at `authority`.
+  pub fn update_shard_location(&self, shard: ShardId, authority: impl Into<String>) {
+    let authority = authority.into();
+    self.locations.with_write(|locations| {
+      locations.insert(shard, authority);
+    });
+  }
+
+  /// Pins every shard in `locations` to its member.
+  pub fn update_shard_locations(&self, locations: BTreeMap<ShardId, String>) {
+    self.locations.with_write(|current| current.extend(locations));
+  }
+
+  /// Removes the pin of `shard`, leaving it where it currently runs.
+  pub fn remove_shard_location(&self, shard: ShardId) {
+    self.locations.with_write(|locations| {
+      locations.remove(&shard);
+    });
+  }
+
+  /// Returns the pinned location of `shard`.
+  #[must_use]
+  pub fn shard_location(&self, shard: ShardId) -> Option<String> {
+    self.locations.with_read(|locations| locations.get(&shard).cloned())
+  }
+
+  /// Returns every pinned shard location.
+  #[must_use]
+  pub fn shard_locations(&self) -> BTreeMap<ShardId, String> {
+    self.locations.with_read(Clone::clone)
+  }
+}
+
+impl Default for ExternalShardAllocation {
+  fn default() -> Self {
+    Self::new()
+  }
+}
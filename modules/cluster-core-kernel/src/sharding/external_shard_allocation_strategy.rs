@@ -0,0 +1,68 @@
+//! Allocation strategy following operator-pinned shard locations.
+
+#[cfg(test)]
+#[path = "external_shard_allocation_strategy_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  string::String,
+  vec::Vec,
+};
+
+use super::{ExternalShardAllocation, ShardAllocationStrategy, ShardId};
+
+/// Places shards where an [`ExternalShardAllocation`] pins them.
+///
+/// Shards without a pin, or pinned to a member that is not part of the cluster, are allocated
+/// to the requesting member. Rebalancing moves every shard whose owner differs from its
+/// available pinned location.
+#[derive(Clone)]
+pub struct ExternalShardAllocationStrategy {
+  allocation: ExternalShardAllocation,
+}
+
+impl ExternalShardAllocationStrategy {
+  /// Creates a strategy reading locations from `allocation`.
+  #[must_use]
+  pub const fn new(allocation: ExternalShardAllocation) -> Self {
+    Self { allocation }
+  }
+
+  /// Returns the operator API backing this strategy.
+  #[must_use]
+  pub const fn allocation(&self) -> &ExternalShardAllocation {
+    &self.allocation
+  }
+}
+
+impl ShardAllocationStrategy for ExternalShardAllocationStrategy {
+  fn allocate_shard(
+    &mut self,
+    requester: &str,
+    shard: ShardId,
+    allocations: &BTreeMap<String, Vec<ShardId>>,
+  ) -> Option<String> {
+    match self.allocation.shard_location(shard) {
+      | Some(location) if allocations.contains_key(&location) => Some(location),
+      | _ => allocations.contains_key(requester).then(|| String::from(requester)),
+    }
+  }
+
+  fn rebalance(
+    &mut self,
+    allocations: &BTreeMap<String, Vec<ShardId>>,
+    in_progress: &BTreeSet<ShardId>,
+  ) -> Vec<ShardId> {
+    let locations = self.allocation.shard_locations();
+    allocations
+      .iter()
+      .flat_map(|(owner, shards)| shards.iter().map(move |shard| (owner, *shard)))
+      .filter(|(owner, shard)| {
+        !in_progress.contains(shard)
+          && locations.get(shard).is_some_and(|location| location != *owner && allocations.contains_key(location))
+      })
+      .map(|(_, shard)| shard)
+      .collect()
+  }
+}
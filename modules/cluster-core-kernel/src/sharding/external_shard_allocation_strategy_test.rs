@@ -0,0 +1,54 @@
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  string::String,
+  vec,
+  vec::Vec,
+};
+
+use super::ExternalShardAllocationStrategy;
+use crate::sharding::{ExternalShardAllocation, ShardAllocationStrategy, ShardId};
+
+fn allocations(entries: &[(&str, &[u32])]) -> BTreeMap<String, Vec<ShardId>> {
+  entries
+    .iter()
+    .map(|(authority, ids)| (String::from(*authority), ids.iter().copied().map(ShardId).collect()))
+    .collect()
+}
+
+#[test]
+fn pinned_shard_is_allocated_to_its_location() {
+  let allocation = ExternalShardAllocation::new();
+  allocation.update_shard_location(ShardId(1), "b");
+  let mut strategy = ExternalShardAllocationStrategy::new(allocation);
+  let current = allocations(&[("a", &[]), ("b", &[])]);
+
+  assert_eq!(strategy.allocate_shard("a", ShardId(1), &current).as_deref(), Some("b"));
+  assert_eq!(strategy.allocate_shard("a", ShardId(2), &current).as_deref(), Some("a"));
+}
+
+#[test]
+fn pin_to_an_absent_member_falls_back_to_the_requester() {
+  let allocation = ExternalShardAllocation::new();
+  allocation.update_shard_location(ShardId(1), "gone");
+  let mut strategy = ExternalShardAllocationStrategy::new(allocation);
+
+  let current = allocations(&[("a", &[])]);
+  assert_eq!(strategy.allocate_shard("a", ShardId(1), &current).as_deref(), Some("a"));
+}
+
+#[test]
+fn rebalance_moves_shards_away_from_their_unpinned_owner() {
+  let allocation = ExternalShardAllocation::new();
+  let mut strategy = ExternalShardAllocationStrategy::new(allocation.clone());
+  let current = allocations(&[("a", &[1, 2, 3]), ("b", &[])]);
+  assert!(strategy.rebalance(&current, &BTreeSet::new()).is_empty());
+
+  allocation.update_shard_locations(BTreeMap::from([
+    (ShardId(1), String::from("b")),
+    (ShardId(2), String::from("b")),
+    (ShardId(3), String::from("a")),
+  ]));
+
+  assert_eq!(strategy.rebalance(&current, &BTreeSet::new()), vec![ShardId(1), ShardId(2)]);
+  assert_eq!(strategy.rebalance(&current, &BTreeSet::from([ShardId(1)])), vec![ShardId(2)]);
+}
@@ -0,0 +1,110 @@
+//! Allocation strategy balancing the number of shards per member.
+
+#[cfg(test)]
+#[path = "least_shard_allocation_strategy_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  string::String,
+  vec::Vec,
+};
+
+use super::{ShardAllocationStrategy, ShardId};
+
+const DEFAULT_ABSOLUTE_LIMIT: usize = 20;
+const DEFAULT_RELATIVE_LIMIT: f64 = 0.1;
+
+/// Allocates new shards to the member owning the fewest shards and rebalances towards an
+/// even distribution.
+///
+/// A rebalance round first moves the shards exceeding the optimal maximum per member, then
+/// one shard from each fuller member when some member is below the optimal minimum. Each
+/// round moves at most `min(absolute_limit, relative_limit * total shards)` shards, and at
+/// least one, and no round starts while a previous hand-off is still in progress.
+#[derive(Debug, Clone)]
+pub struct LeastShardAllocationStrategy {
+  absolute_limit: usize,
+  relative_limit: f64,
+}
+
+impl LeastShardAllocationStrategy {
+  /// Creates a strategy moving at most `absolute_limit` shards, and at most
+  /// `relative_limit` of all shards, per rebalance round.
+  #[must_use]
+  pub const fn new(absolute_limit: usize, relative_limit: f64) -> Self {
+    Self { absolute_limit, relative_limit }
+  }
+
+  /// Returns the absolute limit of shards moved per round.
+  #[must_use]
+  pub const fn absolute_limit(&self) -> usize {
+    self.absolute_limit
+  }
+
+  /// Returns the limit of shards moved per round relative to all shards.
+  #[must_use]
+  pub const fn relative_limit(&self) -> f64 {
+    self.relative_limit
+  }
+
+  fn round_limit(&self, total: usize) -> usize {
+    let relative = (self.relative_limit * total as f64) as usize;
+    self.absolute_limit.min(relative).max(1)
+  }
+}
+
+impl Default for LeastShardAllocationStrategy {
+  fn default() -> Self {
+    Self::new(DEFAULT_ABSOLUTE_LIMIT, DEFAULT_RELATIVE_LIMIT)
+  }
+}
+
+impl ShardAllocationStrategy for LeastShardAllocationStrategy {
+  fn allocate_shard(
+    &mut self,
+    requester: &str,
+    _shard: ShardId,
+    allocations: &BTreeMap<String, Vec<ShardId>>,
+  ) -> Option<String> {
+    // 同数なら要求元を優先し、それ以外は authority 順で決定的に選ぶ
+    allocations
+      .iter()
+      .min_by_key(|(authority, shards)| (shards.len(), authority.as_str() != requester))
+      .map(|(authority, _)| authority.clone())
+  }
+
+  fn rebalance(
+    &mut self,
+    allocations: &BTreeMap<String, Vec<ShardId>>,
+    in_progress: &BTreeSet<ShardId>,
+  ) -> Vec<ShardId> {
+    let members = allocations.len();
+    let total: usize = allocations.values().map(Vec::len).sum();
+    if !in_progress.is_empty() || members < 2 || total == 0 {
+      return Vec::new();
+    }
+    let optimal_max = total.div_ceil(members);
+    let optimal_min = total / members;
+    let mut fullest: Vec<&Vec<ShardId>> = allocations.values().collect();
+    fullest.sort_by_key(|shards| core::cmp::Reverse(shards.len()));
+
+    let mut selected: Vec<ShardId> = fullest
+      .iter()
+      .filter(|shards| shards.len() > optimal_max)
+      .flat_map(|shards| shards.iter().take(shards.len() - optimal_max).copied())
+      .collect();
+    if selected.is_empty() {
+      let deficit: usize =
+        allocations.values().filter(|shards| shards.len() < optimal_min).map(|shards| optimal_min - shards.len()).sum();
+      selected = fullest
+        .iter()
+        .filter(|shards| shards.len() > optimal_min)
+        .filter_map(|shards| shards.first().copied())
+        .take(deficit)
+        .collect();
+    }
+    selected.truncate(self.round_limit(total));
+    selected
+  }
+}
@@ -0,0 +1,60 @@
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  string::String,
+  vec::Vec,
+};
+
+use super::LeastShardAllocationStrategy;
+use crate::sharding::{ShardAllocationStrategy, ShardId};
+
+fn shards(ids: &[u32]) -> Vec<ShardId> {
+  ids.iter().copied().map(ShardId).collect()
+}
+
+fn allocations(entries: &[(&str, &[u32])]) -> BTreeMap<String, Vec<ShardId>> {
+  entries.iter().map(|(authority, ids)| (String::from(*authority), shards(ids))).collect()
+}
+
+#[test]
+fn new_shard_goes_to_the_member_with_the_fewest_shards() {
+  let mut strategy = LeastShardAllocationStrategy::default();
+  let current = allocations(&[("a", &[1, 2]), ("b", &[3]), ("c", &[4, 5])]);
+
+  assert_eq!(strategy.allocate_shard("a", ShardId(6), &current).as_deref(), Some("b"));
+}
+
+#[test]
+fn requester_wins_a_tie() {
+  let mut strategy = LeastShardAllocationStrategy::default();
+  let current = allocations(&[("a", &[]), ("b", &[])]);
+
+  assert_eq!(strategy.allocate_shard("b", ShardId(1), &current).as_deref(), Some("b"));
+}
+
+#[test]
+fn shards_above_the_optimal_maximum_are_rebalanced() {
+  let mut strategy = LeastShardAllocationStrategy::new(10, 1.0);
+  let current = allocations(&[("a", &[1, 2, 3, 4]), ("b", &[]), ("c", &[5, 6])]);
+
+  // 6 shards / 3 members = 2 per member
+  assert_eq!(strategy.rebalance(&current, &BTreeSet::new()), shards(&[1, 2]));
+}
+
+#[test]
+fn member_below_the_optimal_minimum_receives_shards_from_fuller_members() {
+  let mut strategy = LeastShardAllocationStrategy::new(10, 1.0);
+  let current = allocations(&[("a", &[1, 2, 3]), ("b", &[4, 5, 6]), ("c", &[7, 8, 9]), ("d", &[])]);
+
+  // 9 shards / 4 members: optimal maximum 3, optimal minimum 2
+  assert_eq!(strategy.rebalance(&current, &BTreeSet::new()), shards(&[1, 4]));
+}
+
+#[test]
+fn rebalance_round_is_limited_and_waits_for_hand_offs_in_progress() {
+  let mut strategy = LeastShardAllocationStrategy::new(1, 1.0);
+  let current = allocations(&[("a", &[1, 2, 3, 4]), ("b", &[])]);
+
+  assert_eq!(strategy.rebalance(&current, &BTreeSet::new()), shards(&[1]));
+  assert!(strategy.rebalance(&current, &BTreeSet::from([ShardId(1)])).is_empty());
+  assert!(strategy.rebalance(&allocations(&[("a", &[1, 2])]), &BTreeSet::new()).is_empty());
+}
@@ -0,0 +1,56 @@
+//! Per-kind shard allocation settings.
+
+const DEFAULT_NUMBER_OF_SHARDS: u32 = 100;
+const DEFAULT_MAX_SIMULTANEOUS_REBALANCE: usize = 3;
+
+/// Settings of a [`ShardCoordinator`](super::ShardCoordinator).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardAllocationConfig {
+  number_of_shards:           u32,
+  max_simultaneous_rebalance: usize,
+}
+
+impl ShardAllocationConfig {
+  /// Creates settings with 100 shards and at most 3 simultaneous hand-offs.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self {
+      number_of_shards:           DEFAULT_NUMBER_OF_SHARDS,
+      max_simultaneous_rebalance: DEFAULT_MAX_SIMULTANEOUS_REBALANCE,
+    }
+  }
+
+  /// Sets the number of shards grain keys are grouped into.
+  ///
+  /// The value must be the same on every member; zero is treated as one.
+  #[must_use]
+  pub const fn with_number_of_shards(mut self, number_of_shards: u32) -> Self {
+    self.number_of_shards = number_of_shards;
+    self
+  }
+
+  /// Sets how many shards may be handed off at the same time.
+  #[must_use]
+  pub const fn with_max_simultaneous_rebalance(mut self, max_simultaneous_rebalance: usize) -> Self {
+    self.max_simultaneous_rebalance = max_simultaneous_rebalance;
+    self
+  }
+
+  /// Returns the number of shards.
+  #[must_use]
+  pub const fn number_of_shards(&self) -> u32 {
+    self.number_of_shards
+  }
+
+  /// Returns how many shards may be handed off at the same time.
+  #[must_use]
+  pub const fn max_simultaneous_rebalance(&self) -> usize {
+    self.max_simultaneous_rebalance
+  }
+}
+
+impl Default for ShardAllocationConfig {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,34 @@
+//! Strategy deciding where shards are allocated and which shards are rebalanced.
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  string::String,
+  vec::Vec,
+};
+
+use super::ShardId;
+
+/// Decides shard placement for a [`ShardCoordinator`](super::ShardCoordinator).
+///
+/// `allocations` maps every member authority to the shards it currently owns, including
+/// members that own no shard.
+pub trait ShardAllocationStrategy: Send + Sync {
+  /// Returns the authority that should own `shard`, which was first requested by `requester`.
+  ///
+  /// Returning `None` leaves the shard unallocated; it is requested again on the next lookup.
+  fn allocate_shard(
+    &mut self,
+    requester: &str,
+    shard: ShardId,
+    allocations: &BTreeMap<String, Vec<ShardId>>,
+  ) -> Option<String>;
+
+  /// Returns the shards that should be handed off to another member.
+  ///
+  /// Shards in `in_progress` are still being handed off and must not be returned.
+  fn rebalance(
+    &mut self,
+    allocations: &BTreeMap<String, Vec<ShardId>>,
+    in_progress: &BTreeSet<ShardId>,
+  ) -> Vec<ShardId>;
+}
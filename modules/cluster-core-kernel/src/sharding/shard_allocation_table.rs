@@ -0,0 +1,20 @@
+//! Shard allocations published by the shard coordinator of a grain kind.
+
+use alloc::{collections::BTreeMap, string::String};
+
+use super::ShardId;
+
+/// Allocations decided by the member acting as shard coordinator.
+///
+/// Only the coordinator allocates and rebalances shards; the other members apply the table
+/// with [`ShardCoordinator::apply_table`](super::ShardCoordinator::apply_table). Tables with a
+/// revision not greater than the applied one are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShardAllocationTable {
+  /// Revision of the table; grows with every change made by the coordinator.
+  pub revision:    u64,
+  /// Owner authority of every allocated shard.
+  pub allocations: BTreeMap<ShardId, String>,
+  /// Authority each shard being handed off is leaving.
+  pub hand_offs:   BTreeMap<ShardId, String>,
+}
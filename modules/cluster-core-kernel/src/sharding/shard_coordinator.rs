@@ -0,0 +1,280 @@
+//! Shard allocation and rebalance state for one grain kind.
+
+#[cfg(test)]
+#[path = "shard_coordinator_test.rs"]
+mod tests;
+
+use alloc::{
+  boxed::Box,
+  collections::{BTreeMap, BTreeSet},
+  string::String,
+  vec::Vec,
+};
+
+use super::{
+  ShardAllocationConfig, ShardAllocationStrategy, ShardAllocationTable, ShardHandOff, ShardHome, ShardId,
+  ShardRegionRequests,
+};
+use crate::grain::GrainKey;
+
+struct HandOffState {
+  from:     String,
+  buffered: Vec<GrainKey>,
+  revision: u64,
+}
+
+/// Tracks which member owns each shard of a grain kind and drives rebalancing.
+///
+/// Shards are allocated lazily by the configured [`ShardAllocationStrategy`] on the first
+/// lookup. [`Self::start_rebalance`] starts hand-offs chosen by the strategy, bounded by
+/// [`ShardAllocationConfig::max_simultaneous_rebalance`]; lookups of a shard being handed off
+/// are buffered and returned by [`Self::complete_hand_off`] so they can be resolved again
+/// on the new owner.
+///
+/// Every member holds a coordinator per kind, but only one member allocates and rebalances.
+/// It publishes its decisions with [`Self::table`]; the other members apply them with
+/// [`Self::apply_table`], look shards up with [`Self::lookup`] and send their
+/// [`Self::requests`] back to it.
+pub struct ShardCoordinator {
+  config:      ShardAllocationConfig,
+  strategy:    Box<dyn ShardAllocationStrategy>,
+  members:     Vec<String>,
+  allocations: BTreeMap<ShardId, String>,
+  hand_offs:   BTreeMap<ShardId, HandOffState>,
+  revision:    u64,
+  requested:   BTreeSet<ShardId>,
+  stopped:     BTreeMap<ShardId, u64>,
+}
+
+impl ShardCoordinator {
+  /// Creates a coordinator allocating shards with `strategy`.
+  #[must_use]
+  pub fn new(config: ShardAllocationConfig, strategy: Box<dyn ShardAllocationStrategy>) -> Self {
+    Self {
+      config,
+      strategy,
+      members: Vec::new(),
+      allocations: BTreeMap::new(),
+      hand_offs: BTreeMap::new(),
+      revision: 0,
+      requested: BTreeSet::new(),
+      stopped: BTreeMap::new(),
+    }
+  }
+
+  /// Returns the allocation settings.
+  #[must_use]
+  pub const fn config(&self) -> &ShardAllocationConfig {
+    &self.config
+  }
+
+  /// Returns the shard of `key`.
+  #[must_use]
+  pub fn shard_of(&self, key: &GrainKey) -> ShardId {
+    ShardId::for_key(key, self.config.number_of_shards())
+  }
+
+  /// Returns the owner of `shard`, if allocated.
+  #[must_use]
+  pub fn owner(&self, shard: ShardId) -> Option<&str> {
+    self.allocations.get(&shard).map(String::as_str)
+  }
+
+  /// Returns the shards being handed off.
+  #[must_use]
+  pub fn in_progress(&self) -> Vec<ShardId> {
+    self.hand_offs.keys().copied().collect()
+  }
+
+  /// Replaces the member list.
+  ///
+  /// Shards of departed members become unallocated, and hand-offs from departed members
+  /// complete; the keys buffered by those hand-offs are returned.
+  pub fn update_members(&mut self, members: &[String]) -> Vec<GrainKey> {
+    self.members = members.to_vec();
+    self.allocations.retain(|_, owner| members.contains(owner));
+    let departed: Vec<ShardId> =
+      self.hand_offs.iter().filter(|(_, state)| !members.contains(&state.from)).map(|(shard, _)| *shard).collect();
+    departed.into_iter().flat_map(|shard| self.finish_hand_off(shard)).collect()
+  }
+
+  /// Returns where the shard of `key` runs, allocating it on behalf of `requester` when needed.
+  pub fn home(&mut self, key: &GrainKey, requester: &str) -> ShardHome {
+    let shard = self.shard_of(key);
+    if let Some(state) = self.hand_offs.get_mut(&shard) {
+      if !state.buffered.contains(key) {
+        state.buffered.push(key.clone());
+      }
+      return ShardHome::Buffered;
+    }
+    match self.allocate(shard, requester) {
+      | Some(owner) => ShardHome::Owner(owner),
+      | None => ShardHome::Unallocated,
+    }
+  }
+
+  /// Returns where the shard of `key` runs without allocating it.
+  ///
+  /// Used by members that are not the coordinator: an unallocated shard is recorded in
+  /// [`Self::requests`] so the coordinator can allocate it.
+  pub fn lookup(&mut self, key: &GrainKey) -> ShardHome {
+    let shard = self.shard_of(key);
+    if let Some(state) = self.hand_offs.get_mut(&shard) {
+      if !state.buffered.contains(key) {
+        state.buffered.push(key.clone());
+      }
+      return ShardHome::Buffered;
+    }
+    if let Some(owner) = self.allocations.get(&shard) {
+      return ShardHome::Owner(owner.clone());
+    }
+    self.requested.insert(shard);
+    ShardHome::Unallocated
+  }
+
+  /// Returns the owner of `shard`, allocating it on behalf of `requester` when needed.
+  ///
+  /// Shards being handed off are not allocated.
+  pub fn allocate(&mut self, shard: ShardId, requester: &str) -> Option<String> {
+    if let Some(owner) = self.allocations.get(&shard) {
+      return Some(owner.clone());
+    }
+    if self.hand_offs.contains_key(&shard) {
+      return None;
+    }
+    let allocations = self.allocations_by_member();
+    match self.strategy.allocate_shard(requester, shard, &allocations) {
+      | Some(owner) if self.members.contains(&owner) => {
+        self.allocations.insert(shard, owner.clone());
+        self.revision += 1;
+        Some(owner)
+      },
+      | _ => None,
+    }
+  }
+
+  /// Starts the hand-offs chosen by the strategy.
+  ///
+  /// At most [`ShardAllocationConfig::max_simultaneous_rebalance`] hand-offs are in progress at
+  /// any time, so a round may start fewer hand-offs than the strategy asked for.
+  pub fn start_rebalance(&mut self) -> Vec<ShardHandOff> {
+    let available = self.config.max_simultaneous_rebalance().saturating_sub(self.hand_offs.len());
+    if available == 0 {
+      return Vec::new();
+    }
+    let allocations = self.allocations_by_member();
+    let in_progress: BTreeSet<ShardId> = self.hand_offs.keys().copied().collect();
+    let mut started = Vec::new();
+    for shard in self.strategy.rebalance(&allocations, &in_progress) {
+      if started.len() == available {
+        break;
+      }
+      let Some(from) = self.allocations.get(&shard).cloned() else {
+        continue;
+      };
+      if self.hand_offs.contains_key(&shard) {
+        continue;
+      }
+      self.revision += 1;
+      self.hand_offs.insert(shard, HandOffState { from: from.clone(), buffered: Vec::new(), revision: self.revision });
+      started.push(ShardHandOff { shard, from });
+    }
+    started
+  }
+
+  /// Returns `true` when `from` stopped the grains of `shard` for its current hand-off.
+  ///
+  /// `revision` is the table revision `from` observed the hand-off in; acknowledgements of an
+  /// earlier hand-off of the same shard are rejected.
+  #[must_use]
+  pub fn is_acknowledged(&self, shard: ShardId, from: &str, revision: u64) -> bool {
+    self.hand_offs.get(&shard).is_some_and(|state| state.from == from && state.revision <= revision)
+  }
+
+  /// Completes the hand-off of `shard` after its grains stopped on the previous owner.
+  ///
+  /// The shard becomes unallocated and is allocated again on the next lookup. Returns the keys
+  /// buffered during the hand-off; an unknown shard returns no keys.
+  pub fn complete_hand_off(&mut self, shard: ShardId) -> Vec<GrainKey> {
+    if !self.hand_offs.contains_key(&shard) {
+      return Vec::new();
+    }
+    self.revision += 1;
+    self.finish_hand_off(shard)
+  }
+
+  /// Returns the allocations to publish to the other members.
+  #[must_use]
+  pub fn table(&self) -> ShardAllocationTable {
+    ShardAllocationTable {
+      revision:    self.revision,
+      allocations: self.allocations.clone(),
+      hand_offs:   self.hand_offs.iter().map(|(shard, state)| (*shard, state.from.clone())).collect(),
+    }
+  }
+
+  /// Replaces the allocations with `table` published by the coordinator.
+  ///
+  /// Tables with a revision not greater than the current one are ignored. Returns the keys
+  /// buffered by hand-offs the table no longer lists.
+  pub fn apply_table(&mut self, table: ShardAllocationTable) -> Vec<GrainKey> {
+    if table.revision <= self.revision {
+      return Vec::new();
+    }
+    let completed: Vec<ShardId> =
+      self.hand_offs.keys().filter(|shard| !table.hand_offs.contains_key(shard)).copied().collect();
+    let released = completed.into_iter().flat_map(|shard| self.finish_hand_off(shard)).collect();
+    for (shard, from) in table.hand_offs {
+      self.hand_offs.entry(shard).or_insert(HandOffState { from, buffered: Vec::new(), revision: table.revision });
+    }
+    self.allocations = table.allocations;
+    self.revision = table.revision;
+    self.requested.retain(|shard| !self.allocations.contains_key(shard));
+    self.stopped.retain(|shard, _| self.hand_offs.contains_key(shard));
+    released
+  }
+
+  /// Records that the local member stopped the grains of `shard` for its hand-off.
+  pub fn acknowledge_hand_off(&mut self, shard: ShardId) {
+    if self.hand_offs.contains_key(&shard) {
+      self.stopped.entry(shard).or_insert(self.revision);
+    }
+  }
+
+  /// Returns the shards handed off by `from` that the local member has not stopped yet.
+  #[must_use]
+  pub fn unacknowledged_hand_offs(&self, from: &str) -> Vec<ShardId> {
+    self
+      .hand_offs
+      .iter()
+      .filter(|(shard, state)| state.from == from && !self.stopped.contains_key(shard))
+      .map(|(shard, _)| *shard)
+      .collect()
+  }
+
+  /// Returns the requests of the local member to the coordinator.
+  #[must_use]
+  pub fn requests(&self) -> ShardRegionRequests {
+    ShardRegionRequests { home: self.requested.clone(), hand_offs: self.stopped.clone() }
+  }
+
+  fn finish_hand_off(&mut self, shard: ShardId) -> Vec<GrainKey> {
+    let Some(state) = self.hand_offs.remove(&shard) else {
+      return Vec::new();
+    };
+    self.allocations.remove(&shard);
+    self.stopped.remove(&shard);
+    state.buffered
+  }
+
+  fn allocations_by_member(&self) -> BTreeMap<String, Vec<ShardId>> {
+    let mut by_member: BTreeMap<String, Vec<ShardId>> =
+      self.members.iter().map(|member| (member.clone(), Vec::new())).collect();
+    for (shard, owner) in &self.allocations {
+      if let Some(shards) = by_member.get_mut(owner) {
+        shards.push(*shard);
+      }
+    }
+    by_member
+  }
+}
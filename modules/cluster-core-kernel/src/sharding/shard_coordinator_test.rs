@@ -0,0 +1,167 @@
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+
+use super::ShardCoordinator;
+use crate::{
+  grain::GrainKey,
+  sharding::{
+    ExternalShardAllocation, ExternalShardAllocationStrategy, LeastShardAllocationStrategy, ShardAllocationConfig,
+    ShardAllocationTable, ShardHandOff, ShardHome, ShardId,
+  },
+};
+
+fn coordinator(max_simultaneous_rebalance: usize) -> ShardCoordinator {
+  let config =
+    ShardAllocationConfig::new().with_number_of_shards(8).with_max_simultaneous_rebalance(max_simultaneous_rebalance);
+  ShardCoordinator::new(config, Box::new(LeastShardAllocationStrategy::new(10, 1.0)))
+}
+
+fn members(authorities: &[&str]) -> Vec<String> {
+  authorities.iter().map(|authority| String::from(*authority)).collect()
+}
+
+/// Returns one key for each of the first `count` shards.
+fn keys_of_distinct_shards(coordinator: &ShardCoordinator, count: usize) -> Vec<GrainKey> {
+  let mut seen = Vec::new();
+  let mut keys = Vec::new();
+  for index in 0.. {
+    let key = GrainKey::new(format!("user/{index}"));
+    let shard = coordinator.shard_of(&key);
+    if !seen.contains(&shard) {
+      seen.push(shard);
+      keys.push(key);
+    }
+    if keys.len() == count {
+      break;
+    }
+  }
+  keys
+}
+
+#[test]
+fn shards_are_allocated_lazily_and_keep_their_owner() {
+  let mut coordinator = coordinator(3);
+  coordinator.update_members(&members(&["a", "b"]));
+  let keys = keys_of_distinct_shards(&coordinator, 2);
+
+  assert_eq!(coordinator.home(&keys[0], "a"), ShardHome::Owner(String::from("a")));
+  assert_eq!(coordinator.home(&keys[1], "a"), ShardHome::Owner(String::from("b")));
+  assert_eq!(coordinator.home(&keys[0], "b"), ShardHome::Owner(String::from("a")));
+}
+
+#[test]
+fn no_member_leaves_the_shard_unallocated() {
+  let mut coordinator = coordinator(3);
+
+  assert_eq!(coordinator.home(&GrainKey::new(String::from("user/1")), "a"), ShardHome::Unallocated);
+}
+
+#[test]
+fn rebalance_is_limited_and_buffers_lookups_until_the_hand_off_completes() {
+  let mut coordinator = coordinator(1);
+  coordinator.update_members(&members(&["a"]));
+  let keys = keys_of_distinct_shards(&coordinator, 4);
+  for key in &keys {
+    assert_eq!(coordinator.home(key, "a"), ShardHome::Owner(String::from("a")));
+  }
+  coordinator.update_members(&members(&["a", "b"]));
+
+  let started = coordinator.start_rebalance();
+  assert_eq!(started.len(), 1);
+  let moving = started[0].shard;
+  assert_eq!(started, vec![ShardHandOff { shard: moving, from: String::from("a") }]);
+  assert!(coordinator.start_rebalance().is_empty());
+  let key = keys.iter().find(|key| coordinator.shard_of(key) == moving).expect("moving key").clone();
+
+  assert_eq!(coordinator.home(&key, "a"), ShardHome::Buffered);
+  assert_eq!(coordinator.home(&key, "a"), ShardHome::Buffered);
+  assert_eq!(coordinator.complete_hand_off(moving), vec![key.clone()]);
+  assert_eq!(coordinator.home(&key, "a"), ShardHome::Owner(String::from("b")));
+  assert_eq!(coordinator.start_rebalance().len(), 1);
+}
+
+#[test]
+fn departed_members_lose_their_shards_and_complete_their_hand_offs() {
+  let mut coordinator = coordinator(3);
+  coordinator.update_members(&members(&["a"]));
+  let keys = keys_of_distinct_shards(&coordinator, 2);
+  for key in &keys {
+    let _ = coordinator.home(key, "a");
+  }
+  coordinator.update_members(&members(&["a", "b"]));
+  let started = coordinator.start_rebalance();
+  assert_eq!(started.len(), 1);
+  let moving = started[0].shard;
+  let buffered_key = keys.iter().find(|key| coordinator.shard_of(key) == moving).expect("moving key").clone();
+  assert_eq!(coordinator.home(&buffered_key, "b"), ShardHome::Buffered);
+
+  let released = coordinator.update_members(&members(&["b"]));
+
+  assert_eq!(released, vec![buffered_key]);
+  assert!(coordinator.in_progress().is_empty());
+  for key in &keys {
+    assert_eq!(coordinator.home(key, "b"), ShardHome::Owner(String::from("b")));
+  }
+  assert_eq!(coordinator.owner(ShardId(u32::MAX)), None);
+}
+
+#[test]
+fn followers_apply_newer_tables_and_acknowledge_hand_offs_with_the_observed_revision() {
+  let mut leader = coordinator(1);
+  let mut follower = coordinator(1);
+  leader.update_members(&members(&["a", "b"]));
+  follower.update_members(&members(&["a", "b"]));
+  let key = keys_of_distinct_shards(&leader, 1).remove(0);
+  let shard = leader.shard_of(&key);
+
+  assert_eq!(follower.lookup(&key), ShardHome::Unallocated);
+  assert!(follower.requests().home.contains(&shard));
+  assert_eq!(leader.allocate(shard, "b"), Some(String::from("b")));
+  let allocated = leader.table();
+  assert!(follower.apply_table(allocated.clone()).is_empty());
+  assert_eq!(follower.lookup(&key), ShardHome::Owner(String::from("b")));
+  assert!(follower.requests().home.is_empty());
+
+  let mut handing_off = allocated.clone();
+  handing_off.revision += 1;
+  handing_off.hand_offs.insert(shard, String::from("b"));
+  assert!(follower.apply_table(handing_off.clone()).is_empty());
+  assert_eq!(follower.unacknowledged_hand_offs("b"), vec![shard]);
+  follower.acknowledge_hand_off(shard);
+  assert_eq!(follower.requests().hand_offs.get(&shard), Some(&handing_off.revision));
+  assert!(follower.unacknowledged_hand_offs("b").is_empty());
+
+  // 古い改訂の表は無視される
+  assert!(follower.apply_table(allocated).is_empty());
+  assert_eq!(follower.lookup(&key), ShardHome::Buffered);
+  let completed = ShardAllocationTable { revision: handing_off.revision + 1, ..ShardAllocationTable::default() };
+  assert_eq!(follower.apply_table(completed), vec![key]);
+  assert!(follower.requests().hand_offs.is_empty());
+}
+
+#[test]
+fn acknowledgements_of_an_earlier_hand_off_do_not_complete_a_later_one() {
+  let allocation = ExternalShardAllocation::new();
+  let mut coordinator = ShardCoordinator::new(
+    ShardAllocationConfig::new(),
+    Box::new(ExternalShardAllocationStrategy::new(allocation.clone())),
+  );
+  coordinator.update_members(&members(&["a", "b"]));
+  let key = GrainKey::new(String::from("user/1"));
+  let shard = coordinator.shard_of(&key);
+  allocation.update_shard_location(shard, "a");
+  assert_eq!(coordinator.home(&key, "a"), ShardHome::Owner(String::from("a")));
+
+  allocation.update_shard_location(shard, "b");
+  assert_eq!(coordinator.start_rebalance(), vec![ShardHandOff { shard, from: String::from("a") }]);
+  let first = coordinator.table().revision;
+  assert!(coordinator.is_acknowledged(shard, "a", first));
+  assert!(coordinator.complete_hand_off(shard).is_empty());
+  assert_eq!(coordinator.home(&key, "a"), ShardHome::Owner(String::from("b")));
+
+  allocation.update_shard_location(shard, "a");
+  assert_eq!(coordinator.start_rebalance(), vec![ShardHandOff { shard, from: String::from("b") }]);
+
+  assert!(!coordinator.is_acknowledged(shard, "b", first));
+  assert!(!coordinator.is_acknowledged(shard, "a", coordinator.table().revision));
+  assert!(coordinator.is_acknowledged(shard, "b", coordinator.table().revision));
+}
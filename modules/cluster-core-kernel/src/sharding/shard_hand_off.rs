@@ -0,0 +1,17 @@
+//! Hand-off of a shard started by a rebalance round.
+
+use alloc::string::String;
+
+use super::ShardId;
+
+/// Shard leaving its current owner.
+///
+/// The owner stops the grains of the shard and the hand-off is completed with
+/// [`ShardCoordinator::complete_hand_off`](super::ShardCoordinator::complete_hand_off).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardHandOff {
+  /// Shard being handed off.
+  pub shard: ShardId,
+  /// Authority currently owning the shard.
+  pub from:  String,
+}
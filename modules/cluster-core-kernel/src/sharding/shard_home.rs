@@ -0,0 +1,15 @@
+//! Result of looking up where a shard runs.
+
+use alloc::string::String;
+
+/// Location of a shard returned by [`ShardCoordinator::home`](super::ShardCoordinator::home) and
+/// [`ShardCoordinator::lookup`](super::ShardCoordinator::lookup).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShardHome {
+  /// The shard is owned by the member at this authority.
+  Owner(String),
+  /// The shard is being handed off; the key was buffered until the hand-off completes.
+  Buffered,
+  /// The shard is not allocated to any member.
+  Unallocated,
+}
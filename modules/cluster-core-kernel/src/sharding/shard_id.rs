@@ -0,0 +1,29 @@
+//! Shard identifier grouping grain keys for allocation.
+
+#[cfg(test)]
+#[path = "shard_id_test.rs"]
+mod tests;
+
+use crate::grain::GrainKey;
+
+/// Identifies the shard a grain key belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ShardId(pub u32);
+
+impl ShardId {
+  /// Returns the shard of `key` among `number_of_shards` shards.
+  ///
+  /// Only the identity part of a `kind/identity` key is hashed, so the same identity maps to
+  /// the same shard on every member. `number_of_shards` of zero is treated as one.
+  #[must_use]
+  pub fn for_key(key: &GrainKey, number_of_shards: u32) -> Self {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for byte in key.identity().as_bytes() {
+      hash ^= u64::from(*byte);
+      hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    let shards = u64::from(number_of_shards.max(1));
+    // shards は u32 に収まるため剰余も u32 に収まる
+    Self(u32::try_from(hash % shards).unwrap_or_default())
+  }
+}
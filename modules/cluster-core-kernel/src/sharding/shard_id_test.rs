@@ -0,0 +1,18 @@
+use alloc::string::String;
+
+use super::ShardId;
+use crate::grain::GrainKey;
+
+#[test]
+fn same_identity_maps_to_the_same_shard_for_every_kind() {
+  let user = ShardId::for_key(&GrainKey::new(String::from("user/42")), 100);
+  let order = ShardId::for_key(&GrainKey::new(String::from("order/42")), 100);
+
+  assert_eq!(user, order);
+  assert!(user.0 < 100);
+}
+
+#[test]
+fn zero_shards_is_treated_as_a_single_shard() {
+  assert_eq!(ShardId::for_key(&GrainKey::new(String::from("user/42")), 0), ShardId(0));
+}
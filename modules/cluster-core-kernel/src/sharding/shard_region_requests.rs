@@ -0,0 +1,19 @@
+//! Requests of one member to the shard coordinator of a grain kind.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+
+use super::ShardId;
+
+/// Shard homes requested by a member and the hand-offs it has acknowledged.
+///
+/// Members other than the coordinator cannot allocate shards, so they request a home for the
+/// shards they looked up, and acknowledge a hand-off once they stopped the grains of the shard.
+/// Acknowledgements carry the table revision the hand-off was observed in, so an old
+/// acknowledgement never completes a later hand-off of the same shard.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShardRegionRequests {
+  /// Shards looked up while unallocated.
+  pub home:      BTreeSet<ShardId>,
+  /// Shards stopped for a hand-off, with the table revision the hand-off was observed in.
+  pub hand_offs: BTreeMap<ShardId, u64>,
+}
@@ -0,0 +1,25 @@
+//! Shards of one grain kind hosted by the local member.
+
+use alloc::{string::String, vec::Vec};
+
+use super::ShardState;
+
+/// Local shards of a grain kind with the grains active in each of them.
+///
+/// This is the fraktor equivalent of Pekko's `CurrentShardRegionState`. Shards are listed in
+/// ascending order and only shards with at least one active grain are included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardRegionState {
+  /// Grain kind the shards belong to.
+  pub kind:   String,
+  /// Shards with active grains on the local member.
+  pub shards: Vec<ShardState>,
+}
+
+impl ShardRegionState {
+  /// Creates a state without shards for `kind`.
+  #[must_use]
+  pub fn empty(kind: impl Into<String>) -> Self {
+    Self { kind: kind.into(), shards: Vec::new() }
+  }
+}
@@ -0,0 +1,18 @@
+//! Number of grains hosted by one member.
+
+use alloc::{collections::BTreeMap, string::String};
+
+/// Count of active grains per kind on one member.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShardRegionStats {
+  /// Number of active grains keyed by grain kind.
+  pub entity_counts: BTreeMap<String, usize>,
+}
+
+impl ShardRegionStats {
+  /// Returns the total number of active grains over all kinds.
+  #[must_use]
+  pub fn total(&self) -> usize {
+    self.entity_counts.values().sum()
+  }
+}
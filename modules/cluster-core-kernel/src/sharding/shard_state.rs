@@ -0,0 +1,14 @@
+//! Grains of one shard hosted by the local member.
+
+use alloc::{string::String, vec::Vec};
+
+use super::ShardId;
+
+/// Shard and the identities of its grains active on the local member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardState {
+  /// Shard the grains belong to.
+  pub shard:      ShardId,
+  /// Identities of the active grains, without the kind prefix.
+  pub entity_ids: Vec<String>,
+}
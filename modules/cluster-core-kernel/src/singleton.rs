@@ -1,13 +1,27 @@
-//! Cluster Singleton configuration, validation, and error vocabulary.
+//! Cluster Singleton configuration, runtime, and error vocabulary.
 
 mod cluster_singleton_config_error;
+mod cluster_singleton_manager;
 mod cluster_singleton_manager_config;
+mod cluster_singleton_member_event;
+mod cluster_singleton_proxy;
 mod cluster_singleton_proxy_config;
 mod lease_usage_config;
+mod singleton_lease;
+mod singleton_members;
+mod singleton_peer_message;
 mod singleton_stuck_phase;
+mod singleton_tick;
 
 pub use cluster_singleton_config_error::ClusterSingletonConfigError;
+pub use cluster_singleton_manager::ClusterSingletonManager;
 pub use cluster_singleton_manager_config::ClusterSingletonManagerConfig;
+pub use cluster_singleton_member_event::ClusterSingletonMemberEvent;
+pub use cluster_singleton_proxy::ClusterSingletonProxy;
 pub use cluster_singleton_proxy_config::ClusterSingletonProxyConfig;
 pub use lease_usage_config::LeaseUsageConfig;
+pub use singleton_lease::SingletonLease;
+pub(crate) use singleton_members::SingletonMembers;
+pub(crate) use singleton_peer_message::SingletonPeerMessage;
 pub use singleton_stuck_phase::SingletonStuckPhase;
+pub(crate) use singleton_tick::SingletonTick;
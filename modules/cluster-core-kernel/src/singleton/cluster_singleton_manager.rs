@@ -0,0 +1,502 @@
+//! Actor that runs the cluster singleton on the oldest member.
+
+#[cfg(test)]
+#[path = "cluster_singleton_manager_test.rs"]
+mod tests;
+
+use alloc::{boxed::Box, format, string::String};
+use core::time::Duration;
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext, Pid,
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView, PoisonPill},
+    props::Props,
+  },
+  event::{logging::LogLevel, stream::EventStreamEvent},
+};
+use fraktor_utils_core_rs::{sync::SharedAccess, time::TimerInstant};
+
+use super::{
+  ClusterSingletonManagerConfig, ClusterSingletonMemberEvent, LeaseUsageConfig, SingletonLease, SingletonMembers,
+  SingletonPeerMessage, SingletonStuckPhase, SingletonTick,
+};
+use crate::{
+  lease::{LeaseError, LeaseProvider, LeaseSettings},
+  topology::ClusterEvent,
+};
+
+const HAND_OVER_RETRY_TIMER: &str = "singleton-hand-over-retry";
+const TAKE_OVER_RETRY_TIMER: &str = "singleton-take-over-retry";
+const LEASE_RETRY_TIMER: &str = "singleton-lease-retry";
+const LEASE_HEARTBEAT_TIMER: &str = "singleton-lease-heartbeat";
+const REMOVAL_MARGIN_TIMER: &str = "singleton-removal-margin";
+
+enum ManagerState {
+  /// The local member is not up yet.
+  Start,
+  /// Another member is the oldest.
+  Younger,
+  /// Waiting for the previous oldest to hand over, or for the removal margin to pass.
+  BecomingOldest { previous: Option<String>, retries: u32 },
+  /// Oldest, waiting for the lease before starting the singleton.
+  AcquiringLease,
+  /// Oldest and running the singleton; `None` when the singleton stopped on its own.
+  Oldest { singleton: Option<ActorRef> },
+  /// Leaving while running the singleton, offering it to the next oldest.
+  WasOldest { singleton: Option<ActorRef>, retries: u32 },
+  /// Stopping the singleton for the manager in `hand_over_to`.
+  HandingOver { singleton: ActorRef, hand_over_to: Option<ActorRef> },
+  /// The local member left; the manager no longer takes part.
+  End,
+}
+
+/// Runs one singleton actor on the oldest member with the configured role.
+///
+/// Each member runs a manager with the same singleton name. Managers learn membership through
+/// [`ClusterSingletonMemberEvent`]s and elect the oldest up member by
+/// [`member_age_order`](crate::membership::member_age_order). When the oldest leaves, it
+/// offers the singleton to the next oldest, stops its instance with the termination message and
+/// reports completion; the next oldest starts its instance only then. A removed oldest is
+/// replaced after the removal margin.
+///
+/// A hand-over that exceeds the maximum retries is reported as
+/// [`ClusterEvent::SingletonHandOverStuck`] on the event stream and then given up: the new oldest
+/// starts its singleton without the hand-over, and a leaving oldest stops its singleton without
+/// waiting for a successor. Pair the manager with a lease when an unresponsive previous oldest may
+/// still be running its instance. With a [`SingletonLease`], the singleton starts only once the
+/// lease is acquired; the lease is renewed every lease retry interval and the singleton is stopped
+/// when it is lost.
+pub struct ClusterSingletonManager {
+  singleton_props:     Props,
+  config:              ClusterSingletonManagerConfig,
+  self_authority:      String,
+  termination_message: AnyMessage,
+  lease:               Option<Box<dyn SingletonLease>>,
+  lease_held:          bool,
+  members:             SingletonMembers,
+  oldest:              Option<String>,
+  state:               ManagerState,
+}
+
+impl ClusterSingletonManager {
+  /// Creates a manager running `singleton_props` on the member with authority `self_authority`.
+  ///
+  /// The singleton is stopped with [`PoisonPill`] unless another termination message is set.
+  #[must_use]
+  pub fn new(singleton_props: Props, config: ClusterSingletonManagerConfig, self_authority: &str) -> Self {
+    let members = SingletonMembers::new(config.role(), None);
+    Self {
+      singleton_props,
+      config,
+      self_authority: String::from(self_authority),
+      termination_message: AnyMessage::new(PoisonPill),
+      lease: None,
+      lease_held: false,
+      members,
+      oldest: None,
+      state: ManagerState::Start,
+    }
+  }
+
+  /// Sets the message sent to the singleton to stop it for a hand-over.
+  #[must_use]
+  pub fn with_termination_message(mut self, message: AnyMessage) -> Self {
+    self.termination_message = message;
+    self
+  }
+
+  /// Sets the lease that must be held while the singleton runs.
+  #[must_use]
+  pub fn with_lease(mut self, lease: Box<dyn SingletonLease>) -> Self {
+    self.lease = Some(lease);
+    self
+  }
+
+  /// Sets the lease configured by [`LeaseUsageConfig`], created through `provider`.
+  ///
+  /// The lease is named after the singleton and owned by `self_authority`. Without a lease
+  /// configuration the manager is returned unchanged.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LeaseError::UnknownImplementation`] when the configured lease implementation is not
+  /// registered in `provider`.
+  pub fn with_lease_provider(self, provider: &LeaseProvider) -> Result<Self, LeaseError> {
+    let Some(lease_config) = self.config.lease_config() else {
+      return Ok(self);
+    };
+    let lease_name = format!("singleton-{}", self.config.singleton_name());
+    let settings = LeaseSettings::new(&lease_name, &self.self_authority)
+      .with_heartbeat_interval(lease_config.lease_retry_interval());
+    let lease = provider.get_lease(lease_config.lease_implementation(), settings)?;
+    Ok(self.with_lease(Box::new(lease)))
+  }
+
+  fn is_self(&self, authority: Option<&str>) -> bool {
+    authority == Some(self.self_authority.as_str())
+  }
+
+  fn on_member_event(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    event: &ClusterSingletonMemberEvent,
+  ) -> Result<(), ActorError> {
+    let previous_oldest = self.oldest.clone();
+    let removed = match event {
+      | ClusterSingletonMemberEvent::Removed { authority } => Some(authority.clone()),
+      | _ => None,
+    };
+    self.members.apply(event);
+    self.oldest = self.members.oldest();
+    if self.is_self(removed.as_deref()) {
+      return self.self_removed(ctx);
+    }
+    match &self.state {
+      | ManagerState::Start if self.members.contains(&self.self_authority) => {
+        if self.is_self(self.oldest.as_deref()) {
+          self.goto_oldest(ctx)
+        } else {
+          self.state = ManagerState::Younger;
+          Ok(())
+        }
+      },
+      | ManagerState::Younger if self.is_self(self.oldest.as_deref()) => match previous_oldest {
+        | Some(previous) if self.members.contains(&previous) => self.become_oldest(ctx, Some(previous)),
+        | Some(previous) if removed.as_ref() == Some(&previous) => match self.config.removal_margin() {
+          | Some(margin) if !margin.is_zero() => {
+            start_single(ctx, REMOVAL_MARGIN_TIMER, SingletonTick::RemovalMarginElapsed, margin)?;
+            self.state = ManagerState::BecomingOldest { previous: None, retries: 0 };
+            Ok(())
+          },
+          | _ => self.goto_oldest(ctx),
+        },
+        | _ => self.goto_oldest(ctx),
+      },
+      | ManagerState::BecomingOldest { previous: Some(previous), .. } if removed.as_ref() == Some(previous) => {
+        self.goto_oldest(ctx)
+      },
+      | ManagerState::Oldest { .. } if self.members.is_exiting(&self.self_authority) => {
+        let ManagerState::Oldest { singleton } = core::mem::replace(&mut self.state, ManagerState::End) else {
+          return Ok(());
+        };
+        self.goto_was_oldest(ctx, singleton)
+      },
+      | _ => Ok(()),
+    }
+  }
+
+  fn on_peer_message(&mut self, ctx: &mut ActorContext<'_>, message: &SingletonPeerMessage) -> Result<(), ActorError> {
+    let Some(sender) = ctx.sender().cloned() else {
+      return Ok(());
+    };
+    match message {
+      | SingletonPeerMessage::Identify => {
+        let singleton = match &self.state {
+          | ManagerState::Oldest { singleton } => singleton.clone(),
+          | _ => None,
+        };
+        tell_peer(ctx, &sender, SingletonPeerMessage::Identified { singleton });
+        Ok(())
+      },
+      | SingletonPeerMessage::TakeOverFromMe => match &self.state {
+        | ManagerState::Start | ManagerState::Younger => {
+          let previous = self.members.authority_of(&sender).map(String::from);
+          self.become_oldest(ctx, previous)
+        },
+        | ManagerState::BecomingOldest { .. } => {
+          tell_peer(ctx, &sender, SingletonPeerMessage::HandOverToMe);
+          Ok(())
+        },
+        | _ => Ok(()),
+      },
+      | SingletonPeerMessage::HandOverToMe => match core::mem::replace(&mut self.state, ManagerState::End) {
+        | ManagerState::Oldest { singleton } | ManagerState::WasOldest { singleton, .. } => {
+          self.goto_handing_over(ctx, singleton, Some(sender));
+          Ok(())
+        },
+        | state @ ManagerState::HandingOver { .. } => {
+          self.state = state;
+          tell_peer(ctx, &sender, SingletonPeerMessage::HandOverInProgress);
+          Ok(())
+        },
+        | state => {
+          // 単一インスタンスを保持していないので、要求側はすぐに開始してよい。
+          self.state = state;
+          tell_peer(ctx, &sender, SingletonPeerMessage::HandOverDone);
+          Ok(())
+        },
+      },
+      | SingletonPeerMessage::HandOverDone => match &self.state {
+        | ManagerState::BecomingOldest { .. } => self.goto_oldest(ctx),
+        | _ => Ok(()),
+      },
+      | SingletonPeerMessage::HandOverInProgress | SingletonPeerMessage::Identified { .. } => Ok(()),
+    }
+  }
+
+  fn on_tick(&mut self, ctx: &mut ActorContext<'_>, tick: SingletonTick) -> Result<(), ActorError> {
+    let max_retries = self.config.max_hand_over_retries();
+    match (&mut self.state, tick) {
+      | (ManagerState::BecomingOldest { previous, retries }, SingletonTick::HandOverRetry) => {
+        *retries = retries.saturating_add(1);
+        if *retries > max_retries {
+          // 前任の応答を待ち続けるとシングルトンが止まったままになるため、報告したうえで引き継ぐ
+          self.report_stuck(ctx, SingletonStuckPhase::BecomingOldest);
+          return self.goto_oldest(ctx);
+        }
+        let previous_manager = previous.as_deref().and_then(|previous| self.members.manager(previous)).cloned();
+        match previous_manager {
+          | Some(manager) => {
+            tell_peer(ctx, &manager, SingletonPeerMessage::HandOverToMe);
+            Ok(())
+          },
+          | None => self.goto_oldest(ctx),
+        }
+      },
+      | (ManagerState::WasOldest { retries, .. }, SingletonTick::TakeOverRetry) => {
+        *retries = retries.saturating_add(1);
+        let gave_up = *retries > max_retries;
+        if gave_up {
+          // 後継が応答しないまま退出を止めないよう、報告したうえで自身のシングルトンを停止する
+          self.report_stuck(ctx, SingletonStuckPhase::HandingOver);
+        }
+        match self.oldest_manager().filter(|_| !gave_up) {
+          | Some(manager) => {
+            tell_peer(ctx, &manager, SingletonPeerMessage::TakeOverFromMe);
+            Ok(())
+          },
+          | None => {
+            let ManagerState::WasOldest { singleton, .. } = core::mem::replace(&mut self.state, ManagerState::End)
+            else {
+              return Ok(());
+            };
+            self.goto_handing_over(ctx, singleton, None);
+            Ok(())
+          },
+        }
+      },
+      | (ManagerState::Oldest { .. }, SingletonTick::LeaseHeartbeat) => self.renew_lease(ctx),
+      | (ManagerState::AcquiringLease, SingletonTick::LeaseRetry)
+      | (ManagerState::BecomingOldest { previous: None, .. }, SingletonTick::RemovalMarginElapsed) => {
+        self.goto_oldest(ctx)
+      },
+      | _ => Ok(()),
+    }
+  }
+
+  fn oldest_manager(&self) -> Option<ActorRef> {
+    self
+      .oldest
+      .as_deref()
+      .filter(|oldest| *oldest != self.self_authority)
+      .and_then(|oldest| self.members.manager(oldest))
+      .cloned()
+  }
+
+  fn become_oldest(&mut self, ctx: &mut ActorContext<'_>, previous: Option<String>) -> Result<(), ActorError> {
+    let Some(manager) = previous.as_deref().and_then(|previous| self.members.manager(previous)).cloned() else {
+      return self.goto_oldest(ctx);
+    };
+    tell_peer(ctx, &manager, SingletonPeerMessage::HandOverToMe);
+    start_periodic(ctx, HAND_OVER_RETRY_TIMER, SingletonTick::HandOverRetry, self.config.hand_over_retry_interval())?;
+    self.state = ManagerState::BecomingOldest { previous, retries: 0 };
+    Ok(())
+  }
+
+  fn goto_oldest(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    // must-ignore: 取り消せなかったタイマーの tick は BecomingOldest 以外の状態では無視される。
+    drop(ctx.timers().cancel(HAND_OVER_RETRY_TIMER));
+    // must-ignore: 取り消せなかったタイマーの tick は BecomingOldest 以外の状態では無視される。
+    drop(ctx.timers().cancel(REMOVAL_MARGIN_TIMER));
+    if let Some(lease) = self.lease.as_mut()
+      && !self.lease_held
+    {
+      self.lease_held = match lease.acquire() {
+        | Ok(held) => held,
+        | Err(error) => {
+          // 他メンバーが保持している場合と区別できるよう、バックエンド障害はログに残して再試行する
+          ctx.log(LogLevel::Warn, format!("cluster singleton lease acquisition failed, retrying: {error}"));
+          false
+        },
+      };
+      if !self.lease_held {
+        start_single(ctx, LEASE_RETRY_TIMER, SingletonTick::LeaseRetry, self.lease_retry_interval())?;
+        self.state = ManagerState::AcquiringLease;
+        return Ok(());
+      }
+    }
+    if self.lease_held {
+      start_single(ctx, LEASE_HEARTBEAT_TIMER, SingletonTick::LeaseHeartbeat, self.lease_retry_interval())?;
+    }
+    let props = self.singleton_props.clone().with_name(self.config.singleton_name());
+    let singleton = ctx
+      .spawn_child_watched(&props)
+      .map_err(|error| ActorError::recoverable(format!("singleton spawn failed: {error:?}")))?
+      .into_actor_ref();
+    self.state = ManagerState::Oldest { singleton: Some(singleton) };
+    Ok(())
+  }
+
+  fn renew_lease(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    let Some(lease) = self.lease.as_mut() else {
+      return Ok(());
+    };
+    if !self.lease_held {
+      return Ok(());
+    }
+    if lease.renew() {
+      return start_single(ctx, LEASE_HEARTBEAT_TIMER, SingletonTick::LeaseHeartbeat, self.lease_retry_interval());
+    }
+    // リースを失ったのでシングルトンを止め、停止を見届けてから再取得する
+    self.lease_held = false;
+    match &self.state {
+      | ManagerState::Oldest { singleton: Some(singleton) } => {
+        singleton.clone().tell(self.termination_message.clone());
+        Ok(())
+      },
+      | _ => self.goto_oldest(ctx),
+    }
+  }
+
+  fn lease_retry_interval(&self) -> Duration {
+    self.config.lease_config().map_or(self.config.hand_over_retry_interval(), LeaseUsageConfig::lease_retry_interval)
+  }
+
+  fn goto_was_oldest(&mut self, ctx: &mut ActorContext<'_>, singleton: Option<ActorRef>) -> Result<(), ActorError> {
+    let Some(manager) = self.oldest_manager() else {
+      self.goto_handing_over(ctx, singleton, None);
+      return Ok(());
+    };
+    tell_peer(ctx, &manager, SingletonPeerMessage::TakeOverFromMe);
+    start_periodic(ctx, TAKE_OVER_RETRY_TIMER, SingletonTick::TakeOverRetry, self.config.hand_over_retry_interval())?;
+    self.state = ManagerState::WasOldest { singleton, retries: 0 };
+    Ok(())
+  }
+
+  fn goto_handing_over(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    singleton: Option<ActorRef>,
+    hand_over_to: Option<ActorRef>,
+  ) {
+    // must-ignore: 取り消せなかったタイマーの tick は WasOldest 以外の状態では無視される。
+    drop(ctx.timers().cancel(TAKE_OVER_RETRY_TIMER));
+    let Some(mut singleton) = singleton else {
+      self.hand_over_done(ctx, hand_over_to.as_ref());
+      return;
+    };
+    if let Some(manager) = &hand_over_to {
+      tell_peer(ctx, manager, SingletonPeerMessage::HandOverInProgress);
+    }
+    singleton.tell(self.termination_message.clone());
+    self.state = ManagerState::HandingOver { singleton, hand_over_to };
+  }
+
+  fn hand_over_done(&mut self, ctx: &ActorContext<'_>, hand_over_to: Option<&ActorRef>) {
+    self.release_lease();
+    if let Some(manager) = hand_over_to {
+      tell_peer(ctx, manager, SingletonPeerMessage::HandOverDone);
+    }
+    self.state = if self.members.is_exiting(&self.self_authority) { ManagerState::End } else { ManagerState::Younger };
+  }
+
+  fn self_removed(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    self.state = ManagerState::End;
+    self.release_lease();
+    ctx.stop_self().map_err(|error| ActorError::recoverable(format!("singleton manager stop failed: {error:?}")))
+  }
+
+  fn release_lease(&mut self) {
+    if let Some(lease) = self.lease.as_mut()
+      && self.lease_held
+    {
+      lease.release();
+      self.lease_held = false;
+    }
+  }
+
+  fn report_stuck(&self, ctx: &ActorContext<'_>, phase: SingletonStuckPhase) {
+    let system = ctx.system();
+    let observed_at = system
+      .scheduler()
+      .with_read(|scheduler| TimerInstant::from_ticks(scheduler.current_tick(), scheduler.resolution()));
+    let event = ClusterEvent::SingletonHandOverStuck {
+      singleton_name: String::from(self.config.singleton_name()),
+      phase,
+      observed_at,
+    };
+    let payload = AnyMessage::new(event);
+    system.event_stream().publish(&EventStreamEvent::Extension { name: String::from("cluster"), payload });
+  }
+}
+
+impl Actor for ClusterSingletonManager {
+  fn receive(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(event) = message.downcast_ref::<ClusterSingletonMemberEvent>() {
+      return self.on_member_event(ctx, event);
+    }
+    if let Some(peer_message) = message.downcast_ref::<SingletonPeerMessage>() {
+      return self.on_peer_message(ctx, peer_message);
+    }
+    if let Some(tick) = message.downcast_ref::<SingletonTick>() {
+      return self.on_tick(ctx, *tick);
+    }
+    Ok(())
+  }
+
+  fn post_stop(&mut self, _ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    self.release_lease();
+    Ok(())
+  }
+
+  fn on_terminated(&mut self, ctx: &mut ActorContext<'_>, terminated: Pid) -> Result<(), ActorError> {
+    match core::mem::replace(&mut self.state, ManagerState::End) {
+      | ManagerState::HandingOver { singleton, hand_over_to } if singleton.pid() == terminated => {
+        self.hand_over_done(ctx, hand_over_to.as_ref());
+        Ok(())
+      },
+      | ManagerState::Oldest { singleton: Some(singleton) } if singleton.pid() == terminated => {
+        self.state = ManagerState::Oldest { singleton: None };
+        if self.lease.is_some() && !self.lease_held {
+          // リース喪失で停止したので、リースを取り直してから再開する
+          return self.goto_oldest(ctx);
+        }
+        Ok(())
+      },
+      | ManagerState::WasOldest { singleton: Some(singleton), retries } if singleton.pid() == terminated => {
+        self.state = ManagerState::WasOldest { singleton: None, retries };
+        Ok(())
+      },
+      | state => {
+        self.state = state;
+        Ok(())
+      },
+    }
+  }
+}
+
+fn tell_peer(ctx: &ActorContext<'_>, target: &ActorRef, message: SingletonPeerMessage) {
+  target.clone().tell(AnyMessage::new(message).with_sender(ctx.self_ref()));
+}
+
+fn start_periodic(
+  ctx: &ActorContext<'_>,
+  key: &str,
+  tick: SingletonTick,
+  interval: Duration,
+) -> Result<(), ActorError> {
+  ctx
+    .timers()
+    .start_timer_with_fixed_delay(key, AnyMessage::new(tick), interval)
+    .map_err(|error| ActorError::recoverable(format!("singleton timer {key} failed: {error:?}")))
+}
+
+fn start_single(ctx: &ActorContext<'_>, key: &str, tick: SingletonTick, delay: Duration) -> Result<(), ActorError> {
+  ctx
+    .timers()
+    .start_single_timer(key, AnyMessage::new(tick), delay)
+    .map_err(|error| ActorError::recoverable(format!("singleton timer {key} failed: {error:?}")))
+}
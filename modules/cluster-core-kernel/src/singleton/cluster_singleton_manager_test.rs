@@ -0,0 +1,446 @@
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::{
+  sync::atomic::{AtomicBool, AtomicU32, Ordering},
+  time::Duration,
+};
+use std::{thread, time::Instant};
+
+use fraktor_actor_adaptor_std_rs::{dispatch::dispatcher::ThreadedExecutor, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext,
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView},
+    props::Props,
+    scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  dispatch::dispatcher::{
+    DEFAULT_DISPATCHER_ID, DefaultDispatcherFactory, DispatcherConfig, ExecutorShared, MessageDispatcherFactory,
+    TrampolineState,
+  },
+  event::{
+    logging::LogLevel,
+    stream::{EventStreamEvent, EventStreamSubscriber, EventStreamSubscription, subscriber_handle},
+  },
+  system::ActorSystem,
+};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+use super::ClusterSingletonManager;
+use crate::{
+  lease::{Lease, LeaseError, LeaseLostCallback, LeaseProvider, LeaseSettings},
+  membership::{MembershipVersion, NodeRecord, NodeStatus},
+  singleton::{
+    ClusterSingletonManagerConfig, ClusterSingletonMemberEvent, LeaseUsageConfig, SingletonLease, SingletonStuckPhase,
+  },
+  topology::ClusterEvent,
+};
+
+type Log = ArcShared<SpinSyncMutex<Vec<String>>>;
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+#[derive(Clone)]
+struct StopSingleton;
+
+struct SingletonActor {
+  label: &'static str,
+  log:   Log,
+}
+
+impl Actor for SingletonActor {
+  fn pre_start(&mut self, _ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    self.log.lock().push(format!("{}:started", self.label));
+    Ok(())
+  }
+
+  fn receive(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if message.downcast_ref::<StopSingleton>().is_some() {
+      ctx.stop_self().map_err(|_| ActorError::recoverable("singleton stop failed"))?;
+    }
+    Ok(())
+  }
+
+  fn post_stop(&mut self, _ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    self.log.lock().push(format!("{}:stopped", self.label));
+    Ok(())
+  }
+}
+
+struct SilentActor;
+
+impl Actor for SilentActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+#[derive(Clone)]
+struct WarningRecorder {
+  messages: Log,
+}
+
+impl EventStreamSubscriber for WarningRecorder {
+  fn on_event(&mut self, event: &EventStreamEvent) {
+    if let EventStreamEvent::Log(log) = event
+      && log.level() == LogLevel::Warn
+    {
+      self.messages.lock().push(String::from(log.message()));
+    }
+  }
+}
+
+#[derive(Clone)]
+struct StuckRecorder {
+  phases: ArcShared<SpinSyncMutex<Vec<SingletonStuckPhase>>>,
+}
+
+impl EventStreamSubscriber for StuckRecorder {
+  fn on_event(&mut self, event: &EventStreamEvent) {
+    if let EventStreamEvent::Extension { name, payload } = event
+      && name == "cluster"
+      && let Some(ClusterEvent::SingletonHandOverStuck { phase, .. }) = payload.payload().downcast_ref::<ClusterEvent>()
+    {
+      self.phases.lock().push(*phase);
+    }
+  }
+}
+
+struct RefusingLease {
+  refusals: u32,
+  attempts: ArcShared<AtomicU32>,
+  releases: ArcShared<AtomicU32>,
+}
+
+impl SingletonLease for RefusingLease {
+  fn acquire(&mut self) -> Result<bool, LeaseError> {
+    let attempt = self.attempts.fetch_add(1, Ordering::AcqRel);
+    Ok(attempt >= self.refusals)
+  }
+
+  fn release(&mut self) {
+    self.releases.fetch_add(1, Ordering::AcqRel);
+  }
+}
+
+struct RevocableLease {
+  settings:     LeaseSettings,
+  available:    ArcShared<AtomicBool>,
+  backend_down: ArcShared<AtomicBool>,
+  held:         bool,
+}
+
+impl Lease for RevocableLease {
+  fn settings(&self) -> &LeaseSettings {
+    &self.settings
+  }
+
+  fn acquire(&mut self, _on_lost: Option<LeaseLostCallback>) -> Result<bool, LeaseError> {
+    if self.backend_down.load(Ordering::Acquire) {
+      return Err(LeaseError::Unavailable { reason: String::from("backend down") });
+    }
+    self.held = self.available.load(Ordering::Acquire);
+    Ok(self.held)
+  }
+
+  fn renew(&mut self) -> Result<(), LeaseError> {
+    self.held = self.held && self.available.load(Ordering::Acquire);
+    if self.held { Ok(()) } else { Err(LeaseError::Lost { reason: String::from("revoked") }) }
+  }
+
+  fn release(&mut self) -> Result<bool, LeaseError> {
+    Ok(core::mem::replace(&mut self.held, false))
+  }
+
+  fn check_lease(&self) -> bool {
+    self.held
+  }
+}
+
+fn new_system() -> ActorSystem {
+  let props = Props::from_fn(|| GuardianActor);
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  // 再試行タイマーはスケジューラスレッドから再設定されるため、インライン実行しない dispatcher を使う
+  let executor = ExecutorShared::new(Box::new(ThreadedExecutor::new()), TrampolineState::new());
+  let dispatcher: Box<dyn MessageDispatcherFactory> =
+    Box::new(DefaultDispatcherFactory::new(&DispatcherConfig::with_defaults(DEFAULT_DISPATCHER_ID), executor));
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(scheduler)
+    .with_dispatcher_factory(DEFAULT_DISPATCHER_ID, ArcShared::new(dispatcher));
+  ActorSystem::create_from_props(&props, config).expect("system should build")
+}
+
+fn new_log() -> Log {
+  ArcShared::new(SpinSyncMutex::new(Vec::new()))
+}
+
+fn singleton_props(label: &'static str, log: &Log) -> Props {
+  let log = log.clone();
+  Props::from_fn(move || SingletonActor { label, log: log.clone() })
+}
+
+fn fast_config() -> ClusterSingletonManagerConfig {
+  ClusterSingletonManagerConfig::new()
+    .with_hand_over_retry_interval(Duration::from_millis(10))
+    .with_min_hand_over_retries(1)
+}
+
+fn spawn_manager(
+  system: &ActorSystem,
+  name: &str,
+  create: impl Fn() -> ClusterSingletonManager + Send + Sync + 'static,
+) -> ActorRef {
+  let props = Props::from_fn(create).with_name(name);
+  system.extended().spawn_system_actor(&props).expect("spawn manager").into_actor_ref()
+}
+
+fn spawn_silent(system: &ActorSystem, name: &str) -> ActorRef {
+  let props = Props::from_fn(|| SilentActor).with_name(name);
+  system.extended().spawn_system_actor(&props).expect("spawn silent actor").into_actor_ref()
+}
+
+fn member(authority: &str, join_version: u64) -> NodeRecord {
+  NodeRecord::new(
+    String::from(authority),
+    String::from(authority),
+    NodeStatus::Up,
+    MembershipVersion::new(join_version),
+    String::from("1.0.0"),
+    Vec::new(),
+  )
+}
+
+fn up(authority: &str, join_version: u64, manager: &ActorRef) -> ClusterSingletonMemberEvent {
+  ClusterSingletonMemberEvent::Up { member: Box::new(member(authority, join_version)), manager: manager.clone() }
+}
+
+fn broadcast(managers: &[&ActorRef], event: &ClusterSingletonMemberEvent) {
+  for manager in managers {
+    (*manager).clone().tell(AnyMessage::new(event.clone()));
+  }
+}
+
+fn wait_until(description: &str, condition: impl Fn() -> bool) {
+  let deadline = Instant::now() + Duration::from_secs(3);
+  while !condition() {
+    assert!(Instant::now() < deadline, "{description}");
+    thread::yield_now();
+  }
+}
+
+fn entries(log: &Log) -> Vec<String> {
+  log.lock().clone()
+}
+
+#[test]
+fn only_member_starts_the_singleton() {
+  let system = new_system();
+  let log = new_log();
+  let singleton = singleton_props("a", &log);
+  let manager =
+    spawn_manager(&system, "manager-a", move || ClusterSingletonManager::new(singleton.clone(), fast_config(), "a:1"));
+
+  broadcast(&[&manager], &up("a:1", 1, &manager));
+
+  wait_until("singleton did not start", || entries(&log).len() == 1);
+  assert_eq!(entries(&log), [String::from("a:started")]);
+}
+
+#[test]
+fn younger_member_does_not_start_the_singleton() {
+  let system = new_system();
+  let log = new_log();
+  let silent = spawn_silent(&system, "manager-a");
+  let singleton = singleton_props("b", &log);
+  let manager_b =
+    spawn_manager(&system, "manager-b", move || ClusterSingletonManager::new(singleton.clone(), fast_config(), "b:1"));
+
+  broadcast(&[&manager_b], &up("a:1", 1, &silent));
+  broadcast(&[&manager_b], &up("b:1", 2, &manager_b));
+
+  thread::sleep(Duration::from_millis(50));
+  assert!(entries(&log).is_empty());
+}
+
+#[test]
+fn exiting_oldest_hands_over_to_the_next_oldest() {
+  let system = new_system();
+  let log = new_log();
+  let singleton_a = singleton_props("a", &log);
+  let singleton_b = singleton_props("b", &log);
+  let manager_a = spawn_manager(&system, "manager-a", move || {
+    ClusterSingletonManager::new(singleton_a.clone(), fast_config(), "a:1")
+      .with_termination_message(AnyMessage::new(StopSingleton))
+  });
+  let manager_b = spawn_manager(&system, "manager-b", move || {
+    ClusterSingletonManager::new(singleton_b.clone(), fast_config(), "b:1")
+      .with_termination_message(AnyMessage::new(StopSingleton))
+  });
+  let up_a = up("a:1", 1, &manager_a);
+  let up_b = up("b:1", 2, &manager_b);
+  broadcast(&[&manager_a, &manager_b], &up_a);
+  broadcast(&[&manager_a, &manager_b], &up_b);
+  wait_until("singleton did not start on the oldest", || entries(&log).len() == 1);
+
+  let exiting = ClusterSingletonMemberEvent::Exiting { authority: String::from("a:1") };
+  broadcast(&[&manager_a, &manager_b], &exiting);
+
+  wait_until("singleton was not handed over", || entries(&log).len() == 3);
+  assert_eq!(entries(&log), [String::from("a:started"), String::from("a:stopped"), String::from("b:started")]);
+}
+
+#[test]
+fn unanswered_hand_over_is_reported_as_stuck_and_taken_over() {
+  let system = new_system();
+  let recorder = StuckRecorder { phases: ArcShared::new(SpinSyncMutex::new(Vec::new())) };
+  let _subscription: EventStreamSubscription = system.event_stream().subscribe(&subscriber_handle(recorder.clone()));
+  let log = new_log();
+  let silent = spawn_silent(&system, "manager-a");
+  let singleton = singleton_props("b", &log);
+  let manager_b =
+    spawn_manager(&system, "manager-b", move || ClusterSingletonManager::new(singleton.clone(), fast_config(), "b:1"));
+  broadcast(&[&manager_b], &up("a:1", 1, &silent));
+  broadcast(&[&manager_b], &up("b:1", 2, &manager_b));
+
+  broadcast(&[&manager_b], &ClusterSingletonMemberEvent::Exiting { authority: String::from("a:1") });
+
+  wait_until("stuck hand-over was not taken over", || entries(&log).len() == 1);
+  thread::sleep(Duration::from_millis(50));
+  assert_eq!(recorder.phases.lock().as_slice(), [SingletonStuckPhase::BecomingOldest]);
+  assert_eq!(entries(&log), [String::from("b:started")]);
+}
+
+#[test]
+fn leaving_oldest_stops_its_singleton_when_no_successor_takes_over() {
+  let system = new_system();
+  let recorder = StuckRecorder { phases: ArcShared::new(SpinSyncMutex::new(Vec::new())) };
+  let _subscription: EventStreamSubscription = system.event_stream().subscribe(&subscriber_handle(recorder.clone()));
+  let log = new_log();
+  let singleton = singleton_props("a", &log);
+  let manager_a = spawn_manager(&system, "manager-a", move || {
+    ClusterSingletonManager::new(singleton.clone(), fast_config(), "a:1")
+      .with_termination_message(AnyMessage::new(StopSingleton))
+  });
+  let silent = spawn_silent(&system, "manager-b");
+  broadcast(&[&manager_a], &up("a:1", 1, &manager_a));
+  broadcast(&[&manager_a], &up("b:1", 2, &silent));
+  wait_until("singleton did not start on the oldest", || entries(&log).len() == 1);
+
+  broadcast(&[&manager_a], &ClusterSingletonMemberEvent::Exiting { authority: String::from("a:1") });
+
+  wait_until("singleton was not stopped after giving up", || entries(&log).len() == 2);
+  assert_eq!(recorder.phases.lock().as_slice(), [SingletonStuckPhase::HandingOver]);
+  assert_eq!(entries(&log), [String::from("a:started"), String::from("a:stopped")]);
+}
+
+#[test]
+fn singleton_starts_only_after_the_lease_is_acquired() {
+  let system = new_system();
+  let log = new_log();
+  let attempts = ArcShared::new(AtomicU32::new(0));
+  let releases = ArcShared::new(AtomicU32::new(0));
+  let singleton = singleton_props("a", &log);
+  let lease_config = LeaseUsageConfig::new("test-lease", Duration::from_millis(10));
+  let manager = {
+    let attempts = attempts.clone();
+    let releases = releases.clone();
+    spawn_manager(&system, "manager-a", move || {
+      let lease = RefusingLease { refusals: 2, attempts: attempts.clone(), releases: releases.clone() };
+      ClusterSingletonManager::new(singleton.clone(), fast_config().with_lease_config(lease_config.clone()), "a:1")
+        .with_lease(Box::new(lease))
+    })
+  };
+
+  broadcast(&[&manager], &up("a:1", 1, &manager));
+
+  wait_until("singleton did not start after the lease", || entries(&log).len() == 1);
+  assert_eq!(attempts.load(Ordering::Acquire), 3);
+
+  broadcast(&[&manager], &ClusterSingletonMemberEvent::Removed { authority: String::from("a:1") });
+  wait_until("lease was not released", || releases.load(Ordering::Acquire) == 1);
+}
+
+#[test]
+fn lost_lease_stops_the_singleton_until_the_lease_is_reacquired() {
+  let system = new_system();
+  let log = new_log();
+  let available = ArcShared::new(AtomicBool::new(true));
+  let singleton = singleton_props("a", &log);
+  let lease_config = LeaseUsageConfig::new("revocable", Duration::from_millis(10));
+  let provider = {
+    let available = available.clone();
+    LeaseProvider::new().with_implementation("revocable", move |settings| {
+      let backend_down = ArcShared::new(AtomicBool::new(false));
+      Box::new(RevocableLease { settings, available: available.clone(), backend_down, held: false }) as Box<dyn Lease>
+    })
+  };
+  let manager = spawn_manager(&system, "manager-a", move || {
+    ClusterSingletonManager::new(singleton.clone(), fast_config().with_lease_config(lease_config.clone()), "a:1")
+      .with_termination_message(AnyMessage::new(StopSingleton))
+      .with_lease_provider(&provider)
+      .expect("lease implementation should be registered")
+  });
+  broadcast(&[&manager], &up("a:1", 1, &manager));
+  wait_until("singleton did not start with the lease", || entries(&log).len() == 1);
+
+  available.store(false, Ordering::Release);
+  wait_until("singleton was not stopped after the lease was lost", || entries(&log).len() == 2);
+  thread::sleep(Duration::from_millis(50));
+  assert_eq!(entries(&log).len(), 2);
+
+  available.store(true, Ordering::Release);
+  wait_until("singleton did not restart after the lease was reacquired", || entries(&log).len() == 3);
+  assert_eq!(entries(&log), [String::from("a:started"), String::from("a:stopped"), String::from("a:started")]);
+}
+
+#[test]
+fn unavailable_lease_backend_is_logged_and_retried() {
+  let system = new_system();
+  let log = new_log();
+  let recorder = WarningRecorder { messages: new_log() };
+  let _subscription: EventStreamSubscription = system.event_stream().subscribe(&subscriber_handle(recorder.clone()));
+  let backend_down = ArcShared::new(AtomicBool::new(true));
+  let singleton = singleton_props("a", &log);
+  let lease_config = LeaseUsageConfig::new("flaky", Duration::from_millis(10));
+  let provider = {
+    let backend_down = backend_down.clone();
+    LeaseProvider::new().with_implementation("flaky", move |settings| {
+      let available = ArcShared::new(AtomicBool::new(true));
+      Box::new(RevocableLease { settings, available, backend_down: backend_down.clone(), held: false })
+        as Box<dyn Lease>
+    })
+  };
+  let manager = spawn_manager(&system, "manager-a", move || {
+    ClusterSingletonManager::new(singleton.clone(), fast_config().with_lease_config(lease_config.clone()), "a:1")
+      .with_lease_provider(&provider)
+      .expect("lease implementation should be registered")
+  });
+
+  broadcast(&[&manager], &up("a:1", 1, &manager));
+
+  wait_until("lease backend failure was not logged", || {
+    recorder.messages.lock().iter().any(|message| message.contains("lease backend unavailable: backend down"))
+  });
+  assert!(entries(&log).is_empty());
+  backend_down.store(false, Ordering::Release);
+  wait_until("singleton did not start once the lease backend recovered", || entries(&log).len() == 1);
+}
+
+#[test]
+fn with_lease_provider_rejects_an_unregistered_implementation() {
+  let log = new_log();
+  let config = fast_config().with_lease_config(LeaseUsageConfig::new("missing", Duration::from_millis(10)));
+
+  let result =
+    ClusterSingletonManager::new(singleton_props("a", &log), config, "a:1").with_lease_provider(&LeaseProvider::new());
+
+  assert_eq!(result.err(), Some(LeaseError::UnknownImplementation { name: String::from("missing") }));
+}
@@ -0,0 +1,32 @@
+//! Membership changes fed to singleton managers and proxies.
+
+use alloc::{boxed::Box, string::String};
+
+use fraktor_actor_core_kernel_rs::actor::actor_ref::ActorRef;
+
+use crate::membership::NodeRecord;
+
+/// Membership change observed by a singleton manager or proxy.
+///
+/// Members are identified by their authority. A member is a singleton candidate from `Up` until
+/// it is `Exiting`; it stays reachable for the hand-over until it is `Removed`.
+#[derive(Clone)]
+pub enum ClusterSingletonMemberEvent {
+  /// A member is up, with the singleton manager running on it.
+  Up {
+    /// Record of the member, used for the age order and the role filter.
+    member:  Box<NodeRecord>,
+    /// Singleton manager of the member.
+    manager: ActorRef,
+  },
+  /// A member is leaving and must hand over the singleton.
+  Exiting {
+    /// Authority of the leaving member.
+    authority: String,
+  },
+  /// A member was removed from the cluster.
+  Removed {
+    /// Authority of the removed member.
+    authority: String,
+  },
+}
@@ -0,0 +1,155 @@
+//! Actor routing messages to the cluster singleton wherever it runs.
+
+#[cfg(test)]
+#[path = "cluster_singleton_proxy_test.rs"]
+mod tests;
+
+use alloc::{collections::VecDeque, format};
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext, Pid,
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView},
+  },
+  event::logging::LogLevel,
+};
+
+use super::{
+  ClusterSingletonMemberEvent, ClusterSingletonProxyConfig, SingletonMembers, SingletonPeerMessage, SingletonTick,
+};
+
+const IDENTIFY_TIMER: &str = "singleton-identify";
+
+/// Forwards messages to the singleton of the oldest member with the configured role.
+///
+/// The proxy learns membership through [`ClusterSingletonMemberEvent`]s and asks the singleton
+/// manager of the oldest member for the singleton at the identification interval until it is
+/// found. Messages arriving while the singleton is unknown, for example during a hand-over, are
+/// buffered up to the buffer size, dropping the oldest message when full, and forwarded with
+/// their original sender once the singleton is identified.
+pub struct ClusterSingletonProxy {
+  config:    ClusterSingletonProxyConfig,
+  members:   SingletonMembers,
+  oldest:    Option<ActorRef>,
+  singleton: Option<ActorRef>,
+  buffer:    VecDeque<AnyMessage>,
+}
+
+impl ClusterSingletonProxy {
+  /// Creates a proxy for the singleton described by `config`.
+  #[must_use]
+  pub fn new(config: ClusterSingletonProxyConfig) -> Self {
+    let members = SingletonMembers::new(config.role(), config.data_center().cloned());
+    Self { config, members, oldest: None, singleton: None, buffer: VecDeque::new() }
+  }
+
+  fn on_member_event(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    event: &ClusterSingletonMemberEvent,
+  ) -> Result<(), ActorError> {
+    self.members.apply(event);
+    let oldest = self.members.oldest().and_then(|oldest| self.members.manager(&oldest).cloned());
+    if oldest != self.oldest {
+      self.oldest = oldest;
+      self.forget_singleton(ctx);
+      self.identify(ctx)?;
+    }
+    Ok(())
+  }
+
+  fn on_identified(&mut self, ctx: &mut ActorContext<'_>, singleton: Option<&ActorRef>) {
+    let Some(singleton) = singleton else {
+      return;
+    };
+    if ctx.sender() != self.oldest.as_ref() || self.singleton.is_some() {
+      return;
+    }
+    // 停止を検知できない相手には転送せず、次の識別を待つ。
+    if ctx.watch(singleton).is_err() {
+      return;
+    }
+    // must-ignore: 取り消せなかった識別 tick は既知の singleton があるため応答ごと無視される。
+    drop(ctx.timers().cancel(IDENTIFY_TIMER));
+    let mut singleton = singleton.clone();
+    while let Some(message) = self.buffer.pop_front() {
+      singleton.tell(message);
+    }
+    self.singleton = Some(singleton);
+  }
+
+  fn identify(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    if self.oldest.is_none() {
+      // must-ignore: 取り消せなかった識別 tick は最古メンバーが居ないため何も送らずに終わる。
+      drop(ctx.timers().cancel(IDENTIFY_TIMER));
+      return Ok(());
+    }
+    self.send_identify(ctx);
+    let interval = self.config.singleton_identification_interval();
+    ctx
+      .timers()
+      .start_timer_with_fixed_delay(IDENTIFY_TIMER, AnyMessage::new(SingletonTick::Identify), interval)
+      .map_err(|error| ActorError::recoverable(format!("singleton timer {IDENTIFY_TIMER} failed: {error:?}")))
+  }
+
+  fn send_identify(&self, ctx: &ActorContext<'_>) {
+    if let Some(oldest) = &self.oldest {
+      oldest.clone().tell(AnyMessage::new(SingletonPeerMessage::Identify).with_sender(ctx.self_ref()));
+    }
+  }
+
+  fn forget_singleton(&mut self, ctx: &mut ActorContext<'_>) {
+    if let Some(singleton) = self.singleton.take()
+      && let Err(error) = ctx.unwatch(&singleton)
+    {
+      ctx.log(LogLevel::Warn, format!("failed to unwatch forgotten cluster singleton: {error:?}"));
+    }
+  }
+
+  fn deliver(&mut self, ctx: &ActorContext<'_>) {
+    let Some(message) = ctx.clone_current_message() else {
+      return;
+    };
+    match &mut self.singleton {
+      | Some(singleton) => singleton.tell(message),
+      | None => {
+        let capacity = self.config.buffer_size() as usize;
+        if capacity == 0 {
+          return;
+        }
+        if self.buffer.len() >= capacity {
+          self.buffer.pop_front();
+        }
+        self.buffer.push_back(message);
+      },
+    }
+  }
+}
+
+impl Actor for ClusterSingletonProxy {
+  fn receive(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(event) = message.downcast_ref::<ClusterSingletonMemberEvent>() {
+      return self.on_member_event(ctx, event);
+    }
+    if let Some(SingletonPeerMessage::Identified { singleton }) = message.downcast_ref::<SingletonPeerMessage>() {
+      self.on_identified(ctx, singleton.as_ref());
+      return Ok(());
+    }
+    if let Some(SingletonTick::Identify) = message.downcast_ref::<SingletonTick>() {
+      self.send_identify(ctx);
+      return Ok(());
+    }
+    self.deliver(ctx);
+    Ok(())
+  }
+
+  fn on_terminated(&mut self, ctx: &mut ActorContext<'_>, terminated: Pid) -> Result<(), ActorError> {
+    if self.singleton.as_ref().is_some_and(|singleton| singleton.pid() == terminated) {
+      self.singleton = None;
+      return self.identify(ctx);
+    }
+    Ok(())
+  }
+}
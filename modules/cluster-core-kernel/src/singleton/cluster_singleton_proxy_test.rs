@@ -0,0 +1,140 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+use fraktor_actor_adaptor_std_rs::{dispatch::dispatcher::ThreadedExecutor, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext,
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView},
+    props::Props,
+    scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  dispatch::dispatcher::{
+    DEFAULT_DISPATCHER_ID, DefaultDispatcherFactory, DispatcherConfig, ExecutorShared, MessageDispatcherFactory,
+    TrampolineState,
+  },
+  system::ActorSystem,
+};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+use super::ClusterSingletonProxy;
+use crate::{
+  membership::{MembershipVersion, NodeRecord, NodeStatus},
+  singleton::{
+    ClusterSingletonManager, ClusterSingletonManagerConfig, ClusterSingletonMemberEvent, ClusterSingletonProxyConfig,
+  },
+};
+
+type Received = ArcShared<SpinSyncMutex<Vec<u32>>>;
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+#[derive(Clone)]
+struct Ping(u32);
+
+struct SingletonActor {
+  received: Received,
+}
+
+impl Actor for SingletonActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(Ping(value)) = message.downcast_ref::<Ping>() {
+      self.received.lock().push(*value);
+    }
+    Ok(())
+  }
+}
+
+fn new_system() -> ActorSystem {
+  let props = Props::from_fn(|| GuardianActor);
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  // 識別タイマーの応答処理でタイマーを操作するため、インライン実行しない dispatcher を使う
+  let executor = ExecutorShared::new(Box::new(ThreadedExecutor::new()), TrampolineState::new());
+  let dispatcher: Box<dyn MessageDispatcherFactory> =
+    Box::new(DefaultDispatcherFactory::new(&DispatcherConfig::with_defaults(DEFAULT_DISPATCHER_ID), executor));
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(scheduler)
+    .with_dispatcher_factory(DEFAULT_DISPATCHER_ID, ArcShared::new(dispatcher));
+  ActorSystem::create_from_props(&props, config).expect("system should build")
+}
+
+fn spawn_manager(system: &ActorSystem, received: &Received) -> ActorRef {
+  let received = received.clone();
+  let singleton = Props::from_fn(move || SingletonActor { received: received.clone() });
+  let props = Props::from_fn(move || {
+    ClusterSingletonManager::new(singleton.clone(), ClusterSingletonManagerConfig::new(), "a:1")
+  })
+  .with_name("manager");
+  system.extended().spawn_system_actor(&props).expect("spawn manager").into_actor_ref()
+}
+
+fn spawn_proxy(system: &ActorSystem, buffer_size: u32) -> ActorRef {
+  let config = ClusterSingletonProxyConfig::new()
+    .with_singleton_identification_interval(Duration::from_millis(10))
+    .with_buffer_size(buffer_size);
+  let props = Props::from_fn(move || ClusterSingletonProxy::new(config.clone())).with_name("proxy");
+  system.extended().spawn_system_actor(&props).expect("spawn proxy").into_actor_ref()
+}
+
+fn member_up(manager: &ActorRef) -> AnyMessage {
+  let member = NodeRecord::new(
+    String::from("a"),
+    String::from("a:1"),
+    NodeStatus::Up,
+    MembershipVersion::new(1),
+    String::from("1.0.0"),
+    Vec::new(),
+  );
+  AnyMessage::new(ClusterSingletonMemberEvent::Up { member: Box::new(member), manager: manager.clone() })
+}
+
+fn wait_for(received: &Received, expected: &[u32]) {
+  let deadline = Instant::now() + Duration::from_secs(3);
+  while received.lock().as_slice() != expected {
+    assert!(Instant::now() < deadline, "singleton received {:?}", received.lock().as_slice());
+    thread::yield_now();
+  }
+}
+
+#[test]
+fn messages_are_buffered_until_the_singleton_is_identified() {
+  let system = new_system();
+  let received: Received = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let mut proxy = spawn_proxy(&system, 10);
+  proxy.tell(AnyMessage::new(Ping(1)));
+  proxy.tell(AnyMessage::new(Ping(2)));
+
+  let mut manager = spawn_manager(&system, &received);
+  manager.tell(member_up(&manager));
+  proxy.tell(member_up(&manager));
+  proxy.tell(AnyMessage::new(Ping(3)));
+
+  wait_for(&received, &[1, 2, 3]);
+}
+
+#[test]
+fn full_buffer_drops_the_oldest_message() {
+  let system = new_system();
+  let received: Received = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let mut proxy = spawn_proxy(&system, 1);
+  proxy.tell(AnyMessage::new(Ping(1)));
+  proxy.tell(AnyMessage::new(Ping(2)));
+
+  let mut manager = spawn_manager(&system, &received);
+  manager.tell(member_up(&manager));
+  proxy.tell(member_up(&manager));
+
+  wait_for(&received, &[2]);
+  proxy.tell(AnyMessage::new(Ping(3)));
+  wait_for(&received, &[2, 3]);
+}
@@ -0,0 +1,48 @@
+//! Lease port guarding the singleton instance.
+
+use alloc::boxed::Box;
+
+use crate::lease::{Lease, LeaseError};
+
+/// Lease the singleton manager holds while it runs the singleton.
+///
+/// The lease is owned by one manager; acquiring it again while held must succeed. A manager
+/// without a lease starts the singleton as soon as it becomes the oldest member.
+pub trait SingletonLease: Send {
+  /// Tries to acquire the lease, returning true when this manager holds it afterwards.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`LeaseError`] when the lease backend could not decide, as opposed to `Ok(false)`
+  /// when another owner holds the lease.
+  fn acquire(&mut self) -> Result<bool, LeaseError>;
+
+  /// Renews the held lease, returning false when it has been lost.
+  ///
+  /// The manager renews every lease retry interval while it runs the singleton and stops the
+  /// singleton once the lease is lost.
+  fn renew(&mut self) -> bool {
+    true
+  }
+
+  /// Releases the lease if held.
+  fn release(&mut self);
+}
+
+impl SingletonLease for Box<dyn Lease> {
+  fn acquire(&mut self) -> Result<bool, LeaseError> {
+    if self.check_lease() {
+      return Ok(true);
+    }
+    Lease::acquire(self.as_mut(), None)
+  }
+
+  fn renew(&mut self) -> bool {
+    Lease::renew(self.as_mut()).is_ok()
+  }
+
+  fn release(&mut self) {
+    // 解放に失敗してもハートビートが途絶えればリースは失効する
+    drop(Lease::release(self.as_mut()));
+  }
+}
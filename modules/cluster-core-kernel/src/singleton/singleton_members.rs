@@ -0,0 +1,88 @@
+//! Singleton candidates known to a manager or proxy.
+
+#[cfg(test)]
+#[path = "singleton_members_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  string::String,
+  vec::Vec,
+};
+
+use fraktor_actor_core_kernel_rs::actor::actor_ref::ActorRef;
+
+use super::ClusterSingletonMemberEvent;
+use crate::membership::{DataCenter, NodeRecord, oldest_member};
+
+/// Up members matching the role and data center of a singleton, with their managers.
+pub(crate) struct SingletonMembers {
+  role:        Option<String>,
+  data_center: Option<DataCenter>,
+  members:     BTreeMap<String, (NodeRecord, ActorRef)>,
+  exiting:     BTreeSet<String>,
+}
+
+impl SingletonMembers {
+  /// Creates an empty member set for singletons constrained to `role` and `data_center`.
+  pub(crate) fn new(role: Option<&str>, data_center: Option<DataCenter>) -> Self {
+    Self { role: role.map(String::from), data_center, members: BTreeMap::new(), exiting: BTreeSet::new() }
+  }
+
+  /// Applies a membership change; members outside the role or data center are ignored.
+  pub(crate) fn apply(&mut self, event: &ClusterSingletonMemberEvent) {
+    match event {
+      | ClusterSingletonMemberEvent::Up { member, manager } => {
+        if self.accepts(member) {
+          self.members.insert(member.authority.clone(), (member.as_ref().clone(), manager.clone()));
+        }
+      },
+      | ClusterSingletonMemberEvent::Exiting { authority } => {
+        if self.members.contains_key(authority) {
+          self.exiting.insert(authority.clone());
+        }
+      },
+      | ClusterSingletonMemberEvent::Removed { authority } => {
+        self.members.remove(authority);
+        self.exiting.remove(authority);
+      },
+    }
+  }
+
+  /// Returns the authority of the oldest member that is not leaving.
+  pub(crate) fn oldest(&self) -> Option<String> {
+    let candidates: Vec<NodeRecord> = self
+      .members
+      .iter()
+      .filter(|(authority, _)| !self.exiting.contains(*authority))
+      .map(|(_, (record, _))| record.clone())
+      .collect();
+    oldest_member(&candidates).map(|record| record.authority.clone())
+  }
+
+  /// Returns true when `authority` is a known member, leaving or not.
+  pub(crate) fn contains(&self, authority: &str) -> bool {
+    self.members.contains_key(authority)
+  }
+
+  /// Returns true when `authority` is leaving.
+  pub(crate) fn is_exiting(&self, authority: &str) -> bool {
+    self.exiting.contains(authority)
+  }
+
+  /// Returns the singleton manager of `authority`.
+  pub(crate) fn manager(&self, authority: &str) -> Option<&ActorRef> {
+    self.members.get(authority).map(|(_, manager)| manager)
+  }
+
+  /// Returns the authority of the member whose manager is `manager`.
+  pub(crate) fn authority_of(&self, manager: &ActorRef) -> Option<&str> {
+    self.members.iter().find(|(_, (_, candidate))| candidate == manager).map(|(authority, _)| authority.as_str())
+  }
+
+  fn accepts(&self, member: &NodeRecord) -> bool {
+    let role_matches = self.role.as_ref().is_none_or(|role| member.roles.contains(role));
+    let data_center_matches = self.data_center.as_ref().is_none_or(|data_center| &member.data_center == data_center);
+    role_matches && data_center_matches
+  }
+}
@@ -0,0 +1,90 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use fraktor_actor_core_kernel_rs::actor::actor_ref::ActorRef;
+
+use super::SingletonMembers;
+use crate::{
+  membership::{DataCenter, MembershipVersion, NodeRecord, NodeStatus},
+  singleton::ClusterSingletonMemberEvent,
+};
+
+fn record(authority: &str, join_version: u64, roles: &[&str]) -> NodeRecord {
+  NodeRecord::new(
+    String::from("node"),
+    String::from(authority),
+    NodeStatus::Up,
+    MembershipVersion::new(join_version),
+    String::from("1.0.0"),
+    roles.iter().map(|role| String::from(*role)).collect::<Vec<_>>(),
+  )
+}
+
+fn up(member: NodeRecord) -> ClusterSingletonMemberEvent {
+  ClusterSingletonMemberEvent::Up { member: Box::new(member), manager: ActorRef::null() }
+}
+
+#[test]
+fn oldest_is_the_member_that_joined_first() {
+  let mut members = SingletonMembers::new(None, None);
+
+  members.apply(&up(record("n2:4000", 2, &[])));
+  members.apply(&up(record("n1:4000", 1, &[])));
+  members.apply(&up(record("n3:4000", 3, &[])));
+
+  assert_eq!(members.oldest().as_deref(), Some("n1:4000"));
+}
+
+#[test]
+fn members_without_the_role_are_not_candidates() {
+  let mut members = SingletonMembers::new(Some("backend"), None);
+
+  members.apply(&up(record("n1:4000", 1, &["frontend"])));
+  members.apply(&up(record("n2:4000", 2, &["backend", "frontend"])));
+
+  assert!(!members.contains("n1:4000"));
+  assert_eq!(members.oldest().as_deref(), Some("n2:4000"));
+}
+
+#[test]
+fn members_outside_the_data_center_are_not_candidates() {
+  let mut members = SingletonMembers::new(None, Some(DataCenter::new("east")));
+  let mut west = record("n1:4000", 1, &[]);
+  west.data_center = DataCenter::new("west");
+  let mut east = record("n2:4000", 2, &[]);
+  east.data_center = DataCenter::new("east");
+
+  members.apply(&up(west));
+  members.apply(&up(east));
+
+  assert_eq!(members.oldest().as_deref(), Some("n2:4000"));
+}
+
+#[test]
+fn exiting_members_stay_known_but_lose_the_oldest_position() {
+  let mut members = SingletonMembers::new(None, None);
+  members.apply(&up(record("n1:4000", 1, &[])));
+  members.apply(&up(record("n2:4000", 2, &[])));
+
+  members.apply(&ClusterSingletonMemberEvent::Exiting { authority: String::from("n1:4000") });
+
+  assert!(members.contains("n1:4000"));
+  assert!(members.is_exiting("n1:4000"));
+  assert_eq!(members.oldest().as_deref(), Some("n2:4000"));
+
+  members.apply(&ClusterSingletonMemberEvent::Removed { authority: String::from("n1:4000") });
+
+  assert!(!members.contains("n1:4000"));
+  assert!(!members.is_exiting("n1:4000"));
+  assert_eq!(members.oldest().as_deref(), Some("n2:4000"));
+}
+
+#[test]
+fn exiting_unknown_members_is_ignored() {
+  let mut members = SingletonMembers::new(None, None);
+
+  members.apply(&ClusterSingletonMemberEvent::Exiting { authority: String::from("n1:4000") });
+
+  assert!(!members.is_exiting("n1:4000"));
+  assert_eq!(members.oldest(), None);
+  assert!(members.manager("n1:4000").is_none());
+}
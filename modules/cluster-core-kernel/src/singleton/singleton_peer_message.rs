@@ -0,0 +1,22 @@
+//! Messages exchanged between singleton managers and proxies.
+
+use fraktor_actor_core_kernel_rs::actor::actor_ref::ActorRef;
+
+/// Manager-to-manager hand-over protocol and the proxy identification request.
+///
+/// Every message is sent with the sending actor as sender, and answers go back to that sender.
+#[derive(Clone)]
+pub(crate) enum SingletonPeerMessage {
+  /// Sent by the new oldest to the previous oldest to request the singleton.
+  HandOverToMe,
+  /// The previous oldest is stopping the singleton.
+  HandOverInProgress,
+  /// The previous oldest stopped the singleton; the receiver may start it.
+  HandOverDone,
+  /// Sent by a leaving oldest to the next oldest to offer the singleton.
+  TakeOverFromMe,
+  /// Asks a manager for its running singleton.
+  Identify,
+  /// Answers [`Identify`](Self::Identify); `None` when the manager does not run the singleton.
+  Identified { singleton: Option<ActorRef> },
+}
@@ -0,0 +1,18 @@
+//! Timer messages singleton managers and proxies schedule for themselves.
+
+/// Retry and timeout ticks of the singleton manager and proxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SingletonTick {
+  /// Asks the previous oldest for the singleton again.
+  HandOverRetry,
+  /// Offers the singleton to the next oldest again.
+  TakeOverRetry,
+  /// Tries to acquire the lease again.
+  LeaseRetry,
+  /// Renews the held lease.
+  LeaseHeartbeat,
+  /// The removal margin after the previous oldest was removed has passed.
+  RemovalMarginElapsed,
+  /// Asks the oldest manager for the singleton location again.
+  Identify,
+}
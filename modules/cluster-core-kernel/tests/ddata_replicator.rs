@@ -0,0 +1,287 @@
+//! Replicator convergence across several actor systems.
+
+use core::{any::Any, time::Duration};
+use std::{thread, time::Instant};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext,
+    actor_ref::ActorRef,
+    error::ActorError,
+    extension::ExtensionInstallers,
+    messaging::{AnyMessage, AnyMessageView},
+    props::Props,
+    scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  system::ActorSystem,
+};
+use fraktor_cluster_core_kernel_rs::ddata::{
+  GCounter, GCounterKey, Get, GetResponse, ORSet, ORSetKey, ReadConsistency, RemovedNodePruning, ReplicatorCommand,
+  ReplicatorExtension, ReplicatorExtensionInstaller, ReplicatorSettings, SelfUniqueAddress, Subscribe,
+  SubscribeResponse, Update, UpdateResponse, WriteConsistency,
+};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+type Received = ArcShared<SpinSyncMutex<Vec<Box<dyn Any + Send + Sync>>>>;
+
+type Capture = fn(&AnyMessageView<'_>) -> Option<Box<dyn Any + Send + Sync>>;
+
+const CAPTURES: &[Capture] = &[
+  capture::<GetResponse<GCounter>>,
+  capture::<GetResponse<ORSet<String>>>,
+  capture::<UpdateResponse<GCounter>>,
+  capture::<UpdateResponse<ORSet<String>>>,
+  capture::<SubscribeResponse<GCounter>>,
+];
+
+fn capture<T: Clone + Send + Sync + 'static>(message: &AnyMessageView<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+  message.downcast_ref::<T>().map(|message| Box::new(message.clone()) as Box<dyn Any + Send + Sync>)
+}
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+struct ProbeActor {
+  received: Received,
+}
+
+impl Actor for ProbeActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(captured) = CAPTURES.iter().find_map(|capture| capture(&message)) {
+      self.received.lock().push(captured);
+    }
+    Ok(())
+  }
+}
+
+struct Probe {
+  actor_ref: ActorRef,
+  received:  Received,
+}
+
+impl Probe {
+  fn expect<T: Clone + 'static>(&self) -> T {
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+      {
+        let mut received = self.received.lock();
+        if let Some(index) = received.iter().position(|message| message.is::<T>()) {
+          let message = received.remove(index);
+          return message.downcast_ref::<T>().cloned().expect("message type was checked");
+        }
+      }
+      assert!(Instant::now() < deadline, "expected message did not arrive");
+      thread::yield_now();
+    }
+  }
+}
+
+struct Node {
+  _system:    ActorSystem,
+  address:    SelfUniqueAddress,
+  replicator: ActorRef,
+  probe:      Probe,
+}
+
+impl Node {
+  fn start(host: &str, uid: u64, settings: ReplicatorSettings) -> Self {
+    let address = SelfUniqueAddress::new(UniqueAddress::new(Address::new("sys", host, 2552), uid));
+    let installer = ReplicatorExtensionInstaller::new(address.clone()).with_settings(settings);
+    let config = ActorSystemConfig::new(TestTickDriver::default())
+      .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+      .with_extension_installers(ExtensionInstallers::default().with_extension_installer(installer));
+    let system =
+      ActorSystem::create_from_props(&Props::from_fn(|| GuardianActor), config).expect("system should build");
+    let replicator =
+      system.extended().extension_by_type::<ReplicatorExtension>().expect("replicator is installed").replicator();
+    let received = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+    let props = {
+      let received = received.clone();
+      Props::from_fn(move || ProbeActor { received: received.clone() }).with_name("probe")
+    };
+    let actor_ref = system.extended().spawn_system_actor(&props).expect("spawn probe").into_actor_ref();
+    Self { _system: system, address, replicator, probe: Probe { actor_ref, received } }
+  }
+
+  fn tell(&self, command: ReplicatorCommand) {
+    self.replicator.clone().tell(AnyMessage::new(command));
+  }
+
+  fn unique_address(&self) -> UniqueAddress {
+    self.address.unique_address().clone()
+  }
+
+  fn increment(&self, amount: u64, consistency: WriteConsistency) -> UpdateResponse<GCounter> {
+    let address = self.address.clone();
+    let update = Update::<GCounter>::new(GCounterKey::new("counter"), consistency);
+    self.tell(ReplicatorCommand::update(
+      update,
+      move |current: Option<&GCounter>| {
+        current.cloned().unwrap_or_default().increment(&address, amount).map_err(|error| format!("{error:?}"))
+      },
+      self.probe.actor_ref.clone(),
+    ));
+    self.probe.expect::<UpdateResponse<GCounter>>()
+  }
+
+  fn read_counter(&self, consistency: ReadConsistency) -> GetResponse<GCounter> {
+    let get = Get::<GCounter>::new(GCounterKey::new("counter"), consistency);
+    self.tell(ReplicatorCommand::get(get, self.probe.actor_ref.clone()));
+    self.probe.expect::<GetResponse<GCounter>>()
+  }
+
+  fn local_counter(&self) -> Option<u128> {
+    self.read_counter(ReadConsistency::Local).data().map(|counter| counter.value().expect("value fits"))
+  }
+}
+
+fn settings() -> ReplicatorSettings {
+  ReplicatorSettings::new()
+    .with_gossip_interval(Duration::from_millis(50))
+    .with_notify_subscribers_interval(Duration::from_millis(20))
+    .with_delta_propagation_interval(Duration::from_millis(20))
+    .with_pruning_interval(Duration::from_millis(50))
+    .with_max_pruning_dissemination(Duration::from_millis(200))
+}
+
+fn connect(nodes: &[&Node]) {
+  for node in nodes {
+    for peer in nodes {
+      node.tell(ReplicatorCommand::member_up(peer.unique_address(), peer.replicator.clone()));
+    }
+  }
+}
+
+fn eventually(mut predicate: impl FnMut() -> bool) {
+  let deadline = Instant::now() + TIMEOUT;
+  while !predicate() {
+    assert!(Instant::now() < deadline, "condition was not reached in time");
+    thread::sleep(Duration::from_millis(10));
+  }
+}
+
+#[test]
+fn local_updates_reach_other_replicas_through_deltas() {
+  let a = Node::start("node-a", 1, settings());
+  let b = Node::start("node-b", 2, settings());
+  connect(&[&a, &b]);
+
+  assert!(a.increment(2, WriteConsistency::Local).is_locally_applied());
+  assert!(b.increment(3, WriteConsistency::Local).is_locally_applied());
+
+  eventually(|| a.local_counter() == Some(5) && b.local_counter() == Some(5));
+}
+
+#[test]
+fn full_state_gossip_converges_without_deltas() {
+  let settings = settings().with_delta_crdt_enabled(false);
+  let a = Node::start("node-a", 1, settings.clone());
+  let b = Node::start("node-b", 2, settings);
+  connect(&[&a, &b]);
+
+  a.increment(4, WriteConsistency::Local);
+
+  eventually(|| b.local_counter() == Some(4));
+}
+
+#[test]
+fn majority_writes_are_visible_to_majority_reads() {
+  let settings = settings().with_gossip_interval(Duration::from_secs(60)).with_delta_crdt_enabled(false);
+  let a = Node::start("node-a", 1, settings.clone());
+  let b = Node::start("node-b", 2, settings.clone());
+  let c = Node::start("node-c", 3, settings);
+  connect(&[&a, &b, &c]);
+
+  let written = a.increment(7, WriteConsistency::Majority { timeout: TIMEOUT, min_cap: 0 });
+  assert!(matches!(written, UpdateResponse::Success { .. }));
+
+  let read = c.read_counter(ReadConsistency::Majority { timeout: TIMEOUT, min_cap: 0 });
+  assert_eq!(read.data().map(|counter| counter.value().expect("value fits")), Some(7));
+  // 読み取りで得た値はローカルにも反映される。
+  assert_eq!(c.local_counter(), Some(7));
+}
+
+#[test]
+fn writes_time_out_without_enough_replicas() {
+  let a = Node::start("node-a", 1, settings());
+  let b = Node::start("node-b", 2, settings());
+  a.tell(ReplicatorCommand::member_up(b.unique_address(), b.probe.actor_ref.clone()));
+
+  let written = a.increment(1, WriteConsistency::All { timeout: Duration::from_millis(100) });
+
+  assert!(matches!(written, UpdateResponse::Timeout { .. }));
+  assert_eq!(a.local_counter(), Some(1));
+}
+
+#[test]
+fn subscribers_are_notified_of_remote_changes() {
+  let a = Node::start("node-a", 1, settings());
+  let b = Node::start("node-b", 2, settings());
+  connect(&[&a, &b]);
+
+  b.tell(ReplicatorCommand::subscribe(Subscribe::new(GCounterKey::new("counter"), b.probe.actor_ref.clone())));
+  a.increment(6, WriteConsistency::Local);
+
+  let notification = b.probe.expect::<SubscribeResponse<GCounter>>();
+  assert_eq!(notification.data().map(|counter| counter.value().expect("value fits")), Some(6));
+}
+
+#[test]
+fn causal_deltas_converge_for_or_sets() {
+  let a = Node::start("node-a", 1, settings());
+  let b = Node::start("node-b", 2, settings());
+  connect(&[&a, &b]);
+
+  for element in ["x", "y", "z"] {
+    let address = a.address.clone();
+    let update = Update::<ORSet<String>>::new(ORSetKey::new("set"), WriteConsistency::Local);
+    a.tell(ReplicatorCommand::update(
+      update,
+      move |current: Option<&ORSet<String>>| {
+        Ok(current.cloned().unwrap_or_default().add(&address, String::from(element)))
+      },
+      a.probe.actor_ref.clone(),
+    ));
+    a.probe.expect::<UpdateResponse<ORSet<String>>>();
+  }
+
+  eventually(|| {
+    let get = Get::<ORSet<String>>::new(ORSetKey::new("set"), ReadConsistency::Local);
+    b.tell(ReplicatorCommand::get(get, b.probe.actor_ref.clone()));
+    b.probe.expect::<GetResponse<ORSet<String>>>().data().is_some_and(|set| set.elements().len() == 3)
+  });
+}
+
+#[test]
+fn removed_members_are_pruned_into_the_leader() {
+  let a = Node::start("node-a", 1, settings());
+  let b = Node::start("node-b", 2, settings());
+  let c = Node::start("node-c", 3, settings());
+  connect(&[&a, &b, &c]);
+
+  a.increment(1, WriteConsistency::Local);
+  c.increment(2, WriteConsistency::Local);
+  eventually(|| a.local_counter() == Some(3) && b.local_counter() == Some(3));
+
+  let removed = c.unique_address();
+  a.tell(ReplicatorCommand::member_removed(removed.clone()));
+  b.tell(ReplicatorCommand::member_removed(removed.clone()));
+
+  let pruned = |node: &Node| {
+    node
+      .read_counter(ReadConsistency::Local)
+      .data()
+      .is_some_and(|counter| !counter.modified_by_nodes().contains(&removed) && counter.value() == Ok(3))
+  };
+  eventually(|| pruned(&a) && pruned(&b));
+}
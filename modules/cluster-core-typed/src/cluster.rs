@@ -368,6 +368,10 @@ impl Cluster {
     self.inner.unsubscribe(subscription_id);
   }
 
+  pub(crate) fn self_authority(&self) -> String {
+    self.inner.self_authority()
+  }
+
   pub(crate) fn join(&self, authority: &str) -> Result<(), ClusterError> {
     self.inner.join(authority)
   }
@@ -0,0 +1,513 @@
+//! Receptionist replicating service registrations across cluster members.
+
+#[cfg(test)]
+#[path = "cluster_receptionist_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  format,
+  string::String,
+  vec::Vec,
+};
+use core::any::TypeId;
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{Pid, actor_ref::ActorRef, error::ActorError},
+  event::logging::LogLevel,
+};
+use fraktor_actor_core_typed_rs::{
+  Behavior, TypedActorRef,
+  actor::TypedActorContext,
+  dsl::Behaviors,
+  message_and_signals::BehaviorSignal,
+  receptionist::{Deregistered, Listing, ReceptionistCommand, ReceptionistSetup, Registered},
+};
+use fraktor_cluster_core_kernel_rs::{
+  ddata::{ORMultiMap, ORMultiMapKey, SelfUniqueAddress, SubscribeResponse, Update, UpdateResponse, WriteConsistency},
+  extension::ClusterSubscriptionInitialStateMode,
+  membership::NodeStatus,
+  topology::{ClusterEvent, ClusterEventType},
+};
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedLock};
+
+use crate::{
+  Cluster, ClusterReceptionistConfig, DistributedData, ReplicatorMessageAdapter,
+  cluster_receptionist_entry::ClusterReceptionistEntry, cluster_receptionist_message::ClusterReceptionistMessage,
+};
+
+/// Identifier of the replicated registry.
+const REGISTRY_KEY_ID: &str = "ReceptionistKey";
+
+/// Service key as stored in the replicated registry: service id and message type.
+type RegistryKey = (String, TypeId);
+
+/// Replicated registrations of all members, keyed by service key.
+pub(crate) type ServiceRegistry = ORMultiMap<RegistryKey, ClusterReceptionistEntry>;
+
+/// Factory for the receptionist behavior of clustered actor systems.
+///
+/// This is the fraktor equivalent of Pekko's `ClusterReceptionist`. Install it with
+/// [`ClusterReceptionist::setup`] so that
+/// [`TypedActorSystem::receptionist`](fraktor_actor_core_typed_rs::TypedActorSystem::receptionist)
+/// answers `Find` and `Subscribe` with the actors registered on every member. Registrations are
+/// replicated through the distributed-data Replicator, so both the cluster and the Replicator
+/// extensions must be installed.
+///
+/// Registrations of members that are removed or downed are dropped from the registry.
+/// Registrations of unreachable members are left out of [`Listing::service_instances`] but kept
+/// in [`Listing::all_service_instances`]; subscribers are notified of such reachability changes
+/// with [`Listing::services_were_added_or_removed`] set to `false`.
+pub struct ClusterReceptionist;
+
+impl ClusterReceptionist {
+  /// Creates the setup installing the cluster receptionist with default configuration.
+  #[must_use]
+  pub fn setup() -> ReceptionistSetup {
+    Self::setup_with_config(ClusterReceptionistConfig::new())
+  }
+
+  /// Creates the setup installing the cluster receptionist with the given configuration.
+  #[must_use]
+  pub fn setup_with_config(config: ClusterReceptionistConfig) -> ReceptionistSetup {
+    ReceptionistSetup::new(move |_system| Self::behavior_with_config(config.clone()))
+  }
+
+  /// Creates the receptionist behavior with default configuration.
+  #[must_use]
+  pub fn behavior() -> Behavior<ReceptionistCommand> {
+    Self::behavior_with_config(ClusterReceptionistConfig::new())
+  }
+
+  /// Creates the receptionist behavior with the given configuration.
+  #[must_use]
+  pub fn behavior_with_config(config: ClusterReceptionistConfig) -> Behavior<ReceptionistCommand> {
+    Behaviors::setup(move |ctx| {
+      // レプリケーターやクラスタイベントのアダプターを使うため、状態は専用の子アクターが持つ
+      let core = match ctx.spawn_anonymous(&core_behavior(config.clone())) {
+        | Ok(core) => core.actor_ref(),
+        | Err(error) => {
+          let message = format!("ClusterReceptionist failed to spawn its core actor: {error:?}");
+          ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+          return Behaviors::stopped();
+        },
+      };
+      if let Err(error) = ctx.watch(&core) {
+        let message = format!("ClusterReceptionist failed to watch its core actor: {error:?}");
+        ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+        return Behaviors::stopped();
+      }
+      Behaviors::receive_message(move |_ctx, command: &ReceptionistCommand| {
+        let mut core = core.clone();
+        core.tell(ClusterReceptionistMessage::Command(command.clone()));
+        Ok(Behaviors::same())
+      })
+      .receive_signal(|_ctx, signal| match signal {
+        | BehaviorSignal::Terminated(_) => Ok(Behaviors::stopped()),
+        | _ => Ok(Behaviors::same()),
+      })
+    })
+  }
+}
+
+fn registry_key() -> ORMultiMapKey<RegistryKey, ClusterReceptionistEntry> {
+  ORMultiMapKey::new(REGISTRY_KEY_ID)
+}
+
+fn core_behavior(config: ClusterReceptionistConfig) -> Behavior<ClusterReceptionistMessage> {
+  Behaviors::setup(move |ctx| {
+    let system = ctx.system();
+    let (distributed_data, cluster) = match (DistributedData::get(&system), Cluster::get(&system)) {
+      | (Ok(distributed_data), Ok(cluster)) => (distributed_data, cluster),
+      | (distributed_data, cluster) => {
+        let message = format!(
+          "ClusterReceptionist requires the Replicator and cluster extensions: replicator={:?}, cluster={:?}",
+          distributed_data.err(),
+          cluster.err()
+        );
+        system.emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+        return Behaviors::stopped();
+      },
+    };
+    let adapter =
+      distributed_data.message_adapter::<ClusterReceptionistMessage, ServiceRegistry>(config.unexpected_ask_timeout());
+    if let Err(error) = adapter.subscribe(ctx, registry_key(), ClusterReceptionistMessage::RegistryChanged) {
+      let message = format!("ClusterReceptionist failed to subscribe to the registry: {error:?}");
+      system.emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+      return Behaviors::stopped();
+    }
+    let cluster_events =
+      match ctx.message_adapter(|event: ClusterEvent| Ok(ClusterReceptionistMessage::ClusterEvent(event))) {
+        | Ok(cluster_events) => cluster_events,
+        | Err(error) => {
+          let message = format!("ClusterReceptionist failed to create cluster event adapter: {error:?}");
+          system.emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+          return Behaviors::stopped();
+        },
+      };
+    let subscription = cluster.subscribe(&cluster_events, ClusterSubscriptionInitialStateMode::AsSnapshot, &[
+      ClusterEventType::MemberStatusChanged,
+      ClusterEventType::UnreachableMember,
+      ClusterEventType::ReachableMember,
+    ]);
+
+    let state = SharedLock::new_with_driver::<DefaultMutex<_>>(ClusterReceptionistState::new(cluster.self_authority()));
+    let state_for_signal = state.clone();
+    let registry = RegistryWriter {
+      adapter,
+      self_address: distributed_data.self_unique_address().clone(),
+      write_consistency: config.write_consistency(),
+    };
+    let registry_for_signal = registry.clone();
+
+    Behaviors::receive_message(move |ctx, message: &ClusterReceptionistMessage| {
+      if let ClusterReceptionistMessage::RegistryUpdated(response) = message
+        && !matches!(response, UpdateResponse::Success { .. })
+      {
+        let message = format!("ClusterReceptionist failed to update the registry: {response:?}");
+        ctx.system().emit_log(LogLevel::Warn, message, Some(ctx.pid()), None);
+      }
+      // ロック保持中に遅延アクションを収集し、ロック解放後に実行する
+      let deferred = state.with_lock(|state| {
+        let mut deferred = Vec::new();
+        collect_deferred_for_message(state, message, &mut deferred);
+        deferred
+      });
+      execute_deferred(ctx, deferred, &registry)?;
+      Ok(Behaviors::same())
+    })
+    .receive_signal(move |ctx, signal| {
+      match signal {
+        | BehaviorSignal::Terminated(terminated) => {
+          let deferred = state_for_signal.with_lock(|state| {
+            let mut deferred = Vec::new();
+            collect_terminated(state, terminated.pid(), &mut deferred);
+            deferred
+          });
+          execute_deferred(ctx, deferred, &registry_for_signal)?;
+        },
+        | BehaviorSignal::PostStop => {
+          cluster.unsubscribe(subscription.id());
+          registry_for_signal.adapter.unsubscribe(&registry_key());
+        },
+        | _ => {},
+      }
+      Ok(Behaviors::same())
+    })
+  })
+}
+
+/// Writes registry changes of this member through the Replicator.
+#[derive(Clone)]
+struct RegistryWriter {
+  adapter:           ReplicatorMessageAdapter<ClusterReceptionistMessage, ServiceRegistry>,
+  self_address:      SelfUniqueAddress,
+  write_consistency: WriteConsistency,
+}
+
+impl RegistryWriter {
+  fn update(
+    &self,
+    ctx: &mut TypedActorContext<'_, ClusterReceptionistMessage>,
+    ops: Vec<RegistryOp>,
+  ) -> Result<(), ActorError> {
+    let self_address = self.self_address.clone();
+    let update = Update::new(registry_key(), self.write_consistency);
+    self
+      .adapter
+      .ask_update(
+        ctx,
+        update,
+        move |current: Option<&ServiceRegistry>| Ok(apply_registry_ops(current, &self_address, &ops)),
+        ClusterReceptionistMessage::RegistryUpdated,
+      )
+      .map_err(|error| ActorError::recoverable(format!("receptionist registry update failed: {error:?}")))
+  }
+}
+
+fn apply_registry_ops(
+  current: Option<&ServiceRegistry>,
+  self_address: &SelfUniqueAddress,
+  ops: &[RegistryOp],
+) -> ServiceRegistry {
+  let mut registry = current.cloned().unwrap_or_default();
+  for op in ops {
+    registry = match op {
+      | RegistryOp::Add(key, entry) => registry.add_binding(self_address, key.clone(), entry.clone()),
+      | RegistryOp::Remove(key, entry) => registry.remove_binding(self_address, key, entry),
+    };
+  }
+  registry
+}
+
+fn execute_deferred(
+  ctx: &mut TypedActorContext<'_, ClusterReceptionistMessage>,
+  deferred: Vec<DeferredAction>,
+  registry: &RegistryWriter,
+) -> Result<(), ActorError> {
+  for action in deferred {
+    match action {
+      | DeferredAction::WatchRegistered(actor_ref) => ctx
+        .as_untyped_mut()
+        .watch(&actor_ref)
+        .map_err(|error| ActorError::recoverable(format!("watch failed: {error:?}")))?,
+      | DeferredAction::WatchSubscriber(subscriber) => {
+        ctx.watch(&subscriber).map_err(|error| ActorError::recoverable(format!("watch failed: {error:?}")))?
+      },
+      | DeferredAction::SendListing(mut target, listing) => target.tell(listing),
+      | DeferredAction::SendRegistered(mut target, registered) => target.tell(registered),
+      | DeferredAction::SendDeregistered(mut target, deregistered) => target.tell(deregistered),
+      | DeferredAction::UpdateRegistry(ops) => registry.update(ctx, ops)?,
+    }
+  }
+  Ok(())
+}
+
+/// Change applied to the replicated registry.
+#[derive(Clone)]
+pub(crate) enum RegistryOp {
+  Add(RegistryKey, ClusterReceptionistEntry),
+  Remove(RegistryKey, ClusterReceptionistEntry),
+}
+
+/// Side effect collected while the state is locked and executed after the lock is released.
+pub(crate) enum DeferredAction {
+  WatchRegistered(ActorRef),
+  WatchSubscriber(TypedActorRef<Listing>),
+  SendListing(TypedActorRef<Listing>, Listing),
+  SendRegistered(TypedActorRef<Registered>, Registered),
+  SendDeregistered(TypedActorRef<Deregistered>, Deregistered),
+  UpdateRegistry(Vec<RegistryOp>),
+}
+
+/// Registrations and subscriptions known to the receptionist of this member.
+pub(crate) struct ClusterReceptionistState {
+  self_authority: String,
+  /// Actors registered on this member; they are known before the registry update is replicated.
+  local:          BTreeMap<RegistryKey, Vec<ActorRef>>,
+  /// Registrations of all members as last seen in the replicated registry.
+  replicated:     BTreeMap<RegistryKey, BTreeSet<ClusterReceptionistEntry>>,
+  unreachable:    BTreeSet<String>,
+  removed:        BTreeSet<String>,
+  subscribers:    BTreeMap<RegistryKey, Vec<TypedActorRef<Listing>>>,
+}
+
+impl ClusterReceptionistState {
+  pub(crate) const fn new(self_authority: String) -> Self {
+    Self {
+      self_authority,
+      local: BTreeMap::new(),
+      replicated: BTreeMap::new(),
+      unreachable: BTreeSet::new(),
+      removed: BTreeSet::new(),
+      subscribers: BTreeMap::new(),
+    }
+  }
+
+  fn listing(&self, key: &RegistryKey) -> Listing {
+    let local = self.local.get(key).map(Vec::as_slice).unwrap_or_default();
+    let mut reachable = local.to_vec();
+    let mut all = local.to_vec();
+    for entry in self.replicated.get(key).into_iter().flatten() {
+      // 自メンバーの登録は local が正とし、除去済みメンバーの登録は表示しない
+      if entry.authority() == self.self_authority || self.removed.contains(entry.authority()) {
+        continue;
+      }
+      all.push(entry.actor_ref().clone());
+      if !self.unreachable.contains(entry.authority()) {
+        reachable.push(entry.actor_ref().clone());
+      }
+    }
+    Listing::new(key.0.clone(), key.1, reachable).with_all_refs(all)
+  }
+
+  fn subscribed_listings(&self) -> Vec<(RegistryKey, Listing)> {
+    self.subscribers.keys().map(|key| (key.clone(), self.listing(key))).collect()
+  }
+
+  fn collect_listing_changes(&self, before: Vec<(RegistryKey, Listing)>, deferred: &mut Vec<DeferredAction>) {
+    for (key, previous) in before {
+      let current = self.listing(&key);
+      let services_were_added_or_removed = current.all_refs() != previous.all_refs();
+      if !services_were_added_or_removed && current.refs() == previous.refs() {
+        continue;
+      }
+      let listing = current.with_services_were_added_or_removed(services_were_added_or_removed);
+      for subscriber in self.subscribers.get(&key).into_iter().flatten() {
+        deferred.push(DeferredAction::SendListing(subscriber.clone(), listing.clone()));
+      }
+    }
+  }
+
+  fn self_entry(&self, actor_ref: &ActorRef) -> ClusterReceptionistEntry {
+    ClusterReceptionistEntry::new(actor_ref.clone(), self.self_authority.clone())
+  }
+}
+
+pub(crate) fn collect_deferred_for_message(
+  state: &mut ClusterReceptionistState,
+  message: &ClusterReceptionistMessage,
+  deferred: &mut Vec<DeferredAction>,
+) {
+  match message {
+    | ClusterReceptionistMessage::Command(command) => collect_command(state, command, deferred),
+    | ClusterReceptionistMessage::RegistryChanged(SubscribeResponse::Changed { data, .. }) => {
+      let before = state.subscribed_listings();
+      state.replicated = data.entries();
+      state.collect_listing_changes(before, deferred);
+    },
+    | ClusterReceptionistMessage::RegistryChanged(SubscribeResponse::Deleted { .. }) => {
+      let before = state.subscribed_listings();
+      state.replicated.clear();
+      state.collect_listing_changes(before, deferred);
+    },
+    | ClusterReceptionistMessage::RegistryUpdated(_) => {},
+    | ClusterReceptionistMessage::ClusterEvent(event) => collect_cluster_event(state, event, deferred),
+  }
+}
+
+fn collect_command(
+  state: &mut ClusterReceptionistState,
+  command: &ReceptionistCommand,
+  deferred: &mut Vec<DeferredAction>,
+) {
+  match command {
+    | ReceptionistCommand::Register { service_id, type_id, actor_ref, reply_to } => {
+      let key = (service_id.clone(), *type_id);
+      let already_registered =
+        state.local.get(&key).is_some_and(|refs| refs.iter().any(|existing| existing.pid() == actor_ref.pid()));
+      if !already_registered {
+        let before = state.subscribed_listings();
+        deferred.push(DeferredAction::WatchRegistered(actor_ref.clone()));
+        state.local.entry(key.clone()).or_default().push(actor_ref.clone());
+        deferred.push(DeferredAction::UpdateRegistry(alloc::vec![RegistryOp::Add(key, state.self_entry(actor_ref))]));
+        state.collect_listing_changes(before, deferred);
+      }
+      if let Some(reply_to) = reply_to {
+        let ack = Registered::new(service_id.clone(), *type_id, actor_ref.clone());
+        deferred.push(DeferredAction::SendRegistered(reply_to.clone(), ack));
+      }
+    },
+    | ReceptionistCommand::Deregister { service_id, type_id, actor_ref, reply_to } => {
+      let key = (service_id.clone(), *type_id);
+      let before = state.subscribed_listings();
+      if remove_local(state, &key, actor_ref.pid()) {
+        deferred
+          .push(DeferredAction::UpdateRegistry(alloc::vec![RegistryOp::Remove(key, state.self_entry(actor_ref))]));
+        state.collect_listing_changes(before, deferred);
+      }
+      if let Some(reply_to) = reply_to {
+        let ack = Deregistered::new(service_id.clone(), *type_id, actor_ref.clone());
+        deferred.push(DeferredAction::SendDeregistered(reply_to.clone(), ack));
+      }
+    },
+    | ReceptionistCommand::Subscribe { service_id, type_id, subscriber } => {
+      let key = (service_id.clone(), *type_id);
+      deferred.push(DeferredAction::SendListing(subscriber.clone(), state.listing(&key)));
+      let subscribers = state.subscribers.entry(key).or_default();
+      if !subscribers.iter().any(|existing| existing.pid() == subscriber.pid()) {
+        deferred.push(DeferredAction::WatchSubscriber(subscriber.clone()));
+        subscribers.push(subscriber.clone());
+      }
+    },
+    | ReceptionistCommand::Unsubscribe { service_id, type_id, subscriber } => {
+      let key = (service_id.clone(), *type_id);
+      if let Some(subscribers) = state.subscribers.get_mut(&key) {
+        subscribers.retain(|existing| existing.pid() != subscriber.pid());
+        if subscribers.is_empty() {
+          state.subscribers.remove(&key);
+        }
+      }
+    },
+    | ReceptionistCommand::Find { service_id, type_id, reply_to } => {
+      let key = (service_id.clone(), *type_id);
+      deferred.push(DeferredAction::SendListing(reply_to.clone(), state.listing(&key)));
+    },
+  }
+}
+
+fn remove_local(state: &mut ClusterReceptionistState, key: &RegistryKey, pid: Pid) -> bool {
+  let Some(refs) = state.local.get_mut(key) else {
+    return false;
+  };
+  let before = refs.len();
+  refs.retain(|existing| existing.pid() != pid);
+  let removed = refs.len() != before;
+  if refs.is_empty() {
+    state.local.remove(key);
+  }
+  removed
+}
+
+fn collect_cluster_event(
+  state: &mut ClusterReceptionistState,
+  event: &ClusterEvent,
+  deferred: &mut Vec<DeferredAction>,
+) {
+  let before = state.subscribed_listings();
+  match event {
+    | ClusterEvent::CurrentClusterState { state: snapshot, .. } => {
+      state.unreachable = snapshot
+        .unreachable
+        .iter()
+        .filter(|record| record.authority != state.self_authority)
+        .map(|record| record.authority.clone())
+        .collect();
+    },
+    | ClusterEvent::UnreachableMember { authority, .. } if *authority != state.self_authority => {
+      state.unreachable.insert(authority.clone());
+    },
+    | ClusterEvent::ReachableMember { authority, .. } => {
+      state.unreachable.remove(authority);
+    },
+    | ClusterEvent::MemberStatusChanged { authority, to, .. } if *authority != state.self_authority => match to {
+      | NodeStatus::Removed | NodeStatus::Dead => {
+        state.unreachable.remove(authority);
+        state.removed.insert(authority.clone());
+        // 除去されたメンバーは自分で登録を消せないため、残ったメンバーが代わりに消す
+        let ops: Vec<RegistryOp> = state
+          .replicated
+          .iter()
+          .flat_map(|(key, entries)| {
+            entries
+              .iter()
+              .filter(|entry| entry.authority() == authority)
+              .map(|entry| RegistryOp::Remove(key.clone(), entry.clone()))
+          })
+          .collect();
+        if !ops.is_empty() {
+          deferred.push(DeferredAction::UpdateRegistry(ops));
+        }
+      },
+      | NodeStatus::Joining | NodeStatus::WeaklyUp | NodeStatus::Up => {
+        state.removed.remove(authority);
+      },
+      | _ => {},
+    },
+    | _ => {},
+  }
+  state.collect_listing_changes(before, deferred);
+}
+
+pub(crate) fn collect_terminated(state: &mut ClusterReceptionistState, pid: Pid, deferred: &mut Vec<DeferredAction>) {
+  let before = state.subscribed_listings();
+  let mut ops = Vec::new();
+  for (key, refs) in &state.local {
+    for actor_ref in refs.iter().filter(|actor_ref| actor_ref.pid() == pid) {
+      ops.push(RegistryOp::Remove(key.clone(), state.self_entry(actor_ref)));
+    }
+  }
+  for op in &ops {
+    if let RegistryOp::Remove(key, _) = op {
+      remove_local(state, key, pid);
+    }
+  }
+  for subscribers in state.subscribers.values_mut() {
+    subscribers.retain(|subscriber| subscriber.pid() != pid);
+  }
+  state.subscribers.retain(|_, subscribers| !subscribers.is_empty());
+  if !ops.is_empty() {
+    deferred.push(DeferredAction::UpdateRegistry(ops));
+  }
+  state.collect_listing_changes(before, deferred);
+}
@@ -0,0 +1,61 @@
+//! Configuration for the cluster receptionist.
+
+#[cfg(test)]
+#[path = "cluster_receptionist_config_test.rs"]
+mod tests;
+
+use core::time::Duration;
+
+use fraktor_cluster_core_kernel_rs::ddata::WriteConsistency;
+
+/// Default timeout of the asks the receptionist sends to the Replicator.
+const DEFAULT_UNEXPECTED_ASK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Configuration for [`ClusterReceptionist`](crate::ClusterReceptionist).
+///
+/// Corresponds to Pekko's `ClusterReceptionistSettings`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterReceptionistConfig {
+  write_consistency:      WriteConsistency,
+  unexpected_ask_timeout: Duration,
+}
+
+impl ClusterReceptionistConfig {
+  /// Creates default config: local writes and a 5 s Replicator ask timeout.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self { write_consistency: WriteConsistency::Local, unexpected_ask_timeout: DEFAULT_UNEXPECTED_ASK_TIMEOUT }
+  }
+
+  /// Returns the consistency used when replicating registration changes.
+  #[must_use]
+  pub const fn write_consistency(&self) -> WriteConsistency {
+    self.write_consistency
+  }
+
+  /// Returns a new config with the given write consistency.
+  #[must_use]
+  pub const fn with_write_consistency(self, write_consistency: WriteConsistency) -> Self {
+    Self { write_consistency, ..self }
+  }
+
+  /// Returns the timeout of the asks sent to the Replicator.
+  ///
+  /// It should exceed the timeout of the configured write consistency.
+  #[must_use]
+  pub const fn unexpected_ask_timeout(&self) -> Duration {
+    self.unexpected_ask_timeout
+  }
+
+  /// Returns a new config with the given Replicator ask timeout.
+  #[must_use]
+  pub const fn with_unexpected_ask_timeout(self, timeout: Duration) -> Self {
+    Self { unexpected_ask_timeout: timeout, ..self }
+  }
+}
+
+impl Default for ClusterReceptionistConfig {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,26 @@
+use core::time::Duration;
+
+use fraktor_cluster_core_kernel_rs::ddata::WriteConsistency;
+
+use crate::ClusterReceptionistConfig;
+
+#[test]
+fn default_config_writes_locally() {
+  let config = ClusterReceptionistConfig::default();
+
+  assert_eq!(config.write_consistency(), WriteConsistency::Local);
+  assert_eq!(config.unexpected_ask_timeout(), Duration::from_secs(5));
+  assert_eq!(config, ClusterReceptionistConfig::new());
+}
+
+#[test]
+fn builders_override_only_their_field() {
+  let consistency = WriteConsistency::All { timeout: Duration::from_secs(2) };
+
+  let config = ClusterReceptionistConfig::new()
+    .with_write_consistency(consistency)
+    .with_unexpected_ask_timeout(Duration::from_secs(3));
+
+  assert_eq!(config.write_consistency(), consistency);
+  assert_eq!(config.unexpected_ask_timeout(), Duration::from_secs(3));
+}
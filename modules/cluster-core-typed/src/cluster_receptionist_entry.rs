@@ -0,0 +1,59 @@
+//! Replicated registration of an actor under a service key.
+
+#[cfg(test)]
+#[path = "cluster_receptionist_entry_test.rs"]
+mod tests;
+
+use alloc::string::String;
+use core::cmp::Ordering;
+
+use fraktor_actor_core_kernel_rs::actor::actor_ref::ActorRef;
+
+/// Actor registered by the receptionist of the member at `authority`.
+///
+/// Entries are identified by the member authority and the actor pid, which is unique within a
+/// member.
+#[derive(Clone, Debug)]
+pub(crate) struct ClusterReceptionistEntry {
+  actor_ref: ActorRef,
+  authority: String,
+}
+
+impl ClusterReceptionistEntry {
+  pub(crate) const fn new(actor_ref: ActorRef, authority: String) -> Self {
+    Self { actor_ref, authority }
+  }
+
+  pub(crate) const fn actor_ref(&self) -> &ActorRef {
+    &self.actor_ref
+  }
+
+  pub(crate) fn authority(&self) -> &str {
+    &self.authority
+  }
+}
+
+impl PartialEq for ClusterReceptionistEntry {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for ClusterReceptionistEntry {}
+
+impl PartialOrd for ClusterReceptionistEntry {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for ClusterReceptionistEntry {
+  fn cmp(&self, other: &Self) -> Ordering {
+    let pid = self.actor_ref.pid();
+    let other_pid = other.actor_ref.pid();
+    self
+      .authority
+      .cmp(&other.authority)
+      .then_with(|| (pid.value(), pid.generation()).cmp(&(other_pid.value(), other_pid.generation())))
+  }
+}
@@ -0,0 +1,26 @@
+use alloc::string::String;
+
+use fraktor_actor_core_kernel_rs::actor::{
+  Pid,
+  actor_ref::{ActorRef, ActorRefSenderShared, NullSender},
+};
+
+use super::ClusterReceptionistEntry;
+
+fn entry(authority: &str, pid: u64) -> ClusterReceptionistEntry {
+  let actor_ref = ActorRef::new(Pid::new(pid, 0), ActorRefSenderShared::new(alloc::boxed::Box::new(NullSender)));
+  ClusterReceptionistEntry::new(actor_ref, String::from(authority))
+}
+
+#[test]
+fn entries_are_identified_by_authority_and_pid() {
+  assert_eq!(entry("node1:8080", 1), entry("node1:8080", 1));
+  assert_ne!(entry("node1:8080", 1), entry("node2:8080", 1));
+  assert_ne!(entry("node1:8080", 1), entry("node1:8080", 2));
+}
+
+#[test]
+fn entries_are_ordered_by_authority_first() {
+  assert!(entry("node1:8080", 9) < entry("node2:8080", 1));
+  assert!(entry("node1:8080", 1) < entry("node1:8080", 2));
+}
@@ -0,0 +1,24 @@
+//! Messages handled by the cluster receptionist actor.
+
+use fraktor_actor_core_typed_rs::receptionist::ReceptionistCommand;
+use fraktor_cluster_core_kernel_rs::{
+  ddata::{SubscribeResponse, UpdateResponse},
+  topology::ClusterEvent,
+};
+
+use crate::cluster_receptionist::ServiceRegistry;
+
+/// Protocol of the actor holding the cluster receptionist state.
+///
+/// Receptionist commands are wrapped by the system receptionist facing behavior; the other
+/// variants arrive through message adapters.
+pub(crate) enum ClusterReceptionistMessage {
+  /// Command sent to the system receptionist.
+  Command(ReceptionistCommand),
+  /// Replicated registry changed.
+  RegistryChanged(SubscribeResponse<ServiceRegistry>),
+  /// Outcome of a registry update issued by this receptionist.
+  RegistryUpdated(UpdateResponse<ServiceRegistry>),
+  /// Cluster membership or reachability changed.
+  ClusterEvent(ClusterEvent),
+}
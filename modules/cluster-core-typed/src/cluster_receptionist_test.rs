@@ -0,0 +1,191 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::{any::TypeId, time::Duration};
+
+use fraktor_actor_core_kernel_rs::actor::{
+  Pid,
+  actor_ref::{ActorRef, ActorRefSenderShared, NullSender},
+};
+use fraktor_actor_core_typed_rs::{TypedActorRef, receptionist::ReceptionistCommand};
+use fraktor_cluster_core_kernel_rs::{
+  ddata::{SelfUniqueAddress, SubscribeResponse},
+  membership::NodeStatus,
+  topology::ClusterEvent,
+};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::time::TimerInstant;
+
+use super::{
+  ClusterReceptionistState, DeferredAction, RegistryOp, ServiceRegistry, collect_deferred_for_message,
+  collect_terminated, registry_key,
+};
+use crate::{
+  cluster_receptionist_entry::ClusterReceptionistEntry, cluster_receptionist_message::ClusterReceptionistMessage,
+};
+
+const SELF_AUTHORITY: &str = "node1:8080";
+const REMOTE_AUTHORITY: &str = "node2:8080";
+const SERVICE_ID: &str = "pinger";
+
+fn actor_ref(pid: u64) -> ActorRef {
+  ActorRef::new(Pid::new(pid, 0), ActorRefSenderShared::new(Box::new(NullSender)))
+}
+
+fn service_key() -> (String, TypeId) {
+  (String::from(SERVICE_ID), TypeId::of::<u32>())
+}
+
+fn now() -> TimerInstant {
+  TimerInstant::from_ticks(1, Duration::from_secs(1))
+}
+
+fn handle(state: &mut ClusterReceptionistState, message: ClusterReceptionistMessage) -> Vec<DeferredAction> {
+  let mut deferred = Vec::new();
+  collect_deferred_for_message(state, &message, &mut deferred);
+  deferred
+}
+
+fn command(state: &mut ClusterReceptionistState, command: ReceptionistCommand) -> Vec<DeferredAction> {
+  handle(state, ClusterReceptionistMessage::Command(command))
+}
+
+fn register(state: &mut ClusterReceptionistState, pid: u64) -> Vec<DeferredAction> {
+  command(state, ReceptionistCommand::Register {
+    service_id: String::from(SERVICE_ID),
+    type_id:    TypeId::of::<u32>(),
+    actor_ref:  actor_ref(pid),
+    reply_to:   None,
+  })
+}
+
+fn subscribe(state: &mut ClusterReceptionistState, pid: u64) -> Vec<DeferredAction> {
+  command(state, ReceptionistCommand::Subscribe {
+    service_id: String::from(SERVICE_ID),
+    type_id:    TypeId::of::<u32>(),
+    subscriber: TypedActorRef::from_untyped(actor_ref(pid)),
+  })
+}
+
+fn registry_with(entries: &[(&str, u64)]) -> ClusterReceptionistMessage {
+  let node = SelfUniqueAddress::new(UniqueAddress::new(Address::new("sys", "node1", 8080), 1));
+  let mut registry = ServiceRegistry::new();
+  for (authority, pid) in entries {
+    let entry = ClusterReceptionistEntry::new(actor_ref(*pid), String::from(*authority));
+    registry = registry.add_binding(&node, service_key(), entry);
+  }
+  ClusterReceptionistMessage::RegistryChanged(SubscribeResponse::Changed { key: registry_key(), data: registry })
+}
+
+fn pids(refs: &[ActorRef]) -> Vec<u64> {
+  refs.iter().map(|actor_ref| actor_ref.pid().value()).collect()
+}
+
+fn sent_listings(deferred: &[DeferredAction]) -> Vec<(Vec<u64>, Vec<u64>, bool)> {
+  deferred
+    .iter()
+    .filter_map(|action| match action {
+      | DeferredAction::SendListing(_, listing) => {
+        Some((pids(listing.refs()), pids(listing.all_refs()), listing.services_were_added_or_removed()))
+      },
+      | _ => None,
+    })
+    .collect()
+}
+
+fn registry_ops(deferred: &[DeferredAction]) -> Vec<(bool, String, u64)> {
+  deferred
+    .iter()
+    .filter_map(|action| match action {
+      | DeferredAction::UpdateRegistry(ops) => Some(ops),
+      | _ => None,
+    })
+    .flatten()
+    .map(|op| match op {
+      | RegistryOp::Add(_, entry) => (true, String::from(entry.authority()), entry.actor_ref().pid().value()),
+      | RegistryOp::Remove(_, entry) => (false, String::from(entry.authority()), entry.actor_ref().pid().value()),
+    })
+    .collect()
+}
+
+#[test]
+fn register_watches_and_replicates_the_local_actor() {
+  let mut state = ClusterReceptionistState::new(String::from(SELF_AUTHORITY));
+
+  let deferred = register(&mut state, 10);
+
+  assert!(
+    deferred
+      .iter()
+      .any(|action| matches!(action, DeferredAction::WatchRegistered(actor_ref) if actor_ref.pid().value() == 10))
+  );
+  assert_eq!(registry_ops(&deferred), alloc::vec![(true, String::from(SELF_AUTHORITY), 10)]);
+  assert!(registry_ops(&register(&mut state, 10)).is_empty());
+}
+
+#[test]
+fn registry_changes_merge_remote_entries_without_duplicating_local_ones() {
+  let mut state = ClusterReceptionistState::new(String::from(SELF_AUTHORITY));
+  register(&mut state, 10);
+  assert_eq!(sent_listings(&subscribe(&mut state, 99)), alloc::vec![(alloc::vec![10], alloc::vec![10], true)]);
+
+  let deferred = handle(&mut state, registry_with(&[(SELF_AUTHORITY, 10), (REMOTE_AUTHORITY, 20)]));
+
+  assert_eq!(sent_listings(&deferred), alloc::vec![(alloc::vec![10, 20], alloc::vec![10, 20], true)]);
+  assert!(
+    sent_listings(&handle(&mut state, registry_with(&[(SELF_AUTHORITY, 10), (REMOTE_AUTHORITY, 20)]))).is_empty()
+  );
+}
+
+#[test]
+fn unreachable_members_are_only_listed_in_all_refs() {
+  let mut state = ClusterReceptionistState::new(String::from(SELF_AUTHORITY));
+  subscribe(&mut state, 99);
+  handle(&mut state, registry_with(&[(REMOTE_AUTHORITY, 20)]));
+
+  let unreachable = ClusterEvent::UnreachableMember {
+    node_id:     String::from(REMOTE_AUTHORITY),
+    authority:   String::from(REMOTE_AUTHORITY),
+    observed_at: now(),
+  };
+  let deferred = handle(&mut state, ClusterReceptionistMessage::ClusterEvent(unreachable));
+  assert_eq!(sent_listings(&deferred), alloc::vec![(alloc::vec![], alloc::vec![20], false)]);
+
+  let reachable = ClusterEvent::ReachableMember {
+    node_id:     String::from(REMOTE_AUTHORITY),
+    authority:   String::from(REMOTE_AUTHORITY),
+    observed_at: now(),
+  };
+  let deferred = handle(&mut state, ClusterReceptionistMessage::ClusterEvent(reachable));
+  assert_eq!(sent_listings(&deferred), alloc::vec![(alloc::vec![20], alloc::vec![20], false)]);
+}
+
+#[test]
+fn removed_members_are_dropped_from_listings_and_registry() {
+  let mut state = ClusterReceptionistState::new(String::from(SELF_AUTHORITY));
+  subscribe(&mut state, 99);
+  handle(&mut state, registry_with(&[(REMOTE_AUTHORITY, 20)]));
+
+  let removed = ClusterEvent::MemberStatusChanged {
+    node_id:     String::from(REMOTE_AUTHORITY),
+    authority:   String::from(REMOTE_AUTHORITY),
+    from:        NodeStatus::Exiting,
+    to:          NodeStatus::Removed,
+    observed_at: now(),
+  };
+  let deferred = handle(&mut state, ClusterReceptionistMessage::ClusterEvent(removed));
+
+  assert_eq!(registry_ops(&deferred), alloc::vec![(false, String::from(REMOTE_AUTHORITY), 20)]);
+  assert_eq!(sent_listings(&deferred), alloc::vec![(alloc::vec![], alloc::vec![], true)]);
+}
+
+#[test]
+fn terminated_local_actors_are_removed_from_the_registry() {
+  let mut state = ClusterReceptionistState::new(String::from(SELF_AUTHORITY));
+  register(&mut state, 10);
+  subscribe(&mut state, 99);
+
+  let mut deferred = Vec::new();
+  collect_terminated(&mut state, Pid::new(10, 0), &mut deferred);
+
+  assert_eq!(registry_ops(&deferred), alloc::vec![(false, String::from(SELF_AUTHORITY), 10)]);
+  assert_eq!(sent_listings(&deferred), alloc::vec![(alloc::vec![], alloc::vec![], true)]);
+}
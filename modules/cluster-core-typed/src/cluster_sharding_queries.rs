@@ -0,0 +1,78 @@
+//! Typed cluster sharding query access point.
+
+#[cfg(test)]
+#[path = "cluster_sharding_queries_test.rs"]
+mod tests;
+
+use alloc::format;
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{props::Props, spawn::SpawnError},
+  serialization::contribution::register_serialization_registry_contributor,
+  system::ActorSystem,
+};
+use fraktor_actor_core_typed_rs::{TypedActorRef, TypedActorSystem};
+use fraktor_cluster_core_kernel_rs::extension::{ClusterApi, ClusterApiError, ClusterExtension};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{
+  ClusterShardingQuery, cluster_sharding_query_actor::ClusterShardingQueryActor,
+  cluster_sharding_query_serializer::ClusterShardingQuerySerializationContributor,
+};
+
+const QUERY_ACTOR_NAME: &str = "clusterShardingQuery";
+
+/// Typed entry point for observing which grains run where.
+///
+/// Complements the aggregate counters of `GrainMetrics` with per-member answers: the shards and
+/// grain identities active on a member, the grain count per member and kind, and the members
+/// grains are currently placed on.
+pub struct ClusterShardingQueries {
+  cluster:   ClusterApi,
+  extension: ArcShared<ClusterExtension>,
+  system:    ActorSystem,
+}
+
+impl ClusterShardingQueries {
+  /// Retrieves the typed cluster sharding query facade from a typed actor system.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the cluster extension has not been installed.
+  pub fn get<S>(system: &TypedActorSystem<S>) -> Result<Self, ClusterApiError>
+  where
+    S: Send + Sync + 'static, {
+    let cluster = ClusterApi::try_from_system(system.as_untyped())?;
+    let extension = system
+      .as_untyped()
+      .extended()
+      .extension_by_type::<ClusterExtension>()
+      .ok_or(ClusterApiError::ExtensionNotInstalled)?;
+    Ok(Self { cluster, extension, system: system.as_untyped().clone() })
+  }
+
+  /// Starts the local query actor and returns the reference accepting
+  /// [`ClusterShardingQuery`]s.
+  ///
+  /// Every member should call this once: cluster-wide statistics are collected from the query
+  /// actors of the other members, and members without one are reported as failed.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when the serializer of the stats protocol collides with another
+  /// registration, or when the query actor cannot be spawned, for example because it was already
+  /// initialized.
+  pub fn init(&self) -> Result<TypedActorRef<ClusterShardingQuery>, SpawnError> {
+    register_serialization_registry_contributor(&self.system, ClusterShardingQuerySerializationContributor).map_err(
+      |error| {
+        SpawnError::SystemBuildError(format!("cluster sharding query serialization registration failed: {error}"))
+      },
+    )?;
+    let cluster = self.cluster.clone();
+    let extension = self.extension.clone();
+    let props = Props::from_fn(move || ClusterShardingQueryActor::new(cluster.clone(), extension.clone()))
+      .with_name(QUERY_ACTOR_NAME);
+    let query_actor = self.system.extended().spawn_system_actor(&props)?.into_actor_ref();
+    Ok(TypedActorRef::from_untyped(query_actor))
+  }
+}
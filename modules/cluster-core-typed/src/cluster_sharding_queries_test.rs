@@ -0,0 +1,51 @@
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::actor::{
+  extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig,
+};
+use fraktor_actor_core_typed_rs::{TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::{
+  cluster_provider::NoopClusterProvider,
+  extension::{ClusterApiError, ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+};
+
+use crate::ClusterShardingQueries;
+
+#[derive(Debug)]
+struct TestMsg;
+
+fn typed_system(installers: ExtensionInstallers) -> TypedActorSystem<TestMsg> {
+  let props = TypedProps::<TestMsg>::from_behavior_factory(Behaviors::ignore);
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(installers);
+  TypedActorSystem::create_from_props(&props, config).expect("typed system")
+}
+
+fn cluster_system() -> TypedActorSystem<TestMsg> {
+  let config = ClusterExtensionConfig::new().with_advertised_address("node1:8080");
+  let installer =
+    ClusterExtensionInstaller::new(config, |_event_stream, _block_list, _address| Box::new(NoopClusterProvider::new()));
+  let system = typed_system(ExtensionInstallers::default().with_extension_installer(installer));
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  system
+}
+
+#[test]
+fn get_fails_when_the_cluster_extension_is_not_installed() {
+  let system = typed_system(ExtensionInstallers::default());
+
+  assert!(matches!(ClusterShardingQueries::get(&system), Err(ClusterApiError::ExtensionNotInstalled)));
+}
+
+#[test]
+fn init_spawns_the_query_actor_once() {
+  let system = cluster_system();
+  let queries = ClusterShardingQueries::get(&system).expect("cluster sharding queries");
+
+  let query_actor = queries.init().expect("init");
+
+  let path = query_actor.as_untyped().path().expect("query actor has a path");
+  assert!(path.to_string().ends_with("/clusterShardingQuery"), "unexpected path {path}");
+  assert!(queries.init().is_err());
+}
@@ -0,0 +1,37 @@
+//! Queries accepted by the cluster sharding query actor.
+
+use alloc::string::String;
+use core::time::Duration;
+
+use fraktor_actor_core_typed_rs::TypedActorRef;
+use fraktor_cluster_core_kernel_rs::sharding::ShardRegionState;
+
+use crate::{ClusterShardingStats, CurrentRegions};
+
+/// Queries sent to the reference returned by
+/// [`ClusterShardingQueries::init`](crate::ClusterShardingQueries::init).
+///
+/// This is the fraktor equivalent of Pekko's typed `ClusterShardingQuery` together with
+/// `GetCurrentRegions`.
+#[derive(Clone)]
+pub enum ClusterShardingQuery {
+  /// Replies with the shards of `kind` and their grains active on the queried member.
+  GetShardRegionState {
+    /// Grain kind to report.
+    kind:     String,
+    /// Receives the local shard region state.
+    reply_to: TypedActorRef<ShardRegionState>,
+  },
+  /// Replies with the number of active grains per kind on every region.
+  ///
+  /// Regions that are unreachable, quarantined or do not answer within `timeout` are
+  /// reported as failed.
+  GetClusterShardingStats {
+    /// Time to wait for the answers of the other members.
+    timeout:  Duration,
+    /// Receives the aggregated statistics.
+    reply_to: TypedActorRef<ClusterShardingStats>,
+  },
+  /// Replies with the members grains are currently placed on.
+  GetCurrentRegions(TypedActorRef<CurrentRegions>),
+}
@@ -0,0 +1,209 @@
+//! Actor answering cluster sharding queries on one member.
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  format,
+  string::String,
+};
+use core::time::Duration;
+
+use fraktor_actor_core_kernel_rs::actor::{
+  Actor, ActorContext,
+  actor_path::ActorPathParser,
+  actor_ref::ActorRef,
+  error::ActorError,
+  messaging::{AnyMessage, AnyMessageView},
+};
+use fraktor_actor_core_typed_rs::TypedActorRef;
+use fraktor_cluster_core_kernel_rs::{
+  activation::{IdentityTable, ResolveResult},
+  extension::{ClusterApi, ClusterExtension},
+  membership::{MembershipDelta, MembershipTable, MembershipVersion},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{
+  ClusterShardingQuery, ClusterShardingStats, CurrentRegions, region_stats_reply::RegionStatsReply,
+  region_stats_request::RegionStatsRequest,
+};
+
+const STATS_TIMEOUT_TIMER_PREFIX: &str = "cluster-sharding-stats-timeout-";
+const REMOTE_ACTOR_PATH_SCHEME: &str = "fraktor.tcp";
+const BLOCKED_MEMBER_REASON: &str = "blocked member";
+
+#[derive(Clone, Copy)]
+struct StatsTimeout {
+  request_id: u64,
+}
+
+struct PendingStats {
+  reply_to: TypedActorRef<ClusterShardingStats>,
+  stats:    ClusterShardingStats,
+  awaiting: BTreeSet<String>,
+}
+
+/// Answers [`ClusterShardingQuery`]s from the placement state of the local member.
+///
+/// Every member runs a query actor under the same name. Cluster-wide statistics are gathered
+/// by asking the query actors of the other regions; regions the identity table reports as
+/// unreachable or quarantined are marked failed without being asked.
+pub(crate) struct ClusterShardingQueryActor {
+  cluster:         ClusterApi,
+  extension:       ArcShared<ClusterExtension>,
+  self_authority:  String,
+  next_request_id: u64,
+  pending:         BTreeMap<u64, PendingStats>,
+}
+
+impl ClusterShardingQueryActor {
+  /// Creates a query actor reading the placement state through `cluster`.
+  pub(crate) fn new(cluster: ClusterApi, extension: ArcShared<ClusterExtension>) -> Self {
+    let self_authority = cluster.self_authority();
+    Self { cluster, extension, self_authority, next_request_id: 0, pending: BTreeMap::new() }
+  }
+
+  fn on_query(&mut self, ctx: &mut ActorContext<'_>, query: &ClusterShardingQuery) -> Result<(), ActorError> {
+    match query {
+      | ClusterShardingQuery::GetShardRegionState { kind, reply_to } => {
+        reply_to.clone().tell(self.cluster.shard_region_state(kind));
+        Ok(())
+      },
+      | ClusterShardingQuery::GetClusterShardingStats { timeout, reply_to } => {
+        self.collect_stats(ctx, *timeout, reply_to.clone())
+      },
+      | ClusterShardingQuery::GetCurrentRegions(reply_to) => {
+        reply_to.clone().tell(CurrentRegions { regions: self.cluster.current_regions() });
+        Ok(())
+      },
+    }
+  }
+
+  fn collect_stats(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    timeout: Duration,
+    reply_to: TypedActorRef<ClusterShardingStats>,
+  ) -> Result<(), ActorError> {
+    self.next_request_id += 1;
+    let request_id = self.next_request_id;
+    let mut pending = PendingStats { reply_to, stats: ClusterShardingStats::default(), awaiting: BTreeSet::new() };
+    let mut identities = self.identity_table();
+    let relative = ctx.self_ref().path().map(|path| path.to_relative_string());
+    for authority in self.cluster.current_regions() {
+      if authority == self.self_authority {
+        pending.stats.regions.insert(authority, self.cluster.shard_region_stats());
+        continue;
+      }
+      let peer = relative.as_deref().and_then(|relative| peer_query_actor(ctx, &mut identities, &authority, relative));
+      match peer {
+        | Some(mut peer) => {
+          peer.tell(AnyMessage::new(RegionStatsRequest { request_id, requester: self.self_authority.clone() }));
+          pending.awaiting.insert(authority);
+        },
+        | None => pending.stats.failed.push(authority),
+      }
+    }
+    if pending.awaiting.is_empty() {
+      pending.reply_to.tell(pending.stats);
+      return Ok(());
+    }
+    ctx
+      .timers()
+      .start_single_timer(
+        format!("{STATS_TIMEOUT_TIMER_PREFIX}{request_id}"),
+        AnyMessage::new(StatsTimeout { request_id }),
+        timeout,
+      )
+      .map_err(|error| ActorError::recoverable(format!("cluster sharding stats timer failed: {error:?}")))?;
+    self.pending.insert(request_id, pending);
+    Ok(())
+  }
+
+  fn identity_table(&self) -> IdentityTable {
+    let state = self.cluster.current_state();
+    let mut membership = MembershipTable::new(1);
+    membership.apply_delta(MembershipDelta::new(MembershipVersion::zero(), MembershipVersion::new(1), state.members));
+    let mut identities = IdentityTable::new(membership);
+    for authority in self.extension.blocked_members() {
+      identities.quarantine(authority, String::from(BLOCKED_MEMBER_REASON));
+    }
+    identities
+  }
+
+  fn on_stats_reply(&mut self, ctx: &ActorContext<'_>, reply: &RegionStatsReply) {
+    // タイムアウト後に届いた応答は破棄する
+    let Some(pending) = self.pending.get_mut(&reply.request_id) else {
+      return;
+    };
+    if !pending.awaiting.remove(&reply.authority) {
+      return;
+    }
+    pending.stats.regions.insert(reply.authority.clone(), reply.stats.clone());
+    if pending.awaiting.is_empty() {
+      // must-ignore: 取り消せなかったタイムアウトは pending から外れた request_id として無視される。
+      drop(ctx.timers().cancel(&format!("{STATS_TIMEOUT_TIMER_PREFIX}{}", reply.request_id)));
+      self.complete(reply.request_id);
+    }
+  }
+
+  fn on_stats_request(&self, ctx: &ActorContext<'_>, request: &RegionStatsRequest) {
+    // 応答先は要求元メンバーの同名 query actor で、到達できなければ要求元のタイムアウトに任せる
+    let mut identities = self.identity_table();
+    let relative = ctx.self_ref().path().map(|path| path.to_relative_string());
+    let requester =
+      relative.as_deref().and_then(|relative| peer_query_actor(ctx, &mut identities, &request.requester, relative));
+    if let Some(mut requester) = requester {
+      requester.tell(AnyMessage::new(RegionStatsReply {
+        request_id: request.request_id,
+        authority:  self.self_authority.clone(),
+        stats:      self.cluster.shard_region_stats(),
+      }));
+    }
+  }
+
+  fn complete(&mut self, request_id: u64) {
+    let Some(mut pending) = self.pending.remove(&request_id) else {
+      return;
+    };
+    pending.stats.failed.extend(pending.awaiting);
+    pending.stats.failed.sort();
+    pending.reply_to.tell(pending.stats);
+  }
+}
+
+impl Actor for ClusterShardingQueryActor {
+  fn receive(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(query) = message.downcast_ref::<ClusterShardingQuery>() {
+      return self.on_query(ctx, query);
+    }
+    if let Some(request) = message.downcast_ref::<RegionStatsRequest>() {
+      self.on_stats_request(ctx, request);
+      return Ok(());
+    }
+    if let Some(reply) = message.downcast_ref::<RegionStatsReply>() {
+      self.on_stats_reply(ctx, reply);
+      return Ok(());
+    }
+    if let Some(timeout) = message.downcast_ref::<StatsTimeout>() {
+      self.complete(timeout.request_id);
+    }
+    Ok(())
+  }
+}
+
+/// Resolves the query actor of `authority` when the identity table reports it reachable.
+fn peer_query_actor(
+  ctx: &ActorContext<'_>,
+  identities: &mut IdentityTable,
+  authority: &str,
+  relative: &str,
+) -> Option<ActorRef> {
+  // IdentityTable は到達性と隔離の判定に使い、参照はシステム名を含む正規パスから解決する
+  if !matches!(identities.resolve(authority, relative), Ok(ResolveResult::Ready { .. })) {
+    return None;
+  }
+  let system = ctx.system();
+  let canonical = format!("{REMOTE_ACTOR_PATH_SCHEME}://{}@{authority}{relative}", system.name());
+  let path = ActorPathParser::parse(&canonical).ok()?;
+  system.resolve_actor_ref(path).ok()
+}
@@ -0,0 +1,224 @@
+//! Serializer for the stats protocol exchanged between cluster sharding query actors.
+
+#[cfg(test)]
+#[path = "cluster_sharding_query_serializer_test.rs"]
+mod tests;
+
+use alloc::{
+  borrow::Cow,
+  boxed::Box,
+  collections::BTreeMap,
+  string::{String, ToString},
+  vec::Vec,
+};
+use core::any::{Any, TypeId};
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  SerializationError, Serializer, SerializerId, SerializerWithStringManifest,
+  contribution::SerializationRegistryContributor, serialization_registry::SerializationRegistry,
+};
+use fraktor_cluster_core_kernel_rs::sharding::ShardRegionStats;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{region_stats_reply::RegionStatsReply, region_stats_request::RegionStatsRequest};
+
+/// Stable serializer identifier for the cluster sharding query stats protocol.
+pub(crate) const CLUSTER_SHARDING_QUERY_SERIALIZER_ID: SerializerId = SerializerId::from_raw(45);
+const REGION_STATS_REQUEST_MANIFEST: &str = "A";
+const REGION_STATS_REPLY_MANIFEST: &str = "B";
+const REGION_STATS_REQUEST_BINDING: &str = "RegionStatsRequest";
+const REGION_STATS_REPLY_BINDING: &str = "RegionStatsReply";
+
+/// Serializes [`RegionStatsRequest`] and [`RegionStatsReply`] sent to remote query actors.
+pub(crate) struct ClusterShardingQuerySerializer {
+  id: SerializerId,
+}
+
+impl ClusterShardingQuerySerializer {
+  /// Creates a serializer with the provided identifier.
+  pub(crate) const fn new(id: SerializerId) -> Self {
+    Self { id }
+  }
+
+  fn encode_request(request: &RegionStatsRequest) -> Result<Vec<u8>, SerializationError> {
+    let mut buffer = Vec::new();
+    write_u64(&mut buffer, request.request_id);
+    write_string(&mut buffer, &request.requester)?;
+    Ok(buffer)
+  }
+
+  fn decode_request(bytes: &[u8]) -> Result<RegionStatsRequest, SerializationError> {
+    let mut cursor = 0;
+    let request_id = read_u64(bytes, &mut cursor)?;
+    let requester = read_string(bytes, &mut cursor)?;
+    ensure_finished(bytes, cursor)?;
+    Ok(RegionStatsRequest { request_id, requester })
+  }
+
+  fn encode_reply(reply: &RegionStatsReply) -> Result<Vec<u8>, SerializationError> {
+    let mut buffer = Vec::new();
+    write_u64(&mut buffer, reply.request_id);
+    write_string(&mut buffer, &reply.authority)?;
+    write_u32(&mut buffer, reply.stats.entity_counts.len())?;
+    for (kind, count) in &reply.stats.entity_counts {
+      write_string(&mut buffer, kind)?;
+      write_u64(&mut buffer, u64::try_from(*count).map_err(|_| SerializationError::InvalidFormat)?);
+    }
+    Ok(buffer)
+  }
+
+  fn decode_reply(bytes: &[u8]) -> Result<RegionStatsReply, SerializationError> {
+    let mut cursor = 0;
+    let request_id = read_u64(bytes, &mut cursor)?;
+    let authority = read_string(bytes, &mut cursor)?;
+    let kind_count = read_u32(bytes, &mut cursor)?;
+    let mut entity_counts = BTreeMap::new();
+    for _ in 0..kind_count {
+      let kind = read_string(bytes, &mut cursor)?;
+      let count = usize::try_from(read_u64(bytes, &mut cursor)?).map_err(|_| SerializationError::InvalidFormat)?;
+      entity_counts.insert(kind, count);
+    }
+    ensure_finished(bytes, cursor)?;
+    Ok(RegionStatsReply { request_id, authority, stats: ShardRegionStats { entity_counts } })
+  }
+}
+
+impl Serializer for ClusterShardingQuerySerializer {
+  fn identifier(&self) -> SerializerId {
+    self.id
+  }
+
+  fn include_manifest(&self) -> bool {
+    true
+  }
+
+  fn to_binary(&self, message: &(dyn Any + Send + Sync)) -> Result<Vec<u8>, SerializationError> {
+    if let Some(request) = message.downcast_ref::<RegionStatsRequest>() {
+      return Self::encode_request(request);
+    }
+    if let Some(reply) = message.downcast_ref::<RegionStatsReply>() {
+      return Self::encode_reply(reply);
+    }
+    Err(SerializationError::InvalidFormat)
+  }
+
+  fn from_binary(
+    &self,
+    bytes: &[u8],
+    type_hint: Option<TypeId>,
+  ) -> Result<Box<dyn Any + Send + Sync>, SerializationError> {
+    match type_hint {
+      | Some(type_id) if type_id == TypeId::of::<RegionStatsRequest>() => Ok(Box::new(Self::decode_request(bytes)?)),
+      | Some(type_id) if type_id == TypeId::of::<RegionStatsReply>() => Ok(Box::new(Self::decode_reply(bytes)?)),
+      | _ => Err(SerializationError::InvalidFormat),
+    }
+  }
+
+  fn as_any(&self) -> &(dyn Any + Send + Sync) {
+    self
+  }
+
+  fn as_string_manifest(&self) -> Option<&dyn SerializerWithStringManifest> {
+    Some(self)
+  }
+}
+
+impl SerializerWithStringManifest for ClusterShardingQuerySerializer {
+  fn manifest(&self, message: &(dyn Any + Send + Sync)) -> Cow<'_, str> {
+    if message.downcast_ref::<RegionStatsRequest>().is_some() {
+      return Cow::Borrowed(REGION_STATS_REQUEST_MANIFEST);
+    }
+    if message.downcast_ref::<RegionStatsReply>().is_some() {
+      return Cow::Borrowed(REGION_STATS_REPLY_MANIFEST);
+    }
+    Cow::Borrowed("")
+  }
+
+  fn from_binary_with_manifest(
+    &self,
+    bytes: &[u8],
+    manifest: &str,
+  ) -> Result<Box<dyn Any + Send + Sync>, SerializationError> {
+    match manifest {
+      | REGION_STATS_REQUEST_MANIFEST => Ok(Box::new(Self::decode_request(bytes)?)),
+      | REGION_STATS_REPLY_MANIFEST => Ok(Box::new(Self::decode_reply(bytes)?)),
+      | other => Err(SerializationError::UnknownManifest(other.to_string())),
+    }
+  }
+}
+
+/// Registers the stats protocol serializer and its type bindings.
+///
+/// Re-registering is idempotent; an id or binding already taken by another serializer is reported
+/// as a collision.
+pub(crate) struct ClusterShardingQuerySerializationContributor;
+
+impl SerializationRegistryContributor for ClusterShardingQuerySerializationContributor {
+  fn contribute(&self, registry: &ArcShared<SerializationRegistry>) -> Result<(), SerializationError> {
+    let id = CLUSTER_SHARDING_QUERY_SERIALIZER_ID;
+    match registry.registered_serializer(id) {
+      | Some(existing) if existing.as_any().downcast_ref::<ClusterShardingQuerySerializer>().is_some() => (),
+      | Some(_) => return Err(SerializationError::SerializerIdCollision(id)),
+      | None => {
+        let serializer: ArcShared<dyn Serializer> = ArcShared::new(ClusterShardingQuerySerializer::new(id));
+        if !registry.register_serializer(id, serializer) {
+          return Err(SerializationError::SerializerIdCollision(id));
+        }
+      },
+    }
+    register_binding::<RegionStatsRequest>(registry, REGION_STATS_REQUEST_BINDING)?;
+    register_binding::<RegionStatsReply>(registry, REGION_STATS_REPLY_BINDING)
+  }
+}
+
+fn register_binding<T: 'static>(registry: &SerializationRegistry, type_name: &str) -> Result<(), SerializationError> {
+  let id = CLUSTER_SHARDING_QUERY_SERIALIZER_ID;
+  match registry.binding_for(TypeId::of::<T>()) {
+    | Some(existing) if existing == id => Ok(()),
+    | Some(existing) => Err(SerializationError::serializer_binding_collision(type_name, existing, id)),
+    | None => registry.register_binding(TypeId::of::<T>(), type_name, id),
+  }
+}
+
+fn write_u32(buffer: &mut Vec<u8>, value: usize) -> Result<(), SerializationError> {
+  let value = u32::try_from(value).map_err(|_| SerializationError::InvalidFormat)?;
+  buffer.extend_from_slice(&value.to_le_bytes());
+  Ok(())
+}
+
+fn write_u64(buffer: &mut Vec<u8>, value: u64) {
+  buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_string(buffer: &mut Vec<u8>, value: &str) -> Result<(), SerializationError> {
+  write_u32(buffer, value.len())?;
+  buffer.extend_from_slice(value.as_bytes());
+  Ok(())
+}
+
+fn read_bytes<'a>(bytes: &'a [u8], cursor: &mut usize, len: usize) -> Result<&'a [u8], SerializationError> {
+  let end = cursor.checked_add(len).ok_or(SerializationError::InvalidFormat)?;
+  let slice = bytes.get(*cursor..end).ok_or(SerializationError::InvalidFormat)?;
+  *cursor = end;
+  Ok(slice)
+}
+
+fn read_u32(bytes: &[u8], cursor: &mut usize) -> Result<u32, SerializationError> {
+  let raw = read_bytes(bytes, cursor, 4)?.try_into().map_err(|_| SerializationError::InvalidFormat)?;
+  Ok(u32::from_le_bytes(raw))
+}
+
+fn read_u64(bytes: &[u8], cursor: &mut usize) -> Result<u64, SerializationError> {
+  let raw = read_bytes(bytes, cursor, 8)?.try_into().map_err(|_| SerializationError::InvalidFormat)?;
+  Ok(u64::from_le_bytes(raw))
+}
+
+fn read_string(bytes: &[u8], cursor: &mut usize) -> Result<String, SerializationError> {
+  let len = usize::try_from(read_u32(bytes, cursor)?).map_err(|_| SerializationError::InvalidFormat)?;
+  let raw = read_bytes(bytes, cursor, len)?;
+  String::from_utf8(raw.to_vec()).map_err(|_| SerializationError::InvalidFormat)
+}
+
+const fn ensure_finished(bytes: &[u8], cursor: usize) -> Result<(), SerializationError> {
+  if cursor == bytes.len() { Ok(()) } else { Err(SerializationError::InvalidFormat) }
+}
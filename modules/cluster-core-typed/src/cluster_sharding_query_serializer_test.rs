@@ -0,0 +1,69 @@
+use alloc::{collections::BTreeMap, string::String};
+use core::any::TypeId;
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  SerializationError, Serializer, SerializerWithStringManifest, contribution::SerializationRegistryContributor,
+  default_serialization_setup, serialization_registry::SerializationRegistry,
+};
+use fraktor_cluster_core_kernel_rs::sharding::ShardRegionStats;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::{
+  CLUSTER_SHARDING_QUERY_SERIALIZER_ID, ClusterShardingQuerySerializationContributor, ClusterShardingQuerySerializer,
+  REGION_STATS_REPLY_MANIFEST, REGION_STATS_REQUEST_MANIFEST,
+};
+use crate::{region_stats_reply::RegionStatsReply, region_stats_request::RegionStatsRequest};
+
+fn serializer() -> ClusterShardingQuerySerializer {
+  ClusterShardingQuerySerializer::new(CLUSTER_SHARDING_QUERY_SERIALIZER_ID)
+}
+
+#[test]
+fn region_stats_request_round_trips_with_manifest() {
+  let serializer = serializer();
+  let request = RegionStatsRequest { request_id: 7, requester: String::from("node1:8080") };
+
+  let bytes = serializer.to_binary(&request).expect("serialize request");
+  let decoded = serializer.from_binary_with_manifest(&bytes, REGION_STATS_REQUEST_MANIFEST).expect("deserialize");
+
+  assert_eq!(serializer.manifest(&request), REGION_STATS_REQUEST_MANIFEST);
+  assert_eq!(*decoded.downcast::<RegionStatsRequest>().expect("request"), request);
+}
+
+#[test]
+fn region_stats_reply_round_trips_entity_counts() {
+  let serializer = serializer();
+  let stats =
+    ShardRegionStats { entity_counts: BTreeMap::from([(String::from("user"), 3), (String::from("order"), 1)]) };
+  let reply = RegionStatsReply { request_id: 9, authority: String::from("node2:8080"), stats };
+
+  let bytes = serializer.to_binary(&reply).expect("serialize reply");
+  let decoded = serializer.from_binary_with_manifest(&bytes, REGION_STATS_REPLY_MANIFEST).expect("deserialize");
+
+  assert_eq!(serializer.manifest(&reply), REGION_STATS_REPLY_MANIFEST);
+  assert_eq!(*decoded.downcast::<RegionStatsReply>().expect("reply"), reply);
+}
+
+#[test]
+fn truncated_reply_is_rejected() {
+  let serializer = serializer();
+  let reply =
+    RegionStatsReply { request_id: 1, authority: String::from("node2:8080"), stats: ShardRegionStats::default() };
+  let bytes = serializer.to_binary(&reply).expect("serialize reply");
+
+  let result = serializer.from_binary_with_manifest(&bytes[..bytes.len() - 1], REGION_STATS_REPLY_MANIFEST);
+
+  assert!(matches!(result, Err(SerializationError::InvalidFormat)));
+}
+
+#[test]
+fn contributor_registers_bindings_idempotently() {
+  let setup = default_serialization_setup();
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&setup));
+
+  ClusterShardingQuerySerializationContributor.contribute(&registry).expect("first contribution");
+  ClusterShardingQuerySerializationContributor.contribute(&registry).expect("second contribution");
+
+  assert_eq!(registry.binding_for(TypeId::of::<RegionStatsRequest>()), Some(CLUSTER_SHARDING_QUERY_SERIALIZER_ID));
+  assert_eq!(registry.binding_for(TypeId::of::<RegionStatsReply>()), Some(CLUSTER_SHARDING_QUERY_SERIALIZER_ID));
+}
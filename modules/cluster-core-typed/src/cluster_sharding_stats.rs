@@ -0,0 +1,22 @@
+//! Cluster-wide grain statistics.
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use fraktor_cluster_core_kernel_rs::sharding::ShardRegionStats;
+
+/// Reply to [`ClusterShardingQuery::GetClusterShardingStats`](crate::ClusterShardingQuery).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClusterShardingStats {
+  /// Statistics of the regions that answered, keyed by member authority.
+  pub regions: BTreeMap<String, ShardRegionStats>,
+  /// Regions that could not be reached or did not answer in time.
+  pub failed:  Vec<String>,
+}
+
+impl ClusterShardingStats {
+  /// Returns the number of active grains of `kind` over all regions that answered.
+  #[must_use]
+  pub fn entity_count(&self, kind: &str) -> usize {
+    self.regions.values().filter_map(|stats| stats.entity_counts.get(kind)).sum()
+  }
+}
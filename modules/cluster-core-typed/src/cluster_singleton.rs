@@ -0,0 +1,98 @@
+//! Typed cluster singleton access point.
+
+#[cfg(test)]
+#[path = "cluster_singleton_test.rs"]
+mod tests;
+
+use alloc::format;
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{actor_ref::ActorRef, messaging::AnyMessage, props::Props},
+  system::ActorSystem,
+};
+use fraktor_actor_core_typed_rs::{TypedActorRef, TypedActorSystem};
+use fraktor_cluster_core_kernel_rs::{
+  extension::{ClusterApi, ClusterApiError},
+  singleton::{ClusterSingletonManager, ClusterSingletonProxy},
+};
+
+use crate::{ClusterSingletonError, SingletonActor, singleton_membership_feed::SingletonMembershipFeed};
+
+/// Typed entry point for running actors once in the cluster.
+///
+/// This is the fraktor equivalent of Pekko's typed `ClusterSingleton` extension.
+pub struct ClusterSingleton {
+  cluster: ClusterApi,
+  system:  ActorSystem,
+}
+
+impl ClusterSingleton {
+  /// Retrieves the typed cluster singleton facade from a typed actor system.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the cluster extension has not been installed.
+  pub fn get<S>(system: &TypedActorSystem<S>) -> Result<Self, ClusterApiError>
+  where
+    S: Send + Sync + 'static, {
+    let cluster = ClusterApi::try_from_system(system.as_untyped())?;
+    Ok(Self { cluster, system: system.as_untyped().clone() })
+  }
+
+  /// Starts the singleton manager and proxy for `singleton` on the local member.
+  ///
+  /// The manager runs the singleton while the local member is the oldest with the configured
+  /// role; the returned proxy reference routes messages to wherever the singleton runs and
+  /// buffers them during hand-over. Membership is followed through cluster events.
+  ///
+  /// # Errors
+  ///
+  /// - [`ClusterSingletonError::InvalidConfig`] when the settings are invalid.
+  /// - [`ClusterSingletonError::SpawnFailed`] when an actor cannot be spawned, for example because
+  ///   a singleton with the same name was already initialized.
+  pub fn init<M>(&self, singleton: SingletonActor<M>) -> Result<TypedActorRef<M>, ClusterSingletonError>
+  where
+    M: Send + Sync + 'static, {
+    let (name, props, settings, stop_message) = singleton.into_parts();
+    let manager_config = settings.to_manager_config(&name);
+    let proxy_config = settings.to_proxy_config(&name);
+    manager_config.validate().map_err(ClusterSingletonError::InvalidConfig)?;
+    proxy_config.validate().map_err(ClusterSingletonError::InvalidConfig)?;
+
+    let self_authority = self.cluster.self_authority();
+    let singleton_props = props.into_untyped();
+    let stop_message = stop_message.map(AnyMessage::new);
+    let manager_props = Props::from_fn(move || {
+      let manager = ClusterSingletonManager::new(singleton_props.clone(), manager_config.clone(), &self_authority);
+      match &stop_message {
+        | Some(message) => manager.with_termination_message(message.clone()),
+        | None => manager,
+      }
+    })
+    .with_name(format!("singletonManager{name}"));
+    let manager = self.spawn(&manager_props)?;
+
+    let proxy_props = Props::from_fn(move || ClusterSingletonProxy::new(proxy_config.clone()))
+      .with_name(format!("singletonProxy{name}"));
+    let proxy = self.spawn(&proxy_props)?;
+
+    let cluster = self.cluster.clone();
+    let feed_props = {
+      let manager = manager.clone();
+      let proxy = proxy.clone();
+      Props::from_fn(move || SingletonMembershipFeed::new(cluster.clone(), manager.clone(), proxy.clone()))
+        .with_name(format!("singletonMembership{name}"))
+    };
+    self.spawn(&feed_props)?;
+    Ok(TypedActorRef::from_untyped(proxy))
+  }
+
+  fn spawn(&self, props: &Props) -> Result<ActorRef, ClusterSingletonError> {
+    self
+      .system
+      .extended()
+      .spawn_system_actor(props)
+      .map(|child| child.into_actor_ref())
+      .map_err(ClusterSingletonError::SpawnFailed)
+  }
+}
@@ -0,0 +1,14 @@
+//! Errors returned when initializing a typed cluster singleton.
+
+use fraktor_actor_core_kernel_rs::actor::spawn::SpawnError;
+use fraktor_cluster_core_kernel_rs::singleton::ClusterSingletonConfigError;
+
+/// Errors raised by [`ClusterSingleton::init`](crate::ClusterSingleton::init).
+#[derive(Debug)]
+pub enum ClusterSingletonError {
+  /// The singleton settings derive an invalid manager or proxy configuration.
+  InvalidConfig(ClusterSingletonConfigError),
+  /// The manager, proxy or membership feed could not be spawned, for example because a
+  /// singleton with the same name was already initialized.
+  SpawnFailed(SpawnError),
+}
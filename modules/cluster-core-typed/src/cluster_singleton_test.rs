@@ -0,0 +1,80 @@
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::actor::{
+  extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig,
+};
+use fraktor_actor_core_typed_rs::{TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::{
+  cluster_provider::NoopClusterProvider,
+  extension::{ClusterApiError, ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+  singleton::ClusterSingletonConfigError,
+};
+
+use crate::{ClusterSingleton, ClusterSingletonConfig, ClusterSingletonError, SingletonActor};
+
+#[derive(Debug)]
+struct TestMsg;
+
+fn typed_system(installers: ExtensionInstallers) -> TypedActorSystem<TestMsg> {
+  let props = TypedProps::<TestMsg>::from_behavior_factory(Behaviors::ignore);
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(installers);
+  TypedActorSystem::create_from_props(&props, config).expect("typed system")
+}
+
+fn cluster_system() -> TypedActorSystem<TestMsg> {
+  let config = ClusterExtensionConfig::new().with_advertised_address("node1:8080");
+  let installer =
+    ClusterExtensionInstaller::new(config, |_event_stream, _block_list, _address| Box::new(NoopClusterProvider::new()));
+  let system = typed_system(ExtensionInstallers::default().with_extension_installer(installer));
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  system
+}
+
+fn singleton(name: &str) -> SingletonActor<TestMsg> {
+  SingletonActor::new(TypedProps::<TestMsg>::from_behavior_factory(Behaviors::ignore), name)
+}
+
+#[test]
+fn get_fails_when_the_cluster_extension_is_not_installed() {
+  let system = typed_system(ExtensionInstallers::default());
+
+  assert!(matches!(ClusterSingleton::get(&system), Err(ClusterApiError::ExtensionNotInstalled)));
+}
+
+#[test]
+fn init_returns_the_proxy_of_the_singleton() {
+  let system = cluster_system();
+  let singletons = ClusterSingleton::get(&system).expect("cluster singleton");
+
+  let proxy = singletons.init(singleton("counter")).expect("init");
+
+  let path = proxy.as_untyped().path().expect("proxy has a path");
+  assert!(path.to_string().ends_with("/singletonProxycounter"), "unexpected path {path}");
+}
+
+#[test]
+fn init_rejects_invalid_settings() {
+  let system = cluster_system();
+  let singletons = ClusterSingleton::get(&system).expect("cluster singleton");
+  let settings = ClusterSingletonConfig::new().with_buffer_size(u32::MAX);
+
+  let result = singletons.init(singleton("counter").with_settings(settings));
+
+  assert!(matches!(
+    result,
+    Err(ClusterSingletonError::InvalidConfig(ClusterSingletonConfigError::BufferSizeOutOfRange { .. }))
+  ));
+}
+
+#[test]
+fn init_rejects_a_second_singleton_with_the_same_name() {
+  let system = cluster_system();
+  let singletons = ClusterSingleton::get(&system).expect("cluster singleton");
+  singletons.init(singleton("counter")).expect("first init");
+
+  let result = singletons.init(singleton("counter"));
+
+  assert!(matches!(result, Err(ClusterSingletonError::SpawnFailed(_))));
+}
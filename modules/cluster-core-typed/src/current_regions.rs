@@ -0,0 +1,10 @@
+//! Members hosting grains.
+
+use alloc::{string::String, vec::Vec};
+
+/// Reply to [`ClusterShardingQuery::GetCurrentRegions`](crate::ClusterShardingQuery).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CurrentRegions {
+  /// Authorities of the members grains are placed on.
+  pub regions: Vec<String>,
+}
@@ -0,0 +1,76 @@
+//! Typed distributed-data access point.
+
+#[cfg(test)]
+#[path = "distributed_data_test.rs"]
+mod tests;
+
+use core::time::Duration;
+
+use fraktor_actor_core_typed_rs::{TypedActorRef, TypedActorSystem};
+use fraktor_cluster_core_kernel_rs::ddata::{
+  ReplicatorCommand, ReplicatorData, ReplicatorExtension, ReplicatorSettings, SelfUniqueAddress,
+};
+
+use crate::{DistributedDataError, ReplicatorMessageAdapter};
+
+/// Typed facade for the Replicator extension of an actor system.
+///
+/// This is the fraktor equivalent of Pekko's typed `DistributedData` extension.
+#[derive(Clone)]
+pub struct DistributedData {
+  replicator:   TypedActorRef<ReplicatorCommand>,
+  self_address: SelfUniqueAddress,
+  settings:     ReplicatorSettings,
+}
+
+impl DistributedData {
+  /// Retrieves the typed distributed-data facade from a typed actor system.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the Replicator extension has not been installed.
+  pub fn get<M>(system: &TypedActorSystem<M>) -> Result<Self, DistributedDataError>
+  where
+    M: Send + Sync + 'static, {
+    let extension = system
+      .as_untyped()
+      .extended()
+      .extension_by_type::<ReplicatorExtension>()
+      .ok_or(DistributedDataError::ExtensionNotInstalled)?;
+    Ok(Self {
+      replicator:   TypedActorRef::from_untyped(extension.replicator()),
+      self_address: extension.self_unique_address().clone(),
+      settings:     extension.settings().clone(),
+    })
+  }
+
+  /// Returns the Replicator actor as a typed reference.
+  #[must_use]
+  pub fn replicator(&self) -> TypedActorRef<ReplicatorCommand> {
+    self.replicator.clone()
+  }
+
+  /// Returns the address of the local member used by node-local CRDT updates.
+  #[must_use]
+  pub const fn self_unique_address(&self) -> &SelfUniqueAddress {
+    &self.self_address
+  }
+
+  /// Returns the settings the Replicator runs with.
+  #[must_use]
+  pub const fn settings(&self) -> &ReplicatorSettings {
+    &self.settings
+  }
+
+  /// Creates a message adapter for behaviors of message type `M` working with data type `D`.
+  ///
+  /// `unexpected_ask_timeout` bounds every ask issued through the adapter and should exceed the
+  /// timeouts of the requested consistency levels.
+  #[must_use]
+  pub fn message_adapter<M, D>(&self, unexpected_ask_timeout: Duration) -> ReplicatorMessageAdapter<M, D>
+  where
+    M: Send + Sync + 'static,
+    D: ReplicatorData, {
+    ReplicatorMessageAdapter::new(self.replicator(), unexpected_ask_timeout)
+  }
+}
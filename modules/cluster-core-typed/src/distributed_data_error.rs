@@ -0,0 +1,9 @@
+//! Errors returned when acquiring the typed distributed-data facade.
+
+/// Errors raised while retrieving [`DistributedData`](crate::DistributedData) from the actor
+/// system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DistributedDataError {
+  /// The Replicator extension has not been installed.
+  ExtensionNotInstalled,
+}
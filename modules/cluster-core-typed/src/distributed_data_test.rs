@@ -0,0 +1,48 @@
+use core::time::Duration;
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::actor::{
+  extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig,
+};
+use fraktor_actor_core_typed_rs::{TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::ddata::{
+  GCounter, ReplicatorExtensionInstaller, ReplicatorSettings, SelfUniqueAddress,
+};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+
+use crate::{DistributedData, DistributedDataError};
+
+#[derive(Debug)]
+struct TestMsg;
+
+fn typed_system(installers: ExtensionInstallers) -> TypedActorSystem<TestMsg> {
+  let props = TypedProps::<TestMsg>::from_behavior_factory(Behaviors::ignore);
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(installers);
+  TypedActorSystem::create_from_props(&props, config).expect("typed system")
+}
+
+#[test]
+fn get_fails_when_the_replicator_extension_is_not_installed() {
+  let system = typed_system(ExtensionInstallers::default());
+
+  assert!(matches!(DistributedData::get(&system), Err(DistributedDataError::ExtensionNotInstalled)));
+}
+
+#[test]
+fn get_exposes_the_installed_replicator() {
+  let self_address = SelfUniqueAddress::new(UniqueAddress::new(Address::new("sys", "node-a", 2552), 1));
+  let settings = ReplicatorSettings::new().with_name("typed-replicator");
+  let installer = ReplicatorExtensionInstaller::new(self_address.clone()).with_settings(settings.clone());
+  let system = typed_system(ExtensionInstallers::default().with_extension_installer(installer));
+
+  let distributed_data = DistributedData::get(&system).expect("distributed data");
+
+  assert_eq!(distributed_data.self_unique_address(), &self_address);
+  assert_eq!(distributed_data.settings(), &settings);
+  let path = distributed_data.replicator().as_untyped().path().expect("replicator has a path");
+  assert!(path.to_string().ends_with("/typed-replicator"), "unexpected path {path}");
+  let adapter = distributed_data.message_adapter::<TestMsg, GCounter>(Duration::from_secs(3));
+  assert_eq!(adapter.unexpected_ask_timeout(), Duration::from_secs(3));
+}
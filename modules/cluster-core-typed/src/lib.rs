@@ -15,24 +15,82 @@ mod cluster;
 mod cluster_command;
 mod cluster_event_subscription;
 mod cluster_identity;
+mod cluster_receptionist;
+mod cluster_receptionist_config;
+mod cluster_receptionist_entry;
+mod cluster_receptionist_message;
 mod cluster_setup;
+mod cluster_sharding_queries;
+mod cluster_sharding_query;
+mod cluster_sharding_query_actor;
+mod cluster_sharding_query_serializer;
+mod cluster_sharding_stats;
+mod cluster_singleton;
 mod cluster_singleton_config;
+mod cluster_singleton_error;
 mod cluster_state_subscription;
 mod cluster_state_subscription_result;
+mod current_regions;
+mod distributed_data;
+mod distributed_data_error;
 mod grain_ref;
 mod grain_type_key;
+mod region_stats_reply;
+mod region_stats_request;
+mod replicator_message_adapter;
 mod self_removed;
 mod self_up;
+mod shard_coordination;
+mod shard_coordination_error;
+mod shard_coordinator_replicator;
+mod sharded_daemon_process;
+mod sharded_daemon_process_command;
+mod sharded_daemon_process_error;
+mod sharded_daemon_process_keeper;
+mod sharded_daemon_process_settings;
+mod sharded_daemon_process_state;
+mod sharding_consumer_controller;
+mod sharding_envelope;
+mod sharding_producer_controller;
+mod sharding_producer_controller_command;
+mod sharding_producer_controller_config;
+mod sharding_producer_controller_request_next;
+mod singleton_actor;
+mod singleton_membership_feed;
 
 pub use cluster::Cluster;
 pub use cluster_command::ClusterCommand;
 pub use cluster_event_subscription::ClusterEventSubscription;
 pub use cluster_identity::ClusterIdentity;
+pub use cluster_receptionist::ClusterReceptionist;
+pub use cluster_receptionist_config::ClusterReceptionistConfig;
 pub use cluster_setup::ClusterSetup;
+pub use cluster_sharding_queries::ClusterShardingQueries;
+pub use cluster_sharding_query::ClusterShardingQuery;
+pub use cluster_sharding_stats::ClusterShardingStats;
+pub use cluster_singleton::ClusterSingleton;
 pub use cluster_singleton_config::ClusterSingletonConfig;
+pub use cluster_singleton_error::ClusterSingletonError;
 pub use cluster_state_subscription::ClusterStateSubscription;
 pub use cluster_state_subscription_result::ClusterStateSubscriptionResult;
+pub use current_regions::CurrentRegions;
+pub use distributed_data::DistributedData;
+pub use distributed_data_error::DistributedDataError;
 pub use grain_ref::GrainRef;
 pub use grain_type_key::GrainTypeKey;
+pub use replicator_message_adapter::ReplicatorMessageAdapter;
 pub use self_removed::SelfRemoved;
 pub use self_up::SelfUp;
+pub use shard_coordination::ShardCoordination;
+pub use shard_coordination_error::ShardCoordinationError;
+pub use sharded_daemon_process::ShardedDaemonProcess;
+pub use sharded_daemon_process_command::ShardedDaemonProcessCommand;
+pub use sharded_daemon_process_error::ShardedDaemonProcessError;
+pub use sharded_daemon_process_settings::ShardedDaemonProcessSettings;
+pub use sharding_consumer_controller::ShardingConsumerController;
+pub use sharding_envelope::ShardingEnvelope;
+pub use sharding_producer_controller::ShardingProducerController;
+pub use sharding_producer_controller_command::ShardingProducerControllerCommand;
+pub use sharding_producer_controller_config::ShardingProducerControllerConfig;
+pub use sharding_producer_controller_request_next::ShardingProducerControllerRequestNext;
+pub use singleton_actor::SingletonActor;
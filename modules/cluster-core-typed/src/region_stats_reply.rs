@@ -0,0 +1,16 @@
+//! Local statistics returned to the member aggregating a stats query.
+
+use alloc::string::String;
+
+use fraktor_cluster_core_kernel_rs::sharding::ShardRegionStats;
+
+/// Local statistics returned to the member aggregating a stats query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RegionStatsReply {
+  /// Identifier of the stats query on the requesting member.
+  pub(crate) request_id: u64,
+  /// Authority of the member whose statistics are reported.
+  pub(crate) authority:  String,
+  /// Grain counts of the reporting member.
+  pub(crate) stats:      ShardRegionStats,
+}
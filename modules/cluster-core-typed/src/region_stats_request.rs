@@ -0,0 +1,15 @@
+//! Request for the local statistics of another member's query actor.
+
+use alloc::string::String;
+
+/// Request for the local statistics sent to the query actors of the other members.
+///
+/// The reply is routed back to the query actor of `requester`, so the request carries no actor
+/// reference and can be serialized for remote members.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RegionStatsRequest {
+  /// Identifier of the stats query on the requesting member.
+  pub(crate) request_id: u64,
+  /// Authority of the member aggregating the stats query.
+  pub(crate) requester:  String,
+}
@@ -0,0 +1,196 @@
+//! Adapter translating Replicator responses into the message type of a typed behavior.
+
+#[cfg(test)]
+#[path = "replicator_message_adapter_test.rs"]
+mod tests;
+
+use alloc::{boxed::Box, collections::BTreeMap, format, string::String};
+use core::time::Duration;
+
+use fraktor_actor_core_typed_rs::{
+  TypedActorRef,
+  actor::{AskOnContextError, TypedActorContext},
+  message_adapter::AdapterError,
+};
+use fraktor_cluster_core_kernel_rs::ddata::{
+  Delete, DeleteResponse, Get, GetResponse, Key, ReplicatorCommand, ReplicatorData, Subscribe, SubscribeResponse,
+  Unsubscribe, Update, UpdateResponse, UpdateWriteOutcome,
+};
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedAccess, SharedLock};
+
+type ChangeAdapter<M, D> = Box<dyn Fn(SubscribeResponse<D>) -> M + Send + Sync>;
+
+struct AdapterSubscriptions<M, D: ReplicatorData> {
+  adapters:   BTreeMap<String, ChangeAdapter<M, D>>,
+  subscriber: Option<TypedActorRef<SubscribeResponse<D>>>,
+}
+
+/// Lets a behavior of message type `M` talk to the Replicator about data of type `D`.
+///
+/// Responses of [`ask_get`](Self::ask_get), [`ask_update`](Self::ask_update) and
+/// [`ask_delete`](Self::ask_delete) are mapped to `M` through the typed ask machinery of the
+/// actor context; a missing response within the unexpected-ask timeout is reported as the
+/// failure or timeout response of the command. Subscription notifications are delivered through
+/// a single message adapter that dispatches on the key of the notification.
+///
+/// Clones share their subscriptions. The adapter must be used from the actor it was created for.
+pub struct ReplicatorMessageAdapter<M, D: ReplicatorData> {
+  replicator:             TypedActorRef<ReplicatorCommand>,
+  unexpected_ask_timeout: Duration,
+  subscriptions:          SharedLock<AdapterSubscriptions<M, D>>,
+}
+
+impl<M, D> ReplicatorMessageAdapter<M, D>
+where
+  M: Send + Sync + 'static,
+  D: ReplicatorData,
+{
+  /// Creates an adapter sending commands to `replicator`.
+  #[must_use]
+  pub fn new(replicator: TypedActorRef<ReplicatorCommand>, unexpected_ask_timeout: Duration) -> Self {
+    let subscriptions = AdapterSubscriptions { adapters: BTreeMap::new(), subscriber: None };
+    Self {
+      replicator,
+      unexpected_ask_timeout,
+      subscriptions: SharedLock::new_with_driver::<DefaultMutex<_>>(subscriptions),
+    }
+  }
+
+  /// Returns the timeout applied to asks issued through this adapter.
+  #[must_use]
+  pub const fn unexpected_ask_timeout(&self) -> Duration {
+    self.unexpected_ask_timeout
+  }
+
+  /// Reads the value of a key and delivers the mapped [`GetResponse`] to the actor.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`AskOnContextError`] when the response cannot be piped to the actor.
+  pub fn ask_get<C, F>(
+    &self,
+    ctx: &mut TypedActorContext<'_, M>,
+    get: Get<D, C>,
+    response_adapter: F,
+  ) -> Result<(), AskOnContextError>
+  where
+    C: Clone + Send + Sync + 'static,
+    F: Fn(GetResponse<D, C>) -> M + Send + Sync + 'static, {
+    let failure = get.failure();
+    let mut replicator = self.replicator.clone();
+    ctx.ask(
+      &mut replicator,
+      |reply_to: TypedActorRef<GetResponse<D, C>>| ReplicatorCommand::get(get, reply_to.into_untyped()),
+      move |response| response_adapter(response.unwrap_or_else(|_| failure.clone())),
+      self.unexpected_ask_timeout,
+    )
+  }
+
+  /// Applies `modify` to the value of a key and delivers the mapped [`UpdateResponse`] to the
+  /// actor.
+  ///
+  /// `modify` runs inside the Replicator and must not block.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`AskOnContextError`] when the response cannot be piped to the actor.
+  pub fn ask_update<C, U, F>(
+    &self,
+    ctx: &mut TypedActorContext<'_, M>,
+    update: Update<D, C>,
+    modify: U,
+    response_adapter: F,
+  ) -> Result<(), AskOnContextError>
+  where
+    C: Clone + Send + Sync + 'static,
+    U: Fn(Option<&D>) -> Result<D, String> + Send + Sync + 'static,
+    F: Fn(UpdateResponse<D, C>) -> M + Send + Sync + 'static, {
+    let timeout = update.write_response(UpdateWriteOutcome::Timeout);
+    let mut replicator = self.replicator.clone();
+    ctx.ask(
+      &mut replicator,
+      |reply_to: TypedActorRef<UpdateResponse<D, C>>| {
+        ReplicatorCommand::update(update, modify, reply_to.into_untyped())
+      },
+      move |response| response_adapter(response.unwrap_or_else(|_| timeout.clone())),
+      self.unexpected_ask_timeout,
+    )
+  }
+
+  /// Deletes a key and delivers the mapped [`DeleteResponse`] to the actor.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`AskOnContextError`] when the response cannot be piped to the actor.
+  pub fn ask_delete<C, F>(
+    &self,
+    ctx: &mut TypedActorContext<'_, M>,
+    delete: Delete<D, C>,
+    response_adapter: F,
+  ) -> Result<(), AskOnContextError>
+  where
+    C: Clone + Send + Sync + 'static,
+    F: Fn(DeleteResponse<D, C>) -> M + Send + Sync + 'static, {
+    let timeout = DeleteResponse::Timeout { key: delete.key().clone(), request: delete.request().cloned() };
+    let mut replicator = self.replicator.clone();
+    ctx.ask(
+      &mut replicator,
+      |reply_to: TypedActorRef<DeleteResponse<D, C>>| ReplicatorCommand::delete(delete, reply_to.into_untyped()),
+      move |response| response_adapter(response.unwrap_or_else(|_| timeout.clone())),
+      self.unexpected_ask_timeout,
+    )
+  }
+
+  /// Subscribes the actor to changes of `key`, mapping each notification with
+  /// `response_adapter`.
+  ///
+  /// Subscribing to the same key again replaces its mapping.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`AdapterError`] when the subscription message adapter cannot be registered.
+  pub fn subscribe<F>(
+    &self,
+    ctx: &mut TypedActorContext<'_, M>,
+    key: Key<D>,
+    response_adapter: F,
+  ) -> Result<(), AdapterError>
+  where
+    F: Fn(SubscribeResponse<D>) -> M + Send + Sync + 'static, {
+    let subscriptions = self.subscriptions.clone();
+    let subscriber = ctx.message_adapter(move |response: SubscribeResponse<D>| {
+      subscriptions.with_read(|subscriptions| match subscriptions.adapters.get(response.key().id()) {
+        | Some(adapter) => Ok(adapter(response)),
+        | None => Err(AdapterError::Custom(format!("no subscription for key {}", response.key().id()))),
+      })
+    })?;
+    self.subscriptions.with_write(|subscriptions| {
+      subscriptions.adapters.insert(String::from(key.id()), Box::new(response_adapter));
+      subscriptions.subscriber = Some(subscriber.clone());
+    });
+    let mut replicator = self.replicator.clone();
+    replicator.tell(ReplicatorCommand::subscribe(Subscribe::new(key, subscriber.into_untyped())));
+    Ok(())
+  }
+
+  /// Stops delivering changes of `key` to the actor.
+  pub fn unsubscribe(&self, key: &Key<D>) {
+    let subscriber = self
+      .subscriptions
+      .with_write(|subscriptions| subscriptions.adapters.remove(key.id()).and(subscriptions.subscriber.clone()));
+    if let Some(subscriber) = subscriber {
+      let mut replicator = self.replicator.clone();
+      replicator.tell(ReplicatorCommand::unsubscribe(&Unsubscribe::new(key.clone(), subscriber.into_untyped())));
+    }
+  }
+}
+
+impl<M, D: ReplicatorData> Clone for ReplicatorMessageAdapter<M, D> {
+  fn clone(&self) -> Self {
+    Self {
+      replicator:             self.replicator.clone(),
+      unexpected_ask_timeout: self.unexpected_ask_timeout,
+      subscriptions:          self.subscriptions.clone(),
+    }
+  }
+}
@@ -0,0 +1,104 @@
+use alloc::{format, vec::Vec};
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::actor::{error::ActorError, scheduler::SchedulerConfig, setup::ActorSystemConfig};
+use fraktor_actor_core_typed_rs::{TypedActorRef, TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::ddata::{
+  Delete, DeleteResponse, GCounter, GCounterKey, Get, GetResponse, ReadConsistency, ReplicatorCommand, Update,
+  UpdateResponse, WriteConsistency,
+};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+use crate::ReplicatorMessageAdapter;
+
+#[derive(Clone, Debug)]
+enum Command {
+  AskAll,
+  GotGet(GetResponse<GCounter, u32>),
+  GotUpdate(UpdateResponse<GCounter, u32>),
+  GotDelete(DeleteResponse<GCounter, u32>),
+}
+
+type Received = ArcShared<SpinSyncMutex<Vec<Command>>>;
+
+fn wait_until(mut condition: impl FnMut() -> bool) {
+  let deadline = Instant::now() + Duration::from_secs(3);
+  while !condition() {
+    assert!(Instant::now() < deadline, "condition was not met in time");
+    thread::yield_now();
+  }
+}
+
+fn spawn_requester(
+  system: &TypedActorSystem<Command>,
+  adapter: ReplicatorMessageAdapter<Command, GCounter>,
+  received: &Received,
+) -> TypedActorRef<Command> {
+  let received = received.clone();
+  let props = TypedProps::<Command>::from_behavior_factory(move || {
+    let adapter = adapter.clone();
+    let received = received.clone();
+    Behaviors::receive_message(move |ctx, command: &Command| {
+      match command {
+        | Command::AskAll => {
+          let key = GCounterKey::new("counter");
+          let get = Get::new(key.clone(), ReadConsistency::Local).with_request(1);
+          adapter.ask_get(ctx, get, Command::GotGet).map_err(|error| ActorError::recoverable(format!("{error:?}")))?;
+          let update = Update::new(key.clone(), WriteConsistency::Local).with_request(2);
+          adapter
+            .ask_update(
+              ctx,
+              update,
+              |current: Option<&GCounter>| Ok(current.cloned().unwrap_or_default()),
+              Command::GotUpdate,
+            )
+            .map_err(|error| ActorError::recoverable(format!("{error:?}")))?;
+          let delete = Delete::new(key, WriteConsistency::Local).with_request(3);
+          adapter
+            .ask_delete(ctx, delete, Command::GotDelete)
+            .map_err(|error| ActorError::recoverable(format!("{error:?}")))?;
+        },
+        | response => received.lock().push(response.clone()),
+      }
+      Ok(Behaviors::same())
+    })
+  });
+  let requester = system.as_untyped().actor_of(props.to_untyped()).expect("spawn requester");
+  TypedActorRef::from_untyped(requester.into_actor_ref())
+}
+
+#[test]
+fn unanswered_asks_are_mapped_to_failure_and_timeout_responses() {
+  let props = TypedProps::<Command>::from_behavior_factory(Behaviors::ignore);
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true));
+  let system = TypedActorSystem::create_from_props(&props, config).expect("typed system");
+  // 応答しない Replicator の代わりに、すべてのコマンドを無視するアクターを使う。
+  let silent = TypedProps::<ReplicatorCommand>::from_behavior_factory(Behaviors::ignore);
+  let silent = system.as_untyped().actor_of(silent.to_untyped()).expect("spawn silent replicator");
+  let adapter = ReplicatorMessageAdapter::new(TypedActorRef::from_untyped(silent.into_actor_ref()), Duration::ZERO);
+  let received = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let mut requester = spawn_requester(&system, adapter, &received);
+
+  requester.tell(Command::AskAll);
+  wait_until(|| received.lock().len() == 3);
+
+  let received = received.lock();
+  assert!(
+    received.iter().any(|command| matches!(command, Command::GotGet(GetResponse::Failure { request: Some(1), .. })))
+  );
+  assert!(
+    received
+      .iter()
+      .any(|command| matches!(command, Command::GotUpdate(UpdateResponse::Timeout { request: Some(2), .. })))
+  );
+  assert!(
+    received
+      .iter()
+      .any(|command| matches!(command, Command::GotDelete(DeleteResponse::Timeout { request: Some(3), .. })))
+  );
+  drop(received);
+  system.terminate().expect("terminate");
+}
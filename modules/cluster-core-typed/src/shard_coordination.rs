@@ -0,0 +1,103 @@
+//! Typed access point replicating shard allocations between members.
+
+#[cfg(test)]
+#[path = "shard_coordination_test.rs"]
+mod tests;
+
+use alloc::format;
+use core::time::Duration;
+
+use fraktor_actor_core_kernel_rs::{actor::props::Props, system::ActorSystem};
+use fraktor_actor_core_typed_rs::TypedActorSystem;
+use fraktor_cluster_core_kernel_rs::{
+  ddata::ReplicatorExtension,
+  extension::{ClusterApi, ClusterApiError, ClusterExtension},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{ShardCoordinationError, shard_coordinator_replicator::ShardCoordinatorReplicator};
+
+const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Typed entry point keeping one owner per shard across the cluster.
+///
+/// Kinds placed by shard, configured with
+/// `PartitionIdentityLookup::with_shard_allocation`, are allocated and rebalanced by the member
+/// with the lowest authority only. Shard coordination replicates its decisions to the other
+/// members, and their shard requests and hand-off acknowledgements back, through the
+/// distributed-data Replicator. This corresponds to Pekko's coordinator with the `ddata` state
+/// store mode.
+pub struct ShardCoordination {
+  cluster:   ClusterApi,
+  extension: ArcShared<ClusterExtension>,
+  system:    ActorSystem,
+}
+
+impl ShardCoordination {
+  /// Retrieves the typed shard coordination facade from a typed actor system.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the cluster extension has not been installed.
+  pub fn get<S>(system: &TypedActorSystem<S>) -> Result<Self, ClusterApiError>
+  where
+    S: Send + Sync + 'static, {
+    let cluster = ClusterApi::try_from_system(system.as_untyped())?;
+    let extension = system
+      .as_untyped()
+      .extended()
+      .extension_by_type::<ClusterExtension>()
+      .ok_or(ClusterApiError::ExtensionNotInstalled)?;
+    Ok(Self { cluster, extension, system: system.as_untyped().clone() })
+  }
+
+  /// Starts replicating the allocations of every kind placed by shard, synchronizing every
+  /// second.
+  ///
+  /// # Errors
+  ///
+  /// Returns the errors of [`Self::start_with_sync_interval`].
+  pub fn start(&self) -> Result<(), ShardCoordinationError> {
+    self.start_with_sync_interval(DEFAULT_SYNC_INTERVAL)
+  }
+
+  /// Starts replicating the allocations of every kind placed by shard.
+  ///
+  /// Every member must call this after `start_member`. Allocation changes and shard requests are
+  /// replicated at most every `sync_interval`, which bounds how long lookups of an unallocated
+  /// shard stay pending on members that do not coordinate shards.
+  ///
+  /// # Errors
+  ///
+  /// - [`ShardCoordinationError::ReplicatorNotInstalled`] when the Replicator is not installed.
+  /// - [`ShardCoordinationError::SpawnFailed`] when a replicating actor cannot be spawned, for
+  ///   example because shard coordination was already started.
+  pub fn start_with_sync_interval(&self, sync_interval: Duration) -> Result<(), ShardCoordinationError> {
+    let replicator = self
+      .system
+      .extended()
+      .extension_by_type::<ReplicatorExtension>()
+      .ok_or(ShardCoordinationError::ReplicatorNotInstalled)?;
+    let self_authority = self.cluster.self_authority();
+    for kind in self.extension.sharded_kinds() {
+      let extension = self.extension.clone();
+      let replicator_ref = replicator.replicator();
+      let self_address = replicator.self_unique_address().clone();
+      let self_authority = self_authority.clone();
+      let name = format!("shardCoordinator{kind}");
+      let props = Props::from_fn(move || {
+        ShardCoordinatorReplicator::new(
+          kind.clone(),
+          extension.clone(),
+          self_authority.clone(),
+          replicator_ref.clone(),
+          self_address.clone(),
+          sync_interval,
+        )
+      })
+      .with_name(name);
+      self.system.extended().spawn_system_actor(&props).map_err(ShardCoordinationError::SpawnFailed)?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,13 @@
+//! Errors returned when starting shard coordination.
+
+use fraktor_actor_core_kernel_rs::actor::spawn::SpawnError;
+
+/// Errors raised by [`ShardCoordination::start`](crate::ShardCoordination::start).
+#[derive(Debug)]
+pub enum ShardCoordinationError {
+  /// The distributed-data Replicator replicating the shard allocations is not installed.
+  ReplicatorNotInstalled,
+  /// A replicating actor could not be spawned, for example because shard coordination was
+  /// already started.
+  SpawnFailed(SpawnError),
+}
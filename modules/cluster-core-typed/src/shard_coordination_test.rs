@@ -0,0 +1,80 @@
+use alloc::{boxed::Box, string::String};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::actor::{
+  extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig,
+};
+use fraktor_actor_core_typed_rs::{TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivatedKind, PartitionIdentityLookup},
+  cluster_provider::NoopClusterProvider,
+  ddata::{ReplicatorExtensionInstaller, SelfUniqueAddress},
+  extension::{ClusterApiError, ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+  sharding::{LeastShardAllocationStrategy, ShardAllocationConfig},
+};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+
+use crate::{ShardCoordination, ShardCoordinationError};
+
+#[derive(Debug)]
+struct TestMsg;
+
+fn typed_system(installers: ExtensionInstallers) -> TypedActorSystem<TestMsg> {
+  let props = TypedProps::<TestMsg>::from_behavior_factory(Behaviors::ignore);
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(installers);
+  TypedActorSystem::create_from_props(&props, config).expect("typed system")
+}
+
+fn cluster_installers() -> ExtensionInstallers {
+  let config = ClusterExtensionConfig::new().with_advertised_address("node1:8080");
+  let installer =
+    ClusterExtensionInstaller::new(config, |_event_stream, _block_list, _address| Box::new(NoopClusterProvider::new()))
+      .with_identity_lookup_factory(|| {
+        Box::new(PartitionIdentityLookup::with_defaults().with_shard_allocation(
+          "counter",
+          ShardAllocationConfig::new(),
+          Box::new(LeastShardAllocationStrategy::new(1, 0.1)),
+        ))
+      });
+  ExtensionInstallers::default().with_extension_installer(installer)
+}
+
+fn start_member(system: &TypedActorSystem<TestMsg>) {
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  extension.setup_member_kinds(alloc::vec![ActivatedKind::new(String::from("counter"))]).expect("setup kinds");
+}
+
+#[test]
+fn get_fails_when_the_cluster_extension_is_not_installed() {
+  let system = typed_system(ExtensionInstallers::default());
+
+  assert!(matches!(ShardCoordination::get(&system), Err(ClusterApiError::ExtensionNotInstalled)));
+}
+
+#[test]
+fn start_spawns_one_replicating_actor_per_sharded_kind() {
+  let replicator_installer = ReplicatorExtensionInstaller::new(SelfUniqueAddress::new(UniqueAddress::new(
+    Address::new("sys", "node1", 8080),
+    1,
+  )));
+  let system = typed_system(cluster_installers().with_extension_installer(replicator_installer));
+  start_member(&system);
+  let coordination = ShardCoordination::get(&system).expect("shard coordination");
+
+  coordination.start().expect("start");
+
+  // 同じ kind の複製アクターは二重に起動できない
+  assert!(matches!(coordination.start(), Err(ShardCoordinationError::SpawnFailed(_))));
+}
+
+#[test]
+fn start_fails_when_the_replicator_is_not_installed() {
+  let system = typed_system(cluster_installers());
+  start_member(&system);
+  let coordination = ShardCoordination::get(&system).expect("shard coordination");
+
+  assert!(matches!(coordination.start(), Err(ShardCoordinationError::ReplicatorNotInstalled)));
+}
@@ -0,0 +1,192 @@
+//! Actor replicating the shard allocations of one grain kind between members.
+
+use alloc::{collections::BTreeMap, format, string::String};
+use core::time::Duration;
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext,
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView},
+  },
+  event::logging::LogLevel,
+};
+use fraktor_cluster_core_kernel_rs::{
+  ddata::{
+    LWWMap, LWWMapKey, LWWRegister, LWWRegisterKey, ReplicatorCommand, SelfUniqueAddress, Subscribe, SubscribeResponse,
+    Unsubscribe, Update, UpdateResponse, WriteConsistency,
+  },
+  extension::ClusterExtension,
+  sharding::{ShardAllocationTable, ShardRegionRequests},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+const SYNC_TIMER: &str = "shard-coordinator-sync";
+
+/// Register holding the allocations published by the coordinating member.
+type ShardAllocationRegister = LWWRegister<ShardAllocationTable>;
+
+/// Map holding the shard requests of every member, keyed by member authority.
+type ShardRegionRequestsMap = LWWMap<String, ShardRegionRequests>;
+
+#[derive(Clone, Copy)]
+struct Sync;
+
+/// Replicates the shard allocations of one kind through the distributed-data Replicator.
+///
+/// Only the member with the lowest authority allocates and rebalances shards. Its allocation
+/// table is written to a replicated register and applied by the other members, which in turn
+/// write the shard homes they requested and the hand-offs they acknowledged to a replicated map.
+/// The coordinating member handles those requests, so a hand-off completes once the previous
+/// owner has stopped the grains of the shard.
+pub(crate) struct ShardCoordinatorReplicator {
+  kind:               String,
+  extension:          ArcShared<ClusterExtension>,
+  self_authority:     String,
+  replicator:         ActorRef,
+  self_address:       SelfUniqueAddress,
+  sync_interval:      Duration,
+  published_revision: u64,
+  written_requests:   ShardRegionRequests,
+  member_requests:    BTreeMap<String, ShardRegionRequests>,
+}
+
+impl ShardCoordinatorReplicator {
+  /// Creates a replicator for the shard allocations of `kind`.
+  pub(crate) fn new(
+    kind: String,
+    extension: ArcShared<ClusterExtension>,
+    self_authority: String,
+    replicator: ActorRef,
+    self_address: SelfUniqueAddress,
+    sync_interval: Duration,
+  ) -> Self {
+    Self {
+      kind,
+      extension,
+      self_authority,
+      replicator,
+      self_address,
+      sync_interval,
+      published_revision: 0,
+      written_requests: ShardRegionRequests::default(),
+      member_requests: BTreeMap::new(),
+    }
+  }
+
+  fn allocations_key(&self) -> LWWRegisterKey<ShardAllocationTable> {
+    LWWRegisterKey::new(format!("ShardAllocations-{}", self.kind))
+  }
+
+  fn requests_key(&self) -> LWWMapKey<String, ShardRegionRequests> {
+    LWWMapKey::new(format!("ShardRegionRequests-{}", self.kind))
+  }
+
+  fn sync(&mut self, ctx: &ActorContext<'_>) {
+    // 調整役が交代しても要求を取りこぼさないよう、受信済みの要求を毎周期処理し直す
+    for (authority, requests) in &self.member_requests {
+      self.extension.handle_shard_region_requests(&self.kind, authority, requests);
+    }
+    if let Some(table) = self.extension.shard_allocations(&self.kind)
+      && table.revision > self.published_revision
+    {
+      self.publish_allocations(ctx, table);
+    }
+    if let Some(requests) = self.extension.shard_region_requests(&self.kind)
+      && requests != self.written_requests
+    {
+      self.write_requests(ctx, requests);
+    }
+  }
+
+  fn publish_allocations(&mut self, ctx: &ActorContext<'_>, table: ShardAllocationTable) {
+    self.published_revision = table.revision;
+    let self_address = self.self_address.clone();
+    let update = Update::<ShardAllocationRegister>::new(self.allocations_key(), WriteConsistency::Local);
+    let modify = move |current: Option<&ShardAllocationRegister>| {
+      let register = match current {
+        | Some(register) => register.with_value_with_clock(&self_address, table.clone(), |timestamp, _| timestamp + 1),
+        | None => Some(ShardAllocationRegister::new_with_clock(&self_address, table.clone(), |_, _| 1)),
+      };
+      register.ok_or_else(|| String::from("shard allocation timestamp was reused"))
+    };
+    self.replicator.tell(AnyMessage::new(ReplicatorCommand::update(update, modify, ctx.self_ref())));
+  }
+
+  fn write_requests(&mut self, ctx: &ActorContext<'_>, requests: ShardRegionRequests) {
+    self.written_requests = requests.clone();
+    let self_address = self.self_address.clone();
+    let authority = self.self_authority.clone();
+    let update = Update::<ShardRegionRequestsMap>::new(self.requests_key(), WriteConsistency::Local);
+    let modify = move |current: Option<&ShardRegionRequestsMap>| {
+      let map = current.cloned().unwrap_or_default();
+      Ok::<_, String>(
+        map.put_with_clock(&self_address, authority.clone(), requests.clone(), |timestamp, _| timestamp + 1),
+      )
+    };
+    self.replicator.tell(AnyMessage::new(ReplicatorCommand::update(update, modify, ctx.self_ref())));
+  }
+
+  fn on_requests(&mut self, requests: &ShardRegionRequestsMap) {
+    let mut entries = requests.entries();
+    entries.remove(&self.self_authority);
+    for (authority, member_requests) in &entries {
+      self.extension.handle_shard_region_requests(&self.kind, authority, member_requests);
+    }
+    self.member_requests = entries;
+  }
+}
+
+impl Actor for ShardCoordinatorReplicator {
+  fn pre_start(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    let allocations = Subscribe::new(self.allocations_key(), ctx.self_ref());
+    self.replicator.tell(AnyMessage::new(ReplicatorCommand::subscribe::<ShardAllocationRegister>(allocations)));
+    let requests = Subscribe::new(self.requests_key(), ctx.self_ref());
+    self.replicator.tell(AnyMessage::new(ReplicatorCommand::subscribe::<ShardRegionRequestsMap>(requests)));
+    ctx
+      .timers()
+      .start_timer_with_fixed_delay(SYNC_TIMER, AnyMessage::new(Sync), self.sync_interval)
+      .map_err(|error| ActorError::recoverable(format!("shard coordinator sync timer failed: {error:?}")))?;
+    self.sync(ctx);
+    Ok(())
+  }
+
+  fn receive(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if message.downcast_ref::<Sync>().is_some() {
+      self.sync(ctx);
+      return Ok(());
+    }
+    if let Some(SubscribeResponse::Changed { data, .. }) =
+      message.downcast_ref::<SubscribeResponse<ShardAllocationRegister>>()
+    {
+      self.extension.apply_shard_allocations(&self.kind, data.value().clone());
+      return Ok(());
+    }
+    if let Some(SubscribeResponse::Changed { data, .. }) =
+      message.downcast_ref::<SubscribeResponse<ShardRegionRequestsMap>>()
+    {
+      self.on_requests(data);
+      return Ok(());
+    }
+    if let Some(response) = message.downcast_ref::<UpdateResponse<ShardAllocationRegister>>()
+      && !matches!(response, UpdateResponse::Success { .. })
+    {
+      ctx.log(LogLevel::Warn, format!("shard allocations of {} could not be replicated: {response:?}", self.kind));
+    }
+    if let Some(response) = message.downcast_ref::<UpdateResponse<ShardRegionRequestsMap>>()
+      && !matches!(response, UpdateResponse::Success { .. })
+    {
+      ctx.log(LogLevel::Warn, format!("shard requests of {} could not be replicated: {response:?}", self.kind));
+    }
+    Ok(())
+  }
+
+  fn post_stop(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    let allocations = Unsubscribe::new(self.allocations_key(), ctx.self_ref());
+    self.replicator.tell(AnyMessage::new(ReplicatorCommand::unsubscribe::<ShardAllocationRegister>(&allocations)));
+    let requests = Unsubscribe::new(self.requests_key(), ctx.self_ref());
+    self.replicator.tell(AnyMessage::new(ReplicatorCommand::unsubscribe::<ShardRegionRequestsMap>(&requests)));
+    Ok(())
+  }
+}
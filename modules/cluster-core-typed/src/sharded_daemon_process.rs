@@ -0,0 +1,148 @@
+//! Typed sharded daemon process access point.
+
+#[cfg(test)]
+#[path = "sharded_daemon_process_test.rs"]
+mod tests;
+
+use alloc::{format, string::String, vec};
+
+use fraktor_actor_core_kernel_rs::{actor::props::Props, system::ActorSystem};
+use fraktor_actor_core_typed_rs::{Behavior, TypedActorRef, TypedActorSystem, TypedProps};
+use fraktor_cluster_core_kernel_rs::{
+  activation::ActivatedKind,
+  ddata::ReplicatorExtension,
+  extension::{ClusterApi, ClusterApiError, ClusterExtension},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{
+  ShardedDaemonProcessCommand, ShardedDaemonProcessError, ShardedDaemonProcessSettings,
+  sharded_daemon_process_keeper::{ShardedDaemonProcessKeeper, WorkerPropsFactory},
+};
+
+const KIND_PREFIX: &str = "sharded-daemon-process-";
+
+/// Typed entry point for keeping a fixed number of workers alive across the cluster.
+///
+/// This is the fraktor equivalent of Pekko's typed `ShardedDaemonProcess` extension. Worker
+/// indices are placed as grains of the kind `sharded-daemon-process-<name>`, so they are spread
+/// over the members like any other grain and follow placement on topology changes.
+pub struct ShardedDaemonProcess {
+  cluster:   ClusterApi,
+  extension: ArcShared<ClusterExtension>,
+  system:    ActorSystem,
+}
+
+impl ShardedDaemonProcess {
+  /// Retrieves the typed sharded daemon process facade from a typed actor system.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the cluster extension has not been installed.
+  pub fn get<S>(system: &TypedActorSystem<S>) -> Result<Self, ClusterApiError>
+  where
+    S: Send + Sync + 'static, {
+    let cluster = ClusterApi::try_from_system(system.as_untyped())?;
+    let extension = system
+      .as_untyped()
+      .extended()
+      .extension_by_type::<ClusterExtension>()
+      .ok_or(ClusterApiError::ExtensionNotInstalled)?;
+    Ok(Self { cluster, extension, system: system.as_untyped().clone() })
+  }
+
+  /// Starts `number_of_processes` workers named `name` with default settings.
+  ///
+  /// See [`Self::init_with_settings`].
+  ///
+  /// # Errors
+  ///
+  /// Returns the errors of [`Self::init_with_settings`].
+  pub fn init<M, F>(
+    &self,
+    name: &str,
+    number_of_processes: usize,
+    behavior_factory: F,
+  ) -> Result<TypedActorRef<ShardedDaemonProcessCommand>, ShardedDaemonProcessError>
+  where
+    M: Send + Sync + 'static,
+    F: Fn(usize) -> Behavior<M> + Send + Sync + 'static, {
+    self.init_with_settings(name, number_of_processes, behavior_factory, ShardedDaemonProcessSettings::new())
+  }
+
+  /// Starts `number_of_processes` workers named `name`, built by `behavior_factory` from their
+  /// index in `0..number_of_processes`.
+  ///
+  /// Every member must call this after `start_member` with the same name and number of
+  /// processes. The local keeper registers the daemon kind, runs the workers placed on the local
+  /// member and pings every index at the keep-alive interval. The returned reference accepts
+  /// [`ShardedDaemonProcessCommand`]s, for example to change the number of workers at runtime.
+  ///
+  /// Changes of the number of processes are replicated through the distributed-data Replicator,
+  /// which must be installed. Once a change has been replicated it overrides
+  /// `number_of_processes`; make the key `ShardedDaemonProcessState-<name>` durable to keep it
+  /// across full cluster restarts.
+  ///
+  /// # Errors
+  ///
+  /// - [`ShardedDaemonProcessError::InvalidNumberOfProcesses`] when `number_of_processes` is zero.
+  /// - [`ShardedDaemonProcessError::ReplicatorNotInstalled`] when the Replicator is not installed.
+  /// - [`ShardedDaemonProcessError::KindSetupFailed`] when the daemon kind cannot be registered.
+  /// - [`ShardedDaemonProcessError::SpawnFailed`] when the keeper cannot be spawned, for example
+  ///   because a daemon process with the same name was already initialized.
+  pub fn init_with_settings<M, F>(
+    &self,
+    name: &str,
+    number_of_processes: usize,
+    behavior_factory: F,
+    settings: ShardedDaemonProcessSettings,
+  ) -> Result<TypedActorRef<ShardedDaemonProcessCommand>, ShardedDaemonProcessError>
+  where
+    M: Send + Sync + 'static,
+    F: Fn(usize) -> Behavior<M> + Send + Sync + 'static, {
+    if number_of_processes == 0 {
+      return Err(ShardedDaemonProcessError::InvalidNumberOfProcesses);
+    }
+    let replicator = self
+      .system
+      .extended()
+      .extension_by_type::<ReplicatorExtension>()
+      .ok_or(ShardedDaemonProcessError::ReplicatorNotInstalled)?;
+    let replicator_ref = replicator.replicator();
+    let self_address = replicator.self_unique_address().clone();
+    let kind = format!("{KIND_PREFIX}{name}");
+    self
+      .extension
+      .setup_member_kinds(vec![ActivatedKind::new(kind.clone())])
+      .map_err(ShardedDaemonProcessError::KindSetupFailed)?;
+
+    let behavior_factory = ArcShared::new(behavior_factory);
+    let worker_props: WorkerPropsFactory = ArcShared::new(move |index| {
+      let behavior_factory = behavior_factory.clone();
+      TypedProps::<M>::from_behavior_factory(move || behavior_factory(index)).into_untyped()
+    });
+    let cluster = self.cluster.clone();
+    let keeper_name = String::from(name);
+    let keep_alive_interval = settings.keep_alive_interval();
+    let keeper_props = Props::from_fn(move || {
+      ShardedDaemonProcessKeeper::new(
+        keeper_name.clone(),
+        kind.clone(),
+        cluster.clone(),
+        worker_props.clone(),
+        replicator_ref.clone(),
+        self_address.clone(),
+        number_of_processes,
+        keep_alive_interval,
+      )
+    })
+    .with_name(format!("shardedDaemonProcess{name}"));
+    let keeper = self
+      .system
+      .extended()
+      .spawn_system_actor(&keeper_props)
+      .map_err(ShardedDaemonProcessError::SpawnFailed)?
+      .into_actor_ref();
+    Ok(TypedActorRef::from_untyped(keeper))
+  }
+}
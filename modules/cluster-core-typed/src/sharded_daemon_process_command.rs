@@ -0,0 +1,16 @@
+//! Commands accepted by a running sharded daemon process.
+
+use fraktor_actor_core_typed_rs::TypedActorRef;
+
+/// Commands sent to the reference returned by
+/// [`ShardedDaemonProcess::init`](crate::ShardedDaemonProcess::init).
+#[derive(Clone)]
+pub enum ShardedDaemonProcessCommand {
+  /// Changes the number of workers on every member.
+  ///
+  /// Workers with an index at or above the new number are stopped; new indices are started by
+  /// their owners on the next keep-alive round. Zero is ignored.
+  ChangeNumberOfProcesses(usize),
+  /// Replies with the current number of workers.
+  GetNumberOfProcesses(TypedActorRef<usize>),
+}
@@ -0,0 +1,18 @@
+//! Errors returned when initializing a typed sharded daemon process.
+
+use fraktor_actor_core_kernel_rs::actor::spawn::SpawnError;
+use fraktor_cluster_core_kernel_rs::activation::IdentitySetupError;
+
+/// Errors raised by [`ShardedDaemonProcess::init`](crate::ShardedDaemonProcess::init).
+#[derive(Debug)]
+pub enum ShardedDaemonProcessError {
+  /// The number of processes is zero.
+  InvalidNumberOfProcesses,
+  /// The distributed-data Replicator replicating the number of processes is not installed.
+  ReplicatorNotInstalled,
+  /// The grain kind of the daemon process could not be registered with identity lookup.
+  KindSetupFailed(IdentitySetupError),
+  /// The keeper could not be spawned, for example because a daemon process with the same name
+  /// was already initialized.
+  SpawnFailed(SpawnError),
+}
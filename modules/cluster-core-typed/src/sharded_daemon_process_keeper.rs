@@ -0,0 +1,224 @@
+//! Actor keeping the workers of a sharded daemon process alive on one member.
+
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use core::time::Duration;
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext, ChildRef, Pid,
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView},
+    props::Props,
+  },
+  event::logging::LogLevel,
+};
+use fraktor_cluster_core_kernel_rs::{
+  activation::ClusterIdentity as KernelClusterIdentity,
+  ddata::{
+    ReplicatorCommand, SelfUniqueAddress, Subscribe, SubscribeResponse, Unsubscribe, Update, UpdateResponse,
+    WriteConsistency,
+  },
+  extension::ClusterApi,
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{
+  ShardedDaemonProcessCommand,
+  sharded_daemon_process_state::{ShardedDaemonProcessRegister, ShardedDaemonProcessState},
+};
+
+const KEEP_ALIVE_TIMER: &str = "sharded-daemon-process-keep-alive";
+
+/// Builds the props of the worker with the given index.
+pub(crate) type WorkerPropsFactory = ArcShared<dyn Fn(usize) -> Props + Send + Sync>;
+
+#[derive(Clone, Copy)]
+struct KeepAlive;
+
+/// Runs the workers of a sharded daemon process that placement assigns to the local member.
+///
+/// Every member runs a keeper under the same name. On each keep-alive round the keeper asks
+/// grain placement for the owner of every worker index: indices owned by the local member are
+/// started when not running, and local workers whose index moved to another member are stopped.
+/// Workers lost with a member are therefore restarted by their new owner on the next round.
+///
+/// The number of processes is replicated through the distributed-data Replicator, so members that
+/// join or restart later run with the latest number and concurrent changes converge on one value.
+pub(crate) struct ShardedDaemonProcessKeeper {
+  name:                String,
+  kind:                String,
+  cluster:             ClusterApi,
+  self_authority:      String,
+  worker_props:        WorkerPropsFactory,
+  replicator:          ActorRef,
+  self_address:        SelfUniqueAddress,
+  state:               ShardedDaemonProcessState,
+  keep_alive_interval: Duration,
+  workers:             BTreeMap<usize, ChildRef>,
+}
+
+impl ShardedDaemonProcessKeeper {
+  /// Creates a keeper for `number_of_processes` workers placed as grains of `kind`.
+  ///
+  /// `number_of_processes` applies until the replicated number of processes is known.
+  #[allow(clippy::too_many_arguments)]
+  pub(crate) fn new(
+    name: String,
+    kind: String,
+    cluster: ClusterApi,
+    worker_props: WorkerPropsFactory,
+    replicator: ActorRef,
+    self_address: SelfUniqueAddress,
+    number_of_processes: usize,
+    keep_alive_interval: Duration,
+  ) -> Self {
+    let self_authority = cluster.self_authority();
+    Self {
+      name,
+      kind,
+      cluster,
+      self_authority,
+      worker_props,
+      replicator,
+      self_address,
+      state: ShardedDaemonProcessState { revision: 0, number_of_processes },
+      keep_alive_interval,
+      workers: BTreeMap::new(),
+    }
+  }
+
+  fn keep_alive(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    for index in 0..self.state.number_of_processes {
+      let Ok(identity) = KernelClusterIdentity::new(self.kind.as_str(), format!("{index}")) else {
+        continue;
+      };
+      // worker は keeper の子として動くため、grain を活性化せずに所有者だけを問い合わせる
+      match self.cluster.owner_of(&identity) {
+        | Ok(owner) if owner == self.self_authority => self.ensure_worker(ctx, index)?,
+        | Ok(_) => self.stop_worker(ctx, index),
+        // 所有者が決まらない間は現状の worker を維持し、次の周期で再試行する
+        | Err(_) => {},
+      }
+    }
+    Ok(())
+  }
+
+  fn ensure_worker(&mut self, ctx: &mut ActorContext<'_>, index: usize) -> Result<(), ActorError> {
+    if self.workers.contains_key(&index) {
+      return Ok(());
+    }
+    let props = (self.worker_props)(index).with_name(format!("{}-{index}", self.name));
+    let worker = ctx
+      .spawn_child_watched(&props)
+      .map_err(|error| ActorError::recoverable(format!("sharded daemon worker {index} spawn failed: {error:?}")))?;
+    self.workers.insert(index, worker);
+    Ok(())
+  }
+
+  fn stop_worker(&self, ctx: &ActorContext<'_>, index: usize) {
+    // 停止完了 (on_terminated) までは登録を残し、同名での再生成を避ける
+    if let Some(worker) = self.workers.get(&index)
+      && let Err(error) = worker.stop()
+    {
+      ctx.log(LogLevel::Warn, format!("sharded daemon worker {index} could not be stopped: {error:?}"));
+    }
+  }
+
+  fn apply_state(&mut self, ctx: &mut ActorContext<'_>, state: ShardedDaemonProcessState) -> Result<(), ActorError> {
+    self.state = state;
+    let indices: Vec<usize> = self.workers.range(state.number_of_processes..).map(|(index, _)| *index).collect();
+    for index in indices {
+      self.stop_worker(ctx, index);
+    }
+    self.keep_alive(ctx)
+  }
+
+  fn change_number_of_processes(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    number_of_processes: usize,
+  ) -> Result<(), ActorError> {
+    let known_revision = self.state.revision;
+    let self_address = self.self_address.clone();
+    let update =
+      Update::<ShardedDaemonProcessRegister>::new(ShardedDaemonProcessState::key(&self.name), WriteConsistency::Local);
+    let modify = move |current: Option<&ShardedDaemonProcessRegister>| {
+      let revision = current.map_or(known_revision, |register| register.value().revision.max(known_revision)) + 1;
+      let state = ShardedDaemonProcessState { revision, number_of_processes };
+      let register = match current {
+        | Some(register) => register.with_value_with_clock(&self_address, state, |timestamp, _| timestamp + 1),
+        | None => Some(ShardedDaemonProcessRegister::new_with_clock(&self_address, state, |_, _| 1)),
+      };
+      register.ok_or_else(|| String::from("sharded daemon process state timestamp was reused"))
+    };
+    self.replicator.tell(AnyMessage::new(ReplicatorCommand::update(update, modify, ctx.self_ref())));
+    // 複製された値が通知されるまでの間も新しい数で動かす
+    let state = ShardedDaemonProcessState { revision: known_revision + 1, number_of_processes };
+    self.apply_state(ctx, state)
+  }
+
+  fn on_command(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    command: &ShardedDaemonProcessCommand,
+  ) -> Result<(), ActorError> {
+    match command {
+      | ShardedDaemonProcessCommand::ChangeNumberOfProcesses(0) => Ok(()),
+      | ShardedDaemonProcessCommand::ChangeNumberOfProcesses(number_of_processes) => {
+        self.change_number_of_processes(ctx, *number_of_processes)
+      },
+      | ShardedDaemonProcessCommand::GetNumberOfProcesses(reply_to) => {
+        reply_to.clone().tell(self.state.number_of_processes);
+        Ok(())
+      },
+    }
+  }
+}
+
+impl Actor for ShardedDaemonProcessKeeper {
+  fn pre_start(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    let subscribe = Subscribe::new(ShardedDaemonProcessState::key(&self.name), ctx.self_ref());
+    self.replicator.tell(AnyMessage::new(ReplicatorCommand::subscribe::<ShardedDaemonProcessRegister>(subscribe)));
+    ctx
+      .timers()
+      .start_timer_with_fixed_delay(KEEP_ALIVE_TIMER, AnyMessage::new(KeepAlive), self.keep_alive_interval)
+      .map_err(|error| ActorError::recoverable(format!("sharded daemon keep-alive timer failed: {error:?}")))?;
+    self.keep_alive(ctx)
+  }
+
+  fn receive(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if message.downcast_ref::<KeepAlive>().is_some() {
+      return self.keep_alive(ctx);
+    }
+    if let Some(command) = message.downcast_ref::<ShardedDaemonProcessCommand>() {
+      return self.on_command(ctx, command);
+    }
+    if let Some(SubscribeResponse::Changed { data, .. }) =
+      message.downcast_ref::<SubscribeResponse<ShardedDaemonProcessRegister>>()
+      && *data.value() != self.state
+    {
+      return self.apply_state(ctx, *data.value());
+    }
+    if let Some(response) = message.downcast_ref::<UpdateResponse<ShardedDaemonProcessRegister>>()
+      && !matches!(response, UpdateResponse::Success { .. })
+    {
+      ctx.log(
+        LogLevel::Warn,
+        format!("sharded daemon process {} failed to replicate its state: {response:?}", self.name),
+      );
+    }
+    Ok(())
+  }
+
+  fn on_terminated(&mut self, _ctx: &mut ActorContext<'_>, terminated: Pid) -> Result<(), ActorError> {
+    self.workers.retain(|_, worker| worker.pid() != terminated);
+    Ok(())
+  }
+
+  fn post_stop(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    let unsubscribe = Unsubscribe::new(ShardedDaemonProcessState::key(&self.name), ctx.self_ref());
+    self.replicator.tell(AnyMessage::new(ReplicatorCommand::unsubscribe::<ShardedDaemonProcessRegister>(&unsubscribe)));
+    Ok(())
+  }
+}
@@ -0,0 +1,44 @@
+//! Settings of a typed sharded daemon process.
+
+#[cfg(test)]
+#[path = "sharded_daemon_process_settings_test.rs"]
+mod tests;
+
+use core::time::Duration;
+
+const DEFAULT_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Settings of a [`ShardedDaemonProcess`](crate::ShardedDaemonProcess).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardedDaemonProcessSettings {
+  keep_alive_interval: Duration,
+}
+
+impl ShardedDaemonProcessSettings {
+  /// Creates settings with a keep-alive interval of 10 s.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self { keep_alive_interval: DEFAULT_KEEP_ALIVE_INTERVAL }
+  }
+
+  /// Sets how often every worker index is pinged through placement.
+  ///
+  /// Each ping restarts workers lost with a member and moves workers whose placement changed.
+  #[must_use]
+  pub const fn with_keep_alive_interval(mut self, interval: Duration) -> Self {
+    self.keep_alive_interval = interval;
+    self
+  }
+
+  /// Returns the keep-alive interval.
+  #[must_use]
+  pub const fn keep_alive_interval(&self) -> Duration {
+    self.keep_alive_interval
+  }
+}
+
+impl Default for ShardedDaemonProcessSettings {
+  fn default() -> Self {
+    Self::new()
+  }
+}
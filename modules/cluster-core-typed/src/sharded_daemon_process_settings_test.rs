@@ -0,0 +1,16 @@
+use core::time::Duration;
+
+use crate::ShardedDaemonProcessSettings;
+
+#[test]
+fn defaults_ping_workers_every_ten_seconds() {
+  assert_eq!(ShardedDaemonProcessSettings::new().keep_alive_interval(), Duration::from_secs(10));
+  assert_eq!(ShardedDaemonProcessSettings::default(), ShardedDaemonProcessSettings::new());
+}
+
+#[test]
+fn keep_alive_interval_can_be_overridden() {
+  let settings = ShardedDaemonProcessSettings::new().with_keep_alive_interval(Duration::from_millis(50));
+
+  assert_eq!(settings.keep_alive_interval(), Duration::from_millis(50));
+}
@@ -0,0 +1,25 @@
+//! Replicated number of processes of a sharded daemon process.
+
+use alloc::format;
+
+use fraktor_cluster_core_kernel_rs::ddata::{LWWRegister, LWWRegisterKey};
+
+/// Register holding the replicated state of one sharded daemon process.
+pub(crate) type ShardedDaemonProcessRegister = LWWRegister<ShardedDaemonProcessState>;
+
+/// Number of processes shared by the keepers of every member.
+///
+/// Corresponds to Pekko's `ShardedDaemonProcessState`. The revision grows with every change, so
+/// keepers that start late or restart apply the latest number instead of their initial one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ShardedDaemonProcessState {
+  pub(crate) revision:            u64,
+  pub(crate) number_of_processes: usize,
+}
+
+impl ShardedDaemonProcessState {
+  /// Returns the key of the register replicating the state of the daemon process `name`.
+  pub(crate) fn key(name: &str) -> LWWRegisterKey<Self> {
+    LWWRegisterKey::new(format!("ShardedDaemonProcessState-{name}"))
+  }
+}
@@ -0,0 +1,105 @@
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::actor::{
+  extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig,
+};
+use fraktor_actor_core_typed_rs::{Behavior, TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::{
+  activation::ClusterIdentity,
+  cluster_provider::NoopClusterProvider,
+  ddata::{ReplicatorExtensionInstaller, SelfUniqueAddress},
+  extension::{
+    ClusterApi, ClusterApiError, ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller,
+    ClusterResolveError,
+  },
+};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+
+use crate::{ShardedDaemonProcess, ShardedDaemonProcessError};
+
+#[derive(Debug)]
+struct TestMsg;
+
+fn typed_system(installers: ExtensionInstallers) -> TypedActorSystem<TestMsg> {
+  let props = TypedProps::<TestMsg>::from_behavior_factory(Behaviors::ignore);
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(installers);
+  TypedActorSystem::create_from_props(&props, config).expect("typed system")
+}
+
+fn cluster_system() -> TypedActorSystem<TestMsg> {
+  let config = ClusterExtensionConfig::new().with_advertised_address("node1:8080");
+  let installer =
+    ClusterExtensionInstaller::new(config, |_event_stream, _block_list, _address| Box::new(NoopClusterProvider::new()));
+  let replicator_installer = ReplicatorExtensionInstaller::new(SelfUniqueAddress::new(UniqueAddress::new(
+    Address::new("sys", "node1", 8080),
+    1,
+  )));
+  let system = typed_system(
+    ExtensionInstallers::default().with_extension_installer(installer).with_extension_installer(replicator_installer),
+  );
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  system
+}
+
+fn ignore_worker(_index: usize) -> Behavior<TestMsg> {
+  Behaviors::ignore()
+}
+
+#[test]
+fn get_fails_when_the_cluster_extension_is_not_installed() {
+  let system = typed_system(ExtensionInstallers::default());
+
+  assert!(matches!(ShardedDaemonProcess::get(&system), Err(ClusterApiError::ExtensionNotInstalled)));
+}
+
+#[test]
+fn init_returns_the_keeper_and_registers_the_daemon_kind() {
+  let system = cluster_system();
+  let daemons = ShardedDaemonProcess::get(&system).expect("sharded daemon process");
+
+  let keeper = daemons.init("projections", 3, ignore_worker).expect("init");
+
+  let path = keeper.as_untyped().path().expect("keeper has a path");
+  assert!(path.to_string().ends_with("/shardedDaemonProcessprojections"), "unexpected path {path}");
+  let cluster = ClusterApi::try_from_system(system.as_untyped()).expect("cluster api");
+  let identity = ClusterIdentity::new("sharded-daemon-process-projections", "0").expect("identity");
+  assert!(!matches!(cluster.owner_of(&identity), Err(ClusterResolveError::KindNotRegistered { .. })));
+}
+
+#[test]
+fn init_fails_when_the_replicator_is_not_installed() {
+  let config = ClusterExtensionConfig::new().with_advertised_address("node1:8080");
+  let installer =
+    ClusterExtensionInstaller::new(config, |_event_stream, _block_list, _address| Box::new(NoopClusterProvider::new()));
+  let system = typed_system(ExtensionInstallers::default().with_extension_installer(installer));
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  let daemons = ShardedDaemonProcess::get(&system).expect("sharded daemon process");
+
+  let result = daemons.init("projections", 1, ignore_worker);
+
+  assert!(matches!(result, Err(ShardedDaemonProcessError::ReplicatorNotInstalled)));
+}
+
+#[test]
+fn init_rejects_zero_processes() {
+  let system = cluster_system();
+  let daemons = ShardedDaemonProcess::get(&system).expect("sharded daemon process");
+
+  let result = daemons.init("projections", 0, ignore_worker);
+
+  assert!(matches!(result, Err(ShardedDaemonProcessError::InvalidNumberOfProcesses)));
+}
+
+#[test]
+fn init_rejects_a_second_daemon_process_with_the_same_name() {
+  let system = cluster_system();
+  let daemons = ShardedDaemonProcess::get(&system).expect("sharded daemon process");
+  daemons.init("projections", 1, ignore_worker).expect("first init");
+
+  let result = daemons.init("projections", 1, ignore_worker);
+
+  assert!(matches!(result, Err(ShardedDaemonProcessError::SpawnFailed(_))));
+}
@@ -0,0 +1,102 @@
+//! Consumer side of reliable delivery to sharded entities.
+
+#[cfg(test)]
+#[path = "sharding_consumer_controller_test.rs"]
+mod tests;
+
+use alloc::{collections::BTreeMap, format, string::String};
+
+use fraktor_actor_core_kernel_rs::event::logging::LogLevel;
+use fraktor_actor_core_typed_rs::{
+  Behavior, TypedActorRef,
+  delivery::{
+    ConsumerController, ConsumerControllerCommand, ConsumerControllerConfig, ConsumerControllerDelivery,
+    SequencedMessage,
+  },
+  dsl::Behaviors,
+  message_and_signals::BehaviorSignal,
+};
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedLock};
+
+/// Factory for the entity behavior receiving reliably delivered messages.
+///
+/// This is the fraktor equivalent of Pekko's `ShardingConsumerController`. Register the returned
+/// behavior as the grain of the kind addressed by a
+/// [`ShardingProducerController`](crate::ShardingProducerController). The entity spawns the
+/// consumer as a child and one
+/// [`ConsumerController`](fraktor_actor_core_typed_rs::delivery::ConsumerController) per producer,
+/// so messages from several producers are sequenced independently. The consumer receives
+/// [`ConsumerControllerDelivery`] messages and confirms each one through its `confirm_to`
+/// reference. The entity stops when the consumer stops.
+pub struct ShardingConsumerController;
+
+impl ShardingConsumerController {
+  /// Creates the entity behavior with default consumer-controller configuration.
+  #[must_use]
+  pub fn behavior<A>(consumer: Behavior<ConsumerControllerDelivery<A>>) -> Behavior<SequencedMessage<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    Self::behavior_with_config(consumer, ConsumerControllerConfig::new())
+  }
+
+  /// Creates the entity behavior with the given configuration for its consumer controllers.
+  #[must_use]
+  pub fn behavior_with_config<A>(
+    consumer: Behavior<ConsumerControllerDelivery<A>>,
+    config: ConsumerControllerConfig,
+  ) -> Behavior<SequencedMessage<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    Behaviors::setup(move |ctx| {
+      let deliver_to = match ctx.spawn_anonymous(&consumer) {
+        | Ok(consumer) => consumer.actor_ref(),
+        | Err(error) => {
+          let message = format!("ShardingConsumerController failed to spawn consumer: {error:?}");
+          ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+          return Behaviors::stopped();
+        },
+      };
+      if let Err(error) = ctx.watch(&deliver_to) {
+        let message = format!("ShardingConsumerController failed to watch consumer: {error:?}");
+        ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+        return Behaviors::stopped();
+      }
+      let consumer_pid = deliver_to.pid();
+      let controllers: SharedLock<BTreeMap<String, TypedActorRef<ConsumerControllerCommand<A>>>> =
+        SharedLock::new_with_driver::<DefaultMutex<_>>(BTreeMap::new());
+      let config = config.clone();
+
+      Behaviors::receive_message(move |ctx, message: &SequencedMessage<A>| {
+        let existing = controllers.with_lock(|controllers| controllers.get(message.producer_id()).cloned());
+        let mut controller = match existing {
+          | Some(controller) => controller,
+          | None => {
+            let mut controller = match ctx.spawn_anonymous(&ConsumerController::behavior_with_config(config.clone())) {
+              | Ok(controller) => controller.actor_ref(),
+              | Err(error) => {
+                let message = format!(
+                  "ShardingConsumerController failed to spawn consumer controller for producer {}: {error:?}",
+                  message.producer_id()
+                );
+                ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+                return Ok(Behaviors::same());
+              },
+            };
+            controller.tell(ConsumerController::start(deliver_to.clone()));
+            controllers.with_lock(|controllers| {
+              controllers.insert(String::from(message.producer_id()), controller.clone());
+            });
+            controller
+          },
+        };
+        // 最初のメッセージで ConsumerController が ProducerController との session を確立する
+        controller.tell(ConsumerController::sequenced_message(message.clone()));
+        Ok(Behaviors::same())
+      })
+      .receive_signal(move |_ctx, signal| match signal {
+        | BehaviorSignal::Terminated(terminated) if terminated.pid() == consumer_pid => Ok(Behaviors::stopped()),
+        | _ => Ok(Behaviors::same()),
+      })
+    })
+  }
+}
@@ -0,0 +1,15 @@
+use fraktor_actor_core_typed_rs::{
+  delivery::{ConsumerControllerConfig, ConsumerControllerDelivery},
+  dsl::Behaviors,
+};
+
+use crate::ShardingConsumerController;
+
+#[test]
+fn behavior_factories_compile() {
+  let _behavior = ShardingConsumerController::behavior(Behaviors::ignore::<ConsumerControllerDelivery<u32>>());
+  let _configured = ShardingConsumerController::behavior_with_config(
+    Behaviors::ignore::<ConsumerControllerDelivery<u32>>(),
+    ConsumerControllerConfig::new().with_flow_control_window(4),
+  );
+}
@@ -0,0 +1,44 @@
+//! Envelope addressing a message to a sharded entity.
+
+#[cfg(test)]
+#[path = "sharding_envelope_test.rs"]
+mod tests;
+
+use alloc::string::String;
+
+/// Message together with the id of the entity it is addressed to.
+///
+/// This is the fraktor equivalent of Pekko's `ShardingEnvelope`. Producers hand envelopes to the
+/// [`ShardingProducerController`](crate::ShardingProducerController), which routes each message
+/// to the grain with the entity id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShardingEnvelope<M> {
+  entity_id: String,
+  message:   M,
+}
+
+impl<M> ShardingEnvelope<M> {
+  /// Creates an envelope for the entity `entity_id`.
+  #[must_use]
+  pub fn new(entity_id: impl Into<String>, message: M) -> Self {
+    Self { entity_id: entity_id.into(), message }
+  }
+
+  /// Returns the id of the addressed entity.
+  #[must_use]
+  pub fn entity_id(&self) -> &str {
+    &self.entity_id
+  }
+
+  /// Returns the wrapped message.
+  #[must_use]
+  pub const fn message(&self) -> &M {
+    &self.message
+  }
+
+  /// Consumes the envelope and returns the wrapped message.
+  #[must_use]
+  pub fn into_message(self) -> M {
+    self.message
+  }
+}
@@ -0,0 +1,10 @@
+use crate::ShardingEnvelope;
+
+#[test]
+fn envelope_keeps_entity_id_and_message() {
+  let envelope = ShardingEnvelope::new("order-1", 42_u32);
+
+  assert_eq!(envelope.entity_id(), "order-1");
+  assert_eq!(*envelope.message(), 42);
+  assert_eq!(envelope.into_message(), 42);
+}
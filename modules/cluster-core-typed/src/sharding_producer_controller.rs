@@ -0,0 +1,472 @@
+//! Producer side of reliable delivery to sharded entities.
+
+#[cfg(test)]
+#[path = "sharding_producer_controller_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::{BTreeMap, VecDeque},
+  format,
+  string::String,
+  vec::Vec,
+};
+
+use fraktor_actor_core_kernel_rs::event::logging::LogLevel;
+use fraktor_actor_core_typed_rs::{
+  Behavior, TypedActorRef,
+  actor::TypedActorContext,
+  delivery::{
+    ConsumerControllerCommand, DurableProducerQueueCommand, ProducerController, ProducerControllerRequestNext, SeqNr,
+    SequencedMessage,
+  },
+  dsl::Behaviors,
+};
+use fraktor_utils_core_rs::sync::{ArcShared, DefaultMutex, SharedLock};
+
+use crate::{
+  Cluster, ClusterIdentity, GrainRef, GrainTypeKey, ShardingEnvelope, ShardingProducerControllerCommand,
+  ShardingProducerControllerConfig, ShardingProducerControllerRequestNext,
+  sharding_producer_controller_command::ShardingProducerControllerCommandKind,
+};
+
+type DurableQueueFactory<A> = ArcShared<dyn Fn(&str) -> Behavior<DurableProducerQueueCommand<A>> + Send + Sync>;
+
+/// Deferred side-effects executed after releasing the state lock.
+enum DeferredAction<A>
+where
+  A: Clone + Send + Sync + 'static, {
+  RequestNext(TypedActorRef<ShardingProducerControllerRequestNext<A>>, ShardingProducerControllerRequestNext<A>),
+  SendToEntity { entity_id: String, target: TypedActorRef<A>, message: A },
+  StartEntity { entity_id: String, identity: ClusterIdentity<SequencedMessage<A>> },
+  LogDropped { entity_id: String, reason: String },
+}
+
+/// Delivery state of one entity.
+///
+/// The entity's producer controller signals demand only when the consumer requests more, so the
+/// demand between two requests is tracked here: the entity accepts messages up to `window_end`.
+struct EntityState<A>
+where
+  A: Clone + Send + Sync + 'static, {
+  /// Where the entity's producer controller accepts messages, once it has signalled demand.
+  send_next_to: Option<TypedActorRef<A>>,
+  /// Sequence number the entity's producer controller assigns to the next message.
+  next_seq_nr:  SeqNr,
+  /// Highest sequence number within the entity's flow-control window.
+  window_end:   SeqNr,
+  buffered:     VecDeque<A>,
+}
+
+impl<A> EntityState<A>
+where
+  A: Clone + Send + Sync + 'static,
+{
+  const fn new() -> Self {
+    Self { send_next_to: None, next_seq_nr: 0, window_end: 0, buffered: VecDeque::new() }
+  }
+
+  const fn has_demand(&self) -> bool {
+    self.send_next_to.is_some() && self.next_seq_nr <= self.window_end
+  }
+
+  fn on_demand(&mut self, request: &ProducerControllerRequestNext<A>, window: u32) {
+    self.send_next_to = Some(request.send_next_to().clone());
+    self.next_seq_nr = request.current_seq_nr();
+    // 要求を受けた時点で current_seq_nr は消費者の要求範囲内にある
+    self.window_end = request.current_seq_nr().max(request.confirmed_seq_nr() + SeqNr::from(window));
+  }
+
+  /// Takes the target for one message when the entity has demand.
+  fn take_target(&mut self) -> Option<TypedActorRef<A>> {
+    if !self.has_demand() {
+      return None;
+    }
+    self.next_seq_nr += 1;
+    self.send_next_to.clone()
+  }
+}
+
+struct ShardingProducerControllerState<A>
+where
+  A: Clone + Send + Sync + 'static, {
+  producer:        Option<TypedActorRef<ShardingProducerControllerRequestNext<A>>>,
+  entities:        BTreeMap<String, EntityState<A>>,
+  /// Whether a `RequestNext` has been sent and the producer's answer is pending.
+  request_pending: bool,
+}
+
+impl<A> ShardingProducerControllerState<A>
+where
+  A: Clone + Send + Sync + 'static,
+{
+  const fn new() -> Self {
+    Self { producer: None, entities: BTreeMap::new(), request_pending: false }
+  }
+
+  fn buffered_len(&self) -> usize {
+    self.entities.values().map(|entity| entity.buffered.len()).sum()
+  }
+}
+
+struct ShardingCommandContext<'a, A>
+where
+  A: Clone + Send + Sync + 'static, {
+  type_key:       &'a GrainTypeKey<SequencedMessage<A>>,
+  send_adapter:   &'a TypedActorRef<ShardingEnvelope<A>>,
+  buffer_size:    usize,
+  /// Prefix of the producer ids of the per-entity producer controllers.
+  entity_prefix:  &'a str,
+  window:         u32,
+  demand_adapter: &'a TypedActorRef<ProducerControllerRequestNext<A>>,
+  config:         &'a ShardingProducerControllerConfig,
+  durable_queue:  &'a Option<DurableQueueFactory<A>>,
+}
+
+/// Factory for the producer side of reliable delivery to sharded entities.
+///
+/// This is the fraktor equivalent of Pekko's `ShardingProducerController`. The producer sends
+/// [`ShardingEnvelope`]s and the controller runs one
+/// [`ProducerController`](fraktor_actor_core_typed_rs::delivery::ProducerController) per entity,
+/// so every entity has its own sequence numbers, flow control and unconfirmed messages. Sequenced
+/// messages are routed through a [`GrainRef`] and therefore follow the entity when it moves to
+/// another member or is passivated.
+///
+/// The entities run a [`ShardingConsumerController`](crate::ShardingConsumerController). When an
+/// entity is reactivated without the messages it had not confirmed, its producer controller resends
+/// the first unconfirmed message as the start of a new session at the resend-first interval of
+/// [`ShardingProducerControllerConfig::producer_controller_config`], and the entity requests the
+/// rest.
+pub struct ShardingProducerController;
+
+impl ShardingProducerController {
+  /// Creates a `Start` command registering the producer.
+  #[must_use]
+  pub const fn start<A>(
+    producer: TypedActorRef<ShardingProducerControllerRequestNext<A>>,
+  ) -> ShardingProducerControllerCommand<A>
+  where
+    A: Clone + Send + Sync + 'static, {
+    ShardingProducerControllerCommand::start(producer)
+  }
+
+  /// Creates the controller behavior with default configuration.
+  ///
+  /// `producer_id` must be unique among the producers sending to the same entities.
+  #[must_use]
+  pub fn behavior<A>(
+    producer_id: impl Into<String>,
+    type_key: &GrainTypeKey<SequencedMessage<A>>,
+  ) -> Behavior<ShardingProducerControllerCommand<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    Self::behavior_with_config(producer_id, type_key, &ShardingProducerControllerConfig::new())
+  }
+
+  /// Creates the controller behavior with custom configuration.
+  #[must_use]
+  pub fn behavior_with_config<A>(
+    producer_id: impl Into<String>,
+    type_key: &GrainTypeKey<SequencedMessage<A>>,
+    config: &ShardingProducerControllerConfig,
+  ) -> Behavior<ShardingProducerControllerCommand<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    Self::create_behavior(producer_id.into(), type_key.clone(), config.clone(), None)
+  }
+
+  /// Creates the controller behavior storing sent messages in durable producer queues.
+  ///
+  /// `durable_queue` builds the queue of the entity with the given id. The queue of an entity is
+  /// loaded when the controller first addresses that entity, and its unconfirmed messages are
+  /// delivered again before new ones.
+  ///
+  /// Corresponds to Pekko's `ShardingProducerController.apply` with `durableQueueBehavior`.
+  #[must_use]
+  pub fn behavior_with_durable_queue<A, F>(
+    producer_id: impl Into<String>,
+    type_key: &GrainTypeKey<SequencedMessage<A>>,
+    config: &ShardingProducerControllerConfig,
+    durable_queue: F,
+  ) -> Behavior<ShardingProducerControllerCommand<A>>
+  where
+    A: Clone + Send + Sync + 'static,
+    F: Fn(&str) -> Behavior<DurableProducerQueueCommand<A>> + Send + Sync + 'static, {
+    let durable_queue: DurableQueueFactory<A> = ArcShared::new(durable_queue);
+    Self::create_behavior(producer_id.into(), type_key.clone(), config.clone(), Some(durable_queue))
+  }
+
+  fn create_behavior<A>(
+    producer_id: String,
+    type_key: GrainTypeKey<SequencedMessage<A>>,
+    config: ShardingProducerControllerConfig,
+    durable_queue: Option<DurableQueueFactory<A>>,
+  ) -> Behavior<ShardingProducerControllerCommand<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    Behaviors::setup(move |ctx| {
+      let cluster = match Cluster::get(&ctx.system()) {
+        | Ok(cluster) => cluster,
+        | Err(error) => {
+          let message = format!("ShardingProducerController requires the cluster extension: {error:?}");
+          ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+          return Behaviors::stopped();
+        },
+      };
+      let send_adapter = match ctx
+        .message_adapter(|envelope: ShardingEnvelope<A>| Ok(ShardingProducerControllerCommand::msg(envelope)))
+      {
+        | Ok(adapter) => adapter,
+        | Err(error) => {
+          let message = format!("ShardingProducerController failed to create send adapter: {error:?}");
+          ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+          return Behaviors::stopped();
+        },
+      };
+      let demand_adapter = match ctx.message_adapter(|request: ProducerControllerRequestNext<A>| {
+        Ok(ShardingProducerControllerCommand::entity_demand(request))
+      }) {
+        | Ok(adapter) => adapter,
+        | Err(error) => {
+          let message = format!("ShardingProducerController failed to create demand adapter: {error:?}");
+          ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+          return Behaviors::stopped();
+        },
+      };
+      let state = SharedLock::new_with_driver::<DefaultMutex<_>>(ShardingProducerControllerState::<A>::new());
+      let entity_prefix = format!("{producer_id}-");
+      let type_key = type_key.clone();
+      let config = config.clone();
+      let durable_queue = durable_queue.clone();
+
+      Behaviors::receive_message(move |ctx, command: &ShardingProducerControllerCommand<A>| {
+        let command_context = ShardingCommandContext {
+          type_key:       &type_key,
+          send_adapter:   &send_adapter,
+          buffer_size:    config.buffer_size() as usize,
+          entity_prefix:  &entity_prefix,
+          window:         config.consumer_flow_control_window(),
+          demand_adapter: &demand_adapter,
+          config:         &config,
+          durable_queue:  &durable_queue,
+        };
+        // ロック保持中に遅延アクションを収集し、ロック解放後に実行する
+        let deferred = state.with_lock(|state| {
+          let mut deferred = Vec::new();
+          collect_deferred_for_command(state, command, &command_context, &mut deferred);
+          deferred
+        });
+        execute_deferred(ctx, deferred, &cluster, &command_context);
+        Ok(Behaviors::same())
+      })
+    })
+  }
+}
+
+fn collect_deferred_for_command<A>(
+  state: &mut ShardingProducerControllerState<A>,
+  command: &ShardingProducerControllerCommand<A>,
+  command_context: &ShardingCommandContext<'_, A>,
+  deferred: &mut Vec<DeferredAction<A>>,
+) where
+  A: Clone + Send + Sync + 'static, {
+  match command.kind() {
+    | ShardingProducerControllerCommandKind::Start { producer } => {
+      state.producer = Some(producer.clone());
+      state.request_pending = false;
+    },
+    | ShardingProducerControllerCommandKind::Msg { envelope } => {
+      state.request_pending = false;
+      collect_on_msg(state, envelope, command_context, deferred);
+    },
+    | ShardingProducerControllerCommandKind::EntityDemand { request } => {
+      collect_on_entity_demand(state, request, command_context, deferred);
+    },
+  }
+  collect_request_next(state, command_context, deferred);
+}
+
+/// Routes a producer message to its entity, buffering it while the entity has no demand.
+fn collect_on_msg<A>(
+  state: &mut ShardingProducerControllerState<A>,
+  envelope: &ShardingEnvelope<A>,
+  command_context: &ShardingCommandContext<'_, A>,
+  deferred: &mut Vec<DeferredAction<A>>,
+) where
+  A: Clone + Send + Sync + 'static, {
+  let entity_id = envelope.entity_id();
+  if !state.entities.contains_key(entity_id) {
+    let identity = match command_context.type_key.identity_for(entity_id) {
+      | Ok(identity) => identity,
+      | Err(error) => {
+        deferred.push(DeferredAction::LogDropped {
+          entity_id: String::from(entity_id),
+          reason:    format!("invalid entity id: {error:?}"),
+        });
+        return;
+      },
+    };
+    state.entities.insert(String::from(entity_id), EntityState::new());
+    deferred.push(DeferredAction::StartEntity { entity_id: String::from(entity_id), identity });
+  }
+  let buffered_len = state.buffered_len();
+  let Some(entity) = state.entities.get_mut(entity_id) else {
+    return;
+  };
+  if let Some(target) = entity.take_target() {
+    deferred.push(DeferredAction::SendToEntity {
+      entity_id: String::from(entity_id),
+      target,
+      message: envelope.message().clone(),
+    });
+  } else if buffered_len < command_context.buffer_size {
+    entity.buffered.push_back(envelope.message().clone());
+  } else {
+    deferred.push(DeferredAction::LogDropped {
+      entity_id: String::from(entity_id),
+      reason:    format!("buffer of {} messages is full", command_context.buffer_size),
+    });
+  }
+}
+
+/// Records the demand of an entity and hands it the buffered messages its window admits.
+fn collect_on_entity_demand<A>(
+  state: &mut ShardingProducerControllerState<A>,
+  request: &ProducerControllerRequestNext<A>,
+  command_context: &ShardingCommandContext<'_, A>,
+  deferred: &mut Vec<DeferredAction<A>>,
+) where
+  A: Clone + Send + Sync + 'static, {
+  let Some(entity_id) = request.producer_id().strip_prefix(command_context.entity_prefix) else {
+    return;
+  };
+  let Some(entity) = state.entities.get_mut(entity_id) else {
+    return;
+  };
+  entity.on_demand(request, command_context.window);
+  while !entity.buffered.is_empty()
+    && let Some(target) = entity.take_target()
+    && let Some(message) = entity.buffered.pop_front()
+  {
+    deferred.push(DeferredAction::SendToEntity { entity_id: String::from(entity_id), target, message });
+  }
+}
+
+/// Asks the producer for the next message while the buffer has room.
+fn collect_request_next<A>(
+  state: &mut ShardingProducerControllerState<A>,
+  command_context: &ShardingCommandContext<'_, A>,
+  deferred: &mut Vec<DeferredAction<A>>,
+) where
+  A: Clone + Send + Sync + 'static, {
+  if state.request_pending || state.buffered_len() >= command_context.buffer_size {
+    return;
+  }
+  let Some(producer) = state.producer.clone() else {
+    return;
+  };
+  let entities_with_demand =
+    state.entities.iter().filter(|(_, entity)| entity.has_demand()).map(|(id, _)| id.clone()).collect();
+  let buffered = state
+    .entities
+    .iter()
+    .filter(|(_, entity)| !entity.buffered.is_empty())
+    .map(|(id, entity)| (id.clone(), entity.buffered.len()))
+    .collect();
+  let request_next =
+    ShardingProducerControllerRequestNext::new(command_context.send_adapter.clone(), entities_with_demand, buffered);
+  deferred.push(DeferredAction::RequestNext(producer, request_next));
+  state.request_pending = true;
+}
+
+fn execute_deferred<A>(
+  ctx: &mut TypedActorContext<'_, ShardingProducerControllerCommand<A>>,
+  actions: Vec<DeferredAction<A>>,
+  cluster: &Cluster,
+  command_context: &ShardingCommandContext<'_, A>,
+) where
+  A: Clone + Send + Sync + 'static, {
+  for action in actions {
+    match action {
+      | DeferredAction::RequestNext(mut producer, request_next) => {
+        if let Err(error) = producer.try_tell(request_next) {
+          let message = format!("ShardingProducerController failed to request next message: {error:?}");
+          ctx.system().emit_log(LogLevel::Warn, message, Some(ctx.pid()), None);
+        }
+      },
+      | DeferredAction::SendToEntity { entity_id, mut target, message } => {
+        if let Err(error) = target.try_tell(message) {
+          let message = format!("ShardingProducerController failed to send to entity {entity_id}: {error:?}");
+          ctx.system().emit_log(LogLevel::Warn, message, Some(ctx.pid()), None);
+        }
+      },
+      | DeferredAction::StartEntity { entity_id, identity } => {
+        start_entity(ctx, &entity_id, cluster.grain_ref_for(&identity), command_context);
+      },
+      | DeferredAction::LogDropped { entity_id, reason } => {
+        let message = format!(
+          "ShardingProducerController dropped a {} for entity {entity_id}: {reason}",
+          core::any::type_name::<A>()
+        );
+        ctx.system().emit_log(LogLevel::Warn, message, Some(ctx.pid()), None);
+      },
+    }
+  }
+}
+
+/// Spawns the producer controller of an entity and the relay forwarding its messages to the grain.
+fn start_entity<A>(
+  ctx: &mut TypedActorContext<'_, ShardingProducerControllerCommand<A>>,
+  entity_id: &str,
+  grain: GrainRef<SequencedMessage<A>>,
+  command_context: &ShardingCommandContext<'_, A>,
+) where
+  A: Clone + Send + Sync + 'static, {
+  let relay = match ctx.spawn_anonymous(&entity_relay(grain)) {
+    | Ok(relay) => relay.actor_ref(),
+    | Err(error) => {
+      let message = format!("ShardingProducerController failed to spawn relay of entity {entity_id}: {error:?}");
+      ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+      return;
+    },
+  };
+  let durable_queue = command_context.durable_queue.as_ref().map(|factory| factory(entity_id));
+  let behavior = ProducerController::behavior_with_config(
+    format!("{}{entity_id}", command_context.entity_prefix),
+    command_context.config.producer_controller_config(),
+    durable_queue,
+  );
+  let mut producer_controller = match ctx.spawn_anonymous(&behavior) {
+    | Ok(producer_controller) => producer_controller.actor_ref(),
+    | Err(error) => {
+      let message =
+        format!("ShardingProducerController failed to spawn producer controller of entity {entity_id}: {error:?}");
+      ctx.system().emit_log(LogLevel::Error, message, Some(ctx.pid()), None);
+      return;
+    },
+  };
+  producer_controller.tell(ProducerController::start(command_context.demand_adapter.clone()));
+  producer_controller.tell(ProducerController::register_consumer(relay));
+}
+
+/// Behavior registered as the consumer of an entity's producer controller.
+///
+/// Forwards every sequenced message to the grain, which resolves the entity's current location on
+/// each send. Messages lost on the way are recovered by the producer controller's resends.
+fn entity_relay<A>(grain: GrainRef<SequencedMessage<A>>) -> Behavior<ConsumerControllerCommand<A>>
+where
+  A: Clone + Send + Sync + 'static, {
+  let grain = ArcShared::new(grain);
+  Behaviors::receive_message(move |ctx, command: &ConsumerControllerCommand<A>| {
+    if let Some(sequenced) = command.as_sequenced_message()
+      && let Err(error) = grain.tell_with_sender(sequenced.clone(), &ctx.self_ref())
+    {
+      let message = format!(
+        "ShardingProducerController failed to deliver seq_nr {} to entity {}: {error:?}",
+        sequenced.seq_nr(),
+        grain.identity().identity()
+      );
+      ctx.system().emit_log(LogLevel::Warn, message, Some(ctx.pid()), None);
+    }
+    Ok(Behaviors::same())
+  })
+}
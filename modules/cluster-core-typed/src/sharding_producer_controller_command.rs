@@ -0,0 +1,56 @@
+//! Commands accepted by the sharding producer controller actor.
+
+#[cfg(test)]
+#[path = "sharding_producer_controller_command_test.rs"]
+mod tests;
+
+use fraktor_actor_core_typed_rs::{TypedActorRef, delivery::ProducerControllerRequestNext};
+
+use crate::{ShardingEnvelope, ShardingProducerControllerRequestNext};
+
+/// Command protocol of [`ShardingProducerController`](crate::ShardingProducerController).
+///
+/// Public commands are created through the controller facade; the remaining variants are
+/// exchanged with the per-entity producer controllers.
+#[derive(Clone)]
+pub struct ShardingProducerControllerCommand<A>(pub(crate) ShardingProducerControllerCommandKind<A>)
+where
+  A: Clone + Send + Sync + 'static;
+
+/// Internal command variants.
+#[derive(Clone)]
+pub(crate) enum ShardingProducerControllerCommandKind<A>
+where
+  A: Clone + Send + Sync + 'static, {
+  /// Registers the producer that receives demand signals.
+  Start { producer: TypedActorRef<ShardingProducerControllerRequestNext<A>> },
+  /// A message from the producer, addressed to an entity.
+  Msg { envelope: ShardingEnvelope<A> },
+  /// Demand signalled by the producer controller of one entity.
+  EntityDemand { request: ProducerControllerRequestNext<A> },
+}
+
+impl<A> ShardingProducerControllerCommand<A>
+where
+  A: Clone + Send + Sync + 'static,
+{
+  /// Creates a `Start` command.
+  pub(crate) const fn start(producer: TypedActorRef<ShardingProducerControllerRequestNext<A>>) -> Self {
+    Self(ShardingProducerControllerCommandKind::Start { producer })
+  }
+
+  /// Creates a `Msg` command.
+  pub(crate) const fn msg(envelope: ShardingEnvelope<A>) -> Self {
+    Self(ShardingProducerControllerCommandKind::Msg { envelope })
+  }
+
+  /// Creates an `EntityDemand` command.
+  pub(crate) const fn entity_demand(request: ProducerControllerRequestNext<A>) -> Self {
+    Self(ShardingProducerControllerCommandKind::EntityDemand { request })
+  }
+
+  /// Returns a reference to the command kind.
+  pub(crate) const fn kind(&self) -> &ShardingProducerControllerCommandKind<A> {
+    &self.0
+  }
+}
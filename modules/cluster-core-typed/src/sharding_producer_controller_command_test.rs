@@ -0,0 +1,25 @@
+use alloc::string::String;
+
+use crate::{
+  ShardingEnvelope, ShardingProducerControllerCommand,
+  sharding_producer_controller_command::ShardingProducerControllerCommandKind,
+};
+
+#[test]
+fn command_is_clone() {
+  fn _assert_clone<T: Clone>() {}
+  _assert_clone::<ShardingProducerControllerCommand<String>>();
+}
+
+#[test]
+fn msg_keeps_the_envelope() {
+  let command = ShardingProducerControllerCommand::msg(ShardingEnvelope::new("a", 1_u32));
+
+  match command.kind() {
+    | ShardingProducerControllerCommandKind::Msg { envelope } => {
+      assert_eq!(envelope.entity_id(), "a");
+      assert_eq!(*envelope.message(), 1);
+    },
+    | _ => panic!("expected Msg"),
+  }
+}
@@ -0,0 +1,83 @@
+//! Configuration for the sharding producer controller.
+
+#[cfg(test)]
+#[path = "sharding_producer_controller_config_test.rs"]
+mod tests;
+
+use fraktor_actor_core_typed_rs::delivery::{ConsumerControllerConfig, ProducerControllerConfig};
+
+/// Default number of messages buffered for entities without demand.
+const DEFAULT_BUFFER_SIZE: u32 = 1000;
+
+/// Configuration for [`ShardingProducerController`](crate::ShardingProducerController).
+///
+/// Corresponds to Pekko's `ShardingProducerController.Settings`.
+#[derive(Debug, Clone)]
+pub struct ShardingProducerControllerConfig {
+  buffer_size:                  u32,
+  consumer_flow_control_window: u32,
+  producer_controller_config:   ProducerControllerConfig,
+}
+
+impl ShardingProducerControllerConfig {
+  /// Creates default config.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self {
+      buffer_size:                  DEFAULT_BUFFER_SIZE,
+      consumer_flow_control_window: ConsumerControllerConfig::new().flow_control_window(),
+      producer_controller_config:   ProducerControllerConfig::new(),
+    }
+  }
+
+  /// Returns the maximum number of messages buffered, over all entities, while waiting for
+  /// entity demand.
+  #[must_use]
+  pub const fn buffer_size(&self) -> u32 {
+    self.buffer_size
+  }
+
+  /// Returns a new config with the given buffer size.
+  ///
+  /// Corresponds to Pekko's `ShardingProducerController.Settings.withBufferSize`.
+  #[must_use]
+  pub const fn with_buffer_size(self, size: u32) -> Self {
+    Self { buffer_size: size, ..self }
+  }
+
+  /// Returns how many messages past its last confirmed one an entity accepts.
+  ///
+  /// An entity is sent messages up to this many sequence numbers after the one it last reported as
+  /// confirmed. It must not exceed the flow-control window of the entities' consumer controllers.
+  #[must_use]
+  pub const fn consumer_flow_control_window(&self) -> u32 {
+    self.consumer_flow_control_window
+  }
+
+  /// Returns a new config with the given consumer flow-control window.
+  #[must_use]
+  pub const fn with_consumer_flow_control_window(self, window: u32) -> Self {
+    Self { consumer_flow_control_window: window, ..self }
+  }
+
+  /// Returns the config of the per-entity producer controllers.
+  ///
+  /// Its resend-first interval decides how soon a reactivated entity receives the messages it has
+  /// not confirmed yet.
+  #[must_use]
+  pub const fn producer_controller_config(&self) -> &ProducerControllerConfig {
+    &self.producer_controller_config
+  }
+
+  /// Returns a new config with the given per-entity producer-controller config.
+  #[must_use]
+  pub const fn with_producer_controller_config(self, config: ProducerControllerConfig) -> Self {
+    Self { producer_controller_config: config, ..self }
+  }
+}
+
+impl Default for ShardingProducerControllerConfig {
+  fn default() -> Self {
+    Self::new()
+  }
+}
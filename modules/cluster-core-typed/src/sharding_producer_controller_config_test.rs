@@ -0,0 +1,37 @@
+use core::time::Duration;
+
+use fraktor_actor_core_typed_rs::delivery::{ConsumerControllerConfig, ProducerControllerConfig};
+
+use crate::ShardingProducerControllerConfig;
+
+#[test]
+fn default_config_buffers_a_thousand_messages() {
+  assert_eq!(ShardingProducerControllerConfig::new().buffer_size(), 1000);
+  assert_eq!(ShardingProducerControllerConfig::default().buffer_size(), 1000);
+}
+
+#[test]
+fn default_window_matches_the_default_consumer_window() {
+  assert_eq!(
+    ShardingProducerControllerConfig::new().consumer_flow_control_window(),
+    ConsumerControllerConfig::new().flow_control_window()
+  );
+}
+
+#[test]
+fn builders_override_only_their_field() {
+  let producer_config =
+    ProducerControllerConfig::new().with_durable_queue_resend_first_interval(Duration::from_millis(20));
+
+  let config = ShardingProducerControllerConfig::new()
+    .with_buffer_size(10)
+    .with_consumer_flow_control_window(4)
+    .with_producer_controller_config(producer_config.clone());
+
+  assert_eq!(config.buffer_size(), 10);
+  assert_eq!(config.consumer_flow_control_window(), 4);
+  assert_eq!(
+    config.producer_controller_config().durable_queue_resend_first_interval(),
+    producer_config.durable_queue_resend_first_interval()
+  );
+}
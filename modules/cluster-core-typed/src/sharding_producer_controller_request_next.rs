@@ -0,0 +1,63 @@
+//! Demand signal from the sharding producer controller to the producer.
+
+#[cfg(test)]
+#[path = "sharding_producer_controller_request_next_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  string::String,
+};
+
+use fraktor_actor_core_typed_rs::TypedActorRef;
+
+use crate::ShardingEnvelope;
+
+/// Asks the producer for the next message.
+///
+/// The producer answers with one [`ShardingEnvelope`] sent to [`send_next_to`](Self::send_next_to).
+/// Messages for entities without demand are buffered by the controller, so producers may use
+/// [`entities_with_demand`](Self::entities_with_demand) and
+/// [`buffered_for_entities_without_demand`](Self::buffered_for_entities_without_demand) to prefer
+/// entities that can take a message right away.
+///
+/// Corresponds to Pekko's `ShardingProducerController.RequestNext`.
+#[derive(Clone)]
+pub struct ShardingProducerControllerRequestNext<A>
+where
+  A: Clone + Send + Sync + 'static, {
+  send_next_to: TypedActorRef<ShardingEnvelope<A>>,
+  entities_with_demand: BTreeSet<String>,
+  buffered_for_entities_without_demand: BTreeMap<String, usize>,
+}
+
+impl<A> ShardingProducerControllerRequestNext<A>
+where
+  A: Clone + Send + Sync + 'static,
+{
+  pub(crate) const fn new(
+    send_next_to: TypedActorRef<ShardingEnvelope<A>>,
+    entities_with_demand: BTreeSet<String>,
+    buffered_for_entities_without_demand: BTreeMap<String, usize>,
+  ) -> Self {
+    Self { send_next_to, entities_with_demand, buffered_for_entities_without_demand }
+  }
+
+  /// Returns the reference the next envelope must be sent to.
+  #[must_use]
+  pub const fn send_next_to(&self) -> &TypedActorRef<ShardingEnvelope<A>> {
+    &self.send_next_to
+  }
+
+  /// Returns the entities whose consumers currently accept a message.
+  #[must_use]
+  pub const fn entities_with_demand(&self) -> &BTreeSet<String> {
+    &self.entities_with_demand
+  }
+
+  /// Returns the number of buffered messages per entity that has no demand.
+  #[must_use]
+  pub const fn buffered_for_entities_without_demand(&self) -> &BTreeMap<String, usize> {
+    &self.buffered_for_entities_without_demand
+  }
+}
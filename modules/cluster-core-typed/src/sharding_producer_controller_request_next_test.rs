@@ -0,0 +1,23 @@
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  string::String,
+};
+
+use fraktor_actor_core_kernel_rs::actor::{Pid, actor_ref::ActorRef};
+use fraktor_actor_core_typed_rs::TypedActorRef;
+
+use crate::{ShardingEnvelope, ShardingProducerControllerRequestNext};
+
+#[test]
+fn request_next_exposes_demand_and_buffer_snapshot() {
+  let send_next_to = TypedActorRef::<ShardingEnvelope<u32>>::from_untyped(ActorRef::null());
+  let entities_with_demand = BTreeSet::from([String::from("a")]);
+  let buffered = BTreeMap::from([(String::from("b"), 2)]);
+
+  let request =
+    ShardingProducerControllerRequestNext::new(send_next_to, entities_with_demand.clone(), buffered.clone());
+
+  assert_eq!(request.send_next_to().pid(), Pid::new(0, 0));
+  assert_eq!(request.entities_with_demand(), &entities_with_demand);
+  assert_eq!(request.buffered_for_entities_without_demand(), &buffered);
+}
@@ -0,0 +1,145 @@
+use alloc::vec::Vec;
+
+use fraktor_actor_core_kernel_rs::actor::actor_ref::ActorRef;
+use fraktor_actor_core_typed_rs::{
+  TypedActorRef,
+  delivery::{ProducerControllerRequestNext, SequencedMessage},
+};
+
+use super::{
+  DeferredAction, ShardingCommandContext, ShardingProducerController, ShardingProducerControllerState,
+  collect_deferred_for_command,
+};
+use crate::{
+  GrainTypeKey, ShardingEnvelope, ShardingProducerControllerCommand, ShardingProducerControllerConfig,
+  ShardingProducerControllerRequestNext,
+};
+
+fn null_ref<M: Send + Sync + 'static>() -> TypedActorRef<M> {
+  TypedActorRef::from_untyped(ActorRef::null())
+}
+
+struct Fixture {
+  type_key:       GrainTypeKey<SequencedMessage<u32>>,
+  send_adapter:   TypedActorRef<ShardingEnvelope<u32>>,
+  demand_adapter: TypedActorRef<ProducerControllerRequestNext<u32>>,
+  config:         ShardingProducerControllerConfig,
+}
+
+impl Fixture {
+  fn new(buffer_size: u32) -> Self {
+    Self {
+      type_key:       GrainTypeKey::new("orders"),
+      send_adapter:   null_ref(),
+      demand_adapter: null_ref(),
+      config:         ShardingProducerControllerConfig::new().with_buffer_size(buffer_size),
+    }
+  }
+
+  fn handle(
+    &self,
+    state: &mut ShardingProducerControllerState<u32>,
+    command: &ShardingProducerControllerCommand<u32>,
+  ) -> Vec<DeferredAction<u32>> {
+    let command_context = ShardingCommandContext {
+      type_key:       &self.type_key,
+      send_adapter:   &self.send_adapter,
+      buffer_size:    self.config.buffer_size() as usize,
+      entity_prefix:  "producer-",
+      window:         self.config.consumer_flow_control_window(),
+      demand_adapter: &self.demand_adapter,
+      config:         &self.config,
+      durable_queue:  &None,
+    };
+    let mut deferred = Vec::new();
+    collect_deferred_for_command(state, command, &command_context, &mut deferred);
+    deferred
+  }
+}
+
+fn msg(entity_id: &str, message: u32) -> ShardingProducerControllerCommand<u32> {
+  ShardingProducerControllerCommand::msg(ShardingEnvelope::new(entity_id, message))
+}
+
+#[test]
+fn behavior_factories_compile() {
+  let type_key = GrainTypeKey::<SequencedMessage<u32>>::new("orders");
+  let _behavior = ShardingProducerController::behavior("producer", &type_key);
+  let _start = ShardingProducerController::start(null_ref::<ShardingProducerControllerRequestNext<u32>>());
+}
+
+#[test]
+fn first_message_for_an_entity_starts_it_and_waits_for_demand() {
+  let fixture = Fixture::new(10);
+  let mut state = ShardingProducerControllerState::new();
+
+  let deferred = fixture.handle(&mut state, &msg("a", 1));
+
+  assert!(matches!(
+    deferred.as_slice(),
+    [DeferredAction::StartEntity { entity_id, identity }] if entity_id == "a" && identity.identity() == "a"
+  ));
+  assert_eq!(state.entities.get("a").map(|entity| entity.buffered.len()), Some(1));
+  assert!(fixture.handle(&mut state, &msg("a", 2)).is_empty(), "a known entity is not started again");
+}
+
+#[test]
+fn messages_beyond_the_buffer_size_are_dropped() {
+  let fixture = Fixture::new(2);
+  let mut state = ShardingProducerControllerState::new();
+  fixture.handle(&mut state, &msg("a", 1));
+  fixture.handle(&mut state, &msg("b", 2));
+
+  let deferred = fixture.handle(&mut state, &msg("a", 3));
+
+  assert!(matches!(deferred.as_slice(), [DeferredAction::LogDropped { entity_id, .. }] if entity_id == "a"));
+  assert_eq!(state.buffered_len(), 2);
+}
+
+#[test]
+fn invalid_entity_ids_are_dropped_without_starting_an_entity() {
+  let fixture = Fixture::new(10);
+  let mut state = ShardingProducerControllerState::new();
+
+  let deferred = fixture.handle(&mut state, &msg("", 1));
+
+  assert!(matches!(deferred.as_slice(), [DeferredAction::LogDropped { .. }]));
+  assert!(state.entities.is_empty());
+}
+
+#[test]
+fn start_requests_the_next_message_with_the_buffer_snapshot() {
+  let fixture = Fixture::new(10);
+  let mut state = ShardingProducerControllerState::new();
+  fixture.handle(&mut state, &msg("a", 1));
+  fixture.handle(&mut state, &msg("a", 2));
+
+  let deferred = fixture.handle(&mut state, &ShardingProducerController::start(null_ref()));
+
+  let [DeferredAction::RequestNext(_, request_next)] = deferred.as_slice() else {
+    panic!("expected a single RequestNext");
+  };
+  assert!(request_next.entities_with_demand().is_empty());
+  assert_eq!(request_next.buffered_for_entities_without_demand().get("a"), Some(&2));
+  assert!(state.request_pending);
+  assert_eq!(
+    fixture.handle(&mut state, &ShardingProducerController::start(null_ref())).len(),
+    1,
+    "a new Start replaces the pending request"
+  );
+}
+
+#[test]
+fn full_buffer_holds_back_the_next_request() {
+  let fixture = Fixture::new(1);
+  let mut state = ShardingProducerControllerState::new();
+  fixture.handle(&mut state, &ShardingProducerController::start(null_ref()));
+
+  let deferred = fixture.handle(&mut state, &msg("a", 1));
+
+  assert!(
+    deferred.iter().all(|action| !matches!(action, DeferredAction::RequestNext(..))),
+    "no demand is signalled while the buffer is full"
+  );
+  assert!(!state.request_pending);
+}
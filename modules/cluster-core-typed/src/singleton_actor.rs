@@ -0,0 +1,77 @@
+//! Description of a typed cluster singleton.
+
+#[cfg(test)]
+#[path = "singleton_actor_test.rs"]
+mod tests;
+
+use alloc::string::String;
+
+use fraktor_actor_core_typed_rs::TypedProps;
+
+use crate::ClusterSingletonConfig;
+
+/// Describes the actor that [`ClusterSingleton::init`](crate::ClusterSingleton::init) runs once
+/// in the cluster.
+///
+/// The singleton is stopped with `PoisonPill` for a hand-over unless a stop message is set.
+pub struct SingletonActor<M>
+where
+  M: Send + Sync + 'static, {
+  name:         String,
+  props:        TypedProps<M>,
+  settings:     ClusterSingletonConfig,
+  stop_message: Option<M>,
+}
+
+impl<M> SingletonActor<M>
+where
+  M: Send + Sync + 'static,
+{
+  /// Creates a singleton named `name` that runs `props` with default settings.
+  #[must_use]
+  pub fn new(props: TypedProps<M>, name: &str) -> Self {
+    Self { name: String::from(name), props, settings: ClusterSingletonConfig::new(), stop_message: None }
+  }
+
+  /// Sets the manager and proxy settings.
+  #[must_use]
+  pub fn with_settings(mut self, settings: ClusterSingletonConfig) -> Self {
+    self.settings = settings;
+    self
+  }
+
+  /// Sets the message sent to the singleton to stop it for a hand-over.
+  #[must_use]
+  pub fn with_stop_message(mut self, message: M) -> Self {
+    self.stop_message = Some(message);
+    self
+  }
+
+  /// Returns the singleton name.
+  #[must_use]
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Returns the props of the singleton actor.
+  #[must_use]
+  pub const fn props(&self) -> &TypedProps<M> {
+    &self.props
+  }
+
+  /// Returns the manager and proxy settings.
+  #[must_use]
+  pub const fn settings(&self) -> &ClusterSingletonConfig {
+    &self.settings
+  }
+
+  /// Returns the stop message, if one is set.
+  #[must_use]
+  pub const fn stop_message(&self) -> Option<&M> {
+    self.stop_message.as_ref()
+  }
+
+  pub(crate) fn into_parts(self) -> (String, TypedProps<M>, ClusterSingletonConfig, Option<M>) {
+    (self.name, self.props, self.settings, self.stop_message)
+  }
+}
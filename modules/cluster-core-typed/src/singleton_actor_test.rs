@@ -0,0 +1,32 @@
+use fraktor_actor_core_typed_rs::{TypedProps, dsl::Behaviors};
+
+use crate::{ClusterSingletonConfig, SingletonActor};
+
+#[derive(Debug, PartialEq)]
+enum Command {
+  Stop,
+}
+
+fn props() -> TypedProps<Command> {
+  TypedProps::<Command>::from_behavior_factory(Behaviors::ignore)
+}
+
+#[test]
+fn new_uses_default_settings_without_stop_message() {
+  let singleton = SingletonActor::new(props(), "counter");
+
+  assert_eq!(singleton.name(), "counter");
+  assert_eq!(singleton.settings(), &ClusterSingletonConfig::new());
+  assert!(singleton.stop_message().is_none());
+}
+
+#[test]
+fn builders_replace_settings_and_stop_message() {
+  let settings = ClusterSingletonConfig::new().with_role("backend").with_buffer_size(10);
+
+  let singleton =
+    SingletonActor::new(props(), "counter").with_settings(settings.clone()).with_stop_message(Command::Stop);
+
+  assert_eq!(singleton.settings(), &settings);
+  assert_eq!(singleton.stop_message(), Some(&Command::Stop));
+}
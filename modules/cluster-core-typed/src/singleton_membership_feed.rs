@@ -0,0 +1,146 @@
+//! Actor feeding cluster membership to a singleton manager and proxy.
+
+use alloc::{boxed::Box, format, string::String};
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext,
+    actor_path::ActorPathParser,
+    actor_ref::ActorRef,
+    error::ActorError,
+    messaging::{AnyMessage, AnyMessageView},
+  },
+  event::{
+    logging::LogLevel,
+    stream::{EventStreamEvent, EventStreamSubscriber, EventStreamSubscription, subscriber_handle},
+  },
+  system::ActorSystemWeak,
+};
+use fraktor_cluster_core_kernel_rs::{
+  extension::{ClusterApi, ClusterSubscriptionInitialStateMode},
+  membership::{NodeRecord, NodeStatus},
+  singleton::ClusterSingletonMemberEvent,
+  topology::{ClusterEvent, ClusterEventType},
+};
+
+const REMOTE_ACTOR_PATH_SCHEME: &str = "fraktor.tcp";
+
+struct ForwardingSubscriber {
+  target: ActorRef,
+  system: ActorSystemWeak,
+}
+
+impl EventStreamSubscriber for ForwardingSubscriber {
+  fn on_event(&mut self, event: &EventStreamEvent) {
+    if let EventStreamEvent::Extension { payload, .. } = event
+      && let Some(cluster_event) = payload.payload().downcast_ref::<ClusterEvent>()
+    {
+      // 購読者は配送失敗を再送できないため記録だけ残し、次のメンバーシップ変化で追従する。
+      if let Err(error) = self.target.try_tell(AnyMessage::new(cluster_event.clone()))
+        && let Some(system) = self.system.upgrade()
+      {
+        let message = format!("failed to forward cluster event to singleton membership feed: {error:?}");
+        system.emit_log(LogLevel::Warn, message, Some(self.target.pid()), None);
+      }
+    }
+  }
+}
+
+/// Translates cluster membership events into [`ClusterSingletonMemberEvent`]s.
+///
+/// Managers of remote members are addressed by the relative path of the local manager, since
+/// every member spawns its manager under the same name.
+pub(crate) struct SingletonMembershipFeed {
+  cluster:        ClusterApi,
+  self_authority: String,
+  manager:        ActorRef,
+  proxy:          ActorRef,
+  subscription:   Option<EventStreamSubscription>,
+}
+
+impl SingletonMembershipFeed {
+  /// Creates a feed for the local `manager` and `proxy`.
+  pub(crate) fn new(cluster: ClusterApi, manager: ActorRef, proxy: ActorRef) -> Self {
+    let self_authority = cluster.self_authority();
+    Self { cluster, self_authority, manager, proxy, subscription: None }
+  }
+
+  fn on_cluster_event(&mut self, ctx: &ActorContext<'_>, event: &ClusterEvent) {
+    match event {
+      | ClusterEvent::CurrentClusterState { state, .. } => {
+        for record in &state.members {
+          match record.status {
+            | NodeStatus::Up => self.member_up(ctx, record),
+            | NodeStatus::Leaving | NodeStatus::Exiting => {
+              // 離脱中のメンバーも年齢順の判定には必要なので、一度 Up として伝える。
+              self.member_up(ctx, record);
+              self.publish(&ClusterSingletonMemberEvent::Exiting { authority: record.authority.clone() });
+            },
+            | _ => {},
+          }
+        }
+      },
+      | ClusterEvent::MemberStatusChanged { authority, to, .. } => match to {
+        | NodeStatus::Up => {
+          let state = self.cluster.current_state();
+          if let Some(record) = state.members.iter().find(|record| &record.authority == authority) {
+            self.member_up(ctx, record);
+          }
+        },
+        | NodeStatus::Leaving | NodeStatus::Exiting => {
+          self.publish(&ClusterSingletonMemberEvent::Exiting { authority: authority.clone() });
+        },
+        | NodeStatus::Removed | NodeStatus::Dead => {
+          self.publish(&ClusterSingletonMemberEvent::Removed { authority: authority.clone() });
+        },
+        | _ => {},
+      },
+      | _ => {},
+    }
+  }
+
+  fn member_up(&mut self, ctx: &ActorContext<'_>, record: &NodeRecord) {
+    if let Some(manager) = self.manager_of(ctx, &record.authority) {
+      self.publish(&ClusterSingletonMemberEvent::Up { member: Box::new(record.clone()), manager });
+    }
+  }
+
+  fn manager_of(&self, ctx: &ActorContext<'_>, authority: &str) -> Option<ActorRef> {
+    if authority == self.self_authority {
+      return Some(self.manager.clone());
+    }
+    let relative = self.manager.path()?.to_relative_string();
+    let system = ctx.system();
+    let canonical = format!("{REMOTE_ACTOR_PATH_SCHEME}://{}@{authority}{relative}", system.name());
+    let path = ActorPathParser::parse(&canonical).ok()?;
+    system.resolve_actor_ref(path).ok()
+  }
+
+  fn publish(&mut self, event: &ClusterSingletonMemberEvent) {
+    self.manager.tell(AnyMessage::new(event.clone()));
+    self.proxy.tell(AnyMessage::new(event.clone()));
+  }
+}
+
+impl Actor for SingletonMembershipFeed {
+  fn pre_start(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    let subscriber =
+      subscriber_handle(ForwardingSubscriber { target: ctx.self_ref(), system: ctx.system().downgrade() });
+    self.subscription = Some(self.cluster.subscribe(&subscriber, ClusterSubscriptionInitialStateMode::AsSnapshot, &[
+      ClusterEventType::MemberStatusChanged,
+    ]));
+    Ok(())
+  }
+
+  fn receive(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(event) = message.downcast_ref::<ClusterEvent>() {
+      self.on_cluster_event(ctx, event);
+    }
+    Ok(())
+  }
+
+  fn post_stop(&mut self, _ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    self.subscription = None;
+    Ok(())
+  }
+}
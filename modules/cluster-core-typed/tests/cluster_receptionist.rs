@@ -0,0 +1,155 @@
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+extern crate alloc;
+
+use fraktor_actor_adaptor_std_rs::{dispatch::dispatcher::ThreadedExecutor, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    extension::ExtensionInstallers, messaging::AnyMessage, scheduler::SchedulerConfig, setup::ActorSystemConfig,
+  },
+  dispatch::dispatcher::{
+    DEFAULT_DISPATCHER_ID, DefaultDispatcherFactory, DispatcherConfig, ExecutorShared, MessageDispatcherFactory,
+    TrampolineState,
+  },
+  event::stream::EventStreamEvent,
+};
+use fraktor_actor_core_typed_rs::{
+  TypedActorRef, TypedActorSystem, TypedProps,
+  dsl::Behaviors,
+  receptionist::{Listing, Receptionist, ServiceKey},
+};
+use fraktor_cluster_core_kernel_rs::{
+  cluster_provider::NoopClusterProvider,
+  ddata::{ReplicatorCommand, ReplicatorExtensionInstaller, ReplicatorSettings, SelfUniqueAddress},
+  extension::{ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+  membership::NodeStatus,
+  topology::ClusterEvent,
+};
+use fraktor_cluster_core_typed_rs::{ClusterReceptionist, DistributedData};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::{
+  sync::{ArcShared, SpinSyncMutex},
+  time::TimerInstant,
+};
+
+#[derive(Debug)]
+struct UserMessage;
+
+type Listings = ArcShared<SpinSyncMutex<Vec<Listing>>>;
+
+fn unique_address(host: &str) -> UniqueAddress {
+  UniqueAddress::new(Address::new("sys", host, 8080), 1)
+}
+
+fn cluster_system(host: &str) -> TypedActorSystem<UserMessage> {
+  let cluster_config = ClusterExtensionConfig::new().with_advertised_address(alloc::format!("{host}:8080"));
+  let cluster_installer = ClusterExtensionInstaller::new(cluster_config, |_event_stream, _block_list, _address| {
+    Box::new(NoopClusterProvider::new())
+  });
+  let replicator_settings = ReplicatorSettings::new()
+    .with_gossip_interval(Duration::from_millis(20))
+    .with_notify_subscribers_interval(Duration::from_millis(10));
+  let replicator_installer =
+    ReplicatorExtensionInstaller::new(SelfUniqueAddress::new(unique_address(host))).with_settings(replicator_settings);
+  let extensions = ExtensionInstallers::default()
+    .with_extension_installer(cluster_installer)
+    .with_extension_installer(replicator_installer)
+    .with_extension_installer(ClusterReceptionist::setup());
+  // レプリケーターの問い合わせを応答処理から発行するため、インライン実行しない dispatcher を使う
+  let executor = ExecutorShared::new(Box::new(ThreadedExecutor::new()), TrampolineState::new());
+  let dispatcher: Box<dyn MessageDispatcherFactory> =
+    Box::new(DefaultDispatcherFactory::new(&DispatcherConfig::with_defaults(DEFAULT_DISPATCHER_ID), executor));
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(extensions)
+    .with_dispatcher_factory(DEFAULT_DISPATCHER_ID, ArcShared::new(dispatcher));
+  let props = TypedProps::<UserMessage>::from_behavior_factory(Behaviors::ignore);
+  let system = TypedActorSystem::create_from_props(&props, config).expect("typed system");
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  system
+}
+
+fn connect(from: &TypedActorSystem<UserMessage>, to: &TypedActorSystem<UserMessage>, to_host: &str) {
+  let mut replicator = DistributedData::get(from).expect("distributed data").replicator();
+  let peer = DistributedData::get(to).expect("distributed data").replicator().into_untyped();
+  replicator.tell(ReplicatorCommand::member_up(unique_address(to_host), peer));
+}
+
+fn listing_recorder(system: &TypedActorSystem<UserMessage>, listings: &Listings) -> TypedActorRef<Listing> {
+  let listings = listings.clone();
+  let props = TypedProps::<Listing>::from_behavior_factory(move || {
+    let listings = listings.clone();
+    Behaviors::receive_message(move |_ctx, listing: &Listing| {
+      listings.lock().push(listing.clone());
+      Ok(Behaviors::same())
+    })
+  });
+  let recorder = system.as_untyped().actor_of(props.to_untyped()).expect("spawn listing recorder");
+  TypedActorRef::from_untyped(recorder.into_actor_ref())
+}
+
+fn wait_for_listing(listings: &Listings, mut predicate: impl FnMut(&Listing) -> bool) -> Listing {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  loop {
+    if let Some(listing) = listings.lock().iter().find(|listing| predicate(listing)) {
+      return listing.clone();
+    }
+    assert!(Instant::now() < deadline, "no matching listing among {} listings", listings.lock().len());
+    thread::sleep(Duration::from_millis(5));
+  }
+}
+
+fn publish_cluster_event(system: &TypedActorSystem<UserMessage>, event: ClusterEvent) {
+  let event = EventStreamEvent::Extension { name: String::from("cluster"), payload: AnyMessage::new(event) };
+  system.as_untyped().event_stream().publish(&event);
+}
+
+#[test]
+fn registrations_are_visible_on_other_members_until_the_member_is_removed() {
+  let node1 = cluster_system("node1");
+  let node2 = cluster_system("node2");
+  connect(&node1, &node2, "node2");
+  connect(&node2, &node1, "node1");
+
+  let key = ServiceKey::<u32>::new("pinger");
+  let listings: Listings = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  node2.receptionist().tell(Receptionist::subscribe(&key, listing_recorder(&node2, &listings)));
+  wait_for_listing(&listings, Listing::is_empty);
+  listings.lock().clear();
+
+  let service_props = TypedProps::<u32>::from_behavior_factory(Behaviors::ignore);
+  let service = node1.as_untyped().actor_of(service_props.to_untyped()).expect("spawn service");
+  let service = TypedActorRef::<u32>::from_untyped(service.into_actor_ref());
+  node1.receptionist().tell(Receptionist::register(&key, service.clone()));
+
+  let listing = wait_for_listing(&listings, |listing| listing.refs().len() == 1);
+  assert_eq!(listing.service_instances(&key).expect("service instances").len(), 1);
+  assert_eq!(listing.refs()[0].pid(), service.pid());
+  listings.lock().clear();
+
+  publish_cluster_event(&node2, ClusterEvent::UnreachableMember {
+    node_id:     String::from("node-node1:8080"),
+    authority:   String::from("node1:8080"),
+    observed_at: TimerInstant::from_ticks(1, Duration::from_secs(1)),
+  });
+  let listing = wait_for_listing(&listings, |listing| listing.refs().is_empty() && listing.all_refs().len() == 1);
+  assert!(!listing.services_were_added_or_removed());
+  assert_eq!(listing.all_service_instances(&key).expect("all service instances").len(), 1);
+  listings.lock().clear();
+
+  publish_cluster_event(&node2, ClusterEvent::MemberStatusChanged {
+    node_id:     String::from("node-node1:8080"),
+    authority:   String::from("node1:8080"),
+    from:        NodeStatus::Up,
+    to:          NodeStatus::Removed,
+    observed_at: TimerInstant::from_ticks(2, Duration::from_secs(1)),
+  });
+  let listing = wait_for_listing(&listings, |listing| listing.all_refs().is_empty() && listing.refs().is_empty());
+  assert!(listing.services_were_added_or_removed());
+
+  node1.terminate().expect("terminate node1");
+  node2.terminate().expect("terminate node2");
+}
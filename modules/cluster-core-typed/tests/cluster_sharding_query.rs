@@ -0,0 +1,161 @@
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+extern crate alloc;
+
+use fraktor_actor_adaptor_std_rs::{dispatch::dispatcher::ThreadedExecutor, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{
+  actor::{extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig},
+  dispatch::dispatcher::{
+    DEFAULT_DISPATCHER_ID, DefaultDispatcherFactory, DispatcherConfig, ExecutorShared, MessageDispatcherFactory,
+    TrampolineState,
+  },
+};
+use fraktor_actor_core_typed_rs::{TypedActorRef, TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivatedKind, ClusterIdentity, PartitionIdentityLookup, RendezvousHasher},
+  cluster_provider::NoopClusterProvider,
+  extension::{ClusterApi, ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+  sharding::ShardRegionState,
+  topology::{ClusterTopology, TopologyUpdate},
+};
+use fraktor_cluster_core_typed_rs::{
+  ClusterShardingQueries, ClusterShardingQuery, ClusterShardingStats, CurrentRegions,
+};
+use fraktor_utils_core_rs::{
+  sync::{ArcShared, SpinSyncMutex},
+  time::TimerInstant,
+};
+
+#[derive(Debug)]
+struct UserMessage;
+
+type Received<T> = ArcShared<SpinSyncMutex<Vec<T>>>;
+
+fn cluster_system(members: &[&str]) -> TypedActorSystem<UserMessage> {
+  let cluster_config = ClusterExtensionConfig::new().with_advertised_address("node1:8080");
+  let cluster_installer = ClusterExtensionInstaller::new(cluster_config, |_event_stream, _block_list, _address| {
+    Box::new(NoopClusterProvider::new())
+  })
+  .with_identity_lookup_factory(|| Box::new(PartitionIdentityLookup::with_defaults()));
+  let extensions = ExtensionInstallers::default().with_extension_installer(cluster_installer);
+  // 統計収集のタイムアウトを問い合わせ処理と並行して進めるため、インライン実行しない dispatcher
+  // を使う
+  let executor = ExecutorShared::new(Box::new(ThreadedExecutor::new()), TrampolineState::new());
+  let dispatcher: Box<dyn MessageDispatcherFactory> =
+    Box::new(DefaultDispatcherFactory::new(&DispatcherConfig::with_defaults(DEFAULT_DISPATCHER_ID), executor));
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(extensions)
+    .with_dispatcher_factory(DEFAULT_DISPATCHER_ID, ArcShared::new(dispatcher));
+  let props = TypedProps::<UserMessage>::from_behavior_factory(Behaviors::ignore);
+  let system = TypedActorSystem::create_from_props(&props, config).expect("typed system");
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  extension.setup_member_kinds(vec![ActivatedKind::new("user")]).expect("setup kinds");
+  let members: Vec<String> = members.iter().map(|member| String::from(*member)).collect();
+  let topology = ClusterTopology::new(1, members.clone(), Vec::new(), Vec::new());
+  extension.on_topology(&TopologyUpdate::new(
+    topology,
+    members.clone(),
+    members,
+    Vec::new(),
+    Vec::new(),
+    Vec::new(),
+    TimerInstant::from_ticks(1, Duration::from_secs(1)),
+  ));
+  system
+}
+
+fn activate_local_grains(system: &TypedActorSystem<UserMessage>, members: &[&str], count: usize) -> Vec<String> {
+  let cluster = ClusterApi::try_from_system(system.as_untyped()).expect("cluster api");
+  let authorities: Vec<String> = members.iter().map(|member| String::from(*member)).collect();
+  let ids: Vec<String> = (0..)
+    .map(|index| format!("{index}"))
+    .filter(|id| {
+      let identity = ClusterIdentity::new("user", id.as_str()).expect("identity");
+      RendezvousHasher::select(&authorities, &identity.key()).is_some_and(|owner| owner == "node1:8080")
+    })
+    .take(count)
+    .collect();
+  for id in &ids {
+    let identity = ClusterIdentity::new("user", id.as_str()).expect("identity");
+    cluster.placement_of(&identity).expect("placement");
+  }
+  ids
+}
+
+fn probe<T>(system: &TypedActorSystem<UserMessage>) -> (TypedActorRef<T>, Received<T>)
+where
+  T: Clone + Send + Sync + 'static, {
+  let received: Received<T> = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let props = {
+    let received = received.clone();
+    TypedProps::<T>::from_behavior_factory(move || {
+      let received = received.clone();
+      Behaviors::receive_message(move |_ctx, message: &T| {
+        received.lock().push(message.clone());
+        Ok(Behaviors::same())
+      })
+    })
+  };
+  let probe = system.as_untyped().actor_of(props.to_untyped()).expect("spawn probe");
+  (TypedActorRef::from_untyped(probe.into_actor_ref()), received)
+}
+
+fn wait_for<T: Clone>(received: &Received<T>) -> T {
+  let deadline = Instant::now() + Duration::from_secs(3);
+  loop {
+    if let Some(message) = received.lock().first().cloned() {
+      return message;
+    }
+    assert!(Instant::now() < deadline, "expected reply did not arrive");
+    thread::yield_now();
+  }
+}
+
+#[test]
+fn queries_report_local_grains_regions_and_cluster_stats() {
+  let members = ["node1:8080"];
+  let system = cluster_system(&members);
+  let mut ids = activate_local_grains(&system, &members, 3);
+  ids.sort();
+  let mut queries = ClusterShardingQueries::get(&system).expect("queries").init().expect("init");
+
+  let (state_probe, state) = probe::<ShardRegionState>(&system);
+  queries.tell(ClusterShardingQuery::GetShardRegionState { kind: String::from("user"), reply_to: state_probe });
+  let mut entity_ids: Vec<String> = wait_for(&state).shards.into_iter().flat_map(|shard| shard.entity_ids).collect();
+  entity_ids.sort();
+  assert_eq!(entity_ids, ids);
+
+  let (regions_probe, regions) = probe::<CurrentRegions>(&system);
+  queries.tell(ClusterShardingQuery::GetCurrentRegions(regions_probe));
+  assert_eq!(wait_for(&regions).regions, vec![String::from("node1:8080")]);
+
+  let (stats_probe, stats) = probe::<ClusterShardingStats>(&system);
+  queries
+    .tell(ClusterShardingQuery::GetClusterShardingStats { timeout: Duration::from_secs(1), reply_to: stats_probe });
+  let stats = wait_for(&stats);
+  assert_eq!(stats.entity_count("user"), 3);
+  assert_eq!(stats.regions.keys().cloned().collect::<Vec<_>>(), vec![String::from("node1:8080")]);
+  assert!(stats.failed.is_empty());
+  system.terminate().expect("terminate");
+}
+
+#[test]
+fn cluster_stats_report_regions_without_an_answer_as_failed() {
+  let members = ["node1:8080", "node2:8080"];
+  let system = cluster_system(&members);
+  activate_local_grains(&system, &members, 2);
+  let mut queries = ClusterShardingQueries::get(&system).expect("queries").init().expect("init");
+
+  let (stats_probe, stats) = probe::<ClusterShardingStats>(&system);
+  queries
+    .tell(ClusterShardingQuery::GetClusterShardingStats { timeout: Duration::from_millis(50), reply_to: stats_probe });
+
+  let stats = wait_for(&stats);
+  assert_eq!(stats.regions.get("node1:8080").and_then(|region| region.entity_counts.get("user")), Some(&2));
+  assert_eq!(stats.failed, vec![String::from("node2:8080")]);
+  system.terminate().expect("terminate");
+}
@@ -0,0 +1,87 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+extern crate alloc;
+
+use fraktor_actor_adaptor_std_rs::{dispatch::dispatcher::ThreadedExecutor, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{
+  actor::{extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig},
+  dispatch::dispatcher::{
+    DEFAULT_DISPATCHER_ID, DefaultDispatcherFactory, DispatcherConfig, ExecutorShared, MessageDispatcherFactory,
+    TrampolineState,
+  },
+};
+use fraktor_actor_core_typed_rs::{TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::{
+  cluster_provider::NoopClusterProvider,
+  extension::{ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+};
+use fraktor_cluster_core_typed_rs::{ClusterSingleton, ClusterSingletonConfig, SingletonActor};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+#[derive(Debug)]
+struct UserMessage;
+
+#[derive(Clone, Debug)]
+enum CounterCommand {
+  Increment(u32),
+}
+
+type Received = ArcShared<SpinSyncMutex<Vec<u32>>>;
+
+fn cluster_system() -> TypedActorSystem<UserMessage> {
+  let cluster_config = ClusterExtensionConfig::new().with_advertised_address("node1:8080");
+  let cluster_installer = ClusterExtensionInstaller::new(cluster_config, |_event_stream, _block_list, _address| {
+    Box::new(NoopClusterProvider::new())
+  });
+  let extensions = ExtensionInstallers::default().with_extension_installer(cluster_installer);
+  // 識別・引き継ぎタイマーを応答処理から操作するため、インライン実行しない dispatcher を使う
+  let executor = ExecutorShared::new(Box::new(ThreadedExecutor::new()), TrampolineState::new());
+  let dispatcher: Box<dyn MessageDispatcherFactory> =
+    Box::new(DefaultDispatcherFactory::new(&DispatcherConfig::with_defaults(DEFAULT_DISPATCHER_ID), executor));
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(extensions)
+    .with_dispatcher_factory(DEFAULT_DISPATCHER_ID, ArcShared::new(dispatcher));
+  let props = TypedProps::<UserMessage>::from_behavior_factory(Behaviors::ignore);
+  let system = TypedActorSystem::create_from_props(&props, config).expect("typed system");
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  system
+}
+
+fn counter(received: &Received) -> TypedProps<CounterCommand> {
+  let received = received.clone();
+  TypedProps::<CounterCommand>::from_behavior_factory(move || {
+    let received = received.clone();
+    Behaviors::receive_message(move |_ctx, command: &CounterCommand| {
+      let CounterCommand::Increment(amount) = command;
+      received.lock().push(*amount);
+      Ok(Behaviors::same())
+    })
+  })
+}
+
+fn wait_for(received: &Received, expected: &[u32]) {
+  let deadline = Instant::now() + Duration::from_secs(3);
+  while received.lock().as_slice() != expected {
+    assert!(Instant::now() < deadline, "singleton received {:?}", received.lock().as_slice());
+    thread::yield_now();
+  }
+}
+
+#[test]
+fn messages_sent_through_the_proxy_reach_the_singleton_on_the_oldest_member() {
+  let system = cluster_system();
+  let received: Received = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let settings = ClusterSingletonConfig::new().with_singleton_identification_interval(Duration::from_millis(10));
+  let singleton = SingletonActor::new(counter(&received), "counter").with_settings(settings);
+
+  let mut proxy = ClusterSingleton::get(&system).expect("cluster singleton").init(singleton).expect("init");
+  proxy.tell(CounterCommand::Increment(1));
+  proxy.tell(CounterCommand::Increment(2));
+
+  wait_for(&received, &[1, 2]);
+  system.terminate().expect("terminate");
+}
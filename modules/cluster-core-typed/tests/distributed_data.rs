@@ -0,0 +1,163 @@
+extern crate alloc;
+
+use alloc::{format, vec::Vec};
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::actor::{
+  error::ActorError, extension::ExtensionInstallers, messaging::AnyMessage, scheduler::SchedulerConfig,
+  setup::ActorSystemConfig,
+};
+use fraktor_actor_core_typed_rs::{TypedActorRef, TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::ddata::{
+  FlushChanges, GCounter, GCounterKey, Get, GetResponse, ReadConsistency, ReplicatorExtensionInstaller,
+  ReplicatorSettings, SelfUniqueAddress, SubscribeResponse, Update, UpdateResponse, WriteConsistency,
+};
+use fraktor_cluster_core_typed_rs::{DistributedData, ReplicatorMessageAdapter};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+#[derive(Clone, Debug)]
+enum CounterCommand {
+  Increment(u64),
+  Read,
+  Subscribe,
+  Unsubscribe,
+  UpdateDone(UpdateResponse<GCounter>),
+  GetDone(GetResponse<GCounter>),
+  Changed(SubscribeResponse<GCounter>),
+}
+
+type Received = ArcShared<SpinSyncMutex<Vec<CounterCommand>>>;
+
+fn counter_key() -> GCounterKey {
+  GCounterKey::new("counter")
+}
+
+fn recoverable(error: impl core::fmt::Debug) -> ActorError {
+  ActorError::recoverable(format!("{error:?}"))
+}
+
+fn counter_behavior_props(
+  adapter: ReplicatorMessageAdapter<CounterCommand, GCounter>,
+  self_address: SelfUniqueAddress,
+  received: Received,
+) -> TypedProps<CounterCommand> {
+  TypedProps::<CounterCommand>::from_behavior_factory(move || {
+    let adapter = adapter.clone();
+    let self_address = self_address.clone();
+    let received = received.clone();
+    Behaviors::receive_message(move |ctx, command: &CounterCommand| {
+      match command {
+        | CounterCommand::Increment(amount) => {
+          let amount = *amount;
+          let self_address = self_address.clone();
+          let update = Update::new(counter_key(), WriteConsistency::Local);
+          adapter
+            .ask_update(
+              ctx,
+              update,
+              move |current: Option<&GCounter>| {
+                current
+                  .cloned()
+                  .unwrap_or_default()
+                  .increment(&self_address, amount)
+                  .map_err(|error| format!("{error:?}"))
+              },
+              CounterCommand::UpdateDone,
+            )
+            .map_err(recoverable)?;
+        },
+        | CounterCommand::Read => {
+          let get = Get::new(counter_key(), ReadConsistency::Local);
+          adapter.ask_get(ctx, get, CounterCommand::GetDone).map_err(recoverable)?;
+        },
+        | CounterCommand::Subscribe => {
+          adapter.subscribe(ctx, counter_key(), CounterCommand::Changed).map_err(recoverable)?;
+        },
+        | CounterCommand::Unsubscribe => adapter.unsubscribe(&counter_key()),
+        | response => received.lock().push(response.clone()),
+      }
+      Ok(Behaviors::same())
+    })
+  })
+}
+
+fn wait_for<T>(received: &Received, mut select: impl FnMut(&CounterCommand) -> Option<T>) -> T {
+  let deadline = Instant::now() + Duration::from_secs(3);
+  loop {
+    {
+      let mut received = received.lock();
+      if let Some(index) = received.iter().position(|command| select(command).is_some()) {
+        let command = received.remove(index);
+        return select(&command).expect("command was selected");
+      }
+    }
+    assert!(Instant::now() < deadline, "expected response did not arrive");
+    thread::yield_now();
+  }
+}
+
+fn counter_value(counter: &GCounter) -> u128 {
+  counter.value().expect("value fits")
+}
+
+#[test]
+fn typed_behavior_reads_updates_and_subscribes_to_replicated_counter() {
+  let self_address = SelfUniqueAddress::new(UniqueAddress::new(Address::new("sys", "node-a", 2552), 1));
+  // 変更通知は FlushChanges で明示的に流す。
+  let settings = ReplicatorSettings::new().with_notify_subscribers_interval(Duration::from_secs(60));
+  let installer = ReplicatorExtensionInstaller::new(self_address.clone()).with_settings(settings);
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(ExtensionInstallers::default().with_extension_installer(installer));
+  let guardian = TypedProps::<CounterCommand>::from_behavior_factory(Behaviors::ignore);
+  let system = TypedActorSystem::create_from_props(&guardian, config).expect("typed system");
+  let distributed_data = DistributedData::get(&system).expect("distributed data");
+  let adapter = distributed_data.message_adapter::<CounterCommand, GCounter>(Duration::from_secs(3));
+  let received: Received = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let props = counter_behavior_props(adapter, distributed_data.self_unique_address().clone(), received.clone());
+  let counter = system.as_untyped().actor_of(props.to_untyped()).expect("spawn counter");
+  let mut counter = TypedActorRef::<CounterCommand>::from_untyped(counter.into_actor_ref());
+  let mut replicator = distributed_data.replicator().into_untyped();
+
+  counter.tell(CounterCommand::Read);
+  wait_for(&received, |command| match command {
+    | CounterCommand::GetDone(GetResponse::NotFound { .. }) => Some(()),
+    | _ => None,
+  });
+
+  counter.tell(CounterCommand::Subscribe);
+  counter.tell(CounterCommand::Increment(2));
+  wait_for(&received, |command| match command {
+    | CounterCommand::UpdateDone(UpdateResponse::Success { .. }) => Some(()),
+    | _ => None,
+  });
+  replicator.tell(AnyMessage::new(FlushChanges));
+  let changed = wait_for(&received, |command| match command {
+    | CounterCommand::Changed(SubscribeResponse::Changed { data, .. }) => Some(counter_value(data)),
+    | _ => None,
+  });
+  assert_eq!(changed, 2);
+
+  counter.tell(CounterCommand::Unsubscribe);
+  counter.tell(CounterCommand::Increment(3));
+  wait_for(&received, |command| match command {
+    | CounterCommand::UpdateDone(UpdateResponse::Success { .. }) => Some(()),
+    | _ => None,
+  });
+  replicator.tell(AnyMessage::new(FlushChanges));
+  counter.tell(CounterCommand::Read);
+  let value = wait_for(&received, |command| match command {
+    | CounterCommand::GetDone(response) => response.data().map(counter_value),
+    | _ => None,
+  });
+  assert_eq!(value, 5);
+  assert!(
+    !received.lock().iter().any(|command| matches!(command, CounterCommand::Changed(_))),
+    "unsubscribed behavior must not receive further changes"
+  );
+
+  system.terminate().expect("terminate");
+}
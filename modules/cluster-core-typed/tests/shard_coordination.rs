@@ -0,0 +1,197 @@
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+extern crate alloc;
+
+use fraktor_actor_adaptor_std_rs::{dispatch::dispatcher::ThreadedExecutor, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{
+  actor::{extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig},
+  dispatch::dispatcher::{
+    DEFAULT_DISPATCHER_ID, DefaultDispatcherFactory, DispatcherConfig, ExecutorShared, MessageDispatcherFactory,
+    TrampolineState,
+  },
+};
+use fraktor_actor_core_typed_rs::{TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_cluster_core_kernel_rs::{
+  activation::{ActivatedKind, ClusterIdentity, PartitionIdentityLookup},
+  cluster_provider::NoopClusterProvider,
+  ddata::{ReplicatorCommand, ReplicatorExtensionInstaller, ReplicatorSettings, SelfUniqueAddress},
+  extension::{ClusterApi, ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+  sharding::{
+    ExternalShardAllocation, ExternalShardAllocationStrategy, LeastShardAllocationStrategy, ShardAllocationConfig,
+    ShardAllocationStrategy, ShardId,
+  },
+  topology::{ClusterTopology, TopologyUpdate},
+};
+use fraktor_cluster_core_typed_rs::{DistributedData, ShardCoordination};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::{sync::ArcShared, time::TimerInstant};
+
+#[derive(Debug)]
+struct UserMessage;
+
+const KIND: &str = "counter";
+const NUMBER_OF_SHARDS: u32 = 8;
+
+fn unique_address(host: &str) -> UniqueAddress {
+  UniqueAddress::new(Address::new("sys", host, 8080), 1)
+}
+
+fn cluster_system<F>(host: &str, strategy: F) -> TypedActorSystem<UserMessage>
+where
+  F: Fn() -> Box<dyn ShardAllocationStrategy> + Send + Sync + 'static, {
+  let cluster_config = ClusterExtensionConfig::new().with_advertised_address(alloc::format!("{host}:8080"));
+  let cluster_installer = ClusterExtensionInstaller::new(cluster_config, |_event_stream, _block_list, _address| {
+    Box::new(NoopClusterProvider::new())
+  })
+  .with_identity_lookup_factory(move || {
+    Box::new(PartitionIdentityLookup::with_defaults().with_shard_allocation(
+      KIND,
+      ShardAllocationConfig::new().with_number_of_shards(NUMBER_OF_SHARDS),
+      strategy(),
+    ))
+  });
+  let replicator_settings = ReplicatorSettings::new()
+    .with_gossip_interval(Duration::from_millis(20))
+    .with_notify_subscribers_interval(Duration::from_millis(10));
+  let replicator_installer =
+    ReplicatorExtensionInstaller::new(SelfUniqueAddress::new(unique_address(host))).with_settings(replicator_settings);
+  let extensions = ExtensionInstallers::default()
+    .with_extension_installer(cluster_installer)
+    .with_extension_installer(replicator_installer);
+  // 同期タイマーを複製と並行して進めるため、インライン実行しない dispatcher を使う
+  let executor = ExecutorShared::new(Box::new(ThreadedExecutor::new()), TrampolineState::new());
+  let dispatcher: Box<dyn MessageDispatcherFactory> =
+    Box::new(DefaultDispatcherFactory::new(&DispatcherConfig::with_defaults(DEFAULT_DISPATCHER_ID), executor));
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(extensions)
+    .with_dispatcher_factory(DEFAULT_DISPATCHER_ID, ArcShared::new(dispatcher));
+  let props = TypedProps::<UserMessage>::from_behavior_factory(Behaviors::ignore);
+  let system = TypedActorSystem::create_from_props(&props, config).expect("typed system");
+  let extension = extension(&system);
+  extension.start_member().expect("start member");
+  extension.setup_member_kinds(vec![ActivatedKind::new(KIND)]).expect("setup kinds");
+  system
+}
+
+fn extension(system: &TypedActorSystem<UserMessage>) -> ArcShared<ClusterExtension> {
+  system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension")
+}
+
+fn cluster(system: &TypedActorSystem<UserMessage>) -> ClusterApi {
+  ClusterApi::try_from_system(system.as_untyped()).expect("cluster api")
+}
+
+fn update_members(system: &TypedActorSystem<UserMessage>, members: &[&str]) {
+  let members: Vec<String> = members.iter().map(|host| alloc::format!("{host}:8080")).collect();
+  let topology = ClusterTopology::new(1, members.clone(), Vec::new(), Vec::new());
+  extension(system).on_topology(&TopologyUpdate::new(
+    topology,
+    members.clone(),
+    members,
+    Vec::new(),
+    Vec::new(),
+    Vec::new(),
+    TimerInstant::from_ticks(1, Duration::from_secs(1)),
+  ));
+}
+
+fn connect(from: &TypedActorSystem<UserMessage>, to: &TypedActorSystem<UserMessage>, to_host: &str) {
+  let mut replicator = DistributedData::get(from).expect("distributed data").replicator();
+  let peer = DistributedData::get(to).expect("distributed data").replicator().into_untyped();
+  replicator.tell(ReplicatorCommand::member_up(unique_address(to_host), peer));
+}
+
+/// Starts two connected members coordinating the shards of [`KIND`].
+fn two_members<F>(strategy: F) -> (TypedActorSystem<UserMessage>, TypedActorSystem<UserMessage>)
+where
+  F: Fn() -> Box<dyn ShardAllocationStrategy> + Clone + Send + Sync + 'static, {
+  let node1 = cluster_system("node1", strategy.clone());
+  let node2 = cluster_system("node2", strategy);
+  connect(&node1, &node2, "node2");
+  connect(&node2, &node1, "node1");
+  update_members(&node1, &["node1", "node2"]);
+  update_members(&node2, &["node1", "node2"]);
+  for system in [&node1, &node2] {
+    ShardCoordination::get(system)
+      .expect("shard coordination")
+      .start_with_sync_interval(Duration::from_millis(10))
+      .expect("start shard coordination");
+  }
+  (node1, node2)
+}
+
+fn wait_until(mut condition: impl FnMut() -> bool, description: &str) {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while !condition() {
+    assert!(Instant::now() < deadline, "timed out waiting until {description}");
+    thread::sleep(Duration::from_millis(5));
+  }
+}
+
+/// Returns whether the grain `id` is active on `system`, activating it when it is owned locally.
+fn active_locally(system: &TypedActorSystem<UserMessage>, id: &str) -> bool {
+  let cluster = cluster(system);
+  let identity = ClusterIdentity::new(KIND, id).expect("identity");
+  if cluster.owner_of(&identity).ok() != Some(cluster.self_authority()) {
+    return false;
+  }
+  // 所有者が自メンバーに決まった後は活性化して、ローカルのシャード状態に現れるのを確認する
+  if cluster.placement_of(&identity).is_err() {
+    return false;
+  }
+  hosts(system, id)
+}
+
+fn hosts(system: &TypedActorSystem<UserMessage>, id: &str) -> bool {
+  cluster(system).shard_region_state(KIND).shards.iter().any(|shard| shard.entity_ids.iter().any(|entity| entity == id))
+}
+
+#[test]
+fn every_shard_has_a_single_owner_across_members() {
+  let (node1, node2) = two_members(|| Box::new(LeastShardAllocationStrategy::new(1, 0.1)));
+  let identities: Vec<ClusterIdentity> =
+    (0..16).map(|index| ClusterIdentity::new(KIND, alloc::format!("{index}")).expect("identity")).collect();
+
+  wait_until(
+    || {
+      identities.iter().all(|identity| {
+        let on_node1 = cluster(&node1).owner_of(identity);
+        let on_node2 = cluster(&node2).owner_of(identity);
+        on_node1.is_ok() && on_node1.ok() == on_node2.ok()
+      })
+    },
+    "both members agree on the owner of every grain",
+  );
+
+  node1.terminate().expect("terminate node1");
+  node2.terminate().expect("terminate node2");
+}
+
+#[test]
+fn a_shard_is_handed_off_to_another_member_once_its_previous_owner_stopped_it() {
+  let allocation = ExternalShardAllocation::new();
+  let pin = |host: &str| {
+    let locations = (0..NUMBER_OF_SHARDS).map(|shard| (ShardId(shard), alloc::format!("{host}:8080"))).collect();
+    allocation.update_shard_locations(locations);
+  };
+  pin("node2");
+  let strategy = {
+    let allocation = allocation.clone();
+    move || -> Box<dyn ShardAllocationStrategy> { Box::new(ExternalShardAllocationStrategy::new(allocation.clone())) }
+  };
+  let (node1, node2) = two_members(strategy);
+  wait_until(|| active_locally(&node2, "g1"), "the grain is active on node2");
+
+  pin("node1");
+  extension(&node1).rebalance_shards();
+
+  wait_until(|| !hosts(&node2, "g1"), "node2 stopped the handed-off shard");
+  wait_until(|| active_locally(&node1, "g1"), "the grain is active on node1 after the hand-off");
+  assert!(!hosts(&node2, "g1"), "the grain must not be active on both members");
+
+  node1.terminate().expect("terminate node1");
+  node2.terminate().expect("terminate node2");
+}
@@ -0,0 +1,233 @@
+use alloc::{boxed::Box, collections::BTreeSet, string::String, vec::Vec};
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+extern crate alloc;
+
+use fraktor_actor_adaptor_std_rs::{dispatch::dispatcher::ThreadedExecutor, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{
+  actor::{extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig},
+  dispatch::dispatcher::{
+    DEFAULT_DISPATCHER_ID, DefaultDispatcherFactory, DispatcherConfig, ExecutorShared, MessageDispatcherFactory,
+    TrampolineState,
+  },
+};
+use fraktor_actor_core_typed_rs::{
+  Behavior, TypedActorSystem, TypedProps, dsl::Behaviors, message_and_signals::BehaviorSignal,
+};
+use fraktor_cluster_core_kernel_rs::{
+  activation::PartitionIdentityLookup,
+  cluster_provider::NoopClusterProvider,
+  ddata::{ReplicatorCommand, ReplicatorExtensionInstaller, ReplicatorSettings, SelfUniqueAddress},
+  extension::{ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+  topology::{ClusterTopology, TopologyUpdate},
+};
+use fraktor_cluster_core_typed_rs::{
+  DistributedData, ShardedDaemonProcess, ShardedDaemonProcessCommand, ShardedDaemonProcessSettings,
+};
+use fraktor_remote_core_rs::address::{Address, UniqueAddress};
+use fraktor_utils_core_rs::{
+  sync::{ArcShared, SpinSyncMutex},
+  time::TimerInstant,
+};
+
+#[derive(Debug)]
+struct UserMessage;
+
+type Indices = ArcShared<SpinSyncMutex<BTreeSet<usize>>>;
+
+fn unique_address(host: &str) -> UniqueAddress {
+  UniqueAddress::new(Address::new("sys", host, 8080), 1)
+}
+
+fn cluster_system(host: &str) -> TypedActorSystem<UserMessage> {
+  let cluster_config = ClusterExtensionConfig::new().with_advertised_address(alloc::format!("{host}:8080"));
+  let cluster_installer = ClusterExtensionInstaller::new(cluster_config, |_event_stream, _block_list, _address| {
+    Box::new(NoopClusterProvider::new())
+  })
+  .with_identity_lookup_factory(|| Box::new(PartitionIdentityLookup::with_defaults()));
+  let replicator_settings = ReplicatorSettings::new()
+    .with_gossip_interval(Duration::from_millis(20))
+    .with_notify_subscribers_interval(Duration::from_millis(10));
+  let replicator_installer =
+    ReplicatorExtensionInstaller::new(SelfUniqueAddress::new(unique_address(host))).with_settings(replicator_settings);
+  let extensions = ExtensionInstallers::default()
+    .with_extension_installer(cluster_installer)
+    .with_extension_installer(replicator_installer);
+  // keep-alive タイマーを worker の起動・停止と並行して進めるため、インライン実行しない dispatcher
+  // を使う
+  let executor = ExecutorShared::new(Box::new(ThreadedExecutor::new()), TrampolineState::new());
+  let dispatcher: Box<dyn MessageDispatcherFactory> =
+    Box::new(DefaultDispatcherFactory::new(&DispatcherConfig::with_defaults(DEFAULT_DISPATCHER_ID), executor));
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(extensions)
+    .with_dispatcher_factory(DEFAULT_DISPATCHER_ID, ArcShared::new(dispatcher));
+  let props = TypedProps::<UserMessage>::from_behavior_factory(Behaviors::ignore);
+  let system = TypedActorSystem::create_from_props(&props, config).expect("typed system");
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  system
+}
+
+fn update_members(system: &TypedActorSystem<UserMessage>, hash: u64, members: &[&str], left: &[&str]) {
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  let members: Vec<String> = members.iter().map(|host| alloc::format!("{host}:8080")).collect();
+  let left: Vec<String> = left.iter().map(|host| alloc::format!("{host}:8080")).collect();
+  let topology = ClusterTopology::new(hash, members.clone(), left.clone(), Vec::new());
+  extension.on_topology(&TopologyUpdate::new(
+    topology,
+    members.clone(),
+    members,
+    left,
+    Vec::new(),
+    Vec::new(),
+    TimerInstant::from_ticks(hash, Duration::from_secs(1)),
+  ));
+}
+
+fn connect(from: &TypedActorSystem<UserMessage>, to: &TypedActorSystem<UserMessage>, to_host: &str) {
+  let mut replicator = DistributedData::get(from).expect("distributed data").replicator();
+  let peer = DistributedData::get(to).expect("distributed data").replicator().into_untyped();
+  replicator.tell(ReplicatorCommand::member_up(unique_address(to_host), peer));
+}
+
+fn new_indices() -> Indices {
+  ArcShared::new(SpinSyncMutex::new(BTreeSet::new()))
+}
+
+fn worker_factory(
+  started: &Indices,
+  stopped: &Indices,
+) -> impl Fn(usize) -> Behavior<UserMessage> + Send + Sync + 'static {
+  let started = started.clone();
+  let stopped = stopped.clone();
+  move |index| worker(index, &started, &stopped)
+}
+
+fn fast_settings() -> ShardedDaemonProcessSettings {
+  ShardedDaemonProcessSettings::new().with_keep_alive_interval(Duration::from_millis(10))
+}
+
+fn worker(index: usize, started: &Indices, stopped: &Indices) -> Behavior<UserMessage> {
+  let started = started.clone();
+  let stopped = stopped.clone();
+  Behaviors::setup(move |_ctx| {
+    started.lock().insert(index);
+    let stopped = stopped.clone();
+    Behaviors::receive_signal(move |_ctx, signal| {
+      if matches!(signal, BehaviorSignal::PostStop) {
+        stopped.lock().insert(index);
+      }
+      Ok(Behaviors::same())
+    })
+  })
+}
+
+fn wait_for(indices: &Indices, expected: &[usize]) {
+  let expected: BTreeSet<usize> = expected.iter().copied().collect();
+  let deadline = Instant::now() + Duration::from_secs(3);
+  while *indices.lock() != expected {
+    assert!(Instant::now() < deadline, "workers {:?}, expected {expected:?}", indices.lock());
+    thread::yield_now();
+  }
+}
+
+fn wait_until(mut condition: impl FnMut() -> bool, description: &str) {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while !condition() {
+    assert!(Instant::now() < deadline, "timed out waiting until {description}");
+    thread::sleep(Duration::from_millis(5));
+  }
+}
+
+/// Returns the indices currently running: started and not stopped since.
+fn running(started: &Indices, stopped: &Indices) -> BTreeSet<usize> {
+  started.lock().difference(&stopped.lock()).copied().collect()
+}
+
+#[test]
+fn workers_are_started_for_every_index_and_follow_changes_of_the_number_of_processes() {
+  let system = cluster_system("node1");
+  update_members(&system, 1, &["node1"], &[]);
+  let started = new_indices();
+  let stopped = new_indices();
+
+  let mut keeper = ShardedDaemonProcess::get(&system)
+    .expect("sharded daemon process")
+    .init_with_settings("projections", 3, worker_factory(&started, &stopped), fast_settings())
+    .expect("init");
+  wait_for(&started, &[0, 1, 2]);
+
+  keeper.tell(ShardedDaemonProcessCommand::ChangeNumberOfProcesses(5));
+  wait_for(&started, &[0, 1, 2, 3, 4]);
+
+  keeper.tell(ShardedDaemonProcessCommand::ChangeNumberOfProcesses(2));
+  wait_for(&stopped, &[2, 3, 4]);
+  system.terminate().expect("terminate");
+}
+
+#[test]
+fn workers_are_spread_over_members_and_taken_over_when_a_member_is_lost() {
+  let node1 = cluster_system("node1");
+  let node2 = cluster_system("node2");
+  connect(&node1, &node2, "node2");
+  connect(&node2, &node1, "node1");
+  update_members(&node1, 1, &["node1", "node2"], &[]);
+  update_members(&node2, 1, &["node1", "node2"], &[]);
+  let (started1, stopped1) = (new_indices(), new_indices());
+  let (started2, stopped2) = (new_indices(), new_indices());
+
+  ShardedDaemonProcess::get(&node1)
+    .expect("sharded daemon process")
+    .init_with_settings("projections", 8, worker_factory(&started1, &stopped1), fast_settings())
+    .expect("init node1");
+  ShardedDaemonProcess::get(&node2)
+    .expect("sharded daemon process")
+    .init_with_settings("projections", 8, worker_factory(&started2, &stopped2), fast_settings())
+    .expect("init node2");
+
+  let all: BTreeSet<usize> = (0..8).collect();
+  wait_until(
+    || {
+      let on_node1 = running(&started1, &stopped1);
+      let on_node2 = running(&started2, &stopped2);
+      on_node1.is_disjoint(&on_node2) && on_node1.union(&on_node2).copied().collect::<BTreeSet<_>>() == all
+    },
+    "every index runs on exactly one member",
+  );
+  assert!(!running(&started2, &stopped2).is_empty(), "node2 should own some of the workers");
+
+  node2.terminate().expect("terminate node2");
+  update_members(&node1, 2, &["node1"], &["node2"]);
+  wait_until(|| running(&started1, &stopped1) == all, "node1 runs every index after node2 left");
+  node1.terminate().expect("terminate node1");
+}
+
+#[test]
+fn a_member_joining_later_runs_with_the_replicated_number_of_processes() {
+  let node1 = cluster_system("node1");
+  update_members(&node1, 1, &["node1"], &[]);
+  let (started1, stopped1) = (new_indices(), new_indices());
+  let mut keeper = ShardedDaemonProcess::get(&node1)
+    .expect("sharded daemon process")
+    .init_with_settings("projections", 2, worker_factory(&started1, &stopped1), fast_settings())
+    .expect("init node1");
+  keeper.tell(ShardedDaemonProcessCommand::ChangeNumberOfProcesses(4));
+  wait_for(&started1, &[0, 1, 2, 3]);
+
+  // node2 は初期値 2 で起動するが、複製された数 4 に従う
+  let node2 = cluster_system("node2");
+  update_members(&node2, 1, &["node2"], &[]);
+  connect(&node1, &node2, "node2");
+  connect(&node2, &node1, "node1");
+  let (started2, stopped2) = (new_indices(), new_indices());
+  ShardedDaemonProcess::get(&node2)
+    .expect("sharded daemon process")
+    .init_with_settings("projections", 2, worker_factory(&started2, &stopped2), fast_settings())
+    .expect("init node2");
+  wait_for(&started2, &[0, 1, 2, 3]);
+
+  node1.terminate().expect("terminate node1");
+  node2.terminate().expect("terminate node2");
+}
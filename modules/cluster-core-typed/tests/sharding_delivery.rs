@@ -0,0 +1,288 @@
+use alloc::{
+  boxed::Box,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+extern crate alloc;
+
+use fraktor_actor_adaptor_std_rs::{dispatch::dispatcher::ThreadedExecutor, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Pid,
+    actor_path::{ActorPath, ActorPathScheme},
+    actor_ref::{ActorRef, ActorRefSender, SendOutcome},
+    actor_ref_provider::{ActorRefProvider, ActorRefProviderHandleShared},
+    error::{ActorError, SendError},
+    extension::ExtensionInstallers,
+    messaging::AnyMessage,
+    scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  dispatch::dispatcher::{
+    DEFAULT_DISPATCHER_ID, DefaultDispatcherFactory, DispatcherConfig, ExecutorShared, MessageDispatcherFactory,
+    TrampolineState,
+  },
+  system::{ActorSystem, TerminationSignal},
+};
+use fraktor_actor_core_typed_rs::{
+  TypedActorRef, TypedActorSystem, TypedProps,
+  delivery::{ConsumerControllerConfirmed, ConsumerControllerDelivery, ProducerControllerConfig, SequencedMessage},
+  dsl::Behaviors,
+};
+use fraktor_cluster_core_kernel_rs::{
+  activation::{
+    ActivatedKind, IdentityLookup, IdentitySetupError, LookupError, PlacementDecision, PlacementLocality,
+    PlacementResolution,
+  },
+  cluster_provider::NoopClusterProvider,
+  extension::{ClusterExtension, ClusterExtensionConfig, ClusterExtensionInstaller},
+  grain::GrainKey,
+};
+use fraktor_cluster_core_typed_rs::{
+  GrainTypeKey, ShardingConsumerController, ShardingEnvelope, ShardingProducerController,
+  ShardingProducerControllerCommand, ShardingProducerControllerConfig, ShardingProducerControllerRequestNext,
+};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+#[derive(Debug)]
+struct UserMessage;
+
+type Shared<T> = ArcShared<SpinSyncMutex<T>>;
+
+// ─── fixture: grain を現在の活性化先へ転送する ActorRefProvider ──────────────
+
+struct RemoteIdentityLookup;
+
+impl IdentityLookup for RemoteIdentityLookup {
+  fn setup_member(&mut self, _kinds: &[ActivatedKind]) -> Result<(), IdentitySetupError> {
+    Ok(())
+  }
+
+  fn setup_client(&mut self, _kinds: &[ActivatedKind]) -> Result<(), IdentitySetupError> {
+    Ok(())
+  }
+
+  fn resolve(&mut self, key: &GrainKey, now: u64) -> Result<PlacementResolution, LookupError> {
+    let authority = "node2:8080".to_string();
+    Ok(PlacementResolution {
+      pid:      alloc::format!("{authority}::{}", key.value()),
+      decision: PlacementDecision { key: key.clone(), authority, observed_at: now },
+      locality: PlacementLocality::Remote,
+    })
+  }
+}
+
+struct ActivationProvider {
+  system:     ActorSystem,
+  activation: Shared<Option<ActorRef>>,
+}
+
+impl ActorRefProvider for ActivationProvider {
+  fn supported_schemes(&self) -> &'static [ActorPathScheme] {
+    static SCHEMES: [ActorPathScheme; 1] = [ActorPathScheme::FraktorTcp];
+    &SCHEMES
+  }
+
+  fn actor_ref(&mut self, _path: ActorPath) -> Result<ActorRef, ActorError> {
+    Ok(ActorRef::with_system(
+      Pid::new(1, 0),
+      ActivationSender { activation: self.activation.clone() },
+      &self.system.state(),
+    ))
+  }
+
+  fn termination_signal(&self) -> TerminationSignal {
+    TerminationSignal::already_terminated()
+  }
+}
+
+struct ActivationSender {
+  activation: Shared<Option<ActorRef>>,
+}
+
+impl ActorRefSender for ActivationSender {
+  fn send(&mut self, message: AnyMessage) -> Result<SendOutcome, SendError> {
+    // 非活性化中の grain 宛てメッセージは失われる
+    let target = self.activation.lock().clone();
+    if let Some(mut target) = target {
+      target.tell(message);
+    }
+    Ok(SendOutcome::Delivered)
+  }
+}
+
+// ─── fixture: typed bootstrap ────────────────────────────────────────────────
+
+fn cluster_system(activation: &Shared<Option<ActorRef>>) -> TypedActorSystem<UserMessage> {
+  let cluster_config = ClusterExtensionConfig::new().with_advertised_address("node1:8080");
+  let cluster_installer = ClusterExtensionInstaller::new(cluster_config, |_event_stream, _block_list, _address| {
+    Box::new(NoopClusterProvider::new())
+  })
+  .with_identity_lookup_factory(|| Box::new(RemoteIdentityLookup));
+  let extensions = ExtensionInstallers::default().with_extension_installer(cluster_installer);
+  // 再送タイマーをメッセージ処理と並行して進めるため、インライン実行しない dispatcher を使う
+  let executor = ExecutorShared::new(Box::new(ThreadedExecutor::new()), TrampolineState::new());
+  let dispatcher: Box<dyn MessageDispatcherFactory> =
+    Box::new(DefaultDispatcherFactory::new(&DispatcherConfig::with_defaults(DEFAULT_DISPATCHER_ID), executor));
+  let activation = activation.clone();
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+    .with_extension_installers(extensions)
+    .with_dispatcher_factory(DEFAULT_DISPATCHER_ID, ArcShared::new(dispatcher))
+    .with_actor_ref_provider_installer(move |system: &ActorSystem| {
+      let provider = ActorRefProviderHandleShared::new(ActivationProvider {
+        system:     system.clone(),
+        activation: activation.clone(),
+      });
+      system.extended().register_actor_ref_provider(&provider)
+    });
+  let props = TypedProps::<UserMessage>::from_behavior_factory(Behaviors::ignore);
+  let system = TypedActorSystem::create_from_props(&props, config).expect("typed system");
+  let extension = system.as_untyped().extended().extension_by_type::<ClusterExtension>().expect("cluster extension");
+  extension.start_member().expect("start member");
+  extension.setup_member_kinds(vec![ActivatedKind::new("orders")]).expect("setup kinds");
+  system
+}
+
+// 受信したメッセージを記録して確認応答する consumer を持つ entity を活性化する。
+fn activate_entity(
+  system: &TypedActorSystem<UserMessage>,
+  activation: &Shared<Option<ActorRef>>,
+) -> Shared<Vec<(String, u32)>> {
+  let received: Shared<Vec<(String, u32)>> = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let consumer = {
+    let received = received.clone();
+    Behaviors::receive_message(move |_ctx, delivery: &ConsumerControllerDelivery<u32>| {
+      received.lock().push((delivery.producer_id().to_string(), *delivery.message()));
+      delivery.confirm_to().clone().tell(ConsumerControllerConfirmed);
+      Ok(Behaviors::same())
+    })
+  };
+  let props = TypedProps::from_behavior_factory(move || ShardingConsumerController::behavior(consumer.clone()));
+  let entity = system.as_untyped().actor_of(props.to_untyped()).expect("spawn entity");
+  *activation.lock() = Some(entity.into_actor_ref());
+  received
+}
+
+fn spawn_producer_controller(
+  system: &TypedActorSystem<UserMessage>,
+) -> Shared<Vec<ShardingProducerControllerRequestNext<u32>>> {
+  let requests: Shared<Vec<ShardingProducerControllerRequestNext<u32>>> =
+    ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let producer_props = {
+    let requests = requests.clone();
+    TypedProps::from_behavior_factory(move || {
+      let requests = requests.clone();
+      Behaviors::receive_message(move |_ctx, request: &ShardingProducerControllerRequestNext<u32>| {
+        requests.lock().push(request.clone());
+        Ok(Behaviors::same())
+      })
+    })
+  };
+  let producer = system.as_untyped().actor_of(producer_props.to_untyped()).expect("spawn producer");
+
+  let type_key = GrainTypeKey::<SequencedMessage<u32>>::new("orders");
+  let config = ShardingProducerControllerConfig::new().with_producer_controller_config(
+    ProducerControllerConfig::new().with_durable_queue_resend_first_interval(Duration::from_millis(50)),
+  );
+  let controller_props = TypedProps::from_behavior_factory(move || {
+    ShardingProducerController::behavior_with_config("producer", &type_key, &config)
+  });
+  let controller = system.as_untyped().actor_of(controller_props.to_untyped()).expect("spawn controller");
+  let mut controller =
+    TypedActorRef::<ShardingProducerControllerCommand<u32>>::from_untyped(controller.into_actor_ref());
+  controller.tell(ShardingProducerController::start(TypedActorRef::from_untyped(producer.into_actor_ref())));
+  requests
+}
+
+fn wait_until(description: &str, condition: impl Fn() -> bool) {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while !condition() {
+    assert!(Instant::now() < deadline, "timed out waiting until {description}");
+    thread::sleep(Duration::from_millis(1));
+  }
+}
+
+// 次の RequestNext を待ち、その送信先へ envelope を送る。
+fn send(
+  requests: &Shared<Vec<ShardingProducerControllerRequestNext<u32>>>,
+  sent: &mut usize,
+  entity_id: &str,
+  value: u32,
+) {
+  wait_until("the producer is asked for the next message", || requests.lock().len() > *sent);
+  let mut send_next_to = requests.lock()[*sent].send_next_to().clone();
+  *sent += 1;
+  send_next_to.tell(ShardingEnvelope::new(entity_id, value));
+}
+
+fn values_of(received: &Shared<Vec<(String, u32)>>, producer_id: &str) -> Vec<u32> {
+  received.lock().iter().filter(|(id, _)| id == producer_id).map(|(_, value)| *value).collect()
+}
+
+// ─── テスト ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn messages_reach_each_entity_in_order_with_their_own_sequence() {
+  let activation: Shared<Option<ActorRef>> = ArcShared::new(SpinSyncMutex::new(None));
+  let system = cluster_system(&activation);
+  let received = activate_entity(&system, &activation);
+  let requests = spawn_producer_controller(&system);
+
+  let mut sent = 0;
+  for (entity_id, value) in [("a", 1), ("b", 10), ("a", 2), ("b", 20), ("a", 3)] {
+    send(&requests, &mut sent, entity_id, value);
+  }
+
+  wait_until("every message is delivered", || received.lock().len() == 5);
+  assert_eq!(values_of(&received, "producer-a"), vec![1, 2, 3]);
+  assert_eq!(values_of(&received, "producer-b"), vec![10, 20]);
+  system.terminate().expect("terminate");
+}
+
+#[test]
+fn unconfirmed_messages_are_resent_to_the_reactivated_entity() {
+  let activation: Shared<Option<ActorRef>> = ArcShared::new(SpinSyncMutex::new(None));
+  let system = cluster_system(&activation);
+  let first_activation = activate_entity(&system, &activation);
+  let requests = spawn_producer_controller(&system);
+  let mut sent = 0;
+  send(&requests, &mut sent, "a", 1);
+  send(&requests, &mut sent, "a", 2);
+  wait_until("the first activation receives its messages", || first_activation.lock().len() == 2);
+
+  // entity を非活性化し、その間に送ったメッセージを失わせる
+  *activation.lock() = None;
+  send(&requests, &mut sent, "a", 3);
+  thread::sleep(Duration::from_millis(20));
+  let second_activation = activate_entity(&system, &activation);
+
+  wait_until("the reactivated entity receives the lost message", || !second_activation.lock().is_empty());
+  send(&requests, &mut sent, "a", 4);
+  wait_until("the reactivated entity receives new messages", || second_activation.lock().len() == 2);
+  assert_eq!(values_of(&first_activation, "producer-a"), vec![1, 2]);
+  assert_eq!(values_of(&second_activation, "producer-a"), vec![3, 4]);
+  system.terminate().expect("terminate");
+}
+
+#[test]
+fn messages_beyond_the_consumer_window_keep_flowing() {
+  let activation: Shared<Option<ActorRef>> = ArcShared::new(SpinSyncMutex::new(None));
+  let system = cluster_system(&activation);
+  let received = activate_entity(&system, &activation);
+  let requests = spawn_producer_controller(&system);
+
+  // 既定の flow-control window (50) を何度も跨ぐ数を 1 entity へ送る
+  let mut sent = 0;
+  for value in 1..=160 {
+    send(&requests, &mut sent, "a", value);
+  }
+
+  wait_until("every message is delivered", || received.lock().len() == 160);
+  assert_eq!(values_of(&received, "producer-a"), (1..=160).collect::<Vec<_>>());
+  system.terminate().expect("terminate");
+}
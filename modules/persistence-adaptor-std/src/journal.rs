@@ -0,0 +1,12 @@
+//! Filesystem-backed journal package.
+
+mod journal_record;
+mod local_journal;
+mod local_journal_config;
+mod local_journal_fsync_policy;
+mod local_journal_state;
+
+pub(crate) use journal_record::{FRAME_HEADER_LEN, MAX_RECORD_BODY_LEN, crc32};
+pub use local_journal::LocalJournal;
+pub use local_journal_config::LocalJournalConfig;
+pub use local_journal_fsync_policy::LocalJournalFsyncPolicy;
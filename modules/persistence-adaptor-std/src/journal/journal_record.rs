@@ -0,0 +1,143 @@
+//! Checksummed on-disk record framing for the local journal.
+
+#[cfg(test)]
+#[path = "journal_record_test.rs"]
+mod tests;
+
+const WRITE_RECORD_TAG: u8 = 1;
+const DELETE_RECORD_TAG: u8 = 2;
+const CRC32_POLYNOMIAL: u32 = 0xEDB8_8320;
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+/// Byte length of the `[body length][crc32]` prefix written before each record body.
+pub(crate) const FRAME_HEADER_LEN: usize = 8;
+
+/// Upper bound for a single record body, used to reject garbage lengths during recovery.
+pub(crate) const MAX_RECORD_BODY_LEN: u32 = 256 * 1024 * 1024;
+
+/// One record appended to a journal segment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum JournalRecord {
+  /// One serialized atomic write with its index keys.
+  Write {
+    persistence_id:      String,
+    lowest_sequence_nr:  u64,
+    highest_sequence_nr: u64,
+    payload:             Vec<u8>,
+  },
+  /// Logical deletion marker for a persistence id.
+  Delete { persistence_id: String, to_sequence_nr: u64 },
+}
+
+impl JournalRecord {
+  /// Encodes the record body and prefixes it with its length and checksum.
+  pub(crate) fn encode_frame(&self) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    match self {
+      | Self::Write { persistence_id, lowest_sequence_nr, highest_sequence_nr, payload } => {
+        body.push(WRITE_RECORD_TAG);
+        write_bytes(&mut body, persistence_id.as_bytes())?;
+        body.extend_from_slice(&lowest_sequence_nr.to_le_bytes());
+        body.extend_from_slice(&highest_sequence_nr.to_le_bytes());
+        write_bytes(&mut body, payload)?;
+      },
+      | Self::Delete { persistence_id, to_sequence_nr } => {
+        body.push(DELETE_RECORD_TAG);
+        write_bytes(&mut body, persistence_id.as_bytes())?;
+        body.extend_from_slice(&to_sequence_nr.to_le_bytes());
+      },
+    }
+    let body_len = u32::try_from(body.len())
+      .ok()
+      .filter(|len| *len <= MAX_RECORD_BODY_LEN)
+      .ok_or_else(|| format!("journal record body of {} bytes exceeds the record size limit", body.len()))?;
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + body.len());
+    frame.extend_from_slice(&body_len.to_le_bytes());
+    frame.extend_from_slice(&crc32(&body).to_le_bytes());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+  }
+
+  /// Splits a frame header into the declared body length and checksum.
+  pub(crate) const fn parse_frame_header(header: [u8; FRAME_HEADER_LEN]) -> (u32, u32) {
+    let body_len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let checksum = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    (body_len, checksum)
+  }
+
+  /// Decodes a record body after verifying it against the frame checksum.
+  ///
+  /// Returns `None` when the checksum does not match or the body is malformed.
+  pub(crate) fn decode_body(body: &[u8], checksum: u32) -> Option<Self> {
+    if crc32(body) != checksum {
+      return None;
+    }
+    let mut cursor = 0;
+    let tag = *body.first()?;
+    cursor += 1;
+    let persistence_id = String::from_utf8(read_bytes(body, &mut cursor)?.to_vec()).ok()?;
+    let record = match tag {
+      | WRITE_RECORD_TAG => {
+        let lowest_sequence_nr = read_u64(body, &mut cursor)?;
+        let highest_sequence_nr = read_u64(body, &mut cursor)?;
+        let payload = read_bytes(body, &mut cursor)?.to_vec();
+        Self::Write { persistence_id, lowest_sequence_nr, highest_sequence_nr, payload }
+      },
+      | DELETE_RECORD_TAG => {
+        let to_sequence_nr = read_u64(body, &mut cursor)?;
+        Self::Delete { persistence_id, to_sequence_nr }
+      },
+      | _ => return None,
+    };
+    (cursor == body.len()).then_some(record)
+  }
+}
+
+fn write_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) -> Result<(), String> {
+  let len =
+    u32::try_from(bytes.len()).map_err(|_| format!("journal record field of {} bytes is too large", bytes.len()))?;
+  buffer.extend_from_slice(&len.to_le_bytes());
+  buffer.extend_from_slice(bytes);
+  Ok(())
+}
+
+fn read_u64(bytes: &[u8], cursor: &mut usize) -> Option<u64> {
+  let end = cursor.checked_add(8)?;
+  let value = u64::from_le_bytes(bytes.get(*cursor..end)?.try_into().ok()?);
+  *cursor = end;
+  Some(value)
+}
+
+fn read_bytes<'a>(bytes: &'a [u8], cursor: &mut usize) -> Option<&'a [u8]> {
+  let len_end = cursor.checked_add(4)?;
+  let len = u32::from_le_bytes(bytes.get(*cursor..len_end)?.try_into().ok()?) as usize;
+  let end = len_end.checked_add(len)?;
+  let value = bytes.get(len_end..end)?;
+  *cursor = end;
+  Some(value)
+}
+
+/// Computes the IEEE CRC-32 checksum of `bytes`.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+  let mut crc = u32::MAX;
+  for byte in bytes {
+    crc = CRC32_TABLE[((crc ^ u32::from(*byte)) & 0xFF) as usize] ^ (crc >> 8);
+  }
+  !crc
+}
+
+const fn crc32_table() -> [u32; 256] {
+  let mut table = [0_u32; 256];
+  let mut index = 0;
+  while index < 256 {
+    let mut value = index as u32;
+    let mut bit = 0;
+    while bit < 8 {
+      value = if value & 1 == 1 { CRC32_POLYNOMIAL ^ (value >> 1) } else { value >> 1 };
+      bit += 1;
+    }
+    table[index] = value;
+    index += 1;
+  }
+  table
+}
@@ -0,0 +1,50 @@
+use super::{FRAME_HEADER_LEN, JournalRecord, crc32};
+
+fn split_frame(frame: &[u8]) -> (u32, u32, &[u8]) {
+  let header: [u8; FRAME_HEADER_LEN] = frame[..FRAME_HEADER_LEN].try_into().expect("frame header");
+  let (body_len, checksum) = JournalRecord::parse_frame_header(header);
+  (body_len, checksum, &frame[FRAME_HEADER_LEN..])
+}
+
+#[test]
+fn crc32_matches_ieee_check_value() {
+  assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+  assert_eq!(crc32(b""), 0);
+}
+
+#[test]
+fn write_record_round_trips_through_frame() {
+  let record = JournalRecord::Write {
+    persistence_id:      String::from("pid-1"),
+    lowest_sequence_nr:  3,
+    highest_sequence_nr: 5,
+    payload:             vec![1, 2, 3],
+  };
+
+  let frame = record.encode_frame().expect("encode frame");
+  let (body_len, checksum, body) = split_frame(&frame);
+
+  assert_eq!(body_len as usize, body.len());
+  assert_eq!(JournalRecord::decode_body(body, checksum), Some(record));
+}
+
+#[test]
+fn delete_record_round_trips_through_frame() {
+  let record = JournalRecord::Delete { persistence_id: String::from("pid-1"), to_sequence_nr: 9 };
+
+  let frame = record.encode_frame().expect("encode frame");
+  let (_, checksum, body) = split_frame(&frame);
+
+  assert_eq!(JournalRecord::decode_body(body, checksum), Some(record));
+}
+
+#[test]
+fn decode_body_rejects_checksum_mismatch() {
+  let record = JournalRecord::Delete { persistence_id: String::from("pid-1"), to_sequence_nr: 9 };
+  let mut frame = record.encode_frame().expect("encode frame");
+  let last = frame.len() - 1;
+  frame[last] ^= 0xFF;
+  let (_, checksum, body) = split_frame(&frame);
+
+  assert_eq!(JournalRecord::decode_body(body, checksum), None);
+}
@@ -0,0 +1,117 @@
+//! Filesystem-backed append-only journal.
+
+#[cfg(test)]
+#[path = "local_journal_test.rs"]
+mod tests;
+
+use core::future::{Ready, ready};
+
+use fraktor_persistence_core_kernel_rs::{
+  journal::{Journal, JournalError},
+  persistent::{AtomicWrite, PersistentRepr},
+  query::QueryableJournal,
+};
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedLock};
+
+use crate::journal::{LocalJournalConfig, local_journal_state::LocalJournalState};
+
+/// Segment-file journal compatible with the kernel [`Journal`] trait.
+///
+/// Each [`AtomicWrite`] is appended as one checksummed record, so a crash can only lose whole
+/// atomic writes. A torn record at the tail of the newest segment is truncated when the journal is
+/// reopened. Clones share the same open segment and index.
+#[derive(Clone)]
+pub struct LocalJournal {
+  state: SharedLock<LocalJournalState>,
+}
+
+impl LocalJournal {
+  /// Opens a local journal, creating its directory when missing and recovering existing segments.
+  ///
+  /// # Errors
+  ///
+  /// Returns a [`JournalError`] when the configuration is invalid, the directory cannot be created,
+  /// or a sealed segment is corrupt.
+  pub fn open(config: LocalJournalConfig) -> Result<Self, JournalError> {
+    let state = LocalJournalState::open(config)?;
+    Ok(Self { state: SharedLock::new_with_driver::<DefaultMutex<_>>(state) })
+  }
+
+  /// Forces every appended record to stable storage regardless of the fsync policy.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JournalError::WriteFailed`] when the active segment cannot be synced.
+  pub fn sync(&self) -> Result<(), JournalError> {
+    self.state.with_lock(LocalJournalState::sync)
+  }
+}
+
+impl Journal for LocalJournal {
+  type DeleteFuture<'a>
+    = Ready<Result<(), JournalError>>
+  where
+    Self: 'a;
+  type HighestSeqNrFuture<'a>
+    = Ready<Result<u64, JournalError>>
+  where
+    Self: 'a;
+  type ReplayFuture<'a>
+    = Ready<Result<Vec<PersistentRepr>, JournalError>>
+  where
+    Self: 'a;
+  type WriteFuture<'a>
+    = Ready<Result<(), JournalError>>
+  where
+    Self: 'a;
+
+  fn write_messages<'a>(&'a mut self, messages: &'a [AtomicWrite]) -> Self::WriteFuture<'a> {
+    ready(self.state.with_lock(|state| state.write_messages(messages)))
+  }
+
+  fn replay_messages<'a>(
+    &'a self,
+    persistence_id: &'a str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    max: u64,
+  ) -> Self::ReplayFuture<'a> {
+    ready(self.state.with_lock(|state| state.replay_messages(persistence_id, from_sequence_nr, to_sequence_nr, max)))
+  }
+
+  fn delete_messages_to<'a>(&'a mut self, persistence_id: &'a str, to_sequence_nr: u64) -> Self::DeleteFuture<'a> {
+    ready(self.state.with_lock(|state| state.delete_messages_to(persistence_id, to_sequence_nr)))
+  }
+
+  fn highest_sequence_nr<'a>(&'a self, persistence_id: &'a str) -> Self::HighestSeqNrFuture<'a> {
+    ready(Ok(self.state.with_lock(|state| state.highest_sequence_nr(persistence_id))))
+  }
+}
+
+impl QueryableJournal for LocalJournal {
+  fn events_by_persistence_id(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    max: u64,
+  ) -> Result<Vec<PersistentRepr>, JournalError> {
+    self.state.with_lock(|state| state.replay_messages(persistence_id, from_sequence_nr, to_sequence_nr, max))
+  }
+
+  fn events_by_ordering(&self, after_ordering: u64, max: u64) -> Result<Vec<(u64, PersistentRepr)>, JournalError> {
+    self.state.with_lock(|state| state.events_by_ordering(after_ordering, max))
+  }
+
+  fn highest_ordering(&self) -> Result<u64, JournalError> {
+    Ok(self.state.with_lock(|state| state.highest_ordering()))
+  }
+
+  fn highest_event_sequence_nr(&self, persistence_id: &str) -> Result<u64, JournalError> {
+    Ok(self.state.with_lock(|state| state.highest_sequence_nr(persistence_id)))
+  }
+
+  fn persistence_ids(&self) -> Result<Vec<String>, JournalError> {
+    Ok(self.state.with_lock(|state| state.persistence_ids()))
+  }
+}
@@ -0,0 +1,95 @@
+//! Local journal configuration.
+
+#[cfg(test)]
+#[path = "local_journal_config_test.rs"]
+mod tests;
+
+use std::path::{Path, PathBuf};
+
+use fraktor_actor_core_kernel_rs::serialization::serialization_registry::SerializationRegistry;
+use fraktor_persistence_core_kernel_rs::journal::JournalError;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::journal::LocalJournalFsyncPolicy;
+
+const DEFAULT_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+const MIN_SEGMENT_SIZE: u64 = 4 * 1024;
+
+/// Configuration for [`LocalJournal`](super::LocalJournal).
+#[derive(Clone)]
+pub struct LocalJournalConfig {
+  directory:     PathBuf,
+  serialization: ArcShared<SerializationRegistry>,
+  segment_size:  u64,
+  fsync_policy:  LocalJournalFsyncPolicy,
+}
+
+impl LocalJournalConfig {
+  /// Creates a new local journal configuration.
+  #[must_use]
+  pub const fn new(directory: PathBuf, serialization: ArcShared<SerializationRegistry>) -> Self {
+    Self { directory, serialization, segment_size: DEFAULT_SEGMENT_SIZE, fsync_policy: LocalJournalFsyncPolicy::Always }
+  }
+
+  /// Returns the journal root directory.
+  #[must_use]
+  pub fn directory(&self) -> &Path {
+    &self.directory
+  }
+
+  /// Returns the serialization registry.
+  #[must_use]
+  pub const fn serialization(&self) -> &ArcShared<SerializationRegistry> {
+    &self.serialization
+  }
+
+  /// Returns the size in bytes after which the active segment is rolled over.
+  #[must_use]
+  pub const fn segment_size(&self) -> u64 {
+    self.segment_size
+  }
+
+  /// Returns a copy with a different segment rollover size.
+  #[must_use]
+  pub const fn with_segment_size(mut self, segment_size: u64) -> Self {
+    self.segment_size = segment_size;
+    self
+  }
+
+  /// Returns the fsync policy.
+  #[must_use]
+  pub const fn fsync_policy(&self) -> LocalJournalFsyncPolicy {
+    self.fsync_policy
+  }
+
+  /// Returns a copy with a different fsync policy.
+  #[must_use]
+  pub const fn with_fsync_policy(mut self, fsync_policy: LocalJournalFsyncPolicy) -> Self {
+    self.fsync_policy = fsync_policy;
+    self
+  }
+
+  /// Validates this configuration.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JournalError::WriteFailed`] when the segment size is below 4 KiB or the fsync policy
+  /// requests a sync every zero writes.
+  pub fn validate(&self) -> Result<(), JournalError> {
+    if self.segment_size < MIN_SEGMENT_SIZE {
+      return Err(JournalError::WriteFailed(format!(
+        "invalid local journal config: segment_size must be at least {MIN_SEGMENT_SIZE} bytes"
+      )));
+    }
+    if self.fsync_policy == LocalJournalFsyncPolicy::EveryWrites(0) {
+      return Err(JournalError::WriteFailed(String::from(
+        "invalid local journal config: fsync policy must sync every one or more writes",
+      )));
+    }
+    Ok(())
+  }
+
+  pub(crate) fn into_parts(self) -> (PathBuf, ArcShared<SerializationRegistry>, u64, LocalJournalFsyncPolicy) {
+    (self.directory, self.serialization, self.segment_size, self.fsync_policy)
+  }
+}
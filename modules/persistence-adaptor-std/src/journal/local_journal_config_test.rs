@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  default_serialization_setup, serialization_registry::SerializationRegistry,
+};
+use fraktor_persistence_core_kernel_rs::journal::JournalError;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::LocalJournalConfig;
+use crate::journal::LocalJournalFsyncPolicy;
+
+fn serialization_registry() -> ArcShared<SerializationRegistry> {
+  let setup = default_serialization_setup();
+  ArcShared::new(SerializationRegistry::from_setup(&setup))
+}
+
+#[test]
+fn local_journal_config_defaults_to_always_fsync() {
+  let config = LocalJournalConfig::new(PathBuf::from("journal"), serialization_registry());
+
+  assert_eq!(config.directory(), PathBuf::from("journal").as_path());
+  assert_eq!(config.fsync_policy(), LocalJournalFsyncPolicy::Always);
+  assert_eq!(config.segment_size(), 64 * 1024 * 1024);
+  assert!(config.validate().is_ok());
+}
+
+#[test]
+fn local_journal_config_builders_replace_values() {
+  let config = LocalJournalConfig::new(PathBuf::from("journal"), serialization_registry())
+    .with_segment_size(8 * 1024)
+    .with_fsync_policy(LocalJournalFsyncPolicy::Never);
+
+  assert_eq!(config.segment_size(), 8 * 1024);
+  assert_eq!(config.fsync_policy(), LocalJournalFsyncPolicy::Never);
+}
+
+#[test]
+fn local_journal_config_rejects_tiny_segments() {
+  let config = LocalJournalConfig::new(PathBuf::from("journal"), serialization_registry()).with_segment_size(1024);
+
+  assert!(matches!(config.validate(), Err(JournalError::WriteFailed(_))));
+}
+
+#[test]
+fn local_journal_config_rejects_zero_write_fsync_interval() {
+  let config = LocalJournalConfig::new(PathBuf::from("journal"), serialization_registry())
+    .with_fsync_policy(LocalJournalFsyncPolicy::EveryWrites(0));
+
+  assert!(matches!(config.validate(), Err(JournalError::WriteFailed(_))));
+}
@@ -0,0 +1,12 @@
+//! Fsync policy for the local journal.
+
+/// Controls when [`LocalJournal`](super::LocalJournal) forces appended records to stable storage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalJournalFsyncPolicy {
+  /// Syncs the active segment after every write or delete batch.
+  Always,
+  /// Syncs the active segment after the given number of write or delete batches.
+  EveryWrites(usize),
+  /// Leaves flushing to the operating system.
+  Never,
+}
@@ -0,0 +1,608 @@
+//! Segment files and in-memory index shared by local journal handles.
+
+use core::ops::Deref;
+use std::{
+  collections::BTreeMap,
+  ffi::OsStr,
+  fs::{self, File, OpenOptions},
+  io::{BufReader, Error, ErrorKind, Read, Seek, SeekFrom, Write},
+  path::{Path, PathBuf},
+};
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  SerializationDelegator, SerializedMessage, serialization_registry::SerializationRegistry,
+};
+use fraktor_persistence_core_kernel_rs::{
+  journal::JournalError,
+  persistent::{AtomicWrite, PersistentRepr},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::journal::{
+  LocalJournalConfig, LocalJournalFsyncPolicy,
+  journal_record::{FRAME_HEADER_LEN, JournalRecord, MAX_RECORD_BODY_LEN},
+};
+
+const SEGMENT_FILE_PREFIX: &str = "segment-";
+const SEGMENT_FILE_EXTENSION: &str = "journal";
+const SEGMENT_MAGIC: &[u8; 8] = b"FRKJRNL1";
+const SEGMENT_HEADER_LEN: u64 = SEGMENT_MAGIC.len() as u64;
+const ATOMIC_WRITE_TYPE_NAME: &str = "AtomicWrite";
+
+/// Position of one write record inside the segment files.
+#[derive(Clone, Copy, Debug)]
+struct RecordLocation {
+  segment_id:          u64,
+  offset:              u64,
+  lowest_sequence_nr:  u64,
+  highest_sequence_nr: u64,
+}
+
+/// Index entry for one persistence id.
+#[derive(Clone, Debug, Default)]
+struct PersistenceIdIndex {
+  highest_sequence_nr: u64,
+  deleted_to:          u64,
+  locations:           Vec<RecordLocation>,
+}
+
+impl PersistenceIdIndex {
+  fn apply(&mut self, record: &JournalRecord, segment_id: u64, offset: u64) {
+    match record {
+      | JournalRecord::Write { lowest_sequence_nr, highest_sequence_nr, .. } => {
+        self.highest_sequence_nr = self.highest_sequence_nr.max(*highest_sequence_nr);
+        self.locations.push(RecordLocation {
+          segment_id,
+          offset,
+          lowest_sequence_nr: *lowest_sequence_nr,
+          highest_sequence_nr: *highest_sequence_nr,
+        });
+      },
+      | JournalRecord::Delete { to_sequence_nr, .. } => {
+        self.deleted_to = self.deleted_to.max(*to_sequence_nr);
+        let deleted_to = self.deleted_to;
+        self.locations.retain(|location| location.highest_sequence_nr > deleted_to);
+      },
+    }
+  }
+}
+
+/// One write record in journal-wide append order.
+#[derive(Clone, Debug)]
+struct OrderedRecord {
+  persistence_id: String,
+  /// Global ordering of the first event in the record; later events follow consecutively.
+  first_ordering: u64,
+  location:       RecordLocation,
+}
+
+impl OrderedRecord {
+  const fn last_ordering(&self) -> u64 {
+    self.first_ordering + (self.location.highest_sequence_nr - self.location.lowest_sequence_nr)
+  }
+}
+
+/// Journal-wide write order used by persistence queries.
+#[derive(Clone, Debug, Default)]
+struct WriteLog {
+  records: Vec<OrderedRecord>,
+}
+
+impl WriteLog {
+  fn apply(&mut self, record: &JournalRecord, segment_id: u64, offset: u64) {
+    let JournalRecord::Write { persistence_id, lowest_sequence_nr, highest_sequence_nr, .. } = record else {
+      return;
+    };
+    let first_ordering = self.records.last().map_or(1, |last| last.last_ordering().saturating_add(1));
+    self.records.push(OrderedRecord {
+      persistence_id: persistence_id.clone(),
+      first_ordering,
+      location: RecordLocation {
+        segment_id,
+        offset,
+        lowest_sequence_nr: *lowest_sequence_nr,
+        highest_sequence_nr: *highest_sequence_nr,
+      },
+    });
+  }
+}
+
+/// Outcome of scanning one segment during recovery.
+enum SegmentScan {
+  /// Every record in the segment was intact.
+  Clean { len: u64 },
+  /// The segment ends with an incomplete or corrupt record starting at `valid_len`.
+  Torn { valid_len: u64 },
+}
+
+/// Append-only segment log plus the index needed to answer journal queries.
+pub(crate) struct LocalJournalState {
+  directory:       PathBuf,
+  serialization:   ArcShared<SerializationRegistry>,
+  segment_size:    u64,
+  fsync_policy:    LocalJournalFsyncPolicy,
+  segment_ids:     Vec<u64>,
+  active:          File,
+  active_len:      u64,
+  unsynced_writes: usize,
+  indexes:         BTreeMap<String, PersistenceIdIndex>,
+  write_log:       WriteLog,
+}
+
+impl LocalJournalState {
+  /// Opens the journal directory, recovering the index and truncating a torn tail record.
+  pub(crate) fn open(config: LocalJournalConfig) -> Result<Self, JournalError> {
+    config.validate()?;
+    let (directory, serialization, segment_size, fsync_policy) = config.into_parts();
+    fs::create_dir_all(&directory).map_err(|error| {
+      JournalError::ReadFailed(format!("create journal directory {}: {error}", directory.display()))
+    })?;
+    let mut segment_ids = Self::list_segment_ids(&directory)?;
+    let mut indexes = BTreeMap::new();
+    let mut write_log = WriteLog::default();
+    let last_position = segment_ids.len().checked_sub(1);
+    let mut active_len = SEGMENT_HEADER_LEN;
+    for (position, segment_id) in segment_ids.iter().copied().enumerate() {
+      let path = Self::segment_path(&directory, segment_id);
+      let is_last = Some(position) == last_position;
+      match Self::scan_segment(&path, segment_id, &mut indexes, &mut write_log)? {
+        | SegmentScan::Clean { len } => active_len = len,
+        | SegmentScan::Torn { valid_len } if is_last => {
+          Self::truncate_segment(&path, valid_len)?;
+          // A torn header is rewritten by the truncation, so appends resume after the magic.
+          active_len = SEGMENT_HEADER_LEN.max(valid_len);
+        },
+        | SegmentScan::Torn { valid_len } => {
+          return Err(JournalError::ReadFailed(format!(
+            "corrupt journal segment {} at offset {valid_len}",
+            path.display()
+          )));
+        },
+      }
+    }
+    let active_id = match segment_ids.last() {
+      | Some(segment_id) => *segment_id,
+      | None => {
+        Self::create_segment(&directory, 1)?;
+        segment_ids.push(1);
+        active_len = SEGMENT_HEADER_LEN;
+        1
+      },
+    };
+    let active = Self::open_for_append(&directory, active_id)?;
+    Ok(Self {
+      directory,
+      serialization,
+      segment_size,
+      fsync_policy,
+      segment_ids,
+      active,
+      active_len,
+      unsynced_writes: 0,
+      indexes,
+      write_log,
+    })
+  }
+
+  pub(crate) fn write_messages(&mut self, messages: &[AtomicWrite]) -> Result<(), JournalError> {
+    let Some(first) = messages.first() else {
+      return Ok(());
+    };
+    let persistence_id = first.persistence_id();
+    if let Some(atomic_write) =
+      messages.iter().skip(1).find(|atomic_write| atomic_write.persistence_id() != persistence_id)
+    {
+      return Err(JournalError::MixedPersistenceId {
+        expected: persistence_id.to_string(),
+        actual:   atomic_write.persistence_id().to_string(),
+      });
+    }
+    let mut expected = self.highest_sequence_nr(persistence_id).saturating_add(1);
+    for atomic_write in messages {
+      for message in atomic_write.payload() {
+        if message.sequence_nr() != expected {
+          return Err(JournalError::SequenceMismatch { expected, actual: message.sequence_nr() });
+        }
+        expected = expected
+          .checked_add(1)
+          .ok_or_else(|| JournalError::WriteFailed(String::from("sequence number overflow in write batch")))?;
+      }
+    }
+
+    let records = messages.iter().map(|atomic_write| self.write_record(atomic_write)).collect::<Result<Vec<_>, _>>()?;
+    self.roll_segment_if_full()?;
+    let offsets = self.append_records(&records).map_err(JournalError::WriteFailed)?;
+    let segment_id = self.active_segment_id();
+    let index = self.indexes.entry(persistence_id.to_string()).or_default();
+    for (record, offset) in records.iter().zip(offsets) {
+      index.apply(record, segment_id, offset);
+      self.write_log.apply(record, segment_id, offset);
+    }
+    Ok(())
+  }
+
+  pub(crate) fn replay_messages(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    max: u64,
+  ) -> Result<Vec<PersistentRepr>, JournalError> {
+    let mut result = Vec::new();
+    let Some(index) = self.indexes.get(persistence_id) else {
+      return Ok(result);
+    };
+    let from_sequence_nr = from_sequence_nr.max(index.deleted_to.saturating_add(1));
+    let mut reader: Option<(u64, BufReader<File>)> = None;
+    // Locations are appended in sequence order, so a page seeks directly to its first record.
+    let start = index.locations.partition_point(|location| location.highest_sequence_nr < from_sequence_nr);
+    for location in index.locations[start..].iter().take_while(|location| location.lowest_sequence_nr <= to_sequence_nr)
+    {
+      let atomic_write = self.read_atomic_write(&mut reader, location)?;
+      for repr in atomic_write.into_payload() {
+        let sequence_nr = repr.sequence_nr();
+        if sequence_nr < from_sequence_nr || sequence_nr > to_sequence_nr {
+          continue;
+        }
+        result.push(repr);
+        if max != 0 && result.len() as u64 >= max {
+          return Ok(result);
+        }
+      }
+    }
+    Ok(result)
+  }
+
+  pub(crate) fn delete_messages_to(&mut self, persistence_id: &str, to_sequence_nr: u64) -> Result<(), JournalError> {
+    let Some(index) = self.indexes.get(persistence_id) else {
+      return Ok(());
+    };
+    // Deletion never reaches past the highest written event so later writes stay visible.
+    let to_sequence_nr = to_sequence_nr.min(index.highest_sequence_nr);
+    if to_sequence_nr <= index.deleted_to {
+      return Ok(());
+    }
+    let record = JournalRecord::Delete { persistence_id: persistence_id.to_string(), to_sequence_nr };
+    self.roll_segment_if_full().map_err(|error| JournalError::DeleteFailed(error.to_string()))?;
+    let offsets = self.append_records(core::slice::from_ref(&record)).map_err(JournalError::DeleteFailed)?;
+    let segment_id = self.active_segment_id();
+    if let Some(index) = self.indexes.get_mut(persistence_id) {
+      for offset in offsets {
+        index.apply(&record, segment_id, offset);
+      }
+    }
+    Ok(())
+  }
+
+  pub(crate) fn events_by_ordering(
+    &self,
+    after_ordering: u64,
+    max: u64,
+  ) -> Result<Vec<(u64, PersistentRepr)>, JournalError> {
+    let mut result = Vec::new();
+    let mut reader: Option<(u64, BufReader<File>)> = None;
+    let start = self.write_log.records.partition_point(|record| record.last_ordering() <= after_ordering);
+    for record in &self.write_log.records[start..] {
+      let deleted_to = self.indexes.get(&record.persistence_id).map_or(0, |index| index.deleted_to);
+      if record.location.highest_sequence_nr <= deleted_to {
+        // Deleted events still consume their ordering so offsets stay stable.
+        continue;
+      }
+      let atomic_write = self.read_atomic_write(&mut reader, &record.location)?;
+      for repr in atomic_write.into_payload() {
+        let ordering = record.first_ordering + (repr.sequence_nr() - record.location.lowest_sequence_nr);
+        if ordering <= after_ordering || repr.sequence_nr() <= deleted_to {
+          continue;
+        }
+        result.push((ordering, repr));
+        if max != 0 && result.len() as u64 >= max {
+          return Ok(result);
+        }
+      }
+    }
+    Ok(result)
+  }
+
+  pub(crate) fn highest_ordering(&self) -> u64 {
+    self.write_log.records.last().map_or(0, OrderedRecord::last_ordering)
+  }
+
+  pub(crate) fn persistence_ids(&self) -> Vec<String> {
+    self.indexes.keys().cloned().collect()
+  }
+
+  pub(crate) fn highest_sequence_nr(&self, persistence_id: &str) -> u64 {
+    self.indexes.get(persistence_id).map_or(0, |index| index.highest_sequence_nr)
+  }
+
+  pub(crate) fn sync(&mut self) -> Result<(), JournalError> {
+    self.active.sync_data().map_err(|error| {
+      JournalError::WriteFailed(format!("sync journal segment {}: {error}", self.active_segment_path().display()))
+    })?;
+    self.unsynced_writes = 0;
+    Ok(())
+  }
+
+  fn write_record(&self, atomic_write: &AtomicWrite) -> Result<JournalRecord, JournalError> {
+    let delegator = SerializationDelegator::new(self.serialization.deref());
+    let serialized = delegator
+      .serialize(atomic_write, ATOMIC_WRITE_TYPE_NAME)
+      .map_err(|error| JournalError::WriteFailed(format!("serialize atomic write: {error}")))?;
+    Ok(JournalRecord::Write {
+      persistence_id:      atomic_write.persistence_id().to_string(),
+      lowest_sequence_nr:  atomic_write.lowest_sequence_nr(),
+      highest_sequence_nr: atomic_write.highest_sequence_nr(),
+      payload:             serialized.encode(),
+    })
+  }
+
+  fn read_atomic_write(
+    &self,
+    reader: &mut Option<(u64, BufReader<File>)>,
+    location: &RecordLocation,
+  ) -> Result<AtomicWrite, JournalError> {
+    let path = self.segment_path_for(location.segment_id);
+    if reader.as_ref().is_none_or(|(segment_id, _)| *segment_id != location.segment_id) {
+      let file = File::open(&path)
+        .map_err(|error| JournalError::ReadFailed(format!("open journal segment {}: {error}", path.display())))?;
+      *reader = Some((location.segment_id, BufReader::new(file)));
+    }
+    let Some((_, reader)) = reader.as_mut() else {
+      return Err(JournalError::ReadFailed(format!("open journal segment {}", path.display())));
+    };
+    reader
+      .seek(SeekFrom::Start(location.offset))
+      .map_err(|error| JournalError::ReadFailed(format!("seek journal segment {}: {error}", path.display())))?;
+    let Ok(Some((record, _))) = Self::read_record(reader) else {
+      return Err(JournalError::ReadFailed(format!(
+        "corrupt journal record in {} at offset {}",
+        path.display(),
+        location.offset
+      )));
+    };
+    let JournalRecord::Write { payload, .. } = record else {
+      return Err(JournalError::ReadFailed(format!(
+        "unexpected journal record kind in {} at offset {}",
+        path.display(),
+        location.offset
+      )));
+    };
+    let serialized = SerializedMessage::decode(&payload)
+      .map_err(|error| JournalError::ReadFailed(format!("decode atomic write {}: {error}", path.display())))?;
+    let delegator = SerializationDelegator::new(self.serialization.deref());
+    let message = delegator
+      .deserialize(&serialized, None)
+      .map_err(|error| JournalError::ReadFailed(format!("deserialize atomic write {}: {error}", path.display())))?;
+    message.downcast::<AtomicWrite>().map(|atomic_write| *atomic_write).map_err(|_| {
+      JournalError::ReadFailed(format!("deserialize atomic write {}: payload type mismatch", path.display()))
+    })
+  }
+
+  fn append_records(&mut self, records: &[JournalRecord]) -> Result<Vec<u64>, String> {
+    let mut buffer = Vec::new();
+    let mut offsets = Vec::with_capacity(records.len());
+    for record in records {
+      offsets.push(self.active_len + buffer.len() as u64);
+      buffer.extend_from_slice(&record.encode_frame()?);
+    }
+    let path = self.active_segment_path();
+    if let Err(error) = self.active.write_all(&buffer) {
+      // Partially appended bytes must not survive, otherwise a later append would follow a torn frame.
+      return Err(self.rollback_append(format!("append journal segment {}: {error}", path.display())));
+    }
+    self.unsynced_writes = self.unsynced_writes.saturating_add(1);
+    let should_sync = match self.fsync_policy {
+      | LocalJournalFsyncPolicy::Always => true,
+      | LocalJournalFsyncPolicy::EveryWrites(count) => self.unsynced_writes >= count,
+      | LocalJournalFsyncPolicy::Never => false,
+    };
+    if should_sync {
+      if let Err(error) = self.active.sync_data() {
+        // The failed batch stays unindexed, so its bytes must go or a retry would duplicate it.
+        self.unsynced_writes = self.unsynced_writes.saturating_sub(1);
+        return Err(self.rollback_append(format!("sync journal segment {}: {error}", path.display())));
+      }
+      self.unsynced_writes = 0;
+    }
+    self.active_len += buffer.len() as u64;
+    Ok(offsets)
+  }
+
+  /// Truncates the active segment back to its last indexed length and returns `message`.
+  fn rollback_append(&mut self, message: String) -> String {
+    match self.active.set_len(self.active_len).and_then(|()| self.active.seek(SeekFrom::Start(self.active_len))) {
+      | Ok(_) => message,
+      | Err(rollback_error) => format!("{message}; rollback failed: {rollback_error}"),
+    }
+  }
+
+  fn roll_segment_if_full(&mut self) -> Result<(), JournalError> {
+    if self.active_len < self.segment_size {
+      return Ok(());
+    }
+    if self.fsync_policy != LocalJournalFsyncPolicy::Never {
+      self.sync()?;
+    }
+    let next_id = self.active_segment_id().saturating_add(1);
+    Self::create_segment(&self.directory, next_id)?;
+    self.active = Self::open_for_append(&self.directory, next_id)?;
+    self.active_len = SEGMENT_HEADER_LEN;
+    self.unsynced_writes = 0;
+    self.segment_ids.push(next_id);
+    Ok(())
+  }
+
+  fn active_segment_id(&self) -> u64 {
+    self.segment_ids.last().copied().unwrap_or(1)
+  }
+
+  fn active_segment_path(&self) -> PathBuf {
+    self.segment_path_for(self.active_segment_id())
+  }
+
+  fn segment_path_for(&self, segment_id: u64) -> PathBuf {
+    Self::segment_path(&self.directory, segment_id)
+  }
+
+  fn segment_path(directory: &Path, segment_id: u64) -> PathBuf {
+    directory.join(format!("{SEGMENT_FILE_PREFIX}{segment_id:020}.{SEGMENT_FILE_EXTENSION}"))
+  }
+
+  fn parse_segment_file_name(file_name: &str) -> Option<u64> {
+    let rest = file_name.strip_prefix(SEGMENT_FILE_PREFIX)?;
+    let digits = rest.strip_suffix(SEGMENT_FILE_EXTENSION)?.strip_suffix('.')?;
+    digits.parse::<u64>().ok()
+  }
+
+  fn list_segment_ids(directory: &Path) -> Result<Vec<u64>, JournalError> {
+    let entries = fs::read_dir(directory)
+      .map_err(|error| JournalError::ReadFailed(format!("list journal directory {}: {error}", directory.display())))?;
+    let mut segment_ids = Vec::new();
+    for entry in entries {
+      let entry = entry.map_err(|error| {
+        JournalError::ReadFailed(format!("list journal directory {}: {error}", directory.display()))
+      })?;
+      let path = entry.path();
+      if let Some(segment_id) = path.file_name().and_then(OsStr::to_str).and_then(Self::parse_segment_file_name) {
+        segment_ids.push(segment_id);
+      }
+    }
+    segment_ids.sort_unstable();
+    Ok(segment_ids)
+  }
+
+  fn create_segment(directory: &Path, segment_id: u64) -> Result<(), JournalError> {
+    let path = Self::segment_path(directory, segment_id);
+    let mut file = OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .open(&path)
+      .map_err(|error| JournalError::WriteFailed(format!("create journal segment {}: {error}", path.display())))?;
+    file.write_all(SEGMENT_MAGIC).and_then(|()| file.sync_all()).map_err(|error| {
+      JournalError::WriteFailed(format!("write journal segment header {}: {error}", path.display()))
+    })?;
+    #[cfg(not(windows))]
+    File::open(directory)
+      .and_then(|directory| directory.sync_all())
+      .map_err(|error| JournalError::WriteFailed(format!("sync journal directory {}: {error}", directory.display())))?;
+    Ok(())
+  }
+
+  fn open_for_append(directory: &Path, segment_id: u64) -> Result<File, JournalError> {
+    let path = Self::segment_path(directory, segment_id);
+    let mut file = OpenOptions::new()
+      .write(true)
+      .open(&path)
+      .map_err(|error| JournalError::ReadFailed(format!("open journal segment {}: {error}", path.display())))?;
+    file
+      .seek(SeekFrom::End(0))
+      .map_err(|error| JournalError::ReadFailed(format!("seek journal segment {}: {error}", path.display())))?;
+    Ok(file)
+  }
+
+  fn truncate_segment(path: &Path, len: u64) -> Result<(), JournalError> {
+    let file = OpenOptions::new()
+      .write(true)
+      .open(path)
+      .map_err(|error| JournalError::ReadFailed(format!("open torn journal segment {}: {error}", path.display())))?;
+    if len < SEGMENT_HEADER_LEN {
+      file.set_len(0).map_err(|error| {
+        JournalError::ReadFailed(format!("truncate torn journal segment {}: {error}", path.display()))
+      })?;
+      let mut file = file;
+      file.write_all(SEGMENT_MAGIC).map_err(|error| {
+        JournalError::ReadFailed(format!("rewrite journal segment header {}: {error}", path.display()))
+      })?;
+      return file
+        .sync_all()
+        .map_err(|error| JournalError::ReadFailed(format!("sync journal segment {}: {error}", path.display())));
+    }
+    file
+      .set_len(len)
+      .and_then(|()| file.sync_all())
+      .map_err(|error| JournalError::ReadFailed(format!("truncate torn journal segment {}: {error}", path.display())))
+  }
+
+  fn scan_segment(
+    path: &Path,
+    segment_id: u64,
+    indexes: &mut BTreeMap<String, PersistenceIdIndex>,
+    write_log: &mut WriteLog,
+  ) -> Result<SegmentScan, JournalError> {
+    let file = File::open(path)
+      .map_err(|error| JournalError::ReadFailed(format!("open journal segment {}: {error}", path.display())))?;
+    let mut reader = BufReader::new(file);
+    let mut magic = [0_u8; SEGMENT_MAGIC.len()];
+    match reader.read_exact(&mut magic) {
+      | Ok(()) if &magic == SEGMENT_MAGIC => (),
+      | Ok(()) => {
+        return Err(JournalError::ReadFailed(format!("journal segment {} has an unknown header", path.display())));
+      },
+      | Err(error) if error.kind() == ErrorKind::UnexpectedEof => return Ok(SegmentScan::Torn { valid_len: 0 }),
+      | Err(error) => {
+        return Err(JournalError::ReadFailed(format!("read journal segment {}: {error}", path.display())));
+      },
+    }
+    let mut offset = SEGMENT_HEADER_LEN;
+    loop {
+      match Self::read_record(&mut reader) {
+        | Ok(Some((record, frame_len))) => {
+          let persistence_id = match &record {
+            | JournalRecord::Write { persistence_id, .. } | JournalRecord::Delete { persistence_id, .. } => {
+              persistence_id.clone()
+            },
+          };
+          indexes.entry(persistence_id).or_default().apply(&record, segment_id, offset);
+          write_log.apply(&record, segment_id, offset);
+          offset += frame_len;
+        },
+        | Ok(None) => return Ok(SegmentScan::Clean { len: offset }),
+        | Err(RecordReadError::Torn) => return Ok(SegmentScan::Torn { valid_len: offset }),
+        | Err(RecordReadError::Io(error)) => {
+          return Err(JournalError::ReadFailed(format!("read journal segment {}: {error}", path.display())));
+        },
+      }
+    }
+  }
+
+  /// Reads the next framed record, returning `Ok(None)` at a clean end of segment.
+  fn read_record(reader: &mut impl Read) -> Result<Option<(JournalRecord, u64)>, RecordReadError> {
+    let mut header = [0_u8; FRAME_HEADER_LEN];
+    match read_full(reader, &mut header)? {
+      | 0 => return Ok(None),
+      | read if read < FRAME_HEADER_LEN => return Err(RecordReadError::Torn),
+      | _ => (),
+    }
+    let (body_len, checksum) = JournalRecord::parse_frame_header(header);
+    if body_len == 0 || body_len > MAX_RECORD_BODY_LEN {
+      return Err(RecordReadError::Torn);
+    }
+    let mut body = vec![0_u8; body_len as usize];
+    if read_full(reader, &mut body)? < body.len() {
+      return Err(RecordReadError::Torn);
+    }
+    let record = JournalRecord::decode_body(&body, checksum).ok_or(RecordReadError::Torn)?;
+    Ok(Some((record, FRAME_HEADER_LEN as u64 + u64::from(body_len))))
+  }
+}
+
+/// Failure while reading one framed record.
+enum RecordReadError {
+  /// The frame is incomplete or fails its checksum.
+  Torn,
+  /// The underlying file could not be read.
+  Io(Error),
+}
+
+fn read_full(reader: &mut impl Read, buffer: &mut [u8]) -> Result<usize, RecordReadError> {
+  let mut read = 0;
+  while read < buffer.len() {
+    match reader.read(&mut buffer[read..]) {
+      | Ok(0) => break,
+      | Ok(count) => read += count,
+      | Err(error) if error.kind() == ErrorKind::Interrupted => (),
+      | Err(error) => return Err(RecordReadError::Io(error)),
+    }
+  }
+  Ok(read)
+}
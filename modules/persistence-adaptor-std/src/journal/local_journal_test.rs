@@ -0,0 +1,327 @@
+extern crate std;
+
+use core::{
+  any::Any,
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+use std::{
+  fs::{self, OpenOptions},
+  io::{ErrorKind, Write},
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  builtin, default_serialization_setup, serialization_registry::SerializationRegistry,
+};
+use fraktor_persistence_core_kernel_rs::{
+  journal::{Journal, JournalError},
+  persistent::{AtomicWrite, PersistentRepr},
+  query::QueryableJournal,
+  serialization::register_persistence_serializers,
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::LocalJournal;
+use crate::journal::{LocalJournalConfig, LocalJournalFsyncPolicy};
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let waker = Waker::noop();
+  let mut cx = Context::from_waker(waker);
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("local journal future should be ready"),
+  }
+}
+
+fn serialization_registry() -> ArcShared<SerializationRegistry> {
+  let setup = default_serialization_setup();
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&setup));
+  builtin::register_defaults(&registry, |name, id| panic!("unexpected serializer collision for {name}: {id:?}"))
+    .expect("register builtin serializers");
+  register_persistence_serializers(&registry).expect("register persistence serializers");
+  registry
+}
+
+fn unique_journal_dir(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  std::env::temp_dir().join(format!("fraktor-local-journal-{name}-{}-{timestamp}", std::process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("journal test directory should be removable: {error}"),
+  }
+}
+
+fn open_journal(directory: &Path) -> LocalJournal {
+  open_journal_with(LocalJournalConfig::new(directory.to_path_buf(), serialization_registry()))
+}
+
+fn open_journal_with(config: LocalJournalConfig) -> LocalJournal {
+  LocalJournal::open(config).expect("open local journal")
+}
+
+fn atomic_write(persistence_id: &str, start: u64, count: u64) -> AtomicWrite {
+  let payload = (0..count)
+    .map(|offset| {
+      let value: ArcShared<dyn Any + Send + Sync> = ArcShared::new((start + offset) as i32);
+      PersistentRepr::new(persistence_id, start + offset, value).with_manifest("counter").with_timestamp(7)
+    })
+    .collect();
+  AtomicWrite::new(payload).expect("atomic write")
+}
+
+fn write(journal: &mut LocalJournal, messages: &[AtomicWrite]) {
+  poll_ready(journal.write_messages(messages)).expect("write messages");
+}
+
+fn replay_values(journal: &LocalJournal, persistence_id: &str, from: u64, to: u64, max: u64) -> Vec<(u64, i32)> {
+  poll_ready(journal.replay_messages(persistence_id, from, to, max))
+    .expect("replay messages")
+    .iter()
+    .map(|repr| (repr.sequence_nr(), *repr.downcast_ref::<i32>().expect("i32 payload")))
+    .collect()
+}
+
+fn segment_files(directory: &Path) -> Vec<PathBuf> {
+  let mut files: Vec<PathBuf> = fs::read_dir(directory)
+    .expect("read journal directory")
+    .map(|entry| entry.expect("journal directory entry").path())
+    .filter(|path| path.extension().is_some_and(|extension| extension == "journal"))
+    .collect();
+  files.sort();
+  files
+}
+
+#[test]
+fn local_journal_open_creates_directory_and_first_segment() {
+  let directory = unique_journal_dir("open");
+  remove_dir_if_exists(&directory);
+
+  let _journal = open_journal(&directory);
+
+  assert!(directory.is_dir());
+  assert_eq!(segment_files(&directory).len(), 1);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_replays_written_events_with_metadata() {
+  let directory = unique_journal_dir("replay");
+  let mut journal = open_journal(&directory);
+
+  write(&mut journal, &[atomic_write("pid-1", 1, 2), atomic_write("pid-1", 3, 1)]);
+  let replayed = poll_ready(journal.replay_messages("pid-1", 1, u64::MAX, 0)).expect("replay");
+
+  assert_eq!(replayed.len(), 3);
+  assert_eq!(replayed[0].manifest(), "counter");
+  assert_eq!(replayed[0].timestamp(), 7);
+  assert_eq!(replay_values(&journal, "pid-1", 2, 3, 0), vec![(2, 2), (3, 3)]);
+  assert_eq!(replay_values(&journal, "pid-1", 1, u64::MAX, 2), vec![(1, 1), (2, 2)]);
+  assert_eq!(poll_ready(journal.highest_sequence_nr("pid-1")), Ok(3));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_recovers_events_after_reopen() {
+  let directory = unique_journal_dir("reopen");
+  {
+    let mut journal = open_journal(&directory);
+    write(&mut journal, &[atomic_write("pid-1", 1, 2)]);
+    write(&mut journal, &[atomic_write("pid-2", 1, 1)]);
+  }
+
+  let reopened = open_journal(&directory);
+
+  assert_eq!(replay_values(&reopened, "pid-1", 1, u64::MAX, 0), vec![(1, 1), (2, 2)]);
+  assert_eq!(replay_values(&reopened, "pid-2", 1, u64::MAX, 0), vec![(1, 1)]);
+  assert_eq!(poll_ready(reopened.highest_sequence_nr("pid-1")), Ok(2));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_rejects_sequence_gaps_and_mixed_persistence_ids() {
+  let directory = unique_journal_dir("validation");
+  let mut journal = open_journal(&directory);
+  write(&mut journal, &[atomic_write("pid-1", 1, 1)]);
+
+  let gap = poll_ready(journal.write_messages(&[atomic_write("pid-1", 3, 1)]));
+  let mixed = poll_ready(journal.write_messages(&[atomic_write("pid-1", 2, 1), atomic_write("pid-2", 1, 1)]));
+
+  assert_eq!(gap, Err(JournalError::SequenceMismatch { expected: 2, actual: 3 }));
+  assert!(matches!(mixed, Err(JournalError::MixedPersistenceId { .. })));
+  assert_eq!(replay_values(&journal, "pid-1", 1, u64::MAX, 0), vec![(1, 1)]);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_delete_hides_events_and_keeps_highest_sequence_nr_after_reopen() {
+  let directory = unique_journal_dir("delete");
+  {
+    let mut journal = open_journal(&directory);
+    write(&mut journal, &[atomic_write("pid-1", 1, 3)]);
+    poll_ready(journal.delete_messages_to("pid-1", 2)).expect("delete");
+    assert_eq!(replay_values(&journal, "pid-1", 1, u64::MAX, 0), vec![(3, 3)]);
+    poll_ready(journal.delete_messages_to("pid-1", 10)).expect("delete beyond highest");
+  }
+
+  let mut reopened = open_journal(&directory);
+
+  assert!(replay_values(&reopened, "pid-1", 1, u64::MAX, 0).is_empty());
+  assert_eq!(poll_ready(reopened.highest_sequence_nr("pid-1")), Ok(3));
+  write(&mut reopened, &[atomic_write("pid-1", 4, 1)]);
+  assert_eq!(replay_values(&reopened, "pid-1", 1, u64::MAX, 0), vec![(4, 4)]);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_truncates_torn_tail_record_on_reopen() {
+  let directory = unique_journal_dir("torn");
+  {
+    let mut journal = open_journal(&directory);
+    write(&mut journal, &[atomic_write("pid-1", 1, 1)]);
+    write(&mut journal, &[atomic_write("pid-1", 2, 2)]);
+  }
+  let segment = segment_files(&directory).pop().expect("segment file");
+  let full_len = fs::metadata(&segment).expect("segment metadata").len();
+  OpenOptions::new().write(true).open(&segment).expect("open segment").set_len(full_len - 5).expect("tear segment");
+
+  let mut reopened = open_journal(&directory);
+
+  assert_eq!(replay_values(&reopened, "pid-1", 1, u64::MAX, 0), vec![(1, 1)]);
+  assert_eq!(poll_ready(reopened.highest_sequence_nr("pid-1")), Ok(1));
+  write(&mut reopened, &[atomic_write("pid-1", 2, 1)]);
+  drop(reopened);
+  let recovered = open_journal(&directory);
+  assert_eq!(replay_values(&recovered, "pid-1", 1, u64::MAX, 0), vec![(1, 1), (2, 2)]);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_truncates_checksum_mismatch_in_tail_record() {
+  let directory = unique_journal_dir("checksum");
+  {
+    let mut journal = open_journal(&directory);
+    write(&mut journal, &[atomic_write("pid-1", 1, 1)]);
+  }
+  let segment = segment_files(&directory).pop().expect("segment file");
+  let mut file = OpenOptions::new().append(true).open(&segment).expect("open segment");
+  file.write_all(&[16, 0, 0, 0, 1, 2, 3, 4]).expect("append garbage header");
+  file.write_all(&[0xAA; 16]).expect("append garbage body");
+  drop(file);
+
+  let reopened = open_journal(&directory);
+
+  assert_eq!(replay_values(&reopened, "pid-1", 1, u64::MAX, 0), vec![(1, 1)]);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_rewrites_torn_segment_header_and_appends_after_it() {
+  let directory = unique_journal_dir("torn-header");
+  {
+    let mut journal = open_journal(&directory);
+    write(&mut journal, &[atomic_write("pid-1", 1, 1)]);
+  }
+  let first_segment = segment_files(&directory).pop().expect("segment file");
+  let torn_segment = first_segment.with_file_name(format!("segment-{:020}.journal", 2));
+  fs::write(&torn_segment, b"FRK").expect("write torn header");
+
+  let mut reopened = open_journal(&directory);
+  write(&mut reopened, &[atomic_write("pid-1", 2, 2)]);
+
+  assert_eq!(replay_values(&reopened, "pid-1", 1, u64::MAX, 0), vec![(1, 1), (2, 2), (3, 3)]);
+  drop(reopened);
+  let recovered = open_journal(&directory);
+  assert_eq!(replay_values(&recovered, "pid-1", 1, u64::MAX, 0), vec![(1, 1), (2, 2), (3, 3)]);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_rolls_segments_and_replays_across_them() {
+  let directory = unique_journal_dir("segments");
+  let config = LocalJournalConfig::new(directory.clone(), serialization_registry())
+    .with_segment_size(4 * 1024)
+    .with_fsync_policy(LocalJournalFsyncPolicy::EveryWrites(8));
+  {
+    let mut journal = open_journal_with(config.clone());
+    for sequence_nr in 1..=200 {
+      write(&mut journal, &[atomic_write("pid-1", sequence_nr, 1)]);
+    }
+    journal.sync().expect("sync journal");
+  }
+
+  let reopened = open_journal_with(config);
+
+  assert!(segment_files(&directory).len() > 1);
+  let replayed = replay_values(&reopened, "pid-1", 1, u64::MAX, 0);
+  assert_eq!(replayed.len(), 200);
+  assert_eq!(replayed.last(), Some(&(200, 200)));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_rejects_corruption_in_sealed_segment() {
+  let directory = unique_journal_dir("sealed-corruption");
+  let config = LocalJournalConfig::new(directory.clone(), serialization_registry()).with_segment_size(4 * 1024);
+  {
+    let mut journal = open_journal_with(config.clone());
+    for sequence_nr in 1..=200 {
+      write(&mut journal, &[atomic_write("pid-1", sequence_nr, 1)]);
+    }
+  }
+  let first_segment = segment_files(&directory).remove(0);
+  let len = fs::metadata(&first_segment).expect("segment metadata").len();
+  OpenOptions::new().write(true).open(&first_segment).expect("open segment").set_len(len - 3).expect("tear segment");
+
+  let result = LocalJournal::open(config);
+
+  assert!(matches!(result, Err(JournalError::ReadFailed(_))));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_clones_share_index() {
+  let directory = unique_journal_dir("clone");
+  let journal = open_journal(&directory);
+  let mut cloned = journal.clone();
+
+  write(&mut cloned, &[atomic_write("pid-1", 1, 1)]);
+
+  assert_eq!(replay_values(&journal, "pid-1", 1, u64::MAX, 0), vec![(1, 1)]);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_journal_orders_events_across_persistence_ids_after_reopen() {
+  let directory = unique_journal_dir("ordering");
+  {
+    let mut journal = open_journal(&directory);
+    write(&mut journal, &[atomic_write("pid-1", 1, 2)]);
+    write(&mut journal, &[atomic_write("pid-2", 1, 1)]);
+    write(&mut journal, &[atomic_write("pid-1", 3, 1)]);
+    poll_ready(journal.delete_messages_to("pid-1", 1)).expect("delete messages");
+  }
+
+  let reopened = open_journal(&directory);
+  let ordered: Vec<(u64, String, u64)> = reopened
+    .events_by_ordering(0, 0)
+    .expect("events by ordering")
+    .iter()
+    .map(|(ordering, repr)| (*ordering, repr.persistence_id().to_string(), repr.sequence_nr()))
+    .collect();
+  let page = reopened.events_by_ordering(2, 1).expect("events by ordering page");
+
+  assert_eq!(ordered, vec![(2, "pid-1".into(), 2), (3, "pid-2".into(), 1), (4, "pid-1".into(), 3)]);
+  assert_eq!(page.iter().map(|(ordering, _)| *ordering).collect::<Vec<_>>(), vec![3]);
+  assert_eq!(reopened.persistence_ids(), Ok(vec![String::from("pid-1"), String::from("pid-2")]));
+  remove_dir_if_exists(&directory);
+}
@@ -46,5 +46,11 @@
 
 //! Standard persistence adaptors.
 
+/// Filesystem-backed journal adaptors.
+pub mod journal;
+/// Filesystem-backed projection offset stores.
+pub mod projection;
 /// Filesystem-backed snapshot adaptors.
 pub mod snapshot;
+/// Filesystem-backed durable state adaptors.
+pub mod state;
@@ -0,0 +1,5 @@
+//! Filesystem-backed projection offset store package.
+
+mod local_offset_store;
+
+pub use local_offset_store::LocalOffsetStore;
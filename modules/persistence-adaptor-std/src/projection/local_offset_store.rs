@@ -0,0 +1,177 @@
+//! Filesystem-backed projection offset store.
+
+#[cfg(test)]
+#[path = "local_offset_store_test.rs"]
+mod tests;
+
+use std::{
+  fs::{self, File},
+  io::{ErrorKind, Write},
+  path::{Path, PathBuf},
+};
+
+use fraktor_persistence_core_kernel_rs::{
+  projection::{OffsetStore, ProjectionError, ProjectionId},
+  query::{Offset, TimestampOffset},
+};
+
+const OFFSET_FILE_PREFIX: &str = "offset-";
+const OFFSET_FILE_SEPARATOR: char = '-';
+const OFFSET_TEMP_EXTENSION: &str = "tmp";
+const SEQUENCE_RECORD: &str = "sequence";
+const TIMESTAMP_RECORD: &str = "timestamp";
+const SEEN_RECORD: &str = "seen";
+const PERCENT_ENCODING_MARKER: char = '%';
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Filesystem-backed [`OffsetStore`] keeping one small text file per projection.
+///
+/// Offsets are replaced atomically through a synced temporary file, so a crash leaves either the
+/// previous or the new offset on disk.
+#[derive(Clone, Debug)]
+pub struct LocalOffsetStore {
+  directory: PathBuf,
+}
+
+impl LocalOffsetStore {
+  /// Opens a local offset store and creates its root directory when missing.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ProjectionError::OffsetStoreFailed`] when the directory cannot be created.
+  pub fn open(directory: impl Into<PathBuf>) -> Result<Self, ProjectionError> {
+    let directory = directory.into();
+    fs::create_dir_all(&directory).map_err(|error| {
+      ProjectionError::offset_store_failed(format!("create offset directory {}: {error}", directory.display()))
+    })?;
+    Ok(Self { directory })
+  }
+
+  /// Returns the root directory.
+  #[must_use]
+  pub fn directory(&self) -> &Path {
+    &self.directory
+  }
+
+  fn offset_path(&self, projection_id: &ProjectionId) -> PathBuf {
+    let name = encode_component(projection_id.name());
+    let key = encode_component(projection_id.key());
+    self.directory.join(format!("{OFFSET_FILE_PREFIX}{name}{OFFSET_FILE_SEPARATOR}{key}"))
+  }
+
+  fn sync_directory(&self) -> Result<(), ProjectionError> {
+    #[cfg(not(windows))]
+    File::open(&self.directory).and_then(|directory| directory.sync_all()).map_err(|error| {
+      ProjectionError::offset_store_failed(format!("sync offset directory {}: {error}", self.directory.display()))
+    })?;
+    Ok(())
+  }
+}
+
+impl OffsetStore for LocalOffsetStore {
+  fn read_offset(&self, projection_id: &ProjectionId) -> Result<Option<Offset>, ProjectionError> {
+    let path = self.offset_path(projection_id);
+    let contents = match fs::read_to_string(&path) {
+      | Ok(contents) => contents,
+      | Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+      | Err(error) => {
+        return Err(ProjectionError::offset_store_failed(format!("read offset {}: {error}", path.display())));
+      },
+    };
+    decode_offset(&contents)
+      .map(Some)
+      .ok_or_else(|| ProjectionError::offset_store_failed(format!("corrupt offset file {}", path.display())))
+  }
+
+  fn save_offset(&mut self, projection_id: &ProjectionId, offset: &Offset) -> Result<(), ProjectionError> {
+    let path = self.offset_path(projection_id);
+    let temp_path = path.with_extension(OFFSET_TEMP_EXTENSION);
+    let mut file = File::create(&temp_path).map_err(|error| {
+      ProjectionError::offset_store_failed(format!("create temp offset {}: {error}", temp_path.display()))
+    })?;
+    file.write_all(encode_offset(offset).as_bytes()).and_then(|()| file.sync_all()).map_err(|error| {
+      ProjectionError::offset_store_failed(format!("write temp offset {}: {error}", temp_path.display()))
+    })?;
+    drop(file);
+    fs::rename(&temp_path, &path)
+      .map_err(|error| ProjectionError::offset_store_failed(format!("replace offset {}: {error}", path.display())))?;
+    self.sync_directory()
+  }
+
+  fn clear_offset(&mut self, projection_id: &ProjectionId) -> Result<(), ProjectionError> {
+    let path = self.offset_path(projection_id);
+    match fs::remove_file(&path) {
+      | Ok(()) => self.sync_directory(),
+      | Err(error) if error.kind() == ErrorKind::NotFound => Ok(()),
+      | Err(error) => Err(ProjectionError::offset_store_failed(format!("remove offset {}: {error}", path.display()))),
+    }
+  }
+}
+
+fn encode_offset(offset: &Offset) -> String {
+  match offset {
+    | Offset::NoOffset => String::new(),
+    | Offset::Sequence(value) => format!("{SEQUENCE_RECORD} {value}\n"),
+    | Offset::Timestamp(timestamp) => {
+      let mut encoded = format!("{TIMESTAMP_RECORD} {}\n", timestamp.timestamp());
+      for (persistence_id, sequence_nr) in timestamp.seen() {
+        encoded.push_str(&format!("{SEEN_RECORD} {sequence_nr} {}\n", encode_component(persistence_id)));
+      }
+      encoded
+    },
+  }
+}
+
+fn decode_offset(contents: &str) -> Option<Offset> {
+  let mut lines = contents.lines();
+  let Some(first) = lines.next() else {
+    return Some(Offset::NoOffset);
+  };
+  let (kind, value) = first.split_once(' ')?;
+  let value = value.parse::<u64>().ok()?;
+  match kind {
+    | SEQUENCE_RECORD if lines.next().is_none() => Some(Offset::Sequence(value)),
+    | TIMESTAMP_RECORD => {
+      let mut timestamp = TimestampOffset::new(value);
+      for line in lines {
+        let (kind, rest) = line.split_once(' ')?;
+        let (sequence_nr, persistence_id) = rest.split_once(' ')?;
+        if kind != SEEN_RECORD {
+          return None;
+        }
+        timestamp = timestamp.with_seen(decode_component(persistence_id)?, sequence_nr.parse::<u64>().ok()?);
+      }
+      Some(Offset::Timestamp(timestamp))
+    },
+    | _ => None,
+  }
+}
+
+fn encode_component(component: &str) -> String {
+  let mut encoded = String::new();
+  for byte in component.bytes() {
+    if byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'.') {
+      encoded.push(char::from(byte));
+    } else {
+      encoded.push(PERCENT_ENCODING_MARKER);
+      encoded.push(char::from(HEX_DIGITS[(byte >> 4) as usize]));
+      encoded.push(char::from(HEX_DIGITS[(byte & 0x0F) as usize]));
+    }
+  }
+  encoded
+}
+
+fn decode_component(encoded: &str) -> Option<String> {
+  let mut bytes = Vec::new();
+  let mut iter = encoded.bytes();
+  while let Some(byte) = iter.next() {
+    if byte == PERCENT_ENCODING_MARKER as u8 {
+      let high = char::from(iter.next()?).to_digit(16)?;
+      let low = char::from(iter.next()?).to_digit(16)?;
+      bytes.push(u8::try_from((high << 4) | low).ok()?);
+    } else {
+      bytes.push(byte);
+    }
+  }
+  String::from_utf8(bytes).ok()
+}
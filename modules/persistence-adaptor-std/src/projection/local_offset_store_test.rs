@@ -0,0 +1,80 @@
+extern crate std;
+
+use std::{
+  fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_persistence_core_kernel_rs::{
+  projection::{OffsetStore, ProjectionError, ProjectionId},
+  query::{Offset, TimestampOffset},
+};
+
+use super::LocalOffsetStore;
+
+fn unique_offset_dir(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  std::env::temp_dir().join(format!("fraktor-local-offset-{name}-{}-{timestamp}", std::process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("offset test directory should be removable: {error}"),
+  }
+}
+
+#[test]
+fn local_offset_store_persists_offsets_across_reopen() {
+  let directory = unique_offset_dir("reopen");
+  let blue = ProjectionId::new("orders", "blue/1");
+  let red = ProjectionId::new("orders", "red");
+  let timestamp = Offset::Timestamp(TimestampOffset::new(42).with_seen("pid 1", 3).with_seen("pid-2", 7));
+
+  let mut store = LocalOffsetStore::open(&directory).expect("open offset store");
+  store.save_offset(&blue, &Offset::sequence(5)).expect("save blue");
+  store.save_offset(&red, &timestamp).expect("save red");
+  store.save_offset(&blue, &Offset::sequence(6)).expect("overwrite blue");
+
+  let reopened = LocalOffsetStore::open(&directory).expect("reopen offset store");
+  assert_eq!(reopened.read_offset(&blue), Ok(Some(Offset::sequence(6))));
+  assert_eq!(reopened.read_offset(&red), Ok(Some(timestamp)));
+  assert_eq!(reopened.read_offset(&ProjectionId::new("orders", "green")), Ok(None));
+
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_offset_store_clears_offsets() {
+  let directory = unique_offset_dir("clear");
+  let id = ProjectionId::new("orders", "blue");
+  let mut store = LocalOffsetStore::open(&directory).expect("open offset store");
+
+  store.save_offset(&id, &Offset::NoOffset).expect("save no offset");
+  assert_eq!(store.read_offset(&id), Ok(Some(Offset::NoOffset)));
+  store.clear_offset(&id).expect("clear offset");
+  store.clear_offset(&id).expect("clear missing offset");
+
+  assert_eq!(store.read_offset(&id), Ok(None));
+
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn local_offset_store_rejects_corrupt_offset_files() {
+  let directory = unique_offset_dir("corrupt");
+  let id = ProjectionId::new("orders", "blue");
+  let store = LocalOffsetStore::open(&directory).expect("open offset store");
+  fs::write(directory.join("offset-orders-blue"), "sequence not-a-number\n").expect("write corrupt offset");
+
+  let result = store.read_offset(&id);
+
+  assert!(matches!(result, Err(ProjectionError::OffsetStoreFailed(_))));
+  remove_dir_if_exists(&directory);
+}
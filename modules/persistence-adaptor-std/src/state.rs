@@ -0,0 +1,9 @@
+//! Filesystem-backed durable state store package.
+
+mod durable_state_record;
+mod local_durable_state_store;
+mod local_durable_state_store_config;
+mod local_durable_state_store_state;
+
+pub use local_durable_state_store::LocalDurableStateStore;
+pub use local_durable_state_store_config::LocalDurableStateStoreConfig;
@@ -0,0 +1,109 @@
+//! Checksummed on-disk record framing for the local durable state store.
+
+#[cfg(test)]
+#[path = "durable_state_record_test.rs"]
+mod tests;
+
+use crate::journal::{FRAME_HEADER_LEN, MAX_RECORD_BODY_LEN, crc32};
+
+const UNTAGGED_MARKER: u8 = 0;
+const TAGGED_MARKER: u8 = 1;
+
+/// One stored revision of a durable state object.
+///
+/// The same record is written as the whole content of an object file and appended to the change
+/// log when the revision is tagged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DurableStateRecord {
+  /// Change log offset reserved for the revision, or `0` for an untagged revision.
+  pub(crate) offset:         u64,
+  pub(crate) persistence_id: String,
+  pub(crate) revision:       u64,
+  pub(crate) tag:            Option<String>,
+  /// Encoded [`SerializedMessage`](fraktor_actor_core_kernel_rs::serialization::SerializedMessage).
+  pub(crate) payload:        Vec<u8>,
+}
+
+impl DurableStateRecord {
+  /// Encodes the record body and prefixes it with its length and checksum.
+  pub(crate) fn encode_frame(&self) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    body.extend_from_slice(&self.offset.to_le_bytes());
+    write_bytes(&mut body, self.persistence_id.as_bytes())?;
+    body.extend_from_slice(&self.revision.to_le_bytes());
+    match &self.tag {
+      | Some(tag) => {
+        body.push(TAGGED_MARKER);
+        write_bytes(&mut body, tag.as_bytes())?;
+      },
+      | None => body.push(UNTAGGED_MARKER),
+    }
+    write_bytes(&mut body, &self.payload)?;
+    let body_len = u32::try_from(body.len())
+      .ok()
+      .filter(|len| *len <= MAX_RECORD_BODY_LEN)
+      .ok_or_else(|| format!("durable state record body of {} bytes exceeds the record size limit", body.len()))?;
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + body.len());
+    frame.extend_from_slice(&body_len.to_le_bytes());
+    frame.extend_from_slice(&crc32(&body).to_le_bytes());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+  }
+
+  /// Splits a frame header into the declared body length and checksum.
+  pub(crate) const fn parse_frame_header(header: [u8; FRAME_HEADER_LEN]) -> (u32, u32) {
+    let body_len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let checksum = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    (body_len, checksum)
+  }
+
+  /// Decodes a record body after verifying it against the frame checksum.
+  ///
+  /// Returns `None` when the checksum does not match or the body is malformed.
+  pub(crate) fn decode_body(body: &[u8], checksum: u32) -> Option<Self> {
+    if crc32(body) != checksum {
+      return None;
+    }
+    let mut cursor = 0;
+    let offset = read_u64(body, &mut cursor)?;
+    let persistence_id = read_string(body, &mut cursor)?;
+    let revision = read_u64(body, &mut cursor)?;
+    let marker = *body.get(cursor)?;
+    cursor += 1;
+    let tag = match marker {
+      | UNTAGGED_MARKER => None,
+      | TAGGED_MARKER => Some(read_string(body, &mut cursor)?),
+      | _ => return None,
+    };
+    let payload = read_bytes(body, &mut cursor)?.to_vec();
+    (cursor == body.len()).then_some(Self { offset, persistence_id, revision, tag, payload })
+  }
+}
+
+fn write_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) -> Result<(), String> {
+  let len = u32::try_from(bytes.len())
+    .map_err(|_| format!("durable state record field of {} bytes is too large", bytes.len()))?;
+  buffer.extend_from_slice(&len.to_le_bytes());
+  buffer.extend_from_slice(bytes);
+  Ok(())
+}
+
+fn read_u64(bytes: &[u8], cursor: &mut usize) -> Option<u64> {
+  let end = cursor.checked_add(8)?;
+  let value = u64::from_le_bytes(bytes.get(*cursor..end)?.try_into().ok()?);
+  *cursor = end;
+  Some(value)
+}
+
+fn read_bytes<'a>(bytes: &'a [u8], cursor: &mut usize) -> Option<&'a [u8]> {
+  let len_end = cursor.checked_add(4)?;
+  let len = u32::from_le_bytes(bytes.get(*cursor..len_end)?.try_into().ok()?) as usize;
+  let end = len_end.checked_add(len)?;
+  let value = bytes.get(len_end..end)?;
+  *cursor = end;
+  Some(value)
+}
+
+fn read_string(bytes: &[u8], cursor: &mut usize) -> Option<String> {
+  String::from_utf8(read_bytes(bytes, cursor)?.to_vec()).ok()
+}
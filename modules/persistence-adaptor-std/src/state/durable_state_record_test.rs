@@ -0,0 +1,49 @@
+use super::DurableStateRecord;
+use crate::journal::FRAME_HEADER_LEN;
+
+fn split_frame(frame: &[u8]) -> (u32, u32, &[u8]) {
+  let header: [u8; FRAME_HEADER_LEN] = frame[..FRAME_HEADER_LEN].try_into().expect("frame header");
+  let (body_len, checksum) = DurableStateRecord::parse_frame_header(header);
+  (body_len, checksum, &frame[FRAME_HEADER_LEN..])
+}
+
+fn record(tag: Option<&str>) -> DurableStateRecord {
+  DurableStateRecord {
+    offset:         4,
+    persistence_id: String::from("pid-1"),
+    revision:       2,
+    tag:            tag.map(String::from),
+    payload:        vec![1, 2, 3],
+  }
+}
+
+#[test]
+fn tagged_record_round_trips_through_frame() {
+  let record = record(Some("orders"));
+
+  let frame = record.encode_frame().expect("encode frame");
+  let (body_len, checksum, body) = split_frame(&frame);
+
+  assert_eq!(body_len as usize, body.len());
+  assert_eq!(DurableStateRecord::decode_body(body, checksum), Some(record));
+}
+
+#[test]
+fn untagged_record_round_trips_through_frame() {
+  let record = record(None);
+
+  let frame = record.encode_frame().expect("encode frame");
+  let (_, checksum, body) = split_frame(&frame);
+
+  assert_eq!(DurableStateRecord::decode_body(body, checksum), Some(record));
+}
+
+#[test]
+fn decode_body_rejects_checksum_mismatch() {
+  let mut frame = record(Some("orders")).encode_frame().expect("encode frame");
+  let last = frame.len() - 1;
+  frame[last] ^= 0xFF;
+  let (_, checksum, body) = split_frame(&frame);
+
+  assert_eq!(DurableStateRecord::decode_body(body, checksum), None);
+}
@@ -0,0 +1,139 @@
+//! Filesystem-backed local durable state store.
+
+#[cfg(test)]
+#[path = "local_durable_state_store_test.rs"]
+mod tests;
+
+use core::{
+  any::type_name,
+  future::{Future, ready},
+  marker::PhantomData,
+  ops::Deref,
+  pin::Pin,
+};
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  SerializationDelegator, SerializedMessage, serialization_registry::SerializationRegistry,
+};
+use fraktor_persistence_core_kernel_rs::state::{
+  DurableStateChange, DurableStateError, DurableStateStore, DurableStateStoreProvider, DurableStateUpdateStore,
+  GetObjectResult,
+};
+use fraktor_utils_core_rs::sync::{ArcShared, DefaultMutex, SharedLock};
+
+use crate::state::{LocalDurableStateStoreConfig, local_durable_state_store_state::LocalDurableStateStoreState};
+
+type DurableStateFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, DurableStateError>> + Send + 'a>>;
+
+/// Embedded durable state store keeping one atomically replaced file per persistence id.
+///
+/// Objects are written to a synced temporary file and renamed into place, so a crash leaves either
+/// the previous or the new revision on disk. Tagged revisions are also appended to a checksummed
+/// change log that backs [`DurableStateUpdateStore::changes`]; change offsets are shared by all
+/// tags and increase in write order. A failed change-log append fails the upsert even though the
+/// object already holds the new revision; the append is retried by the next operation. Clones share
+/// the same directory, indexes and change log.
+pub struct LocalDurableStateStore<A> {
+  state:         SharedLock<LocalDurableStateStoreState>,
+  serialization: ArcShared<SerializationRegistry>,
+  _marker:       PhantomData<fn() -> A>,
+}
+
+impl<A: Send + Sync + 'static> LocalDurableStateStore<A> {
+  /// Opens a local durable state store, creating its directory when missing and recovering the
+  /// change log.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`DurableStateError::GetObjectFailed`] when the directory cannot be prepared or an
+  /// object file is corrupt.
+  pub fn open(config: LocalDurableStateStoreConfig) -> Result<Self, DurableStateError> {
+    let (directory, serialization) = config.into_parts();
+    let state = LocalDurableStateStoreState::open(directory)?;
+    Ok(Self { state: SharedLock::new_with_driver::<DefaultMutex<_>>(state), serialization, _marker: PhantomData })
+  }
+
+  fn encode(&self, object: &A) -> Result<Vec<u8>, String> {
+    let delegator = SerializationDelegator::new(self.serialization.deref());
+    let serialized =
+      delegator.serialize(object, type_name::<A>()).map_err(|error| format!("serialize durable state: {error}"))?;
+    Ok(serialized.encode())
+  }
+
+  fn decode(&self, payload: &[u8]) -> Result<A, String> {
+    let serialized =
+      SerializedMessage::decode(payload).map_err(|error| format!("decode durable state payload: {error}"))?;
+    let delegator = SerializationDelegator::new(self.serialization.deref());
+    let value =
+      delegator.deserialize(&serialized, None).map_err(|error| format!("deserialize durable state: {error}"))?;
+    value.downcast::<A>().map(|value| *value).map_err(|_| String::from("durable state payload type mismatch"))
+  }
+}
+
+impl<A> Clone for LocalDurableStateStore<A> {
+  fn clone(&self) -> Self {
+    Self { state: self.state.clone(), serialization: self.serialization.clone(), _marker: PhantomData }
+  }
+}
+
+impl<A: Send + Sync + 'static> DurableStateStore<A> for LocalDurableStateStore<A> {
+  fn get_object<'a>(&'a self, persistence_id: &'a str) -> DurableStateFuture<'a, GetObjectResult<A>> {
+    let result = self
+      .state
+      .with_lock(|state| state.get_object(persistence_id))
+      .and_then(|record| match record {
+        | Some(record) => Ok(GetObjectResult::new(Some(self.decode(&record.payload)?), record.revision)),
+        | None => Ok(GetObjectResult::empty()),
+      })
+      .map_err(DurableStateError::GetObjectFailed);
+    Box::pin(ready(result))
+  }
+
+  fn upsert_object<'a>(
+    &'a mut self,
+    persistence_id: &'a str,
+    expected_revision: u64,
+    object: A,
+    tag: Option<&'a str>,
+  ) -> DurableStateFuture<'a, ()> {
+    let result = self.encode(&object).map_err(DurableStateError::UpsertObjectFailed).and_then(|payload| {
+      self.state.with_lock(|state| state.upsert_object(persistence_id, expected_revision, tag, payload))
+    });
+    Box::pin(ready(result))
+  }
+
+  fn delete_object<'a>(&'a mut self, persistence_id: &'a str, expected_revision: u64) -> DurableStateFuture<'a, ()> {
+    Box::pin(ready(self.state.with_lock(|state| state.delete_object(persistence_id, expected_revision))))
+  }
+}
+
+impl<A: Send + Sync + 'static> DurableStateUpdateStore<A> for LocalDurableStateStore<A> {
+  fn changes<'a>(&'a self, tag: &'a str, from_offset: usize) -> DurableStateFuture<'a, Option<DurableStateChange<A>>> {
+    let result = self
+      .state
+      .with_lock(|state| state.next_change(tag, from_offset as u64))
+      .and_then(|record| {
+        let Some(record) = record else {
+          return Ok(None);
+        };
+        let offset =
+          usize::try_from(record.offset).map_err(|_| format!("change offset {} overflows", record.offset))?;
+        let value = self.decode(&record.payload)?;
+        Ok(Some(DurableStateChange::new(
+          offset,
+          record.persistence_id,
+          record.revision,
+          record.tag.unwrap_or_default(),
+          value,
+        )))
+      })
+      .map_err(DurableStateError::ChangesFailed);
+    Box::pin(ready(result))
+  }
+}
+
+impl<A: Send + Sync + 'static> DurableStateStoreProvider<A> for LocalDurableStateStore<A> {
+  fn durable_state_store(&self) -> Box<dyn DurableStateStore<A>> {
+    Box::new(self.clone())
+  }
+}
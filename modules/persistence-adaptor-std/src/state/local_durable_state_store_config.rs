@@ -0,0 +1,41 @@
+//! Local durable state store configuration.
+
+#[cfg(test)]
+#[path = "local_durable_state_store_config_test.rs"]
+mod tests;
+
+use std::path::{Path, PathBuf};
+
+use fraktor_actor_core_kernel_rs::serialization::serialization_registry::SerializationRegistry;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+/// Configuration for [`LocalDurableStateStore`](super::LocalDurableStateStore).
+#[derive(Clone)]
+pub struct LocalDurableStateStoreConfig {
+  directory:     PathBuf,
+  serialization: ArcShared<SerializationRegistry>,
+}
+
+impl LocalDurableStateStoreConfig {
+  /// Creates a new local durable state store configuration.
+  #[must_use]
+  pub const fn new(directory: PathBuf, serialization: ArcShared<SerializationRegistry>) -> Self {
+    Self { directory, serialization }
+  }
+
+  /// Returns the durable state root directory.
+  #[must_use]
+  pub fn directory(&self) -> &Path {
+    &self.directory
+  }
+
+  /// Returns the serialization registry used to encode stored objects.
+  #[must_use]
+  pub const fn serialization(&self) -> &ArcShared<SerializationRegistry> {
+    &self.serialization
+  }
+
+  pub(crate) fn into_parts(self) -> (PathBuf, ArcShared<SerializationRegistry>) {
+    (self.directory, self.serialization)
+  }
+}
@@ -0,0 +1,21 @@
+extern crate std;
+
+use std::path::PathBuf;
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  default_serialization_setup, serialization_registry::SerializationRegistry,
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::LocalDurableStateStoreConfig;
+
+#[test]
+fn local_durable_state_store_config_exposes_directory_and_registry() {
+  let directory = PathBuf::from("target/local-durable-state-store/config");
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&default_serialization_setup()));
+
+  let config = LocalDurableStateStoreConfig::new(directory.clone(), registry.clone());
+
+  assert_eq!(config.directory(), directory.as_path());
+  assert!(ArcShared::ptr_eq(config.serialization(), &registry));
+}
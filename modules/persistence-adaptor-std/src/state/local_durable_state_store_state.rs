@@ -0,0 +1,333 @@
+//! Shared index and change log of the local durable state store.
+
+use std::{
+  collections::BTreeMap,
+  fs::{self, File, OpenOptions},
+  io::{BufReader, Error, ErrorKind, Read, Seek, SeekFrom, Write},
+  path::{Path, PathBuf},
+};
+
+use fraktor_persistence_core_kernel_rs::state::DurableStateError;
+
+use crate::{
+  journal::{FRAME_HEADER_LEN, MAX_RECORD_BODY_LEN},
+  state::durable_state_record::DurableStateRecord,
+};
+
+const OBJECT_FILE_PREFIX: &str = "state-";
+const TEMP_FILE_SUFFIX: &str = ".tmp";
+const CHANGE_LOG_FILE_NAME: &str = "changes.log";
+const FIRST_CHANGE_OFFSET: u64 = 1;
+const PERCENT_ENCODING_MARKER: char = '%';
+const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Object files, change log and in-memory indexes shared by every clone of one store.
+///
+/// An upsert first replaces the object file atomically; a tagged revision is then appended to the
+/// change log under the offset reserved in the object record. A revision whose append failed stays
+/// pending and is flushed before the next operation, and on reopen any tagged object newer than the
+/// change log is appended again, so the change log never misses a committed tagged revision.
+pub(crate) struct LocalDurableStateStoreState {
+  directory:      PathBuf,
+  change_log:     File,
+  change_log_len: u64,
+  next_offset:    u64,
+  tag_index:      BTreeMap<String, Vec<(u64, u64)>>,
+  revisions:      BTreeMap<String, u64>,
+  pending:        Option<DurableStateRecord>,
+}
+
+impl LocalDurableStateStoreState {
+  /// Opens the store directory, truncating a torn change log tail and re-appending tagged revisions
+  /// that were committed without reaching the change log.
+  pub(crate) fn open(directory: PathBuf) -> Result<Self, DurableStateError> {
+    fs::create_dir_all(&directory).map_err(|error| {
+      DurableStateError::GetObjectFailed(format!("create durable state directory {}: {error}", directory.display()))
+    })?;
+    let objects = Self::load_objects(&directory).map_err(DurableStateError::GetObjectFailed)?;
+    let change_log_path = directory.join(CHANGE_LOG_FILE_NAME);
+    let change_log =
+      OpenOptions::new().read(true).append(true).create(true).open(&change_log_path).map_err(|error| {
+        DurableStateError::GetObjectFailed(format!("open change log {}: {error}", change_log_path.display()))
+      })?;
+    let mut state = Self {
+      directory,
+      change_log,
+      change_log_len: 0,
+      next_offset: FIRST_CHANGE_OFFSET,
+      tag_index: BTreeMap::new(),
+      revisions: objects.iter().map(|record| (record.persistence_id.clone(), record.revision)).collect(),
+      pending: None,
+    };
+    state.scan_change_log().map_err(DurableStateError::GetObjectFailed)?;
+    let mut unlogged = objects
+      .into_iter()
+      .filter(|record| record.tag.is_some() && record.offset >= state.next_offset)
+      .collect::<Vec<_>>();
+    unlogged.sort_by_key(|record| record.offset);
+    for record in unlogged {
+      state.next_offset = record.offset.saturating_add(1);
+      state.pending = Some(record);
+      state.flush_pending().map_err(DurableStateError::GetObjectFailed)?;
+    }
+    Ok(state)
+  }
+
+  /// Returns the stored revision, or `0` when no object exists.
+  pub(crate) fn revision(&self, persistence_id: &str) -> u64 {
+    self.revisions.get(persistence_id).copied().unwrap_or(0)
+  }
+
+  /// Reads the current object record.
+  pub(crate) fn get_object(&self, persistence_id: &str) -> Result<Option<DurableStateRecord>, String> {
+    if !self.revisions.contains_key(persistence_id) {
+      return Ok(None);
+    }
+    let path = self.object_path(persistence_id);
+    let bytes = fs::read(&path).map_err(|error| format!("read durable state object {}: {error}", path.display()))?;
+    decode_object(&bytes).map(Some).ok_or_else(|| format!("corrupt durable state object {}", path.display()))
+  }
+
+  /// Stores the next revision of an object when `expected_revision` matches the stored revision.
+  ///
+  /// When the tagged change cannot be appended to the change log, the error is returned although
+  /// the object already holds the new revision; the append is retried by the next operation or on
+  /// reopen.
+  pub(crate) fn upsert_object(
+    &mut self,
+    persistence_id: &str,
+    expected_revision: u64,
+    tag: Option<&str>,
+    payload: Vec<u8>,
+  ) -> Result<(), DurableStateError> {
+    self.flush_pending().map_err(DurableStateError::UpsertObjectFailed)?;
+    let actual_revision = self.revision(persistence_id);
+    if actual_revision != expected_revision {
+      return Err(DurableStateError::upsert_revision(persistence_id, expected_revision, actual_revision));
+    }
+    let record = DurableStateRecord {
+      offset: if tag.is_some() { self.next_offset } else { 0 },
+      persistence_id: persistence_id.to_string(),
+      revision: expected_revision.saturating_add(1),
+      tag: tag.map(str::to_string),
+      payload,
+    };
+    let frame = record.encode_frame().map_err(DurableStateError::UpsertObjectFailed)?;
+    self.replace_object(persistence_id, &frame).map_err(DurableStateError::UpsertObjectFailed)?;
+    self.revisions.insert(record.persistence_id.clone(), record.revision);
+    if record.tag.is_some() {
+      self.next_offset = self.next_offset.saturating_add(1);
+      self.pending = Some(record);
+      // 失敗しても pending に残るので、次の操作か再オープン時に追記を再試行する
+      self.flush_pending().map_err(DurableStateError::UpsertObjectFailed)?;
+    }
+    Ok(())
+  }
+
+  /// Removes an object when `expected_revision` matches the stored revision.
+  pub(crate) fn delete_object(
+    &mut self,
+    persistence_id: &str,
+    expected_revision: u64,
+  ) -> Result<(), DurableStateError> {
+    self.flush_pending().map_err(DurableStateError::DeleteObjectFailed)?;
+    let actual_revision = self.revision(persistence_id);
+    if actual_revision != expected_revision {
+      return Err(DurableStateError::delete_revision(persistence_id, expected_revision, actual_revision));
+    }
+    let path = self.object_path(persistence_id);
+    match fs::remove_file(&path) {
+      | Ok(()) => self.sync_directory().map_err(DurableStateError::DeleteObjectFailed)?,
+      | Err(error) if error.kind() == ErrorKind::NotFound => (),
+      | Err(error) => {
+        return Err(DurableStateError::DeleteObjectFailed(format!(
+          "remove durable state object {}: {error}",
+          path.display()
+        )));
+      },
+    }
+    self.revisions.remove(persistence_id);
+    Ok(())
+  }
+
+  /// Reads the first change carrying `tag` whose offset is greater than `from_offset`.
+  pub(crate) fn next_change(&mut self, tag: &str, from_offset: u64) -> Result<Option<DurableStateRecord>, String> {
+    self.flush_pending()?;
+    let Some(&(_, position)) = self
+      .tag_index
+      .get(tag)
+      .and_then(|entries| entries.get(entries.partition_point(|(offset, _)| *offset <= from_offset)))
+    else {
+      return Ok(None);
+    };
+    let mut reader = &self.change_log;
+    reader.seek(SeekFrom::Start(position)).map_err(|error| format!("seek change log: {error}"))?;
+    match read_record(&mut reader) {
+      | Ok(Some((record, _))) => Ok(Some(record)),
+      | Ok(None) | Err(RecordReadError::Torn) => Err(format!("corrupt change log record at {position}")),
+      | Err(RecordReadError::Io(error)) => Err(format!("read change log: {error}")),
+    }
+  }
+
+  fn flush_pending(&mut self) -> Result<(), String> {
+    let Some(record) = self.pending.as_ref() else {
+      return Ok(());
+    };
+    let frame = record.encode_frame()?;
+    // 前回の追記が途中で失敗していた場合に備えて、確定済みの末尾まで切り詰めてから書く
+    self.change_log.set_len(self.change_log_len).map_err(|error| format!("truncate change log: {error}"))?;
+    (&self.change_log)
+      .write_all(&frame)
+      .and_then(|()| self.change_log.sync_data())
+      .map_err(|error| format!("append change log: {error}"))?;
+    if let Some(tag) = &record.tag {
+      self.tag_index.entry(tag.clone()).or_default().push((record.offset, self.change_log_len));
+    }
+    self.change_log_len += frame.len() as u64;
+    self.pending = None;
+    Ok(())
+  }
+
+  fn scan_change_log(&mut self) -> Result<(), String> {
+    let mut reader = BufReader::new(&self.change_log);
+    reader.seek(SeekFrom::Start(0)).map_err(|error| format!("seek change log: {error}"))?;
+    let mut position = 0;
+    loop {
+      match read_record(&mut reader) {
+        | Ok(Some((record, frame_len))) => {
+          if let Some(tag) = record.tag {
+            self.tag_index.entry(tag).or_default().push((record.offset, position));
+          }
+          self.next_offset = self.next_offset.max(record.offset.saturating_add(1));
+          position += frame_len;
+        },
+        | Ok(None) => break,
+        | Err(RecordReadError::Torn) => {
+          self
+            .change_log
+            .set_len(position)
+            .and_then(|()| self.change_log.sync_all())
+            .map_err(|error| format!("truncate torn change log tail: {error}"))?;
+          break;
+        },
+        | Err(RecordReadError::Io(error)) => return Err(format!("read change log: {error}")),
+      }
+    }
+    self.change_log_len = position;
+    Ok(())
+  }
+
+  fn load_objects(directory: &Path) -> Result<Vec<DurableStateRecord>, String> {
+    let entries = fs::read_dir(directory)
+      .map_err(|error| format!("list durable state directory {}: {error}", directory.display()))?;
+    let mut objects = Vec::new();
+    for entry in entries {
+      let path = entry.map_err(|error| format!("list durable state directory: {error}"))?.path();
+      let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        continue;
+      };
+      if !name.starts_with(OBJECT_FILE_PREFIX) {
+        continue;
+      }
+      if name.ends_with(TEMP_FILE_SUFFIX) {
+        // rename 前に中断した書き込みは確定していないので破棄する
+        fs::remove_file(&path).map_err(|error| format!("remove temp object {}: {error}", path.display()))?;
+        continue;
+      }
+      let bytes = fs::read(&path).map_err(|error| format!("read durable state object {}: {error}", path.display()))?;
+      objects.push(decode_object(&bytes).ok_or_else(|| format!("corrupt durable state object {}", path.display()))?);
+    }
+    Ok(objects)
+  }
+
+  fn replace_object(&self, persistence_id: &str, frame: &[u8]) -> Result<(), String> {
+    let path = self.object_path(persistence_id);
+    let mut temp_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    temp_name.push(TEMP_FILE_SUFFIX);
+    let temp_path = path.with_file_name(temp_name);
+    let mut file =
+      File::create(&temp_path).map_err(|error| format!("create temp object {}: {error}", temp_path.display()))?;
+    file
+      .write_all(frame)
+      .and_then(|()| file.sync_all())
+      .map_err(|error| format!("write temp object {}: {error}", temp_path.display()))?;
+    drop(file);
+    fs::rename(&temp_path, &path).map_err(|error| format!("replace object {}: {error}", path.display()))?;
+    self.sync_directory()
+  }
+
+  fn object_path(&self, persistence_id: &str) -> PathBuf {
+    self.directory.join(format!("{OBJECT_FILE_PREFIX}{}", encode_component(persistence_id)))
+  }
+
+  fn sync_directory(&self) -> Result<(), String> {
+    #[cfg(not(windows))]
+    File::open(&self.directory)
+      .and_then(|directory| directory.sync_all())
+      .map_err(|error| format!("sync durable state directory {}: {error}", self.directory.display()))?;
+    Ok(())
+  }
+}
+
+/// Failure while reading one framed record.
+enum RecordReadError {
+  /// The frame is incomplete or fails its checksum.
+  Torn,
+  /// The underlying file could not be read.
+  Io(Error),
+}
+
+fn decode_object(bytes: &[u8]) -> Option<DurableStateRecord> {
+  let mut reader = bytes;
+  match read_record(&mut reader) {
+    | Ok(Some((record, _))) if reader.is_empty() => Some(record),
+    | _ => None,
+  }
+}
+
+/// Reads the next framed record, returning `Ok(None)` at a clean end of file.
+fn read_record(reader: &mut impl Read) -> Result<Option<(DurableStateRecord, u64)>, RecordReadError> {
+  let mut header = [0_u8; FRAME_HEADER_LEN];
+  match read_full(reader, &mut header)? {
+    | 0 => return Ok(None),
+    | read if read < FRAME_HEADER_LEN => return Err(RecordReadError::Torn),
+    | _ => (),
+  }
+  let (body_len, checksum) = DurableStateRecord::parse_frame_header(header);
+  if body_len == 0 || body_len > MAX_RECORD_BODY_LEN {
+    return Err(RecordReadError::Torn);
+  }
+  let mut body = vec![0_u8; body_len as usize];
+  if read_full(reader, &mut body)? < body.len() {
+    return Err(RecordReadError::Torn);
+  }
+  let record = DurableStateRecord::decode_body(&body, checksum).ok_or(RecordReadError::Torn)?;
+  Ok(Some((record, FRAME_HEADER_LEN as u64 + u64::from(body_len))))
+}
+
+fn read_full(reader: &mut impl Read, buffer: &mut [u8]) -> Result<usize, RecordReadError> {
+  let mut read = 0;
+  while read < buffer.len() {
+    match reader.read(&mut buffer[read..]) {
+      | Ok(0) => break,
+      | Ok(count) => read += count,
+      | Err(error) if error.kind() == ErrorKind::Interrupted => (),
+      | Err(error) => return Err(RecordReadError::Io(error)),
+    }
+  }
+  Ok(read)
+}
+
+fn encode_component(component: &str) -> String {
+  let mut encoded = String::new();
+  for byte in component.bytes() {
+    if byte.is_ascii_alphanumeric() || byte == b'_' {
+      encoded.push(char::from(byte));
+    } else {
+      encoded.push(PERCENT_ENCODING_MARKER);
+      encoded.push(char::from(HEX_DIGITS[(byte >> 4) as usize]));
+      encoded.push(char::from(HEX_DIGITS[(byte & 0x0F) as usize]));
+    }
+  }
+  encoded
+}
@@ -0,0 +1,193 @@
+extern crate std;
+
+use core::{
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+use std::{
+  fs::{self, OpenOptions},
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  builtin, default_serialization_setup, serialization_registry::SerializationRegistry,
+};
+use fraktor_persistence_core_kernel_rs::state::{DurableStateError, DurableStateStore, DurableStateUpdateStore};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::LocalDurableStateStore;
+use crate::state::LocalDurableStateStoreConfig;
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let waker = Waker::noop();
+  let mut cx = Context::from_waker(waker);
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("local durable state store future should be ready"),
+  }
+}
+
+fn serialization_registry() -> ArcShared<SerializationRegistry> {
+  let setup = default_serialization_setup();
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&setup));
+  builtin::register_defaults(&registry, |name, id| panic!("unexpected serializer collision for {name}: {id:?}"))
+    .expect("register builtin serializers");
+  registry
+}
+
+fn unique_state_dir(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  std::env::temp_dir().join(format!("fraktor-local-durable-state-{name}-{}-{timestamp}", std::process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("durable state test directory should be removable: {error}"),
+  }
+}
+
+fn open_store(directory: &Path) -> LocalDurableStateStore<i32> {
+  let config = LocalDurableStateStoreConfig::new(directory.to_path_buf(), serialization_registry());
+  LocalDurableStateStore::open(config).expect("open local durable state store")
+}
+
+fn upsert(store: &mut LocalDurableStateStore<i32>, persistence_id: &str, revision: u64, value: i32, tag: Option<&str>) {
+  poll_ready(store.upsert_object(persistence_id, revision, value, tag)).expect("upsert durable state");
+}
+
+fn get(store: &LocalDurableStateStore<i32>, persistence_id: &str) -> (Option<i32>, u64) {
+  let result = poll_ready(store.get_object(persistence_id)).expect("get durable state");
+  (result.value().copied(), result.revision())
+}
+
+fn changes(store: &LocalDurableStateStore<i32>, tag: &str) -> Vec<(usize, String, u64, i32)> {
+  let mut collected = Vec::new();
+  let mut offset = 0;
+  while let Some(change) = poll_ready(store.changes(tag, offset)).expect("load change") {
+    offset = change.offset();
+    collected.push((change.offset(), change.persistence_id().to_string(), change.revision(), *change.value()));
+  }
+  collected
+}
+
+#[test]
+fn upsert_advances_revision_and_clones_share_objects() {
+  let directory = unique_state_dir("upsert");
+  let store = open_store(&directory);
+  let mut cloned = store.clone();
+
+  upsert(&mut cloned, "pid-1", 0, 10, None);
+  upsert(&mut cloned, "pid-1", 1, 11, None);
+
+  assert_eq!(get(&store, "pid-1"), (Some(11), 2));
+  assert_eq!(get(&store, "missing"), (None, 0));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn stale_expected_revision_is_rejected() {
+  let directory = unique_state_dir("revision");
+  let mut store = open_store(&directory);
+  upsert(&mut store, "pid-1", 0, 10, None);
+
+  let upsert_error = poll_ready(store.upsert_object("pid-1", 0, 20, None)).expect_err("stale upsert");
+  let delete_error = poll_ready(store.delete_object("pid-1", 3)).expect_err("stale delete");
+
+  assert_eq!(upsert_error, DurableStateError::upsert_revision("pid-1", 0, 1));
+  assert_eq!(delete_error, DurableStateError::delete_revision("pid-1", 3, 1));
+  assert_eq!(get(&store, "pid-1"), (Some(10), 1));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn delete_resets_revision_to_zero() {
+  let directory = unique_state_dir("delete");
+  let mut store = open_store(&directory);
+  upsert(&mut store, "pid-1", 0, 10, None);
+
+  poll_ready(store.delete_object("pid-1", 1)).expect("delete durable state");
+  assert_eq!(get(&store, "pid-1"), (None, 0));
+  upsert(&mut store, "pid-1", 0, 30, None);
+
+  assert_eq!(get(&open_store(&directory), "pid-1"), (Some(30), 1));
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn changes_return_tagged_revisions_in_write_order() {
+  let directory = unique_state_dir("changes");
+  let mut store = open_store(&directory);
+
+  upsert(&mut store, "order-1", 0, 10, Some("orders"));
+  upsert(&mut store, "payment-1", 0, 20, Some("payments"));
+  upsert(&mut store, "order-1", 1, 11, None);
+  upsert(&mut store, "order-2", 0, 30, Some("orders"));
+
+  assert_eq!(changes(&store, "orders"), vec![(1, String::from("order-1"), 1, 10), (3, String::from("order-2"), 1, 30)]);
+  assert_eq!(changes(&store, "payments"), vec![(2, String::from("payment-1"), 1, 20)]);
+  assert!(changes(&store, "unknown").is_empty());
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn reopened_store_recovers_objects_and_change_feed() {
+  let directory = unique_state_dir("reopen");
+  {
+    let mut store = open_store(&directory);
+    upsert(&mut store, "order-1", 0, 10, Some("orders"));
+    upsert(&mut store, "order-1", 1, 11, Some("orders"));
+  }
+
+  let mut reopened = open_store(&directory);
+  upsert(&mut reopened, "order-1", 2, 12, Some("orders"));
+
+  assert_eq!(get(&reopened, "order-1"), (Some(12), 3));
+  assert_eq!(changes(&reopened, "orders").iter().map(|change| change.0).collect::<Vec<_>>(), vec![1, 2, 3]);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn torn_change_log_tail_is_rebuilt_from_the_committed_object() {
+  let directory = unique_state_dir("torn");
+  {
+    let mut store = open_store(&directory);
+    upsert(&mut store, "order-1", 0, 10, Some("orders"));
+    upsert(&mut store, "order-2", 0, 20, Some("orders"));
+  }
+  let change_log = OpenOptions::new().write(true).open(directory.join("changes.log")).expect("open change log");
+  let len = change_log.metadata().expect("change log metadata").len();
+  change_log.set_len(len - 3).expect("tear change log tail");
+  drop(change_log);
+
+  let reopened = open_store(&directory);
+
+  assert_eq!(changes(&reopened, "orders"), vec![
+    (1, String::from("order-1"), 1, 10),
+    (2, String::from("order-2"), 1, 20)
+  ]);
+  remove_dir_if_exists(&directory);
+}
+
+#[test]
+fn unfinished_temp_object_is_discarded_on_open() {
+  let directory = unique_state_dir("temp");
+  {
+    let mut store = open_store(&directory);
+    upsert(&mut store, "pid-1", 0, 10, None);
+  }
+  fs::write(directory.join("state-pid%2D1.tmp"), b"partial").expect("write temp object");
+
+  let reopened = open_store(&directory);
+
+  assert_eq!(get(&reopened, "pid-1"), (Some(10), 1));
+  assert!(!directory.join("state-pid%2D1.tmp").exists());
+  remove_dir_if_exists(&directory);
+}
@@ -0,0 +1,86 @@
+use core::{
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+use std::{
+  env, fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  process,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  builtin, default_serialization_setup, serialization_registry::SerializationRegistry,
+};
+use fraktor_persistence_adaptor_std_rs::state::{LocalDurableStateStore, LocalDurableStateStoreConfig};
+use fraktor_persistence_core_kernel_rs::state::{
+  DurableStateStoreProvider, DurableStateStoreRegistry, DurableStateUpdateStore,
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+const PROVIDER_ID: &str = "local";
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let waker = Waker::noop();
+  let mut cx = Context::from_waker(waker);
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("local durable state store future should be ready"),
+  }
+}
+
+fn serialization_registry() -> ArcShared<SerializationRegistry> {
+  let setup = default_serialization_setup();
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&setup));
+  builtin::register_defaults(&registry, |name, id| panic!("unexpected serializer collision for {name}: {id:?}"))
+    .expect("register builtin serializers");
+  registry
+}
+
+fn unique_state_dir(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  env::temp_dir().join(format!("fraktor-local-durable-state-flow-{name}-{}-{timestamp}", process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("durable state test directory should be removable: {error}"),
+  }
+}
+
+fn open_store(directory: &Path) -> LocalDurableStateStore<String> {
+  let config = LocalDurableStateStoreConfig::new(directory.to_path_buf(), serialization_registry());
+  LocalDurableStateStore::open(config).expect("open local durable state store")
+}
+
+#[test]
+fn registered_local_store_persists_objects_and_feeds_tagged_changes() {
+  let directory = unique_state_dir("registry");
+  let feed = open_store(&directory);
+  let mut registry = DurableStateStoreRegistry::<String>::empty();
+  let provider: ArcShared<dyn DurableStateStoreProvider<String>> = ArcShared::new(feed.clone());
+  registry.register(PROVIDER_ID, provider).expect("register local provider");
+
+  let mut store = registry.resolve(PROVIDER_ID).expect("resolve local provider");
+  poll_ready(store.upsert_object("cart-1", 0, String::from("apple"), Some("carts"))).expect("upsert first revision");
+  poll_ready(store.upsert_object("cart-1", 1, String::from("apple,pear"), Some("carts"))).expect("upsert second");
+
+  let first = poll_ready(feed.changes("carts", 0)).expect("first change").expect("first change exists");
+  let second = poll_ready(feed.changes("carts", first.offset())).expect("second change").expect("second exists");
+  assert_eq!((first.persistence_id(), first.revision(), first.value().as_str()), ("cart-1", 1, "apple"));
+  assert_eq!((second.revision(), second.tag(), second.value().as_str()), (2, "carts", "apple,pear"));
+  assert_eq!(poll_ready(feed.changes("carts", second.offset())).expect("no third change"), None);
+
+  let reopened = open_store(&directory).durable_state_store();
+  let loaded = poll_ready(reopened.get_object("cart-1")).expect("load after reopen");
+  assert_eq!(loaded.value().map(String::as_str), Some("apple,pear"));
+  assert_eq!(loaded.revision(), 2);
+  remove_dir_if_exists(&directory);
+}
@@ -0,0 +1,190 @@
+use std::{
+  env, fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  process, thread,
+  time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext,
+    actor_ref::ActorRef,
+    error::ActorError,
+    extension::ExtensionInstallers,
+    messaging::{AnyMessage, AnyMessageView},
+    props::Props,
+    scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  serialization::{builtin, default_serialization_setup, serialization_registry::SerializationRegistry},
+  system::{ActorSystem, SpinBlocker},
+};
+use fraktor_persistence_adaptor_std_rs::journal::{LocalJournal, LocalJournalConfig};
+use fraktor_persistence_core_kernel_rs::{
+  extension::PersistenceExtensionInstaller,
+  persistent::{Eventsourced, PersistenceContext, PersistentActor, PersistentRepr, persistent_props, spawn_persistent},
+  serialization::register_persistence_serializers,
+  snapshot::{InMemorySnapshotStore, Snapshot},
+};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+type SharedValue = ArcShared<SpinSyncMutex<i32>>;
+type SharedRefs = ArcShared<SpinSyncMutex<Vec<ActorRef>>>;
+
+const PERSISTENCE_ID: &str = "local-journal-counter";
+
+#[derive(Clone)]
+struct AddAll(Vec<i32>);
+
+struct Start;
+
+struct CounterActor {
+  context: PersistenceContext<CounterActor>,
+  value:   SharedValue,
+}
+
+impl CounterActor {
+  fn new(value: SharedValue) -> Self {
+    Self { context: PersistenceContext::new(PERSISTENCE_ID.to_string()), value }
+  }
+
+  fn apply_event(&mut self, event: i32) {
+    *self.value.lock() += event;
+  }
+}
+
+impl Eventsourced for CounterActor {
+  fn persistence_id(&self) -> &str {
+    self.context.persistence_id()
+  }
+
+  fn receive_recover(&mut self, repr: &PersistentRepr) {
+    if let Some(event) = repr.downcast_ref::<i32>() {
+      self.apply_event(*event);
+    }
+  }
+
+  fn receive_snapshot(&mut self, _snapshot: &Snapshot) {}
+
+  fn receive_command(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if let Some(AddAll(events)) = message.downcast_ref::<AddAll>() {
+      self.persist_all(ctx, events.clone(), |actor, event| actor.apply_event(*event));
+      self.flush_batch(ctx)?;
+    }
+    Ok(())
+  }
+
+  fn last_sequence_nr(&self) -> u64 {
+    self.context.last_sequence_nr()
+  }
+}
+
+impl PersistentActor for CounterActor {
+  fn persistence_context(&mut self) -> &mut PersistenceContext<Self> {
+    &mut self.context
+  }
+}
+
+struct Guardian {
+  value:      SharedValue,
+  child_refs: SharedRefs,
+}
+
+impl Actor for Guardian {
+  fn receive(&mut self, ctx: &mut ActorContext<'_>, message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    if message.downcast_ref::<Start>().is_none() {
+      return Ok(());
+    }
+    let value = self.value.clone();
+    let props = persistent_props(move || CounterActor::new(value.clone()));
+    let child =
+      spawn_persistent(ctx, &props).map_err(|error| ActorError::recoverable(format!("spawn failed: {error:?}")))?;
+    self.child_refs.lock().push(child);
+    Ok(())
+  }
+}
+
+fn serialization_registry() -> ArcShared<SerializationRegistry> {
+  let setup = default_serialization_setup();
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&setup));
+  builtin::register_defaults(&registry, |name, id| panic!("unexpected serializer collision for {name}: {id:?}"))
+    .expect("register builtin serializers");
+  register_persistence_serializers(&registry).expect("register persistence serializers");
+  registry
+}
+
+fn unique_journal_dir(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  env::temp_dir().join(format!("fraktor-local-journal-flow-{name}-{}-{timestamp}", process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("journal test directory should be removable: {error}"),
+  }
+}
+
+fn wait_until(mut predicate: impl FnMut() -> bool) -> bool {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while Instant::now() < deadline {
+    if predicate() {
+      return true;
+    }
+    thread::sleep(Duration::from_millis(10));
+  }
+  predicate()
+}
+
+fn start_system(directory: &Path, value: &SharedValue) -> (ActorSystem, ActorRef) {
+  let journal = LocalJournal::open(LocalJournalConfig::new(directory.to_path_buf(), serialization_registry()))
+    .expect("open local journal");
+  let installer = PersistenceExtensionInstaller::new(journal, InMemorySnapshotStore::new());
+  let installers = ExtensionInstallers::default().with_extension_installer(installer);
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(scheduler)
+    .with_extension_installers(installers);
+  let child_refs: SharedRefs = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let props = Props::from_fn({
+    let value = value.clone();
+    let child_refs = child_refs.clone();
+    move || Guardian { value: value.clone(), child_refs: child_refs.clone() }
+  });
+  let system = ActorSystem::create_from_props(&props, config).expect("system");
+  system.user_guardian_ref().tell(AnyMessage::new(Start));
+  assert!(wait_until(|| !child_refs.lock().is_empty()));
+  let child = child_refs.lock().first().cloned().expect("persistent child");
+  (system, child)
+}
+
+fn stop_system(system: &ActorSystem) {
+  system.terminate().expect("terminate");
+  system.run_until_terminated(&SpinBlocker);
+}
+
+#[test]
+fn persistent_actor_recovers_events_from_local_journal_after_system_restart() {
+  let directory = unique_journal_dir("restart");
+
+  let first_value: SharedValue = ArcShared::new(SpinSyncMutex::new(0));
+  let (system, mut child) = start_system(&directory, &first_value);
+  child.tell(AnyMessage::new(AddAll(vec![1, 2, 3])));
+  assert!(wait_until(|| *first_value.lock() == 6));
+  stop_system(&system);
+
+  let recovered_value: SharedValue = ArcShared::new(SpinSyncMutex::new(0));
+  let (system, mut child) = start_system(&directory, &recovered_value);
+  assert!(wait_until(|| *recovered_value.lock() == 6));
+  child.tell(AnyMessage::new(AddAll(vec![4])));
+  assert!(wait_until(|| *recovered_value.lock() == 10));
+  stop_system(&system);
+
+  remove_dir_if_exists(&directory);
+}
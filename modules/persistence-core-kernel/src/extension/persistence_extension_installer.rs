@@ -11,10 +11,14 @@ use fraktor_actor_core_kernel_rs::{
   serialization::contribution::register_serialization_registry_contributor,
   system::{ActorSystem, ActorSystemBuildError},
 };
+use fraktor_utils_core_rs::sync::ArcShared;
 
 use crate::{
-  config::PersistenceConfig, extension::PersistenceExtensionId, journal::Journal,
-  serialization::PersistenceSerializationContributor, snapshot::SnapshotStore,
+  config::PersistenceConfig,
+  extension::PersistenceExtensionId,
+  journal::Journal,
+  serialization::{EventMigrationRegistry, PersistenceSerializationContributor},
+  snapshot::SnapshotStore,
 };
 
 /// Installs the persistence extension into the actor system.
@@ -22,6 +26,7 @@ pub struct PersistenceExtensionInstaller<J, S> {
   journal:        J,
   snapshot_store: S,
   config:         PersistenceConfig,
+  migrations:     Option<ArcShared<EventMigrationRegistry>>,
 }
 
 impl<J, S> PersistenceExtensionInstaller<J, S> {
@@ -34,7 +39,14 @@ impl<J, S> PersistenceExtensionInstaller<J, S> {
   /// Creates a new installer with explicit persistence configuration.
   #[must_use]
   pub const fn new_with_config(journal: J, snapshot_store: S, config: PersistenceConfig) -> Self {
-    Self { journal, snapshot_store, config }
+    Self { journal, snapshot_store, config, migrations: None }
+  }
+
+  /// Applies `migrations` to events and snapshots read through the persistence serializers.
+  #[must_use]
+  pub fn with_event_migrations(mut self, migrations: ArcShared<EventMigrationRegistry>) -> Self {
+    self.migrations = Some(migrations);
+    self
   }
 }
 
@@ -52,9 +64,13 @@ where
   for<'a> S::DeleteManyFuture<'a>: Send + 'static,
 {
   fn install(&self, system: &ActorSystem) -> Result<(), ActorSystemBuildError> {
-    register_serialization_registry_contributor(system, PersistenceSerializationContributor::new()).map_err(
-      |error| ActorSystemBuildError::Configuration(format!("persistence serialization registration failed: {error}")),
-    )?;
+    let mut contributor = PersistenceSerializationContributor::new();
+    if let Some(migrations) = &self.migrations {
+      contributor = contributor.with_migrations(migrations.clone());
+    }
+    register_serialization_registry_contributor(system, contributor).map_err(|error| {
+      ActorSystemBuildError::Configuration(format!("persistence serialization registration failed: {error}"))
+    })?;
     let extension_id =
       PersistenceExtensionId::new_with_config(self.journal.clone(), self.snapshot_store.clone(), self.config);
     install_extension_id(system, &extension_id);
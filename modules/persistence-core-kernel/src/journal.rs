@@ -5,6 +5,7 @@ mod event_adapters;
 mod event_seq;
 mod identity_event_adapter;
 mod in_memory_journal;
+mod in_memory_journal_state;
 mod journal_actor;
 mod journal_actor_config;
 mod journal_error;
@@ -16,6 +17,7 @@ mod persistence_plugin_proxy;
 mod persistence_plugin_proxy_actor;
 mod persistence_plugin_proxy_command;
 mod read_event_adapter;
+mod shared_in_memory_journal;
 mod tagged;
 mod write_event_adapter;
 
@@ -35,5 +37,6 @@ pub use persistence_plugin_proxy::PersistencePluginProxy;
 pub use persistence_plugin_proxy_actor::PersistencePluginProxyActor;
 pub use persistence_plugin_proxy_command::PersistencePluginProxyCommand;
 pub use read_event_adapter::ReadEventAdapter;
+pub use shared_in_memory_journal::SharedInMemoryJournal;
 pub use tagged::Tagged;
 pub use write_event_adapter::WriteEventAdapter;
@@ -33,7 +33,12 @@ pub trait Journal: Send + Sync + 'static {
   /// Writes a batch of atomic write units.
   fn write_messages<'a>(&'a mut self, messages: &'a [AtomicWrite]) -> Self::WriteFuture<'a>;
 
-  /// Replays messages in the requested range.
+  /// Replays one page of messages in the requested range.
+  ///
+  /// Implementations return at most `max` messages (`0` means unlimited) in ascending sequence
+  /// order. Recovery pages through long histories by calling this repeatedly with
+  /// `from_sequence_nr` set past the last returned message, and treats a page shorter than `max`
+  /// as the end of the range, so implementations should load only the requested page.
   fn replay_messages<'a>(
     &'a self,
     persistence_id: &'a str,
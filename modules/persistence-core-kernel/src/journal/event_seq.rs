@@ -64,6 +64,16 @@ impl EventSeq {
     matches!(self, Self::Empty)
   }
 
+  /// Returns the events as a vector without consuming the sequence.
+  #[must_use]
+  pub fn to_events(&self) -> Vec<ArcShared<dyn Any + Send + Sync>> {
+    match self {
+      | EventSeq::Empty => Vec::new(),
+      | EventSeq::Single(event) => vec![event.clone()],
+      | EventSeq::Multiple(events) => events.clone(),
+    }
+  }
+
   /// Consumes the sequence and returns events as a vector.
   #[must_use]
   pub fn into_events(self) -> Vec<ArcShared<dyn Any + Send + Sync>> {
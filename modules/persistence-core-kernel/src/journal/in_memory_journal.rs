@@ -4,34 +4,30 @@
 #[path = "in_memory_journal_test.rs"]
 mod tests;
 
-use alloc::{
-  collections::BTreeMap,
-  string::{String, ToString},
-  vec::Vec,
-};
+use alloc::vec::Vec;
 use core::future::{Ready, ready};
 
+use super::in_memory_journal_state::InMemoryJournalState;
 use crate::{
   journal::{Journal, JournalError},
   persistent::{AtomicWrite, PersistentRepr},
 };
 
 /// In-memory journal implementation.
+///
+/// Clones copy the stored events. Use
+/// [`SharedInMemoryJournal`](super::SharedInMemoryJournal) when a read journal must observe events
+/// written through the journal actor.
 #[derive(Clone, Debug, Default)]
 pub struct InMemoryJournal {
-  entries:              BTreeMap<String, Vec<PersistentRepr>>,
-  highest_sequence_nrs: BTreeMap<String, u64>,
+  state: InMemoryJournalState,
 }
 
 impl InMemoryJournal {
   /// Creates a new in-memory journal.
   #[must_use]
   pub const fn new() -> Self {
-    Self { entries: BTreeMap::new(), highest_sequence_nrs: BTreeMap::new() }
-  }
-
-  fn expected_sequence_nr(&self, persistence_id: &str) -> u64 {
-    self.highest_sequence_nrs.get(persistence_id).copied().unwrap_or(0).saturating_add(1)
+    Self { state: InMemoryJournalState::new() }
   }
 }
 
@@ -54,45 +50,7 @@ impl Journal for InMemoryJournal {
     Self: 'a;
 
   fn write_messages<'a>(&'a mut self, messages: &'a [AtomicWrite]) -> Self::WriteFuture<'a> {
-    let Some(first) = messages.first() else {
-      return ready(Ok(()));
-    };
-
-    let persistence_id = first.persistence_id().to_string();
-
-    if let Some(atomic_write) =
-      messages.iter().skip(1).find(|atomic_write| atomic_write.persistence_id() != persistence_id)
-    {
-      return ready(Err(JournalError::MixedPersistenceId {
-        expected: persistence_id,
-        actual:   atomic_write.persistence_id().to_string(),
-      }));
-    }
-
-    let mut expected = self.expected_sequence_nr(&persistence_id);
-
-    for atomic_write in messages {
-      for message in atomic_write.payload() {
-        if message.sequence_nr() != expected {
-          return ready(Err(JournalError::SequenceMismatch { expected, actual: message.sequence_nr() }));
-        }
-        expected = match expected.checked_add(1) {
-          | Some(next_expected) => next_expected,
-          | None => {
-            return ready(Err(JournalError::WriteFailed(String::from("sequence number overflow in write batch"))));
-          },
-        };
-      }
-    }
-
-    let entry = self.entries.entry(persistence_id.clone()).or_default();
-    for atomic_write in messages {
-      entry.extend(atomic_write.payload().iter().cloned());
-    }
-    // At this point, expected >= 1 and was only incremented via checked_add, so subtraction is safe.
-    self.highest_sequence_nrs.insert(persistence_id, expected - 1);
-
-    ready(Ok(()))
+    ready(self.state.write_messages(messages))
   }
 
   fn replay_messages<'a>(
@@ -102,32 +60,15 @@ impl Journal for InMemoryJournal {
     to_sequence_nr: u64,
     max: u64,
   ) -> Self::ReplayFuture<'a> {
-    let mut result = Vec::new();
-    if let Some(entries) = self.entries.get(persistence_id) {
-      for repr in entries.iter().filter(|repr| {
-        let sequence_nr = repr.sequence_nr();
-        sequence_nr >= from_sequence_nr && sequence_nr <= to_sequence_nr
-      }) {
-        result.push(repr.clone());
-        if max != 0 && result.len() as u64 >= max {
-          break;
-        }
-      }
-    }
-    ready(Ok(result))
+    ready(Ok(self.state.replay_messages(persistence_id, from_sequence_nr, to_sequence_nr, max)))
   }
 
   fn delete_messages_to<'a>(&'a mut self, persistence_id: &'a str, to_sequence_nr: u64) -> Self::DeleteFuture<'a> {
-    if let Some(entries) = self.entries.get_mut(persistence_id) {
-      entries.retain(|repr| repr.sequence_nr() > to_sequence_nr);
-      if entries.is_empty() {
-        self.entries.remove(persistence_id);
-      }
-    }
+    self.state.delete_messages_to(persistence_id, to_sequence_nr);
     ready(Ok(()))
   }
 
   fn highest_sequence_nr<'a>(&'a self, persistence_id: &'a str) -> Self::HighestSeqNrFuture<'a> {
-    ready(Ok(self.highest_sequence_nrs.get(persistence_id).copied().unwrap_or(0)))
+    ready(Ok(self.state.highest_sequence_nr(persistence_id)))
   }
 }
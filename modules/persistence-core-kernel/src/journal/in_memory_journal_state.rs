@@ -0,0 +1,146 @@
+//! Storage of [`InMemoryJournal`](super::InMemoryJournal) and
+//! [`SharedInMemoryJournal`](super::SharedInMemoryJournal).
+
+use alloc::{
+  collections::BTreeMap,
+  string::{String, ToString},
+  vec::Vec,
+};
+
+use crate::{
+  journal::JournalError,
+  persistent::{AtomicWrite, PersistentRepr},
+};
+
+/// Events, highest sequence numbers and the journal-wide write order.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct InMemoryJournalState {
+  entries: BTreeMap<String, Vec<PersistentRepr>>,
+  pub(super) highest_sequence_nrs: BTreeMap<String, u64>,
+  /// `(persistence_id, sequence_nr)` in write order; the ordering of an event is its index plus
+  /// one.
+  event_log: Vec<(String, u64)>,
+}
+
+impl InMemoryJournalState {
+  pub(crate) const fn new() -> Self {
+    Self {
+      entries:              BTreeMap::new(),
+      highest_sequence_nrs: BTreeMap::new(),
+      event_log:            Vec::new(),
+    }
+  }
+
+  fn expected_sequence_nr(&self, persistence_id: &str) -> u64 {
+    self.highest_sequence_nrs.get(persistence_id).copied().unwrap_or(0).saturating_add(1)
+  }
+
+  pub(crate) fn write_messages(&mut self, messages: &[AtomicWrite]) -> Result<(), JournalError> {
+    let Some(first) = messages.first() else {
+      return Ok(());
+    };
+
+    let persistence_id = first.persistence_id().to_string();
+
+    if let Some(atomic_write) =
+      messages.iter().skip(1).find(|atomic_write| atomic_write.persistence_id() != persistence_id)
+    {
+      return Err(JournalError::MixedPersistenceId {
+        expected: persistence_id,
+        actual:   atomic_write.persistence_id().to_string(),
+      });
+    }
+
+    let mut expected = self.expected_sequence_nr(&persistence_id);
+
+    for atomic_write in messages {
+      for message in atomic_write.payload() {
+        if message.sequence_nr() != expected {
+          return Err(JournalError::SequenceMismatch { expected, actual: message.sequence_nr() });
+        }
+        expected = match expected.checked_add(1) {
+          | Some(next_expected) => next_expected,
+          | None => {
+            return Err(JournalError::WriteFailed(String::from("sequence number overflow in write batch")));
+          },
+        };
+      }
+    }
+
+    let entry = self.entries.entry(persistence_id.clone()).or_default();
+    for atomic_write in messages {
+      for repr in atomic_write.payload() {
+        self.event_log.push((persistence_id.clone(), repr.sequence_nr()));
+        entry.push(repr.clone());
+      }
+    }
+    // At this point, expected >= 1 and was only incremented via checked_add, so subtraction is safe.
+    self.highest_sequence_nrs.insert(persistence_id, expected - 1);
+
+    Ok(())
+  }
+
+  pub(crate) fn replay_messages(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    max: u64,
+  ) -> Vec<PersistentRepr> {
+    let mut result = Vec::new();
+    if let Some(entries) = self.entries.get(persistence_id) {
+      // エントリは sequence_nr 昇順で追記されるため、ページ先頭まで二分探索で飛ばす。
+      let start = entries.partition_point(|repr| repr.sequence_nr() < from_sequence_nr);
+      for repr in entries[start..].iter().take_while(|repr| repr.sequence_nr() <= to_sequence_nr) {
+        result.push(repr.clone());
+        if max != 0 && result.len() as u64 >= max {
+          break;
+        }
+      }
+    }
+    result
+  }
+
+  pub(crate) fn events_by_ordering(&self, after_ordering: u64, max: u64) -> Vec<(u64, PersistentRepr)> {
+    let mut result = Vec::new();
+    let start = usize::try_from(after_ordering).unwrap_or(usize::MAX).min(self.event_log.len());
+    for (index, (persistence_id, sequence_nr)) in self.event_log.iter().enumerate().skip(start) {
+      if max != 0 && result.len() as u64 >= max {
+        break;
+      }
+      let Some(repr) = self.find_event(persistence_id, *sequence_nr) else {
+        // 削除済みのイベントは順序番号だけを消費する。
+        continue;
+      };
+      result.push((index as u64 + 1, repr.clone()));
+    }
+    result
+  }
+
+  pub(crate) const fn highest_ordering(&self) -> u64 {
+    self.event_log.len() as u64
+  }
+
+  fn find_event(&self, persistence_id: &str, sequence_nr: u64) -> Option<&PersistentRepr> {
+    let entries = self.entries.get(persistence_id)?;
+    let index = entries.binary_search_by_key(&sequence_nr, PersistentRepr::sequence_nr).ok()?;
+    entries.get(index)
+  }
+
+  pub(crate) fn persistence_ids(&self) -> Vec<String> {
+    self.highest_sequence_nrs.keys().cloned().collect()
+  }
+
+  pub(crate) fn delete_messages_to(&mut self, persistence_id: &str, to_sequence_nr: u64) {
+    if let Some(entries) = self.entries.get_mut(persistence_id) {
+      entries.retain(|repr| repr.sequence_nr() > to_sequence_nr);
+      if entries.is_empty() {
+        self.entries.remove(persistence_id);
+      }
+    }
+  }
+
+  pub(crate) fn highest_sequence_nr(&self, persistence_id: &str) -> u64 {
+    self.highest_sequence_nrs.get(persistence_id).copied().unwrap_or(0)
+  }
+}
@@ -127,7 +127,7 @@ fn in_memory_journal_sequence_mismatch() {
 #[test]
 fn in_memory_journal_rejects_sequence_overflow_without_partial_persistence() {
   let mut journal = InMemoryJournal::new();
-  journal.highest_sequence_nrs.insert("pid-1".into(), u64::MAX - 1);
+  journal.state.highest_sequence_nrs.insert("pid-1".into(), u64::MAX - 1);
   let messages = build_messages("pid-1", u64::MAX, 1);
 
   let result = poll_ready(journal.write_messages(&[atomic_write(messages)]));
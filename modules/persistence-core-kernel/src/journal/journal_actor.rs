@@ -41,6 +41,15 @@ struct JournalReplayRequest {
   from_sequence_nr: u64,
   to_sequence_nr:   u64,
   max:              u64,
+  page_max:         u64,
+}
+
+impl JournalReplayRequest {
+  /// Bounds one replay page by the configured page size and the remaining replay budget.
+  const fn new(from_sequence_nr: u64, to_sequence_nr: u64, max: u64, page_size: u64) -> Self {
+    let page_max = if max != 0 && max < page_size { max } else { page_size };
+    Self { from_sequence_nr, to_sequence_nr, max, page_max }
+  }
 }
 
 enum JournalInFlight {
@@ -52,13 +61,11 @@ enum JournalInFlight {
     retry_count: u32,
   },
   Replay {
-    future:           JournalReplayFuture,
-    sender:           ActorRef,
-    persistence_id:   String,
-    from_sequence_nr: u64,
-    to_sequence_nr:   u64,
-    max:              u64,
-    retry_count:      u32,
+    future:         JournalReplayFuture,
+    sender:         ActorRef,
+    persistence_id: String,
+    request:        JournalReplayRequest,
+    retry_count:    u32,
   },
   Delete {
     future:         JournalDeleteFuture,
@@ -76,6 +83,9 @@ enum JournalInFlight {
 }
 
 /// Actor wrapper around a journal implementation.
+///
+/// Replays are served one page of at most [`JournalActorConfig::replay_page_size`] events at a
+/// time, so recovering a long history never holds more than one page in memory.
 pub struct JournalActor<J: Journal> {
   journal:        J,
   in_flight:      Vec<JournalInFlight>,
@@ -179,14 +189,19 @@ where
           });
         },
         | JournalMessage::ReplayMessages { persistence_id, from_sequence_nr, to_sequence_nr, max, sender } => {
-          let future = Box::pin(self.journal.replay_messages(persistence_id, *from_sequence_nr, *to_sequence_nr, *max));
+          let request =
+            JournalReplayRequest::new(*from_sequence_nr, *to_sequence_nr, *max, self.config.replay_page_size());
+          let future = Box::pin(self.journal.replay_messages(
+            persistence_id,
+            request.from_sequence_nr,
+            request.to_sequence_nr,
+            request.page_max,
+          ));
           self.in_flight.push(JournalInFlight::Replay {
             future,
             sender: sender.clone(),
             persistence_id: persistence_id.clone(),
-            from_sequence_nr: *from_sequence_nr,
-            to_sequence_nr: *to_sequence_nr,
-            max: *max,
+            request,
             retry_count: 0,
           });
         },
@@ -234,21 +249,8 @@ where
     | JournalInFlight::Write { future, messages, sender, instance_id, retry_count } => {
       poll_write_entry(&mut poll_context, cx, future, messages, sender, *instance_id, retry_count)
     },
-    | JournalInFlight::Replay {
-      future,
-      sender,
-      persistence_id,
-      from_sequence_nr,
-      to_sequence_nr,
-      max,
-      retry_count,
-    } => {
-      let request = JournalReplayRequest {
-        from_sequence_nr: *from_sequence_nr,
-        to_sequence_nr:   *to_sequence_nr,
-        max:              *max,
-      };
-      poll_replay_entry(&mut poll_context, cx, future, sender, persistence_id, request, retry_count)
+    | JournalInFlight::Replay { future, sender, persistence_id, request, retry_count } => {
+      poll_replay_entry(&mut poll_context, cx, future, sender, persistence_id, *request, retry_count)
     },
     | JournalInFlight::Delete { future, sender, persistence_id, to_sequence_nr, retry_count } => {
       poll_delete_entry(&mut poll_context, cx, future, sender, persistence_id, *to_sequence_nr, retry_count)
@@ -341,7 +343,7 @@ where
   for<'a> J::ReplayFuture<'a>: Send + 'static, {
   match Future::poll(future.as_mut(), cx) {
     | Poll::Ready(Ok(messages)) => {
-      send_replay_success(sender, &messages);
+      send_replay_page(sender, &messages, request);
       false
     },
     | Poll::Ready(Err(error)) => {
@@ -351,7 +353,7 @@ where
   }
 }
 
-fn send_replay_success(sender: &mut ActorRef, messages: &[PersistentRepr]) {
+fn send_replay_page(sender: &mut ActorRef, messages: &[PersistentRepr], request: JournalReplayRequest) {
   let mut highest = 0;
   for repr in messages.iter().cloned() {
     highest = repr.sequence_nr();
@@ -360,7 +362,20 @@ fn send_replay_success(sender: &mut ActorRef, messages: &[PersistentRepr]) {
     }
     tell_response(sender, JournalResponse::ReplayedMessage { persistent_repr: repr });
   }
-  tell_response(sender, JournalResponse::RecoverySuccess { highest_sequence_nr: highest });
+  let delivered = messages.len() as u64;
+  let remaining_max = if request.max == 0 { 0 } else { request.max.saturating_sub(delivered) };
+  let exhausted =
+    delivered < request.page_max || highest >= request.to_sequence_nr || (request.max != 0 && remaining_max == 0);
+  if exhausted {
+    tell_response(sender, JournalResponse::RecoverySuccess { highest_sequence_nr: highest });
+    return;
+  }
+  // 次ページの要求は回復中のアクターが直前のイベントを適用し終えてから送る (back-pressure)。
+  tell_response(sender, JournalResponse::ReplayPageCompleted {
+    next_from_sequence_nr: highest.saturating_add(1),
+    to_sequence_nr: request.to_sequence_nr,
+    remaining_max,
+  });
 }
 
 fn retry_or_fail_replay<J: Journal>(
@@ -380,7 +395,7 @@ where
       persistence_id,
       request.from_sequence_nr,
       request.to_sequence_nr,
-      request.max,
+      request.page_max,
     ));
     return true;
   }
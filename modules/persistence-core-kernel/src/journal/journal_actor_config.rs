@@ -1,16 +1,19 @@
 //! Journal actor configuration.
 
-/// Configuration for journal actor retry behavior.
+const DEFAULT_REPLAY_PAGE_SIZE: u64 = 512;
+
+/// Configuration for journal actor retry and replay paging behavior.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct JournalActorConfig {
-  retry_max: u32,
+  retry_max:        u32,
+  replay_page_size: u64,
 }
 
 impl JournalActorConfig {
   /// Creates a new configuration with the provided retry limit.
   #[must_use]
   pub const fn new(retry_max: u32) -> Self {
-    Self { retry_max }
+    Self { retry_max, replay_page_size: DEFAULT_REPLAY_PAGE_SIZE }
   }
 
   /// Returns the maximum number of retry polls allowed.
@@ -26,8 +29,23 @@ impl JournalActorConfig {
     self
   }
 
+  /// Returns the maximum number of events loaded from the journal per replay page.
+  #[must_use]
+  pub const fn replay_page_size(&self) -> u64 {
+    self.replay_page_size
+  }
+
+  /// Updates the replay page size.
+  ///
+  /// A page size of zero is treated as one event per page.
+  #[must_use]
+  pub const fn with_replay_page_size(mut self, replay_page_size: u64) -> Self {
+    self.replay_page_size = if replay_page_size == 0 { 1 } else { replay_page_size };
+    self
+  }
+
   pub(crate) const fn default_config() -> Self {
-    Self { retry_max: 1 }
+    Self::new(1)
   }
 }
 
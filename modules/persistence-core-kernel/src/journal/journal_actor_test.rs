@@ -776,3 +776,113 @@ fn journal_actor_failure_responses_to_null_sender_do_not_fail() {
   let any_message = AnyMessage::new(highest);
   actor.receive(&mut ctx, any_message.as_view()).expect("highest receive failed");
 }
+
+fn replay_responses(store: &MessageStore) -> Vec<JournalResponse> {
+  let responses = store
+    .lock()
+    .iter()
+    .map(|message| message.payload().downcast_ref::<JournalResponse>().expect("unexpected payload").clone())
+    .collect();
+  store.lock().clear();
+  responses
+}
+
+fn replayed_sequence_nrs(responses: &[JournalResponse]) -> Vec<u64> {
+  responses
+    .iter()
+    .filter_map(|response| match response {
+      | JournalResponse::ReplayedMessage { persistent_repr } => Some(persistent_repr.sequence_nr()),
+      | _ => None,
+    })
+    .collect()
+}
+
+fn paged_actor_with_events(
+  ctx: &mut ActorContext<'_>,
+  page_size: u64,
+  count: u64,
+  sender: &ActorRef,
+  store: &MessageStore,
+) -> JournalActor<InMemoryJournal> {
+  let config = JournalActorConfig::default().with_replay_page_size(page_size);
+  let mut actor = JournalActor::<InMemoryJournal>::new_with_config(InMemoryJournal::new(), config);
+  let reprs = (1..=count).map(|sequence_nr| PersistentRepr::new("pid-1", sequence_nr, ArcShared::new(1_i32))).collect();
+  let write = JournalMessage::WriteMessages {
+    persistence_id: "pid-1".into(),
+    to_sequence_nr: count,
+    messages:       vec![atomic_write(reprs)],
+    sender:         sender.clone(),
+    instance_id:    1,
+  };
+  actor.receive(ctx, AnyMessage::new(write).as_view()).expect("write receive failed");
+  store.lock().clear();
+  actor
+}
+
+fn replay(
+  actor: &mut JournalActor<InMemoryJournal>,
+  ctx: &mut ActorContext<'_>,
+  sender: &ActorRef,
+  from_sequence_nr: u64,
+  max: u64,
+) {
+  let replay = JournalMessage::ReplayMessages {
+    persistence_id: "pid-1".into(),
+    from_sequence_nr,
+    to_sequence_nr: u64::MAX,
+    max,
+    sender: sender.clone(),
+  };
+  actor.receive(ctx, AnyMessage::new(replay).as_view()).expect("replay receive failed");
+}
+
+#[test]
+fn journal_actor_replays_long_history_in_pages() {
+  let system = new_test_system();
+  let mut ctx = ActorContext::new(&system, test_actor_pid());
+  let (sender, store) = create_sender();
+  let mut actor = paged_actor_with_events(&mut ctx, 2, 5, &sender, &store);
+
+  replay(&mut actor, &mut ctx, &sender, 1, 0);
+  let responses = replay_responses(&store);
+  assert_eq!(replayed_sequence_nrs(&responses), vec![1, 2]);
+  assert!(matches!(
+    responses.last(),
+    Some(JournalResponse::ReplayPageCompleted {
+      next_from_sequence_nr: 3,
+      to_sequence_nr:        u64::MAX,
+      remaining_max:         0,
+    })
+  ));
+
+  replay(&mut actor, &mut ctx, &sender, 3, 0);
+  let responses = replay_responses(&store);
+  assert_eq!(replayed_sequence_nrs(&responses), vec![3, 4]);
+  assert!(matches!(responses.last(), Some(JournalResponse::ReplayPageCompleted { next_from_sequence_nr: 5, .. })));
+
+  replay(&mut actor, &mut ctx, &sender, 5, 0);
+  let responses = replay_responses(&store);
+  assert_eq!(replayed_sequence_nrs(&responses), vec![5]);
+  assert!(matches!(responses.last(), Some(JournalResponse::RecoverySuccess { highest_sequence_nr: 5 })));
+}
+
+#[test]
+fn journal_actor_paged_replay_honours_remaining_max() {
+  let system = new_test_system();
+  let mut ctx = ActorContext::new(&system, test_actor_pid());
+  let (sender, store) = create_sender();
+  let mut actor = paged_actor_with_events(&mut ctx, 2, 5, &sender, &store);
+
+  replay(&mut actor, &mut ctx, &sender, 1, 3);
+  let responses = replay_responses(&store);
+  assert_eq!(replayed_sequence_nrs(&responses), vec![1, 2]);
+  assert!(matches!(
+    responses.last(),
+    Some(JournalResponse::ReplayPageCompleted { next_from_sequence_nr: 3, remaining_max: 1, .. })
+  ));
+
+  replay(&mut actor, &mut ctx, &sender, 3, 1);
+  let responses = replay_responses(&store);
+  assert_eq!(replayed_sequence_nrs(&responses), vec![3]);
+  assert!(matches!(responses.last(), Some(JournalResponse::RecoverySuccess { highest_sequence_nr: 3 })));
+}
@@ -55,6 +55,19 @@ pub enum JournalResponse {
     /// Replayed representation.
     persistent_repr: PersistentRepr,
   },
+  /// One replay page was delivered and more events may follow.
+  ///
+  /// The recovering actor requests the next page by sending
+  /// [`JournalMessage::ReplayMessages`](crate::journal::JournalMessage::ReplayMessages) with the
+  /// carried bounds once it has applied the preceding events.
+  ReplayPageCompleted {
+    /// Sequence number the next page starts from.
+    next_from_sequence_nr: u64,
+    /// Ending sequence number of the overall replay.
+    to_sequence_nr:        u64,
+    /// Remaining number of messages to replay, or `0` when unlimited.
+    remaining_max:         u64,
+  },
   /// Recovery completed with highest sequence number.
   RecoverySuccess {
     /// Highest sequence number after recovery.
@@ -35,6 +35,11 @@ fn journal_response_variants_hold_data() {
     instance_id: 9,
   };
   let replayed = JournalResponse::ReplayedMessage { persistent_repr: repr2.clone() };
+  let page = JournalResponse::ReplayPageCompleted {
+    next_from_sequence_nr: 5,
+    to_sequence_nr:        9,
+    remaining_max:         0,
+  };
   let recovery = JournalResponse::RecoverySuccess { highest_sequence_nr: 9 };
   let highest = JournalResponse::HighestSequenceNr { persistence_id: "pid-1".into(), sequence_nr: 9 };
   let highest_failed = JournalResponse::HighestSequenceNrFailure {
@@ -90,6 +95,15 @@ fn journal_response_variants_hold_data() {
     | _ => panic!("unexpected variant"),
   }
 
+  match page {
+    | JournalResponse::ReplayPageCompleted { next_from_sequence_nr, to_sequence_nr, remaining_max } => {
+      assert_eq!(next_from_sequence_nr, 5);
+      assert_eq!(to_sequence_nr, 9);
+      assert_eq!(remaining_max, 0);
+    },
+    | _ => panic!("unexpected variant"),
+  }
+
   match recovery {
     | JournalResponse::RecoverySuccess { highest_sequence_nr } => assert_eq!(highest_sequence_nr, 9),
     | _ => panic!("unexpected variant"),
@@ -0,0 +1,121 @@
+//! In-memory journal handle sharing its storage across clones.
+
+#[cfg(test)]
+#[path = "shared_in_memory_journal_test.rs"]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use core::{
+  fmt::{Debug, Formatter, Result as FmtResult},
+  future::{Ready, ready},
+};
+
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedLock};
+
+use super::in_memory_journal_state::InMemoryJournalState;
+use crate::{
+  journal::{Journal, JournalError},
+  persistent::{AtomicWrite, PersistentRepr},
+  query::QueryableJournal,
+};
+
+/// In-memory journal whose clones share the same storage.
+///
+/// Hand one clone to the persistence extension and build a read journal from another, so queries
+/// observe events written through the journal actor.
+#[derive(Clone)]
+pub struct SharedInMemoryJournal {
+  state: SharedLock<InMemoryJournalState>,
+}
+
+impl SharedInMemoryJournal {
+  /// Creates an empty shared in-memory journal.
+  #[must_use]
+  pub fn new() -> Self {
+    Self { state: SharedLock::new_with_driver::<DefaultMutex<_>>(InMemoryJournalState::new()) }
+  }
+}
+
+impl Default for SharedInMemoryJournal {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Debug for SharedInMemoryJournal {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    self.state.with_lock(|state| f.debug_struct("SharedInMemoryJournal").field("state", state).finish())
+  }
+}
+
+impl Journal for SharedInMemoryJournal {
+  type DeleteFuture<'a>
+    = Ready<Result<(), JournalError>>
+  where
+    Self: 'a;
+  type HighestSeqNrFuture<'a>
+    = Ready<Result<u64, JournalError>>
+  where
+    Self: 'a;
+  type ReplayFuture<'a>
+    = Ready<Result<Vec<PersistentRepr>, JournalError>>
+  where
+    Self: 'a;
+  type WriteFuture<'a>
+    = Ready<Result<(), JournalError>>
+  where
+    Self: 'a;
+
+  fn write_messages<'a>(&'a mut self, messages: &'a [AtomicWrite]) -> Self::WriteFuture<'a> {
+    ready(self.state.with_lock(|state| state.write_messages(messages)))
+  }
+
+  fn replay_messages<'a>(
+    &'a self,
+    persistence_id: &'a str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    max: u64,
+  ) -> Self::ReplayFuture<'a> {
+    ready(Ok(
+      self.state.with_lock(|state| state.replay_messages(persistence_id, from_sequence_nr, to_sequence_nr, max)),
+    ))
+  }
+
+  fn delete_messages_to<'a>(&'a mut self, persistence_id: &'a str, to_sequence_nr: u64) -> Self::DeleteFuture<'a> {
+    self.state.with_lock(|state| state.delete_messages_to(persistence_id, to_sequence_nr));
+    ready(Ok(()))
+  }
+
+  fn highest_sequence_nr<'a>(&'a self, persistence_id: &'a str) -> Self::HighestSeqNrFuture<'a> {
+    ready(Ok(self.state.with_lock(|state| state.highest_sequence_nr(persistence_id))))
+  }
+}
+
+impl QueryableJournal for SharedInMemoryJournal {
+  fn events_by_persistence_id(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    max: u64,
+  ) -> Result<Vec<PersistentRepr>, JournalError> {
+    Ok(self.state.with_lock(|state| state.replay_messages(persistence_id, from_sequence_nr, to_sequence_nr, max)))
+  }
+
+  fn events_by_ordering(&self, after_ordering: u64, max: u64) -> Result<Vec<(u64, PersistentRepr)>, JournalError> {
+    Ok(self.state.with_lock(|state| state.events_by_ordering(after_ordering, max)))
+  }
+
+  fn highest_ordering(&self) -> Result<u64, JournalError> {
+    Ok(self.state.with_lock(|state| state.highest_ordering()))
+  }
+
+  fn highest_event_sequence_nr(&self, persistence_id: &str) -> Result<u64, JournalError> {
+    Ok(self.state.with_lock(|state| state.highest_sequence_nr(persistence_id)))
+  }
+
+  fn persistence_ids(&self) -> Result<Vec<String>, JournalError> {
+    Ok(self.state.with_lock(|state| state.persistence_ids()))
+  }
+}
@@ -0,0 +1,80 @@
+use alloc::{boxed::Box, format, vec::Vec};
+use core::{
+  any::Any,
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{
+  journal::{InMemoryJournal, Journal, SharedInMemoryJournal},
+  persistent::{AtomicWrite, PersistentRepr},
+  query::QueryableJournal,
+};
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let waker = Waker::noop();
+  let mut cx = Context::from_waker(waker);
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was pending"),
+  }
+}
+
+fn atomic_write(persistence_id: &str, start: u64, count: u64) -> AtomicWrite {
+  let payload: Vec<PersistentRepr> = (0..count)
+    .map(|offset| {
+      let payload: ArcShared<dyn Any + Send + Sync> = ArcShared::new((start + offset) as i32);
+      PersistentRepr::new(persistence_id, start + offset, payload)
+    })
+    .collect();
+  AtomicWrite::new(payload).expect("atomic write creation failed")
+}
+
+#[test]
+fn clones_share_written_events() {
+  let mut writer = SharedInMemoryJournal::new();
+  let reader = writer.clone();
+
+  poll_ready(writer.write_messages(&[atomic_write("pid-1", 1, 2)])).expect("write");
+
+  let replayed = poll_ready(reader.replay_messages("pid-1", 1, u64::MAX, 0)).expect("replay");
+  assert_eq!(replayed.len(), 2);
+  assert_eq!(reader.highest_event_sequence_nr("pid-1"), Ok(2));
+  assert_eq!(reader.highest_ordering(), Ok(2));
+}
+
+#[test]
+fn in_memory_journal_clones_keep_value_semantics() {
+  let mut original = InMemoryJournal::new();
+  let copy = original.clone();
+
+  poll_ready(original.write_messages(&[atomic_write("pid-1", 1, 1)])).expect("write");
+
+  assert_eq!(poll_ready(copy.highest_sequence_nr("pid-1")), Ok(0));
+  assert_eq!(poll_ready(original.highest_sequence_nr("pid-1")), Ok(1));
+}
+
+#[test]
+fn events_by_ordering_skips_deleted_events_but_keeps_their_ordering() {
+  let mut journal = SharedInMemoryJournal::new();
+  poll_ready(journal.write_messages(&[atomic_write("pid-1", 1, 2)])).expect("write");
+  poll_ready(journal.write_messages(&[atomic_write("pid-2", 1, 1)])).expect("write");
+  poll_ready(journal.delete_messages_to("pid-1", 1)).expect("delete");
+
+  let orderings: Vec<u64> =
+    journal.events_by_ordering(0, 0).expect("events").into_iter().map(|(ordering, _)| ordering).collect();
+
+  assert_eq!(orderings, [2, 3]);
+  assert_eq!(journal.persistence_ids().expect("ids"), ["pid-1", "pid-2"]);
+}
+
+#[test]
+fn debug_output_includes_stored_state() {
+  let mut journal = SharedInMemoryJournal::new();
+  poll_ready(journal.write_messages(&[atomic_write("pid-1", 1, 1)])).expect("write");
+
+  assert!(format!("{journal:?}").contains("pid-1"));
+}
@@ -53,7 +53,7 @@
 //! Persistence support for the fraktor actor runtime.
 //!
 //! This crate provides event journal, snapshot, persistent actor, durable state,
-//! delivery, FSM, and extension packages.
+//! delivery, FSM, query, projection, replication, and extension packages.
 
 extern crate alloc;
 
@@ -65,6 +65,9 @@ pub mod fsm;
 pub mod journal;
 pub mod persistent;
 mod plugin_message_handling;
+pub mod projection;
+pub mod query;
+pub mod replication;
 pub mod serialization;
 pub mod snapshot;
 pub mod state;
@@ -6,7 +6,7 @@ mod tests;
 
 use alloc::{boxed::Box, collections::VecDeque, format, string::String, vec, vec::Vec};
 use core::{
-  any::Any,
+  any::{Any, TypeId},
   ops::Deref,
   sync::atomic::{AtomicU32, Ordering},
 };
@@ -16,7 +16,7 @@ use fraktor_utils_core_rs::sync::ArcShared;
 
 use crate::{
   error::PersistenceError,
-  journal::{EventAdapters, JournalError, JournalMessage, JournalResponse, JournalResponseAction},
+  journal::{EventAdapters, EventSeq, JournalError, JournalMessage, JournalResponse, JournalResponseAction},
   persistent::{
     AtomicWrite, AtomicWriteError, PendingHandlerInvocation, PersistentActorState, PersistentEnvelope, PersistentRepr,
     Recovery,
@@ -47,6 +47,7 @@ pub struct PersistenceContext<A: 'static> {
   event_adapters: EventAdapters,
   journal_actor_ref: ActorRef,
   snapshot_actor_ref: ActorRef,
+  replay_sender: ActorRef,
 }
 
 impl<A: 'static> PersistenceContext<A> {
@@ -66,6 +67,7 @@ impl<A: 'static> PersistenceContext<A> {
       event_adapters: EventAdapters::new(),
       journal_actor_ref: ActorRef::null(),
       snapshot_actor_ref: ActorRef::null(),
+      replay_sender: ActorRef::null(),
     }
   }
 
@@ -144,6 +146,24 @@ impl<A: 'static> PersistenceContext<A> {
     self.event_batch.push(EventBatchEntry::Persistent(envelope));
   }
 
+  /// Adds an event with journal metadata to the current batch.
+  ///
+  /// The metadata is stored next to the event and returned by [`PersistentRepr::metadata`] during
+  /// replay and queries.
+  pub fn add_to_event_batch_with_metadata<E: Any + Send + Sync + 'static>(
+    &mut self,
+    event: E,
+    metadata: ArcShared<dyn Any + Send + Sync>,
+    stashing: bool,
+    sender: Option<Pid>,
+    handler: PendingHandler<A>,
+  ) {
+    self.current_sequence_nr = self.current_sequence_nr.saturating_add(1);
+    let envelope = PersistentEnvelope::new(ArcShared::new(event), self.current_sequence_nr, handler, stashing, sender)
+      .with_metadata(metadata);
+    self.event_batch.push(EventBatchEntry::Persistent(envelope));
+  }
+
   /// Adds a deferred handler invocation executed after successful batch persistence.
   pub fn add_deferred_handler<E: Any + Send + Sync + 'static>(
     &mut self,
@@ -317,6 +337,23 @@ impl<A: 'static> PersistenceContext<A> {
           | _ => JournalResponseAction::ReceiveRecoverMany(replayed_reprs),
         }
       },
+      | JournalResponse::ReplayPageCompleted { next_from_sequence_nr, to_sequence_nr, remaining_max } => {
+        if self.state != PersistentActorState::Recovering {
+          return JournalResponseAction::None;
+        }
+        self.current_sequence_nr = self.current_sequence_nr.max(next_from_sequence_nr.saturating_sub(1));
+        let message = JournalMessage::ReplayMessages {
+          persistence_id:   self.persistence_id.clone(),
+          from_sequence_nr: *next_from_sequence_nr,
+          to_sequence_nr:   *to_sequence_nr,
+          max:              *remaining_max,
+          sender:           self.replay_sender.clone(),
+        };
+        match self.send_write_messages(message) {
+          | Ok(()) => JournalResponseAction::None,
+          | Err(error) => JournalResponseAction::RecoveryFailure(error),
+        }
+      },
       | JournalResponse::RecoverySuccess { highest_sequence_nr } => {
         let highest = (*highest_sequence_nr).max(self.current_sequence_nr).max(self.last_sequence_nr);
         self.last_sequence_nr = highest;
@@ -383,6 +420,7 @@ impl<A: 'static> PersistenceContext<A> {
           self.state = state;
         }
         let recovery = self.recovery.clone();
+        self.replay_sender = sender.clone();
         let from_sequence_nr =
           snapshot.as_ref().map(|snap| snap.metadata().sequence_nr().saturating_add(1)).unwrap_or(0);
         let message = JournalMessage::ReplayMessages {
@@ -407,6 +445,7 @@ impl<A: 'static> PersistenceContext<A> {
           self.state = state;
         }
         let recovery = self.recovery.clone();
+        self.replay_sender = sender.clone();
         let message = JournalMessage::ReplayMessages {
           persistence_id: self.persistence_id.clone(),
           from_sequence_nr: 0,
@@ -566,6 +605,24 @@ impl<A: 'static> PersistenceContext<A> {
   }
 
   fn replayed_from_journal_repr(&self, repr: &PersistentRepr) -> Vec<PersistentRepr> {
+    // スキーマ移行で分割・マージされたレコードは EventSeq を運ぶため、イベントごとにアダプタを通す。
+    let Some(sequence) = repr.downcast_ref::<EventSeq>() else {
+      return self.adapted_from_journal_repr(repr);
+    };
+    let sequence_type_id = TypeId::of::<EventSeq>();
+    sequence
+      .to_events()
+      .into_iter()
+      .flat_map(|event| {
+        let adapter_type_id =
+          if repr.adapter_type_id() == sequence_type_id { event.deref().type_id() } else { repr.adapter_type_id() };
+        let event_repr = Self::repr_with_payload(repr, event).with_adapter_type_id(adapter_type_id);
+        self.adapted_from_journal_repr(&event_repr)
+      })
+      .collect()
+  }
+
+  fn adapted_from_journal_repr(&self, repr: &PersistentRepr) -> Vec<PersistentRepr> {
     let adapters = self.select_adapters_for_replay(repr);
     let repr_with_adapters = repr.clone().with_adapters(adapters);
     let adapter_type_id = repr_with_adapters.adapter_type_id();
@@ -596,7 +653,8 @@ impl<A: 'static> PersistenceContext<A> {
       .with_deleted(repr.deleted())
       .with_sender(repr.sender())
       .with_adapters(repr.adapters().clone())
-      .with_adapter_type_id(repr.adapter_type_id());
+      .with_adapter_type_id(repr.adapter_type_id())
+      .with_applied_migrations(repr.applied_migrations().to_vec());
     if let Some(metadata) = repr.metadata() {
       return updated.with_metadata(metadata.clone());
     }
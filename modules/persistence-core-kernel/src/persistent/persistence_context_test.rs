@@ -25,6 +25,7 @@ use crate::{
     AtomicWrite, AtomicWriteError, Eventsourced, PendingHandlerInvocation, PersistenceContext, PersistentActorState,
     PersistentRepr, Recovery,
   },
+  serialization::AppliedMigration,
   snapshot::{Snapshot, SnapshotMessage, SnapshotSelectionCriteria},
 };
 
@@ -483,6 +484,31 @@ fn replayed_message_returns_none_for_empty_event_seq() {
   assert_eq!(context.current_sequence_nr(), 4);
 }
 
+#[test]
+fn replayed_message_expands_migrated_event_seq_payloads() {
+  let mut context = DummyContext::new("pid-1".to_string());
+  let applied = vec![AppliedMigration::Upcast {
+    from: "counter.AddedTwice#1".to_string(),
+    to:   vec!["counter.Added#2".to_string(), "counter.Added#2".to_string()],
+  }];
+  let events: ArcShared<dyn Any + Send + Sync> =
+    ArcShared::new(EventSeq::multiple(vec![ArcShared::new(7_i32), ArcShared::new(8_i32)]));
+  let replay_repr = PersistentRepr::new("pid-1", 5, events).with_applied_migrations(applied.clone());
+
+  let replay_action =
+    context.handle_journal_response(&JournalResponse::ReplayedMessage { persistent_repr: replay_repr });
+
+  match replay_action {
+    | JournalResponseAction::ReceiveRecoverMany(reprs) => {
+      let values = reprs.iter().map(|repr| *repr.downcast_ref::<i32>().expect("i32 event")).collect::<Vec<_>>();
+      assert_eq!(values, vec![7_i32, 8_i32]);
+      assert!(reprs.iter().all(|repr| repr.sequence_nr() == 5 && repr.applied_migrations() == applied.as_slice()));
+      assert!(reprs.iter().all(|repr| repr.adapter_type_id() == TypeId::of::<i32>()));
+    },
+    | _ => panic!("expected expanded replay messages"),
+  }
+}
+
 #[test]
 fn write_messages_successful_triggers_deferred_handlers() {
   let (journal_ref, journal_store) = create_sender();
@@ -1494,3 +1520,53 @@ fn write_messages_failed_with_zero_write_count_is_ignored_during_recovery() {
   assert!(matches!(action, JournalResponseAction::None));
   assert_eq!(context.state(), PersistentActorState::RecoveryStarted);
 }
+
+#[test]
+fn replay_page_completed_requests_next_page_from_journal() {
+  let (journal_ref, journal_store) = create_sender();
+  let (snapshot_ref, _snapshot_store) = create_sender();
+  let (replay_sender, _replay_store) = create_sender();
+  let mut context = DummyContext::new("pid-1".to_string());
+  context.bind_actor_refs(journal_ref, snapshot_ref).expect("bind actor refs");
+  context.state = PersistentActorState::Recovering;
+  context.replay_sender = replay_sender.clone();
+
+  let action = context.handle_journal_response(&JournalResponse::ReplayPageCompleted {
+    next_from_sequence_nr: 513,
+    to_sequence_nr:        u64::MAX,
+    remaining_max:         0,
+  });
+
+  assert!(matches!(action, JournalResponseAction::None));
+  assert_eq!(context.current_sequence_nr(), 512);
+  let journal_messages = journal_store.lock();
+  assert_eq!(journal_messages.len(), 1);
+  match journal_messages[0].payload().downcast_ref::<JournalMessage>().expect("unexpected payload") {
+    | JournalMessage::ReplayMessages { persistence_id, from_sequence_nr, to_sequence_nr, max, sender } => {
+      assert_eq!(persistence_id, "pid-1");
+      assert_eq!(*from_sequence_nr, 513);
+      assert_eq!(*to_sequence_nr, u64::MAX);
+      assert_eq!(*max, 0);
+      assert_eq!(sender.pid(), replay_sender.pid());
+    },
+    | _ => panic!("unexpected message"),
+  }
+}
+
+#[test]
+fn replay_page_completed_is_ignored_outside_recovery() {
+  let (journal_ref, journal_store) = create_sender();
+  let (snapshot_ref, _snapshot_store) = create_sender();
+  let mut context = DummyContext::new("pid-1".to_string());
+  context.bind_actor_refs(journal_ref, snapshot_ref).expect("bind actor refs");
+  context.state = PersistentActorState::ProcessingCommands;
+
+  let action = context.handle_journal_response(&JournalResponse::ReplayPageCompleted {
+    next_from_sequence_nr: 3,
+    to_sequence_nr:        10,
+    remaining_max:         0,
+  });
+
+  assert!(matches!(action, JournalResponseAction::None));
+  assert!(journal_store.lock().is_empty());
+}
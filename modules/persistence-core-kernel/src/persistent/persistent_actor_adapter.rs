@@ -158,7 +158,7 @@ where
     response: &JournalResponse,
   ) -> Result<(), ActorError> {
     match response {
-      | JournalResponse::ReplayedMessage { .. } => {
+      | JournalResponse::ReplayedMessage { .. } | JournalResponse::ReplayPageCompleted { .. } => {
         if self.actor.persistence_context().state() == PersistentActorState::Recovering {
           self.schedule_recovery_timeout(ctx, false)?;
         }
@@ -21,6 +21,7 @@ pub struct PersistentEnvelope<A> {
   handler:     PersistentHandler<A>,
   stashing:    bool,
   sender:      Option<Pid>,
+  metadata:    Option<ArcShared<dyn Any + Send + Sync>>,
 }
 
 impl<A> PersistentEnvelope<A> {
@@ -33,7 +34,14 @@ impl<A> PersistentEnvelope<A> {
     stashing: bool,
     sender: Option<Pid>,
   ) -> Self {
-    Self { event, sequence_nr, handler, stashing, sender }
+    Self { event, sequence_nr, handler, stashing, sender, metadata: None }
+  }
+
+  /// Returns a copy that attaches `metadata` to the persisted representation.
+  #[must_use]
+  pub fn with_metadata(mut self, metadata: ArcShared<dyn Any + Send + Sync>) -> Self {
+    self.metadata = Some(metadata);
+    self
   }
 
   /// Returns true when the envelope stashes commands.
@@ -51,9 +59,13 @@ impl<A> PersistentEnvelope<A> {
   /// Converts the envelope into a persistent representation.
   #[must_use]
   pub fn into_persistent_repr(&self, persistence_id: impl Into<String>, adapters: EventAdapters) -> PersistentRepr {
-    PersistentRepr::new(persistence_id, self.sequence_nr, self.event.clone())
+    let repr = PersistentRepr::new(persistence_id, self.sequence_nr, self.event.clone())
       .with_sender(self.sender)
-      .with_adapters(adapters)
+      .with_adapters(adapters);
+    match &self.metadata {
+      | Some(metadata) => repr.with_metadata(metadata.clone()),
+      | None => repr,
+    }
   }
 
   /// Consumes the envelope and returns the stored handler.
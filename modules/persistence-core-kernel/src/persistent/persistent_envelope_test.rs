@@ -21,3 +21,15 @@ fn persistent_envelope_into_repr() {
   assert_eq!(repr.downcast_ref::<i32>(), Some(&5));
   assert_eq!(repr.sender(), None);
 }
+
+#[test]
+fn persistent_envelope_carries_metadata_into_repr() {
+  let payload: ArcShared<dyn Any + Send + Sync> = ArcShared::new(5_i32);
+  let metadata: ArcShared<dyn Any + Send + Sync> = ArcShared::new(11_u64);
+  let envelope =
+    PersistentEnvelope::new(payload, 1, Box::new(|_actor: &mut Counter, _| {}), true, None).with_metadata(metadata);
+
+  let repr = envelope.into_persistent_repr("pid-1", EventAdapters::new());
+
+  assert_eq!(repr.metadata().and_then(|metadata| metadata.downcast_ref::<u64>()), Some(&11));
+}
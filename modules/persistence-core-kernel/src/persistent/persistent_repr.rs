@@ -4,7 +4,7 @@
 #[path = "persistent_repr_test.rs"]
 mod tests;
 
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
 use core::{
   any::{Any, TypeId},
   ops::Deref,
@@ -13,22 +13,23 @@ use core::{
 use fraktor_actor_core_kernel_rs::actor::Pid;
 use fraktor_utils_core_rs::sync::ArcShared;
 
-use crate::journal::EventAdapters;
+use crate::{journal::EventAdapters, serialization::AppliedMigration};
 
 /// Persistent event wrapper with metadata.
 #[derive(Clone, Debug)]
 pub struct PersistentRepr {
-  persistence_id:  String,
-  sequence_nr:     u64,
-  payload:         ArcShared<dyn Any + Send + Sync>,
-  manifest:        String,
-  writer_uuid:     String,
-  timestamp:       u64,
-  deleted:         bool,
-  sender:          Option<Pid>,
-  metadata:        Option<ArcShared<dyn Any + Send + Sync>>,
-  adapters:        EventAdapters,
-  adapter_type_id: TypeId,
+  persistence_id:     String,
+  sequence_nr:        u64,
+  payload:            ArcShared<dyn Any + Send + Sync>,
+  manifest:           String,
+  writer_uuid:        String,
+  timestamp:          u64,
+  deleted:            bool,
+  sender:             Option<Pid>,
+  metadata:           Option<ArcShared<dyn Any + Send + Sync>>,
+  adapters:           EventAdapters,
+  adapter_type_id:    TypeId,
+  applied_migrations: Vec<AppliedMigration>,
 }
 
 impl PersistentRepr {
@@ -48,6 +49,7 @@ impl PersistentRepr {
       metadata: None,
       adapters: EventAdapters::new(),
       adapter_type_id,
+      applied_migrations: Vec::new(),
     }
   }
 
@@ -172,6 +174,22 @@ impl PersistentRepr {
     self
   }
 
+  /// Returns the schema migrations applied while this event was read from the journal.
+  ///
+  /// The list is filled in by [`MessageSerializer`](crate::serialization::MessageSerializer) and is
+  /// not written back to the journal.
+  #[must_use]
+  pub fn applied_migrations(&self) -> &[AppliedMigration] {
+    &self.applied_migrations
+  }
+
+  /// Returns a new instance reporting the given applied schema migrations.
+  #[must_use]
+  pub fn with_applied_migrations(mut self, applied_migrations: Vec<AppliedMigration>) -> Self {
+    self.applied_migrations = applied_migrations;
+    self
+  }
+
   /// Returns a new instance with a different writer uuid.
   #[must_use]
   pub fn with_writer_uuid(mut self, writer_uuid: impl Into<String>) -> Self {
@@ -0,0 +1,37 @@
+//! Projection package.
+//!
+//! Projections consume envelope sources built from read journal queries, run a handler for each
+//! envelope and store the processed offset so they resume where they left off.
+
+mod at_least_once_strategy;
+mod base;
+mod exactly_once_projection_handler;
+mod exactly_once_strategy;
+mod grouped_projection_handler;
+mod in_memory_offset_store;
+mod offset_store;
+mod per_envelope;
+mod projection_committer;
+mod projection_error;
+mod projection_future;
+mod projection_handler;
+mod projection_id;
+mod projection_sink_logic;
+mod projection_source_provider;
+mod projection_state;
+mod projection_strategy;
+mod upsert_wake_flag;
+
+pub use base::Projection;
+pub use exactly_once_projection_handler::ExactlyOnceProjectionHandler;
+pub use grouped_projection_handler::GroupedProjectionHandler;
+pub use in_memory_offset_store::InMemoryOffsetStore;
+pub use offset_store::OffsetStore;
+pub(crate) use projection_committer::ProjectionCommitter;
+pub use projection_error::ProjectionError;
+pub use projection_future::ProjectionFuture;
+pub use projection_handler::ProjectionHandler;
+pub use projection_id::ProjectionId;
+pub use projection_source_provider::ProjectionSourceProvider;
+pub use projection_state::ProjectionState;
+pub(crate) use projection_strategy::ProjectionStrategy;
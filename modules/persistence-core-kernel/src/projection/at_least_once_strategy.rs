@@ -0,0 +1,92 @@
+//! At-least-once projection strategy backed by an offset store.
+
+use alloc::boxed::Box;
+use core::{future::ready, task::Poll};
+
+use fraktor_utils_core_rs::sync::SharedLock;
+
+use crate::{
+  projection::{
+    GroupedProjectionHandler, OffsetStore, ProjectionCommitter, ProjectionError, ProjectionFuture, ProjectionId,
+    ProjectionStrategy,
+  },
+  query::{EventEnvelope, Offset},
+};
+
+/// Runs the handler and then stores the offset of the last handled envelope.
+///
+/// Per-envelope handlers are adapted with
+/// [`PerEnvelope`](crate::projection::per_envelope::PerEnvelope), so single and grouped projections
+/// share this strategy.
+pub(crate) struct AtLeastOnceStrategy<O, G> {
+  projection_id: ProjectionId,
+  offset_store:  O,
+  handler:       SharedLock<G>,
+  batch_size:    usize,
+}
+
+impl<O: Clone, G> Clone for AtLeastOnceStrategy<O, G> {
+  fn clone(&self) -> Self {
+    Self {
+      projection_id: self.projection_id.clone(),
+      offset_store:  self.offset_store.clone(),
+      handler:       self.handler.clone(),
+      batch_size:    self.batch_size,
+    }
+  }
+}
+
+impl<O, G> AtLeastOnceStrategy<O, G> {
+  pub(crate) const fn new(
+    projection_id: ProjectionId,
+    offset_store: O,
+    handler: SharedLock<G>,
+    batch_size: usize,
+  ) -> Self {
+    Self { projection_id, offset_store, handler, batch_size }
+  }
+}
+
+impl<O, G> ProjectionStrategy for AtLeastOnceStrategy<O, G>
+where
+  O: OffsetStore,
+  G: GroupedProjectionHandler,
+{
+  fn load_offset(&self) -> ProjectionFuture<Option<Offset>> {
+    Box::pin(ready(self.offset_store.read_offset(&self.projection_id)))
+  }
+
+  fn reset_offset(&self, offset: Option<Offset>) -> ProjectionFuture<()> {
+    let mut offset_store = self.offset_store.clone();
+    let result = match offset {
+      | Some(offset) => offset_store.save_offset(&self.projection_id, &offset),
+      | None => offset_store.clear_offset(&self.projection_id),
+    };
+    Box::pin(ready(result))
+  }
+
+  fn batch_size(&self) -> usize {
+    self.batch_size
+  }
+
+  fn committer(&self) -> Box<dyn ProjectionCommitter> {
+    Box::new(self.clone())
+  }
+}
+
+impl<O, G> ProjectionCommitter for AtLeastOnceStrategy<O, G>
+where
+  O: OffsetStore,
+  G: GroupedProjectionHandler,
+{
+  fn commit(&mut self, envelopes: &[EventEnvelope]) -> Poll<Result<(), ProjectionError>> {
+    let Some(last) = envelopes.last() else {
+      return Poll::Ready(Ok(()));
+    };
+    let result = self
+      .handler
+      .with_lock(|handler| handler.process_group(envelopes))
+      .and_then(|()| self.offset_store.save_offset(&self.projection_id, last.offset()));
+    Poll::Ready(result)
+  }
+}
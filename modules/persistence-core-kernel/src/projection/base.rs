@@ -0,0 +1,192 @@
+//! Projection definition.
+
+#[cfg(test)]
+#[path = "base_test.rs"]
+mod tests;
+
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+use fraktor_stream_core_kernel_rs::{
+  RestartConfig, SharedKillSwitch,
+  dsl::Sink,
+  materialization::{KeepRight, RunnableGraph, StreamDone, StreamFuture},
+  stage::StageKind,
+};
+use fraktor_utils_core_rs::sync::{ArcShared, DefaultMutex, SharedLock};
+
+use crate::{
+  projection::{
+    ExactlyOnceProjectionHandler, GroupedProjectionHandler, OffsetStore, ProjectionFuture, ProjectionHandler,
+    ProjectionId, ProjectionSourceProvider, ProjectionState, ProjectionStrategy,
+    at_least_once_strategy::AtLeastOnceStrategy, exactly_once_strategy::ExactlyOnceStrategy, per_envelope::PerEnvelope,
+    projection_sink_logic::ProjectionSinkLogic,
+  },
+  query::Offset,
+  state::DurableStateStore,
+};
+
+/// Consumes an envelope source with a handler and tracks the processed offset.
+///
+/// A projection is a reusable description: every call to
+/// [`runnable_graph`](Self::runnable_graph) builds a fresh stream resuming after the given offset.
+/// Clones share the handler and the offset storage.
+#[derive(Clone)]
+pub struct Projection {
+  projection_id:  ProjectionId,
+  provider:       ArcShared<dyn ProjectionSourceProvider>,
+  strategy:       ArcShared<dyn ProjectionStrategy>,
+  restart_config: Option<RestartConfig>,
+}
+
+impl Projection {
+  /// Creates an at-least-once projection storing the offset after every handled envelope.
+  #[must_use]
+  pub fn at_least_once<P, O, H>(projection_id: ProjectionId, provider: P, offset_store: O, handler: H) -> Self
+  where
+    P: ProjectionSourceProvider,
+    O: OffsetStore,
+    H: ProjectionHandler, {
+    Self::at_least_once_with_save_interval(projection_id, provider, offset_store, handler, 1)
+  }
+
+  /// Creates an at-least-once projection storing the offset once per `save_after_envelopes`
+  /// envelopes, or earlier when the source idles.
+  ///
+  /// A larger interval reduces offset writes at the cost of replaying more envelopes after a
+  /// restart. `0` is treated as `1`.
+  #[must_use]
+  pub fn at_least_once_with_save_interval<P, O, H>(
+    projection_id: ProjectionId,
+    provider: P,
+    offset_store: O,
+    handler: H,
+    save_after_envelopes: usize,
+  ) -> Self
+  where
+    P: ProjectionSourceProvider,
+    O: OffsetStore,
+    H: ProjectionHandler, {
+    let handler = SharedLock::new_with_driver::<DefaultMutex<_>>(PerEnvelope(handler));
+    let strategy = AtLeastOnceStrategy::new(projection_id.clone(), offset_store, handler, save_after_envelopes.max(1));
+    Self::from_strategy(projection_id, provider, ArcShared::new(strategy))
+  }
+
+  /// Creates a projection handing envelopes to the handler in groups of up to `group_size`.
+  ///
+  /// The offset of the last envelope is stored after each group. `0` is treated as `1`.
+  #[must_use]
+  pub fn grouped<P, O, G>(
+    projection_id: ProjectionId,
+    provider: P,
+    offset_store: O,
+    handler: G,
+    group_size: usize,
+  ) -> Self
+  where
+    P: ProjectionSourceProvider,
+    O: OffsetStore,
+    G: GroupedProjectionHandler, {
+    let handler = SharedLock::new_with_driver::<DefaultMutex<_>>(handler);
+    let strategy = AtLeastOnceStrategy::new(projection_id.clone(), offset_store, handler, group_size.max(1));
+    Self::from_strategy(projection_id, provider, ArcShared::new(strategy))
+  }
+
+  /// Creates an exactly-once projection storing its read-model state and offset together.
+  ///
+  /// The state lives in `store` under the projection id's string form, as a
+  /// [`ProjectionState`].
+  #[must_use]
+  pub fn exactly_once<P, D, S, H>(projection_id: ProjectionId, provider: P, store: D, handler: H) -> Self
+  where
+    P: ProjectionSourceProvider,
+    D: DurableStateStore<ProjectionState<S>> + Clone,
+    S: Clone + Send + Sync + 'static,
+    H: ExactlyOnceProjectionHandler<S>, {
+    let handler = SharedLock::new_with_driver::<DefaultMutex<_>>(handler);
+    let strategy = ExactlyOnceStrategy::new(&projection_id, store, handler);
+    Self::from_strategy(projection_id, provider, ArcShared::new(strategy))
+  }
+
+  fn from_strategy<P>(projection_id: ProjectionId, provider: P, strategy: ArcShared<dyn ProjectionStrategy>) -> Self
+  where
+    P: ProjectionSourceProvider, {
+    Self { projection_id, provider: ArcShared::new(provider), strategy, restart_config: None }
+  }
+
+  /// Restarts the envelope source and the handler stage with backoff after failures.
+  ///
+  /// Without a restart configuration the first failure fails the projection stream.
+  #[must_use]
+  pub fn with_restart_config(mut self, restart_config: RestartConfig) -> Self {
+    self.restart_config = Some(restart_config);
+    self
+  }
+
+  /// Returns the projection id.
+  #[must_use]
+  pub const fn projection_id(&self) -> &ProjectionId {
+    &self.projection_id
+  }
+
+  /// Returns the restart configuration, when set.
+  #[must_use]
+  pub const fn restart_config(&self) -> Option<&RestartConfig> {
+    self.restart_config.as_ref()
+  }
+
+  /// Loads the stored offset the projection resumes after.
+  #[must_use]
+  pub fn load_offset(&self) -> ProjectionFuture<Option<Offset>> {
+    self.strategy.load_offset()
+  }
+
+  /// Replaces the stored offset, or clears it when `offset` is `None`.
+  ///
+  /// Exactly-once projections also discard their stored read-model state in the same write, so the
+  /// handler folds from an empty state again.
+  ///
+  /// Stop running projection streams first, otherwise they may overwrite the new offset.
+  #[must_use]
+  pub fn reset_offset(&self, offset: Option<Offset>) -> ProjectionFuture<()> {
+    self.strategy.reset_offset(offset)
+  }
+
+  /// Builds a projection stream resuming after `offset`.
+  ///
+  /// Pass the offset returned by [`load_offset`](Self::load_offset), which also refreshes the
+  /// cached state of exactly-once projections.
+  ///
+  /// The stream stops committing offsets as soon as `kill_switch` is shut down or aborted, and
+  /// materializes a future completing when the source completes or the projection fails.
+  #[must_use]
+  pub fn runnable_graph(
+    &self,
+    offset: Option<Offset>,
+    kill_switch: &SharedKillSwitch,
+  ) -> RunnableGraph<StreamFuture<StreamDone>> {
+    let completion = StreamFuture::new();
+    let logic = ProjectionSinkLogic::new(
+      self.strategy.committer(),
+      self.strategy.batch_size(),
+      kill_switch.clone(),
+      completion.clone(),
+    );
+    let mut sink = Sink::from_definition(StageKind::Custom, logic, completion);
+    let mut source = self.provider.source(offset.unwrap_or_default()).via(kill_switch.flow());
+    if let Some(restart_config) = &self.restart_config {
+      source = source.restart_source_with_config(restart_config.clone());
+      sink = sink.restart_sink_with_config(restart_config.clone());
+    }
+    source.into_mat(sink, KeepRight)
+  }
+}
+
+impl Debug for Projection {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+    formatter
+      .debug_struct("Projection")
+      .field("projection_id", &self.projection_id)
+      .field("restart_config", &self.restart_config)
+      .finish_non_exhaustive()
+  }
+}
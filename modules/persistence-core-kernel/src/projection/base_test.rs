@@ -0,0 +1,269 @@
+use alloc::{
+  boxed::Box,
+  collections::BTreeMap,
+  string::{String, ToString},
+  vec::Vec,
+};
+use core::{
+  any::Any,
+  future::{Future, ready},
+  pin::Pin,
+  task::{Context, Poll, Waker},
+};
+
+use fraktor_stream_core_kernel_rs::{dsl::Source, materialization::StreamNotUsed};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+use crate::{
+  projection::{InMemoryOffsetStore, OffsetStore, Projection, ProjectionError, ProjectionId, ProjectionState},
+  query::{EventEnvelope, Offset},
+  state::{DurableStateError, DurableStateStore, GetObjectResult},
+};
+
+type StoredObjects = BTreeMap<String, (ProjectionState<u64>, u64)>;
+type DurableStateFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, DurableStateError>> + Send + 'a>>;
+
+#[derive(Clone)]
+struct TestDurableStateStore {
+  objects: ArcShared<SpinSyncMutex<StoredObjects>>,
+}
+
+impl TestDurableStateStore {
+  fn new() -> Self {
+    Self { objects: ArcShared::new(SpinSyncMutex::new(BTreeMap::new())) }
+  }
+}
+
+impl DurableStateStore<ProjectionState<u64>> for TestDurableStateStore {
+  fn get_object<'a>(
+    &'a self,
+    persistence_id: &'a str,
+  ) -> DurableStateFuture<'a, GetObjectResult<ProjectionState<u64>>> {
+    let result =
+      self.objects.lock().get(persistence_id).map_or_else(GetObjectResult::empty, |(object, revision)| {
+        GetObjectResult::new(Some(object.clone()), *revision)
+      });
+    Box::pin(ready(Ok(result)))
+  }
+
+  fn upsert_object<'a>(
+    &'a mut self,
+    persistence_id: &'a str,
+    expected_revision: u64,
+    object: ProjectionState<u64>,
+    _tag: Option<&'a str>,
+  ) -> DurableStateFuture<'a, ()> {
+    let mut objects = self.objects.lock();
+    let actual_revision = objects.get(persistence_id).map_or(0, |(_, revision)| *revision);
+    if actual_revision != expected_revision {
+      return Box::pin(ready(Err(DurableStateError::upsert_revision(
+        persistence_id,
+        expected_revision,
+        actual_revision,
+      ))));
+    }
+    objects.insert(persistence_id.to_string(), (object, actual_revision.saturating_add(1)));
+    Box::pin(ready(Ok(())))
+  }
+
+  fn delete_object<'a>(&'a mut self, persistence_id: &'a str, _expected_revision: u64) -> DurableStateFuture<'a, ()> {
+    self.objects.lock().remove(persistence_id);
+    Box::pin(ready(Ok(())))
+  }
+}
+
+#[derive(Default)]
+struct Gate {
+  open:  bool,
+  polls: usize,
+  waker: Option<Waker>,
+}
+
+/// Store whose upserts stay pending until the test opens the gate.
+#[derive(Clone)]
+struct GatedDurableStateStore {
+  inner: TestDurableStateStore,
+  gate:  ArcShared<SpinSyncMutex<Gate>>,
+}
+
+struct GatedUpsert {
+  gate:   ArcShared<SpinSyncMutex<Gate>>,
+  result: Option<Result<(), DurableStateError>>,
+}
+
+impl Future for GatedUpsert {
+  type Output = Result<(), DurableStateError>;
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let mut gate = self.gate.lock();
+    gate.polls += 1;
+    if !gate.open {
+      gate.waker = Some(cx.waker().clone());
+      return Poll::Pending;
+    }
+    drop(gate);
+    Poll::Ready(self.result.take().expect("polled after completion"))
+  }
+}
+
+impl DurableStateStore<ProjectionState<u64>> for GatedDurableStateStore {
+  fn get_object<'a>(
+    &'a self,
+    persistence_id: &'a str,
+  ) -> DurableStateFuture<'a, GetObjectResult<ProjectionState<u64>>> {
+    self.inner.get_object(persistence_id)
+  }
+
+  fn upsert_object<'a>(
+    &'a mut self,
+    persistence_id: &'a str,
+    expected_revision: u64,
+    object: ProjectionState<u64>,
+    tag: Option<&'a str>,
+  ) -> DurableStateFuture<'a, ()> {
+    let result = poll_ready(self.inner.upsert_object(persistence_id, expected_revision, object, tag));
+    Box::pin(GatedUpsert { gate: self.gate.clone(), result: Some(result) })
+  }
+
+  fn delete_object<'a>(&'a mut self, persistence_id: &'a str, expected_revision: u64) -> DurableStateFuture<'a, ()> {
+    self.inner.delete_object(persistence_id, expected_revision)
+  }
+}
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was pending"),
+  }
+}
+
+fn empty_source(_offset: Offset) -> Source<EventEnvelope, StreamNotUsed> {
+  Source::empty()
+}
+
+fn envelope(sequence_nr: u64) -> EventEnvelope {
+  let event: ArcShared<dyn Any + Send + Sync> = ArcShared::new(sequence_nr);
+  EventEnvelope::new(Offset::sequence(sequence_nr), "pid-1", sequence_nr, event, 0)
+}
+
+#[test]
+fn at_least_once_projection_handles_envelopes_before_storing_offset() {
+  let id = ProjectionId::new("orders", "blue");
+  let offset_store = InMemoryOffsetStore::new();
+  let handled = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let recorder = handled.clone();
+  let projection =
+    Projection::at_least_once(id.clone(), empty_source, offset_store.clone(), move |envelope: &EventEnvelope| {
+      recorder.lock().push(envelope.sequence_nr());
+      Ok(())
+    });
+
+  let mut committer = projection.strategy.committer();
+  assert_eq!(committer.commit(&[envelope(1), envelope(2)]), Poll::Ready(Ok(())));
+
+  assert_eq!(*handled.lock(), vec![1, 2]);
+  assert_eq!(offset_store.read_offset(&id), Ok(Some(Offset::sequence(2))));
+  assert_eq!(poll_ready(projection.load_offset()), Ok(Some(Offset::sequence(2))));
+}
+
+#[test]
+fn at_least_once_projection_keeps_offset_when_handler_fails() {
+  let id = ProjectionId::new("orders", "blue");
+  let offset_store = InMemoryOffsetStore::new();
+  let projection = Projection::at_least_once(id.clone(), empty_source, offset_store.clone(), |_: &EventEnvelope| {
+    Err(ProjectionError::handler_failed("boom"))
+  });
+
+  let mut committer = projection.strategy.committer();
+
+  assert_eq!(committer.commit(&[envelope(1)]), Poll::Ready(Err(ProjectionError::handler_failed("boom"))));
+  assert_eq!(offset_store.read_offset(&id), Ok(None));
+}
+
+#[test]
+fn grouped_projection_uses_group_size_as_batch_size() {
+  let id = ProjectionId::new("orders", "blue");
+  let projection = Projection::grouped(id, empty_source, InMemoryOffsetStore::new(), |_: &[EventEnvelope]| Ok(()), 0);
+
+  assert_eq!(projection.strategy.batch_size(), 1);
+}
+
+#[test]
+fn reset_offset_replaces_or_clears_stored_offset() {
+  let id = ProjectionId::new("orders", "blue");
+  let projection = Projection::at_least_once(id, empty_source, InMemoryOffsetStore::new(), |_: &EventEnvelope| Ok(()));
+
+  poll_ready(projection.reset_offset(Some(Offset::sequence(9)))).expect("reset to offset");
+  assert_eq!(poll_ready(projection.load_offset()), Ok(Some(Offset::sequence(9))));
+
+  poll_ready(projection.reset_offset(None)).expect("clear offset");
+  assert_eq!(poll_ready(projection.load_offset()), Ok(None));
+}
+
+#[test]
+fn exactly_once_projection_stores_state_and_offset_together() {
+  let id = ProjectionId::new("totals", "blue");
+  let store = TestDurableStateStore::new();
+  let handler =
+    |state: Option<&u64>, envelope: &EventEnvelope| Ok(state.copied().unwrap_or(0) + envelope.sequence_nr());
+  let projection = Projection::exactly_once(id.clone(), empty_source, store.clone(), handler);
+  assert_eq!(poll_ready(projection.load_offset()), Ok(None));
+
+  let mut committer = projection.strategy.committer();
+  assert_eq!(committer.commit(&[envelope(1)]), Poll::Ready(Ok(())));
+  assert_eq!(committer.commit(&[envelope(2)]), Poll::Ready(Ok(())));
+
+  let stored = poll_ready(store.get_object(&id.to_string())).expect("get object");
+  assert_eq!(stored.revision(), 2);
+  let (offset, state) = stored.into_value().expect("stored projection state").into_parts();
+  assert_eq!(offset, Offset::sequence(2));
+  assert_eq!(state, Some(3));
+}
+
+#[test]
+fn exactly_once_reset_clears_state_together_with_offset() {
+  let id = ProjectionId::new("totals", "blue");
+  let store = TestDurableStateStore::new();
+  let handler =
+    |state: Option<&u64>, envelope: &EventEnvelope| Ok(state.copied().unwrap_or(0) + envelope.sequence_nr());
+  let projection = Projection::exactly_once(id.clone(), empty_source, store.clone(), handler);
+  let mut committer = projection.strategy.committer();
+  assert_eq!(committer.commit(&[envelope(5)]), Poll::Ready(Ok(())));
+
+  poll_ready(projection.reset_offset(None)).expect("reset offset");
+  assert_eq!(poll_ready(projection.load_offset()), Ok(Some(Offset::NoOffset)));
+  let reset = poll_ready(store.get_object(&id.to_string())).expect("get object").into_value().expect("object");
+  assert_eq!(reset.state(), None);
+
+  assert_eq!(committer.commit(&[envelope(1)]), Poll::Ready(Ok(())));
+  let stored = poll_ready(store.get_object(&id.to_string())).expect("get object").into_value().expect("object");
+  assert_eq!(stored.state(), Some(&1));
+}
+
+#[test]
+fn exactly_once_commit_polls_pending_upsert_only_after_wake() {
+  let id = ProjectionId::new("totals", "blue");
+  let gate = ArcShared::new(SpinSyncMutex::new(Gate::default()));
+  let store = GatedDurableStateStore { inner: TestDurableStateStore::new(), gate: gate.clone() };
+  let handler =
+    |state: Option<&u64>, envelope: &EventEnvelope| Ok(state.copied().unwrap_or(0) + envelope.sequence_nr());
+  let projection = Projection::exactly_once(id.clone(), empty_source, store.clone(), handler);
+  let mut committer = projection.strategy.committer();
+
+  assert_eq!(committer.commit(&[envelope(3)]), Poll::Pending);
+  assert_eq!(committer.commit(&[envelope(3)]), Poll::Pending);
+  assert_eq!(gate.lock().polls, 1);
+
+  let waker = {
+    let mut gate = gate.lock();
+    gate.open = true;
+    gate.waker.take().expect("registered waker")
+  };
+  waker.wake();
+
+  assert_eq!(committer.commit(&[envelope(3)]), Poll::Ready(Ok(())));
+  assert_eq!(gate.lock().polls, 2);
+  assert_eq!(poll_ready(projection.load_offset()), Ok(Some(Offset::sequence(3))));
+}
@@ -0,0 +1,28 @@
+//! Exactly-once projection handler.
+
+use crate::{projection::ProjectionError, query::EventEnvelope};
+
+/// Folds envelopes into a read-model state stored together with the projection offset.
+///
+/// The new state and the envelope offset are written in one durable state upsert, so each
+/// envelope is reflected in the stored state exactly once. The handler must not have other side
+/// effects because it is called again with the same state when the upsert fails.
+pub trait ExactlyOnceProjectionHandler<S>: Send + 'static {
+  /// Returns the state after applying `envelope` to `state`.
+  ///
+  /// `state` is `None` until the first envelope has been stored.
+  ///
+  /// # Errors
+  ///
+  /// Returns a [`ProjectionError`] when the envelope cannot be applied.
+  fn process(&mut self, state: Option<&S>, envelope: &EventEnvelope) -> Result<S, ProjectionError>;
+}
+
+impl<S, F> ExactlyOnceProjectionHandler<S> for F
+where
+  F: FnMut(Option<&S>, &EventEnvelope) -> Result<S, ProjectionError> + Send + 'static,
+{
+  fn process(&mut self, state: Option<&S>, envelope: &EventEnvelope) -> Result<S, ProjectionError> {
+    self(state, envelope)
+  }
+}
@@ -0,0 +1,190 @@
+//! Exactly-once projection strategy backed by a durable state store.
+
+use alloc::{
+  boxed::Box,
+  string::{String, ToString},
+};
+use core::{
+  future::Future,
+  pin::Pin,
+  task::{Context, Poll},
+};
+
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedLock};
+
+use crate::{
+  projection::{
+    ExactlyOnceProjectionHandler, ProjectionCommitter, ProjectionError, ProjectionFuture, ProjectionId,
+    ProjectionState, ProjectionStrategy, upsert_wake_flag::UpsertWakeFlag,
+  },
+  query::{EventEnvelope, Offset},
+  state::{DurableStateError, DurableStateStore},
+};
+
+type UpsertFuture = Pin<Box<dyn Future<Output = Result<(), DurableStateError>> + Send>>;
+
+/// Last stored read-model state and its durable state revision.
+struct StoredState<S> {
+  state:    Option<S>,
+  revision: u64,
+}
+
+/// Upsert started by a commit that has not completed yet.
+struct InFlightUpsert<S> {
+  future: UpsertFuture,
+  wake:   UpsertWakeFlag,
+  state:  S,
+}
+
+/// Stores the folded read-model state and the offset in one durable state upsert.
+///
+/// A pending upsert is polled again only after its future wakes the commit waker, so stores may
+/// complete their writes asynchronously.
+pub(crate) struct ExactlyOnceStrategy<D, S, H> {
+  persistence_id: String,
+  store:          D,
+  handler:        SharedLock<H>,
+  stored:         SharedLock<StoredState<S>>,
+  in_flight:      SharedLock<Option<InFlightUpsert<S>>>,
+}
+
+// 進行中の upsert は複製ごとに独立させる。
+impl<D: Clone, S: Send + 'static, H> Clone for ExactlyOnceStrategy<D, S, H> {
+  fn clone(&self) -> Self {
+    Self {
+      persistence_id: self.persistence_id.clone(),
+      store:          self.store.clone(),
+      handler:        self.handler.clone(),
+      stored:         self.stored.clone(),
+      in_flight:      SharedLock::new_with_driver::<DefaultMutex<_>>(None),
+    }
+  }
+}
+
+impl<D, S, H> ExactlyOnceStrategy<D, S, H>
+where
+  S: Send + 'static,
+{
+  pub(crate) fn new(projection_id: &ProjectionId, store: D, handler: SharedLock<H>) -> Self {
+    Self {
+      persistence_id: projection_id.to_string(),
+      store,
+      handler,
+      stored: SharedLock::new_with_driver::<DefaultMutex<_>>(StoredState { state: None, revision: 0 }),
+      in_flight: SharedLock::new_with_driver::<DefaultMutex<_>>(None),
+    }
+  }
+}
+
+impl<D, S, H> ProjectionStrategy for ExactlyOnceStrategy<D, S, H>
+where
+  D: DurableStateStore<ProjectionState<S>> + Clone,
+  S: Clone + Send + Sync + 'static,
+  H: ExactlyOnceProjectionHandler<S>,
+{
+  fn load_offset(&self) -> ProjectionFuture<Option<Offset>> {
+    let store = self.store.clone();
+    let stored = self.stored.clone();
+    let persistence_id = self.persistence_id.clone();
+    Box::pin(async move {
+      let result = store.get_object(&persistence_id).await?;
+      let revision = result.revision();
+      let (offset, state) = match result.into_value() {
+        | Some(object) => {
+          let (offset, state) = object.into_parts();
+          (Some(offset), state)
+        },
+        | None => (None, None),
+      };
+      stored.with_lock(|stored| *stored = StoredState { state, revision });
+      Ok(offset)
+    })
+  }
+
+  fn reset_offset(&self, offset: Option<Offset>) -> ProjectionFuture<()> {
+    let mut store = self.store.clone();
+    let stored = self.stored.clone();
+    let persistence_id = self.persistence_id.clone();
+    Box::pin(async move {
+      let revision = store.get_object(&persistence_id).await?.revision();
+      // 畳み込み済みの状態は旧オフセットまでのイベントを含むため、オフセットと同じ upsert で破棄する。
+      let object = ProjectionState::new(offset.unwrap_or_default(), None);
+      store.upsert_object(&persistence_id, revision, object, None).await?;
+      stored.with_lock(|stored| *stored = StoredState { state: None, revision: revision.saturating_add(1) });
+      Ok(())
+    })
+  }
+
+  fn batch_size(&self) -> usize {
+    1
+  }
+
+  fn committer(&self) -> Box<dyn ProjectionCommitter> {
+    Box::new(self.clone())
+  }
+}
+
+impl<D, S, H> ProjectionCommitter for ExactlyOnceStrategy<D, S, H>
+where
+  D: DurableStateStore<ProjectionState<S>> + Clone,
+  S: Clone + Send + Sync + 'static,
+  H: ExactlyOnceProjectionHandler<S>,
+{
+  fn commit(&mut self, envelopes: &[EventEnvelope]) -> Poll<Result<(), ProjectionError>> {
+    let Some(last) = envelopes.last() else {
+      return Poll::Ready(Ok(()));
+    };
+    let mut in_flight = match self.in_flight.with_lock(Option::take) {
+      | Some(in_flight) => in_flight,
+      | None => match self.start_upsert(envelopes, last.offset()) {
+        | Ok(in_flight) => in_flight,
+        | Err(error) => return Poll::Ready(Err(error)),
+      },
+    };
+    if !in_flight.wake.take() {
+      self.in_flight.with_lock(|slot| *slot = Some(in_flight));
+      return Poll::Pending;
+    }
+    let waker = in_flight.wake.waker();
+    let mut context = Context::from_waker(&waker);
+    match in_flight.future.as_mut().poll(&mut context) {
+      | Poll::Ready(Ok(())) => {
+        let state = in_flight.state;
+        self.stored.with_lock(|stored| {
+          stored.state = Some(state);
+          stored.revision = stored.revision.saturating_add(1);
+        });
+        Poll::Ready(Ok(()))
+      },
+      | Poll::Ready(Err(error)) => Poll::Ready(Err(error.into())),
+      | Poll::Pending => {
+        self.in_flight.with_lock(|slot| *slot = Some(in_flight));
+        Poll::Pending
+      },
+    }
+  }
+}
+
+impl<D, S, H> ExactlyOnceStrategy<D, S, H>
+where
+  D: DurableStateStore<ProjectionState<S>> + Clone,
+  S: Clone + Send + Sync + 'static,
+  H: ExactlyOnceProjectionHandler<S>,
+{
+  fn start_upsert(&self, envelopes: &[EventEnvelope], offset: &Offset) -> Result<InFlightUpsert<S>, ProjectionError> {
+    let (state, revision) = self.stored.with_lock(|stored| (stored.state.clone(), stored.revision));
+    let state = self.handler.with_lock(|handler| {
+      let Some((first, rest)) = envelopes.split_first() else {
+        return Err(ProjectionError::handler_failed("exactly-once commit without envelopes"));
+      };
+      let first = handler.process(state.as_ref(), first)?;
+      rest.iter().try_fold(first, |state, envelope| handler.process(Some(&state), envelope))
+    })?;
+    let mut store = self.store.clone();
+    let persistence_id = self.persistence_id.clone();
+    let object = ProjectionState::new(offset.clone(), Some(state.clone()));
+    let future: UpsertFuture =
+      Box::pin(async move { store.upsert_object(&persistence_id, revision, object, None).await });
+    Ok(InFlightUpsert { future, wake: UpsertWakeFlag::new(), state })
+  }
+}
@@ -0,0 +1,25 @@
+//! Grouped projection handler.
+
+use crate::{projection::ProjectionError, query::EventEnvelope};
+
+/// Handles envelopes in batches, storing one offset per batch.
+///
+/// A batch is handed over once it reaches the configured group size or the source stops delivering
+/// envelopes for a while. A failed batch is retried as a whole.
+pub trait GroupedProjectionHandler: Send + 'static {
+  /// Handles one batch of envelopes in source order.
+  ///
+  /// # Errors
+  ///
+  /// Returns a [`ProjectionError`] when the batch cannot be handled.
+  fn process_group(&mut self, envelopes: &[EventEnvelope]) -> Result<(), ProjectionError>;
+}
+
+impl<F> GroupedProjectionHandler for F
+where
+  F: FnMut(&[EventEnvelope]) -> Result<(), ProjectionError> + Send + 'static,
+{
+  fn process_group(&mut self, envelopes: &[EventEnvelope]) -> Result<(), ProjectionError> {
+    self(envelopes)
+  }
+}
@@ -0,0 +1,59 @@
+//! In-memory projection offset store.
+
+#[cfg(test)]
+#[path = "in_memory_offset_store_test.rs"]
+mod tests;
+
+use alloc::collections::BTreeMap;
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedLock};
+
+use crate::{
+  projection::{OffsetStore, ProjectionError, ProjectionId},
+  query::Offset,
+};
+
+/// Offset store keeping offsets in memory.
+///
+/// Clones share the same offsets.
+#[derive(Clone)]
+pub struct InMemoryOffsetStore {
+  offsets: SharedLock<BTreeMap<ProjectionId, Offset>>,
+}
+
+impl InMemoryOffsetStore {
+  /// Creates an empty offset store.
+  #[must_use]
+  pub fn new() -> Self {
+    Self { offsets: SharedLock::new_with_driver::<DefaultMutex<_>>(BTreeMap::new()) }
+  }
+}
+
+impl Default for InMemoryOffsetStore {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl Debug for InMemoryOffsetStore {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+    formatter.debug_struct("InMemoryOffsetStore").finish_non_exhaustive()
+  }
+}
+
+impl OffsetStore for InMemoryOffsetStore {
+  fn read_offset(&self, projection_id: &ProjectionId) -> Result<Option<Offset>, ProjectionError> {
+    Ok(self.offsets.with_lock(|offsets| offsets.get(projection_id).cloned()))
+  }
+
+  fn save_offset(&mut self, projection_id: &ProjectionId, offset: &Offset) -> Result<(), ProjectionError> {
+    self.offsets.with_lock(|offsets| offsets.insert(projection_id.clone(), offset.clone()));
+    Ok(())
+  }
+
+  fn clear_offset(&mut self, projection_id: &ProjectionId) -> Result<(), ProjectionError> {
+    self.offsets.with_lock(|offsets| offsets.remove(projection_id));
+    Ok(())
+  }
+}
@@ -0,0 +1,30 @@
+use crate::{
+  projection::{InMemoryOffsetStore, OffsetStore, ProjectionId},
+  query::Offset,
+};
+
+#[test]
+fn in_memory_offset_store_saves_and_clears_offsets_per_projection() {
+  let mut store = InMemoryOffsetStore::new();
+  let blue = ProjectionId::new("orders", "blue");
+  let red = ProjectionId::new("orders", "red");
+
+  store.save_offset(&blue, &Offset::sequence(3)).expect("save blue");
+  store.save_offset(&red, &Offset::sequence(7)).expect("save red");
+  store.save_offset(&blue, &Offset::sequence(4)).expect("overwrite blue");
+  store.clear_offset(&red).expect("clear red");
+
+  assert_eq!(store.read_offset(&blue), Ok(Some(Offset::sequence(4))));
+  assert_eq!(store.read_offset(&red), Ok(None));
+}
+
+#[test]
+fn in_memory_offset_store_clones_share_offsets() {
+  let store = InMemoryOffsetStore::new();
+  let mut cloned = store.clone();
+  let id = ProjectionId::new("orders", "blue");
+
+  cloned.save_offset(&id, &Offset::sequence(1)).expect("save");
+
+  assert_eq!(store.read_offset(&id), Ok(Some(Offset::sequence(1))));
+}
@@ -0,0 +1,34 @@
+//! Projection offset storage abstraction.
+
+use crate::{
+  projection::{ProjectionError, ProjectionId},
+  query::Offset,
+};
+
+/// Stores the last processed offset of each projection.
+///
+/// Implementations are cheap handles sharing the underlying storage, so the running projection
+/// stream and the projection actor observe the same offsets.
+pub trait OffsetStore: Clone + Send + Sync + 'static {
+  /// Returns the stored offset of `projection_id`, or `None` when the projection has not stored
+  /// one.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ProjectionError::OffsetStoreFailed`] when the storage cannot be read.
+  fn read_offset(&self, projection_id: &ProjectionId) -> Result<Option<Offset>, ProjectionError>;
+
+  /// Replaces the stored offset of `projection_id`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ProjectionError::OffsetStoreFailed`] when the storage cannot be written.
+  fn save_offset(&mut self, projection_id: &ProjectionId, offset: &Offset) -> Result<(), ProjectionError>;
+
+  /// Removes the stored offset of `projection_id` so the projection restarts from the beginning.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`ProjectionError::OffsetStoreFailed`] when the storage cannot be written.
+  fn clear_offset(&mut self, projection_id: &ProjectionId) -> Result<(), ProjectionError>;
+}
@@ -0,0 +1,15 @@
+//! Adapter running a per-envelope handler over a batch.
+
+use crate::{
+  projection::{GroupedProjectionHandler, ProjectionError, ProjectionHandler},
+  query::EventEnvelope,
+};
+
+/// Feeds each envelope of a batch to a [`ProjectionHandler`] in order.
+pub(crate) struct PerEnvelope<H>(pub(crate) H);
+
+impl<H: ProjectionHandler> GroupedProjectionHandler for PerEnvelope<H> {
+  fn process_group(&mut self, envelopes: &[EventEnvelope]) -> Result<(), ProjectionError> {
+    envelopes.iter().try_for_each(|envelope| self.0.process(envelope))
+  }
+}
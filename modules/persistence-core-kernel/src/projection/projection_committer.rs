@@ -0,0 +1,14 @@
+//! Handler invocation and offset commit for one projection stream.
+
+use core::task::Poll;
+
+use crate::{projection::ProjectionError, query::EventEnvelope};
+
+/// Runs the handler for a batch of envelopes and stores the offset of the last one.
+pub(crate) trait ProjectionCommitter: Send {
+  /// Commits `envelopes`, returning `Poll::Pending` while an asynchronous store write is in flight.
+  ///
+  /// A pending commit is resumed by calling this again with the same envelopes. After an error the
+  /// committer forgets any in-flight work, so the next call retries the batch from scratch.
+  fn commit(&mut self, envelopes: &[EventEnvelope]) -> Poll<Result<(), ProjectionError>>;
+}
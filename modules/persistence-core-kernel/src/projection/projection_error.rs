@@ -0,0 +1,51 @@
+//! Projection errors.
+
+#[cfg(test)]
+#[path = "projection_error_test.rs"]
+mod tests;
+
+use alloc::string::String;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::state::DurableStateError;
+
+/// Errors raised while running a projection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProjectionError {
+  /// The projection handler rejected an envelope.
+  HandlerFailed(String),
+  /// The offset store could not read or write an offset.
+  OffsetStoreFailed(String),
+  /// The durable state store backing an exactly-once projection failed.
+  DurableState(DurableStateError),
+}
+
+impl ProjectionError {
+  /// Creates a handler failure.
+  #[must_use]
+  pub fn handler_failed(reason: impl Into<String>) -> Self {
+    Self::HandlerFailed(reason.into())
+  }
+
+  /// Creates an offset store failure.
+  #[must_use]
+  pub fn offset_store_failed(reason: impl Into<String>) -> Self {
+    Self::OffsetStoreFailed(reason.into())
+  }
+}
+
+impl From<DurableStateError> for ProjectionError {
+  fn from(error: DurableStateError) -> Self {
+    Self::DurableState(error)
+  }
+}
+
+impl Display for ProjectionError {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      | Self::HandlerFailed(reason) => write!(formatter, "projection handler failed: {}", reason),
+      | Self::OffsetStoreFailed(reason) => write!(formatter, "projection offset store failed: {}", reason),
+      | Self::DurableState(error) => write!(formatter, "projection durable state failed: {}", error),
+    }
+  }
+}
@@ -0,0 +1,17 @@
+use alloc::string::ToString;
+
+use crate::{projection::ProjectionError, state::DurableStateError};
+
+#[test]
+fn projection_error_display_names_the_failing_part() {
+  assert_eq!(ProjectionError::handler_failed("boom").to_string(), "projection handler failed: boom");
+  assert_eq!(ProjectionError::offset_store_failed("disk").to_string(), "projection offset store failed: disk");
+}
+
+#[test]
+fn projection_error_wraps_durable_state_errors() {
+  let error = ProjectionError::from(DurableStateError::UpsertObjectFailed("down".into()));
+
+  assert_eq!(error, ProjectionError::DurableState(DurableStateError::UpsertObjectFailed("down".into())));
+  assert_eq!(error.to_string(), "projection durable state failed: upsert durable state object failed: down");
+}
@@ -0,0 +1,9 @@
+//! Future type returned by projection offset operations.
+
+use alloc::boxed::Box;
+use core::{future::Future, pin::Pin};
+
+use crate::projection::ProjectionError;
+
+/// Boxed future returned by [`Projection`](crate::projection::Projection) offset operations.
+pub type ProjectionFuture<T> = Pin<Box<dyn Future<Output = Result<T, ProjectionError>> + Send>>;
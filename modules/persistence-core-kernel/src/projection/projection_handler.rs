@@ -0,0 +1,26 @@
+//! At-least-once projection handler.
+
+use crate::{projection::ProjectionError, query::EventEnvelope};
+
+/// Handles envelopes one at a time for an at-least-once projection.
+///
+/// The offset is stored after the handler succeeds, so an envelope may be handled again after a
+/// failure or restart. Handlers should therefore be idempotent.
+pub trait ProjectionHandler: Send + 'static {
+  /// Handles one envelope.
+  ///
+  /// # Errors
+  ///
+  /// Returns a [`ProjectionError`] when the envelope cannot be handled. The projection retries the
+  /// envelope according to its restart configuration.
+  fn process(&mut self, envelope: &EventEnvelope) -> Result<(), ProjectionError>;
+}
+
+impl<F> ProjectionHandler for F
+where
+  F: FnMut(&EventEnvelope) -> Result<(), ProjectionError> + Send + 'static,
+{
+  fn process(&mut self, envelope: &EventEnvelope) -> Result<(), ProjectionError> {
+    self(envelope)
+  }
+}
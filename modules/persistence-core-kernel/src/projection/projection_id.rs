@@ -0,0 +1,44 @@
+//! Projection identity.
+
+#[cfg(test)]
+#[path = "projection_id_test.rs"]
+mod tests;
+
+use alloc::string::String;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Identifies one projection instance by name and key.
+///
+/// The name groups projections that run the same handler, while the key distinguishes instances
+/// such as one per tag. Offsets are stored per id.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProjectionId {
+  name: String,
+  key:  String,
+}
+
+impl ProjectionId {
+  /// Creates a projection id.
+  #[must_use]
+  pub fn new(name: impl Into<String>, key: impl Into<String>) -> Self {
+    Self { name: name.into(), key: key.into() }
+  }
+
+  /// Returns the projection name.
+  #[must_use]
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Returns the projection key.
+  #[must_use]
+  pub fn key(&self) -> &str {
+    &self.key
+  }
+}
+
+impl Display for ProjectionId {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+    write!(formatter, "{}-{}", self.name, self.key)
+  }
+}
@@ -0,0 +1,21 @@
+use alloc::string::ToString;
+
+use crate::projection::ProjectionId;
+
+#[test]
+fn projection_id_exposes_name_and_key() {
+  let id = ProjectionId::new("orders", "blue");
+
+  assert_eq!(id.name(), "orders");
+  assert_eq!(id.key(), "blue");
+  assert_eq!(id.to_string(), "orders-blue");
+}
+
+#[test]
+fn projection_ids_order_by_name_then_key() {
+  let mut ids = [ProjectionId::new("b", "1"), ProjectionId::new("a", "2"), ProjectionId::new("a", "1")];
+
+  ids.sort();
+
+  assert_eq!(ids, [ProjectionId::new("a", "1"), ProjectionId::new("a", "2"), ProjectionId::new("b", "1")]);
+}
@@ -0,0 +1,180 @@
+//! Sink stage that runs the projection handler and commits offsets.
+
+#[cfg(test)]
+#[path = "projection_sink_logic_test.rs"]
+mod tests;
+
+use alloc::{boxed::Box, string::ToString, vec::Vec};
+use core::task::Poll;
+
+use fraktor_stream_core_kernel_rs::{
+  DemandTracker, DynValue, SharedKillSwitch, SinkDecision, SinkLogic, StreamError,
+  materialization::{StreamDone, StreamFuture},
+};
+
+use crate::{
+  projection::{ProjectionCommitter, ProjectionError},
+  query::EventEnvelope,
+};
+
+/// Ticks without new envelopes after which a partial batch is committed.
+const IDLE_TICKS_BEFORE_FLUSH: u32 = 2;
+
+/// Buffers envelopes into batches and commits each batch through a [`ProjectionCommitter`].
+///
+/// A failed batch stays buffered and blocks further input, so restarting the sink retries the same
+/// envelopes instead of skipping them.
+pub(crate) struct ProjectionSinkLogic {
+  committer:         Box<dyn ProjectionCommitter>,
+  kill_switch:       SharedKillSwitch,
+  completion:        StreamFuture<StreamDone>,
+  batch:             Vec<EventEnvelope>,
+  batch_size:        usize,
+  committing:        bool,
+  demand_owed:       bool,
+  idle_ticks:        u32,
+  upstream_finished: bool,
+  completed:         bool,
+}
+
+impl ProjectionSinkLogic {
+  pub(crate) fn new(
+    committer: Box<dyn ProjectionCommitter>,
+    batch_size: usize,
+    kill_switch: SharedKillSwitch,
+    completion: StreamFuture<StreamDone>,
+  ) -> Self {
+    Self {
+      committer,
+      kill_switch,
+      completion,
+      batch: Vec::new(),
+      batch_size: batch_size.max(1),
+      committing: false,
+      demand_owed: false,
+      idle_ticks: 0,
+      upstream_finished: false,
+      completed: false,
+    }
+  }
+
+  fn stopped(&self) -> bool {
+    self.kill_switch.is_shutdown() || self.kill_switch.is_aborted()
+  }
+
+  /// Commits the buffered batch, returning `Ok(true)` once it is stored.
+  fn commit_batch(&mut self) -> Result<bool, StreamError> {
+    if self.stopped() {
+      // 停止後のコミットはリセットされたオフセットを上書きしうるため破棄する。
+      self.batch.clear();
+      self.committing = false;
+      return Ok(true);
+    }
+    self.committing = true;
+    match self.committer.commit(&self.batch) {
+      | Poll::Ready(Ok(())) => {
+        self.batch.clear();
+        self.committing = false;
+        self.idle_ticks = 0;
+        Ok(true)
+      },
+      | Poll::Ready(Err(error)) => Err(projection_failure(&error)),
+      | Poll::Pending => Ok(false),
+    }
+  }
+
+  fn finish(&mut self) -> bool {
+    if self.completed {
+      return false;
+    }
+    self.completed = true;
+    self.completion.complete(Ok(StreamDone::new()));
+    true
+  }
+
+  fn after_commit(&mut self, demand: &mut DemandTracker) -> Result<bool, StreamError> {
+    if self.upstream_finished {
+      return Ok(self.finish());
+    }
+    if self.demand_owed {
+      self.demand_owed = false;
+      demand.request(1)?;
+    }
+    Ok(true)
+  }
+}
+
+impl SinkLogic for ProjectionSinkLogic {
+  fn can_accept_input(&self) -> bool {
+    !self.committing
+  }
+
+  fn on_start(&mut self, demand: &mut DemandTracker) -> Result<(), StreamError> {
+    demand.request(1)
+  }
+
+  fn on_push(&mut self, input: DynValue, demand: &mut DemandTracker) -> Result<SinkDecision, StreamError> {
+    let envelope = input.downcast::<EventEnvelope>().map_err(|_| StreamError::TypeMismatch)?;
+    if self.stopped() {
+      return Ok(SinkDecision::Complete);
+    }
+    self.batch.push(*envelope);
+    self.idle_ticks = 0;
+    if self.batch.len() >= self.batch_size {
+      self.demand_owed = true;
+      if !self.commit_batch()? {
+        return Ok(SinkDecision::Continue);
+      }
+      self.demand_owed = false;
+    }
+    demand.request(1)?;
+    Ok(SinkDecision::Continue)
+  }
+
+  fn on_complete(&mut self) -> Result<(), StreamError> {
+    self.finish();
+    Ok(())
+  }
+
+  fn on_error(&mut self, error: StreamError) {
+    self.completed = true;
+    self.completion.complete(Err(error));
+  }
+
+  fn on_tick(&mut self, demand: &mut DemandTracker) -> Result<bool, StreamError> {
+    if self.completed {
+      return Ok(false);
+    }
+    if self.committing {
+      // 失敗したバッチの再試行、または非同期コミットの完了待ち。
+      return if self.commit_batch()? { self.after_commit(demand) } else { Ok(false) };
+    }
+    if self.batch.is_empty() {
+      return Ok(self.upstream_finished && self.finish());
+    }
+    self.idle_ticks = self.idle_ticks.saturating_add(1);
+    if self.idle_ticks < IDLE_TICKS_BEFORE_FLUSH && !self.upstream_finished {
+      return Ok(false);
+    }
+    if self.commit_batch()? { self.after_commit(demand) } else { Ok(false) }
+  }
+
+  fn on_upstream_finish(&mut self) -> Result<bool, StreamError> {
+    self.upstream_finished = true;
+    if self.committing {
+      return Ok(false);
+    }
+    if !self.batch.is_empty() && !self.commit_batch()? {
+      return Ok(false);
+    }
+    Ok(self.finish())
+  }
+
+  fn has_pending_work(&self) -> bool {
+    !self.batch.is_empty()
+  }
+}
+
+fn projection_failure(error: &ProjectionError) -> StreamError {
+  StreamError::failed_typed::<ProjectionError>(error.to_string())
+}
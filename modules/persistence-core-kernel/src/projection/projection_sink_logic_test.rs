@@ -0,0 +1,143 @@
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
+use core::{any::Any, task::Poll};
+
+use fraktor_stream_core_kernel_rs::{
+  DemandTracker, DynValue, SharedKillSwitch, SinkDecision, SinkLogic, StreamError,
+  materialization::{StreamDone, StreamFuture},
+};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+use super::ProjectionSinkLogic;
+use crate::{
+  projection::{ProjectionCommitter, ProjectionError},
+  query::{EventEnvelope, Offset},
+};
+
+type Outcome = Poll<Result<(), ProjectionError>>;
+
+struct ScriptedCommitter {
+  outcomes:  VecDeque<Outcome>,
+  committed: ArcShared<SpinSyncMutex<Vec<Vec<u64>>>>,
+}
+
+impl ProjectionCommitter for ScriptedCommitter {
+  fn commit(&mut self, envelopes: &[EventEnvelope]) -> Poll<Result<(), ProjectionError>> {
+    let outcome = self.outcomes.pop_front().unwrap_or(Poll::Ready(Ok(())));
+    if matches!(outcome, Poll::Ready(Ok(()))) {
+      self.committed.lock().push(envelopes.iter().map(EventEnvelope::sequence_nr).collect());
+    }
+    outcome
+  }
+}
+
+struct Fixture {
+  logic:       ProjectionSinkLogic,
+  committed:   ArcShared<SpinSyncMutex<Vec<Vec<u64>>>>,
+  kill_switch: SharedKillSwitch,
+  completion:  StreamFuture<StreamDone>,
+}
+
+fn fixture(batch_size: usize, outcomes: Vec<Outcome>) -> Fixture {
+  let committed = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let committer = ScriptedCommitter { outcomes: outcomes.into(), committed: committed.clone() };
+  let kill_switch = SharedKillSwitch::new();
+  let completion = StreamFuture::new();
+  let logic = ProjectionSinkLogic::new(Box::new(committer), batch_size, kill_switch.clone(), completion.clone());
+  Fixture { logic, committed, kill_switch, completion }
+}
+
+fn envelope(sequence_nr: u64) -> DynValue {
+  let event: ArcShared<dyn Any + Send + Sync> = ArcShared::new(sequence_nr);
+  Box::new(EventEnvelope::new(Offset::sequence(sequence_nr), "pid-1", sequence_nr, event, 0))
+}
+
+#[test]
+fn commits_each_full_batch_and_requests_more_input() {
+  let mut fixture = fixture(2, Vec::new());
+  let mut demand = DemandTracker::new();
+
+  fixture.logic.on_start(&mut demand).expect("start");
+  for sequence_nr in 1..=4 {
+    demand.consume(1).expect("consume demand");
+    assert_eq!(fixture.logic.on_push(envelope(sequence_nr), &mut demand), Ok(SinkDecision::Continue));
+    assert!(demand.has_demand());
+  }
+
+  assert_eq!(*fixture.committed.lock(), vec![vec![1, 2], vec![3, 4]]);
+  assert!(!fixture.logic.has_pending_work());
+}
+
+#[test]
+fn flushes_partial_batch_after_idle_ticks() {
+  let mut fixture = fixture(10, Vec::new());
+  let mut demand = DemandTracker::new();
+  fixture.logic.on_start(&mut demand).expect("start");
+  fixture.logic.on_push(envelope(1), &mut demand).expect("push");
+
+  assert_eq!(fixture.logic.on_tick(&mut demand), Ok(false));
+  assert!(fixture.committed.lock().is_empty());
+  assert_eq!(fixture.logic.on_tick(&mut demand), Ok(true));
+
+  assert_eq!(*fixture.committed.lock(), vec![vec![1]]);
+}
+
+#[test]
+fn failed_batch_blocks_input_and_is_retried() {
+  let failure = Poll::Ready(Err(ProjectionError::handler_failed("boom")));
+  let mut fixture = fixture(1, vec![failure]);
+  let mut demand = DemandTracker::new();
+  fixture.logic.on_start(&mut demand).expect("start");
+  demand.consume(1).expect("consume demand");
+
+  let error = fixture.logic.on_push(envelope(1), &mut demand).expect_err("commit failure");
+
+  assert!(matches!(error, StreamError::FailedWithContext { .. }));
+  assert!(!fixture.logic.can_accept_input());
+  assert!(!demand.has_demand());
+  assert_eq!(fixture.logic.on_tick(&mut demand), Ok(true));
+  assert_eq!(*fixture.committed.lock(), vec![vec![1]]);
+  assert!(fixture.logic.can_accept_input());
+  assert!(demand.has_demand());
+}
+
+#[test]
+fn pending_commit_completes_on_later_tick() {
+  let mut fixture = fixture(1, vec![Poll::Pending]);
+  let mut demand = DemandTracker::new();
+  fixture.logic.on_start(&mut demand).expect("start");
+  demand.consume(1).expect("consume demand");
+
+  assert_eq!(fixture.logic.on_push(envelope(1), &mut demand), Ok(SinkDecision::Continue));
+  assert!(!fixture.logic.can_accept_input());
+  assert!(!demand.has_demand());
+
+  assert_eq!(fixture.logic.on_tick(&mut demand), Ok(true));
+  assert_eq!(*fixture.committed.lock(), vec![vec![1]]);
+  assert!(demand.has_demand());
+}
+
+#[test]
+fn upstream_finish_flushes_batch_and_completes() {
+  let mut fixture = fixture(10, Vec::new());
+  let mut demand = DemandTracker::new();
+  fixture.logic.on_start(&mut demand).expect("start");
+  fixture.logic.on_push(envelope(1), &mut demand).expect("push");
+
+  assert_eq!(fixture.logic.on_upstream_finish(), Ok(true));
+
+  assert_eq!(*fixture.committed.lock(), vec![vec![1]]);
+  assert_eq!(fixture.completion.try_take(), Some(Ok(StreamDone::new())));
+}
+
+#[test]
+fn kill_switch_shutdown_drops_uncommitted_batch() {
+  let mut fixture = fixture(10, Vec::new());
+  let mut demand = DemandTracker::new();
+  fixture.logic.on_start(&mut demand).expect("start");
+  fixture.logic.on_push(envelope(1), &mut demand).expect("push");
+
+  fixture.kill_switch.shutdown();
+
+  assert_eq!(fixture.logic.on_upstream_finish(), Ok(true));
+  assert!(fixture.committed.lock().is_empty());
+}
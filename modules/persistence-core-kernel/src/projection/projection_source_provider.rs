@@ -0,0 +1,23 @@
+//! Envelope sources consumed by projections.
+
+use fraktor_stream_core_kernel_rs::{dsl::Source, materialization::StreamNotUsed};
+
+use crate::query::{EventEnvelope, Offset};
+
+/// Creates the envelope source a projection consumes, resuming after a stored offset.
+///
+/// Typically wraps a read journal query such as
+/// [`EventsByTagQuery::events_by_tag`](crate::query::EventsByTagQuery::events_by_tag).
+pub trait ProjectionSourceProvider: Send + Sync + 'static {
+  /// Returns a source emitting envelopes after `offset`.
+  fn source(&self, offset: Offset) -> Source<EventEnvelope, StreamNotUsed>;
+}
+
+impl<F> ProjectionSourceProvider for F
+where
+  F: Fn(Offset) -> Source<EventEnvelope, StreamNotUsed> + Send + Sync + 'static,
+{
+  fn source(&self, offset: Offset) -> Source<EventEnvelope, StreamNotUsed> {
+    self(offset)
+  }
+}
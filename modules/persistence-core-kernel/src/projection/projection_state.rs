@@ -0,0 +1,40 @@
+//! Durable state object of an exactly-once projection.
+
+#[cfg(test)]
+#[path = "projection_state_test.rs"]
+mod tests;
+
+use crate::query::Offset;
+
+/// Read-model state stored together with the offset of the last applied envelope.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectionState<S> {
+  offset: Offset,
+  state:  Option<S>,
+}
+
+impl<S> ProjectionState<S> {
+  /// Creates a projection state object.
+  #[must_use]
+  pub const fn new(offset: Offset, state: Option<S>) -> Self {
+    Self { offset, state }
+  }
+
+  /// Returns the offset of the last applied envelope.
+  #[must_use]
+  pub const fn offset(&self) -> &Offset {
+    &self.offset
+  }
+
+  /// Returns the read-model state, when any envelope has been applied.
+  #[must_use]
+  pub const fn state(&self) -> Option<&S> {
+    self.state.as_ref()
+  }
+
+  /// Consumes the object and returns the offset and state.
+  #[must_use]
+  pub fn into_parts(self) -> (Offset, Option<S>) {
+    (self.offset, self.state)
+  }
+}
@@ -0,0 +1,10 @@
+use crate::{projection::ProjectionState, query::Offset};
+
+#[test]
+fn projection_state_keeps_offset_and_state_together() {
+  let state = ProjectionState::new(Offset::sequence(5), Some(12_u32));
+
+  assert_eq!(state.offset(), &Offset::sequence(5));
+  assert_eq!(state.state(), Some(&12));
+  assert_eq!(state.into_parts(), (Offset::sequence(5), Some(12)));
+}
@@ -0,0 +1,23 @@
+//! Delivery-semantics strategy behind a projection.
+
+use alloc::boxed::Box;
+
+use crate::{
+  projection::{ProjectionCommitter, ProjectionFuture},
+  query::Offset,
+};
+
+/// Offset handling shared by every projection delivery mode.
+pub(crate) trait ProjectionStrategy: Send + Sync {
+  /// Loads the offset the projection resumes after.
+  fn load_offset(&self) -> ProjectionFuture<Option<Offset>>;
+
+  /// Replaces the stored offset, or clears it when `offset` is `None`.
+  fn reset_offset(&self, offset: Option<Offset>) -> ProjectionFuture<()>;
+
+  /// Returns how many envelopes are committed together.
+  fn batch_size(&self) -> usize;
+
+  /// Creates the committer used by one materialized projection stream.
+  fn committer(&self) -> Box<dyn ProjectionCommitter>;
+}
@@ -0,0 +1,72 @@
+//! Wake-up flag driving an in-flight exactly-once upsert.
+
+#[cfg(test)]
+#[path = "upsert_wake_flag_test.rs"]
+mod tests;
+
+use core::{
+  sync::atomic::{AtomicBool, Ordering},
+  task::{RawWaker, RawWakerVTable, Waker},
+};
+
+use fraktor_utils_core_rs::sync::ArcShared;
+
+/// Records whether the upsert future asked to be polled again.
+///
+/// The flag starts raised so the first commit polls the future. Afterwards the committer only polls
+/// again once the future woke the [`waker`](Self::waker).
+pub(crate) struct UpsertWakeFlag {
+  woken: ArcShared<AtomicBool>,
+}
+
+impl UpsertWakeFlag {
+  /// Creates a raised flag.
+  pub(crate) fn new() -> Self {
+    Self { woken: ArcShared::new(AtomicBool::new(true)) }
+  }
+
+  /// Lowers the flag and returns whether it was raised.
+  pub(crate) fn take(&self) -> bool {
+    // thumbv6m には swap が無いため load と store で下げる。wake が割り込んでも再度立つだけで済む。
+    if !self.woken.load(Ordering::Acquire) {
+      return false;
+    }
+    self.woken.store(false, Ordering::Release);
+    true
+  }
+
+  /// Returns a waker raising this flag.
+  pub(crate) fn waker(&self) -> Waker {
+    unsafe { Waker::from_raw(raw_waker(self.woken.clone())) }
+  }
+}
+
+const VTABLE: RawWakerVTable = RawWakerVTable::new(clone_waker, wake, wake_by_ref, drop_waker);
+
+fn raw_waker(woken: ArcShared<AtomicBool>) -> RawWaker {
+  RawWaker::new(ArcShared::into_raw(woken).cast::<()>(), &VTABLE)
+}
+
+unsafe fn clone_waker(ptr: *const ()) -> RawWaker {
+  let woken = unsafe { ArcShared::from_raw(ptr.cast::<AtomicBool>()) };
+  let cloned = woken.clone();
+  // 元の参照は呼び出し側の RawWaker が所有し続けるので、カウントを戻さずに生ポインタへ返す。
+  let _raw = ArcShared::into_raw(woken);
+  raw_waker(cloned)
+}
+
+unsafe fn wake(ptr: *const ()) {
+  let woken = unsafe { ArcShared::from_raw(ptr.cast::<AtomicBool>()) };
+  woken.store(true, Ordering::Release);
+}
+
+unsafe fn wake_by_ref(ptr: *const ()) {
+  let woken = unsafe { ArcShared::from_raw(ptr.cast::<AtomicBool>()) };
+  woken.store(true, Ordering::Release);
+  // 所有権は呼び出し側に残るので、解放せずに生ポインタへ戻す。
+  let _raw = ArcShared::into_raw(woken);
+}
+
+unsafe fn drop_waker(ptr: *const ()) {
+  drop(unsafe { ArcShared::from_raw(ptr.cast::<AtomicBool>()) });
+}
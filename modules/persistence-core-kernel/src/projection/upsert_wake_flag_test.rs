@@ -0,0 +1,25 @@
+use super::UpsertWakeFlag;
+
+#[test]
+fn flag_starts_raised_and_is_lowered_by_take() {
+  let flag = UpsertWakeFlag::new();
+
+  assert!(flag.take());
+  assert!(!flag.take());
+}
+
+#[test]
+fn waker_and_its_clones_raise_the_flag() {
+  let flag = UpsertWakeFlag::new();
+  assert!(flag.take());
+  let waker = flag.waker();
+  let cloned = waker.clone();
+
+  waker.wake_by_ref();
+  assert!(flag.take());
+
+  cloned.wake();
+  assert!(flag.take());
+  drop(waker);
+  assert!(!flag.take());
+}
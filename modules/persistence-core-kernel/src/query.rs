@@ -0,0 +1,35 @@
+//! Persistence query package.
+//!
+//! Read journals expose journal contents as stream
+//! [`Source`](fraktor_stream_core_kernel_rs::dsl::Source)s for building projections and read
+//! models.
+
+mod current_events_by_persistence_id_query;
+mod current_events_by_tag_query;
+mod current_persistence_ids_query;
+mod event_envelope;
+mod events_by_persistence_id_query;
+mod events_by_persistence_id_source_logic;
+mod events_by_tag_query;
+mod events_by_tag_source_logic;
+mod offset;
+mod persistence_ids_query;
+mod persistence_ids_source_logic;
+mod polling_read_journal;
+mod queryable_journal;
+mod read_journal;
+mod refresh_schedule;
+mod timestamp_offset;
+
+pub use current_events_by_persistence_id_query::CurrentEventsByPersistenceIdQuery;
+pub use current_events_by_tag_query::CurrentEventsByTagQuery;
+pub use current_persistence_ids_query::CurrentPersistenceIdsQuery;
+pub use event_envelope::EventEnvelope;
+pub use events_by_persistence_id_query::EventsByPersistenceIdQuery;
+pub use events_by_tag_query::EventsByTagQuery;
+pub use offset::Offset;
+pub use persistence_ids_query::PersistenceIdsQuery;
+pub use polling_read_journal::PollingReadJournal;
+pub use queryable_journal::QueryableJournal;
+pub use read_journal::ReadJournal;
+pub use timestamp_offset::TimestampOffset;
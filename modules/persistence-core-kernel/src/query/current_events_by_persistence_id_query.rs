@@ -0,0 +1,16 @@
+//! Current-only events-by-persistence-id query.
+
+use fraktor_stream_core_kernel_rs::{dsl::Source, materialization::StreamNotUsed};
+
+use crate::query::{EventEnvelope, ReadJournal};
+
+/// Current-only query for the events of one persistence id.
+pub trait CurrentEventsByPersistenceIdQuery: ReadJournal {
+  /// Streams the currently stored events in `[from_sequence_nr, to_sequence_nr]` and completes.
+  fn current_events_by_persistence_id(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+  ) -> Source<EventEnvelope, StreamNotUsed>;
+}
@@ -0,0 +1,11 @@
+//! Current-only events-by-tag query.
+
+use fraktor_stream_core_kernel_rs::{dsl::Source, materialization::StreamNotUsed};
+
+use crate::query::{EventEnvelope, Offset, ReadJournal};
+
+/// Current-only query for events written with a [`Tagged`](crate::journal::Tagged) payload.
+pub trait CurrentEventsByTagQuery: ReadJournal {
+  /// Streams the currently stored events carrying `tag` after `offset` and completes.
+  fn current_events_by_tag(&self, tag: &str, offset: Offset) -> Source<EventEnvelope, StreamNotUsed>;
+}
@@ -0,0 +1,13 @@
+//! Current-only persistence ids query.
+
+use alloc::string::String;
+
+use fraktor_stream_core_kernel_rs::{dsl::Source, materialization::StreamNotUsed};
+
+use crate::query::ReadJournal;
+
+/// Current-only query for the persistence ids known to the journal.
+pub trait CurrentPersistenceIdsQuery: ReadJournal {
+  /// Streams the currently known persistence ids and completes.
+  fn current_persistence_ids(&self) -> Source<String, StreamNotUsed>;
+}
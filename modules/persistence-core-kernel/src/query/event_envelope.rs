@@ -0,0 +1,130 @@
+//! Event envelope emitted by query streams.
+
+use alloc::{collections::BTreeSet, string::String};
+use core::{
+  any::Any,
+  fmt::{Debug, Formatter, Result as FmtResult},
+};
+
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{journal::Tagged, persistent::PersistentRepr, query::Offset};
+
+/// Event read from the journal together with its query offset.
+#[derive(Clone)]
+pub struct EventEnvelope {
+  offset:         Offset,
+  persistence_id: String,
+  sequence_nr:    u64,
+  event:          ArcShared<dyn Any + Send + Sync>,
+  timestamp:      u64,
+  tags:           BTreeSet<String>,
+  metadata:       Option<ArcShared<dyn Any + Send + Sync>>,
+}
+
+impl EventEnvelope {
+  /// Creates a new event envelope.
+  #[must_use]
+  pub fn new(
+    offset: Offset,
+    persistence_id: impl Into<String>,
+    sequence_nr: u64,
+    event: ArcShared<dyn Any + Send + Sync>,
+    timestamp: u64,
+  ) -> Self {
+    Self {
+      offset,
+      persistence_id: persistence_id.into(),
+      sequence_nr,
+      event,
+      timestamp,
+      tags: BTreeSet::new(),
+      metadata: None,
+    }
+  }
+
+  /// Creates an envelope from a journal representation, unwrapping [`Tagged`] payloads.
+  pub(crate) fn from_repr(offset: Offset, repr: &PersistentRepr) -> Self {
+    let mut envelope =
+      Self::new(offset, repr.persistence_id(), repr.sequence_nr(), repr.payload().clone(), repr.timestamp());
+    envelope.metadata = repr.metadata().cloned();
+    match repr.downcast_ref::<Tagged>() {
+      | Some(tagged) => envelope.with_event(tagged.payload().clone()).with_tags(tagged.tags().clone()),
+      | None => envelope,
+    }
+  }
+
+  fn with_event(mut self, event: ArcShared<dyn Any + Send + Sync>) -> Self {
+    self.event = event;
+    self
+  }
+
+  /// Returns a copy with the provided tags.
+  #[must_use]
+  pub fn with_tags(mut self, tags: BTreeSet<String>) -> Self {
+    self.tags = tags;
+    self
+  }
+
+  /// Returns the query offset of this event.
+  #[must_use]
+  pub const fn offset(&self) -> &Offset {
+    &self.offset
+  }
+
+  /// Returns the persistence id.
+  #[must_use]
+  pub fn persistence_id(&self) -> &str {
+    &self.persistence_id
+  }
+
+  /// Returns the sequence number.
+  #[must_use]
+  pub const fn sequence_nr(&self) -> u64 {
+    self.sequence_nr
+  }
+
+  /// Returns the event payload.
+  #[must_use]
+  pub const fn event(&self) -> &ArcShared<dyn Any + Send + Sync> {
+    &self.event
+  }
+
+  /// Returns the write timestamp.
+  #[must_use]
+  pub const fn timestamp(&self) -> u64 {
+    self.timestamp
+  }
+
+  /// Returns the tags the event was written with.
+  #[must_use]
+  pub const fn tags(&self) -> &BTreeSet<String> {
+    &self.tags
+  }
+
+  /// Returns the metadata the event was written with, if any.
+  #[must_use]
+  pub const fn metadata(&self) -> Option<&ArcShared<dyn Any + Send + Sync>> {
+    self.metadata.as_ref()
+  }
+
+  /// Attempts to downcast the event payload to the requested type.
+  #[must_use]
+  pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+    self.event.downcast_ref::<T>()
+  }
+}
+
+impl Debug for EventEnvelope {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("EventEnvelope")
+      .field("offset", &self.offset)
+      .field("persistence_id", &self.persistence_id)
+      .field("sequence_nr", &self.sequence_nr)
+      .field("timestamp", &self.timestamp)
+      .field("tags", &self.tags)
+      .field("has_metadata", &self.metadata.is_some())
+      .field("event", &"<any>")
+      .finish()
+  }
+}
@@ -0,0 +1,18 @@
+//! Live events-by-persistence-id query.
+
+use fraktor_stream_core_kernel_rs::{dsl::Source, materialization::StreamNotUsed};
+
+use crate::query::{EventEnvelope, ReadJournal};
+
+/// Live query for the events of one persistence id.
+pub trait EventsByPersistenceIdQuery: ReadJournal {
+  /// Streams events in `[from_sequence_nr, to_sequence_nr]` and keeps polling for new events.
+  ///
+  /// The stream completes once `to_sequence_nr` has been emitted.
+  fn events_by_persistence_id(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+  ) -> Source<EventEnvelope, StreamNotUsed>;
+}
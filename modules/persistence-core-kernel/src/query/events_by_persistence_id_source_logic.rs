@@ -0,0 +1,116 @@
+//! Source logic polling the events of one persistence id.
+
+#[cfg(test)]
+#[path = "events_by_persistence_id_source_logic_test.rs"]
+mod tests;
+
+use alloc::{boxed::Box, collections::VecDeque, format, string::String};
+
+use fraktor_stream_core_kernel_rs::{DynValue, SourceLogic, StreamError};
+
+use super::refresh_schedule::RefreshSchedule;
+use crate::{
+  journal::JournalError,
+  query::{EventEnvelope, Offset, QueryableJournal},
+};
+
+/// Pages through one persistence id, optionally polling for new events once caught up.
+///
+/// A current query stops at the highest sequence number stored when it is first pulled.
+pub(crate) struct EventsByPersistenceIdSourceLogic<J> {
+  journal:          J,
+  persistence_id:   String,
+  next_sequence_nr: u64,
+  to_sequence_nr:   u64,
+  page_size:        u64,
+  live:             bool,
+  bounded:          bool,
+  caught_up:        bool,
+  refresh:          RefreshSchedule,
+  buffer:           VecDeque<EventEnvelope>,
+}
+
+impl<J: QueryableJournal> EventsByPersistenceIdSourceLogic<J> {
+  pub(crate) const fn new(
+    journal: J,
+    persistence_id: String,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    page_size: u64,
+    refresh_interval_ticks: u32,
+    live: bool,
+  ) -> Self {
+    Self {
+      journal,
+      persistence_id,
+      next_sequence_nr: from_sequence_nr,
+      to_sequence_nr,
+      page_size,
+      live,
+      bounded: false,
+      caught_up: false,
+      refresh: RefreshSchedule::new(refresh_interval_ticks),
+      buffer: VecDeque::new(),
+    }
+  }
+
+  fn bound_to_highest_stored(&mut self) -> Result<(), StreamError> {
+    let highest = self.journal.highest_event_sequence_nr(&self.persistence_id).map_err(|error| query_error(&error))?;
+    self.to_sequence_nr = self.to_sequence_nr.min(highest);
+    self.bounded = true;
+    Ok(())
+  }
+
+  const fn should_fetch(&mut self) -> bool {
+    if !self.caught_up {
+      return true;
+    }
+    self.live && self.refresh.is_due()
+  }
+
+  fn fetch_page(&mut self) -> Result<(), StreamError> {
+    let page = self
+      .journal
+      .events_by_persistence_id(&self.persistence_id, self.next_sequence_nr, self.to_sequence_nr, self.page_size)
+      .map_err(|error| query_error(&error))?;
+    self.caught_up = (page.len() as u64) < self.page_size;
+    for repr in page {
+      self.next_sequence_nr = repr.sequence_nr().saturating_add(1);
+      if repr.deleted() {
+        continue;
+      }
+      self.buffer.push_back(EventEnvelope::from_repr(Offset::Sequence(repr.sequence_nr()), &repr));
+    }
+    Ok(())
+  }
+
+  const fn reached_end(&self) -> bool {
+    self.next_sequence_nr > self.to_sequence_nr
+  }
+}
+
+impl<J: QueryableJournal> SourceLogic for EventsByPersistenceIdSourceLogic<J> {
+  fn pull(&mut self) -> Result<Option<DynValue>, StreamError> {
+    if !self.live && !self.bounded {
+      self.bound_to_highest_stored()?;
+    }
+    if self.buffer.is_empty() && !self.reached_end() && self.should_fetch() {
+      self.fetch_page()?;
+    }
+    if let Some(envelope) = self.buffer.pop_front() {
+      return Ok(Some(Box::new(envelope)));
+    }
+    if self.reached_end() || !self.live && self.caught_up {
+      return Ok(None);
+    }
+    Err(StreamError::WouldBlock)
+  }
+
+  fn should_drain_on_shutdown(&self) -> bool {
+    !self.live
+  }
+}
+
+pub(crate) fn query_error(error: &JournalError) -> StreamError {
+  StreamError::failed_typed::<JournalError>(format!("persistence query failed: {error}"))
+}
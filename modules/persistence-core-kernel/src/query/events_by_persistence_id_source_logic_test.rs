@@ -0,0 +1,132 @@
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::{
+  any::Any,
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+
+use fraktor_stream_core_kernel_rs::{DynValue, SourceLogic, StreamError};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::EventsByPersistenceIdSourceLogic;
+use crate::{
+  journal::{Journal, SharedInMemoryJournal},
+  persistent::{AtomicWrite, PersistentRepr},
+  query::{EventEnvelope, Offset, QueryableJournal},
+};
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was pending"),
+  }
+}
+
+fn write_events(journal: &mut SharedInMemoryJournal, persistence_id: &str, from: u64, to: u64) {
+  let reprs = (from..=to)
+    .map(|sequence_nr| {
+      let payload: ArcShared<dyn Any + Send + Sync> = ArcShared::new(sequence_nr as i32);
+      PersistentRepr::new(persistence_id, sequence_nr, payload)
+    })
+    .collect();
+  let write = AtomicWrite::new(reprs).expect("atomic write");
+  poll_ready(journal.write_messages(&[write])).expect("write events");
+}
+
+fn envelope(value: Option<DynValue>) -> EventEnvelope {
+  *value.expect("element").downcast::<EventEnvelope>().expect("event envelope")
+}
+
+fn drain_sequence_nrs<J: QueryableJournal>(logic: &mut EventsByPersistenceIdSourceLogic<J>) -> Vec<u64> {
+  let mut sequence_nrs = Vec::new();
+  while let Ok(Some(value)) = logic.pull() {
+    sequence_nrs.push(envelope(Some(value)).sequence_nr());
+  }
+  sequence_nrs
+}
+
+#[test]
+fn current_query_pages_through_stored_events_and_completes() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_events(&mut journal, "pid-1", 1, 5);
+  let mut logic = EventsByPersistenceIdSourceLogic::new(journal, String::from("pid-1"), 2, u64::MAX, 2, 1, false);
+
+  assert_eq!(drain_sequence_nrs(&mut logic), vec![2, 3, 4, 5]);
+  assert!(matches!(logic.pull(), Ok(None)));
+}
+
+#[test]
+fn current_query_emits_sequence_offsets() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_events(&mut journal, "pid-1", 1, 1);
+  let mut logic = EventsByPersistenceIdSourceLogic::new(journal, String::from("pid-1"), 1, u64::MAX, 8, 1, false);
+
+  let first = envelope(logic.pull().expect("pull"));
+  assert_eq!(first.offset(), &Offset::Sequence(1));
+  assert_eq!(first.downcast_ref::<i32>(), Some(&1));
+}
+
+#[test]
+fn live_query_waits_for_new_events_and_completes_at_upper_bound() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_events(&mut journal, "pid-1", 1, 1);
+  let mut logic = EventsByPersistenceIdSourceLogic::new(journal.clone(), String::from("pid-1"), 1, 3, 8, 1, true);
+
+  assert_eq!(envelope(logic.pull().expect("pull")).sequence_nr(), 1);
+  assert!(matches!(logic.pull(), Err(StreamError::WouldBlock)));
+
+  write_events(&mut journal, "pid-1", 2, 4);
+  assert_eq!(envelope(logic.pull().expect("pull")).sequence_nr(), 2);
+  assert_eq!(envelope(logic.pull().expect("pull")).sequence_nr(), 3);
+  assert!(matches!(logic.pull(), Ok(None)));
+  assert!(!logic.should_drain_on_shutdown());
+}
+
+#[test]
+fn emitted_envelopes_carry_journal_metadata() {
+  let mut journal = SharedInMemoryJournal::new();
+  let payload: ArcShared<dyn Any + Send + Sync> = ArcShared::new(1_i32);
+  let metadata: ArcShared<dyn Any + Send + Sync> = ArcShared::new(String::from("origin-a"));
+  let write =
+    AtomicWrite::new(vec![PersistentRepr::new("pid-1", 1, payload).with_metadata(metadata)]).expect("atomic write");
+  poll_ready(journal.write_messages(&[write])).expect("write events");
+  let mut logic = EventsByPersistenceIdSourceLogic::new(journal, String::from("pid-1"), 1, u64::MAX, 2, 1, false);
+
+  let envelope = envelope(logic.pull().expect("pull"));
+
+  assert_eq!(
+    envelope.metadata().and_then(|metadata| metadata.downcast_ref::<String>()),
+    Some(&String::from("origin-a"))
+  );
+}
+
+#[test]
+fn current_query_stops_at_highest_sequence_nr_stored_on_first_pull() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_events(&mut journal, "pid-1", 1, 2);
+  let mut logic =
+    EventsByPersistenceIdSourceLogic::new(journal.clone(), String::from("pid-1"), 1, u64::MAX, 1, 1, false);
+
+  assert_eq!(envelope(logic.pull().expect("pull")).sequence_nr(), 1);
+  write_events(&mut journal, "pid-1", 3, 4);
+
+  assert_eq!(drain_sequence_nrs(&mut logic), vec![2]);
+  assert!(matches!(logic.pull(), Ok(None)));
+}
+
+#[test]
+fn live_query_reads_storage_once_per_refresh_interval() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_events(&mut journal, "pid-1", 1, 1);
+  let mut logic =
+    EventsByPersistenceIdSourceLogic::new(journal.clone(), String::from("pid-1"), 1, u64::MAX, 8, 3, true);
+
+  assert_eq!(envelope(logic.pull().expect("pull")).sequence_nr(), 1);
+  write_events(&mut journal, "pid-1", 2, 2);
+
+  assert!(matches!(logic.pull(), Err(StreamError::WouldBlock)));
+  assert!(matches!(logic.pull(), Err(StreamError::WouldBlock)));
+  assert_eq!(envelope(logic.pull().expect("pull")).sequence_nr(), 2);
+}
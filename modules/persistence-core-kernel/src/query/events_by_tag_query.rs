@@ -0,0 +1,12 @@
+//! Live events-by-tag query.
+
+use fraktor_stream_core_kernel_rs::{dsl::Source, materialization::StreamNotUsed};
+
+use crate::query::{EventEnvelope, Offset, ReadJournal};
+
+/// Live query for events written with a [`Tagged`](crate::journal::Tagged) payload.
+pub trait EventsByTagQuery: ReadJournal {
+  /// Streams events carrying `tag` after `offset` in journal write order and keeps polling for new
+  /// events.
+  fn events_by_tag(&self, tag: &str, offset: Offset) -> Source<EventEnvelope, StreamNotUsed>;
+}
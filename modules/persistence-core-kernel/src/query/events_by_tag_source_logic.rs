@@ -0,0 +1,128 @@
+//! Source logic polling tagged events in journal write order.
+
+#[cfg(test)]
+#[path = "events_by_tag_source_logic_test.rs"]
+mod tests;
+
+use alloc::{boxed::Box, collections::VecDeque, string::String};
+
+use fraktor_stream_core_kernel_rs::{DynValue, SourceLogic, StreamError};
+
+use super::{events_by_persistence_id_source_logic::query_error, refresh_schedule::RefreshSchedule};
+use crate::{
+  journal::Tagged,
+  persistent::PersistentRepr,
+  query::{EventEnvelope, Offset, QueryableJournal, TimestampOffset},
+};
+
+/// Pages through the journal-wide event order, emitting events tagged with one tag.
+///
+/// A current query stops at the highest ordering stored when it is first pulled.
+pub(crate) struct EventsByTagSourceLogic<J> {
+  journal:          J,
+  tag:              String,
+  after_ordering:   u64,
+  upper_ordering:   Option<u64>,
+  timestamp_offset: Option<TimestampOffset>,
+  page_size:        u64,
+  live:             bool,
+  caught_up:        bool,
+  refresh:          RefreshSchedule,
+  buffer:           VecDeque<EventEnvelope>,
+}
+
+impl<J: QueryableJournal> EventsByTagSourceLogic<J> {
+  pub(crate) fn new(
+    journal: J,
+    tag: String,
+    offset: Offset,
+    page_size: u64,
+    refresh_interval_ticks: u32,
+    live: bool,
+  ) -> Self {
+    let (after_ordering, timestamp_offset) = match offset {
+      | Offset::NoOffset => (0, None),
+      | Offset::Sequence(ordering) => (ordering, None),
+      | Offset::Timestamp(timestamp_offset) => (0, Some(timestamp_offset)),
+    };
+    Self {
+      journal,
+      tag,
+      after_ordering,
+      upper_ordering: None,
+      timestamp_offset,
+      page_size,
+      live,
+      caught_up: false,
+      refresh: RefreshSchedule::new(refresh_interval_ticks),
+      buffer: VecDeque::new(),
+    }
+  }
+
+  fn fetch_page(&mut self) -> Result<(), StreamError> {
+    let page =
+      self.journal.events_by_ordering(self.after_ordering, self.page_size).map_err(|error| query_error(&error))?;
+    self.caught_up = (page.len() as u64) < self.page_size;
+    for (ordering, repr) in page {
+      if self.upper_ordering.is_some_and(|upper| ordering > upper) {
+        self.caught_up = true;
+        break;
+      }
+      self.after_ordering = ordering;
+      if repr.deleted() || !repr.downcast_ref::<Tagged>().is_some_and(|tagged| tagged.contains_tag(&self.tag)) {
+        continue;
+      }
+      if let Some(offset) = self.next_offset(ordering, &repr) {
+        self.buffer.push_back(EventEnvelope::from_repr(offset, &repr));
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns the offset to emit for `repr`, or `None` when a timestamp offset already covers it.
+  fn next_offset(&mut self, ordering: u64, repr: &PersistentRepr) -> Option<Offset> {
+    let Some(current) = self.timestamp_offset.take() else {
+      return Some(Offset::Sequence(ordering));
+    };
+    if !current.precedes(repr.timestamp(), repr.persistence_id(), repr.sequence_nr()) {
+      self.timestamp_offset = Some(current);
+      return None;
+    }
+    let next = current.advance(repr.timestamp(), repr.persistence_id(), repr.sequence_nr());
+    self.timestamp_offset = Some(next.clone());
+    Some(Offset::Timestamp(next))
+  }
+
+  fn reached_upper(&self) -> bool {
+    self.upper_ordering.is_some_and(|upper| self.after_ordering >= upper)
+  }
+
+  const fn should_fetch(&mut self) -> bool {
+    if !self.caught_up {
+      return true;
+    }
+    self.live && self.refresh.is_due()
+  }
+}
+
+impl<J: QueryableJournal> SourceLogic for EventsByTagSourceLogic<J> {
+  fn pull(&mut self) -> Result<Option<DynValue>, StreamError> {
+    if !self.live && self.upper_ordering.is_none() {
+      self.upper_ordering = Some(self.journal.highest_ordering().map_err(|error| query_error(&error))?);
+    }
+    if self.buffer.is_empty() && !self.reached_upper() && self.should_fetch() {
+      self.fetch_page()?;
+    }
+    if let Some(envelope) = self.buffer.pop_front() {
+      return Ok(Some(Box::new(envelope)));
+    }
+    if !self.live && (self.caught_up || self.reached_upper()) {
+      return Ok(None);
+    }
+    Err(StreamError::WouldBlock)
+  }
+
+  fn should_drain_on_shutdown(&self) -> bool {
+    !self.live
+  }
+}
@@ -0,0 +1,132 @@
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::{
+  any::Any,
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+
+use fraktor_stream_core_kernel_rs::{DynValue, SourceLogic, StreamError};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::EventsByTagSourceLogic;
+use crate::{
+  journal::{Journal, SharedInMemoryJournal, Tagged},
+  persistent::{AtomicWrite, PersistentRepr},
+  query::{EventEnvelope, Offset, QueryableJournal, TimestampOffset},
+};
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was pending"),
+  }
+}
+
+fn write_tagged(
+  journal: &mut SharedInMemoryJournal,
+  persistence_id: &str,
+  sequence_nr: u64,
+  tag: &str,
+  timestamp: u64,
+) {
+  let event: ArcShared<dyn Any + Send + Sync> = ArcShared::new(sequence_nr as i32);
+  let payload: ArcShared<dyn Any + Send + Sync> = ArcShared::new(Tagged::with_tags(event, [tag]));
+  let repr = PersistentRepr::new(persistence_id, sequence_nr, payload).with_timestamp(timestamp);
+  let write = AtomicWrite::new(vec![repr]).expect("atomic write");
+  poll_ready(journal.write_messages(&[write])).expect("write event");
+}
+
+fn envelope(value: Option<DynValue>) -> EventEnvelope {
+  *value.expect("element").downcast::<EventEnvelope>().expect("event envelope")
+}
+
+fn drain<J: QueryableJournal>(logic: &mut EventsByTagSourceLogic<J>) -> Vec<EventEnvelope> {
+  let mut envelopes = Vec::new();
+  while let Ok(Some(value)) = logic.pull() {
+    envelopes.push(envelope(Some(value)));
+  }
+  envelopes
+}
+
+fn seeded_journal() -> SharedInMemoryJournal {
+  let mut journal = SharedInMemoryJournal::new();
+  write_tagged(&mut journal, "pid-1", 1, "blue", 10);
+  write_tagged(&mut journal, "pid-2", 1, "red", 10);
+  write_tagged(&mut journal, "pid-1", 2, "blue", 20);
+  write_tagged(&mut journal, "pid-2", 2, "blue", 20);
+  journal
+}
+
+#[test]
+fn current_events_by_tag_follow_journal_order_with_sequence_offsets() {
+  let mut logic = EventsByTagSourceLogic::new(seeded_journal(), String::from("blue"), Offset::NoOffset, 2, 1, false);
+
+  let envelopes = drain(&mut logic);
+  let offsets: Vec<_> = envelopes.iter().map(|envelope| envelope.offset().clone()).collect();
+  assert_eq!(offsets, vec![Offset::Sequence(1), Offset::Sequence(3), Offset::Sequence(4)]);
+  assert_eq!(envelopes[0].downcast_ref::<i32>(), Some(&1));
+  assert!(envelopes[0].tags().contains("blue"));
+  assert!(matches!(logic.pull(), Ok(None)));
+}
+
+#[test]
+fn events_by_tag_resumes_after_sequence_offset() {
+  let mut logic = EventsByTagSourceLogic::new(seeded_journal(), String::from("blue"), Offset::Sequence(3), 8, 1, false);
+
+  let envelopes = drain(&mut logic);
+  assert_eq!(envelopes.len(), 1);
+  assert_eq!(envelopes[0].persistence_id(), "pid-2");
+}
+
+#[test]
+fn events_by_tag_resumes_after_timestamp_offset() {
+  let offset = Offset::Timestamp(TimestampOffset::new(20).with_seen("pid-1", 2));
+  let mut logic = EventsByTagSourceLogic::new(seeded_journal(), String::from("blue"), offset, 8, 1, false);
+
+  let envelopes = drain(&mut logic);
+  assert_eq!(envelopes.len(), 1);
+  assert_eq!(envelopes[0].persistence_id(), "pid-2");
+  let expected = TimestampOffset::new(20).with_seen("pid-1", 2).with_seen("pid-2", 2);
+  assert_eq!(envelopes[0].offset(), &Offset::Timestamp(expected));
+}
+
+#[test]
+fn live_events_by_tag_polls_for_new_events() {
+  let mut journal = seeded_journal();
+  let mut logic = EventsByTagSourceLogic::new(journal.clone(), String::from("red"), Offset::NoOffset, 8, 1, true);
+
+  assert_eq!(envelope(logic.pull().expect("pull")).persistence_id(), "pid-2");
+  assert!(matches!(logic.pull(), Err(StreamError::WouldBlock)));
+
+  write_tagged(&mut journal, "pid-3", 1, "red", 30);
+  let next = envelope(logic.pull().expect("pull"));
+  assert_eq!(next.persistence_id(), "pid-3");
+  assert_eq!(next.offset(), &Offset::Sequence(5));
+}
+
+#[test]
+fn current_events_by_tag_stop_at_highest_ordering_stored_on_first_pull() {
+  let mut journal = seeded_journal();
+  let mut logic = EventsByTagSourceLogic::new(journal.clone(), String::from("blue"), Offset::NoOffset, 2, 1, false);
+
+  assert_eq!(envelope(logic.pull().expect("pull")).sequence_nr(), 1);
+  write_tagged(&mut journal, "pid-3", 1, "blue", 30);
+
+  let rest: Vec<String> = drain(&mut logic).iter().map(|envelope| String::from(envelope.persistence_id())).collect();
+  assert_eq!(rest, vec![String::from("pid-1"), String::from("pid-2")]);
+  assert!(matches!(logic.pull(), Ok(None)));
+}
+
+#[test]
+fn live_events_by_tag_read_storage_once_per_refresh_interval() {
+  let mut journal = seeded_journal();
+  let mut logic = EventsByTagSourceLogic::new(journal.clone(), String::from("red"), Offset::NoOffset, 8, 2, true);
+
+  assert_eq!(envelope(logic.pull().expect("pull")).persistence_id(), "pid-2");
+  write_tagged(&mut journal, "pid-3", 1, "red", 30);
+
+  assert!(matches!(logic.pull(), Err(StreamError::WouldBlock)));
+  assert_eq!(envelope(logic.pull().expect("pull")).persistence_id(), "pid-3");
+}
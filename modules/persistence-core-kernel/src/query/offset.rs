@@ -0,0 +1,33 @@
+//! Query offsets.
+
+#[cfg(test)]
+#[path = "offset_test.rs"]
+mod tests;
+
+use crate::query::TimestampOffset;
+
+/// Position in a query stream from which a consumer resumes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Offset {
+  /// Start from the beginning of the stream.
+  #[default]
+  NoOffset,
+  /// Journal-wide ordering or sequence number of the last processed event.
+  Sequence(u64),
+  /// Write timestamp of the last processed events.
+  Timestamp(TimestampOffset),
+}
+
+impl Offset {
+  /// Creates a sequence offset.
+  #[must_use]
+  pub const fn sequence(value: u64) -> Self {
+    Self::Sequence(value)
+  }
+
+  /// Creates a timestamp offset without any seen events.
+  #[must_use]
+  pub const fn timestamp(timestamp: u64) -> Self {
+    Self::Timestamp(TimestampOffset::new(timestamp))
+  }
+}
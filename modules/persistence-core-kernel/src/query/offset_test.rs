@@ -0,0 +1,12 @@
+use crate::query::{Offset, TimestampOffset};
+
+#[test]
+fn offset_defaults_to_no_offset() {
+  assert_eq!(Offset::default(), Offset::NoOffset);
+}
+
+#[test]
+fn offset_constructors_build_matching_variants() {
+  assert_eq!(Offset::sequence(7), Offset::Sequence(7));
+  assert_eq!(Offset::timestamp(42), Offset::Timestamp(TimestampOffset::new(42)));
+}
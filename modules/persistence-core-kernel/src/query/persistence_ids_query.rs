@@ -0,0 +1,13 @@
+//! Live persistence ids query.
+
+use alloc::string::String;
+
+use fraktor_stream_core_kernel_rs::{dsl::Source, materialization::StreamNotUsed};
+
+use crate::query::ReadJournal;
+
+/// Live query for the persistence ids known to the journal.
+pub trait PersistenceIdsQuery: ReadJournal {
+  /// Streams every persistence id once and keeps polling for newly created ones.
+  fn persistence_ids(&self) -> Source<String, StreamNotUsed>;
+}
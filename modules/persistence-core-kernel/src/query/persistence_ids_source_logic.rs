@@ -0,0 +1,74 @@
+//! Source logic polling the known persistence ids.
+
+#[cfg(test)]
+#[path = "persistence_ids_source_logic_test.rs"]
+mod tests;
+
+use alloc::{boxed::Box, collections::BTreeSet, string::String, vec::Vec};
+
+use fraktor_stream_core_kernel_rs::{DynValue, SourceLogic, StreamError};
+
+use super::{events_by_persistence_id_source_logic::query_error, refresh_schedule::RefreshSchedule};
+use crate::query::QueryableJournal;
+
+/// Emits each persistence id once, optionally polling for newly created ids.
+pub(crate) struct PersistenceIdsSourceLogic<J> {
+  journal: J,
+  live:    bool,
+  fetched: bool,
+  refresh: RefreshSchedule,
+  emitted: BTreeSet<String>,
+  buffer:  Vec<String>,
+}
+
+impl<J: QueryableJournal> PersistenceIdsSourceLogic<J> {
+  pub(crate) const fn new(journal: J, refresh_interval_ticks: u32, live: bool) -> Self {
+    Self {
+      journal,
+      live,
+      fetched: false,
+      refresh: RefreshSchedule::new(refresh_interval_ticks),
+      emitted: BTreeSet::new(),
+      buffer: Vec::new(),
+    }
+  }
+
+  const fn should_fetch(&mut self) -> bool {
+    if !self.fetched {
+      return true;
+    }
+    self.live && self.refresh.is_due()
+  }
+
+  fn fetch(&mut self) -> Result<(), StreamError> {
+    self.fetched = true;
+    let mut fresh: Vec<String> = self
+      .journal
+      .persistence_ids()
+      .map_err(|error| query_error(&error))?
+      .into_iter()
+      .filter(|id| !self.emitted.contains(id))
+      .collect();
+    // 取り出しは末尾からなので、昇順で返すために逆順に積む。
+    fresh.reverse();
+    self.emitted.extend(fresh.iter().cloned());
+    self.buffer = fresh;
+    Ok(())
+  }
+}
+
+impl<J: QueryableJournal> SourceLogic for PersistenceIdsSourceLogic<J> {
+  fn pull(&mut self) -> Result<Option<DynValue>, StreamError> {
+    if self.buffer.is_empty() && self.should_fetch() {
+      self.fetch()?;
+    }
+    if let Some(persistence_id) = self.buffer.pop() {
+      return Ok(Some(Box::new(persistence_id)));
+    }
+    if self.live { Err(StreamError::WouldBlock) } else { Ok(None) }
+  }
+
+  fn should_drain_on_shutdown(&self) -> bool {
+    !self.live
+  }
+}
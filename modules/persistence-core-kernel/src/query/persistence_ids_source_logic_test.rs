@@ -0,0 +1,61 @@
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::{
+  any::Any,
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+
+use fraktor_stream_core_kernel_rs::{SourceLogic, StreamError};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::PersistenceIdsSourceLogic;
+use crate::{
+  journal::{Journal, SharedInMemoryJournal},
+  persistent::{AtomicWrite, PersistentRepr},
+  query::QueryableJournal,
+};
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was pending"),
+  }
+}
+
+fn write_event(journal: &mut SharedInMemoryJournal, persistence_id: &str) {
+  let payload: ArcShared<dyn Any + Send + Sync> = ArcShared::new(1_i32);
+  let write = AtomicWrite::new(vec![PersistentRepr::new(persistence_id, 1, payload)]).expect("atomic write");
+  poll_ready(journal.write_messages(&[write])).expect("write event");
+}
+
+fn next_id<J: QueryableJournal>(logic: &mut PersistenceIdsSourceLogic<J>) -> String {
+  *logic.pull().expect("pull").expect("element").downcast::<String>().expect("string")
+}
+
+#[test]
+fn current_persistence_ids_emit_sorted_ids_once_and_complete() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_event(&mut journal, "pid-b");
+  write_event(&mut journal, "pid-a");
+  let mut logic = PersistenceIdsSourceLogic::new(journal, 1, false);
+
+  let ids: Vec<String> = [next_id(&mut logic), next_id(&mut logic)].into();
+  assert_eq!(ids, vec![String::from("pid-a"), String::from("pid-b")]);
+  assert!(matches!(logic.pull(), Ok(None)));
+}
+
+#[test]
+fn live_persistence_ids_emit_only_new_ids() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_event(&mut journal, "pid-a");
+  let mut logic = PersistenceIdsSourceLogic::new(journal.clone(), 1, true);
+
+  assert_eq!(next_id(&mut logic), "pid-a");
+  assert!(matches!(logic.pull(), Err(StreamError::WouldBlock)));
+
+  write_event(&mut journal, "pid-b");
+  assert_eq!(next_id(&mut logic), "pid-b");
+  assert!(matches!(logic.pull(), Err(StreamError::WouldBlock)));
+}
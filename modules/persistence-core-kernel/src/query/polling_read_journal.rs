@@ -0,0 +1,157 @@
+//! Read journal polling a queryable journal.
+
+#[cfg(test)]
+#[path = "polling_read_journal_test.rs"]
+mod tests;
+
+use alloc::string::{String, ToString};
+
+use fraktor_stream_core_kernel_rs::{dsl::Source, materialization::StreamNotUsed, stage::StageKind};
+
+use super::{
+  events_by_persistence_id_source_logic::EventsByPersistenceIdSourceLogic,
+  events_by_tag_source_logic::EventsByTagSourceLogic, persistence_ids_source_logic::PersistenceIdsSourceLogic,
+};
+use crate::query::{
+  CurrentEventsByPersistenceIdQuery, CurrentEventsByTagQuery, CurrentPersistenceIdsQuery, EventEnvelope,
+  EventsByPersistenceIdQuery, EventsByTagQuery, Offset, PersistenceIdsQuery, QueryableJournal, ReadJournal,
+};
+
+const DEFAULT_PAGE_SIZE: u64 = 256;
+const DEFAULT_REFRESH_INTERVAL_TICKS: u32 = 10;
+
+/// Read journal serving every query from a [`QueryableJournal`].
+///
+/// Sources load at most [`page_size`](Self::page_size) events per storage read. Once caught up,
+/// live sources read the storage again every
+/// [`refresh_interval_ticks`](Self::refresh_interval_ticks) stream ticks. Current sources stop at
+/// the highest offset stored when they are first pulled.
+#[derive(Clone)]
+pub struct PollingReadJournal<J> {
+  journal:                J,
+  page_size:              u64,
+  refresh_interval_ticks: u32,
+}
+
+impl<J: QueryableJournal> PollingReadJournal<J> {
+  /// Creates a read journal over the provided storage handle.
+  #[must_use]
+  pub const fn new(journal: J) -> Self {
+    Self { journal, page_size: DEFAULT_PAGE_SIZE, refresh_interval_ticks: DEFAULT_REFRESH_INTERVAL_TICKS }
+  }
+
+  /// Returns the maximum number of events loaded per storage read.
+  #[must_use]
+  pub const fn page_size(&self) -> u64 {
+    self.page_size
+  }
+
+  /// Returns a copy with a different page size.
+  ///
+  /// A page size of zero is treated as one event per page.
+  #[must_use]
+  pub const fn with_page_size(mut self, page_size: u64) -> Self {
+    self.page_size = if page_size == 0 { 1 } else { page_size };
+    self
+  }
+
+  /// Returns the number of stream ticks a caught-up live source waits between storage reads.
+  #[must_use]
+  pub const fn refresh_interval_ticks(&self) -> u32 {
+    self.refresh_interval_ticks
+  }
+
+  /// Returns a copy with a different live refresh interval.
+  ///
+  /// An interval of zero is treated as one tick, which reads the storage on every idle pull.
+  #[must_use]
+  pub const fn with_refresh_interval_ticks(mut self, refresh_interval_ticks: u32) -> Self {
+    self.refresh_interval_ticks = if refresh_interval_ticks == 0 { 1 } else { refresh_interval_ticks };
+    self
+  }
+
+  fn events_by_persistence_id_source(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    live: bool,
+  ) -> Source<EventEnvelope, StreamNotUsed> {
+    let logic = EventsByPersistenceIdSourceLogic::new(
+      self.journal.clone(),
+      persistence_id.to_string(),
+      from_sequence_nr,
+      to_sequence_nr,
+      self.page_size,
+      self.refresh_interval_ticks,
+      live,
+    );
+    Source::from_logic(StageKind::Custom, logic)
+  }
+
+  fn events_by_tag_source(&self, tag: &str, offset: Offset, live: bool) -> Source<EventEnvelope, StreamNotUsed> {
+    let logic = EventsByTagSourceLogic::new(
+      self.journal.clone(),
+      tag.to_string(),
+      offset,
+      self.page_size,
+      self.refresh_interval_ticks,
+      live,
+    );
+    Source::from_logic(StageKind::Custom, logic)
+  }
+}
+
+impl<J: QueryableJournal> ReadJournal for PollingReadJournal<J> {}
+
+impl<J: QueryableJournal> EventsByPersistenceIdQuery for PollingReadJournal<J> {
+  fn events_by_persistence_id(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+  ) -> Source<EventEnvelope, StreamNotUsed> {
+    self.events_by_persistence_id_source(persistence_id, from_sequence_nr, to_sequence_nr, true)
+  }
+}
+
+impl<J: QueryableJournal> CurrentEventsByPersistenceIdQuery for PollingReadJournal<J> {
+  fn current_events_by_persistence_id(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+  ) -> Source<EventEnvelope, StreamNotUsed> {
+    self.events_by_persistence_id_source(persistence_id, from_sequence_nr, to_sequence_nr, false)
+  }
+}
+
+impl<J: QueryableJournal> EventsByTagQuery for PollingReadJournal<J> {
+  fn events_by_tag(&self, tag: &str, offset: Offset) -> Source<EventEnvelope, StreamNotUsed> {
+    self.events_by_tag_source(tag, offset, true)
+  }
+}
+
+impl<J: QueryableJournal> CurrentEventsByTagQuery for PollingReadJournal<J> {
+  fn current_events_by_tag(&self, tag: &str, offset: Offset) -> Source<EventEnvelope, StreamNotUsed> {
+    self.events_by_tag_source(tag, offset, false)
+  }
+}
+
+impl<J: QueryableJournal> PersistenceIdsQuery for PollingReadJournal<J> {
+  fn persistence_ids(&self) -> Source<String, StreamNotUsed> {
+    Source::from_logic(
+      StageKind::Custom,
+      PersistenceIdsSourceLogic::new(self.journal.clone(), self.refresh_interval_ticks, true),
+    )
+  }
+}
+
+impl<J: QueryableJournal> CurrentPersistenceIdsQuery for PollingReadJournal<J> {
+  fn current_persistence_ids(&self) -> Source<String, StreamNotUsed> {
+    Source::from_logic(
+      StageKind::Custom,
+      PersistenceIdsSourceLogic::new(self.journal.clone(), self.refresh_interval_ticks, false),
+    )
+  }
+}
@@ -0,0 +1,25 @@
+use crate::{journal::SharedInMemoryJournal, query::PollingReadJournal};
+
+#[test]
+fn polling_read_journal_uses_default_page_size() {
+  let read_journal = PollingReadJournal::new(SharedInMemoryJournal::new());
+
+  assert_eq!(read_journal.page_size(), 256);
+}
+
+#[test]
+fn polling_read_journal_clamps_zero_page_size_to_one() {
+  let read_journal = PollingReadJournal::new(SharedInMemoryJournal::new()).with_page_size(0);
+
+  assert_eq!(read_journal.page_size(), 1);
+}
+
+#[test]
+fn polling_read_journal_clamps_zero_refresh_interval_to_one_tick() {
+  let read_journal = PollingReadJournal::new(SharedInMemoryJournal::new());
+  assert_eq!(read_journal.refresh_interval_ticks(), 10);
+
+  let read_journal = read_journal.with_refresh_interval_ticks(0);
+
+  assert_eq!(read_journal.refresh_interval_ticks(), 1);
+}
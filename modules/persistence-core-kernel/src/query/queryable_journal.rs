@@ -0,0 +1,60 @@
+//! Storage port consumed by polling read journals.
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{journal::JournalError, persistent::PersistentRepr};
+
+/// Journal storage that can be read outside of the journal actor.
+///
+/// Implementations are cheap handles sharing the underlying storage, so a read journal observes
+/// writes made through the persistence extension.
+pub trait QueryableJournal: Clone + Send + Sync + 'static {
+  /// Returns up to `max` stored events of `persistence_id` in `[from_sequence_nr, to_sequence_nr]`
+  /// in ascending sequence order.
+  ///
+  /// `max == 0` means unlimited.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JournalError::ReadFailed`] when the storage cannot be read.
+  fn events_by_persistence_id(
+    &self,
+    persistence_id: &str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    max: u64,
+  ) -> Result<Vec<PersistentRepr>, JournalError>;
+
+  /// Returns up to `max` stored events whose journal-wide ordering is greater than
+  /// `after_ordering`, paired with that ordering and in ascending ordering.
+  ///
+  /// Orderings start at 1 and follow write order across all persistence ids. Deleted events are
+  /// skipped but keep their ordering. `max == 0` means unlimited.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JournalError::ReadFailed`] when the storage cannot be read.
+  fn events_by_ordering(&self, after_ordering: u64, max: u64) -> Result<Vec<(u64, PersistentRepr)>, JournalError>;
+
+  /// Returns the journal-wide ordering of the latest written event, or `0` when nothing was
+  /// written.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JournalError::ReadFailed`] when the storage cannot be read.
+  fn highest_ordering(&self) -> Result<u64, JournalError>;
+
+  /// Returns the highest sequence number written for `persistence_id`, or `0` when none exists.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JournalError::ReadFailed`] when the storage cannot be read.
+  fn highest_event_sequence_nr(&self, persistence_id: &str) -> Result<u64, JournalError>;
+
+  /// Returns every persistence id that has been written to.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`JournalError::ReadFailed`] when the storage cannot be read.
+  fn persistence_ids(&self) -> Result<Vec<String>, JournalError>;
+}
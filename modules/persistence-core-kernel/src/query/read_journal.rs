@@ -0,0 +1,8 @@
+//! Read journal marker trait.
+
+/// Marker trait for read journals.
+///
+/// A read journal implements any subset of the query traits in this package, such as
+/// [`EventsByPersistenceIdQuery`](super::EventsByPersistenceIdQuery) or
+/// [`EventsByTagQuery`](super::EventsByTagQuery).
+pub trait ReadJournal: Send + Sync + 'static {}
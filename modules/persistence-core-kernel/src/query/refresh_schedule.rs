@@ -0,0 +1,33 @@
+//! Idle-pull counter spacing out storage reads of live queries.
+
+#[cfg(test)]
+#[path = "refresh_schedule_test.rs"]
+mod tests;
+
+/// Decides when a caught-up live query reads the storage again.
+///
+/// Sources are pulled once per interpreter tick while demand is outstanding, so counting idle pulls
+/// measures the refresh interval in ticks.
+pub(crate) struct RefreshSchedule {
+  interval_ticks: u32,
+  idle_ticks:     u32,
+}
+
+impl RefreshSchedule {
+  /// Creates a schedule reading the storage every `interval_ticks` idle pulls.
+  ///
+  /// An interval of zero is treated as one tick.
+  pub(crate) const fn new(interval_ticks: u32) -> Self {
+    Self { interval_ticks: if interval_ticks == 0 { 1 } else { interval_ticks }, idle_ticks: 0 }
+  }
+
+  /// Counts one idle pull and returns `true` when the storage should be read on this pull.
+  pub(crate) const fn is_due(&mut self) -> bool {
+    self.idle_ticks += 1;
+    if self.idle_ticks < self.interval_ticks {
+      return false;
+    }
+    self.idle_ticks = 0;
+    true
+  }
+}
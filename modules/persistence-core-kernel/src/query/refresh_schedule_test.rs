@@ -0,0 +1,21 @@
+use super::RefreshSchedule;
+
+#[test]
+fn is_due_once_per_interval() {
+  let mut schedule = RefreshSchedule::new(3);
+
+  assert!(!schedule.is_due());
+  assert!(!schedule.is_due());
+  assert!(schedule.is_due());
+  assert!(!schedule.is_due());
+  assert!(!schedule.is_due());
+  assert!(schedule.is_due());
+}
+
+#[test]
+fn zero_interval_is_due_on_every_pull() {
+  let mut schedule = RefreshSchedule::new(0);
+
+  assert!(schedule.is_due());
+  assert!(schedule.is_due());
+}
@@ -0,0 +1,66 @@
+//! Timestamp-based query offset.
+
+#[cfg(test)]
+#[path = "timestamp_offset_test.rs"]
+mod tests;
+
+use alloc::{collections::BTreeMap, string::String};
+use core::cmp::Ordering;
+
+/// Offset based on event write timestamps.
+///
+/// Several events may share one timestamp, so the offset also records the highest sequence number
+/// already seen per persistence id at that timestamp.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimestampOffset {
+  timestamp: u64,
+  seen:      BTreeMap<String, u64>,
+}
+
+impl TimestampOffset {
+  /// Creates an offset at `timestamp` with no seen events.
+  #[must_use]
+  pub const fn new(timestamp: u64) -> Self {
+    Self { timestamp, seen: BTreeMap::new() }
+  }
+
+  /// Returns the timestamp of this offset.
+  #[must_use]
+  pub const fn timestamp(&self) -> u64 {
+    self.timestamp
+  }
+
+  /// Returns the highest seen sequence number per persistence id at [`timestamp`](Self::timestamp).
+  #[must_use]
+  pub const fn seen(&self) -> &BTreeMap<String, u64> {
+    &self.seen
+  }
+
+  /// Returns a copy that records `sequence_nr` as seen for `persistence_id`.
+  #[must_use]
+  pub fn with_seen(mut self, persistence_id: impl Into<String>, sequence_nr: u64) -> Self {
+    let entry = self.seen.entry(persistence_id.into()).or_insert(0);
+    *entry = (*entry).max(sequence_nr);
+    self
+  }
+
+  /// Returns whether this offset precedes an event written at `timestamp`.
+  #[must_use]
+  pub fn precedes(&self, timestamp: u64, persistence_id: &str, sequence_nr: u64) -> bool {
+    match timestamp.cmp(&self.timestamp) {
+      | Ordering::Greater => true,
+      | Ordering::Less => false,
+      | Ordering::Equal => sequence_nr > self.seen.get(persistence_id).copied().unwrap_or(0),
+    }
+  }
+
+  /// Returns the offset after emitting an event written at `timestamp`.
+  #[must_use]
+  pub fn advance(self, timestamp: u64, persistence_id: &str, sequence_nr: u64) -> Self {
+    if timestamp > self.timestamp {
+      Self::new(timestamp).with_seen(persistence_id, sequence_nr)
+    } else {
+      self.with_seen(persistence_id, sequence_nr)
+    }
+  }
+}
@@ -0,0 +1,24 @@
+use crate::query::TimestampOffset;
+
+#[test]
+fn timestamp_offset_precedes_later_events_only() {
+  let offset = TimestampOffset::new(10).with_seen("pid-1", 3);
+
+  assert!(offset.precedes(11, "pid-1", 1));
+  assert!(!offset.precedes(9, "pid-1", 10));
+  assert!(!offset.precedes(10, "pid-1", 3));
+  assert!(offset.precedes(10, "pid-1", 4));
+  assert!(offset.precedes(10, "pid-2", 1));
+}
+
+#[test]
+fn timestamp_offset_advance_resets_seen_on_newer_timestamp() {
+  let offset = TimestampOffset::new(10).with_seen("pid-1", 3).advance(10, "pid-2", 5);
+  assert_eq!(offset.timestamp(), 10);
+  assert_eq!(offset.seen().len(), 2);
+
+  let offset = offset.advance(12, "pid-1", 4);
+  assert_eq!(offset.timestamp(), 12);
+  assert_eq!(offset.seen().get("pid-1"), Some(&4));
+  assert_eq!(offset.seen().len(), 1);
+}
@@ -0,0 +1,13 @@
+//! Replicated event sourcing vocabulary shared by active-active replicas.
+
+mod replica_id;
+mod replicated_event_metadata;
+mod replication_id;
+mod version_vector;
+mod version_vector_ordering;
+
+pub use replica_id::ReplicaId;
+pub use replicated_event_metadata::ReplicatedEventMetadata;
+pub use replication_id::ReplicationId;
+pub use version_vector::VersionVector;
+pub use version_vector_ordering::VersionVectorOrdering;
@@ -0,0 +1,30 @@
+//! Replica identity.
+
+use alloc::string::String;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Identifies one replica of a replicated event-sourced entity.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReplicaId {
+  value: String,
+}
+
+impl ReplicaId {
+  /// Creates a replica id.
+  #[must_use]
+  pub fn new(value: impl Into<String>) -> Self {
+    Self { value: value.into() }
+  }
+
+  /// Returns the replica id as a string slice.
+  #[must_use]
+  pub const fn as_str(&self) -> &str {
+    self.value.as_str()
+  }
+}
+
+impl Display for ReplicaId {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.write_str(&self.value)
+  }
+}
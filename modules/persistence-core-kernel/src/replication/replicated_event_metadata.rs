@@ -0,0 +1,60 @@
+//! Metadata attached to replicated events.
+
+use crate::replication::{ReplicaId, VersionVector};
+
+/// Journal metadata describing where a replicated event originated.
+///
+/// Replicas write this metadata next to every event so that other replicas can filter events by
+/// origin, resume from the last origin sequence number they have seen, and detect concurrent
+/// updates when applying the event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplicatedEventMetadata {
+  origin_replica:     ReplicaId,
+  origin_sequence_nr: u64,
+  version:            VersionVector,
+  concurrent:         bool,
+}
+
+impl ReplicatedEventMetadata {
+  /// Creates metadata for an event written by `origin_replica`.
+  #[must_use]
+  pub const fn new(
+    origin_replica: ReplicaId,
+    origin_sequence_nr: u64,
+    version: VersionVector,
+    concurrent: bool,
+  ) -> Self {
+    Self { origin_replica, origin_sequence_nr, version, concurrent }
+  }
+
+  /// Returns the replica that originally persisted the event.
+  #[must_use]
+  pub const fn origin_replica(&self) -> &ReplicaId {
+    &self.origin_replica
+  }
+
+  /// Returns the sequence number of the event in the origin replica's journal.
+  #[must_use]
+  pub const fn origin_sequence_nr(&self) -> u64 {
+    self.origin_sequence_nr
+  }
+
+  /// Returns the version vector of the origin replica after the event.
+  #[must_use]
+  pub const fn version(&self) -> &VersionVector {
+    &self.version
+  }
+
+  /// Returns true when the event was concurrent with the receiving replica's history.
+  #[must_use]
+  pub const fn concurrent(&self) -> bool {
+    self.concurrent
+  }
+
+  /// Returns a copy with the provided concurrency flag.
+  #[must_use]
+  pub const fn with_concurrent(mut self, concurrent: bool) -> Self {
+    self.concurrent = concurrent;
+    self
+  }
+}
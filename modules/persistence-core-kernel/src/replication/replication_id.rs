@@ -0,0 +1,58 @@
+//! Replicated entity identity.
+
+#[cfg(test)]
+#[path = "replication_id_test.rs"]
+mod tests;
+
+use alloc::{format, string::String};
+
+use crate::replication::ReplicaId;
+
+/// Identifies one replica of a replicated entity.
+///
+/// Every replica writes to its own persistence id, `<entity type>|<entity id>|<replica id>`, so
+/// replicas never contend for sequence numbers in the shared journal.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReplicationId {
+  entity_type: String,
+  entity_id:   String,
+  replica_id:  ReplicaId,
+}
+
+impl ReplicationId {
+  /// Creates a replication id.
+  #[must_use]
+  pub fn new(entity_type: impl Into<String>, entity_id: impl Into<String>, replica_id: ReplicaId) -> Self {
+    Self { entity_type: entity_type.into(), entity_id: entity_id.into(), replica_id }
+  }
+
+  /// Returns the entity type.
+  #[must_use]
+  pub fn entity_type(&self) -> &str {
+    &self.entity_type
+  }
+
+  /// Returns the entity id.
+  #[must_use]
+  pub fn entity_id(&self) -> &str {
+    &self.entity_id
+  }
+
+  /// Returns the replica id.
+  #[must_use]
+  pub const fn replica_id(&self) -> &ReplicaId {
+    &self.replica_id
+  }
+
+  /// Returns the same entity addressed at another replica.
+  #[must_use]
+  pub fn with_replica(&self, replica_id: ReplicaId) -> Self {
+    Self { entity_type: self.entity_type.clone(), entity_id: self.entity_id.clone(), replica_id }
+  }
+
+  /// Returns the persistence id this replica writes its events to.
+  #[must_use]
+  pub fn persistence_id(&self) -> String {
+    format!("{}|{}|{}", self.entity_type, self.entity_id, self.replica_id)
+  }
+}
@@ -0,0 +1,22 @@
+use alloc::string::String;
+
+use crate::replication::{ReplicaId, ReplicationId};
+
+#[test]
+fn persistence_id_includes_entity_and_replica() {
+  let id = ReplicationId::new("counter", "c-1", ReplicaId::new("dc-a"));
+
+  assert_eq!(id.persistence_id(), String::from("counter|c-1|dc-a"));
+}
+
+#[test]
+fn with_replica_keeps_entity_identity() {
+  let id = ReplicationId::new("counter", "c-1", ReplicaId::new("dc-a"));
+
+  let other = id.with_replica(ReplicaId::new("dc-b"));
+
+  assert_eq!(other.entity_type(), "counter");
+  assert_eq!(other.entity_id(), "c-1");
+  assert_eq!(other.replica_id(), &ReplicaId::new("dc-b"));
+  assert_eq!(other.persistence_id(), String::from("counter|c-1|dc-b"));
+}
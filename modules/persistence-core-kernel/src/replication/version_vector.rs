@@ -0,0 +1,115 @@
+//! Version vector keyed by replica id.
+
+#[cfg(test)]
+#[path = "version_vector_test.rs"]
+mod tests;
+
+use alloc::collections::BTreeMap;
+
+use crate::replication::{ReplicaId, VersionVectorOrdering};
+
+/// Version vector tracking how many events of each replica a history contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionVector {
+  versions: BTreeMap<ReplicaId, u64>,
+}
+
+impl VersionVector {
+  /// Creates an empty version vector.
+  #[must_use]
+  pub const fn new() -> Self {
+    Self { versions: BTreeMap::new() }
+  }
+
+  /// Creates a version vector from replica-version entries.
+  ///
+  /// Duplicate replica entries keep the highest version. Zero versions are omitted because absence
+  /// is the canonical zero value.
+  #[must_use]
+  pub fn from_entries(entries: impl IntoIterator<Item = (ReplicaId, u64)>) -> Self {
+    let mut vector = Self::new();
+    for (replica, version) in entries {
+      vector.observe(replica, version);
+    }
+    vector
+  }
+
+  /// Returns true when the vector has no replica versions.
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.versions.is_empty()
+  }
+
+  /// Returns the number of replica versions.
+  #[must_use]
+  pub fn len(&self) -> usize {
+    self.versions.len()
+  }
+
+  /// Returns the version for `replica`, or zero when the replica is absent.
+  #[must_use]
+  pub fn version_at(&self, replica: &ReplicaId) -> u64 {
+    self.versions.get(replica).copied().unwrap_or(0)
+  }
+
+  /// Returns all replica-version entries in deterministic replica order.
+  pub fn entries(&self) -> impl Iterator<Item = (&ReplicaId, u64)> {
+    self.versions.iter().map(|(replica, version)| (replica, *version))
+  }
+
+  /// Returns a vector with the version of `replica` incremented.
+  #[must_use]
+  pub fn increment(&self, replica: &ReplicaId) -> Self {
+    let mut versions = self.versions.clone();
+    let next = self.version_at(replica).saturating_add(1);
+    versions.insert(replica.clone(), next);
+    Self { versions }
+  }
+
+  /// Returns the pointwise maximum of both vectors.
+  #[must_use]
+  pub fn merge(&self, other: &Self) -> Self {
+    let mut merged = self.clone();
+    for (replica, version) in other.entries() {
+      merged.observe(replica.clone(), version);
+    }
+    merged
+  }
+
+  /// Compares this vector with `other` using vector-clock causal ordering.
+  #[must_use]
+  pub fn compare(&self, other: &Self) -> VersionVectorOrdering {
+    let mut has_less = false;
+    let mut has_greater = false;
+    for replica in self.versions.keys().chain(other.versions.keys()) {
+      let left = self.version_at(replica);
+      let right = other.version_at(replica);
+      has_less |= left < right;
+      has_greater |= left > right;
+    }
+    match (has_less, has_greater) {
+      | (false, false) => VersionVectorOrdering::Same,
+      | (true, false) => VersionVectorOrdering::Before,
+      | (false, true) => VersionVectorOrdering::After,
+      | (true, true) => VersionVectorOrdering::Concurrent,
+    }
+  }
+
+  /// Returns true when this vector and `other` contain independent histories.
+  #[must_use]
+  pub fn is_concurrent(&self, other: &Self) -> bool {
+    self.compare(other) == VersionVectorOrdering::Concurrent
+  }
+
+  fn observe(&mut self, replica: ReplicaId, version: u64) {
+    if version > self.version_at(&replica) {
+      self.versions.insert(replica, version);
+    }
+  }
+}
+
+impl Default for VersionVector {
+  fn default() -> Self {
+    Self::new()
+  }
+}
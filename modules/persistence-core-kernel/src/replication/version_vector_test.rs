@@ -0,0 +1,36 @@
+use crate::replication::{ReplicaId, VersionVector, VersionVectorOrdering};
+
+fn replica(name: &str) -> ReplicaId {
+  ReplicaId::new(name)
+}
+
+#[test]
+fn increment_and_merge_track_highest_versions() {
+  let left = VersionVector::new().increment(&replica("a")).increment(&replica("a"));
+  let right = VersionVector::new().increment(&replica("b"));
+
+  let merged = left.merge(&right);
+
+  assert_eq!(merged.version_at(&replica("a")), 2);
+  assert_eq!(merged.version_at(&replica("b")), 1);
+  assert_eq!(merged.len(), 2);
+}
+
+#[test]
+fn from_entries_drops_zero_versions_and_keeps_highest_duplicate() {
+  let vector = VersionVector::from_entries([(replica("a"), 0), (replica("b"), 3), (replica("b"), 1)]);
+
+  assert_eq!(vector.entries().collect::<Vec<_>>(), vec![(&replica("b"), 3)]);
+}
+
+#[test]
+fn compare_detects_causal_and_concurrent_histories() {
+  let base = VersionVector::new().increment(&replica("a"));
+  let after = base.increment(&replica("a"));
+  let concurrent = base.increment(&replica("b"));
+
+  assert_eq!(base.compare(&base.clone()), VersionVectorOrdering::Same);
+  assert_eq!(base.compare(&after), VersionVectorOrdering::Before);
+  assert_eq!(after.compare(&base), VersionVectorOrdering::After);
+  assert!(after.is_concurrent(&concurrent));
+}
@@ -1,14 +1,33 @@
 //! Persistence serialization package.
 
+mod applied_migration;
+mod event_manifest;
+mod event_merger;
+mod event_migration;
+mod event_migration_error;
+mod event_migration_outcome;
+mod event_migration_registry;
+mod event_upcaster;
 mod message_serializer;
 mod registration;
+mod replicated_event_metadata_serializer;
 mod snapshot_payload;
 mod snapshot_serializer;
 mod wire;
 
+pub use applied_migration::AppliedMigration;
+pub use event_manifest::EventManifest;
+pub use event_merger::EventMerger;
+pub use event_migration::EventMigration;
+pub use event_migration_error::EventMigrationError;
+pub use event_migration_outcome::EventMigrationOutcome;
+pub use event_migration_registry::EventMigrationRegistry;
+pub use event_upcaster::EventUpcaster;
 pub use message_serializer::MessageSerializer;
 pub use registration::{
-  MESSAGE_SERIALIZER_ID, PersistenceSerializationContributor, SNAPSHOT_SERIALIZER_ID, register_persistence_serializers,
+  MESSAGE_SERIALIZER_ID, PersistenceSerializationContributor, REPLICATED_EVENT_METADATA_SERIALIZER_ID,
+  SNAPSHOT_SERIALIZER_ID, register_persistence_serializers, register_persistence_serializers_with_migrations,
 };
+pub use replicated_event_metadata_serializer::ReplicatedEventMetadataSerializer;
 pub use snapshot_payload::SnapshotPayload;
 pub use snapshot_serializer::SnapshotSerializer;
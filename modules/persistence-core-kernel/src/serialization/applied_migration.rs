@@ -0,0 +1,31 @@
+//! Record of one migration step applied while reading a payload.
+
+use alloc::{string::String, vec::Vec};
+
+/// Migration step applied to a payload during replay or snapshot load.
+///
+/// Manifests are recorded as stored, so versioned manifests read as `name#version`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AppliedMigration {
+  /// The payload manifest was renamed.
+  Renamed {
+    /// Manifest before the rename.
+    from: String,
+    /// Manifest after the rename.
+    to:   String,
+  },
+  /// An upcaster rewrote the payload into zero, one, or many payloads.
+  Upcast {
+    /// Manifest of the rewritten payload.
+    from: String,
+    /// Manifests of the produced payloads, in order.
+    to:   Vec<String>,
+  },
+  /// Consecutive payloads of one atomic write were merged into one.
+  Merged {
+    /// Manifests of the merged payloads, in order.
+    from: Vec<String>,
+    /// Manifest of the merged payload.
+    to:   String,
+  },
+}
@@ -0,0 +1,75 @@
+//! Versioned event manifest.
+
+#[cfg(test)]
+#[path = "event_manifest_test.rs"]
+mod tests;
+
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Event manifest split into a schema name and a schema version.
+///
+/// Versioned manifests are written as `name#version`, for example `counter.Added#2`. A manifest
+/// without a version suffix, or with a suffix that is not a positive integer, is read as version 1
+/// of the whole string, so manifests written before an event got versioned keep their meaning.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EventManifest {
+  name:    String,
+  version: u32,
+}
+
+impl EventManifest {
+  /// Separator between the schema name and the schema version.
+  pub const VERSION_SEPARATOR: char = '#';
+
+  /// Creates a manifest for `version` of the schema `name`.
+  ///
+  /// A version of 0 is raised to 1.
+  #[must_use]
+  pub fn new(name: impl Into<String>, version: u32) -> Self {
+    Self { name: name.into(), version: version.max(1) }
+  }
+
+  /// Parses a manifest string written as `name#version` or as a plain name.
+  #[must_use]
+  pub fn parse(manifest: &str) -> Self {
+    if let Some((name, version)) = manifest.rsplit_once(Self::VERSION_SEPARATOR)
+      && !name.is_empty()
+      && let Ok(version) = version.parse::<u32>()
+      && version > 0
+    {
+      return Self::new(name, version);
+    }
+    Self::new(manifest, 1)
+  }
+
+  /// Returns the schema name.
+  #[must_use]
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Returns the schema version.
+  #[must_use]
+  pub const fn version(&self) -> u32 {
+    self.version
+  }
+
+  /// Returns the manifest of another version of the same schema.
+  #[must_use]
+  pub fn with_version(&self, version: u32) -> Self {
+    Self::new(self.name.clone(), version)
+  }
+
+  /// Returns the manifest string in the `name#version` form.
+  #[must_use]
+  pub fn to_manifest(&self) -> String {
+    self.to_string()
+  }
+}
+
+impl Display for EventManifest {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+    write!(formatter, "{}{}{}", self.name, Self::VERSION_SEPARATOR, self.version)
+  }
+}
@@ -0,0 +1,26 @@
+use crate::serialization::EventManifest;
+
+#[test]
+fn parse_reads_the_version_suffix() {
+  let manifest = EventManifest::parse("counter.Added#3");
+
+  assert_eq!(manifest.name(), "counter.Added");
+  assert_eq!(manifest.version(), 3);
+  assert_eq!(manifest.to_manifest(), "counter.Added#3");
+}
+
+#[test]
+fn parse_treats_unversioned_manifests_as_version_one() {
+  assert_eq!(EventManifest::parse("counter.Added"), EventManifest::new("counter.Added", 1));
+  assert_eq!(EventManifest::parse("counter#Added"), EventManifest::new("counter#Added", 1));
+  assert_eq!(EventManifest::parse("counter.Added#0"), EventManifest::new("counter.Added#0", 1));
+  assert_eq!(EventManifest::parse("#2"), EventManifest::new("#2", 1));
+}
+
+#[test]
+fn with_version_keeps_the_schema_name() {
+  let manifest = EventManifest::parse("counter.Added#1").with_version(2);
+
+  assert_eq!(manifest, EventManifest::new("counter.Added", 2));
+  assert_eq!(EventManifest::new("counter.Added", 0).version(), 1);
+}
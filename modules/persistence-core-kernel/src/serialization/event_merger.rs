@@ -0,0 +1,15 @@
+//! Merger abstraction for consecutive payloads.
+
+use fraktor_actor_core_kernel_rs::serialization::SerializedMessage;
+
+use crate::serialization::EventMigrationError;
+
+/// Combines consecutive payloads of one atomic write into a single payload.
+pub trait EventMerger: Send + Sync + 'static {
+  /// Merges `payloads`, which already carry their current schema versions, into one payload.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`EventMigrationError`] when the payloads cannot be merged.
+  fn merge(&self, payloads: &[SerializedMessage]) -> Result<SerializedMessage, EventMigrationError>;
+}
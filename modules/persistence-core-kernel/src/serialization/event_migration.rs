@@ -0,0 +1,75 @@
+//! Migration chain for one event schema.
+
+#[cfg(test)]
+#[path = "event_migration_test.rs"]
+mod tests;
+
+use alloc::{collections::BTreeMap, format, string::String};
+
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::serialization::{EventMigrationError, EventUpcaster};
+
+/// Ordered upcasters that bring every stored version of one schema to its current version.
+///
+/// The upcaster registered for version `n` rewrites `name#n` payloads into `name#(n + 1)`
+/// payloads (or into payloads of other schemas when it splits the event), so a `v1 -> v2 -> v3`
+/// chain is made of the upcasters for versions 1 and 2.
+#[derive(Clone)]
+pub struct EventMigration {
+  name:            String,
+  current_version: u32,
+  upcasters:       BTreeMap<u32, ArcShared<dyn EventUpcaster>>,
+}
+
+impl EventMigration {
+  /// Creates a migration for the schema `name`, whose payloads are written at `current_version`.
+  #[must_use]
+  pub fn new(name: impl Into<String>, current_version: u32) -> Self {
+    Self { name: name.into(), current_version, upcasters: BTreeMap::new() }
+  }
+
+  /// Registers the upcaster rewriting payloads of `from_version`.
+  #[must_use]
+  pub fn with_upcaster(mut self, from_version: u32, upcaster: ArcShared<dyn EventUpcaster>) -> Self {
+    self.upcasters.insert(from_version, upcaster);
+    self
+  }
+
+  /// Returns the schema name.
+  #[must_use]
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Returns the version new payloads are written at.
+  #[must_use]
+  pub const fn current_version(&self) -> u32 {
+    self.current_version
+  }
+
+  /// Returns the upcaster rewriting payloads of `version`, if registered.
+  #[must_use]
+  pub fn upcaster_for(&self, version: u32) -> Option<&ArcShared<dyn EventUpcaster>> {
+    self.upcasters.get(&version)
+  }
+
+  pub(crate) fn validate(&self) -> Result<(), EventMigrationError> {
+    if self.name.is_empty() {
+      return Err(EventMigrationError::InvalidMigration(String::from("schema name must not be empty")));
+    }
+    if self.current_version == 0 {
+      return Err(EventMigrationError::InvalidMigration(format!(
+        "current version of {} must be at least 1",
+        self.name
+      )));
+    }
+    match self.upcasters.keys().find(|version| **version == 0 || **version >= self.current_version) {
+      | Some(version) => Err(EventMigrationError::InvalidMigration(format!(
+        "upcaster for {} version {} is outside 1..{}",
+        self.name, version, self.current_version
+      ))),
+      | None => Ok(()),
+    }
+  }
+}
@@ -0,0 +1,86 @@
+//! Event migration errors.
+
+#[cfg(test)]
+#[path = "event_migration_error_test.rs"]
+mod tests;
+
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+use fraktor_actor_core_kernel_rs::serialization::SerializationError;
+
+/// Errors raised while registering or applying event migrations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventMigrationError {
+  /// A payload was written with a schema version newer than the registered current version.
+  UnknownVersion {
+    /// Manifest of the payload.
+    manifest:        String,
+    /// Current version registered for the schema.
+    current_version: u32,
+  },
+  /// No upcaster is registered for an older schema version.
+  MissingUpcaster {
+    /// Manifest of the payload that could not be migrated.
+    manifest: String,
+  },
+  /// An upcaster or merger could not rewrite a payload.
+  UpcastFailed {
+    /// Manifest of the payload being rewritten.
+    manifest: String,
+    /// Failure reason.
+    reason:   String,
+  },
+  /// Migrating one payload took more steps than allowed, usually because of a migration cycle.
+  StepLimitExceeded {
+    /// Manifest of the payload being migrated.
+    manifest: String,
+  },
+  /// A snapshot migration did not produce exactly one payload.
+  SnapshotNotSingle {
+    /// Manifest of the snapshot payload.
+    manifest: String,
+    /// Number of payloads produced by the migration.
+    produced: usize,
+  },
+  /// A migration was rejected at registration time.
+  InvalidMigration(String),
+}
+
+impl EventMigrationError {
+  /// Creates an upcast failure for the payload carrying `manifest`.
+  #[must_use]
+  pub fn upcast_failed(manifest: impl Into<String>, reason: impl Into<String>) -> Self {
+    Self::UpcastFailed { manifest: manifest.into(), reason: reason.into() }
+  }
+}
+
+impl Display for EventMigrationError {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      | Self::UnknownVersion { manifest, current_version } => {
+        write!(formatter, "event manifest {} is newer than the current schema version {}", manifest, current_version)
+      },
+      | Self::MissingUpcaster { manifest } => {
+        write!(formatter, "no upcaster registered for event manifest {}", manifest)
+      },
+      | Self::UpcastFailed { manifest, reason } => {
+        write!(formatter, "upcasting event manifest {} failed: {}", manifest, reason)
+      },
+      | Self::StepLimitExceeded { manifest } => {
+        write!(formatter, "migrating event manifest {} exceeded the step limit", manifest)
+      },
+      | Self::SnapshotNotSingle { manifest, produced } => {
+        write!(formatter, "snapshot manifest {} migrated into {} payloads instead of one", manifest, produced)
+      },
+      | Self::InvalidMigration(reason) => write!(formatter, "invalid event migration: {}", reason),
+    }
+  }
+}
+
+impl From<EventMigrationError> for SerializationError {
+  fn from(error: EventMigrationError) -> Self {
+    // 移行の失敗は読み出し側から見ると解釈できないマニフェストとして扱う。
+    Self::UnknownManifest(error.to_string())
+  }
+}
@@ -0,0 +1,33 @@
+use alloc::string::ToString;
+
+use fraktor_actor_core_kernel_rs::serialization::SerializationError;
+
+use crate::serialization::EventMigrationError;
+
+#[test]
+fn unknown_version_names_the_manifest_and_the_current_version() {
+  let error = EventMigrationError::UnknownVersion { manifest: "counter.Added#4".into(), current_version: 3 };
+
+  assert_eq!(error.to_string(), "event manifest counter.Added#4 is newer than the current schema version 3");
+}
+
+#[test]
+fn upcast_failed_keeps_the_reason() {
+  let error = EventMigrationError::upcast_failed("counter.Added#1", "truncated payload");
+
+  assert_eq!(error, EventMigrationError::UpcastFailed {
+    manifest: "counter.Added#1".into(),
+    reason:   "truncated payload".into(),
+  });
+  assert_eq!(error.to_string(), "upcasting event manifest counter.Added#1 failed: truncated payload");
+}
+
+#[test]
+fn converts_into_an_unknown_manifest_serialization_error() {
+  let error = EventMigrationError::MissingUpcaster { manifest: "counter.Added#1".into() };
+
+  assert_eq!(
+    SerializationError::from(error),
+    SerializationError::UnknownManifest("no upcaster registered for event manifest counter.Added#1".into())
+  );
+}
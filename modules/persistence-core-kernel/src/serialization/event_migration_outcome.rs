@@ -0,0 +1,46 @@
+//! Result of migrating one stored payload.
+
+use alloc::vec::Vec;
+
+use fraktor_actor_core_kernel_rs::serialization::SerializedMessage;
+
+use crate::serialization::AppliedMigration;
+
+/// Payloads produced by migrating one stored payload, with the steps that produced them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventMigrationOutcome {
+  payloads: Vec<SerializedMessage>,
+  applied:  Vec<AppliedMigration>,
+}
+
+impl EventMigrationOutcome {
+  /// Creates an outcome from migrated payloads and the applied steps.
+  #[must_use]
+  pub const fn new(payloads: Vec<SerializedMessage>, applied: Vec<AppliedMigration>) -> Self {
+    Self { payloads, applied }
+  }
+
+  /// Returns the payloads at their current schema versions, in order.
+  #[must_use]
+  pub fn payloads(&self) -> &[SerializedMessage] {
+    &self.payloads
+  }
+
+  /// Returns the applied migration steps, in order.
+  #[must_use]
+  pub fn applied(&self) -> &[AppliedMigration] {
+    &self.applied
+  }
+
+  /// Returns whether any migration step was applied.
+  #[must_use]
+  pub const fn is_migrated(&self) -> bool {
+    !self.applied.is_empty()
+  }
+
+  /// Splits the outcome into its payloads and applied steps.
+  #[must_use]
+  pub fn into_parts(self) -> (Vec<SerializedMessage>, Vec<AppliedMigration>) {
+    (self.payloads, self.applied)
+  }
+}
@@ -0,0 +1,258 @@
+//! Registry of event migrations keyed by serializer id and manifest.
+
+#[cfg(test)]
+#[path = "event_migration_registry_test.rs"]
+mod tests;
+
+use alloc::{
+  format,
+  string::{String, ToString},
+  vec,
+  vec::Vec,
+};
+
+use ahash::RandomState;
+use fraktor_actor_core_kernel_rs::serialization::{SerializedMessage, SerializerId};
+use fraktor_utils_core_rs::sync::ArcShared;
+use hashbrown::HashMap;
+
+use crate::serialization::{
+  AppliedMigration, EventManifest, EventMerger, EventMigration, EventMigrationError, EventMigrationOutcome,
+};
+
+/// Upper bound of renames and upcasts applied to one stored payload.
+const MAX_MIGRATION_STEPS: usize = 64;
+
+struct EventMergeRule {
+  serializer_id: SerializerId,
+  names:         Vec<String>,
+  merger:        ArcShared<dyn EventMerger>,
+}
+
+impl Clone for EventMergeRule {
+  fn clone(&self) -> Self {
+    Self { serializer_id: self.serializer_id, names: self.names.clone(), merger: self.merger.clone() }
+  }
+}
+
+/// Migrations applied to stored payloads before they are deserialized.
+///
+/// Payloads are matched by the serializer id and the schema name of their manifest (see
+/// [`EventManifest`]). Reading a payload first applies manifest renames, then runs the upcasters of
+/// the schema's [`EventMigration`] until every produced payload reaches its current version. Within
+/// one atomic write, consecutive migrated payloads matching a registered merge are then combined
+/// into one.
+///
+/// A payload written with a version newer than the registered current version fails with
+/// [`EventMigrationError::UnknownVersion`] instead of being handed to a deserializer that would
+/// misread it. Payloads without a manifest, or whose schema is not registered, pass through
+/// untouched.
+#[derive(Clone)]
+pub struct EventMigrationRegistry {
+  migrations: HashMap<(SerializerId, String), EventMigration, RandomState>,
+  renames:    HashMap<(SerializerId, String), String, RandomState>,
+  merges:     Vec<EventMergeRule>,
+}
+
+impl EventMigrationRegistry {
+  /// Creates an empty registry.
+  #[must_use]
+  pub fn new() -> Self {
+    Self {
+      migrations: HashMap::with_hasher(RandomState::new()),
+      renames:    HashMap::with_hasher(RandomState::new()),
+      merges:     Vec::new(),
+    }
+  }
+
+  /// Returns whether no migration, rename, or merge is registered.
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.migrations.is_empty() && self.renames.is_empty() && self.merges.is_empty()
+  }
+
+  /// Registers the migration chain of one schema written by `serializer_id`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`EventMigrationError::InvalidMigration`] when the migration is malformed, the schema
+  /// already has a migration, or the schema name is renamed away.
+  pub fn with_migration(
+    mut self,
+    serializer_id: SerializerId,
+    migration: EventMigration,
+  ) -> Result<Self, EventMigrationError> {
+    migration.validate()?;
+    let key = (serializer_id, migration.name().to_string());
+    if self.migrations.contains_key(&key) {
+      return Err(EventMigrationError::InvalidMigration(format!("{} already has a migration", migration.name())));
+    }
+    if self.renames.contains_key(&key) {
+      return Err(EventMigrationError::InvalidMigration(format!("{} is renamed away", migration.name())));
+    }
+    self.migrations.insert(key, migration);
+    Ok(self)
+  }
+
+  /// Renames the schema `from` to `to`, keeping the stored version and manifest form.
+  ///
+  /// Renamed payloads continue with the migration registered for `to`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`EventMigrationError::InvalidMigration`] when a name is empty, both names are equal,
+  /// `from` is already renamed, or `from` has a migration.
+  pub fn with_rename(
+    mut self,
+    serializer_id: SerializerId,
+    from: impl Into<String>,
+    to: impl Into<String>,
+  ) -> Result<Self, EventMigrationError> {
+    let from = from.into();
+    let to = to.into();
+    if from.is_empty() || to.is_empty() || from == to {
+      return Err(EventMigrationError::InvalidMigration(format!("cannot rename {from:?} to {to:?}")));
+    }
+    let key = (serializer_id, from);
+    if self.renames.contains_key(&key) || self.migrations.contains_key(&key) {
+      return Err(EventMigrationError::InvalidMigration(format!("{} is already migrated", key.1)));
+    }
+    self.renames.insert(key, to);
+    Ok(self)
+  }
+
+  /// Merges consecutive payloads of one atomic write whose schema names equal `names`, in order.
+  ///
+  /// Merges see payloads after their migrations, and the merged payload is not migrated again.
+  /// Merges are tried in registration order at every position of the write.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`EventMigrationError::InvalidMigration`] when fewer than two names are given.
+  pub fn with_merger(
+    mut self,
+    serializer_id: SerializerId,
+    names: &[&str],
+    merger: ArcShared<dyn EventMerger>,
+  ) -> Result<Self, EventMigrationError> {
+    if names.len() < 2 {
+      return Err(EventMigrationError::InvalidMigration(String::from("a merge needs at least two schema names")));
+    }
+    let names = names.iter().map(|name| (*name).to_string()).collect();
+    self.merges.push(EventMergeRule { serializer_id, names, merger });
+    Ok(self)
+  }
+
+  /// Brings `payload` to the current versions of the schemas it migrates into.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`EventMigrationError`] when the payload has an unknown future version, an upcaster
+  /// is missing or fails, or the migration does not terminate within the step limit.
+  pub fn migrate(&self, payload: SerializedMessage) -> Result<EventMigrationOutcome, EventMigrationError> {
+    let mut payloads = Vec::new();
+    let mut applied = Vec::new();
+    let mut steps = 0;
+    self.migrate_into(payload, &mut payloads, &mut applied, &mut steps)?;
+    Ok(EventMigrationOutcome::new(payloads, applied))
+  }
+
+  /// Merges the leading payloads of `payloads` when a registered merge matches them.
+  ///
+  /// Returns the number of merged payloads together with the merged payload.
+  pub(crate) fn merge_leading(
+    &self,
+    payloads: &[SerializedMessage],
+  ) -> Result<Option<(usize, EventMigrationOutcome)>, EventMigrationError> {
+    let Some(rule) = self.merges.iter().find(|rule| Self::matches(rule, payloads)) else {
+      return Ok(None);
+    };
+    let merged_payloads = &payloads[..rule.names.len()];
+    let merged = rule.merger.merge(merged_payloads)?;
+    let applied = AppliedMigration::Merged {
+      from: merged_payloads.iter().map(|payload| payload.manifest().unwrap_or_default().to_string()).collect(),
+      to:   merged.manifest().unwrap_or_default().to_string(),
+    };
+    Ok(Some((rule.names.len(), EventMigrationOutcome::new(vec![merged], vec![applied]))))
+  }
+
+  pub(crate) const fn has_merges(&self) -> bool {
+    !self.merges.is_empty()
+  }
+
+  fn matches(rule: &EventMergeRule, payloads: &[SerializedMessage]) -> bool {
+    payloads.len() >= rule.names.len()
+      && rule.names.iter().zip(payloads).all(|(name, payload)| {
+        payload.serializer_id() == rule.serializer_id
+          && payload.manifest().is_some_and(|manifest| EventManifest::parse(manifest).name() == name)
+      })
+  }
+
+  fn migrate_into(
+    &self,
+    mut payload: SerializedMessage,
+    payloads: &mut Vec<SerializedMessage>,
+    applied: &mut Vec<AppliedMigration>,
+    steps: &mut usize,
+  ) -> Result<(), EventMigrationError> {
+    loop {
+      let Some(manifest) = payload.manifest().map(ToString::to_string) else {
+        payloads.push(payload);
+        return Ok(());
+      };
+      let serializer_id = payload.serializer_id();
+      let parsed = EventManifest::parse(&manifest);
+      let key = (serializer_id, parsed.name().to_string());
+      if let Some(target) = self.renames.get(&key) {
+        Self::count_step(steps, &manifest)?;
+        // バージョン表記のないマニフェストは、改名後も同じ書式を保つ。
+        let renamed = if manifest == parsed.name() {
+          target.clone()
+        } else {
+          EventManifest::new(target.clone(), parsed.version()).to_manifest()
+        };
+        applied.push(AppliedMigration::Renamed { from: manifest, to: renamed.clone() });
+        payload = SerializedMessage::new(serializer_id, Some(renamed), payload.bytes().to_vec());
+        continue;
+      }
+      let Some(migration) = self.migrations.get(&key) else {
+        payloads.push(payload);
+        return Ok(());
+      };
+      let current_version = migration.current_version();
+      if parsed.version() == current_version {
+        payloads.push(payload);
+        return Ok(());
+      }
+      if parsed.version() > current_version {
+        return Err(EventMigrationError::UnknownVersion { manifest, current_version });
+      }
+      let upcaster = migration
+        .upcaster_for(parsed.version())
+        .ok_or_else(|| EventMigrationError::MissingUpcaster { manifest: manifest.clone() })?;
+      Self::count_step(steps, &manifest)?;
+      let produced = upcaster.upcast(&payload)?;
+      let to = produced.iter().map(|next| next.manifest().unwrap_or_default().to_string()).collect();
+      applied.push(AppliedMigration::Upcast { from: manifest, to });
+      // 分割で生じた各ペイロードも、それぞれのスキーマの現行バージョンまで順番に移行する。
+      for next in produced {
+        self.migrate_into(next, payloads, applied, steps)?;
+      }
+      return Ok(());
+    }
+  }
+
+  fn count_step(steps: &mut usize, manifest: &str) -> Result<(), EventMigrationError> {
+    *steps += 1;
+    if *steps > MAX_MIGRATION_STEPS {
+      return Err(EventMigrationError::StepLimitExceeded { manifest: manifest.to_string() });
+    }
+    Ok(())
+  }
+}
+
+impl Default for EventMigrationRegistry {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,298 @@
+use alloc::{
+  string::{String, ToString},
+  vec::Vec,
+};
+
+use fraktor_actor_core_kernel_rs::serialization::{SerializedMessage, SerializerId};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::serialization::{
+  AppliedMigration, EventManifest, EventMerger, EventMigration, EventMigrationError, EventMigrationRegistry,
+  EventUpcaster,
+};
+
+const ADDED: &str = "counter.Added";
+const ADDED_TWICE: &str = "counter.AddedTwice";
+const RESERVED: &str = "counter.Reserved";
+const COMMITTED: &str = "counter.Committed";
+
+fn serializer_id() -> SerializerId {
+  SerializerId::try_from(120).expect("serializer id")
+}
+
+fn message(manifest: &str, bytes: Vec<u8>) -> SerializedMessage {
+  SerializedMessage::new(serializer_id(), Some(manifest.to_string()), bytes)
+}
+
+fn manifest(payload: &SerializedMessage) -> EventManifest {
+  EventManifest::parse(payload.manifest().expect("manifest"))
+}
+
+fn read_i32(payload: &SerializedMessage) -> Result<i32, EventMigrationError> {
+  let bytes: [u8; 4] = payload
+    .bytes()
+    .try_into()
+    .map_err(|_| EventMigrationError::upcast_failed(payload.manifest().unwrap_or_default(), "expected 4 bytes"))?;
+  Ok(i32::from_le_bytes(bytes))
+}
+
+/// v1 (i32) を v2 (i64) に広げる。
+struct WidenAmount;
+
+impl EventUpcaster for WidenAmount {
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    let amount = i64::from(read_i32(payload)?);
+    let next = manifest(payload).with_version(2).to_manifest();
+    Ok(vec![message(&next, amount.to_le_bytes().to_vec())])
+  }
+}
+
+/// v2 に発生元バイトを付けて v3 にする。
+struct AppendSource;
+
+impl EventUpcaster for AppendSource {
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    let mut bytes = payload.bytes().to_vec();
+    bytes.push(0);
+    Ok(vec![message(&manifest(payload).with_version(3).to_manifest(), bytes)])
+  }
+}
+
+struct SplitTwice;
+
+impl EventUpcaster for SplitTwice {
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    let added =
+      message(&EventManifest::new(ADDED, 2).to_manifest(), i64::from(read_i32(payload)?).to_le_bytes().to_vec());
+    Ok(vec![added.clone(), added])
+  }
+}
+
+struct DropEvent;
+
+impl EventUpcaster for DropEvent {
+  fn upcast(&self, _payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    Ok(Vec::new())
+  }
+}
+
+/// 同じバージョンのまま返すため、移行が終わらない。
+struct Loop;
+
+impl EventUpcaster for Loop {
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    Ok(vec![payload.clone()])
+  }
+}
+
+struct CommitReservation;
+
+impl EventMerger for CommitReservation {
+  fn merge(&self, payloads: &[SerializedMessage]) -> Result<SerializedMessage, EventMigrationError> {
+    let committed = payloads.last().ok_or_else(|| EventMigrationError::upcast_failed(COMMITTED, "no payloads"))?;
+    let mut bytes = i64::from(read_i32(committed)?).to_le_bytes().to_vec();
+    bytes.push(1);
+    Ok(message(&EventManifest::new(ADDED, 3).to_manifest(), bytes))
+  }
+}
+
+fn added_migration() -> EventMigration {
+  EventMigration::new(ADDED, 3)
+    .with_upcaster(1, ArcShared::new(WidenAmount))
+    .with_upcaster(2, ArcShared::new(AppendSource))
+}
+
+fn registry() -> EventMigrationRegistry {
+  EventMigrationRegistry::new()
+    .with_migration(serializer_id(), added_migration())
+    .expect("added migration")
+    .with_migration(serializer_id(), EventMigration::new(ADDED_TWICE, 2).with_upcaster(1, ArcShared::new(SplitTwice)))
+    .expect("added twice migration")
+    .with_rename(serializer_id(), "counter.Incremented", ADDED)
+    .expect("rename")
+}
+
+fn current_added(amount: i64) -> Vec<u8> {
+  let mut bytes = amount.to_le_bytes().to_vec();
+  bytes.push(0);
+  bytes
+}
+
+fn manifests(applied: &[&str]) -> Vec<String> {
+  applied.iter().map(|manifest| (*manifest).to_string()).collect()
+}
+
+#[test]
+fn migrate_chains_upcasters_up_to_the_current_version() {
+  let outcome = registry().migrate(message("counter.Added#1", 5_i32.to_le_bytes().to_vec())).expect("migrate");
+
+  assert_eq!(outcome.payloads(), &[message("counter.Added#3", current_added(5))]);
+  assert_eq!(outcome.applied(), &[
+    AppliedMigration::Upcast { from: "counter.Added#1".into(), to: manifests(&["counter.Added#2"]) },
+    AppliedMigration::Upcast { from: "counter.Added#2".into(), to: manifests(&["counter.Added#3"]) },
+  ]);
+}
+
+#[test]
+fn migrate_reads_unversioned_manifests_as_version_one() {
+  let outcome = registry().migrate(message(ADDED, 2_i32.to_le_bytes().to_vec())).expect("migrate");
+
+  assert_eq!(outcome.payloads(), &[message("counter.Added#3", current_added(2))]);
+  assert_eq!(outcome.applied().len(), 2);
+}
+
+#[test]
+fn migrate_leaves_current_and_unregistered_payloads_untouched() {
+  let registry = registry();
+  let current = message("counter.Added#3", current_added(1));
+  let unregistered = message("counter.Removed#1", vec![1]);
+  let without_manifest = SerializedMessage::new(serializer_id(), None, vec![1]);
+  let other_serializer =
+    SerializedMessage::new(SerializerId::try_from(121).expect("id"), Some("counter.Added#1".into()), vec![1]);
+
+  for payload in [current, unregistered, without_manifest, other_serializer] {
+    let outcome = registry.migrate(payload.clone()).expect("migrate");
+    assert_eq!(outcome.payloads(), &[payload]);
+    assert!(!outcome.is_migrated());
+  }
+}
+
+#[test]
+fn migrate_renames_before_upcasting() {
+  let outcome = registry().migrate(message("counter.Incremented#2", 9_i64.to_le_bytes().to_vec())).expect("migrate");
+
+  assert_eq!(outcome.payloads(), &[message("counter.Added#3", current_added(9))]);
+  assert_eq!(outcome.applied()[0], AppliedMigration::Renamed {
+    from: "counter.Incremented#2".into(),
+    to:   "counter.Added#2".into(),
+  });
+}
+
+#[test]
+fn migrate_splits_and_migrates_every_produced_payload() {
+  let outcome = registry().migrate(message("counter.AddedTwice#1", 4_i32.to_le_bytes().to_vec())).expect("migrate");
+
+  let added = message("counter.Added#3", current_added(4));
+  assert_eq!(outcome.payloads(), &[added.clone(), added]);
+  assert_eq!(outcome.applied(), &[
+    AppliedMigration::Upcast {
+      from: "counter.AddedTwice#1".into(),
+      to:   manifests(&["counter.Added#2", "counter.Added#2"]),
+    },
+    AppliedMigration::Upcast { from: "counter.Added#2".into(), to: manifests(&["counter.Added#3"]) },
+    AppliedMigration::Upcast { from: "counter.Added#2".into(), to: manifests(&["counter.Added#3"]) },
+  ]);
+}
+
+#[test]
+fn migrate_drops_payloads_when_an_upcaster_returns_nothing() {
+  let registry = EventMigrationRegistry::new()
+    .with_migration(
+      serializer_id(),
+      EventMigration::new("counter.Audited", 2).with_upcaster(1, ArcShared::new(DropEvent)),
+    )
+    .expect("migration");
+
+  let outcome = registry.migrate(message("counter.Audited#1", vec![1])).expect("migrate");
+
+  assert!(outcome.payloads().is_empty());
+  assert_eq!(outcome.applied(), &[AppliedMigration::Upcast { from: "counter.Audited#1".into(), to: Vec::new() }]);
+}
+
+#[test]
+fn migrate_rejects_unknown_future_versions() {
+  let result = registry().migrate(message("counter.Added#4", vec![0; 10]));
+
+  assert_eq!(
+    result,
+    Err(EventMigrationError::UnknownVersion { manifest: "counter.Added#4".into(), current_version: 3 })
+  );
+}
+
+#[test]
+fn migrate_reports_missing_and_failing_upcasters() {
+  let registry = EventMigrationRegistry::new()
+    .with_migration(serializer_id(), EventMigration::new(ADDED, 3).with_upcaster(1, ArcShared::new(WidenAmount)))
+    .expect("migration");
+
+  assert_eq!(
+    registry.migrate(message("counter.Added#2", vec![0; 8])),
+    Err(EventMigrationError::MissingUpcaster { manifest: "counter.Added#2".into() })
+  );
+  assert!(matches!(
+    registry.migrate(message("counter.Added#1", vec![0; 3])),
+    Err(EventMigrationError::UpcastFailed { .. })
+  ));
+}
+
+#[test]
+fn migrate_stops_migrations_that_do_not_terminate() {
+  let registry = EventMigrationRegistry::new()
+    .with_migration(serializer_id(), EventMigration::new(ADDED, 2).with_upcaster(1, ArcShared::new(Loop)))
+    .expect("migration");
+
+  let result = registry.migrate(message("counter.Added#1", vec![0]));
+
+  assert_eq!(result, Err(EventMigrationError::StepLimitExceeded { manifest: "counter.Added#1".into() }));
+}
+
+#[test]
+fn registration_rejects_conflicting_entries() {
+  assert!(matches!(
+    registry().with_migration(serializer_id(), added_migration()),
+    Err(EventMigrationError::InvalidMigration(_))
+  ));
+  assert!(matches!(
+    registry().with_rename(serializer_id(), ADDED, "counter.Other"),
+    Err(EventMigrationError::InvalidMigration(_))
+  ));
+  assert!(matches!(
+    registry().with_rename(serializer_id(), "counter.Same", "counter.Same"),
+    Err(EventMigrationError::InvalidMigration(_))
+  ));
+  assert!(matches!(
+    registry().with_migration(serializer_id(), EventMigration::new("counter.Incremented", 1)),
+    Err(EventMigrationError::InvalidMigration(_))
+  ));
+  assert!(matches!(
+    registry().with_merger(serializer_id(), &[RESERVED], ArcShared::new(CommitReservation)),
+    Err(EventMigrationError::InvalidMigration(_))
+  ));
+  assert!(EventMigrationRegistry::default().is_empty());
+  assert!(!registry().is_empty());
+}
+
+#[test]
+fn merge_leading_combines_matching_consecutive_payloads() {
+  let registry =
+    registry().with_merger(serializer_id(), &[RESERVED, COMMITTED], ArcShared::new(CommitReservation)).expect("merger");
+  let reserved = message("counter.Reserved#1", 3_i32.to_le_bytes().to_vec());
+  let committed = message("counter.Committed#1", 3_i32.to_le_bytes().to_vec());
+  let trailing = message("counter.Added#3", current_added(1));
+
+  let merged = registry
+    .merge_leading(&[reserved.clone(), committed.clone(), trailing.clone()])
+    .expect("merge")
+    .expect("matching merge");
+  let unmatched = registry.merge_leading(&[committed, reserved, trailing]).expect("merge");
+
+  let mut expected = 3_i64.to_le_bytes().to_vec();
+  expected.push(1);
+  assert_eq!(merged.0, 2);
+  assert_eq!(merged.1.payloads(), &[message("counter.Added#3", expected)]);
+  assert_eq!(merged.1.applied(), &[AppliedMigration::Merged {
+    from: manifests(&["counter.Reserved#1", "counter.Committed#1"]),
+    to:   "counter.Added#3".into(),
+  }]);
+  assert!(unmatched.is_none());
+}
+
+#[test]
+fn migrate_keeps_unversioned_manifests_unversioned_when_renaming() {
+  let registry =
+    EventMigrationRegistry::new().with_rename(serializer_id(), "counter.Old", "counter.New").expect("rename");
+
+  let outcome = registry.migrate(message("counter.Old", vec![1])).expect("migrate");
+
+  assert_eq!(outcome.payloads(), &[message("counter.New", vec![1])]);
+}
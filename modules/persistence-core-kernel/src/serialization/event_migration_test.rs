@@ -0,0 +1,40 @@
+use alloc::vec::Vec;
+
+use fraktor_actor_core_kernel_rs::serialization::SerializedMessage;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::serialization::{EventMigration, EventMigrationError, EventUpcaster};
+
+struct PassThrough;
+
+impl EventUpcaster for PassThrough {
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    Ok(vec![payload.clone()])
+  }
+}
+
+fn upcaster() -> ArcShared<dyn EventUpcaster> {
+  ArcShared::new(PassThrough)
+}
+
+#[test]
+fn upcasters_are_resolved_by_source_version() {
+  let migration = EventMigration::new("counter.Added", 3).with_upcaster(1, upcaster()).with_upcaster(2, upcaster());
+
+  assert_eq!(migration.name(), "counter.Added");
+  assert_eq!(migration.current_version(), 3);
+  assert!(migration.upcaster_for(1).is_some());
+  assert!(migration.upcaster_for(3).is_none());
+  assert!(migration.validate().is_ok());
+}
+
+#[test]
+fn validate_rejects_upcasters_outside_the_version_range() {
+  let from_current = EventMigration::new("counter.Added", 2).with_upcaster(2, upcaster());
+  let from_zero = EventMigration::new("counter.Added", 2).with_upcaster(0, upcaster());
+
+  assert!(matches!(from_current.validate(), Err(EventMigrationError::InvalidMigration(_))));
+  assert!(matches!(from_zero.validate(), Err(EventMigrationError::InvalidMigration(_))));
+  assert!(matches!(EventMigration::new("", 1).validate(), Err(EventMigrationError::InvalidMigration(_))));
+  assert!(matches!(EventMigration::new("counter.Added", 0).validate(), Err(EventMigrationError::InvalidMigration(_))));
+}
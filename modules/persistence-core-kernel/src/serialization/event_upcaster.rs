@@ -0,0 +1,23 @@
+//! Upcaster abstraction for one schema version step.
+
+use alloc::vec::Vec;
+
+use fraktor_actor_core_kernel_rs::serialization::SerializedMessage;
+
+use crate::serialization::EventMigrationError;
+
+/// Rewrites a payload of one schema version into payloads of a newer version.
+///
+/// Upcasters work on serialized payloads, so the types of retired versions do not have to be kept
+/// around. Returning several payloads splits the event and returning none drops it. Every returned
+/// payload must carry its own manifest, usually built with
+/// [`EventManifest::with_version`](crate::serialization::EventManifest::with_version); the
+/// registry keeps migrating each one until it reaches the current version of its schema.
+pub trait EventUpcaster: Send + Sync + 'static {
+  /// Rewrites `payload` into the payloads of the next schema version.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`EventMigrationError`] when the payload cannot be rewritten.
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError>;
+}
@@ -4,7 +4,7 @@
 #[path = "message_serializer_test.rs"]
 mod tests;
 
-use alloc::{boxed::Box, string::String, vec::Vec};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
 use core::{
   any::{Any, TypeId},
   ops::Deref,
@@ -17,27 +17,63 @@ use fraktor_actor_core_kernel_rs::serialization::{
 use fraktor_utils_core_rs::sync::{ArcShared, WeakShared};
 
 use crate::{
+  journal::EventSeq,
   persistent::{AtomicWrite, PersistentRepr},
-  serialization::wire::{self, ATOMIC_WRITE_TAG, PERSISTENT_REPR_TAG},
+  serialization::{
+    AppliedMigration, EventMigrationRegistry,
+    wire::{self, ATOMIC_WRITE_TAG, PERSISTENT_REPR_TAG},
+  },
 };
 
+/// Journal record whose payload has been migrated but not yet deserialized.
+struct StoredRepr {
+  persistence_id:    String,
+  sequence_nr:       u64,
+  payloads:          Vec<SerializedMessage>,
+  payload_type_hint: Option<TypeId>,
+  applied:           Vec<AppliedMigration>,
+  manifest:          String,
+  writer_uuid:       String,
+  timestamp:         u64,
+  deleted:           bool,
+  adapter_type_id:   Option<TypeId>,
+  metadata:          Option<Box<dyn Any + Send + Sync>>,
+}
+
 /// Serializes [`PersistentRepr`] and [`AtomicWrite`] records.
+///
+/// With an [`EventMigrationRegistry`] attached, stored event payloads are migrated before they are
+/// deserialized. A record whose event was split, dropped, or merged into a preceding record carries
+/// an [`EventSeq`] payload, which replay expands into the individual events. Every decoded record
+/// reports the steps applied to it through [`PersistentRepr::applied_migrations`].
 pub struct MessageSerializer {
-  id:       SerializerId,
-  registry: WeakShared<SerializationRegistry>,
+  id:         SerializerId,
+  registry:   WeakShared<SerializationRegistry>,
+  migrations: Option<ArcShared<EventMigrationRegistry>>,
 }
 
 impl MessageSerializer {
   /// Creates a new message serializer.
   #[must_use]
   pub const fn new(id: SerializerId, registry: WeakShared<SerializationRegistry>) -> Self {
-    Self { id, registry }
+    Self { id, registry, migrations: None }
+  }
+
+  /// Applies `migrations` to event payloads read from the journal.
+  #[must_use]
+  pub fn with_migrations(mut self, migrations: ArcShared<EventMigrationRegistry>) -> Self {
+    self.migrations = Some(migrations);
+    self
   }
 
   pub(crate) fn uses_registry(&self, registry: &ArcShared<SerializationRegistry>) -> bool {
     self.registry.upgrade().is_some_and(|registered| ArcShared::ptr_eq(&registered, registry))
   }
 
+  pub(crate) fn uses_migrations(&self, migrations: &ArcShared<EventMigrationRegistry>) -> bool {
+    self.migrations.as_ref().is_some_and(|registered| ArcShared::ptr_eq(registered, migrations))
+  }
+
   fn registry(&self) -> Result<ArcShared<SerializationRegistry>, SerializationError> {
     self.registry.upgrade().ok_or(SerializationError::Uninitialized)
   }
@@ -88,39 +124,142 @@ impl MessageSerializer {
   fn decode_repr(&self, bytes: &[u8]) -> Result<PersistentRepr, SerializationError> {
     let registry = self.registry()?;
     let delegator = SerializationDelegator::new(&registry);
+    let stored = self.read_repr(&registry, &delegator, bytes)?;
+    Self::build_repr(&delegator, stored)
+  }
+
+  fn decode_atomic_write(&self, bytes: &[u8], cursor: &mut usize) -> Result<AtomicWrite, SerializationError> {
+    let registry = self.registry()?;
+    let delegator = SerializationDelegator::new(&registry);
+    let count = wire::read_u32(bytes, cursor)?;
+    let mut stored = Vec::new();
+    for _ in 0..count {
+      stored.push(self.read_repr(&registry, &delegator, wire::read_bytes(bytes, cursor)?)?);
+    }
+    if let Some(migrations) = &self.migrations
+      && migrations.has_merges()
+    {
+      Self::merge_stored(migrations, &mut stored)?;
+    }
+    let payload = stored.into_iter().map(|repr| Self::build_repr(&delegator, repr)).collect::<Result<Vec<_>, _>>()?;
+    AtomicWrite::new(payload).map_err(|_| SerializationError::InvalidFormat)
+  }
+
+  fn read_repr(
+    &self,
+    registry: &SerializationRegistry,
+    delegator: &SerializationDelegator<'_>,
+    bytes: &[u8],
+  ) -> Result<StoredRepr, SerializationError> {
     let mut cursor = 0;
     let persistence_id = wire::read_string(bytes, &mut cursor)?;
     let sequence_nr = wire::read_u64(bytes, &mut cursor)?;
     let payload_type_name = wire::read_string(bytes, &mut cursor)?;
-    let payload_type_hint = Self::type_hint_for_wire_name(&registry, &payload_type_name);
-    let payload = Self::deserialize_nested(&delegator, &wire::read_serialized(bytes, &mut cursor)?, payload_type_hint)?;
+    let mut payload_type_hint = Self::type_hint_for_wire_name(registry, &payload_type_name);
+    let payload = wire::read_serialized(bytes, &mut cursor)?;
+    if !Self::has_valid_manifest(&payload) {
+      return Err(SerializationError::InvalidFormat);
+    }
+    let (payloads, applied) = match &self.migrations {
+      | Some(migrations) => migrations.migrate(payload).map_err(SerializationError::from)?.into_parts(),
+      | None => (vec![payload], Vec::new()),
+    };
+    if !applied.is_empty() {
+      // 保存時の型名は移行前の型を指すため、移行後のペイロードには使わない。
+      payload_type_hint = None;
+    }
     let manifest = wire::read_string(bytes, &mut cursor)?;
     let writer_uuid = wire::read_string(bytes, &mut cursor)?;
     let timestamp = wire::read_u64(bytes, &mut cursor)?;
     let deleted = wire::read_bool(bytes, &mut cursor)?;
     let adapter_type_name = wire::read_string(bytes, &mut cursor)?;
-    let has_metadata = wire::read_bool(bytes, &mut cursor)?;
-    let mut repr = PersistentRepr::new(persistence_id, sequence_nr, ArcShared::from_boxed(payload))
-      .with_manifest(manifest)
-      .with_writer_uuid(writer_uuid)
-      .with_timestamp(timestamp)
-      .with_deleted(deleted);
-    if !adapter_type_name.is_empty() {
-      let adapter_type_id =
-        registry.type_id_for_binding_name(&adapter_type_name).ok_or(SerializationError::InvalidFormat)?;
+    let adapter_type_id = if adapter_type_name.is_empty() {
+      None
+    } else {
+      Some(registry.type_id_for_binding_name(&adapter_type_name).ok_or(SerializationError::InvalidFormat)?)
+    };
+    let metadata = if wire::read_bool(bytes, &mut cursor)? {
+      let metadata_type_name = wire::read_string(bytes, &mut cursor)?;
+      let metadata_type_hint = Self::type_hint_for_wire_name(registry, &metadata_type_name);
+      Some(Self::deserialize_nested(delegator, &wire::read_serialized(bytes, &mut cursor)?, metadata_type_hint)?)
+    } else {
+      None
+    };
+    wire::ensure_finished(bytes, cursor)?;
+    Ok(StoredRepr {
+      persistence_id,
+      sequence_nr,
+      payloads,
+      payload_type_hint,
+      applied,
+      manifest,
+      writer_uuid,
+      timestamp,
+      deleted,
+      adapter_type_id,
+      metadata,
+    })
+  }
+
+  fn build_repr(
+    delegator: &SerializationDelegator<'_>,
+    stored: StoredRepr,
+  ) -> Result<PersistentRepr, SerializationError> {
+    let mut events = stored
+      .payloads
+      .iter()
+      .map(|payload| Self::deserialize_nested(delegator, payload, stored.payload_type_hint).map(ArcShared::from_boxed))
+      .collect::<Result<Vec<ArcShared<dyn Any + Send + Sync>>, _>>()?;
+    // 分割・マージで 1 件にならなかったイベントは EventSeq として運び、リプレイ時に展開する。
+    let payload: ArcShared<dyn Any + Send + Sync> = if events.len() == 1
+      && let Some(event) = events.pop()
+    {
+      event
+    } else {
+      ArcShared::new(EventSeq::multiple(events))
+    };
+    let mut repr = PersistentRepr::new(stored.persistence_id, stored.sequence_nr, payload)
+      .with_manifest(stored.manifest)
+      .with_writer_uuid(stored.writer_uuid)
+      .with_timestamp(stored.timestamp)
+      .with_deleted(stored.deleted)
+      .with_applied_migrations(stored.applied);
+    if let Some(adapter_type_id) = stored.adapter_type_id {
       repr = repr.with_adapter_type_id(adapter_type_id);
     }
-    if has_metadata {
-      let metadata_type_name = wire::read_string(bytes, &mut cursor)?;
-      let metadata_type_hint = Self::type_hint_for_wire_name(&registry, &metadata_type_name);
-      let metadata =
-        Self::deserialize_nested(&delegator, &wire::read_serialized(bytes, &mut cursor)?, metadata_type_hint)?;
+    if let Some(metadata) = stored.metadata {
       repr = repr.with_metadata(ArcShared::from_boxed(metadata));
     }
-    wire::ensure_finished(bytes, cursor)?;
     Ok(repr)
   }
 
+  fn merge_stored(migrations: &EventMigrationRegistry, stored: &mut [StoredRepr]) -> Result<(), SerializationError> {
+    let mut index = 0;
+    while index < stored.len() {
+      // 分割されたレコードはマージ対象にしない。
+      let leading = stored[index..]
+        .iter()
+        .map_while(|repr| match repr.payloads.as_slice() {
+          | [payload] => Some(payload.clone()),
+          | _ => None,
+        })
+        .collect::<Vec<_>>();
+      let Some((merged_count, outcome)) = migrations.merge_leading(&leading).map_err(SerializationError::from)? else {
+        index += 1;
+        continue;
+      };
+      let (payloads, applied) = outcome.into_parts();
+      // マージ先以外のレコードも連番を保つため残し、イベントを持たない記録として返す。
+      for (offset, repr) in stored[index..index + merged_count].iter_mut().enumerate() {
+        repr.payloads = if offset == 0 { payloads.clone() } else { Vec::new() };
+        repr.payload_type_hint = None;
+        repr.applied.extend(applied.iter().cloned());
+      }
+      index += merged_count;
+    }
+    Ok(())
+  }
+
   fn serialize_nested(
     delegator: &SerializationDelegator<'_>,
     message: &(dyn Any + Send + Sync),
@@ -204,13 +343,8 @@ impl Serializer for MessageSerializer {
         Ok(Box::new(repr))
       },
       | ATOMIC_WRITE_TAG => {
-        let count = wire::read_u32(bytes, &mut cursor)?;
-        let mut payload = Vec::new();
-        for _ in 0..count {
-          payload.push(self.decode_repr(wire::read_bytes(bytes, &mut cursor)?)?);
-        }
+        let atomic_write = self.decode_atomic_write(bytes, &mut cursor)?;
         wire::ensure_finished(bytes, cursor)?;
-        let atomic_write = AtomicWrite::new(payload).map_err(|_| SerializationError::InvalidFormat)?;
         Ok(Box::new(atomic_write))
       },
       | _ => Err(SerializationError::InvalidFormat),
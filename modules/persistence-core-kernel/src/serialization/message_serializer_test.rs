@@ -12,11 +12,13 @@ use fraktor_actor_core_kernel_rs::{
 use fraktor_utils_core_rs::sync::{ArcShared, WeakShared};
 
 use crate::{
-  journal::EventAdapters,
+  journal::{EventAdapters, EventSeq},
   persistent::{AtomicWrite, PersistentRepr},
   serialization::{
-    MESSAGE_SERIALIZER_ID, MessageSerializer, PersistenceSerializationContributor, SNAPSHOT_SERIALIZER_ID,
-    SnapshotPayload, register_persistence_serializers,
+    AppliedMigration, EventManifest, EventMerger, EventMigration, EventMigrationError, EventMigrationRegistry,
+    EventUpcaster, MESSAGE_SERIALIZER_ID, MessageSerializer, PersistenceSerializationContributor,
+    SNAPSHOT_SERIALIZER_ID, SnapshotPayload, register_persistence_serializers,
+    register_persistence_serializers_with_migrations,
     wire::{self, ATOMIC_WRITE_TAG, PERSISTENT_REPR_TAG},
   },
 };
@@ -553,7 +555,7 @@ fn unknown_adapter_type_binding_fails_deserialization() {
 fn persistence_serializer_registration_is_idempotent() {
   let registry = manifest_registry();
 
-  let contributor = PersistenceSerializationContributor;
+  let contributor = PersistenceSerializationContributor::new();
 
   register_persistence_serializers(&registry).expect("register twice");
   contributor.contribute(&registry).expect("contribute twice");
@@ -669,3 +671,268 @@ fn persistence_serializer_registration_rejects_binding_name_collision() {
     }) if type_name == "PersistentRepr" && existing == id && requested == MESSAGE_SERIALIZER_ID
   ));
 }
+
+/// 任意のマニフェストとバイト列で書き込める、旧スキーマを模したイベント。
+struct StoredCounterEvent {
+  manifest: &'static str,
+  bytes:    Vec<u8>,
+}
+
+impl StoredCounterEvent {
+  fn amount(manifest: &'static str, amount: i32) -> Self {
+    Self { manifest, bytes: amount.to_le_bytes().to_vec() }
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct CounterAdded(i64);
+
+struct CounterSerializer {
+  id: SerializerId,
+}
+
+impl Serializer for CounterSerializer {
+  fn identifier(&self) -> SerializerId {
+    self.id
+  }
+
+  fn include_manifest(&self) -> bool {
+    true
+  }
+
+  fn to_binary(&self, message: &(dyn Any + Send + Sync)) -> Result<Vec<u8>, SerializationError> {
+    let event = message.downcast_ref::<StoredCounterEvent>().ok_or(SerializationError::InvalidFormat)?;
+    Ok(event.bytes.clone())
+  }
+
+  fn from_binary(
+    &self,
+    _bytes: &[u8],
+    _type_hint: Option<TypeId>,
+  ) -> Result<Box<dyn Any + Send + Sync>, SerializationError> {
+    Err(SerializationError::InvalidFormat)
+  }
+
+  fn as_any(&self) -> &(dyn Any + Send + Sync) {
+    self
+  }
+
+  fn as_string_manifest(&self) -> Option<&dyn SerializerWithStringManifest> {
+    Some(self)
+  }
+}
+
+impl SerializerWithStringManifest for CounterSerializer {
+  fn manifest(&self, message: &(dyn Any + Send + Sync)) -> Cow<'_, str> {
+    Cow::Borrowed(message.downcast_ref::<StoredCounterEvent>().map_or("", |event| event.manifest))
+  }
+
+  fn from_binary_with_manifest(
+    &self,
+    bytes: &[u8],
+    manifest: &str,
+  ) -> Result<Box<dyn Any + Send + Sync>, SerializationError> {
+    // 現行バージョンだけを読めるデシリアライザ。旧バージョンは移行で書き換えてから届く。
+    if manifest != "counter.Added#2" {
+      return Err(SerializationError::UnknownManifest(manifest.into()));
+    }
+    let bytes: [u8; 8] = bytes.try_into().map_err(|_| SerializationError::InvalidFormat)?;
+    Ok(Box::new(CounterAdded(i64::from_le_bytes(bytes))))
+  }
+}
+
+fn widened(payload: &SerializedMessage, amount_bytes: &[u8]) -> Result<SerializedMessage, EventMigrationError> {
+  let bytes: [u8; 4] = amount_bytes
+    .try_into()
+    .map_err(|_| EventMigrationError::upcast_failed(payload.manifest().unwrap_or_default(), "expected an i32"))?;
+  let amount = i64::from(i32::from_le_bytes(bytes));
+  Ok(SerializedMessage::new(
+    payload.serializer_id(),
+    Some(EventManifest::new("counter.Added", 2).to_manifest()),
+    amount.to_le_bytes().to_vec(),
+  ))
+}
+
+struct WidenAdded;
+
+impl EventUpcaster for WidenAdded {
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    Ok(vec![widened(payload, payload.bytes())?])
+  }
+}
+
+struct SplitAddedTwice;
+
+impl EventUpcaster for SplitAddedTwice {
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    let added = widened(payload, payload.bytes())?;
+    Ok(vec![added.clone(), added])
+  }
+}
+
+struct CommitReservation;
+
+impl EventMerger for CommitReservation {
+  fn merge(&self, payloads: &[SerializedMessage]) -> Result<SerializedMessage, EventMigrationError> {
+    let committed = payloads.last().ok_or_else(|| EventMigrationError::upcast_failed("counter.Committed", "empty"))?;
+    widened(committed, committed.bytes())
+  }
+}
+
+fn counter_migrations(serializer_id: SerializerId) -> ArcShared<EventMigrationRegistry> {
+  let migrations = EventMigrationRegistry::new()
+    .with_migration(serializer_id, EventMigration::new("counter.Added", 2).with_upcaster(1, ArcShared::new(WidenAdded)))
+    .expect("added")
+    .with_migration(
+      serializer_id,
+      EventMigration::new("counter.AddedTwice", 2).with_upcaster(1, ArcShared::new(SplitAddedTwice)),
+    )
+    .expect("added twice")
+    .with_rename(serializer_id, "counter.Incremented", "counter.Added")
+    .expect("rename")
+    .with_merger(serializer_id, &["counter.Reserved", "counter.Committed"], ArcShared::new(CommitReservation))
+    .expect("merger");
+  ArcShared::new(migrations)
+}
+
+fn migrating_registry() -> (ArcShared<SerializationRegistry>, ArcShared<EventMigrationRegistry>) {
+  let id = SerializerId::try_from(130).expect("serializer id");
+  let serializer: ArcShared<dyn Serializer> = ArcShared::new(CounterSerializer { id });
+  let setup = SerializationSetupBuilder::new()
+    .register_serializer("counter", id, serializer)
+    .expect("register")
+    .set_fallback("counter")
+    .expect("fallback")
+    .bind::<StoredCounterEvent>("counter")
+    .expect("bind")
+    .build()
+    .expect("setup");
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&setup));
+  let migrations = counter_migrations(id);
+  register_persistence_serializers_with_migrations(&registry, &migrations).expect("persistence serializers");
+  (registry, migrations)
+}
+
+fn round_trip(
+  registry: &ArcShared<SerializationRegistry>,
+  message: &(dyn Any + Send + Sync),
+) -> Box<dyn Any + Send + Sync> {
+  let serializer = registry.serializer_by_id(MESSAGE_SERIALIZER_ID).expect("message serializer");
+  let bytes = serializer.to_binary(message).expect("serialize");
+  serializer.from_binary(&bytes, None).expect("deserialize")
+}
+
+fn stored_repr(sequence_nr: u64, event: StoredCounterEvent) -> PersistentRepr {
+  PersistentRepr::new("pid-1", sequence_nr, ArcShared::new(event))
+}
+
+fn replayed_amounts(repr: &PersistentRepr) -> Vec<i64> {
+  match repr.downcast_ref::<EventSeq>() {
+    | Some(events) => {
+      events.to_events().iter().map(|event| event.downcast_ref::<CounterAdded>().expect("added").0).collect()
+    },
+    | None => vec![repr.downcast_ref::<CounterAdded>().expect("added").0],
+  }
+}
+
+#[test]
+fn replay_upcasts_renamed_and_old_versions_and_reports_the_steps() {
+  let (registry, _migrations) = migrating_registry();
+
+  let upcast = round_trip(&registry, &stored_repr(1, StoredCounterEvent::amount("counter.Added", 5)));
+  let renamed = round_trip(&registry, &stored_repr(2, StoredCounterEvent::amount("counter.Incremented#1", 6)));
+  let current = round_trip(
+    &registry,
+    &stored_repr(3, StoredCounterEvent { manifest: "counter.Added#2", bytes: 7_i64.to_le_bytes().to_vec() }),
+  );
+
+  let upcast = upcast.downcast_ref::<PersistentRepr>().expect("repr");
+  let renamed = renamed.downcast_ref::<PersistentRepr>().expect("repr");
+  let current = current.downcast_ref::<PersistentRepr>().expect("repr");
+  assert_eq!(upcast.downcast_ref::<CounterAdded>(), Some(&CounterAdded(5)));
+  assert_eq!(upcast.adapter_type_id(), TypeId::of::<CounterAdded>());
+  assert_eq!(upcast.applied_migrations(), &[AppliedMigration::Upcast {
+    from: "counter.Added".into(),
+    to:   vec!["counter.Added#2".into()],
+  }]);
+  assert_eq!(renamed.downcast_ref::<CounterAdded>(), Some(&CounterAdded(6)));
+  assert_eq!(renamed.applied_migrations().len(), 2);
+  assert_eq!(current.downcast_ref::<CounterAdded>(), Some(&CounterAdded(7)));
+  assert!(current.applied_migrations().is_empty());
+}
+
+#[test]
+fn replay_carries_split_events_as_an_event_seq() {
+  let (registry, _migrations) = migrating_registry();
+
+  let restored = round_trip(&registry, &stored_repr(1, StoredCounterEvent::amount("counter.AddedTwice#1", 4)));
+
+  let restored = restored.downcast_ref::<PersistentRepr>().expect("repr");
+  assert_eq!(replayed_amounts(restored), vec![4, 4]);
+  assert_eq!(restored.applied_migrations()[0], AppliedMigration::Upcast {
+    from: "counter.AddedTwice#1".into(),
+    to:   vec!["counter.Added#2".into(), "counter.Added#2".into()],
+  });
+}
+
+#[test]
+fn replay_merges_consecutive_events_of_an_atomic_write() {
+  let (registry, _migrations) = migrating_registry();
+  let atomic_write = AtomicWrite::new(vec![
+    stored_repr(1, StoredCounterEvent::amount("counter.Reserved#1", 3)),
+    stored_repr(2, StoredCounterEvent::amount("counter.Committed#1", 3)),
+    stored_repr(3, StoredCounterEvent::amount("counter.Added#1", 1)),
+  ])
+  .expect("atomic write");
+
+  let restored = round_trip(&registry, &atomic_write);
+
+  let restored = restored.downcast_ref::<AtomicWrite>().expect("atomic write");
+  let payload = restored.payload();
+  let merged = AppliedMigration::Merged {
+    from: vec!["counter.Reserved#1".into(), "counter.Committed#1".into()],
+    to:   "counter.Added#2".into(),
+  };
+  assert_eq!(restored.size(), 3);
+  assert_eq!(replayed_amounts(&payload[0]), vec![3]);
+  assert_eq!(replayed_amounts(&payload[1]), Vec::<i64>::new());
+  assert_eq!(replayed_amounts(&payload[2]), vec![1]);
+  assert_eq!(payload[0].applied_migrations(), payload[1].applied_migrations());
+  assert_eq!(payload[1].applied_migrations(), &[merged]);
+  assert_eq!(payload[2].applied_migrations().len(), 1);
+}
+
+#[test]
+fn replay_rejects_unknown_future_versions() {
+  let (registry, _migrations) = migrating_registry();
+  let serializer = registry.serializer_by_id(MESSAGE_SERIALIZER_ID).expect("message serializer");
+  let bytes =
+    serializer.to_binary(&stored_repr(1, StoredCounterEvent::amount("counter.Added#3", 1))).expect("serialize");
+
+  let result = serializer.from_binary(&bytes, None);
+
+  assert!(matches!(
+    result,
+    Err(SerializationError::UnknownManifest(reason))
+      if reason == "event manifest counter.Added#3 is newer than the current schema version 2"
+  ));
+}
+
+#[test]
+fn persistence_serializer_registration_with_migrations_is_idempotent_and_detects_other_migrations() {
+  let (registry, migrations) = migrating_registry();
+  let other = counter_migrations(SerializerId::try_from(130).expect("serializer id"));
+
+  register_persistence_serializers_with_migrations(&registry, &migrations).expect("same migrations");
+  register_persistence_serializers(&registry).expect("without migrations");
+  PersistenceSerializationContributor::new().with_migrations(migrations).contribute(&registry).expect("contribute");
+
+  assert_eq!(
+    register_persistence_serializers_with_migrations(&registry, &other),
+    Err(SerializationError::SerializerIdCollision(MESSAGE_SERIALIZER_ID))
+  );
+  assert_eq!(
+    register_persistence_serializers_with_migrations(&manifest_registry(), &other),
+    Err(SerializationError::SerializerIdCollision(MESSAGE_SERIALIZER_ID))
+  );
+}
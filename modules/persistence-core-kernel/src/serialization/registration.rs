@@ -14,7 +14,10 @@ use fraktor_utils_core_rs::sync::ArcShared;
 
 use crate::{
   persistent::{AtomicWrite, PersistentRepr},
-  serialization::{MessageSerializer, SnapshotPayload, SnapshotSerializer},
+  replication::ReplicatedEventMetadata,
+  serialization::{
+    EventMigrationRegistry, MessageSerializer, ReplicatedEventMetadataSerializer, SnapshotPayload, SnapshotSerializer,
+  },
 };
 
 /// Serializer id for persistence journal messages.
@@ -23,14 +26,26 @@ pub const MESSAGE_SERIALIZER_ID: SerializerId = SerializerId::from_raw(43);
 /// Serializer id for persistence snapshot payloads.
 pub const SNAPSHOT_SERIALIZER_ID: SerializerId = SerializerId::from_raw(42);
 
+/// Serializer id for replicated event metadata.
+pub const REPLICATED_EVENT_METADATA_SERIALIZER_ID: SerializerId = SerializerId::from_raw(44);
+
 /// Contributes persistence serializers to a serialization registry.
-pub struct PersistenceSerializationContributor;
+pub struct PersistenceSerializationContributor {
+  migrations: Option<ArcShared<EventMigrationRegistry>>,
+}
 
 impl PersistenceSerializationContributor {
   /// Creates a new persistence serialization contributor.
   #[must_use]
   pub const fn new() -> Self {
-    Self
+    Self { migrations: None }
+  }
+
+  /// Returns a contributor whose serializers apply `migrations` when reading.
+  #[must_use]
+  pub fn with_migrations(mut self, migrations: ArcShared<EventMigrationRegistry>) -> Self {
+    self.migrations = Some(migrations);
+    self
   }
 }
 
@@ -42,7 +57,10 @@ impl Default for PersistenceSerializationContributor {
 
 impl SerializationRegistryContributor for PersistenceSerializationContributor {
   fn contribute(&self, registry: &ArcShared<SerializationRegistry>) -> Result<(), SerializationError> {
-    register_persistence_serializers(registry)
+    match &self.migrations {
+      | Some(migrations) => register_persistence_serializers_with_migrations(registry, migrations),
+      | None => register_persistence_serializers(registry),
+    }
   }
 }
 
@@ -56,24 +74,63 @@ impl SerializationRegistryContributor for PersistenceSerializationContributor {
 ///
 /// Returns [`SerializationError`] when an id or binding collision is detected.
 pub fn register_persistence_serializers(registry: &ArcShared<SerializationRegistry>) -> Result<(), SerializationError> {
-  validate_persistence_registration(registry)?;
-  register_message_serializer(registry)?;
-  register_snapshot_serializer(registry)?;
+  register_persistence_serializers_with(registry, None)
+}
+
+/// Registers persistence serializers that apply `migrations` to journal and snapshot payloads.
+///
+/// Registering again with the same migration registry, or without one, is idempotent. Serializers
+/// already registered without these migrations are reported as a collision.
+///
+/// # Errors
+///
+/// Returns [`SerializationError`] when an id or binding collision is detected.
+pub fn register_persistence_serializers_with_migrations(
+  registry: &ArcShared<SerializationRegistry>,
+  migrations: &ArcShared<EventMigrationRegistry>,
+) -> Result<(), SerializationError> {
+  register_persistence_serializers_with(registry, Some(migrations))
+}
+
+fn register_persistence_serializers_with(
+  registry: &ArcShared<SerializationRegistry>,
+  migrations: Option<&ArcShared<EventMigrationRegistry>>,
+) -> Result<(), SerializationError> {
+  validate_persistence_registration(registry, migrations)?;
+  register_message_serializer(registry, migrations)?;
+  register_snapshot_serializer(registry, migrations)?;
+  register_replicated_event_metadata_serializer(registry)?;
   register_binding::<PersistentRepr>(registry, "PersistentRepr", MESSAGE_SERIALIZER_ID)?;
   register_binding::<AtomicWrite>(registry, "AtomicWrite", MESSAGE_SERIALIZER_ID)?;
-  register_binding::<SnapshotPayload>(registry, "SnapshotPayload", SNAPSHOT_SERIALIZER_ID)
+  register_binding::<SnapshotPayload>(registry, "SnapshotPayload", SNAPSHOT_SERIALIZER_ID)?;
+  register_binding::<ReplicatedEventMetadata>(
+    registry,
+    "ReplicatedEventMetadata",
+    REPLICATED_EVENT_METADATA_SERIALIZER_ID,
+  )
 }
 
-fn validate_persistence_registration(registry: &ArcShared<SerializationRegistry>) -> Result<(), SerializationError> {
+fn validate_persistence_registration(
+  registry: &ArcShared<SerializationRegistry>,
+  migrations: Option<&ArcShared<EventMigrationRegistry>>,
+) -> Result<(), SerializationError> {
   validate_serializer(registry, MESSAGE_SERIALIZER_ID, |existing| {
-    existing.as_any().downcast_ref::<MessageSerializer>().is_some_and(|serializer| serializer.uses_registry(registry))
+    is_same_message_serializer(existing, registry, migrations)
   })?;
   validate_serializer(registry, SNAPSHOT_SERIALIZER_ID, |existing| {
-    existing.as_any().downcast_ref::<SnapshotSerializer>().is_some_and(|serializer| serializer.uses_registry(registry))
+    is_same_snapshot_serializer(existing, registry, migrations)
   })?;
   validate_binding::<PersistentRepr>(registry, "PersistentRepr", MESSAGE_SERIALIZER_ID)?;
   validate_binding::<AtomicWrite>(registry, "AtomicWrite", MESSAGE_SERIALIZER_ID)?;
-  validate_binding::<SnapshotPayload>(registry, "SnapshotPayload", SNAPSHOT_SERIALIZER_ID)
+  validate_serializer(registry, REPLICATED_EVENT_METADATA_SERIALIZER_ID, |existing| {
+    existing.as_any().downcast_ref::<ReplicatedEventMetadataSerializer>().is_some()
+  })?;
+  validate_binding::<SnapshotPayload>(registry, "SnapshotPayload", SNAPSHOT_SERIALIZER_ID)?;
+  validate_binding::<ReplicatedEventMetadata>(
+    registry,
+    "ReplicatedEventMetadata",
+    REPLICATED_EVENT_METADATA_SERIALIZER_ID,
+  )
 }
 
 fn validate_serializer<F>(
@@ -92,19 +149,61 @@ where
   Ok(())
 }
 
-fn register_message_serializer(registry: &ArcShared<SerializationRegistry>) -> Result<(), SerializationError> {
-  let serializer: ArcShared<dyn Serializer> =
-    ArcShared::new(MessageSerializer::new(MESSAGE_SERIALIZER_ID, registry.downgrade()));
+fn is_same_message_serializer(
+  existing: &dyn Serializer,
+  registry: &ArcShared<SerializationRegistry>,
+  migrations: Option<&ArcShared<EventMigrationRegistry>>,
+) -> bool {
+  existing.as_any().downcast_ref::<MessageSerializer>().is_some_and(|serializer| {
+    serializer.uses_registry(registry) && migrations.is_none_or(|migrations| serializer.uses_migrations(migrations))
+  })
+}
+
+fn is_same_snapshot_serializer(
+  existing: &dyn Serializer,
+  registry: &ArcShared<SerializationRegistry>,
+  migrations: Option<&ArcShared<EventMigrationRegistry>>,
+) -> bool {
+  existing.as_any().downcast_ref::<SnapshotSerializer>().is_some_and(|serializer| {
+    serializer.uses_registry(registry) && migrations.is_none_or(|migrations| serializer.uses_migrations(migrations))
+  })
+}
+
+fn register_message_serializer(
+  registry: &ArcShared<SerializationRegistry>,
+  migrations: Option<&ArcShared<EventMigrationRegistry>>,
+) -> Result<(), SerializationError> {
+  let mut serializer = MessageSerializer::new(MESSAGE_SERIALIZER_ID, registry.downgrade());
+  if let Some(migrations) = migrations {
+    serializer = serializer.with_migrations(migrations.clone());
+  }
+  let serializer: ArcShared<dyn Serializer> = ArcShared::new(serializer);
   register_serializer(registry, MESSAGE_SERIALIZER_ID, serializer, |existing| {
-    existing.as_any().downcast_ref::<MessageSerializer>().is_some_and(|serializer| serializer.uses_registry(registry))
+    is_same_message_serializer(existing, registry, migrations)
   })
 }
 
-fn register_snapshot_serializer(registry: &ArcShared<SerializationRegistry>) -> Result<(), SerializationError> {
-  let serializer: ArcShared<dyn Serializer> =
-    ArcShared::new(SnapshotSerializer::new(SNAPSHOT_SERIALIZER_ID, registry.downgrade()));
+fn register_snapshot_serializer(
+  registry: &ArcShared<SerializationRegistry>,
+  migrations: Option<&ArcShared<EventMigrationRegistry>>,
+) -> Result<(), SerializationError> {
+  let mut serializer = SnapshotSerializer::new(SNAPSHOT_SERIALIZER_ID, registry.downgrade());
+  if let Some(migrations) = migrations {
+    serializer = serializer.with_migrations(migrations.clone());
+  }
+  let serializer: ArcShared<dyn Serializer> = ArcShared::new(serializer);
   register_serializer(registry, SNAPSHOT_SERIALIZER_ID, serializer, |existing| {
-    existing.as_any().downcast_ref::<SnapshotSerializer>().is_some_and(|serializer| serializer.uses_registry(registry))
+    is_same_snapshot_serializer(existing, registry, migrations)
+  })
+}
+
+fn register_replicated_event_metadata_serializer(
+  registry: &ArcShared<SerializationRegistry>,
+) -> Result<(), SerializationError> {
+  let serializer: ArcShared<dyn Serializer> =
+    ArcShared::new(ReplicatedEventMetadataSerializer::new(REPLICATED_EVENT_METADATA_SERIALIZER_ID));
+  register_serializer(registry, REPLICATED_EVENT_METADATA_SERIALIZER_ID, serializer, |existing| {
+    existing.as_any().downcast_ref::<ReplicatedEventMetadataSerializer>().is_some()
   })
 }
 
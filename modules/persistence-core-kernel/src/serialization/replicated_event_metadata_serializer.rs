@@ -0,0 +1,73 @@
+//! Serializer for replicated event metadata.
+
+#[cfg(test)]
+#[path = "replicated_event_metadata_serializer_test.rs"]
+mod tests;
+
+use alloc::{boxed::Box, vec::Vec};
+use core::any::{Any, TypeId};
+
+use fraktor_actor_core_kernel_rs::serialization::{SerializationError, Serializer, SerializerId};
+
+use crate::{
+  replication::{ReplicaId, ReplicatedEventMetadata, VersionVector},
+  serialization::wire,
+};
+
+/// Serializes [`ReplicatedEventMetadata`] stored next to replicated events.
+pub struct ReplicatedEventMetadataSerializer {
+  id: SerializerId,
+}
+
+impl ReplicatedEventMetadataSerializer {
+  /// Creates a new replicated event metadata serializer.
+  #[must_use]
+  pub const fn new(id: SerializerId) -> Self {
+    Self { id }
+  }
+}
+
+impl Serializer for ReplicatedEventMetadataSerializer {
+  fn identifier(&self) -> SerializerId {
+    self.id
+  }
+
+  fn to_binary(&self, message: &(dyn Any + Send + Sync)) -> Result<Vec<u8>, SerializationError> {
+    let metadata = message.downcast_ref::<ReplicatedEventMetadata>().ok_or(SerializationError::InvalidFormat)?;
+    let mut buffer = Vec::new();
+    wire::write_string(&mut buffer, metadata.origin_replica().as_str())?;
+    wire::write_u64(&mut buffer, metadata.origin_sequence_nr());
+    wire::write_bool(&mut buffer, metadata.concurrent());
+    let entry_count = u32::try_from(metadata.version().len()).map_err(|_| SerializationError::InvalidFormat)?;
+    wire::write_u32(&mut buffer, entry_count);
+    for (replica, version) in metadata.version().entries() {
+      wire::write_string(&mut buffer, replica.as_str())?;
+      wire::write_u64(&mut buffer, version);
+    }
+    Ok(buffer)
+  }
+
+  fn from_binary(
+    &self,
+    bytes: &[u8],
+    _type_hint: Option<TypeId>,
+  ) -> Result<Box<dyn Any + Send + Sync>, SerializationError> {
+    let mut cursor = 0;
+    let origin_replica = ReplicaId::new(wire::read_string(bytes, &mut cursor)?);
+    let origin_sequence_nr = wire::read_u64(bytes, &mut cursor)?;
+    let concurrent = wire::read_bool(bytes, &mut cursor)?;
+    let entry_count = wire::read_u32(bytes, &mut cursor)?;
+    let mut entries = Vec::new();
+    for _ in 0..entry_count {
+      let replica = ReplicaId::new(wire::read_string(bytes, &mut cursor)?);
+      entries.push((replica, wire::read_u64(bytes, &mut cursor)?));
+    }
+    wire::ensure_finished(bytes, cursor)?;
+    let version = VersionVector::from_entries(entries);
+    Ok(Box::new(ReplicatedEventMetadata::new(origin_replica, origin_sequence_nr, version, concurrent)))
+  }
+
+  fn as_any(&self) -> &(dyn Any + Send + Sync) {
+    self
+  }
+}
@@ -0,0 +1,23 @@
+use fraktor_actor_core_kernel_rs::serialization::{SerializationError, Serializer, SerializerId};
+
+use super::ReplicatedEventMetadataSerializer;
+use crate::replication::{ReplicaId, ReplicatedEventMetadata, VersionVector};
+
+#[test]
+fn metadata_round_trips_through_binary_format() {
+  let serializer = ReplicatedEventMetadataSerializer::new(SerializerId::from_raw(44));
+  let version = VersionVector::from_entries([(ReplicaId::new("a"), 3), (ReplicaId::new("b"), 1)]);
+  let metadata = ReplicatedEventMetadata::new(ReplicaId::new("a"), 3, version, true);
+
+  let bytes = serializer.to_binary(&metadata).expect("serialize");
+  let restored = serializer.from_binary(&bytes, None).expect("deserialize");
+
+  assert_eq!(restored.downcast_ref::<ReplicatedEventMetadata>(), Some(&metadata));
+}
+
+#[test]
+fn non_metadata_payload_is_rejected() {
+  let serializer = ReplicatedEventMetadataSerializer::new(SerializerId::from_raw(44));
+
+  assert!(matches!(serializer.to_binary(&1_u64), Err(SerializationError::InvalidFormat)));
+}
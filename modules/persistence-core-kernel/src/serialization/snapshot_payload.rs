@@ -1,20 +1,31 @@
 //! Snapshot payload wrapper for persistence serialization.
 
+use alloc::vec::Vec;
 use core::any::Any;
 
 use fraktor_utils_core_rs::sync::ArcShared;
 
+use crate::serialization::AppliedMigration;
+
 /// Serializable wrapper around snapshot data.
 #[derive(Clone, Debug)]
 pub struct SnapshotPayload {
-  data: ArcShared<dyn Any + Send + Sync>,
+  data:               ArcShared<dyn Any + Send + Sync>,
+  applied_migrations: Vec<AppliedMigration>,
 }
 
 impl SnapshotPayload {
   /// Creates a new snapshot payload wrapper.
   #[must_use]
   pub const fn new(data: ArcShared<dyn Any + Send + Sync>) -> Self {
-    Self { data }
+    Self { data, applied_migrations: Vec::new() }
+  }
+
+  /// Returns a new payload reporting the schema migrations applied while it was loaded.
+  #[must_use]
+  pub fn with_applied_migrations(mut self, applied_migrations: Vec<AppliedMigration>) -> Self {
+    self.applied_migrations = applied_migrations;
+    self
   }
 
   /// Returns the wrapped snapshot data.
@@ -23,6 +34,12 @@ impl SnapshotPayload {
     &self.data
   }
 
+  /// Returns the schema migrations applied while the snapshot was loaded.
+  #[must_use]
+  pub fn applied_migrations(&self) -> &[AppliedMigration] {
+    &self.applied_migrations
+  }
+
   /// Attempts to downcast the snapshot data.
   #[must_use]
   pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
@@ -4,7 +4,7 @@
 #[path = "snapshot_serializer_test.rs"]
 mod tests;
 
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, string::ToString, vec::Vec};
 use core::{
   any::{Any, TypeId},
   ops::Deref,
@@ -16,25 +16,59 @@ use fraktor_actor_core_kernel_rs::serialization::{
 };
 use fraktor_utils_core_rs::sync::{ArcShared, WeakShared};
 
-use crate::serialization::{SnapshotPayload, wire};
+use crate::serialization::{AppliedMigration, EventMigrationError, EventMigrationRegistry, SnapshotPayload, wire};
 
 /// Serializes snapshot payload wrappers.
+///
+/// With an [`EventMigrationRegistry`] attached, stored snapshot data is migrated before it is
+/// deserialized. Snapshot migrations must produce exactly one payload; the applied steps are
+/// reported through [`SnapshotPayload::applied_migrations`].
 pub struct SnapshotSerializer {
-  id:       SerializerId,
-  registry: WeakShared<SerializationRegistry>,
+  id:         SerializerId,
+  registry:   WeakShared<SerializationRegistry>,
+  migrations: Option<ArcShared<EventMigrationRegistry>>,
 }
 
 impl SnapshotSerializer {
   /// Creates a new snapshot serializer.
   #[must_use]
   pub const fn new(id: SerializerId, registry: WeakShared<SerializationRegistry>) -> Self {
-    Self { id, registry }
+    Self { id, registry, migrations: None }
+  }
+
+  /// Applies `migrations` to snapshot data read from the snapshot store.
+  #[must_use]
+  pub fn with_migrations(mut self, migrations: ArcShared<EventMigrationRegistry>) -> Self {
+    self.migrations = Some(migrations);
+    self
   }
 
   pub(crate) fn uses_registry(&self, registry: &ArcShared<SerializationRegistry>) -> bool {
     self.registry.upgrade().is_some_and(|registered| ArcShared::ptr_eq(&registered, registry))
   }
 
+  pub(crate) fn uses_migrations(&self, migrations: &ArcShared<EventMigrationRegistry>) -> bool {
+    self.migrations.as_ref().is_some_and(|registered| ArcShared::ptr_eq(registered, migrations))
+  }
+
+  fn migrate(
+    &self,
+    nested: SerializedMessage,
+  ) -> Result<(SerializedMessage, Vec<AppliedMigration>), SerializationError> {
+    let Some(migrations) = &self.migrations else {
+      return Ok((nested, Vec::new()));
+    };
+    let manifest = nested.manifest().unwrap_or_default().to_string();
+    let (mut payloads, applied) = migrations.migrate(nested).map_err(SerializationError::from)?.into_parts();
+    let produced = payloads.len();
+    if produced == 1
+      && let Some(payload) = payloads.pop()
+    {
+      return Ok((payload, applied));
+    }
+    Err(SerializationError::from(EventMigrationError::SnapshotNotSingle { manifest, produced }))
+  }
+
   fn registry(&self) -> Result<ArcShared<SerializationRegistry>, SerializationError> {
     self.registry.upgrade().ok_or(SerializationError::Uninitialized)
   }
@@ -89,14 +123,19 @@ impl Serializer for SnapshotSerializer {
     let delegator = SerializationDelegator::new(&registry);
     let mut cursor = 0;
     let type_name = wire::read_string(bytes, &mut cursor)?;
-    let type_hint = if type_name.is_empty() { None } else { registry.type_id_for_binding_name(&type_name) };
+    let mut type_hint = if type_name.is_empty() { None } else { registry.type_id_for_binding_name(&type_name) };
     let nested = wire::read_serialized(bytes, &mut cursor)?;
     wire::ensure_finished(bytes, cursor)?;
     if !Self::has_valid_manifest(&nested) {
       return Err(SerializationError::InvalidFormat);
     }
+    let (nested, applied) = self.migrate(nested)?;
+    if !applied.is_empty() {
+      // 保存時の型名は移行前の型を指すため、移行後のデータには使わない。
+      type_hint = None;
+    }
     let data = delegator.deserialize(&nested, type_hint)?;
-    Ok(Box::new(SnapshotPayload::new(ArcShared::from_boxed(data))))
+    Ok(Box::new(SnapshotPayload::new(ArcShared::from_boxed(data)).with_applied_migrations(applied)))
   }
 
   fn as_any(&self) -> &(dyn Any + Send + Sync) {
@@ -8,7 +8,8 @@ use fraktor_actor_core_kernel_rs::serialization::{
 use fraktor_utils_core_rs::sync::ArcShared;
 
 use crate::serialization::{
-  SNAPSHOT_SERIALIZER_ID, SnapshotPayload, SnapshotSerializer, register_persistence_serializers, wire,
+  AppliedMigration, EventMigration, EventMigrationError, EventMigrationRegistry, EventUpcaster, SNAPSHOT_SERIALIZER_ID,
+  SnapshotPayload, SnapshotSerializer, register_persistence_serializers, wire,
 };
 
 const I32_MANIFEST: &str = "test.I32";
@@ -387,3 +388,87 @@ fn snapshot_payload_with_empty_nested_manifest_fails_serialization() {
 
   assert!(matches!(serializer.to_binary(&payload), Err(SerializationError::InvalidFormat)));
 }
+
+/// v1 の値を 2 倍して、移行対象外のマニフェストで返す。
+struct DoubleLegacyValue;
+
+impl EventUpcaster for DoubleLegacyValue {
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    let bytes: [u8; 4] =
+      payload.bytes().try_into().map_err(|_| EventMigrationError::upcast_failed("test.Half", "i32"))?;
+    let doubled = i32::from_le_bytes(bytes) * 2;
+    Ok(vec![SerializedMessage::new(payload.serializer_id(), Some(I32_MANIFEST.into()), doubled.to_le_bytes().to_vec())])
+  }
+}
+
+struct SplitLegacyValue;
+
+impl EventUpcaster for SplitLegacyValue {
+  fn upcast(&self, payload: &SerializedMessage) -> Result<Vec<SerializedMessage>, EventMigrationError> {
+    let current = SerializedMessage::new(payload.serializer_id(), Some(I32_MANIFEST.into()), payload.bytes().to_vec());
+    Ok(vec![current.clone(), current])
+  }
+}
+
+fn migrating_serializer(registry: &ArcShared<SerializationRegistry>) -> SnapshotSerializer {
+  let id = SerializerId::try_from(110).expect("serializer id");
+  let migrations = EventMigrationRegistry::new()
+    .with_rename(id, "test.LegacyI32", I32_MANIFEST)
+    .expect("rename")
+    .with_migration(id, EventMigration::new("test.Half", 2).with_upcaster(1, ArcShared::new(DoubleLegacyValue)))
+    .expect("half")
+    .with_migration(id, EventMigration::new("test.Pair", 2).with_upcaster(1, ArcShared::new(SplitLegacyValue)))
+    .expect("pair");
+  SnapshotSerializer::new(SNAPSHOT_SERIALIZER_ID, registry.downgrade()).with_migrations(ArcShared::new(migrations))
+}
+
+fn stored_snapshot(manifest: &str, value: i32) -> Vec<u8> {
+  let id = SerializerId::try_from(110).expect("serializer id");
+  let nested = SerializedMessage::new(id, Some(manifest.into()), value.to_le_bytes().to_vec());
+  let mut bytes = Vec::new();
+  wire::write_string(&mut bytes, "").expect("type name");
+  wire::write_serialized(&mut bytes, &nested).expect("nested");
+  bytes
+}
+
+#[test]
+fn snapshot_load_applies_renames_and_upcasters() {
+  let registry = registry();
+  let serializer = migrating_serializer(&registry);
+
+  let renamed = serializer.from_binary(&stored_snapshot("test.LegacyI32", 5), None).expect("renamed");
+  let upcast = serializer.from_binary(&stored_snapshot("test.Half#1", 6), None).expect("upcast");
+  let current = serializer.from_binary(&stored_snapshot(I32_MANIFEST, 7), None).expect("current");
+
+  let renamed = renamed.downcast_ref::<SnapshotPayload>().expect("snapshot payload");
+  let upcast = upcast.downcast_ref::<SnapshotPayload>().expect("snapshot payload");
+  let current = current.downcast_ref::<SnapshotPayload>().expect("snapshot payload");
+  assert_eq!(renamed.downcast_ref::<i32>(), Some(&5));
+  assert_eq!(renamed.applied_migrations(), &[AppliedMigration::Renamed {
+    from: "test.LegacyI32".into(),
+    to:   I32_MANIFEST.into(),
+  }]);
+  assert_eq!(upcast.downcast_ref::<i32>(), Some(&12));
+  assert_eq!(upcast.applied_migrations(), &[AppliedMigration::Upcast {
+    from: "test.Half#1".into(),
+    to:   vec![String::from(I32_MANIFEST)],
+  }]);
+  assert_eq!(current.downcast_ref::<i32>(), Some(&7));
+  assert!(current.applied_migrations().is_empty());
+}
+
+#[test]
+fn snapshot_load_rejects_migrations_that_do_not_produce_one_payload() {
+  let registry = registry();
+  let serializer = migrating_serializer(&registry);
+
+  let split = serializer.from_binary(&stored_snapshot("test.Pair#1", 1), None);
+  let future = serializer.from_binary(&stored_snapshot("test.Half#3", 1), None);
+
+  assert!(matches!(
+    split,
+    Err(SerializationError::UnknownManifest(reason))
+      if reason == "snapshot manifest test.Pair#1 migrated into 2 payloads instead of one"
+  ));
+  assert!(matches!(future, Err(SerializationError::UnknownManifest(_))));
+}
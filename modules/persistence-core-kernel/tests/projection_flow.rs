@@ -0,0 +1,183 @@
+//! Projection stream integration tests.
+
+use core::{
+  any::Any,
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+use std::{
+  thread,
+  time::{Duration, Instant},
+};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext, error::ActorError, messaging::AnyMessageView, props::Props, scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  system::ActorSystem,
+};
+use fraktor_persistence_core_kernel_rs::{
+  journal::{Journal, SharedInMemoryJournal, Tagged},
+  persistent::{AtomicWrite, PersistentRepr},
+  projection::{InMemoryOffsetStore, OffsetStore, Projection, ProjectionError, ProjectionId},
+  query::{CurrentEventsByTagQuery, EventEnvelope, EventsByTagQuery, Offset, PollingReadJournal},
+};
+use fraktor_stream_core_kernel_rs::{
+  RestartConfig, SharedKillSwitch,
+  materialization::{ActorMaterializer, ActorMaterializerConfig, StreamDone},
+};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+fn build_materializer() -> ActorMaterializer {
+  let props = Props::from_fn(|| GuardianActor);
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  let config = ActorSystemConfig::new(TestTickDriver::default()).with_scheduler_config(scheduler);
+  let system = ActorSystem::create_from_props(&props, config).expect("system should build");
+  let config = ActorMaterializerConfig::default().with_drive_interval(Duration::from_millis(1));
+  let mut materializer = ActorMaterializer::new(system, config);
+  materializer.start().expect("materializer start");
+  materializer
+}
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was pending"),
+  }
+}
+
+fn write_event(journal: &mut SharedInMemoryJournal, persistence_id: &str, sequence_nr: u64, tag: &str) {
+  let event: ArcShared<dyn Any + Send + Sync> = ArcShared::new(sequence_nr as i32);
+  let payload: ArcShared<dyn Any + Send + Sync> = ArcShared::new(Tagged::with_tags(event, [tag]));
+  let write = AtomicWrite::new(vec![PersistentRepr::new(persistence_id, sequence_nr, payload)]).expect("atomic write");
+  poll_ready(journal.write_messages(&[write])).expect("write event");
+}
+
+fn wait_until(mut condition: impl FnMut() -> bool) {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while !condition() && Instant::now() < deadline {
+    thread::sleep(Duration::from_millis(1));
+  }
+}
+
+type Handled = ArcShared<SpinSyncMutex<Vec<i32>>>;
+
+fn recording_handler(handled: &Handled) -> impl FnMut(&EventEnvelope) -> Result<(), ProjectionError> + Send + 'static {
+  let handled = handled.clone();
+  move |envelope: &EventEnvelope| {
+    handled.lock().push(envelope.downcast_ref::<i32>().copied().expect("i32 event"));
+    Ok(())
+  }
+}
+
+#[test]
+fn projection_over_current_events_handles_all_events_and_stores_last_offset() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_event(&mut journal, "pid-1", 1, "blue");
+  write_event(&mut journal, "pid-2", 1, "red");
+  write_event(&mut journal, "pid-1", 2, "blue");
+  let read_journal = PollingReadJournal::new(journal);
+  let id = ProjectionId::new("blue-events", "0");
+  let offset_store = InMemoryOffsetStore::new();
+  let handled = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let projection = Projection::at_least_once(
+    id.clone(),
+    move |offset| read_journal.current_events_by_tag("blue", offset),
+    offset_store.clone(),
+    recording_handler(&handled),
+  );
+
+  let mut materializer = build_materializer();
+  let completion = projection
+    .runnable_graph(None, &SharedKillSwitch::new())
+    .run(&mut materializer)
+    .expect("materialize projection")
+    .materialized()
+    .clone();
+  wait_until(|| completion.is_ready());
+  materializer.shutdown().expect("materializer shutdown");
+
+  assert_eq!(completion.try_take(), Some(Ok(StreamDone::new())));
+  assert_eq!(*handled.lock(), vec![1, 2]);
+  assert_eq!(offset_store.read_offset(&id), Ok(Some(Offset::sequence(3))));
+}
+
+#[test]
+fn live_projection_resumes_after_stored_offset() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_event(&mut journal, "pid-1", 1, "blue");
+  write_event(&mut journal, "pid-1", 2, "blue");
+  let read_journal = PollingReadJournal::new(journal.clone());
+  let id = ProjectionId::new("blue-events", "0");
+  let offset_store = InMemoryOffsetStore::new();
+  let handled = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let projection = Projection::at_least_once(
+    id.clone(),
+    move |offset| read_journal.events_by_tag("blue", offset),
+    offset_store.clone(),
+    recording_handler(&handled),
+  );
+
+  let mut materializer = build_materializer();
+  let kill_switch = SharedKillSwitch::new();
+  let _first = projection.runnable_graph(None, &kill_switch).run(&mut materializer).expect("first run");
+  wait_until(|| offset_store.read_offset(&id) == Ok(Some(Offset::sequence(2))));
+  kill_switch.shutdown();
+
+  write_event(&mut journal, "pid-1", 3, "blue");
+  let offset = poll_ready(projection.load_offset()).expect("load offset");
+  let _second = projection.runnable_graph(offset, &SharedKillSwitch::new()).run(&mut materializer).expect("second run");
+  wait_until(|| offset_store.read_offset(&id) == Ok(Some(Offset::sequence(3))));
+  materializer.shutdown().expect("materializer shutdown");
+
+  assert_eq!(*handled.lock(), vec![1, 2, 3]);
+}
+
+#[test]
+fn projection_with_restart_config_retries_failed_envelopes() {
+  let mut journal = SharedInMemoryJournal::new();
+  for sequence_nr in 1..=3 {
+    write_event(&mut journal, "pid-1", sequence_nr, "blue");
+  }
+  let read_journal = PollingReadJournal::new(journal);
+  let id = ProjectionId::new("blue-events", "0");
+  let offset_store = InMemoryOffsetStore::new();
+  let handled = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let recorder = handled.clone();
+  let mut failures_left = 1;
+  let projection = Projection::at_least_once(
+    id.clone(),
+    move |offset| read_journal.current_events_by_tag("blue", offset),
+    offset_store.clone(),
+    move |envelope: &EventEnvelope| {
+      let event = envelope.downcast_ref::<i32>().copied().expect("i32 event");
+      if event == 2 && failures_left > 0 {
+        failures_left -= 1;
+        return Err(ProjectionError::handler_failed("transient failure"));
+      }
+      recorder.lock().push(event);
+      Ok(())
+    },
+  )
+  .with_restart_config(RestartConfig::new(1, 4, 3));
+
+  let mut materializer = build_materializer();
+  let _materialized =
+    projection.runnable_graph(None, &SharedKillSwitch::new()).run(&mut materializer).expect("materialize projection");
+  wait_until(|| offset_store.read_offset(&id) == Ok(Some(Offset::sequence(3))));
+  materializer.shutdown().expect("materializer shutdown");
+
+  assert_eq!(*handled.lock(), vec![1, 2, 3]);
+}
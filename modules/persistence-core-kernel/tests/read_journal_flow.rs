@@ -0,0 +1,151 @@
+//! Read journal stream integration tests.
+
+use core::{
+  any::Any,
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+use std::{
+  thread,
+  time::{Duration, Instant},
+};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext, error::ActorError, messaging::AnyMessageView, props::Props, scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  system::ActorSystem,
+};
+use fraktor_persistence_core_kernel_rs::{
+  journal::{Journal, SharedInMemoryJournal, Tagged},
+  persistent::{AtomicWrite, PersistentRepr},
+  query::{
+    CurrentEventsByPersistenceIdQuery, CurrentPersistenceIdsQuery, EventEnvelope, EventsByTagQuery, Offset,
+    PollingReadJournal,
+  },
+};
+use fraktor_stream_core_kernel_rs::{
+  StreamError,
+  dsl::{Sink, Source},
+  materialization::{ActorMaterializer, ActorMaterializerConfig, StreamNotUsed},
+};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+fn build_materializer() -> ActorMaterializer {
+  let props = Props::from_fn(|| GuardianActor);
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  let config = ActorSystemConfig::new(TestTickDriver::default()).with_scheduler_config(scheduler);
+  let system = ActorSystem::create_from_props(&props, config).expect("system should build");
+  let config = ActorMaterializerConfig::default().with_drive_interval(Duration::from_millis(1));
+  let mut materializer = ActorMaterializer::new(system, config);
+  materializer.start().expect("materializer start");
+  materializer
+}
+
+fn collect<Out: Send + Sync + 'static>(source: Source<Out, StreamNotUsed>) -> Result<Vec<Out>, StreamError> {
+  let mut materializer = build_materializer();
+  let materialized = source.run_with(Sink::collect(), &mut materializer)?;
+  let deadline = Instant::now() + Duration::from_secs(5);
+  let result = loop {
+    if let Some(result) = materialized.materialized().try_take() {
+      break result;
+    }
+    if Instant::now() >= deadline {
+      break Err(StreamError::WouldBlock);
+    }
+    thread::sleep(Duration::from_millis(1));
+  };
+  materializer.shutdown()?;
+  result
+}
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was pending"),
+  }
+}
+
+fn write_event(journal: &mut SharedInMemoryJournal, persistence_id: &str, sequence_nr: u64, tag: Option<&str>) {
+  let event: ArcShared<dyn Any + Send + Sync> = ArcShared::new(sequence_nr as i32);
+  let payload: ArcShared<dyn Any + Send + Sync> = match tag {
+    | Some(tag) => ArcShared::new(Tagged::with_tags(event, [tag])),
+    | None => event,
+  };
+  let write = AtomicWrite::new(vec![PersistentRepr::new(persistence_id, sequence_nr, payload)]).expect("atomic write");
+  poll_ready(journal.write_messages(&[write])).expect("write event");
+}
+
+#[test]
+fn current_events_by_persistence_id_stream_completes_with_stored_events() {
+  let mut journal = SharedInMemoryJournal::new();
+  for sequence_nr in 1..=5 {
+    write_event(&mut journal, "pid-1", sequence_nr, None);
+  }
+  let read_journal = PollingReadJournal::new(journal).with_page_size(2);
+
+  let events = collect(
+    read_journal
+      .current_events_by_persistence_id("pid-1", 1, u64::MAX)
+      .map(|envelope| envelope.downcast_ref::<i32>().copied().expect("i32 event")),
+  )
+  .expect("collect events");
+
+  assert_eq!(events, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn current_persistence_ids_stream_lists_written_ids() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_event(&mut journal, "pid-b", 1, None);
+  write_event(&mut journal, "pid-a", 1, None);
+
+  let ids = collect(PollingReadJournal::new(journal).current_persistence_ids()).expect("collect ids");
+
+  assert_eq!(ids, vec![String::from("pid-a"), String::from("pid-b")]);
+}
+
+#[test]
+fn live_events_by_tag_stream_picks_up_events_written_after_materialization() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_event(&mut journal, "pid-1", 1, Some("blue"));
+  let read_journal = PollingReadJournal::new(journal.clone());
+  let received = ArcShared::new(SpinSyncMutex::new(Vec::<Offset>::new()));
+  let sink_received = received.clone();
+
+  // ライブストリームは完了しないため、要素が揃った時点でマテリアライザごと停止する。
+  let mut materializer = build_materializer();
+  let _materialized = read_journal
+    .events_by_tag("blue", Offset::NoOffset)
+    .run_with(
+      Sink::foreach(move |envelope: EventEnvelope| sink_received.lock().push(envelope.offset().clone())),
+      &mut materializer,
+    )
+    .expect("materialize live tag stream");
+
+  thread::sleep(Duration::from_millis(20));
+  write_event(&mut journal, "pid-2", 1, Some("red"));
+  write_event(&mut journal, "pid-2", 2, Some("blue"));
+  write_event(&mut journal, "pid-1", 2, Some("blue"));
+
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while received.lock().len() < 3 && Instant::now() < deadline {
+    thread::sleep(Duration::from_millis(1));
+  }
+  materializer.shutdown().expect("materializer shutdown");
+
+  let offsets = received.lock().clone();
+  assert_eq!(offsets, vec![Offset::Sequence(1), Offset::Sequence(3), Offset::Sequence(4)]);
+}
@@ -22,7 +22,7 @@ use fraktor_actor_core_typed_rs::{
   actor::TypedActorContext,
   dsl::{Behaviors, StashBuffer},
 };
-use fraktor_persistence_core_kernel_rs::error::PersistenceError;
+use fraktor_persistence_core_kernel_rs::{error::PersistenceError, replication::ReplicatedEventMetadata};
 use fraktor_utils_core_rs::sync::{ArcShared, DefaultMutex, SharedLock};
 
 use crate::{
@@ -179,6 +179,12 @@ where
         if let Some(signal) = adapter.unwrap_signal(message) {
           return match signal {
             | EventSourcedEffectorSignal::RecoveryCompleted { state, sequence_nr, .. } => {
+              if config.replication().is_some() {
+                store_ref
+                  .clone()
+                  .try_tell(EventSourcedStoreCommand::StartReplication)
+                  .map_err(|error| ActorError::fatal(format!("start replication send failed: {error:?}")))?;
+              }
               let effector = Self::active(config.clone(), store_ref.clone(), reply_to.clone(), *sequence_nr);
               let next = on_ready(state.clone(), effector)?;
               stash.unstash_all(ctx)?;
@@ -208,6 +214,25 @@ where
     self.sequence_nr.with_lock(|sequence_nr| *sequence_nr)
   }
 
+  /// Returns the event and metadata carried by a [`EventSourcedEffectorSignal::ReplicatedEvent`].
+  ///
+  /// Aggregates of replicated effectors pass every signal they receive while idle to this method
+  /// and apply the returned event to their state. The latest known sequence number advances to the
+  /// local sequence number of the replicated event.
+  #[must_use]
+  pub fn replicated_event<'a>(
+    &self,
+    signal: &'a EventSourcedEffectorSignal<S, E>,
+  ) -> Option<(&'a E, &'a ReplicatedEventMetadata)> {
+    match signal {
+      | EventSourcedEffectorSignal::ReplicatedEvent { event, metadata, sequence_nr, .. } => {
+        self.update_sequence_nr(*sequence_nr);
+        Some((event, metadata))
+      },
+      | _ => None,
+    }
+  }
+
   /// Persists one event and runs a one-shot callback after success.
   pub fn persist_event<F>(
     &self,
@@ -467,6 +492,10 @@ where
             | EventSourcedEffectorSignal::EventSourced { signal, .. } => {
               Self::event_sourced_signal_behavior(signal, persist_failure_backoff_enabled)
             },
+            | EventSourcedEffectorSignal::ReplicatedEvent { .. } => {
+              stash.stash(ctx)?;
+              Ok(Behaviors::same())
+            },
             | _ => Ok(Behaviors::unhandled()),
           };
         }
@@ -534,6 +563,10 @@ where
             | EventSourcedEffectorSignal::EventSourced { signal, .. } => {
               Self::event_sourced_signal_behavior(signal, persist_failure_backoff_enabled)
             },
+            | EventSourcedEffectorSignal::ReplicatedEvent { .. } => {
+              stash.stash(ctx)?;
+              Ok(Behaviors::same())
+            },
             | _ => Ok(Behaviors::unhandled()),
           };
         }
@@ -607,6 +640,10 @@ where
             | EventSourcedEffectorSignal::EventSourced { signal, .. } => {
               Self::event_sourced_signal_behavior(signal, persist_failure_backoff_enabled)
             },
+            | EventSourcedEffectorSignal::ReplicatedEvent { .. } => {
+              stash.stash(ctx)?;
+              Ok(Behaviors::same())
+            },
             | _ => Ok(Behaviors::unhandled()),
           };
         }
@@ -653,6 +690,10 @@ where
           | EventSourcedEffectorSignal::EventSourced { signal, .. } => {
             Self::event_sourced_signal_behavior(signal, persist_failure_backoff_enabled)
           },
+          | EventSourcedEffectorSignal::ReplicatedEvent { .. } => {
+            stash.stash(ctx)?;
+            Ok(Behaviors::same())
+          },
           | _ => Ok(Behaviors::unhandled()),
         };
       }
@@ -691,6 +732,10 @@ where
           | EventSourcedEffectorSignal::EventSourced { signal, .. } => {
             Self::event_sourced_signal_behavior(signal, persist_failure_backoff_enabled)
           },
+          | EventSourcedEffectorSignal::ReplicatedEvent { .. } => {
+            stash.stash(ctx)?;
+            Ok(Behaviors::same())
+          },
           | _ => Ok(Behaviors::unhandled()),
         };
       }
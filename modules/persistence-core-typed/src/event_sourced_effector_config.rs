@@ -6,15 +6,20 @@ mod tests;
 
 use alloc::{collections::BTreeSet, string::String};
 
-use fraktor_persistence_core_kernel_rs::{error::PersistenceError, journal::EventAdapters};
+use fraktor_persistence_core_kernel_rs::{
+  error::PersistenceError,
+  journal::EventAdapters,
+  replication::{ReplicatedEventMetadata, VersionVector},
+};
 use fraktor_utils_core_rs::sync::ArcShared;
 
 use crate::{
   BackoffConfig, EventAdapter, EventSourcedEffectorMessageAdapter, PersistenceId, PersistenceMode, Recovery,
-  RetentionCriteria, SnapshotCriteria, event_adapter::KernelEventAdapterBridge,
+  ReplicationSettings, RetentionCriteria, SnapshotCriteria, event_adapter::KernelEventAdapterBridge,
 };
 
 type ApplyEvent<S, E> = dyn Fn(&S, &E) -> S + Send + Sync;
+type ApplyReplicatedEvent<S, E> = dyn Fn(&S, &E, &ReplicatedEventMetadata) -> S + Send + Sync;
 type EventTagger<E> = dyn Fn(&E) -> BTreeSet<String> + Send + Sync;
 
 /// Configuration used to build a typed event-sourced effector.
@@ -33,6 +38,8 @@ pub struct EventSourcedEffectorConfig<S, E, M> {
   event_publishing:        bool,
   event_tagger:            ArcShared<EventTagger<E>>,
   message_adapter:         Option<EventSourcedEffectorMessageAdapter<S, E, M>>,
+  replication:             Option<ReplicationSettings>,
+  apply_replicated_event:  Option<ArcShared<ApplyReplicatedEvent<S, E>>>,
 }
 
 impl<S, E, M> EventSourcedEffectorConfig<S, E, M> {
@@ -56,9 +63,41 @@ impl<S, E, M> EventSourcedEffectorConfig<S, E, M> {
       event_publishing: true,
       event_tagger: ArcShared::new(|_event: &E| BTreeSet::new()),
       message_adapter: None,
+      replication: None,
+      apply_replicated_event: None,
     }
   }
 
+  /// Creates a configuration for one replica of a replicated (active-active) entity.
+  ///
+  /// The persistence id is derived from the replication id. `apply_event` receives the
+  /// [`ReplicatedEventMetadata`] of every event, including events received from other replicas,
+  /// so it can resolve concurrent updates.
+  ///
+  /// Replicas rebuild their version vector from the full journal, so recovery skips snapshots
+  /// and retention must not delete events.
+  #[must_use]
+  pub fn replicated<F>(replication: ReplicationSettings, initial_state: S, apply_event: F) -> Self
+  where
+    F: Fn(&S, &E, &ReplicatedEventMetadata) -> S + Send + Sync + 'static,
+    S: 'static,
+    E: 'static, {
+    let persistence_id = PersistenceId::of_unique_id(replication.replication_id().persistence_id());
+    let apply_replicated_event: ArcShared<ApplyReplicatedEvent<S, E>> = ArcShared::new(apply_event);
+    // メタデータを持たない経路では、自レプリカ起点の非並行イベントとして適用する。
+    let local_replica = replication.replica_id().clone();
+    let local_apply = apply_replicated_event.clone();
+    let mut config = Self::new(persistence_id, initial_state, move |state: &S, event: &E| {
+      let metadata = ReplicatedEventMetadata::new(local_replica.clone(), 0, VersionVector::new(), false);
+      local_apply(state, event, &metadata)
+    });
+    // バージョンベクタと受信済み位置はジャーナル全体から復元するため、スナップショットは読まない。
+    config.recovery = Recovery::without_snapshot();
+    config.replication = Some(replication);
+    config.apply_replicated_event = Some(apply_replicated_event);
+    config
+  }
+
   /// Returns the persistence id.
   #[must_use]
   pub const fn persistence_id(&self) -> &PersistenceId {
@@ -77,6 +116,23 @@ impl<S, E, M> EventSourcedEffectorConfig<S, E, M> {
     (self.apply_event)(state, event)
   }
 
+  /// Applies one event together with its replication metadata to a state.
+  ///
+  /// Configurations that are not replicated ignore the metadata.
+  #[must_use]
+  pub fn apply_replicated_event(&self, state: &S, event: &E, metadata: &ReplicatedEventMetadata) -> S {
+    match &self.apply_replicated_event {
+      | Some(apply_event) => apply_event(state, event, metadata),
+      | None => (self.apply_event)(state, event),
+    }
+  }
+
+  /// Returns the replication settings of a replicated configuration.
+  #[must_use]
+  pub const fn replication(&self) -> Option<&ReplicationSettings> {
+    self.replication.as_ref()
+  }
+
   /// Returns the selected persistence mode.
   #[must_use]
   pub const fn persistence_mode(&self) -> PersistenceMode {
@@ -249,6 +305,17 @@ impl<S, E, M> EventSourcedEffectorConfig<S, E, M> {
     {
       return Err(validation_error("retention keep_snapshots must be greater than 0"));
     }
+    if self.replication.is_some() {
+      if self.persistence_mode != PersistenceMode::Persisted {
+        return Err(validation_error("replicated event sourcing requires persisted mode"));
+      }
+      if self.recovery != Recovery::without_snapshot() {
+        return Err(validation_error("replicated event sourcing must replay the full journal without snapshots"));
+      }
+      if self.retention_criteria.delete_events_on_snapshot() {
+        return Err(validation_error("replicated event sourcing must not delete events"));
+      }
+    }
     Ok(())
   }
 }
@@ -273,6 +340,8 @@ where
       event_publishing:        self.event_publishing,
       event_tagger:            self.event_tagger.clone(),
       message_adapter:         self.message_adapter.clone(),
+      replication:             self.replication.clone(),
+      apply_replicated_event:  self.apply_replicated_event.clone(),
     }
   }
 }
@@ -4,10 +4,16 @@ use alloc::{
 };
 use core::{any::Any, time::Duration};
 
+use fraktor_persistence_core_kernel_rs::{
+  journal::SharedInMemoryJournal,
+  query::PollingReadJournal,
+  replication::{ReplicaId, ReplicatedEventMetadata, ReplicationId, VersionVector},
+};
 use fraktor_utils_core_rs::sync::ArcShared;
 
 use crate::{
-  BackoffConfig, EventAdapter, EventSeq, EventSourcedEffectorConfig, PersistenceId, Recovery, SnapshotCriteria,
+  BackoffConfig, EventAdapter, EventSeq, EventSourcedEffectorConfig, PersistenceId, PersistenceMode, Recovery,
+  ReplicationSettings, SnapshotCriteria,
 };
 
 fn apply_event(state: &u32, event: &u32) -> u32 {
@@ -121,3 +127,52 @@ impl EventAdapter<u32> for AddTenAdapter {
     EventSeq::single(event.downcast_ref::<u32>().copied().unwrap_or_default())
   }
 }
+
+fn replicated_config() -> EventSourcedEffectorConfig<u32, u32, ()> {
+  let replication_id = ReplicationId::new("counter", "c-1", ReplicaId::new("a"));
+  let settings = ReplicationSettings::new(
+    replication_id,
+    [ReplicaId::new("b")],
+    PollingReadJournal::new(SharedInMemoryJournal::new()),
+  );
+  EventSourcedEffectorConfig::replicated(
+    settings,
+    0,
+    |state: &u32, event: &u32, metadata: &ReplicatedEventMetadata| {
+      if metadata.concurrent() { *state } else { state + event }
+    },
+  )
+}
+
+#[test]
+fn replicated_config_derives_persistence_id_from_replication_id() {
+  let config = replicated_config();
+
+  assert_eq!(config.persistence_id().as_str(), "counter|c-1|a");
+  assert!(config.replication().is_some());
+  assert!(config.validate().is_ok());
+}
+
+#[test]
+fn replicated_config_passes_metadata_to_apply_event() {
+  let config = replicated_config();
+  let concurrent = ReplicatedEventMetadata::new(ReplicaId::new("b"), 1, VersionVector::new(), true);
+
+  assert_eq!(config.apply_replicated_event(&1, &2, &concurrent), 1);
+  assert_eq!(config.apply_event(&1, &2), 3);
+}
+
+#[test]
+fn replicated_config_requires_persisted_mode() {
+  let config = replicated_config().with_persistence_mode(PersistenceMode::Ephemeral);
+
+  assert!(config.validate().is_err());
+}
+
+#[test]
+fn replicated_config_recovers_without_snapshots() {
+  let config = replicated_config();
+
+  assert_eq!(config.recovery(), &Recovery::without_snapshot());
+  assert!(config.with_recovery(Recovery::default()).validate().is_err());
+}
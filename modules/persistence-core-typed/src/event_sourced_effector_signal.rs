@@ -2,6 +2,8 @@
 
 use alloc::vec::Vec;
 
+use fraktor_persistence_core_kernel_rs::replication::ReplicatedEventMetadata;
+
 use crate::{EventSourcedSignal, PublishedEvent, event_sourced_effector_signal_auth::EventSourcedEffectorSignalAuth};
 
 /// Stable signal delivered to the aggregate actor through its private message type.
@@ -29,6 +31,21 @@ pub enum EventSourcedEffectorSignal<S, E> {
     /// Latest sequence number after the batch.
     sequence_nr:      u64,
   },
+  /// An event received from another replica was persisted by the local replica.
+  ///
+  /// Aggregates of replicated effectors apply `event` to their state, typically through
+  /// `EventSourcedEffector::replicated_event`.
+  #[non_exhaustive]
+  ReplicatedEvent {
+    #[doc(hidden)]
+    auth:        EventSourcedEffectorSignalAuth,
+    /// Replicated event.
+    event:       E,
+    /// Origin and version metadata of the event.
+    metadata:    ReplicatedEventMetadata,
+    /// Local sequence number of the persisted event.
+    sequence_nr: u64,
+  },
   /// A snapshot was persisted.
   #[non_exhaustive]
   PersistedSnapshot {
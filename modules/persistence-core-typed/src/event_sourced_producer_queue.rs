@@ -0,0 +1,39 @@
+//! Event-sourced durable producer queue.
+
+use fraktor_actor_core_typed_rs::{Behavior, delivery::DurableProducerQueueCommand};
+
+use crate::{EventSourcedProducerQueueSettings, PersistenceId, internal::ProducerQueueActor};
+
+/// Durable producer queue that journals its state through an event-sourced effector.
+///
+/// The returned behavior is passed as the durable queue of
+/// `ProducerController::behavior_with_config` (or `behavior_with_durable_queue`). It journals
+/// `MessageSent` and confirmation events under `persistence_id`, snapshots the queue state
+/// periodically and removes confirmation qualifiers that stayed unused for
+/// [`cleanup_unused_after`](EventSourcedProducerQueueSettings::cleanup_unused_after). A producer
+/// controller restarted with the same `persistence_id` reloads the queue and resends every
+/// unconfirmed message.
+///
+/// Corresponds to Pekko's `EventSourcedProducerQueue`.
+pub struct EventSourcedProducerQueue;
+
+impl EventSourcedProducerQueue {
+  /// Creates the queue behavior with default settings.
+  #[must_use]
+  pub fn behavior<A>(persistence_id: PersistenceId) -> Behavior<DurableProducerQueueCommand<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    Self::behavior_with_settings(persistence_id, EventSourcedProducerQueueSettings::default())
+  }
+
+  /// Creates the queue behavior with custom settings.
+  #[must_use]
+  pub fn behavior_with_settings<A>(
+    persistence_id: PersistenceId,
+    settings: EventSourcedProducerQueueSettings,
+  ) -> Behavior<DurableProducerQueueCommand<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    ProducerQueueActor::behavior(persistence_id, settings)
+  }
+}
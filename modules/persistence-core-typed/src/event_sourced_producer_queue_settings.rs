@@ -0,0 +1,115 @@
+//! Event-sourced producer queue settings.
+
+#[cfg(test)]
+#[path = "event_sourced_producer_queue_settings_test.rs"]
+mod tests;
+
+use core::time::Duration;
+
+use crate::BackoffConfig;
+
+/// Default number of events between two queue snapshots.
+const DEFAULT_SNAPSHOT_EVERY: u64 = 1000;
+/// Default number of queue snapshots kept by retention.
+const DEFAULT_KEEP_N_SNAPSHOTS: u64 = 2;
+/// Default age after which idle confirmation qualifiers are removed.
+const DEFAULT_CLEANUP_UNUSED_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// Configures an [`EventSourcedProducerQueue`](crate::EventSourcedProducerQueue).
+///
+/// Corresponds to Pekko's `EventSourcedProducerQueue.Settings`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventSourcedProducerQueueSettings {
+  snapshot_every:       u64,
+  keep_n_snapshots:     u64,
+  delete_events:        bool,
+  cleanup_unused_after: Duration,
+  restart_backoff:      BackoffConfig,
+}
+
+impl EventSourcedProducerQueueSettings {
+  /// Creates default settings.
+  #[must_use]
+  pub fn new() -> Self {
+    Self {
+      snapshot_every:       DEFAULT_SNAPSHOT_EVERY,
+      keep_n_snapshots:     DEFAULT_KEEP_N_SNAPSHOTS,
+      delete_events:        true,
+      cleanup_unused_after: DEFAULT_CLEANUP_UNUSED_AFTER,
+      restart_backoff:      BackoffConfig::default(),
+    }
+  }
+
+  /// Returns the number of events between two queue snapshots.
+  #[must_use]
+  pub const fn snapshot_every(&self) -> u64 {
+    self.snapshot_every
+  }
+
+  /// Returns settings that snapshot the queue every `number_of_events` events.
+  #[must_use]
+  pub const fn with_snapshot_every(mut self, number_of_events: u64) -> Self {
+    self.snapshot_every = number_of_events;
+    self
+  }
+
+  /// Returns the number of queue snapshots kept by retention.
+  #[must_use]
+  pub const fn keep_n_snapshots(&self) -> u64 {
+    self.keep_n_snapshots
+  }
+
+  /// Returns settings that keep `keep_n_snapshots` queue snapshots.
+  #[must_use]
+  pub const fn with_keep_n_snapshots(mut self, keep_n_snapshots: u64) -> Self {
+    self.keep_n_snapshots = keep_n_snapshots;
+    self
+  }
+
+  /// Returns whether events older than the retained snapshots are deleted.
+  #[must_use]
+  pub const fn delete_events(&self) -> bool {
+    self.delete_events
+  }
+
+  /// Returns settings that select whether events older than the retained snapshots are deleted.
+  #[must_use]
+  pub const fn with_delete_events(mut self, delete_events: bool) -> Self {
+    self.delete_events = delete_events;
+    self
+  }
+
+  /// Returns the age after which confirmation qualifiers without unconfirmed messages are removed.
+  ///
+  /// The same duration is used as the interval of the cleanup check.
+  #[must_use]
+  pub const fn cleanup_unused_after(&self) -> Duration {
+    self.cleanup_unused_after
+  }
+
+  /// Returns settings with the given confirmation qualifier cleanup age.
+  #[must_use]
+  pub const fn with_cleanup_unused_after(mut self, cleanup_unused_after: Duration) -> Self {
+    self.cleanup_unused_after = cleanup_unused_after;
+    self
+  }
+
+  /// Returns the backoff used to restart the journal store after a persist failure.
+  #[must_use]
+  pub const fn restart_backoff(&self) -> &BackoffConfig {
+    &self.restart_backoff
+  }
+
+  /// Returns settings with the given journal store restart backoff.
+  #[must_use]
+  pub const fn with_restart_backoff(mut self, restart_backoff: BackoffConfig) -> Self {
+    self.restart_backoff = restart_backoff;
+    self
+  }
+}
+
+impl Default for EventSourcedProducerQueueSettings {
+  fn default() -> Self {
+    Self::new()
+  }
+}
@@ -0,0 +1,31 @@
+use core::time::Duration;
+
+use crate::{BackoffConfig, EventSourcedProducerQueueSettings};
+
+#[test]
+fn default_settings_snapshot_every_thousand_events_and_clean_up_after_one_hour() {
+  let settings = EventSourcedProducerQueueSettings::default();
+
+  assert_eq!(settings.snapshot_every(), 1000);
+  assert_eq!(settings.keep_n_snapshots(), 2);
+  assert!(settings.delete_events());
+  assert_eq!(settings.cleanup_unused_after(), Duration::from_secs(3600));
+  assert_eq!(settings.restart_backoff(), &BackoffConfig::default());
+}
+
+#[test]
+fn builders_override_each_setting() {
+  let backoff = BackoffConfig::new(Duration::from_millis(10), Duration::from_millis(100), 0.0);
+  let settings = EventSourcedProducerQueueSettings::new()
+    .with_snapshot_every(10)
+    .with_keep_n_snapshots(3)
+    .with_delete_events(false)
+    .with_cleanup_unused_after(Duration::from_secs(5))
+    .with_restart_backoff(backoff.clone());
+
+  assert_eq!(settings.snapshot_every(), 10);
+  assert_eq!(settings.keep_n_snapshots(), 3);
+  assert!(!settings.delete_events());
+  assert_eq!(settings.cleanup_unused_after(), Duration::from_secs(5));
+  assert_eq!(settings.restart_backoff(), &backoff);
+}
@@ -1,9 +1,15 @@
-//! Internal event-sourced store protocol and actor.
+//! Internal store, producer queue and projection protocols and actors.
 
 mod ephemeral_persistence_store;
 mod event_sourced_store_actor;
 mod event_sourced_store_command;
 mod event_sourced_store_reply;
+mod producer_queue_actor;
+mod producer_queue_command;
+mod producer_queue_event;
+mod projection_actor;
+mod projection_actor_event;
+mod replicated_event_streams;
 mod state_sourced_store_actor;
 mod state_sourced_store_command;
 mod state_sourced_store_reply;
@@ -12,6 +18,12 @@ pub(crate) use ephemeral_persistence_store::EphemeralPersistenceStore;
 pub(crate) use event_sourced_store_actor::EventSourcedStoreActor;
 pub(crate) use event_sourced_store_command::EventSourcedStoreCommand;
 pub(crate) use event_sourced_store_reply::EventSourcedStoreReply;
+pub(crate) use producer_queue_actor::ProducerQueueActor;
+pub(crate) use producer_queue_command::ProducerQueueCommand;
+pub(crate) use producer_queue_event::ProducerQueueEvent;
+pub(crate) use projection_actor::ProjectionActor;
+pub(crate) use projection_actor_event::ProjectionActorEvent;
+pub(crate) use replicated_event_streams::ReplicatedEventStreams;
 pub(crate) use state_sourced_store_actor::StateSourcedStoreActor;
 pub(crate) use state_sourced_store_command::StateSourcedStoreCommand;
 pub(crate) use state_sourced_store_reply::StateSourcedStoreReply;
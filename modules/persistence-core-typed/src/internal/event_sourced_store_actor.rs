@@ -4,7 +4,8 @@
 #[path = "event_sourced_store_actor_test.rs"]
 mod tests;
 
-use alloc::{boxed::Box, format, string::ToString, vec, vec::Vec};
+use alloc::{boxed::Box, collections::BTreeMap, format, string::ToString, vec, vec::Vec};
+use core::any::Any;
 
 use fraktor_actor_core_kernel_rs::actor::{
   ActorContext,
@@ -19,13 +20,15 @@ use fraktor_persistence_core_kernel_rs::{
   persistent::{
     Eventsourced, PersistenceContext, PersistentActor, PersistentRepr, Recovery as KernelRecovery, persistent_props,
   },
+  query::EventEnvelope,
+  replication::{ReplicaId, ReplicatedEventMetadata, VersionVector},
   snapshot::{Snapshot, SnapshotError, SnapshotMetadata, SnapshotSelectionCriteria},
 };
 use fraktor_utils_core_rs::sync::{ArcShared, DefaultMutex, SharedLock};
 
 use crate::{
   EventRejectedError, EventSourcedEffectorConfig, EventSourcedSignal, PersistenceId, PublishedEvent,
-  internal::{EventSourcedStoreCommand, EventSourcedStoreReply},
+  internal::{EventSourcedStoreCommand, EventSourcedStoreReply, ReplicatedEventStreams},
 };
 
 type ReplyRef<S, E> = TypedActorRef<EventSourcedStoreReply<S, E>>;
@@ -45,6 +48,9 @@ where
   pending_snapshot:         Option<(S, ReplyRef<S, E>)>,
   pending_delete_snapshots: Option<(u64, ReplyRef<S, E>)>,
   pending_delete_events:    Option<(u64, ReplyRef<S, E>)>,
+  version:                  VersionVector,
+  seen:                     BTreeMap<ReplicaId, u64>,
+  replication_streams:      Option<ReplicatedEventStreams>,
 }
 
 impl<S, E, M> EventSourcedStoreActor<S, E, M>
@@ -82,6 +88,9 @@ where
       pending_snapshot: None,
       pending_delete_snapshots: None,
       pending_delete_events: None,
+      version: VersionVector::new(),
+      seen: BTreeMap::new(),
+      replication_streams: None,
     }
   }
 
@@ -97,7 +106,7 @@ where
         actor.reply_persist_type_mismatch(repr);
         return;
       };
-      actor.state = actor.config.apply_event(&actor.state, persisted_event);
+      actor.state = actor.apply_persisted_event(repr, persisted_event);
       let sequence_nr = actor.context.last_sequence_nr();
       actor.pending_persist_reply = None;
       Self::reply(&reply_to, EventSourcedStoreReply::PersistedEvents {
@@ -143,7 +152,7 @@ where
           actor.reply_persist_type_mismatch(repr);
           return;
         };
-        actor.state = actor.config.apply_event(&actor.state, persisted_event);
+        actor.state = actor.apply_persisted_event(repr, persisted_event);
         if let Some(published_event) = actor.published_event(repr, persisted_event.clone()) {
           published_events.with_lock(|events| {
             events.push(published_event);
@@ -232,7 +241,88 @@ where
 
   fn add_event_to_batch(&mut self, ctx: &mut ActorContext<'_>, event: E, handler: StorePersistHandler<S, E, M>) {
     let sender = ctx.sender().map(|sender| sender.pid());
-    self.context.add_to_event_batch(event, true, sender, handler);
+    let Some(replica_id) = self.config.replication().map(|replication| replication.replica_id().clone()) else {
+      self.context.add_to_event_batch(event, true, sender, handler);
+      return;
+    };
+    self.version = self.version.increment(&replica_id);
+    let origin_sequence_nr = self.context.current_sequence_nr().saturating_add(1);
+    let metadata: ArcShared<dyn Any + Send + Sync> =
+      ArcShared::new(ReplicatedEventMetadata::new(replica_id, origin_sequence_nr, self.version.clone(), false));
+    self.context.add_to_event_batch_with_metadata(event, metadata, true, sender, handler);
+  }
+
+  fn apply_persisted_event(&self, repr: &PersistentRepr, event: &E) -> S {
+    match replicated_metadata(repr) {
+      | Some(metadata) => self.config.apply_replicated_event(&self.state, event, metadata),
+      | None => self.config.apply_event(&self.state, event),
+    }
+  }
+
+  fn observe_replicated_event(&mut self, metadata: &ReplicatedEventMetadata) {
+    self.version = self.version.merge(metadata.version());
+    let is_local =
+      self.config.replication().is_some_and(|replication| replication.replica_id() == metadata.origin_replica());
+    if !is_local {
+      let seen = self.seen.entry(metadata.origin_replica().clone()).or_insert(0);
+      *seen = (*seen).max(metadata.origin_sequence_nr());
+    }
+  }
+
+  fn start_replication(&mut self, ctx: &mut ActorContext<'_>) -> Result<(), ActorError> {
+    let Some(replication) = self.config.replication() else {
+      return Ok(());
+    };
+    if self.replication_streams.is_some() {
+      return Ok(());
+    }
+    let streams = ReplicatedEventStreams::start::<S, E>(ctx.system().clone(), replication, &self.seen, &ctx.self_ref())
+      .map_err(|error| ActorError::recoverable(format!("replication stream start failed: {error}")))?;
+    self.replication_streams = Some(streams);
+    Ok(())
+  }
+
+  fn persist_replicated_event(
+    &mut self,
+    ctx: &mut ActorContext<'_>,
+    replica: &ReplicaId,
+    envelope: &EventEnvelope,
+  ) -> Result<(), ActorError> {
+    let Some(metadata) = envelope.metadata().and_then(|metadata| metadata.downcast_ref::<ReplicatedEventMetadata>())
+    else {
+      return Ok(());
+    };
+    let Some(event) = envelope.downcast_ref::<E>() else {
+      return Err(ActorError::fatal(format!(
+        "replicated event payload type mismatch for persistence id {} sequence number {}",
+        envelope.persistence_id(),
+        envelope.sequence_nr()
+      )));
+    };
+    let already_seen = self.seen.get(replica).copied().unwrap_or(0);
+    if metadata.origin_replica() != replica || metadata.origin_sequence_nr() <= already_seen {
+      return Ok(());
+    }
+    // 受信時点のローカル履歴と因果関係がなければ並行更新として記録する。
+    let concurrent = self.version.is_concurrent(metadata.version());
+    let metadata = metadata.clone().with_concurrent(concurrent);
+    self.observe_replicated_event(&metadata);
+    let reply_to = self.recovery_reply_to.clone();
+    let stored_metadata: ArcShared<dyn Any + Send + Sync> = ArcShared::new(metadata.clone());
+    let handler = Box::new(move |actor: &mut Self, repr: &PersistentRepr| {
+      let Some(persisted_event) = repr.downcast_ref::<E>() else {
+        return;
+      };
+      actor.state = actor.config.apply_replicated_event(&actor.state, persisted_event, &metadata);
+      Self::reply(&reply_to, EventSourcedStoreReply::ReplicatedEvent {
+        event: persisted_event.clone(),
+        metadata,
+        sequence_nr: repr.sequence_nr(),
+      });
+    });
+    let sender = ctx.sender().map(|sender| sender.pid());
+    self.context.add_to_event_batch_with_metadata(event.clone(), stored_metadata, true, sender, handler);
+    self.flush_batch(ctx)
   }
 
   fn published_events(&self, repr: &PersistentRepr, event: E) -> Vec<PublishedEvent<E>> {
@@ -293,7 +383,10 @@ where
 
   fn receive_recover(&mut self, repr: &PersistentRepr) {
     if let Some(event) = repr.downcast_ref::<E>() {
-      self.state = self.config.apply_event(&self.state, event);
+      self.state = self.apply_persisted_event(repr, event);
+    }
+    if let Some(metadata) = replicated_metadata(repr) {
+      self.observe_replicated_event(metadata);
     }
   }
 
@@ -324,6 +417,12 @@ where
         | EventSourcedStoreCommand::DeleteEvents { to_sequence_nr, reply_to } => {
           self.delete_events_to(ctx, *to_sequence_nr, reply_to.clone())?;
         },
+        | EventSourcedStoreCommand::StartReplication => {
+          self.start_replication(ctx)?;
+        },
+        | EventSourcedStoreCommand::ReplicatedEvent { replica, envelope } => {
+          self.persist_replicated_event(ctx, replica, envelope)?;
+        },
       }
     }
     Ok(())
@@ -462,3 +561,7 @@ where
     self.config.stash_capacity()
   }
 }
+
+fn replicated_metadata(repr: &PersistentRepr) -> Option<&ReplicatedEventMetadata> {
+  repr.metadata().and_then(|metadata| metadata.downcast_ref::<ReplicatedEventMetadata>())
+}
@@ -3,6 +3,7 @@
 use alloc::vec::Vec;
 
 use fraktor_actor_core_typed_rs::TypedActorRef;
+use fraktor_persistence_core_kernel_rs::{query::EventEnvelope, replication::ReplicaId};
 
 use crate::internal::EventSourcedStoreReply;
 
@@ -15,4 +16,6 @@ where
   PersistSnapshot { snapshot: S, reply_to: TypedActorRef<EventSourcedStoreReply<S, E>> },
   DeleteSnapshots { to_sequence_nr: u64, reply_to: TypedActorRef<EventSourcedStoreReply<S, E>> },
   DeleteEvents { to_sequence_nr: u64, reply_to: TypedActorRef<EventSourcedStoreReply<S, E>> },
+  StartReplication,
+  ReplicatedEvent { replica: ReplicaId, envelope: EventEnvelope },
 }
@@ -2,6 +2,8 @@
 
 use alloc::vec::Vec;
 
+use fraktor_persistence_core_kernel_rs::replication::ReplicatedEventMetadata;
+
 use crate::{
   EventSourcedEffectorSignal, EventSourcedSignal, PublishedEvent,
   event_sourced_effector_signal_auth::EventSourcedEffectorSignalAuth,
@@ -11,6 +13,7 @@ use crate::{
 pub(crate) enum EventSourcedStoreReply<S, E> {
   RecoveryCompleted { state: S, sequence_nr: u64 },
   PersistedEvents { events: Vec<E>, published_events: Vec<PublishedEvent<E>>, sequence_nr: u64 },
+  ReplicatedEvent { event: E, metadata: ReplicatedEventMetadata, sequence_nr: u64 },
   PersistedSnapshot { snapshot: S, sequence_nr: u64 },
   DeletedSnapshots { to_sequence_nr: u64 },
   EventSourced { signal: EventSourcedSignal },
@@ -25,6 +28,9 @@ impl<S, E> From<EventSourcedStoreReply<S, E>> for EventSourcedEffectorSignal<S,
       | EventSourcedStoreReply::PersistedEvents { events, published_events, sequence_nr } => {
         Self::PersistedEvents { auth: EventSourcedEffectorSignalAuth::new(), events, published_events, sequence_nr }
       },
+      | EventSourcedStoreReply::ReplicatedEvent { event, metadata, sequence_nr } => {
+        Self::ReplicatedEvent { auth: EventSourcedEffectorSignalAuth::new(), event, metadata, sequence_nr }
+      },
       | EventSourcedStoreReply::PersistedSnapshot { snapshot, sequence_nr } => {
         Self::PersistedSnapshot { auth: EventSourcedEffectorSignalAuth::new(), snapshot, sequence_nr }
       },
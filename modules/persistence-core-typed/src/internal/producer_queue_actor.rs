@@ -0,0 +1,241 @@
+//! Internal actor journaling a durable producer queue.
+
+#[cfg(test)]
+#[path = "producer_queue_actor_test.rs"]
+mod tests;
+
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  format,
+  string::String,
+};
+use core::time::Duration;
+
+use fraktor_actor_core_kernel_rs::actor::error::ActorError;
+use fraktor_actor_core_typed_rs::{
+  Behavior, TypedProps,
+  actor::TypedActorContext,
+  delivery::{DurableProducerQueueCommand, DurableProducerQueueState, StoreMessageSentAck},
+  dsl::{Behaviors, TimerKey},
+};
+
+use crate::{
+  EventSourcedEffector, EventSourcedEffectorConfig, EventSourcedEffectorMessageAdapter,
+  EventSourcedProducerQueueSettings, PersistenceId, RetentionCriteria, SnapshotCriteria,
+  internal::{ProducerQueueCommand, ProducerQueueEvent},
+};
+
+const CLEANUP_TIMER_KEY: &str = "producer-queue-cleanup";
+
+type QueueState<A> = DurableProducerQueueState<A>;
+type QueueEffector<A> = EventSourcedEffector<QueueState<A>, ProducerQueueEvent<A>, ProducerQueueCommand<A>>;
+/// Monotonic millis at which this incarnation last received a confirmation per qualifier.
+type ConfirmedAt = BTreeMap<String, u64>;
+
+/// Journals `MessageSent`, confirmation and cleanup events of one producer queue.
+///
+/// Producer controllers spawn their durable queue from a plain behavior, so the public behavior
+/// only forwards commands to a child that owns the stash mailbox required by the effector.
+pub(crate) struct ProducerQueueActor;
+
+impl ProducerQueueActor {
+  pub(crate) fn behavior<A>(
+    persistence_id: PersistenceId,
+    settings: EventSourcedProducerQueueSettings,
+  ) -> Behavior<DurableProducerQueueCommand<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    Behaviors::setup_result(move |ctx| {
+      let props = Self::props::<A>(persistence_id.clone(), &settings);
+      let child = ctx
+        .spawn_child(&props)
+        .map_err(|error| ActorError::fatal(format!("producer queue spawn failed: {error:?}")))?;
+      let queue = child.actor_ref();
+      Ok(Behaviors::receive_message(move |_ctx, command: &DurableProducerQueueCommand<A>| {
+        let mut queue = queue.clone();
+        queue
+          .try_tell(ProducerQueueCommand::queue(command))
+          .map_err(|error| ActorError::recoverable(format!("producer queue forward failed: {error:?}")))?;
+        Ok(Behaviors::same())
+      }))
+    })
+  }
+
+  fn props<A>(
+    persistence_id: PersistenceId,
+    settings: &EventSourcedProducerQueueSettings,
+  ) -> TypedProps<ProducerQueueCommand<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    let message_adapter =
+      EventSourcedEffectorMessageAdapter::new(ProducerQueueCommand::Persistence, |message| match message {
+        | ProducerQueueCommand::Persistence(signal) => Some(signal),
+        | _ => None,
+      });
+    let mut retention_criteria =
+      RetentionCriteria::snapshot_every(settings.snapshot_every(), settings.keep_n_snapshots());
+    if settings.delete_events() {
+      retention_criteria = retention_criteria.with_delete_events_on_snapshot();
+    }
+    let config = EventSourcedEffectorConfig::new(persistence_id, QueueState::<A>::empty(), ProducerQueueEvent::apply)
+      .with_snapshot_criteria(SnapshotCriteria::every(settings.snapshot_every()))
+      .with_retention_criteria(retention_criteria)
+      .on_persist_failure(settings.restart_backoff().clone())
+      .with_message_adapter(message_adapter);
+    let cleanup_unused_after = settings.cleanup_unused_after();
+    EventSourcedEffector::props(config, move |state, effector| {
+      Ok(Behaviors::with_timers(move |timers| {
+        let state = state.clone();
+        let effector = effector.clone();
+        Behaviors::setup_result(move |ctx| {
+          timers
+            .with_lock(|timers| {
+              timers.start_timer_with_fixed_delay(
+                TimerKey::new(CLEANUP_TIMER_KEY),
+                ProducerQueueCommand::CleanupTick,
+                cleanup_unused_after,
+              )
+            })
+            .map_err(|error| ActorError::recoverable(format!("producer queue cleanup timer failed: {error:?}")))?;
+          // 復元した qualifier は、この incarnation で確認を受けた時点から数え直す
+          let now_millis = duration_millis(ctx.system().state().monotonic_now());
+          let confirmed_at = state.confirmed_seq_nr().keys().map(|qualifier| (qualifier.clone(), now_millis)).collect();
+          Ok(Self::ready(state.clone(), effector.clone(), cleanup_unused_after, confirmed_at))
+        })
+      }))
+    })
+  }
+
+  fn ready<A>(
+    state: QueueState<A>,
+    effector: QueueEffector<A>,
+    cleanup_unused_after: Duration,
+    confirmed_at: ConfirmedAt,
+  ) -> Behavior<ProducerQueueCommand<A>>
+  where
+    A: Clone + Send + Sync + 'static, {
+    Behaviors::receive_message(move |ctx, message| match message {
+      | ProducerQueueCommand::Queue(command) => {
+        Self::receive_queue_command(ctx, &state, &effector, cleanup_unused_after, &confirmed_at, command)
+      },
+      | ProducerQueueCommand::CleanupTick => {
+        let now_millis = duration_millis(ctx.system().state().monotonic_now());
+        let confirmation_qualifiers = Self::unused_confirmation_qualifiers(
+          &state,
+          &confirmed_at,
+          now_millis,
+          duration_millis(cleanup_unused_after),
+        );
+        if confirmation_qualifiers.is_empty() {
+          return Ok(Behaviors::same());
+        }
+        let mut next_confirmed_at = confirmed_at.clone();
+        next_confirmed_at.retain(|qualifier, _| !confirmation_qualifiers.contains(qualifier));
+        let event = ProducerQueueEvent::Cleanup { confirmation_qualifiers };
+        Self::persist(ctx, &state, &effector, cleanup_unused_after, next_confirmed_at, event, || {})
+      },
+      | ProducerQueueCommand::Persistence(_) => Ok(Behaviors::unhandled()),
+    })
+  }
+
+  fn receive_queue_command<A>(
+    ctx: &mut TypedActorContext<'_, ProducerQueueCommand<A>>,
+    state: &QueueState<A>,
+    effector: &QueueEffector<A>,
+    cleanup_unused_after: Duration,
+    confirmed_at: &ConfirmedAt,
+    command: &DurableProducerQueueCommand<A>,
+  ) -> Result<Behavior<ProducerQueueCommand<A>>, ActorError>
+  where
+    A: Clone + Send + Sync + 'static, {
+    match command {
+      | DurableProducerQueueCommand::LoadState { reply_to } => {
+        reply_to.clone().tell(state.clone());
+        Ok(Behaviors::same())
+      },
+      | DurableProducerQueueCommand::StoreMessageSent { sent, reply_to } => {
+        let seq_nr = sent.seq_nr();
+        if seq_nr == state.current_seq_nr() {
+          let mut reply_to = reply_to.clone();
+          let event = ProducerQueueEvent::MessageSent(sent.clone());
+          Self::persist(ctx, state, effector, cleanup_unused_after, confirmed_at.clone(), event, move || {
+            reply_to.tell(StoreMessageSentAck::new(seq_nr));
+          })
+        } else if seq_nr.checked_add(1) == Some(state.current_seq_nr()) {
+          // タイムアウト後の再送は保存済みなので、journal に書かずに ack だけを返す
+          reply_to.clone().tell(StoreMessageSentAck::new(seq_nr));
+          Ok(Behaviors::same())
+        } else {
+          Err(ActorError::recoverable(format!(
+            "invalid producer queue sequence number {seq_nr}, expected {}",
+            state.current_seq_nr()
+          )))
+        }
+      },
+      | DurableProducerQueueCommand::StoreMessageConfirmed { seq_nr, confirmation_qualifier, timestamp_millis } => {
+        // Controllers do not supply a usable clock, so cleanup ages confirmations by their arrival here.
+        let mut next_confirmed_at = confirmed_at.clone();
+        next_confirmed_at.insert(confirmation_qualifier.clone(), duration_millis(ctx.system().state().monotonic_now()));
+        let stored_seq_nr =
+          state.confirmed_seq_nr().get(confirmation_qualifier).map_or(0, |(stored_seq_nr, _)| *stored_seq_nr);
+        if *seq_nr <= stored_seq_nr {
+          return Ok(Self::ready(state.clone(), effector.clone(), cleanup_unused_after, next_confirmed_at));
+        }
+        let event = ProducerQueueEvent::Confirmed {
+          seq_nr:                 *seq_nr,
+          confirmation_qualifier: confirmation_qualifier.clone(),
+          timestamp_millis:       *timestamp_millis,
+        };
+        Self::persist(ctx, state, effector, cleanup_unused_after, next_confirmed_at, event, || {})
+      },
+    }
+  }
+
+  fn persist<A, F>(
+    ctx: &mut TypedActorContext<'_, ProducerQueueCommand<A>>,
+    state: &QueueState<A>,
+    effector: &QueueEffector<A>,
+    cleanup_unused_after: Duration,
+    confirmed_at: ConfirmedAt,
+    event: ProducerQueueEvent<A>,
+    on_persisted: F,
+  ) -> Result<Behavior<ProducerQueueCommand<A>>, ActorError>
+  where
+    A: Clone + Send + Sync + 'static,
+    F: FnOnce() + Send + 'static, {
+    let next_state = ProducerQueueEvent::apply(state, &event);
+    let next_effector = effector.clone();
+    effector.persist_event_with_snapshot(ctx, event, next_state.clone(), false, move |_event| {
+      on_persisted();
+      Ok(Self::ready(next_state, next_effector, cleanup_unused_after, confirmed_at))
+    })
+  }
+
+  /// Returns qualifiers whose last confirmation arrived at or before
+  /// `now_millis - cleanup_unused_after_millis` and that no unconfirmed message still refers to.
+  fn unused_confirmation_qualifiers<A>(
+    state: &QueueState<A>,
+    confirmed_at: &ConfirmedAt,
+    now_millis: u64,
+    cleanup_unused_after_millis: u64,
+  ) -> BTreeSet<String>
+  where
+    A: Clone + Send + Sync + 'static, {
+    let Some(threshold_millis) = now_millis.checked_sub(cleanup_unused_after_millis) else {
+      return BTreeSet::new();
+    };
+    state
+      .confirmed_seq_nr()
+      .iter()
+      .filter(|(qualifier, _)| {
+        confirmed_at.get(qualifier.as_str()).is_some_and(|confirmed_millis| *confirmed_millis <= threshold_millis)
+          && !state.unconfirmed().iter().any(|sent| sent.confirmation_qualifier() == qualifier.as_str())
+      })
+      .map(|(qualifier, _)| qualifier.clone())
+      .collect()
+  }
+}
+
+fn duration_millis(duration: Duration) -> u64 {
+  u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,90 @@
+use alloc::{
+  collections::{BTreeMap, BTreeSet},
+  string::{String, ToString},
+  vec::Vec,
+};
+
+use fraktor_actor_core_typed_rs::delivery::{DurableProducerQueueState, MessageSent};
+
+use super::ProducerQueueActor;
+use crate::internal::ProducerQueueEvent;
+
+fn sent(seq_nr: u64, qualifier: &str) -> MessageSent<u32> {
+  MessageSent::new(seq_nr, 10 + u32::try_from(seq_nr).expect("seq nr"), false, qualifier.to_string(), 0)
+}
+
+fn replay(events: &[ProducerQueueEvent<u32>]) -> DurableProducerQueueState<u32> {
+  events.iter().fold(DurableProducerQueueState::empty(), |state, event| ProducerQueueEvent::apply(&state, event))
+}
+
+fn confirmed(seq_nr: u64, qualifier: &str, timestamp_millis: u64) -> ProducerQueueEvent<u32> {
+  ProducerQueueEvent::Confirmed { seq_nr, confirmation_qualifier: qualifier.to_string(), timestamp_millis }
+}
+
+#[test]
+fn replayed_events_rebuild_unconfirmed_messages_and_confirmations() {
+  let state = replay(&[
+    ProducerQueueEvent::MessageSent(sent(1, "a")),
+    ProducerQueueEvent::MessageSent(sent(2, "b")),
+    ProducerQueueEvent::MessageSent(sent(3, "a")),
+    confirmed(1, "a", 5),
+  ]);
+
+  assert_eq!(state.current_seq_nr(), 4);
+  assert_eq!(state.highest_confirmed_seq_nr(), 1);
+  assert_eq!(state.unconfirmed().iter().map(MessageSent::seq_nr).collect::<Vec<_>>(), [2, 3]);
+  assert_eq!(state.confirmed_seq_nr().get("a"), Some(&(1, 5)));
+}
+
+#[test]
+fn cleanup_event_removes_confirmation_qualifiers() {
+  let state =
+    replay(&[ProducerQueueEvent::MessageSent(sent(1, "a")), confirmed(1, "a", 5), ProducerQueueEvent::Cleanup {
+      confirmation_qualifiers: BTreeSet::from(["a".to_string()]),
+    }]);
+
+  assert!(state.confirmed_seq_nr().is_empty());
+  assert_eq!(state.highest_confirmed_seq_nr(), 1);
+}
+
+#[test]
+fn unused_qualifiers_are_old_and_have_no_unconfirmed_messages() {
+  let state = replay(&[
+    ProducerQueueEvent::MessageSent(sent(1, "idle")),
+    ProducerQueueEvent::MessageSent(sent(2, "busy")),
+    ProducerQueueEvent::MessageSent(sent(3, "busy")),
+    ProducerQueueEvent::MessageSent(sent(4, "recent")),
+    confirmed(1, "idle", 100),
+    confirmed(2, "busy", 100),
+    confirmed(4, "recent", 900),
+  ]);
+
+  let confirmed_at =
+    BTreeMap::from([(String::from("idle"), 100), (String::from("busy"), 100), (String::from("recent"), 900)]);
+
+  let unused = ProducerQueueActor::unused_confirmation_qualifiers(&state, &confirmed_at, 1000, 500);
+
+  assert_eq!(unused, BTreeSet::from([String::from("idle")]));
+}
+
+#[test]
+fn no_qualifier_is_unused_before_the_cleanup_age_has_elapsed() {
+  let state = replay(&[ProducerQueueEvent::MessageSent(sent(1, "a")), confirmed(1, "a", 0)]);
+
+  let confirmed_at = BTreeMap::from([(String::from("a"), 0)]);
+
+  assert!(ProducerQueueActor::unused_confirmation_qualifiers(&state, &confirmed_at, 400, 500).is_empty());
+}
+
+#[test]
+fn timestamp_zero_confirmation_survives_cleanup_while_recently_received() {
+  // Producer controllers journal confirmations with timestamp 0, long before the cleanup age.
+  let state = replay(&[ProducerQueueEvent::MessageSent(sent(1, "a")), confirmed(1, "a", 0)]);
+  let confirmed_at = BTreeMap::from([(String::from("a"), 9_800)]);
+
+  assert!(ProducerQueueActor::unused_confirmation_qualifiers(&state, &confirmed_at, 10_000, 500).is_empty());
+  assert_eq!(
+    ProducerQueueActor::unused_confirmation_qualifiers(&state, &confirmed_at, 10_300, 500),
+    BTreeSet::from([String::from("a")])
+  );
+}
@@ -0,0 +1,48 @@
+//! Internal messages of the event-sourced producer queue actor.
+
+use fraktor_actor_core_typed_rs::delivery::{DurableProducerQueueCommand, DurableProducerQueueState};
+
+use crate::{EventSourcedEffectorSignal, internal::ProducerQueueEvent};
+
+/// Message handled by the actor that journals the producer queue.
+pub(crate) enum ProducerQueueCommand<A>
+where
+  A: Clone + Send + Sync + 'static, {
+  Queue(DurableProducerQueueCommand<A>),
+  CleanupTick,
+  Persistence(EventSourcedEffectorSignal<DurableProducerQueueState<A>, ProducerQueueEvent<A>>),
+}
+
+impl<A> ProducerQueueCommand<A>
+where
+  A: Clone + Send + Sync + 'static,
+{
+  /// Wraps a copy of a queue protocol command.
+  pub(crate) fn queue(command: &DurableProducerQueueCommand<A>) -> Self {
+    // DurableProducerQueueCommand は Clone を実装しないため、フィールド単位で複製する
+    Self::Queue(match command {
+      | DurableProducerQueueCommand::LoadState { reply_to } => {
+        DurableProducerQueueCommand::load_state(reply_to.clone())
+      },
+      | DurableProducerQueueCommand::StoreMessageSent { sent, reply_to } => {
+        DurableProducerQueueCommand::store_message_sent(sent.clone(), reply_to.clone())
+      },
+      | DurableProducerQueueCommand::StoreMessageConfirmed { seq_nr, confirmation_qualifier, timestamp_millis } => {
+        DurableProducerQueueCommand::store_message_confirmed(*seq_nr, confirmation_qualifier.clone(), *timestamp_millis)
+      },
+    })
+  }
+}
+
+impl<A> Clone for ProducerQueueCommand<A>
+where
+  A: Clone + Send + Sync + 'static,
+{
+  fn clone(&self) -> Self {
+    match self {
+      | Self::Queue(command) => Self::queue(command),
+      | Self::CleanupTick => Self::CleanupTick,
+      | Self::Persistence(signal) => Self::Persistence(signal.clone()),
+    }
+  }
+}
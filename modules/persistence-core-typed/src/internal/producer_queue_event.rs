@@ -0,0 +1,39 @@
+//! Internal journal events of the event-sourced producer queue.
+
+use alloc::{collections::BTreeSet, string::String};
+
+use fraktor_actor_core_typed_rs::delivery::{ConfirmationQualifier, DurableProducerQueueState, MessageSent, SeqNr};
+
+/// Event journaled by the event-sourced producer queue.
+///
+/// Corresponds to Pekko's `DurableProducerQueue.Event` family.
+#[derive(Clone, Debug)]
+pub(crate) enum ProducerQueueEvent<A>
+where
+  A: Clone + Send + Sync + 'static, {
+  MessageSent(MessageSent<A>),
+  Confirmed {
+    seq_nr:                 SeqNr,
+    confirmation_qualifier: ConfirmationQualifier,
+    timestamp_millis:       u64,
+  },
+  Cleanup {
+    confirmation_qualifiers: BTreeSet<String>,
+  },
+}
+
+impl<A> ProducerQueueEvent<A>
+where
+  A: Clone + Send + Sync + 'static,
+{
+  /// Applies the event to the queue state.
+  pub(crate) fn apply(state: &DurableProducerQueueState<A>, event: &Self) -> DurableProducerQueueState<A> {
+    match event {
+      | Self::MessageSent(sent) => state.clone().add_message_sent(sent.clone()),
+      | Self::Confirmed { seq_nr, confirmation_qualifier, timestamp_millis } => {
+        state.clone().confirmed(*seq_nr, confirmation_qualifier.clone(), *timestamp_millis)
+      },
+      | Self::Cleanup { confirmation_qualifiers } => state.clone().cleanup(confirmation_qualifiers),
+    }
+  }
+}
@@ -0,0 +1,200 @@
+//! Internal typed projection actor.
+
+#[cfg(test)]
+#[path = "projection_actor_test.rs"]
+mod tests;
+
+use alloc::format;
+use core::future::Future;
+
+use fraktor_actor_core_kernel_rs::actor::{error::ActorError, messaging::AnyMessage};
+use fraktor_actor_core_typed_rs::{
+  TypedActorRef, TypedProps,
+  actor::{TypedActor, TypedActorContext},
+};
+use fraktor_persistence_core_kernel_rs::{
+  projection::{Projection, ProjectionError},
+  query::Offset,
+};
+use fraktor_stream_core_kernel_rs::{
+  SharedKillSwitch, StreamError,
+  materialization::{ActorMaterializer, ActorMaterializerConfig},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{ProjectionCommand, ProjectionSignal, internal::ProjectionActorEvent};
+
+type MaterializerConfigFactory = ArcShared<dyn Fn() -> ActorMaterializerConfig + Send + Sync>;
+
+/// Runs one projection stream and restarts it from the stored offset on demand.
+///
+/// Every started or stopped stream bumps `generation`, so completions of superseded streams are
+/// ignored.
+pub(crate) struct ProjectionActor {
+  projection:          Projection,
+  materializer_config: MaterializerConfigFactory,
+  materializer:        Option<ActorMaterializer>,
+  kill_switch:         Option<SharedKillSwitch>,
+  generation:          u64,
+  paused:              bool,
+}
+
+impl ProjectionActor {
+  pub(crate) fn props(
+    projection: Projection,
+    materializer_config: MaterializerConfigFactory,
+  ) -> TypedProps<ProjectionCommand> {
+    TypedProps::new(move || Self::new(projection.clone(), materializer_config.clone()))
+  }
+
+  fn new(projection: Projection, materializer_config: MaterializerConfigFactory) -> Self {
+    Self { projection, materializer_config, materializer: None, kill_switch: None, generation: 0, paused: false }
+  }
+
+  fn stop_stream(&mut self) {
+    self.generation = self.generation.wrapping_add(1);
+    if let Some(kill_switch) = self.kill_switch.take() {
+      kill_switch.shutdown();
+    }
+  }
+
+  fn load_offset(&mut self, ctx: &mut TypedActorContext<'_, ProjectionCommand>) -> Result<(), ActorError> {
+    self.stop_stream();
+    let generation = self.generation;
+    let future = self.projection.load_offset();
+    Self::pipe_to_self(ctx, async move { ProjectionActorEvent::OffsetLoaded { generation, result: future.await } })
+  }
+
+  fn start_stream(
+    &mut self,
+    ctx: &mut TypedActorContext<'_, ProjectionCommand>,
+    offset: Option<Offset>,
+  ) -> Result<(), ActorError> {
+    let Some(materializer) = self.materializer.as_mut() else {
+      return Err(ActorError::fatal("projection materializer is not started"));
+    };
+    let kill_switch = SharedKillSwitch::new();
+    let graph = self.projection.runnable_graph(offset, &kill_switch);
+    let completion = materializer
+      .materialize(graph)
+      .map_err(|error| ActorError::recoverable(format!("projection stream materialization failed: {error}")))?
+      .into_materialized();
+    self.kill_switch = Some(kill_switch);
+    let generation = self.generation;
+    Self::pipe_to_self(ctx, async move {
+      ProjectionActorEvent::StreamFinished { generation, result: completion.await.map(|_done| ()) }
+    })
+  }
+
+  fn reset_offset(
+    &mut self,
+    ctx: &mut TypedActorContext<'_, ProjectionCommand>,
+    offset: Option<Offset>,
+    reply_to: TypedActorRef<Result<(), ProjectionError>>,
+  ) -> Result<(), ActorError> {
+    self.stop_stream();
+    let future = self.projection.reset_offset(offset);
+    Self::pipe_to_self(ctx, async move { ProjectionActorEvent::ResetFinished { result: future.await, reply_to } })
+  }
+
+  fn report_offset(
+    &self,
+    ctx: &mut TypedActorContext<'_, ProjectionCommand>,
+    reply_to: &TypedActorRef<Result<Option<Offset>, ProjectionError>>,
+  ) -> Result<(), ActorError> {
+    ctx
+      .pipe_to(self.projection.load_offset(), reply_to, |offset| Ok(Ok(offset)), |error| Ok(Err(error)))
+      .map_err(|error| ActorError::recoverable(format!("projection offset pipe failed: {error}")))
+  }
+
+  fn handle_event(
+    &mut self,
+    ctx: &mut TypedActorContext<'_, ProjectionCommand>,
+    event: &ProjectionActorEvent,
+  ) -> Result<(), ActorError> {
+    match event {
+      | ProjectionActorEvent::OffsetLoaded { generation, result } => {
+        if *generation != self.generation || self.paused {
+          return Ok(());
+        }
+        match result {
+          | Ok(offset) => self.start_stream(ctx, offset.clone()),
+          | Err(error) => Err(ActorError::recoverable_typed::<ProjectionError>(format!("{error}"))),
+        }
+      },
+      | ProjectionActorEvent::StreamFinished { generation, result } => {
+        if *generation != self.generation {
+          return Ok(());
+        }
+        self.kill_switch = None;
+        // 失敗はスーパーバイザへ委ね、再起動時に保存済みオフセットから再開する。
+        result.clone().map_err(|error| projection_stream_failure(&error))
+      },
+      | ProjectionActorEvent::ResetFinished { result, reply_to } => {
+        let mut reply_to = reply_to.clone();
+        reply_to.tell(result.clone());
+        if self.paused { Ok(()) } else { self.load_offset(ctx) }
+      },
+    }
+  }
+
+  fn pipe_to_self(
+    ctx: &mut TypedActorContext<'_, ProjectionCommand>,
+    future: impl Future<Output = ProjectionActorEvent> + Send + 'static,
+  ) -> Result<(), ActorError> {
+    let future = async move { ProjectionCommand::Signal(ProjectionSignal::new(future.await)) };
+    ctx
+      .as_untyped_mut()
+      .pipe_to_self(future, AnyMessage::new)
+      .map_err(|error| ActorError::recoverable(format!("projection pipe failed: {error}")))
+  }
+}
+
+fn projection_stream_failure(error: &StreamError) -> ActorError {
+  ActorError::recoverable_typed::<StreamError>(format!("projection stream failed: {error}"))
+}
+
+impl TypedActor<ProjectionCommand> for ProjectionActor {
+  fn pre_start(&mut self, ctx: &mut TypedActorContext<'_, ProjectionCommand>) -> Result<(), ActorError> {
+    let mut materializer = ActorMaterializer::new(ctx.system().into_untyped(), (self.materializer_config)());
+    materializer
+      .start()
+      .map_err(|error| ActorError::fatal(format!("projection materializer start failed: {error}")))?;
+    self.materializer = Some(materializer);
+    self.load_offset(ctx)
+  }
+
+  fn receive(
+    &mut self,
+    ctx: &mut TypedActorContext<'_, ProjectionCommand>,
+    message: &ProjectionCommand,
+  ) -> Result<(), ActorError> {
+    match message {
+      | ProjectionCommand::Pause => {
+        self.paused = true;
+        self.stop_stream();
+        Ok(())
+      },
+      | ProjectionCommand::Resume => {
+        if !self.paused {
+          return Ok(());
+        }
+        self.paused = false;
+        self.load_offset(ctx)
+      },
+      | ProjectionCommand::ResetOffset { offset, reply_to } => self.reset_offset(ctx, offset.clone(), reply_to.clone()),
+      | ProjectionCommand::GetOffset { reply_to } => self.report_offset(ctx, reply_to),
+      | ProjectionCommand::Signal(signal) => self.handle_event(ctx, signal.event()),
+    }
+  }
+
+  fn post_stop(&mut self, _ctx: &mut TypedActorContext<'_, ProjectionCommand>) -> Result<(), ActorError> {
+    self.stop_stream();
+    if let Some(mut materializer) = self.materializer.take() {
+      materializer
+        .shutdown()
+        .map_err(|error| ActorError::recoverable(format!("projection materializer shutdown failed: {error}")))?;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,12 @@
+//! Internal projection actor completions.
+
+use fraktor_actor_core_typed_rs::TypedActorRef;
+use fraktor_persistence_core_kernel_rs::{projection::ProjectionError, query::Offset};
+use fraktor_stream_core_kernel_rs::StreamError;
+
+#[derive(Clone)]
+pub(crate) enum ProjectionActorEvent {
+  OffsetLoaded { generation: u64, result: Result<Option<Offset>, ProjectionError> },
+  StreamFinished { generation: u64, result: Result<(), StreamError> },
+  ResetFinished { result: Result<(), ProjectionError>, reply_to: TypedActorRef<Result<(), ProjectionError>> },
+}
@@ -0,0 +1,49 @@
+use fraktor_persistence_core_kernel_rs::{
+  projection::{InMemoryOffsetStore, Projection, ProjectionId},
+  query::{EventEnvelope, Offset},
+};
+use fraktor_stream_core_kernel_rs::{
+  SharedKillSwitch,
+  dsl::Source,
+  materialization::{ActorMaterializerConfig, StreamNotUsed},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::ProjectionActor;
+
+fn empty_source(_offset: Offset) -> Source<EventEnvelope, StreamNotUsed> {
+  Source::empty()
+}
+
+fn actor() -> ProjectionActor {
+  let projection = Projection::at_least_once(
+    ProjectionId::new("projection-actor-test", "0"),
+    empty_source,
+    InMemoryOffsetStore::new(),
+    |_: &EventEnvelope| Ok(()),
+  );
+  ProjectionActor::new(projection, ArcShared::new(ActorMaterializerConfig::default))
+}
+
+#[test]
+fn new_actor_has_no_running_stream() {
+  let actor = actor();
+
+  assert!(actor.materializer.is_none());
+  assert!(actor.kill_switch.is_none());
+  assert_eq!(actor.generation, 0);
+  assert!(!actor.paused);
+}
+
+#[test]
+fn stop_stream_shuts_down_running_stream_and_supersedes_its_generation() {
+  let mut actor = actor();
+  let kill_switch = SharedKillSwitch::new();
+  actor.kill_switch = Some(kill_switch.clone());
+
+  actor.stop_stream();
+
+  assert!(kill_switch.is_shutdown());
+  assert!(actor.kill_switch.is_none());
+  assert_eq!(actor.generation, 1);
+}
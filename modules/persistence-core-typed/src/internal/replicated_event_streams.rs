@@ -0,0 +1,86 @@
+//! Internal streams that feed events of other replicas into a replicated store.
+
+use alloc::{collections::BTreeMap, format};
+
+use fraktor_actor_core_kernel_rs::{
+  actor::{actor_ref::ActorRef, messaging::AnyMessage},
+  event::logging::LogLevel,
+  system::ActorSystem,
+};
+use fraktor_persistence_core_kernel_rs::{
+  query::EventEnvelope,
+  replication::{ReplicaId, ReplicatedEventMetadata},
+};
+use fraktor_stream_core_kernel_rs::{SharedKillSwitch, StreamError, dsl::Sink, materialization::ActorMaterializer};
+
+use crate::{ReplicationSettings, internal::EventSourcedStoreCommand};
+
+/// Runs one live events-by-persistence-id stream per other replica.
+///
+/// Every stream starts after the last origin sequence number already seen from its replica and
+/// only forwards events that originated at that replica. Dropping the value stops all streams.
+pub(crate) struct ReplicatedEventStreams {
+  system:       ActorSystem,
+  materializer: ActorMaterializer,
+  kill_switch:  SharedKillSwitch,
+}
+
+impl ReplicatedEventStreams {
+  pub(crate) fn start<S, E>(
+    system: ActorSystem,
+    settings: &ReplicationSettings,
+    seen: &BTreeMap<ReplicaId, u64>,
+    target: &ActorRef,
+  ) -> Result<Self, StreamError>
+  where
+    S: Send + Sync + 'static,
+    E: Send + Sync + 'static, {
+    let mut materializer = ActorMaterializer::new(system.clone(), settings.materializer_config());
+    materializer.start()?;
+    let kill_switch = SharedKillSwitch::new();
+    for replica in settings.other_replicas() {
+      let persistence_id = settings.replication_id().with_replica(replica.clone()).persistence_id();
+      let from_sequence_nr = seen.get(replica).copied().unwrap_or(0).saturating_add(1);
+      let origin = replica.clone();
+      let replica = replica.clone();
+      let mut target = target.clone();
+      settings
+        .query()
+        .events_by_persistence_id(&persistence_id, from_sequence_nr, u64::MAX)
+        .filter(move |envelope: &EventEnvelope| originates_at(envelope, &origin))
+        .via(kill_switch.flow::<EventEnvelope>())
+        .run_with(
+          Sink::foreach(move |envelope: EventEnvelope| {
+            target.tell(AnyMessage::new(EventSourcedStoreCommand::<S, E>::ReplicatedEvent {
+              replica: replica.clone(),
+              envelope,
+            }));
+          }),
+          &mut materializer,
+        )?;
+    }
+    Ok(Self { system, materializer, kill_switch })
+  }
+}
+
+impl Drop for ReplicatedEventStreams {
+  fn drop(&mut self) {
+    self.kill_switch.shutdown();
+    // Drop からは呼び出し元へ返せないため、停止失敗はログに残す。
+    if let Err(error) = self.materializer.shutdown() {
+      self.system.emit_log(
+        LogLevel::Warn,
+        format!("failed to shut down replicated event streams: {error}"),
+        None,
+        None,
+      );
+    }
+  }
+}
+
+fn originates_at(envelope: &EventEnvelope, replica: &ReplicaId) -> bool {
+  envelope
+    .metadata()
+    .and_then(|metadata| metadata.downcast_ref::<ReplicatedEventMetadata>())
+    .is_some_and(|metadata| metadata.origin_replica() == replica)
+}
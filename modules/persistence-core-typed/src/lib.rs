@@ -8,7 +8,9 @@
 //! Typed persistence effector APIs for fraktor actors.
 //!
 //! This crate connects typed actors with the persistence kernel while keeping
-//! aggregate actors on the normal `Behavior<M>` DSL.
+//! aggregate actors on the normal `Behavior<M>` DSL, replicates event-sourced
+//! aggregates across active-active replicas, backs reliable delivery with a durable
+//! producer queue, and runs projections as typed actors.
 
 extern crate alloc;
 
@@ -21,12 +23,18 @@ mod event_sourced_effector_config;
 mod event_sourced_effector_message_adapter;
 mod event_sourced_effector_signal;
 mod event_sourced_effector_signal_auth;
+mod event_sourced_producer_queue;
+mod event_sourced_producer_queue_settings;
 mod event_sourced_signal;
 mod internal;
 mod persistence_id;
 mod persistence_mode;
+mod projection_behavior;
+mod projection_command;
+mod projection_signal;
 mod published_event;
 mod recovery;
+mod replication_settings;
 mod retention_criteria;
 mod snapshot_adapter;
 mod snapshot_criteria;
@@ -45,11 +53,17 @@ pub use event_sourced_effector::EventSourcedEffector;
 pub use event_sourced_effector_config::EventSourcedEffectorConfig;
 pub use event_sourced_effector_message_adapter::EventSourcedEffectorMessageAdapter;
 pub use event_sourced_effector_signal::EventSourcedEffectorSignal;
+pub use event_sourced_producer_queue::EventSourcedProducerQueue;
+pub use event_sourced_producer_queue_settings::EventSourcedProducerQueueSettings;
 pub use event_sourced_signal::EventSourcedSignal;
 pub use persistence_id::PersistenceId;
 pub use persistence_mode::PersistenceMode;
+pub use projection_behavior::ProjectionBehavior;
+pub use projection_command::ProjectionCommand;
+pub use projection_signal::ProjectionSignal;
 pub use published_event::PublishedEvent;
 pub use recovery::Recovery;
+pub use replication_settings::ReplicationSettings;
 pub use retention_criteria::RetentionCriteria;
 pub use snapshot_adapter::SnapshotAdapter;
 pub use snapshot_criteria::SnapshotCriteria;
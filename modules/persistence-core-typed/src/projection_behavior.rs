@@ -0,0 +1,33 @@
+//! Typed projection actor entry point.
+
+use fraktor_actor_core_typed_rs::TypedProps;
+use fraktor_persistence_core_kernel_rs::projection::Projection;
+use fraktor_stream_core_kernel_rs::materialization::ActorMaterializerConfig;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{ProjectionCommand, internal::ProjectionActor};
+
+/// Runs a [`Projection`] inside an actor controlled through [`ProjectionCommand`]s.
+///
+/// The actor resumes from the stored offset when it starts. A failing projection stream fails the
+/// actor with a recoverable error, so the parent's supervisor strategy decides whether and when
+/// the projection restarts from its stored offset.
+pub struct ProjectionBehavior;
+
+impl ProjectionBehavior {
+  /// Builds props for a projection actor using the default materializer configuration.
+  #[must_use]
+  pub fn props(projection: Projection) -> TypedProps<ProjectionCommand> {
+    Self::props_with_materializer_config(projection, ActorMaterializerConfig::default)
+  }
+
+  /// Builds props for a projection actor whose streams use the configuration from `config`.
+  ///
+  /// `config` is called whenever the actor (re)starts.
+  #[must_use]
+  pub fn props_with_materializer_config<F>(projection: Projection, config: F) -> TypedProps<ProjectionCommand>
+  where
+    F: Fn() -> ActorMaterializerConfig + Send + Sync + 'static, {
+    ProjectionActor::props(projection, ArcShared::new(config))
+  }
+}
@@ -0,0 +1,33 @@
+//! Projection actor protocol.
+
+use fraktor_actor_core_typed_rs::TypedActorRef;
+use fraktor_persistence_core_kernel_rs::{projection::ProjectionError, query::Offset};
+
+use crate::ProjectionSignal;
+
+/// Commands accepted by a projection actor created with
+/// [`ProjectionBehavior`](crate::ProjectionBehavior).
+#[derive(Clone)]
+pub enum ProjectionCommand {
+  /// Stops the running projection stream without clearing its offset.
+  Pause,
+  /// Restarts a paused projection from its stored offset.
+  Resume,
+  /// Stops the projection stream, replaces the stored offset and restarts unless paused.
+  ///
+  /// `None` clears the offset so the projection starts over from the beginning.
+  ResetOffset {
+    /// Offset to resume after.
+    offset:   Option<Offset>,
+    /// Receives the outcome of the offset update.
+    reply_to: TypedActorRef<Result<(), ProjectionError>>,
+  },
+  /// Reports the stored offset.
+  GetOffset {
+    /// Receives the stored offset.
+    reply_to: TypedActorRef<Result<Option<Offset>, ProjectionError>>,
+  },
+  /// Completion delivered by the projection actor to itself.
+  #[doc(hidden)]
+  Signal(ProjectionSignal),
+}
@@ -0,0 +1,19 @@
+//! Opaque projection actor completion signal.
+
+use crate::internal::ProjectionActorEvent;
+
+/// Asynchronous completion the projection actor sends to itself.
+///
+/// The contents are private so external crates cannot forge completions.
+#[derive(Clone)]
+pub struct ProjectionSignal(ProjectionActorEvent);
+
+impl ProjectionSignal {
+  pub(crate) const fn new(event: ProjectionActorEvent) -> Self {
+    Self(event)
+  }
+
+  pub(crate) const fn event(&self) -> &ProjectionActorEvent {
+    &self.0
+  }
+}
@@ -0,0 +1,109 @@
+//! Replication settings for active-active event-sourced effectors.
+
+#[cfg(test)]
+#[path = "replication_settings_test.rs"]
+mod tests;
+
+use alloc::collections::BTreeSet;
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+use fraktor_persistence_core_kernel_rs::{
+  query::EventsByPersistenceIdQuery,
+  replication::{ReplicaId, ReplicationId},
+};
+use fraktor_stream_core_kernel_rs::materialization::ActorMaterializerConfig;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+type MaterializerConfigFactory = ArcShared<dyn Fn() -> ActorMaterializerConfig + Send + Sync>;
+
+/// Describes one replica of a replicated event-sourced entity and how it reads the others.
+///
+/// Each replica persists its own events under [`ReplicationId::persistence_id`] and consumes the
+/// events of every other replica in `all_replicas` through the `query` read journal.
+pub struct ReplicationSettings {
+  replication_id:      ReplicationId,
+  all_replicas:        BTreeSet<ReplicaId>,
+  query:               ArcShared<dyn EventsByPersistenceIdQuery>,
+  materializer_config: MaterializerConfigFactory,
+}
+
+impl ReplicationSettings {
+  /// Creates replication settings.
+  ///
+  /// The local replica of `replication_id` is always part of the replica set.
+  #[must_use]
+  pub fn new<Q>(replication_id: ReplicationId, all_replicas: impl IntoIterator<Item = ReplicaId>, query: Q) -> Self
+  where
+    Q: EventsByPersistenceIdQuery, {
+    let mut all_replicas: BTreeSet<ReplicaId> = all_replicas.into_iter().collect();
+    all_replicas.insert(replication_id.replica_id().clone());
+    Self {
+      replication_id,
+      all_replicas,
+      query: ArcShared::new(query),
+      materializer_config: ArcShared::new(ActorMaterializerConfig::default),
+    }
+  }
+
+  /// Returns a copy whose replication streams use the configuration from `config`.
+  ///
+  /// `config` is called whenever the replica's store (re)starts.
+  #[must_use]
+  pub fn with_materializer_config<F>(mut self, config: F) -> Self
+  where
+    F: Fn() -> ActorMaterializerConfig + Send + Sync + 'static, {
+    self.materializer_config = ArcShared::new(config);
+    self
+  }
+
+  /// Returns the replication id of the local replica.
+  #[must_use]
+  pub const fn replication_id(&self) -> &ReplicationId {
+    &self.replication_id
+  }
+
+  /// Returns the local replica id.
+  #[must_use]
+  pub const fn replica_id(&self) -> &ReplicaId {
+    self.replication_id.replica_id()
+  }
+
+  /// Returns every replica of the entity, including the local one.
+  #[must_use]
+  pub const fn all_replicas(&self) -> &BTreeSet<ReplicaId> {
+    &self.all_replicas
+  }
+
+  /// Returns the replicas whose events the local replica consumes.
+  pub fn other_replicas(&self) -> impl Iterator<Item = &ReplicaId> {
+    self.all_replicas.iter().filter(|replica| *replica != self.replica_id())
+  }
+
+  pub(crate) fn query(&self) -> &dyn EventsByPersistenceIdQuery {
+    &*self.query
+  }
+
+  pub(crate) fn materializer_config(&self) -> ActorMaterializerConfig {
+    (self.materializer_config)()
+  }
+}
+
+impl Clone for ReplicationSettings {
+  fn clone(&self) -> Self {
+    Self {
+      replication_id:      self.replication_id.clone(),
+      all_replicas:        self.all_replicas.clone(),
+      query:               self.query.clone(),
+      materializer_config: self.materializer_config.clone(),
+    }
+  }
+}
+
+impl Debug for ReplicationSettings {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("ReplicationSettings")
+      .field("replication_id", &self.replication_id)
+      .field("all_replicas", &self.all_replicas)
+      .finish_non_exhaustive()
+  }
+}
@@ -0,0 +1,23 @@
+use alloc::vec::Vec;
+
+use fraktor_persistence_core_kernel_rs::{
+  journal::SharedInMemoryJournal,
+  query::PollingReadJournal,
+  replication::{ReplicaId, ReplicationId},
+};
+
+use crate::ReplicationSettings;
+
+#[test]
+fn local_replica_is_part_of_the_replica_set_but_not_of_other_replicas() {
+  let replication_id = ReplicationId::new("counter", "c-1", ReplicaId::new("a"));
+  let settings = ReplicationSettings::new(
+    replication_id,
+    [ReplicaId::new("b")],
+    PollingReadJournal::new(SharedInMemoryJournal::new()),
+  );
+
+  assert_eq!(settings.all_replicas().iter().collect::<Vec<_>>(), [&ReplicaId::new("a"), &ReplicaId::new("b")]);
+  assert_eq!(settings.other_replicas().collect::<Vec<_>>(), [&ReplicaId::new("b")]);
+  assert_eq!(settings.replica_id(), &ReplicaId::new("a"));
+}
@@ -24,6 +24,7 @@ fn forge(signal: EventSourcedEffectorSignal<u64, u64>) -> EventSourcedEffectorSi
   let auth = match signal {
     | EventSourcedEffectorSignal::RecoveryCompleted { auth, .. }
     | EventSourcedEffectorSignal::PersistedEvents { auth, .. }
+    | EventSourcedEffectorSignal::ReplicatedEvent { auth, .. }
     | EventSourcedEffectorSignal::PersistedSnapshot { auth, .. }
     | EventSourcedEffectorSignal::DeletedSnapshots { auth, .. }
     | EventSourcedEffectorSignal::EventSourced { auth, .. } => auth,
@@ -0,0 +1,301 @@
+#![cfg(not(target_os = "none"))]
+
+use core::{
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+use std::{
+  boxed::Box,
+  string::{String, ToString},
+  thread,
+  time::{Duration, Instant},
+  vec,
+  vec::Vec,
+};
+
+use fraktor_actor_adaptor_std_rs::{dispatch::dispatcher::ThreadedExecutor, tick_driver::TestTickDriver};
+use fraktor_actor_core_kernel_rs::{
+  actor::{extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig},
+  dispatch::dispatcher::{
+    DEFAULT_DISPATCHER_ID, DefaultDispatcherFactory, DispatcherConfig, ExecutorShared, MessageDispatcherFactory,
+    TrampolineState,
+  },
+  system::SpinBlocker,
+};
+use fraktor_actor_core_typed_rs::{
+  TypedActorRef, TypedActorSystem, TypedProps,
+  actor::TypedChildRef,
+  delivery::{
+    ConsumerController, ConsumerControllerConfirmed, ConsumerControllerDelivery, DurableProducerQueueCommand,
+    DurableProducerQueueState, MessageSent, NO_QUALIFIER, ProducerController, ProducerControllerConfig,
+    ProducerControllerRequestNext, StoreMessageSentAck,
+  },
+  dsl::Behaviors,
+};
+use fraktor_persistence_core_kernel_rs::{
+  extension::PersistenceExtensionInstaller,
+  journal::{Journal, SharedInMemoryJournal},
+  persistent::PersistentRepr,
+  snapshot::InMemorySnapshotStore,
+};
+use fraktor_persistence_core_typed_rs::{EventSourcedProducerQueue, EventSourcedProducerQueueSettings, PersistenceId};
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+type QueueCommand = DurableProducerQueueCommand<String>;
+type SharedMessages = ArcShared<SpinSyncMutex<Vec<String>>>;
+
+const QUEUE_ID: &str = "producer-queue-1";
+
+#[derive(Clone)]
+enum ManagerCommand {
+  Start { reply_to: TypedActorRef<TypedActorRef<QueueCommand>> },
+  Stop,
+}
+
+fn system_config(journal: &SharedInMemoryJournal) -> ActorSystemConfig {
+  let installer = PersistenceExtensionInstaller::new(journal.clone(), InMemorySnapshotStore::new());
+  let installers = ExtensionInstallers::default().with_extension_installer(installer);
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(scheduler)
+    .with_extension_installers(installers)
+}
+
+/// Starts a system whose guardian (re)starts the queue as a child, so that restarts keep the
+/// system-scoped snapshot store.
+fn start_manager(
+  journal: &SharedInMemoryJournal,
+  settings: EventSourcedProducerQueueSettings,
+) -> TypedActorSystem<ManagerCommand> {
+  let props = TypedProps::from_behavior_factory(move || {
+    let settings = settings.clone();
+    let queue = ArcShared::new(SpinSyncMutex::new(None::<TypedChildRef<QueueCommand>>));
+    Behaviors::receive_message(move |ctx, command: &ManagerCommand| {
+      match command {
+        | ManagerCommand::Start { reply_to } => {
+          let behavior =
+            EventSourcedProducerQueue::behavior_with_settings(PersistenceId::of_unique_id(QUEUE_ID), settings.clone());
+          let child = ctx.spawn_child(&TypedProps::from_behavior_factory(move || behavior.clone())).expect("queue");
+          reply_to.clone().tell(child.actor_ref());
+          *queue.lock() = Some(child);
+        },
+        | ManagerCommand::Stop => {
+          if let Some(child) = queue.lock().take() {
+            child.stop().expect("stop queue");
+          }
+        },
+      }
+      Ok(Behaviors::same())
+    })
+  });
+  TypedActorSystem::<ManagerCommand>::create_from_props(&props, system_config(journal)).expect("queue system")
+}
+
+fn start_queue(system: &TypedActorSystem<ManagerCommand>) -> TypedActorRef<QueueCommand> {
+  let mut manager = system.user_guardian_ref();
+  let response = manager.ask::<TypedActorRef<QueueCommand>, _>(|reply_to| ManagerCommand::Start { reply_to });
+  let mut future = response.future().clone();
+  assert!(wait_until(|| future.is_ready()));
+  future.try_take().expect("queue reply").expect("queue payload")
+}
+
+fn restart_queue(system: &TypedActorSystem<ManagerCommand>) -> TypedActorRef<QueueCommand> {
+  system.user_guardian_ref().tell(ManagerCommand::Stop);
+  start_queue(system)
+}
+
+fn sent(seq_nr: u64, message: &str) -> MessageSent<String> {
+  MessageSent::new(seq_nr, message.to_string(), false, NO_QUALIFIER, 0)
+}
+
+fn store_message_sent(queue: &mut TypedActorRef<QueueCommand>, sent: MessageSent<String>) -> StoreMessageSentAck {
+  let response = queue.ask::<StoreMessageSentAck, _>(|reply_to| QueueCommand::store_message_sent(sent, reply_to));
+  let mut future = response.future().clone();
+  assert!(wait_until(|| future.is_ready()));
+  future.try_take().expect("ack reply").expect("ack payload")
+}
+
+fn load_state(queue: &mut TypedActorRef<QueueCommand>) -> DurableProducerQueueState<String> {
+  let response = queue.ask::<DurableProducerQueueState<String>, _>(QueueCommand::load_state);
+  let mut future = response.future().clone();
+  assert!(wait_until(|| future.is_ready()));
+  future.try_take().expect("state reply").expect("state payload")
+}
+
+fn unconfirmed_messages(state: &DurableProducerQueueState<String>) -> Vec<&str> {
+  state.unconfirmed().iter().map(|sent| sent.message().as_str()).collect()
+}
+
+fn highest_sequence_nr(journal: &SharedInMemoryJournal) -> u64 {
+  drive_ready(journal.highest_sequence_nr(QUEUE_ID)).expect("highest sequence nr")
+}
+
+fn lowest_sequence_nr(journal: &SharedInMemoryJournal) -> Option<u64> {
+  let events = drive_ready(journal.replay_messages(QUEUE_ID, 1, u64::MAX, u64::MAX)).expect("replay");
+  events.first().map(PersistentRepr::sequence_nr)
+}
+
+fn wait_until(mut predicate: impl FnMut() -> bool) -> bool {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while Instant::now() < deadline {
+    if predicate() {
+      return true;
+    }
+    thread::sleep(Duration::from_millis(1));
+  }
+  predicate()
+}
+
+fn drive_ready<F: Future>(future: F) -> F::Output {
+  let mut context = Context::from_waker(Waker::noop());
+  let mut future = core::pin::pin!(future);
+  match Future::poll(future.as_mut(), &mut context) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was not ready"),
+  }
+}
+
+fn terminate<M: Send + Sync + 'static>(system: TypedActorSystem<M>) {
+  system.terminate().expect("terminate");
+  system.as_untyped().run_until_terminated(&SpinBlocker);
+}
+
+/// Starts a producer controller backed by the event-sourced queue, a consumer controller and
+/// both endpoints. The producer sends `to_send` once, the consumer confirms only when `confirm`
+/// is set and records every delivered message.
+fn start_delivery(
+  journal: &SharedInMemoryJournal,
+  to_send: Option<&str>,
+  confirm: bool,
+  delivered: SharedMessages,
+) -> TypedActorSystem<u32> {
+  let to_send = ArcShared::new(SpinSyncMutex::new(to_send.map(ToString::to_string)));
+  let props = TypedProps::from_behavior_factory(move || {
+    let to_send = to_send.clone();
+    let delivered = delivered.clone();
+    Behaviors::setup(move |ctx| {
+      let config = ProducerControllerConfig::new().with_durable_queue_resend_first_interval(Duration::from_millis(20));
+      let durable_queue = EventSourcedProducerQueue::behavior(PersistenceId::of_unique_id(QUEUE_ID));
+      let producer_controller = ProducerController::behavior_with_config("producer-1", &config, Some(durable_queue));
+      let producer_controller =
+        ctx.spawn_anonymous(&producer_controller).expect("producer controller").into_actor_ref();
+      let consumer_controller =
+        ctx.spawn_anonymous(&ConsumerController::behavior::<String>()).expect("consumer controller").into_actor_ref();
+
+      let to_send = to_send.clone();
+      let producer = Behaviors::receive_message(move |_ctx, request: &ProducerControllerRequestNext<String>| {
+        if let Some(message) = to_send.lock().take() {
+          request.send_next_to().clone().tell(message);
+        }
+        Ok(Behaviors::same())
+      });
+      let producer = ctx.spawn_anonymous(&producer).expect("producer").into_actor_ref();
+
+      let delivered = delivered.clone();
+      let consumer = Behaviors::receive_message(move |_ctx, delivery: &ConsumerControllerDelivery<String>| {
+        delivered.lock().push(delivery.message().clone());
+        if confirm {
+          delivery.confirm_to().clone().tell(ConsumerControllerConfirmed);
+        }
+        Ok(Behaviors::same())
+      });
+      let consumer = ctx.spawn_anonymous(&consumer).expect("consumer").into_actor_ref();
+
+      let mut consumer_controller_ref = consumer_controller.clone();
+      consumer_controller_ref.tell(ConsumerController::start(consumer));
+      let mut producer_controller_ref = producer_controller.clone();
+      producer_controller_ref.tell(ProducerController::start(producer));
+      producer_controller_ref.tell(ProducerController::register_consumer(consumer_controller));
+      Behaviors::ignore()
+    })
+  });
+  // resend-first タイマーはスケジューラスレッドから再設定されるため、インライン実行しない dispatcher
+  // を使う
+  let executor = ExecutorShared::new(Box::new(ThreadedExecutor::new()), TrampolineState::new());
+  let dispatcher: Box<dyn MessageDispatcherFactory> =
+    Box::new(DefaultDispatcherFactory::new(&DispatcherConfig::with_defaults(DEFAULT_DISPATCHER_ID), executor));
+  let config = system_config(journal).with_dispatcher_factory(DEFAULT_DISPATCHER_ID, ArcShared::new(dispatcher));
+  TypedActorSystem::<u32>::create_from_props(&props, config).expect("delivery system")
+}
+
+#[test]
+fn restarted_queue_recovers_unconfirmed_messages() {
+  let journal = SharedInMemoryJournal::new();
+  let system = start_manager(&journal, EventSourcedProducerQueueSettings::new());
+
+  let mut queue = start_queue(&system);
+  assert_eq!(store_message_sent(&mut queue, sent(1, "a")).stored_seq_nr(), 1);
+  assert_eq!(store_message_sent(&mut queue, sent(2, "b")).stored_seq_nr(), 2);
+  queue.tell(QueueCommand::store_message_confirmed(1, NO_QUALIFIER, 0));
+  assert!(wait_until(|| highest_sequence_nr(&journal) == 3));
+
+  let mut restarted = restart_queue(&system);
+  let state = load_state(&mut restarted);
+
+  assert_eq!(state.current_seq_nr(), 3);
+  assert_eq!(state.highest_confirmed_seq_nr(), 1);
+  assert_eq!(unconfirmed_messages(&state), ["b"]);
+
+  terminate(system);
+}
+
+#[test]
+fn duplicate_store_is_acknowledged_without_journaling_it_again() {
+  let journal = SharedInMemoryJournal::new();
+  let system = start_manager(&journal, EventSourcedProducerQueueSettings::new());
+  let mut queue = start_queue(&system);
+
+  assert_eq!(store_message_sent(&mut queue, sent(1, "a")).stored_seq_nr(), 1);
+  assert_eq!(store_message_sent(&mut queue, sent(1, "a")).stored_seq_nr(), 1);
+
+  assert_eq!(highest_sequence_nr(&journal), 1);
+  assert_eq!(unconfirmed_messages(&load_state(&mut queue)), ["a"]);
+
+  terminate(system);
+}
+
+#[test]
+fn snapshots_keep_the_queue_recoverable_after_old_events_are_deleted() {
+  let journal = SharedInMemoryJournal::new();
+  let settings = EventSourcedProducerQueueSettings::new().with_snapshot_every(2).with_keep_n_snapshots(1);
+  let system = start_manager(&journal, settings);
+
+  let mut queue = start_queue(&system);
+  for (seq_nr, message) in (1..).zip(["a", "b", "c", "d", "e"]) {
+    assert_eq!(store_message_sent(&mut queue, sent(seq_nr, message)).stored_seq_nr(), seq_nr);
+  }
+  queue.tell(QueueCommand::store_message_confirmed(3, NO_QUALIFIER, 0));
+  assert!(wait_until(|| highest_sequence_nr(&journal) == 6));
+  assert!(wait_until(|| lowest_sequence_nr(&journal).is_some_and(|seq_nr| seq_nr > 1)));
+
+  let mut restarted = restart_queue(&system);
+  let state = load_state(&mut restarted);
+
+  assert_eq!(state.current_seq_nr(), 6);
+  assert_eq!(unconfirmed_messages(&state), ["d", "e"]);
+
+  terminate(system);
+}
+
+#[test]
+fn restarted_producer_controller_redelivers_unconfirmed_messages() {
+  let journal = SharedInMemoryJournal::new();
+
+  let first_delivered = SharedMessages::new(SpinSyncMutex::new(Vec::new()));
+  let first = start_delivery(&journal, Some("hello"), false, first_delivered.clone());
+  assert!(wait_until(|| first_delivered.lock().as_slice() == ["hello"]));
+  first.terminate().expect("terminate");
+
+  let delivered = SharedMessages::new(SpinSyncMutex::new(Vec::new()));
+  let restarted = start_delivery(&journal, None, true, delivered.clone());
+  assert!(wait_until(|| highest_sequence_nr(&journal) == 2));
+  restarted.terminate().expect("terminate");
+  assert_eq!(delivered.lock().clone(), vec![String::from("hello")]);
+
+  let system = start_manager(&journal, EventSourcedProducerQueueSettings::new());
+  let state = load_state(&mut start_queue(&system));
+  assert_eq!(state.highest_confirmed_seq_nr(), 1);
+  assert!(state.unconfirmed().is_empty());
+
+  terminate(system);
+}
@@ -0,0 +1,175 @@
+#![cfg(not(target_os = "none"))]
+
+use core::{
+  any::Any,
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+use std::{
+  thread,
+  time::{Duration, Instant},
+  vec,
+  vec::Vec,
+};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{scheduler::SchedulerConfig, setup::ActorSystemConfig},
+  system::SpinBlocker,
+};
+use fraktor_actor_core_typed_rs::{TypedActorRef, TypedActorSystem};
+use fraktor_persistence_core_kernel_rs::{
+  journal::{Journal, SharedInMemoryJournal, Tagged},
+  persistent::{AtomicWrite, PersistentRepr},
+  projection::{InMemoryOffsetStore, OffsetStore, Projection, ProjectionError, ProjectionId},
+  query::{EventEnvelope, EventsByTagQuery, Offset, PollingReadJournal},
+};
+use fraktor_persistence_core_typed_rs::{ProjectionBehavior, ProjectionCommand};
+use fraktor_stream_core_kernel_rs::materialization::ActorMaterializerConfig;
+use fraktor_utils_core_rs::sync::{ArcShared, SpinSyncMutex};
+
+type Handled = ArcShared<SpinSyncMutex<Vec<i32>>>;
+
+fn actor_system_config() -> ActorSystemConfig {
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  ActorSystemConfig::new(TestTickDriver::default()).with_scheduler_config(scheduler)
+}
+
+fn materializer_config() -> ActorMaterializerConfig {
+  ActorMaterializerConfig::default().with_drive_interval(Duration::from_millis(1))
+}
+
+fn wait_until(deadline_ms: u64, mut predicate: impl FnMut() -> bool) -> bool {
+  let deadline = Instant::now() + Duration::from_millis(deadline_ms);
+  while Instant::now() < deadline {
+    if predicate() {
+      return true;
+    }
+    thread::sleep(Duration::from_millis(1));
+  }
+  predicate()
+}
+
+fn poll_ready<F: Future>(future: F) -> F::Output {
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut future = Box::pin(future);
+  match Future::poll(future.as_mut(), &mut cx) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was pending"),
+  }
+}
+
+fn write_event(journal: &mut SharedInMemoryJournal, sequence_nr: u64) {
+  let event: ArcShared<dyn Any + Send + Sync> = ArcShared::new(sequence_nr as i32);
+  let payload: ArcShared<dyn Any + Send + Sync> = ArcShared::new(Tagged::with_tags(event, ["blue"]));
+  let write = AtomicWrite::new(vec![PersistentRepr::new("pid-1", sequence_nr, payload)]).expect("atomic write");
+  poll_ready(journal.write_messages(&[write])).expect("write event");
+}
+
+fn projection(journal: &SharedInMemoryJournal, offset_store: &InMemoryOffsetStore, handled: &Handled) -> Projection {
+  let read_journal = PollingReadJournal::new(journal.clone());
+  let handled = handled.clone();
+  Projection::at_least_once(
+    ProjectionId::new("blue-events", "0"),
+    move |offset| read_journal.events_by_tag("blue", offset),
+    offset_store.clone(),
+    move |envelope: &EventEnvelope| {
+      handled.lock().push(envelope.downcast_ref::<i32>().copied().expect("i32 event"));
+      Ok(())
+    },
+  )
+}
+
+fn stored_offset(offset_store: &InMemoryOffsetStore) -> Option<Offset> {
+  offset_store.read_offset(&ProjectionId::new("blue-events", "0")).expect("read offset")
+}
+
+fn ask_offset(actor: &mut TypedActorRef<ProjectionCommand>) -> Result<Option<Offset>, ProjectionError> {
+  let response =
+    actor.ask::<Result<Option<Offset>, ProjectionError>, _>(|reply_to| ProjectionCommand::GetOffset { reply_to });
+  let mut future = response.future().clone();
+  assert!(wait_until(5000, || future.is_ready()));
+  future.try_take().expect("offset reply").expect("offset payload")
+}
+
+fn reset_offset(actor: &mut TypedActorRef<ProjectionCommand>, offset: Option<Offset>) -> Result<(), ProjectionError> {
+  let response =
+    actor.ask::<Result<(), ProjectionError>, _>(|reply_to| ProjectionCommand::ResetOffset { offset, reply_to });
+  let mut future = response.future().clone();
+  assert!(wait_until(5000, || future.is_ready()));
+  future.try_take().expect("reset reply").expect("reset payload")
+}
+
+fn spawn(projection: Projection) -> TypedActorSystem<ProjectionCommand> {
+  let props = ProjectionBehavior::props_with_materializer_config(projection, materializer_config);
+  TypedActorSystem::<ProjectionCommand>::create_from_props(&props, actor_system_config()).expect("system")
+}
+
+fn terminate_system(system: TypedActorSystem<ProjectionCommand>) {
+  system.terminate().expect("terminate");
+  system.as_untyped().run_until_terminated(&SpinBlocker);
+}
+
+#[test]
+fn projection_actor_handles_events_and_reports_stored_offset() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_event(&mut journal, 1);
+  write_event(&mut journal, 2);
+  let offset_store = InMemoryOffsetStore::new();
+  let handled: Handled = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let system = spawn(projection(&journal, &offset_store, &handled));
+  let mut actor = system.user_guardian_ref();
+
+  assert!(wait_until(5000, || stored_offset(&offset_store) == Some(Offset::sequence(2))));
+  write_event(&mut journal, 3);
+  assert!(wait_until(5000, || stored_offset(&offset_store) == Some(Offset::sequence(3))));
+
+  assert_eq!(ask_offset(&mut actor), Ok(Some(Offset::sequence(3))));
+  assert_eq!(*handled.lock(), vec![1, 2, 3]);
+
+  terminate_system(system);
+}
+
+#[test]
+fn paused_projection_resumes_from_stored_offset() {
+  let mut journal = SharedInMemoryJournal::new();
+  write_event(&mut journal, 1);
+  let offset_store = InMemoryOffsetStore::new();
+  let handled: Handled = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let system = spawn(projection(&journal, &offset_store, &handled));
+  let mut actor = system.user_guardian_ref();
+  assert!(wait_until(5000, || stored_offset(&offset_store) == Some(Offset::sequence(1))));
+
+  actor.tell(ProjectionCommand::Pause);
+  assert_eq!(ask_offset(&mut actor), Ok(Some(Offset::sequence(1))));
+  write_event(&mut journal, 2);
+  thread::sleep(Duration::from_millis(50));
+  assert_eq!(*handled.lock(), vec![1]);
+
+  actor.tell(ProjectionCommand::Resume);
+  assert!(wait_until(5000, || stored_offset(&offset_store) == Some(Offset::sequence(2))));
+  assert_eq!(*handled.lock(), vec![1, 2]);
+
+  terminate_system(system);
+}
+
+#[test]
+fn reset_offset_replays_events_after_the_new_offset() {
+  let mut journal = SharedInMemoryJournal::new();
+  for sequence_nr in 1..=3 {
+    write_event(&mut journal, sequence_nr);
+  }
+  let offset_store = InMemoryOffsetStore::new();
+  let handled: Handled = ArcShared::new(SpinSyncMutex::new(Vec::new()));
+  let system = spawn(projection(&journal, &offset_store, &handled));
+  let mut actor = system.user_guardian_ref();
+  assert!(wait_until(5000, || stored_offset(&offset_store) == Some(Offset::sequence(3))));
+
+  assert_eq!(reset_offset(&mut actor, Some(Offset::sequence(1))), Ok(()));
+  assert!(wait_until(5000, || handled.lock().len() == 5));
+
+  assert_eq!(*handled.lock(), vec![1, 2, 3, 2, 3]);
+  assert_eq!(stored_offset(&offset_store), Some(Offset::sequence(3)));
+
+  terminate_system(system);
+}
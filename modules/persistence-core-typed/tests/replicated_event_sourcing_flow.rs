@@ -0,0 +1,214 @@
+#![cfg(not(target_os = "none"))]
+
+use core::{
+  any::Any,
+  future::Future,
+  task::{Context, Poll, Waker},
+};
+use std::{
+  thread,
+  time::{Duration, Instant},
+  vec,
+};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig},
+  system::SpinBlocker,
+};
+use fraktor_actor_core_typed_rs::{Behavior, TypedActorRef, TypedActorSystem, TypedProps, dsl::Behaviors};
+use fraktor_persistence_core_kernel_rs::{
+  extension::PersistenceExtensionInstaller,
+  journal::{Journal, SharedInMemoryJournal},
+  persistent::{AtomicWrite, PersistentRepr},
+  query::PollingReadJournal,
+  replication::{ReplicaId, ReplicatedEventMetadata, ReplicationId, VersionVector},
+  snapshot::InMemorySnapshotStore,
+};
+use fraktor_persistence_core_typed_rs::{
+  EventSourcedEffector, EventSourcedEffectorConfig, EventSourcedEffectorMessageAdapter, EventSourcedEffectorSignal,
+  ReplicationSettings,
+};
+use fraktor_stream_core_kernel_rs::materialization::ActorMaterializerConfig;
+use fraktor_utils_core_rs::sync::ArcShared;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CounterState {
+  value:      i32,
+  concurrent: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Added(i32);
+
+#[derive(Clone, Debug)]
+enum CounterCommand {
+  Add(i32),
+  Read { reply_to: TypedActorRef<CounterState> },
+  Persistence(EventSourcedEffectorSignal<CounterState, Added>),
+}
+
+fn apply_added(state: &CounterState, event: &Added, metadata: &ReplicatedEventMetadata) -> CounterState {
+  CounterState { value: state.value + event.0, concurrent: state.concurrent + u32::from(metadata.concurrent()) }
+}
+
+fn replica(name: &str) -> ReplicaId {
+  ReplicaId::new(name)
+}
+
+fn replication_id(name: &str) -> ReplicationId {
+  ReplicationId::new("counter", "c-1", replica(name))
+}
+
+fn counter_props(journal: &SharedInMemoryJournal, replica_name: &str) -> TypedProps<CounterCommand> {
+  let settings = ReplicationSettings::new(
+    replication_id(replica_name),
+    [replica("a"), replica("b")],
+    PollingReadJournal::new(journal.clone()),
+  )
+  .with_materializer_config(|| ActorMaterializerConfig::default().with_drive_interval(Duration::from_millis(1)));
+  let message_adapter = EventSourcedEffectorMessageAdapter::new(CounterCommand::Persistence, |message| match message {
+    | CounterCommand::Persistence(signal) => Some(signal),
+    | _ => None,
+  });
+  let config = EventSourcedEffectorConfig::replicated(settings, CounterState { value: 0, concurrent: 0 }, apply_added)
+    .with_message_adapter(message_adapter);
+  EventSourcedEffector::props(config, |state, effector| Ok(counter_behavior(state, effector)))
+}
+
+fn counter_behavior(
+  state: CounterState,
+  effector: EventSourcedEffector<CounterState, Added, CounterCommand>,
+) -> Behavior<CounterCommand> {
+  Behaviors::receive_message(move |ctx, message| match message {
+    | CounterCommand::Add(delta) => {
+      let next_state = CounterState { value: state.value + delta, concurrent: state.concurrent };
+      let next_effector = effector.clone();
+      effector.persist_event(ctx, Added(*delta), move |_event| Ok(counter_behavior(next_state, next_effector)))
+    },
+    | CounterCommand::Read { reply_to } => {
+      let mut reply_to = reply_to.clone();
+      reply_to.tell(state.clone());
+      Ok(Behaviors::same())
+    },
+    | CounterCommand::Persistence(signal) => match effector.replicated_event(signal) {
+      | Some((event, metadata)) => Ok(counter_behavior(apply_added(&state, event, metadata), effector.clone())),
+      | None => Ok(Behaviors::unhandled()),
+    },
+  })
+}
+
+fn start_replica(journal: &SharedInMemoryJournal, replica_name: &str) -> TypedActorSystem<CounterCommand> {
+  let installer = PersistenceExtensionInstaller::new(journal.clone(), InMemorySnapshotStore::new());
+  let installers = ExtensionInstallers::default().with_extension_installer(installer);
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  let config = ActorSystemConfig::new(TestTickDriver::default())
+    .with_scheduler_config(scheduler)
+    .with_extension_installers(installers);
+  TypedActorSystem::<CounterCommand>::create_from_props(&counter_props(journal, replica_name), config)
+    .expect("replica system")
+}
+
+fn read_state(system: &TypedActorSystem<CounterCommand>) -> CounterState {
+  let mut actor = system.user_guardian_ref();
+  let response = actor.ask::<CounterState, _>(|reply_to| CounterCommand::Read { reply_to });
+  let mut future = response.future().clone();
+  assert!(wait_until(|| future.is_ready()));
+  future.try_take().expect("state reply").expect("state payload")
+}
+
+fn wait_for_state(
+  system: &TypedActorSystem<CounterCommand>,
+  predicate: impl Fn(&CounterState) -> bool,
+) -> CounterState {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  loop {
+    let state = read_state(system);
+    if predicate(&state) || Instant::now() >= deadline {
+      return state;
+    }
+    thread::sleep(Duration::from_millis(5));
+  }
+}
+
+fn wait_until(mut predicate: impl FnMut() -> bool) -> bool {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  while Instant::now() < deadline {
+    if predicate() {
+      return true;
+    }
+    thread::yield_now();
+  }
+  predicate()
+}
+
+fn drive_ready<F: Future>(future: F) -> F::Output {
+  let mut context = Context::from_waker(Waker::noop());
+  let mut future = core::pin::pin!(future);
+  match Future::poll(future.as_mut(), &mut context) {
+    | Poll::Ready(output) => output,
+    | Poll::Pending => panic!("future was not ready"),
+  }
+}
+
+fn seed_event(journal: &mut SharedInMemoryJournal, origin: &str, sequence_nr: u64, delta: i32, version: VersionVector) {
+  let metadata: ArcShared<dyn Any + Send + Sync> =
+    ArcShared::new(ReplicatedEventMetadata::new(replica(origin), sequence_nr, version, false));
+  let repr = PersistentRepr::new(replication_id(origin).persistence_id(), sequence_nr, ArcShared::new(Added(delta)))
+    .with_metadata(metadata);
+  drive_ready(journal.write_messages(&[AtomicWrite::new(vec![repr]).expect("atomic write")])).expect("seed event");
+}
+
+fn terminate(system: TypedActorSystem<CounterCommand>) {
+  system.terminate().expect("terminate");
+  system.as_untyped().run_until_terminated(&SpinBlocker);
+}
+
+#[test]
+fn replicas_converge_on_events_persisted_by_each_other() {
+  let journal = SharedInMemoryJournal::new();
+  let replica_a = start_replica(&journal, "a");
+  let replica_b = start_replica(&journal, "b");
+
+  replica_a.user_guardian_ref().tell(CounterCommand::Add(5));
+  replica_b.user_guardian_ref().tell(CounterCommand::Add(3));
+
+  assert_eq!(wait_for_state(&replica_a, |state| state.value == 8).value, 8);
+  assert_eq!(wait_for_state(&replica_b, |state| state.value == 8).value, 8);
+
+  terminate(replica_a);
+  terminate(replica_b);
+}
+
+#[test]
+fn events_unaware_of_each_other_are_applied_as_concurrent() {
+  let mut journal = SharedInMemoryJournal::new();
+  seed_event(&mut journal, "a", 1, 5, VersionVector::new().increment(&replica("a")));
+  seed_event(&mut journal, "b", 1, 3, VersionVector::new().increment(&replica("b")));
+
+  let replica_a = start_replica(&journal, "a");
+
+  let state = wait_for_state(&replica_a, |state| state.value == 8);
+  assert_eq!(state, CounterState { value: 8, concurrent: 1 });
+
+  terminate(replica_a);
+}
+
+#[test]
+fn restarted_replica_recovers_replicated_events_without_applying_them_twice() {
+  let mut journal = SharedInMemoryJournal::new();
+  seed_event(&mut journal, "b", 1, 3, VersionVector::new().increment(&replica("b")));
+
+  let replica_a = start_replica(&journal, "a");
+  assert_eq!(wait_for_state(&replica_a, |state| state.value == 3).value, 3);
+  replica_a.user_guardian_ref().tell(CounterCommand::Add(5));
+  assert_eq!(wait_for_state(&replica_a, |state| state.value == 8).value, 8);
+  terminate(replica_a);
+
+  let restarted = start_replica(&journal, "a");
+  thread::sleep(Duration::from_millis(50));
+
+  assert_eq!(read_state(&restarted), CounterState { value: 8, concurrent: 0 });
+
+  terminate(restarted);
+}
@@ -0,0 +1,23 @@
+//! Blocking future driver shared by the suites and harnesses.
+
+use core::{
+  future::Future,
+  pin::pin,
+  task::{Context, Poll, Waker},
+};
+use std::thread;
+
+/// Polls `future` on the current thread until it completes.
+///
+/// Plugin futures may complete on another thread, so a pending poll yields and retries instead of
+/// failing.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+  let mut context = Context::from_waker(Waker::noop());
+  let mut future = pin!(future);
+  loop {
+    match future.as_mut().poll(&mut context) {
+      | Poll::Ready(output) => return output,
+      | Poll::Pending => thread::yield_now(),
+    }
+  }
+}
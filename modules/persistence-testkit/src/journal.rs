@@ -0,0 +1,8 @@
+//! Fault-injecting journal package.
+
+mod faulty_journal;
+mod faulty_journal_future;
+mod journal_faults;
+
+pub use faulty_journal::FaultyJournal;
+pub use faulty_journal_future::FaultyJournalFuture;
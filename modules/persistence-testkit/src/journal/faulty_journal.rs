@@ -0,0 +1,124 @@
+//! Fault-injecting journal wrapper.
+
+#[cfg(test)]
+#[path = "faulty_journal_test.rs"]
+mod tests;
+
+use fraktor_persistence_core_kernel_rs::{
+  journal::{Journal, JournalError},
+  persistent::AtomicWrite,
+};
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedLock};
+
+use crate::journal::{FaultyJournalFuture, journal_faults::JournalFaults};
+
+/// Journal wrapper that fails or rejects operations on demand.
+///
+/// Queued faults are consumed one per operation, before the wrapped journal is called, so a
+/// faulted write leaves the wrapped journal untouched. Clones share the fault queue, so a test can
+/// keep one clone while the persistence extension owns another; they also share stored events
+/// whenever clones of the wrapped journal do, as with
+/// [`SharedInMemoryJournal`](fraktor_persistence_core_kernel_rs::journal::SharedInMemoryJournal).
+///
+/// The journal actor retries a failed write up to its configured retry limit before reporting a
+/// persist failure, so a write has to fail once more than that limit to reach the actor.
+#[derive(Clone)]
+pub struct FaultyJournal<J> {
+  inner:  J,
+  faults: SharedLock<JournalFaults>,
+}
+
+impl<J> FaultyJournal<J> {
+  /// Wraps `inner` without any queued fault.
+  #[must_use]
+  pub fn new(inner: J) -> Self {
+    Self { inner, faults: SharedLock::new_with_driver::<DefaultMutex<_>>(JournalFaults::default()) }
+  }
+
+  /// Returns the wrapped journal.
+  #[must_use]
+  pub const fn inner(&self) -> &J {
+    &self.inner
+  }
+
+  /// Fails the next `count` writes with [`JournalError::WriteFailed`].
+  pub fn fail_next_writes(&self, count: usize) {
+    let error = JournalError::WriteFailed(String::from("injected write failure"));
+    self.faults.with_lock(|faults| faults.push_writes(&error, count));
+  }
+
+  /// Rejects the next `count` writes with [`JournalError::InvalidAtomicWrite`].
+  ///
+  /// Failures and rejections share one queue and apply in the order they were requested.
+  pub fn reject_next_writes(&self, count: usize) {
+    let error = JournalError::InvalidAtomicWrite(String::from("write rejected by fault injection"));
+    self.faults.with_lock(|faults| faults.push_writes(&error, count));
+  }
+
+  /// Fails the next `count` replays with [`JournalError::ReadFailed`].
+  pub fn fail_next_replays(&self, count: usize) {
+    self.faults.with_lock(|faults| faults.add_replays(count));
+  }
+
+  /// Fails the next `count` deletes with [`JournalError::DeleteFailed`].
+  pub fn fail_next_deletes(&self, count: usize) {
+    self.faults.with_lock(|faults| faults.add_deletes(count));
+  }
+
+  /// Drops every queued fault.
+  pub fn clear_faults(&self) {
+    self.faults.with_lock(JournalFaults::clear);
+  }
+}
+
+impl<J: Journal> Journal for FaultyJournal<J> {
+  type DeleteFuture<'a>
+    = FaultyJournalFuture<J::DeleteFuture<'a>>
+  where
+    Self: 'a;
+  type HighestSeqNrFuture<'a>
+    = J::HighestSeqNrFuture<'a>
+  where
+    Self: 'a;
+  type ReplayFuture<'a>
+    = FaultyJournalFuture<J::ReplayFuture<'a>>
+  where
+    Self: 'a;
+  type WriteFuture<'a>
+    = FaultyJournalFuture<J::WriteFuture<'a>>
+  where
+    Self: 'a;
+
+  fn write_messages<'a>(&'a mut self, messages: &'a [AtomicWrite]) -> Self::WriteFuture<'a> {
+    match self.faults.with_lock(JournalFaults::take_write) {
+      | Some(fault) => FaultyJournalFuture::failed(fault),
+      | None => FaultyJournalFuture::inner(self.inner.write_messages(messages)),
+    }
+  }
+
+  fn replay_messages<'a>(
+    &'a self,
+    persistence_id: &'a str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    max: u64,
+  ) -> Self::ReplayFuture<'a> {
+    match self.faults.with_lock(JournalFaults::take_replay) {
+      | Some(fault) => FaultyJournalFuture::failed(fault),
+      | None => {
+        FaultyJournalFuture::inner(self.inner.replay_messages(persistence_id, from_sequence_nr, to_sequence_nr, max))
+      },
+    }
+  }
+
+  fn delete_messages_to<'a>(&'a mut self, persistence_id: &'a str, to_sequence_nr: u64) -> Self::DeleteFuture<'a> {
+    match self.faults.with_lock(JournalFaults::take_delete) {
+      | Some(fault) => FaultyJournalFuture::failed(fault),
+      | None => FaultyJournalFuture::inner(self.inner.delete_messages_to(persistence_id, to_sequence_nr)),
+    }
+  }
+
+  fn highest_sequence_nr<'a>(&'a self, persistence_id: &'a str) -> Self::HighestSeqNrFuture<'a> {
+    self.inner.highest_sequence_nr(persistence_id)
+  }
+}
@@ -0,0 +1,42 @@
+//! Future returned by the faulty journal.
+
+use core::{
+  future::Future,
+  pin::Pin,
+  task::{Context, Poll},
+};
+
+use fraktor_persistence_core_kernel_rs::journal::JournalError;
+
+/// Resolves to an injected fault, or to the wrapped journal's result when no fault was queued.
+pub struct FaultyJournalFuture<F> {
+  inner: Option<Pin<Box<F>>>,
+  fault: Option<JournalError>,
+}
+
+impl<F> FaultyJournalFuture<F> {
+  pub(crate) fn inner(future: F) -> Self {
+    Self { inner: Some(Box::pin(future)), fault: None }
+  }
+
+  pub(crate) const fn failed(fault: JournalError) -> Self {
+    Self { inner: None, fault: Some(fault) }
+  }
+}
+
+impl<F, T> Future for FaultyJournalFuture<F>
+where
+  F: Future<Output = Result<T, JournalError>>,
+{
+  type Output = Result<T, JournalError>;
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    if let Some(fault) = self.fault.take() {
+      return Poll::Ready(Err(fault));
+    }
+    match self.inner.as_mut() {
+      | Some(inner) => inner.as_mut().poll(cx),
+      | None => Poll::Pending,
+    }
+  }
+}
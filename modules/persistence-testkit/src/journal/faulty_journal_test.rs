@@ -0,0 +1,65 @@
+use core::any::Any;
+
+use fraktor_persistence_core_kernel_rs::{
+  journal::{Journal, JournalError, SharedInMemoryJournal},
+  persistent::{AtomicWrite, PersistentRepr},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::FaultyJournal;
+use crate::{block_on::block_on, tck::JournalTck};
+
+const PERSISTENCE_ID: &str = "faulty-journal";
+
+fn write(sequence_nr: u64) -> AtomicWrite {
+  let value: ArcShared<dyn Any + Send + Sync> = ArcShared::new(sequence_nr);
+  AtomicWrite::new(vec![PersistentRepr::new(PERSISTENCE_ID, sequence_nr, value)]).expect("atomic write")
+}
+
+#[test]
+fn queued_write_faults_apply_in_order_and_skip_the_wrapped_journal() {
+  let mut journal = FaultyJournal::new(SharedInMemoryJournal::new());
+  let observer = journal.clone();
+  observer.reject_next_writes(1);
+  observer.fail_next_writes(1);
+
+  let rejected = block_on(journal.write_messages(&[write(1)]));
+  let failed = block_on(journal.write_messages(&[write(1)]));
+  block_on(journal.write_messages(&[write(1)])).expect("write after the faults");
+
+  assert!(matches!(rejected, Err(JournalError::InvalidAtomicWrite(_))));
+  assert!(matches!(failed, Err(JournalError::WriteFailed(_))));
+  assert_eq!(block_on(observer.highest_sequence_nr(PERSISTENCE_ID)), Ok(1));
+}
+
+#[test]
+fn replay_and_delete_faults_are_consumed_once_each() {
+  let mut journal = FaultyJournal::new(SharedInMemoryJournal::new());
+  block_on(journal.write_messages(&[write(1)])).expect("write");
+  journal.fail_next_replays(1);
+  journal.fail_next_deletes(1);
+
+  let replay_fault = block_on(journal.replay_messages(PERSISTENCE_ID, 1, u64::MAX, 0));
+  let delete_fault = block_on(journal.delete_messages_to(PERSISTENCE_ID, 1));
+  let replayed = block_on(journal.replay_messages(PERSISTENCE_ID, 1, u64::MAX, 0)).expect("replay");
+
+  assert!(matches!(replay_fault, Err(JournalError::ReadFailed(_))));
+  assert!(matches!(delete_fault, Err(JournalError::DeleteFailed(_))));
+  assert_eq!(replayed.len(), 1);
+}
+
+#[test]
+fn cleared_faults_no_longer_apply() {
+  let mut journal = FaultyJournal::new(SharedInMemoryJournal::new());
+  journal.fail_next_writes(3);
+  journal.fail_next_replays(1);
+  journal.clear_faults();
+
+  block_on(journal.write_messages(&[write(1)])).expect("write");
+  assert_eq!(block_on(journal.replay_messages(PERSISTENCE_ID, 1, u64::MAX, 0)).expect("replay").len(), 1);
+}
+
+#[test]
+fn faulty_journal_without_faults_passes_the_journal_tck() {
+  JournalTck::new(|| FaultyJournal::new(SharedInMemoryJournal::new())).run().expect("transparent wrapper conforms");
+}
@@ -0,0 +1,53 @@
+//! Pending faults shared by the clones of a faulty journal.
+
+use std::{collections::VecDeque, iter};
+
+use fraktor_persistence_core_kernel_rs::journal::JournalError;
+
+/// Faults queued for upcoming journal operations.
+#[derive(Default)]
+pub(crate) struct JournalFaults {
+  writes:  VecDeque<JournalError>,
+  replays: usize,
+  deletes: usize,
+}
+
+impl JournalFaults {
+  pub(crate) fn push_writes(&mut self, error: &JournalError, count: usize) {
+    self.writes.extend(iter::repeat_n(error, count).cloned());
+  }
+
+  pub(crate) const fn add_replays(&mut self, count: usize) {
+    self.replays = self.replays.saturating_add(count);
+  }
+
+  pub(crate) const fn add_deletes(&mut self, count: usize) {
+    self.deletes = self.deletes.saturating_add(count);
+  }
+
+  pub(crate) fn clear(&mut self) {
+    self.writes.clear();
+    self.replays = 0;
+    self.deletes = 0;
+  }
+
+  pub(crate) fn take_write(&mut self) -> Option<JournalError> {
+    self.writes.pop_front()
+  }
+
+  pub(crate) fn take_replay(&mut self) -> Option<JournalError> {
+    take_counted(&mut self.replays, "injected replay failure").map(JournalError::ReadFailed)
+  }
+
+  pub(crate) fn take_delete(&mut self) -> Option<JournalError> {
+    take_counted(&mut self.deletes, "injected delete failure").map(JournalError::DeleteFailed)
+  }
+}
+
+fn take_counted(remaining: &mut usize, reason: &str) -> Option<String> {
+  if *remaining == 0 {
+    return None;
+  }
+  *remaining -= 1;
+  Some(String::from(reason))
+}
@@ -0,0 +1,55 @@
+#![deny(missing_docs)]
+#![cfg_attr(test, allow(clippy::unwrap_used, clippy::expect_used, clippy::disallowed_types))]
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(unsafe_op_in_unsafe_fn)]
+#![deny(clippy::missing_errors_doc)]
+#![deny(clippy::missing_panics_doc)]
+#![deny(clippy::missing_safety_doc)]
+#![deny(clippy::redundant_clone)]
+#![deny(clippy::redundant_field_names)]
+#![deny(clippy::redundant_pattern)]
+#![deny(clippy::unnecessary_to_owned)]
+#![deny(clippy::unnecessary_struct_initialization)]
+#![deny(clippy::needless_borrow)]
+#![deny(clippy::needless_pass_by_value)]
+#![deny(clippy::manual_ok_or)]
+#![deny(clippy::manual_map)]
+#![deny(clippy::manual_let_else)]
+#![deny(clippy::unused_async)]
+#![deny(clippy::unnecessary_wraps)]
+#![deny(clippy::unreachable)]
+#![deny(clippy::empty_enums)]
+#![deny(clippy::no_effect)]
+#![deny(dropping_copy_types)]
+#![cfg_attr(not(test), deny(clippy::unwrap_used))]
+#![cfg_attr(not(test), deny(clippy::expect_used))]
+#![deny(clippy::todo)]
+#![deny(clippy::unimplemented)]
+#![deny(clippy::print_stdout)]
+#![deny(clippy::dbg_macro)]
+#![deny(clippy::missing_const_for_fn)]
+#![deny(clippy::must_use_candidate)]
+#![deny(clippy::trivially_copy_pass_by_ref)]
+#![deny(clippy::clone_on_copy)]
+#![deny(clippy::len_without_is_empty)]
+#![deny(clippy::wrong_self_convention)]
+#![deny(clippy::from_over_into)]
+#![deny(clippy::eq_op)]
+#![deny(clippy::bool_comparison)]
+#![deny(clippy::needless_bool)]
+#![deny(clippy::match_like_matches_macro)]
+#![deny(clippy::manual_assert)]
+#![deny(clippy::naive_bytecount)]
+#![deny(clippy::if_same_then_else)]
+#![deny(unreachable_pub)]
+#![allow(unknown_lints)]
+
+//! Conformance suites and test harnesses for persistence plugins and event-sourced actors.
+
+mod block_on;
+/// Fault-injecting journal wrappers.
+pub mod journal;
+/// Technology compatibility kits for journal, snapshot and durable state plugins.
+pub mod tck;
+/// Harnesses for typed event-sourced effectors.
+pub mod typed;
@@ -0,0 +1,16 @@
+//! Technology compatibility kit package.
+//!
+//! Each suite takes a factory that builds a fresh, empty plugin instance and runs every check
+//! against its own instance, so checks never observe each other's writes.
+
+mod durable_state_store_tck;
+mod expect_eq;
+mod journal_tck;
+mod snapshot_store_tck;
+mod tck_failure;
+
+pub use durable_state_store_tck::DurableStateStoreTck;
+pub(crate) use expect_eq::expect_eq;
+pub use journal_tck::JournalTck;
+pub use snapshot_store_tck::SnapshotStoreTck;
+pub use tck_failure::TckFailure;
@@ -0,0 +1,201 @@
+//! Durable state store conformance suite.
+
+#[cfg(test)]
+#[path = "durable_state_store_tck_test.rs"]
+mod tests;
+
+use std::collections::BTreeMap;
+
+use fraktor_persistence_core_kernel_rs::state::{DurableStateStore, DurableStateUpdateStore};
+
+use crate::{
+  block_on::block_on,
+  tck::{TckFailure, expect_eq},
+};
+
+const PERSISTENCE_ID: &str = "tck-state-a";
+const OTHER_PERSISTENCE_ID: &str = "tck-state-b";
+const THIRD_PERSISTENCE_ID: &str = "tck-state-c";
+const UNKNOWN_PERSISTENCE_ID: &str = "tck-state-unknown";
+const TAG: &str = "tck-tag";
+const OTHER_TAG: &str = "tck-other-tag";
+
+/// Conformance suite for [`DurableStateStore`] implementations storing `String` objects.
+///
+/// [`run`](Self::run) checks revision tracking, optimistic concurrency on upsert and delete, and
+/// that a delete resets the revision. [`run_with_changes`](Self::run_with_changes) additionally
+/// checks the tagged change feed of a [`DurableStateUpdateStore`].
+pub struct DurableStateStoreTck<F> {
+  factory: F,
+}
+
+impl<F> DurableStateStoreTck<F> {
+  /// Creates a suite that builds a fresh, empty durable state store with `factory` for every check.
+  #[must_use]
+  pub const fn new(factory: F) -> Self {
+    Self { factory }
+  }
+}
+
+impl<F, D> DurableStateStoreTck<F>
+where
+  F: FnMut() -> D,
+  D: DurableStateStore<String>,
+{
+  /// Runs every object check and stops at the first violation.
+  ///
+  /// # Errors
+  ///
+  /// Returns a [`TckFailure`] naming the first contract the store violates.
+  pub fn run(&mut self) -> Result<(), TckFailure> {
+    self.check_missing_object()?;
+    self.check_upsert_advances_revision()?;
+    self.check_stale_revision_is_rejected()?;
+    self.check_delete_resets_revision()?;
+    Ok(())
+  }
+
+  fn check_missing_object(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "missing_object";
+    let store = (self.factory)();
+    expect_eq(CHECK, "unknown object", &(None, 0), &get(CHECK, &store, UNKNOWN_PERSISTENCE_ID)?)
+  }
+
+  fn check_upsert_advances_revision(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "upsert_advances_revision";
+    let mut store = (self.factory)();
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 0, None)?;
+    expect_eq(CHECK, "first revision", &(Some(value(PERSISTENCE_ID, 1)), 1), &get(CHECK, &store, PERSISTENCE_ID)?)?;
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 1, None)?;
+    upsert(CHECK, &mut store, OTHER_PERSISTENCE_ID, 0, None)?;
+    expect_eq(CHECK, "second revision", &(Some(value(PERSISTENCE_ID, 2)), 2), &get(CHECK, &store, PERSISTENCE_ID)?)?;
+    expect_eq(
+      CHECK,
+      "another persistence id",
+      &(Some(value(OTHER_PERSISTENCE_ID, 1)), 1),
+      &get(CHECK, &store, OTHER_PERSISTENCE_ID)?,
+    )
+  }
+
+  fn check_stale_revision_is_rejected(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "stale_revision_is_rejected";
+    let mut store = (self.factory)();
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 0, None)?;
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 1, None)?;
+    if block_on(store.upsert_object(PERSISTENCE_ID, 1, String::from("stale"), None)).is_ok() {
+      return Err(TckFailure::new(CHECK, "an upsert expecting stale revision 1 was accepted"));
+    }
+    if block_on(store.delete_object(PERSISTENCE_ID, 3)).is_ok() {
+      return Err(TckFailure::new(CHECK, "a delete expecting future revision 3 was accepted"));
+    }
+    expect_eq(
+      CHECK,
+      "object after the rejected writes",
+      &(Some(value(PERSISTENCE_ID, 2)), 2),
+      &get(CHECK, &store, PERSISTENCE_ID)?,
+    )
+  }
+
+  fn check_delete_resets_revision(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "delete_resets_revision";
+    let mut store = (self.factory)();
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 0, None)?;
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 1, None)?;
+    block_on(store.delete_object(PERSISTENCE_ID, 2))
+      .map_err(|error| TckFailure::new(CHECK, format!("delete_object failed: {error}")))?;
+    expect_eq(CHECK, "deleted object", &(None, 0), &get(CHECK, &store, PERSISTENCE_ID)?)?;
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 0, None)?;
+    expect_eq(CHECK, "recreated object", &(Some(value(PERSISTENCE_ID, 1)), 1), &get(CHECK, &store, PERSISTENCE_ID)?)
+  }
+}
+
+impl<F, D> DurableStateStoreTck<F>
+where
+  F: FnMut() -> D,
+  D: DurableStateUpdateStore<String>,
+{
+  /// Runs every object check plus the change feed checks, stopping at the first violation.
+  ///
+  /// The feed may coalesce intermediate revisions, but it must deliver changes in increasing
+  /// offset order, only for the requested tag, and end with the latest tagged revision of each
+  /// persistence id.
+  ///
+  /// # Errors
+  ///
+  /// Returns a [`TckFailure`] naming the first contract the store violates.
+  pub fn run_with_changes(&mut self) -> Result<(), TckFailure> {
+    self.run()?;
+    self.check_tagged_changes()
+  }
+
+  fn check_tagged_changes(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "tagged_changes";
+    let mut store = (self.factory)();
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 0, Some(TAG))?;
+    upsert(CHECK, &mut store, OTHER_PERSISTENCE_ID, 0, Some(OTHER_TAG))?;
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 1, None)?;
+    upsert(CHECK, &mut store, THIRD_PERSISTENCE_ID, 0, Some(TAG))?;
+    upsert(CHECK, &mut store, PERSISTENCE_ID, 2, Some(TAG))?;
+
+    let tagged = latest_changes(CHECK, &store, TAG)?;
+    let expected = BTreeMap::from([(String::from(PERSISTENCE_ID), 3), (String::from(THIRD_PERSISTENCE_ID), 1)]);
+    expect_eq(CHECK, "latest revisions for the tag", &expected, &tagged)?;
+    let other = latest_changes(CHECK, &store, OTHER_TAG)?;
+    let expected_other = BTreeMap::from([(String::from(OTHER_PERSISTENCE_ID), 1)]);
+    expect_eq(CHECK, "latest revisions for another tag", &expected_other, &other)?;
+    let unknown = latest_changes(CHECK, &store, "tck-unknown-tag")?;
+    expect_eq(CHECK, "latest revisions for an unknown tag", &BTreeMap::new(), &unknown)
+  }
+}
+
+fn value(persistence_id: &str, revision: u64) -> String {
+  format!("{persistence_id}-{revision}")
+}
+
+fn get<D: DurableStateStore<String>>(
+  check: &'static str,
+  store: &D,
+  persistence_id: &str,
+) -> Result<(Option<String>, u64), TckFailure> {
+  let result = block_on(store.get_object(persistence_id))
+    .map_err(|error| TckFailure::new(check, format!("get_object failed: {error}")))?;
+  Ok((result.value().cloned(), result.revision()))
+}
+
+/// Upserts the object expected at `expected_revision + 1`, so stored values encode their revision.
+fn upsert<D: DurableStateStore<String>>(
+  check: &'static str,
+  store: &mut D,
+  persistence_id: &str,
+  expected_revision: u64,
+  tag: Option<&str>,
+) -> Result<(), TckFailure> {
+  let object = value(persistence_id, expected_revision + 1);
+  block_on(store.upsert_object(persistence_id, expected_revision, object, tag))
+    .map_err(|error| TckFailure::new(check, format!("upsert_object({expected_revision}) failed: {error}")))
+}
+
+/// Drains the change feed for `tag` and returns the last revision seen per persistence id.
+fn latest_changes<D: DurableStateUpdateStore<String>>(
+  check: &'static str,
+  store: &D,
+  tag: &str,
+) -> Result<BTreeMap<String, u64>, TckFailure> {
+  let mut latest = BTreeMap::new();
+  let mut offset = 0;
+  while let Some(change) =
+    block_on(store.changes(tag, offset)).map_err(|error| TckFailure::new(check, format!("changes failed: {error}")))?
+  {
+    if change.offset() <= offset {
+      return Err(TckFailure::new(check, format!("change offset {} did not advance past {offset}", change.offset())));
+    }
+    if change.tag() != tag {
+      return Err(TckFailure::new(check, format!("feed for {tag} returned a change tagged {}", change.tag())));
+    }
+    let expected = value(change.persistence_id(), change.revision());
+    expect_eq(check, "changed object", &expected, change.value())?;
+    offset = change.offset();
+    latest.insert(String::from(change.persistence_id()), change.revision());
+  }
+  Ok(latest)
+}
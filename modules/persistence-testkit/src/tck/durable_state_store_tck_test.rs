@@ -0,0 +1,93 @@
+use core::{
+  future::{Future, ready},
+  pin::Pin,
+};
+use std::collections::BTreeMap;
+
+use fraktor_persistence_core_kernel_rs::state::{
+  DurableStateChange, DurableStateError, DurableStateStore, DurableStateUpdateStore, GetObjectResult,
+};
+
+use super::DurableStateStoreTck;
+
+type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, DurableStateError>> + Send + 'a>>;
+
+/// Map-backed store keeping every tagged revision in a change log.
+#[derive(Default)]
+struct MapDurableStateStore {
+  objects:          BTreeMap<String, (String, u64)>,
+  changes:          Vec<(String, u64, String, String)>,
+  ignore_revisions: bool,
+}
+
+impl MapDurableStateStore {
+  fn ignoring_revisions() -> Self {
+    Self { ignore_revisions: true, ..Self::default() }
+  }
+
+  fn revision(&self, persistence_id: &str) -> u64 {
+    self.objects.get(persistence_id).map_or(0, |(_, revision)| *revision)
+  }
+}
+
+impl DurableStateStore<String> for MapDurableStateStore {
+  fn get_object<'a>(&'a self, persistence_id: &'a str) -> StoreFuture<'a, GetObjectResult<String>> {
+    let result = match self.objects.get(persistence_id) {
+      | Some((value, revision)) => GetObjectResult::new(Some(value.clone()), *revision),
+      | None => GetObjectResult::empty(),
+    };
+    Box::pin(ready(Ok(result)))
+  }
+
+  fn upsert_object<'a>(
+    &'a mut self,
+    persistence_id: &'a str,
+    expected_revision: u64,
+    object: String,
+    tag: Option<&'a str>,
+  ) -> StoreFuture<'a, ()> {
+    let actual = self.revision(persistence_id);
+    if actual != expected_revision && !self.ignore_revisions {
+      return Box::pin(ready(Err(DurableStateError::upsert_revision(persistence_id, expected_revision, actual))));
+    }
+    let revision = actual + 1;
+    if let Some(tag) = tag {
+      self.changes.push((String::from(persistence_id), revision, String::from(tag), object.clone()));
+    }
+    self.objects.insert(String::from(persistence_id), (object, revision));
+    Box::pin(ready(Ok(())))
+  }
+
+  fn delete_object<'a>(&'a mut self, persistence_id: &'a str, expected_revision: u64) -> StoreFuture<'a, ()> {
+    let actual = self.revision(persistence_id);
+    if actual != expected_revision && !self.ignore_revisions {
+      return Box::pin(ready(Err(DurableStateError::delete_revision(persistence_id, expected_revision, actual))));
+    }
+    self.objects.remove(persistence_id);
+    Box::pin(ready(Ok(())))
+  }
+}
+
+impl DurableStateUpdateStore<String> for MapDurableStateStore {
+  fn changes<'a>(&'a self, tag: &'a str, from_offset: usize) -> StoreFuture<'a, Option<DurableStateChange<String>>> {
+    let change = self.changes.iter().enumerate().skip(from_offset).find(|(_, change)| change.2 == tag).map(
+      |(index, (persistence_id, revision, tag, value))| {
+        DurableStateChange::new(index + 1, persistence_id.clone(), *revision, tag.clone(), value.clone())
+      },
+    );
+    Box::pin(ready(Ok(change)))
+  }
+}
+
+#[test]
+fn conforming_store_passes_the_durable_state_store_tck() {
+  DurableStateStoreTck::new(MapDurableStateStore::default).run_with_changes().expect("map store conforms");
+}
+
+#[test]
+fn store_ignoring_revisions_fails_the_concurrency_check() {
+  let failure =
+    DurableStateStoreTck::new(MapDurableStateStore::ignoring_revisions).run().expect_err("revisions are ignored");
+
+  assert_eq!(failure.check(), "stale_revision_is_rejected");
+}
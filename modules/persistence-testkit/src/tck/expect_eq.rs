@@ -0,0 +1,19 @@
+//! Equality assertion shared by the conformance suites.
+
+use core::fmt::Debug;
+
+use crate::tck::TckFailure;
+
+/// Fails `check` unless `actual` equals `expected`, describing the compared `subject`.
+pub(crate) fn expect_eq<T: Debug + PartialEq>(
+  check: &'static str,
+  subject: &str,
+  expected: &T,
+  actual: &T,
+) -> Result<(), TckFailure> {
+  if expected == actual {
+    Ok(())
+  } else {
+    Err(TckFailure::new(check, format!("{subject}: expected {expected:?}, got {actual:?}")))
+  }
+}
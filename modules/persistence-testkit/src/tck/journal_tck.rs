@@ -0,0 +1,272 @@
+//! Journal conformance suite.
+
+#[cfg(test)]
+#[path = "journal_tck_test.rs"]
+mod tests;
+
+use core::{any::Any, ops::RangeInclusive};
+
+use fraktor_persistence_core_kernel_rs::{
+  journal::Journal,
+  persistent::{AtomicWrite, PersistentRepr},
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{
+  block_on::block_on,
+  tck::{TckFailure, expect_eq},
+};
+
+const PERSISTENCE_ID: &str = "tck-journal-a";
+const OTHER_PERSISTENCE_ID: &str = "tck-journal-b";
+const UNKNOWN_PERSISTENCE_ID: &str = "tck-journal-unknown";
+
+/// Conformance suite for [`Journal`] implementations.
+///
+/// Events carry `String` payloads so serializing backends can store them with the builtin
+/// serializers. The suite checks replay ordering, range and page limits, persistence id isolation,
+/// `delete_messages_to` semantics, the highest sequence number after deletes, and that rejected
+/// batches leave the journal untouched.
+pub struct JournalTck<F> {
+  factory:                   F,
+  multi_event_atomic_writes: bool,
+}
+
+impl<F> JournalTck<F> {
+  /// Creates a suite that builds a fresh, empty journal with `factory` for every check.
+  #[must_use]
+  pub const fn new(factory: F) -> Self {
+    Self { factory, multi_event_atomic_writes: true }
+  }
+
+  /// Declares whether the journal stores atomic writes holding more than one event.
+  ///
+  /// Backends that answer such writes with
+  /// [`JournalError::UnsupportedAtomicWrite`](fraktor_persistence_core_kernel_rs::journal::JournalError::UnsupportedAtomicWrite)
+  /// pass `false` to skip the checks that need them.
+  #[must_use]
+  pub const fn with_multi_event_atomic_writes(mut self, supported: bool) -> Self {
+    self.multi_event_atomic_writes = supported;
+    self
+  }
+}
+
+impl<F, J> JournalTck<F>
+where
+  F: FnMut() -> J,
+  J: Journal,
+{
+  /// Runs every check and stops at the first violation.
+  ///
+  /// # Errors
+  ///
+  /// Returns a [`TckFailure`] naming the first contract the journal violates.
+  pub fn run(&mut self) -> Result<(), TckFailure> {
+    self.check_replay_order()?;
+    self.check_replay_range()?;
+    self.check_persistence_id_isolation()?;
+    self.check_highest_sequence_nr()?;
+    self.check_delete_messages_to()?;
+    self.check_highest_sequence_nr_after_delete()?;
+    self.check_sequence_gap_is_rejected()?;
+    self.check_mixed_persistence_id_is_rejected()?;
+    if self.multi_event_atomic_writes {
+      self.check_multi_event_atomic_write()?;
+    }
+    Ok(())
+  }
+
+  fn check_replay_order(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "replay_order";
+    let mut journal = (self.factory)();
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 1..=3)?)?;
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 4..=4)?)?;
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 5..=5)?)?;
+    let replayed = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "full replay", &entries(PERSISTENCE_ID, 1..=5), &replayed)
+  }
+
+  fn check_replay_range(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "replay_range";
+    let mut journal = (self.factory)();
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 1..=5)?)?;
+    let bounded = replay(CHECK, &journal, PERSISTENCE_ID, 2, 4, 0)?;
+    expect_eq(CHECK, "replay 2..=4", &entries(PERSISTENCE_ID, 2..=4), &bounded)?;
+    let paged = replay(CHECK, &journal, PERSISTENCE_ID, 2, u64::MAX, 2)?;
+    expect_eq(CHECK, "replay from 2 with max 2", &entries(PERSISTENCE_ID, 2..=3), &paged)?;
+    let beyond = replay(CHECK, &journal, PERSISTENCE_ID, 6, u64::MAX, 0)?;
+    expect_eq(CHECK, "replay past the highest sequence number", &Vec::new(), &beyond)?;
+    let inverted = replay(CHECK, &journal, PERSISTENCE_ID, 4, 2, 0)?;
+    expect_eq(CHECK, "replay with from greater than to", &Vec::new(), &inverted)
+  }
+
+  fn check_persistence_id_isolation(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "persistence_id_isolation";
+    let mut journal = (self.factory)();
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 1..=2)?)?;
+    write(CHECK, &mut journal, &single_writes(CHECK, OTHER_PERSISTENCE_ID, 1..=3)?)?;
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 3..=3)?)?;
+    let replayed = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "first persistence id", &entries(PERSISTENCE_ID, 1..=3), &replayed)?;
+    let other = replay(CHECK, &journal, OTHER_PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "second persistence id", &entries(OTHER_PERSISTENCE_ID, 1..=3), &other)?;
+    let unknown = replay(CHECK, &journal, UNKNOWN_PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "unknown persistence id", &Vec::new(), &unknown)
+  }
+
+  fn check_highest_sequence_nr(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "highest_sequence_nr";
+    let mut journal = (self.factory)();
+    expect_eq(CHECK, "highest of an unknown id", &0, &highest(CHECK, &journal, UNKNOWN_PERSISTENCE_ID)?)?;
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 1..=3)?)?;
+    write(CHECK, &mut journal, &single_writes(CHECK, OTHER_PERSISTENCE_ID, 1..=1)?)?;
+    expect_eq(CHECK, "highest after writes", &3, &highest(CHECK, &journal, PERSISTENCE_ID)?)?;
+    expect_eq(CHECK, "highest of another id", &1, &highest(CHECK, &journal, OTHER_PERSISTENCE_ID)?)
+  }
+
+  fn check_delete_messages_to(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "delete_messages_to";
+    let mut journal = (self.factory)();
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 1..=5)?)?;
+    write(CHECK, &mut journal, &single_writes(CHECK, OTHER_PERSISTENCE_ID, 1..=2)?)?;
+    delete(CHECK, &mut journal, PERSISTENCE_ID, 3)?;
+    let replayed = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "replay after deleting to 3", &entries(PERSISTENCE_ID, 4..=5), &replayed)?;
+    delete(CHECK, &mut journal, PERSISTENCE_ID, 3)?;
+    let repeated = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "replay after repeating the delete", &entries(PERSISTENCE_ID, 4..=5), &repeated)?;
+    let other = replay(CHECK, &journal, OTHER_PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "another persistence id", &entries(OTHER_PERSISTENCE_ID, 1..=2), &other)
+  }
+
+  fn check_highest_sequence_nr_after_delete(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "highest_sequence_nr_after_delete";
+    let mut journal = (self.factory)();
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 1..=5)?)?;
+    delete(CHECK, &mut journal, PERSISTENCE_ID, 5)?;
+    let replayed = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "replay after deleting everything", &Vec::new(), &replayed)?;
+    expect_eq(CHECK, "highest after deleting everything", &5, &highest(CHECK, &journal, PERSISTENCE_ID)?)?;
+    delete(CHECK, &mut journal, PERSISTENCE_ID, 10)?;
+    expect_eq(CHECK, "highest after deleting past it", &5, &highest(CHECK, &journal, PERSISTENCE_ID)?)?;
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 6..=6)?)?;
+    let continued = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "replay after continuing the sequence", &entries(PERSISTENCE_ID, 6..=6), &continued)
+  }
+
+  fn check_sequence_gap_is_rejected(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "sequence_gap_is_rejected";
+    let mut journal = (self.factory)();
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 1..=2)?)?;
+    let mut batch = single_writes(CHECK, PERSISTENCE_ID, 3..=3)?;
+    batch.extend(single_writes(CHECK, PERSISTENCE_ID, 5..=5)?);
+    if block_on(journal.write_messages(&batch)).is_ok() {
+      return Err(TckFailure::new(CHECK, "a batch skipping sequence number 4 was accepted"));
+    }
+    let replayed = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "replay after the rejected batch", &entries(PERSISTENCE_ID, 1..=2), &replayed)?;
+    expect_eq(CHECK, "highest after the rejected batch", &2, &highest(CHECK, &journal, PERSISTENCE_ID)?)?;
+    write(CHECK, &mut journal, &single_writes(CHECK, PERSISTENCE_ID, 3..=3)?)
+  }
+
+  fn check_mixed_persistence_id_is_rejected(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "mixed_persistence_id_is_rejected";
+    let mut journal = (self.factory)();
+    let mut batch = single_writes(CHECK, PERSISTENCE_ID, 1..=1)?;
+    batch.extend(single_writes(CHECK, OTHER_PERSISTENCE_ID, 1..=1)?);
+    if block_on(journal.write_messages(&batch)).is_ok() {
+      return Err(TckFailure::new(CHECK, "a batch spanning two persistence ids was accepted"));
+    }
+    expect_eq(CHECK, "highest of the first id", &0, &highest(CHECK, &journal, PERSISTENCE_ID)?)?;
+    expect_eq(CHECK, "highest of the second id", &0, &highest(CHECK, &journal, OTHER_PERSISTENCE_ID)?)?;
+    let replayed = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "replay after the rejected batch", &Vec::new(), &replayed)
+  }
+
+  fn check_multi_event_atomic_write(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "multi_event_atomic_write";
+    let mut journal = (self.factory)();
+    let batch = atomic_write(CHECK, (1..=3).map(|sequence_nr| event(PERSISTENCE_ID, sequence_nr)).collect())?;
+    write(CHECK, &mut journal, &[batch])?;
+    let replayed = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "replay of the atomic write", &entries(PERSISTENCE_ID, 1..=3), &replayed)?;
+    let gapped = atomic_write(CHECK, vec![event(PERSISTENCE_ID, 4), event(PERSISTENCE_ID, 6)])?;
+    if block_on(journal.write_messages(&[gapped])).is_ok() {
+      return Err(TckFailure::new(CHECK, "an atomic write skipping sequence number 5 was accepted"));
+    }
+    let after = replay(CHECK, &journal, PERSISTENCE_ID, 1, u64::MAX, 0)?;
+    expect_eq(CHECK, "replay after the rejected atomic write", &entries(PERSISTENCE_ID, 1..=3), &after)?;
+    expect_eq(CHECK, "highest after the rejected atomic write", &3, &highest(CHECK, &journal, PERSISTENCE_ID)?)
+  }
+}
+
+fn payload(persistence_id: &str, sequence_nr: u64) -> String {
+  format!("{persistence_id}-{sequence_nr}")
+}
+
+fn event(persistence_id: &str, sequence_nr: u64) -> PersistentRepr {
+  let value: ArcShared<dyn Any + Send + Sync> = ArcShared::new(payload(persistence_id, sequence_nr));
+  PersistentRepr::new(persistence_id, sequence_nr, value)
+}
+
+fn entries(persistence_id: &str, range: RangeInclusive<u64>) -> Vec<(u64, String)> {
+  range.map(|sequence_nr| (sequence_nr, payload(persistence_id, sequence_nr))).collect()
+}
+
+fn atomic_write(check: &'static str, payload: Vec<PersistentRepr>) -> Result<AtomicWrite, TckFailure> {
+  AtomicWrite::new(payload).map_err(|error| TckFailure::new(check, format!("building an atomic write failed: {error}")))
+}
+
+fn single_writes(
+  check: &'static str,
+  persistence_id: &str,
+  range: RangeInclusive<u64>,
+) -> Result<Vec<AtomicWrite>, TckFailure> {
+  range.map(|sequence_nr| atomic_write(check, vec![event(persistence_id, sequence_nr)])).collect()
+}
+
+fn write<J: Journal>(check: &'static str, journal: &mut J, messages: &[AtomicWrite]) -> Result<(), TckFailure> {
+  block_on(journal.write_messages(messages)).map_err(|error| TckFailure::new(check, format!("write failed: {error}")))
+}
+
+fn delete<J: Journal>(
+  check: &'static str,
+  journal: &mut J,
+  persistence_id: &str,
+  to_sequence_nr: u64,
+) -> Result<(), TckFailure> {
+  block_on(journal.delete_messages_to(persistence_id, to_sequence_nr))
+    .map_err(|error| TckFailure::new(check, format!("delete_messages_to({to_sequence_nr}) failed: {error}")))
+}
+
+fn highest<J: Journal>(check: &'static str, journal: &J, persistence_id: &str) -> Result<u64, TckFailure> {
+  block_on(journal.highest_sequence_nr(persistence_id))
+    .map_err(|error| TckFailure::new(check, format!("highest_sequence_nr failed: {error}")))
+}
+
+fn replay<J: Journal>(
+  check: &'static str,
+  journal: &J,
+  persistence_id: &str,
+  from_sequence_nr: u64,
+  to_sequence_nr: u64,
+  max: u64,
+) -> Result<Vec<(u64, String)>, TckFailure> {
+  let replayed = block_on(journal.replay_messages(persistence_id, from_sequence_nr, to_sequence_nr, max))
+    .map_err(|error| TckFailure::new(check, format!("replay failed: {error}")))?;
+  replayed
+    .iter()
+    .map(|repr| {
+      if repr.persistence_id() != persistence_id {
+        return Err(TckFailure::new(
+          check,
+          format!("replay of {persistence_id} returned an event of {}", repr.persistence_id()),
+        ));
+      }
+      let value = repr.downcast_ref::<String>().ok_or_else(|| {
+        TckFailure::new(check, format!("replayed event {} lost its String payload", repr.sequence_nr()))
+      })?;
+      Ok((repr.sequence_nr(), value.clone()))
+    })
+    .collect()
+}
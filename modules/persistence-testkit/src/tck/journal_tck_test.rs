@@ -0,0 +1,68 @@
+use core::future::{Ready, ready};
+
+use fraktor_persistence_core_kernel_rs::{
+  journal::{InMemoryJournal, Journal, JournalError},
+  persistent::AtomicWrite,
+};
+
+use super::JournalTck;
+
+/// Journal that acknowledges deletes without removing anything.
+#[derive(Default)]
+struct DeleteIgnoringJournal {
+  inner: InMemoryJournal,
+}
+
+impl Journal for DeleteIgnoringJournal {
+  type DeleteFuture<'a>
+    = Ready<Result<(), JournalError>>
+  where
+    Self: 'a;
+  type HighestSeqNrFuture<'a>
+    = <InMemoryJournal as Journal>::HighestSeqNrFuture<'a>
+  where
+    Self: 'a;
+  type ReplayFuture<'a>
+    = <InMemoryJournal as Journal>::ReplayFuture<'a>
+  where
+    Self: 'a;
+  type WriteFuture<'a>
+    = <InMemoryJournal as Journal>::WriteFuture<'a>
+  where
+    Self: 'a;
+
+  fn write_messages<'a>(&'a mut self, messages: &'a [AtomicWrite]) -> Self::WriteFuture<'a> {
+    self.inner.write_messages(messages)
+  }
+
+  fn replay_messages<'a>(
+    &'a self,
+    persistence_id: &'a str,
+    from_sequence_nr: u64,
+    to_sequence_nr: u64,
+    max: u64,
+  ) -> Self::ReplayFuture<'a> {
+    self.inner.replay_messages(persistence_id, from_sequence_nr, to_sequence_nr, max)
+  }
+
+  fn delete_messages_to<'a>(&'a mut self, _persistence_id: &'a str, _to_sequence_nr: u64) -> Self::DeleteFuture<'a> {
+    ready(Ok(()))
+  }
+
+  fn highest_sequence_nr<'a>(&'a self, persistence_id: &'a str) -> Self::HighestSeqNrFuture<'a> {
+    self.inner.highest_sequence_nr(persistence_id)
+  }
+}
+
+#[test]
+fn in_memory_journal_passes_the_journal_tck() {
+  JournalTck::new(InMemoryJournal::new).run().expect("in-memory journal conforms");
+}
+
+#[test]
+fn journal_ignoring_deletes_fails_the_delete_check() {
+  let failure = JournalTck::new(DeleteIgnoringJournal::default).run().expect_err("deletes are ignored");
+
+  assert_eq!(failure.check(), "delete_messages_to");
+  assert!(failure.reason().starts_with("replay after deleting to 3"));
+}
@@ -0,0 +1,165 @@
+//! Snapshot store conformance suite.
+
+#[cfg(test)]
+#[path = "snapshot_store_tck_test.rs"]
+mod tests;
+
+use core::any::Any;
+
+use fraktor_persistence_core_kernel_rs::snapshot::{SnapshotMetadata, SnapshotSelectionCriteria, SnapshotStore};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use crate::{
+  block_on::block_on,
+  tck::{TckFailure, expect_eq},
+};
+
+const PERSISTENCE_ID: &str = "tck-snapshot-a";
+const OTHER_PERSISTENCE_ID: &str = "tck-snapshot-b";
+const UNKNOWN_PERSISTENCE_ID: &str = "tck-snapshot-unknown";
+
+/// Conformance suite for [`SnapshotStore`] implementations.
+///
+/// Snapshots carry `String` payloads. Every check saves snapshots at sequence numbers 1, 3 and 5
+/// (timestamps 10, 30 and 50) for one persistence id plus one snapshot for another id, then
+/// verifies selection by [`SnapshotSelectionCriteria`] bounds and both delete operations.
+pub struct SnapshotStoreTck<F> {
+  factory: F,
+}
+
+impl<F> SnapshotStoreTck<F> {
+  /// Creates a suite that builds a fresh, empty snapshot store with `factory` for every check.
+  #[must_use]
+  pub const fn new(factory: F) -> Self {
+    Self { factory }
+  }
+}
+
+impl<F, S> SnapshotStoreTck<F>
+where
+  F: FnMut() -> S,
+  S: SnapshotStore,
+{
+  /// Runs every check and stops at the first violation.
+  ///
+  /// # Errors
+  ///
+  /// Returns a [`TckFailure`] naming the first contract the snapshot store violates.
+  pub fn run(&mut self) -> Result<(), TckFailure> {
+    self.check_load_latest()?;
+    self.check_selection_criteria()?;
+    self.check_delete_snapshot()?;
+    self.check_delete_snapshots()?;
+    Ok(())
+  }
+
+  fn seeded_store(&mut self, check: &'static str) -> Result<S, TckFailure> {
+    let mut store = (self.factory)();
+    for (sequence_nr, timestamp) in [(1, 10), (3, 30), (5, 50)] {
+      save(check, &mut store, PERSISTENCE_ID, sequence_nr, timestamp)?;
+    }
+    save(check, &mut store, OTHER_PERSISTENCE_ID, 9, 90)?;
+    Ok(store)
+  }
+
+  fn check_load_latest(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "load_latest";
+    let store = self.seeded_store(CHECK)?;
+    let latest = load(CHECK, &store, PERSISTENCE_ID, SnapshotSelectionCriteria::latest())?;
+    expect_eq(CHECK, "latest snapshot", &Some(loaded(PERSISTENCE_ID, 5, 50)), &latest)?;
+    let other = load(CHECK, &store, OTHER_PERSISTENCE_ID, SnapshotSelectionCriteria::latest())?;
+    expect_eq(CHECK, "latest snapshot of another id", &Some(loaded(OTHER_PERSISTENCE_ID, 9, 90)), &other)?;
+    let unknown = load(CHECK, &store, UNKNOWN_PERSISTENCE_ID, SnapshotSelectionCriteria::latest())?;
+    expect_eq(CHECK, "latest snapshot of an unknown id", &None, &unknown)?;
+    let none = load(CHECK, &store, PERSISTENCE_ID, SnapshotSelectionCriteria::none())?;
+    expect_eq(CHECK, "snapshot selected by none()", &None, &none)
+  }
+
+  fn check_selection_criteria(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "selection_criteria";
+    let store = self.seeded_store(CHECK)?;
+    let by_max_sequence_nr = load(CHECK, &store, PERSISTENCE_ID, criteria(4, u64::MAX, 0, 0))?;
+    expect_eq(CHECK, "max sequence number 4", &Some(loaded(PERSISTENCE_ID, 3, 30)), &by_max_sequence_nr)?;
+    let by_max_timestamp = load(CHECK, &store, PERSISTENCE_ID, criteria(u64::MAX, 20, 0, 0))?;
+    expect_eq(CHECK, "max timestamp 20", &Some(loaded(PERSISTENCE_ID, 1, 10)), &by_max_timestamp)?;
+    let by_min_sequence_nr = load(CHECK, &store, PERSISTENCE_ID, criteria(4, u64::MAX, 2, 0))?;
+    expect_eq(CHECK, "sequence numbers 2..=4", &Some(loaded(PERSISTENCE_ID, 3, 30)), &by_min_sequence_nr)?;
+    let by_min_timestamp = load(CHECK, &store, PERSISTENCE_ID, criteria(u64::MAX, u64::MAX, 0, 40))?;
+    expect_eq(CHECK, "min timestamp 40", &Some(loaded(PERSISTENCE_ID, 5, 50)), &by_min_timestamp)?;
+    let empty_window = load(CHECK, &store, PERSISTENCE_ID, criteria(u64::MAX, u64::MAX, 6, 0))?;
+    expect_eq(CHECK, "min sequence number 6", &None, &empty_window)
+  }
+
+  fn check_delete_snapshot(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "delete_snapshot";
+    let mut store = self.seeded_store(CHECK)?;
+    let metadata = SnapshotMetadata::new(PERSISTENCE_ID, 3, 30);
+    block_on(store.delete_snapshot(&metadata))
+      .map_err(|error| TckFailure::new(CHECK, format!("delete_snapshot failed: {error}")))?;
+    let below_latest = load(CHECK, &store, PERSISTENCE_ID, criteria(4, u64::MAX, 0, 0))?;
+    expect_eq(CHECK, "max sequence number 4 after the delete", &Some(loaded(PERSISTENCE_ID, 1, 10)), &below_latest)?;
+    let latest = load(CHECK, &store, PERSISTENCE_ID, SnapshotSelectionCriteria::latest())?;
+    expect_eq(CHECK, "latest snapshot after the delete", &Some(loaded(PERSISTENCE_ID, 5, 50)), &latest)
+  }
+
+  fn check_delete_snapshots(&mut self) -> Result<(), TckFailure> {
+    const CHECK: &str = "delete_snapshots";
+    let mut store = self.seeded_store(CHECK)?;
+    block_on(store.delete_snapshots(PERSISTENCE_ID, criteria(3, u64::MAX, 0, 0)))
+      .map_err(|error| TckFailure::new(CHECK, format!("delete_snapshots failed: {error}")))?;
+    let deleted = load(CHECK, &store, PERSISTENCE_ID, criteria(4, u64::MAX, 0, 0))?;
+    expect_eq(CHECK, "max sequence number 4 after the delete", &None, &deleted)?;
+    let latest = load(CHECK, &store, PERSISTENCE_ID, SnapshotSelectionCriteria::latest())?;
+    expect_eq(CHECK, "latest snapshot after the delete", &Some(loaded(PERSISTENCE_ID, 5, 50)), &latest)?;
+    let other = load(CHECK, &store, OTHER_PERSISTENCE_ID, SnapshotSelectionCriteria::latest())?;
+    expect_eq(CHECK, "another persistence id", &Some(loaded(OTHER_PERSISTENCE_ID, 9, 90)), &other)
+  }
+}
+
+const fn criteria(
+  max_sequence_nr: u64,
+  max_timestamp: u64,
+  min_sequence_nr: u64,
+  min_timestamp: u64,
+) -> SnapshotSelectionCriteria {
+  SnapshotSelectionCriteria::new(max_sequence_nr, max_timestamp, min_sequence_nr, min_timestamp)
+}
+
+fn payload(persistence_id: &str, sequence_nr: u64) -> String {
+  format!("{persistence_id}-snapshot-{sequence_nr}")
+}
+
+fn loaded(persistence_id: &str, sequence_nr: u64, timestamp: u64) -> (SnapshotMetadata, String) {
+  (SnapshotMetadata::new(persistence_id, sequence_nr, timestamp), payload(persistence_id, sequence_nr))
+}
+
+fn save<S: SnapshotStore>(
+  check: &'static str,
+  store: &mut S,
+  persistence_id: &str,
+  sequence_nr: u64,
+  timestamp: u64,
+) -> Result<(), TckFailure> {
+  let metadata = SnapshotMetadata::new(persistence_id, sequence_nr, timestamp);
+  let snapshot: ArcShared<dyn Any + Send + Sync> = ArcShared::new(payload(persistence_id, sequence_nr));
+  block_on(store.save_snapshot(metadata, snapshot))
+    .map_err(|error| TckFailure::new(check, format!("save_snapshot failed: {error}")))
+}
+
+fn load<S: SnapshotStore>(
+  check: &'static str,
+  store: &S,
+  persistence_id: &str,
+  criteria: SnapshotSelectionCriteria,
+) -> Result<Option<(SnapshotMetadata, String)>, TckFailure> {
+  let snapshot = block_on(store.load_snapshot(persistence_id, criteria))
+    .map_err(|error| TckFailure::new(check, format!("load_snapshot failed: {error}")))?;
+  snapshot
+    .map(|snapshot| {
+      let value = snapshot.downcast_ref::<String>().ok_or_else(|| {
+        TckFailure::new(check, format!("snapshot {} lost its String payload", snapshot.metadata().sequence_nr()))
+      })?;
+      Ok((snapshot.metadata().clone(), value.clone()))
+    })
+    .transpose()
+}
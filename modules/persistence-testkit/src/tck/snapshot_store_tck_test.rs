@@ -0,0 +1,76 @@
+use core::{
+  any::Any,
+  future::{Ready, ready},
+};
+
+use fraktor_persistence_core_kernel_rs::snapshot::{
+  InMemorySnapshotStore, Snapshot, SnapshotError, SnapshotMetadata, SnapshotSelectionCriteria, SnapshotStore,
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::SnapshotStoreTck;
+
+/// Snapshot store that ignores the selection criteria and always loads the newest snapshot.
+#[derive(Default)]
+struct LatestOnlySnapshotStore {
+  inner: InMemorySnapshotStore,
+}
+
+impl SnapshotStore for LatestOnlySnapshotStore {
+  type DeleteManyFuture<'a>
+    = <InMemorySnapshotStore as SnapshotStore>::DeleteManyFuture<'a>
+  where
+    Self: 'a;
+  type DeleteOneFuture<'a>
+    = <InMemorySnapshotStore as SnapshotStore>::DeleteOneFuture<'a>
+  where
+    Self: 'a;
+  type LoadFuture<'a>
+    = Ready<Result<Option<Snapshot>, SnapshotError>>
+  where
+    Self: 'a;
+  type SaveFuture<'a>
+    = <InMemorySnapshotStore as SnapshotStore>::SaveFuture<'a>
+  where
+    Self: 'a;
+
+  fn save_snapshot<'a>(
+    &'a mut self,
+    metadata: SnapshotMetadata,
+    snapshot: ArcShared<dyn Any + Send + Sync>,
+  ) -> Self::SaveFuture<'a> {
+    self.inner.save_snapshot(metadata, snapshot)
+  }
+
+  fn load_snapshot<'a>(&'a self, persistence_id: &'a str, criteria: SnapshotSelectionCriteria) -> Self::LoadFuture<'a> {
+    if criteria == SnapshotSelectionCriteria::none() {
+      return ready(Ok(None));
+    }
+    self.inner.load_snapshot(persistence_id, SnapshotSelectionCriteria::latest())
+  }
+
+  fn delete_snapshot<'a>(&'a mut self, metadata: &'a SnapshotMetadata) -> Self::DeleteOneFuture<'a> {
+    self.inner.delete_snapshot(metadata)
+  }
+
+  fn delete_snapshots<'a>(
+    &'a mut self,
+    persistence_id: &'a str,
+    criteria: SnapshotSelectionCriteria,
+  ) -> Self::DeleteManyFuture<'a> {
+    self.inner.delete_snapshots(persistence_id, criteria)
+  }
+}
+
+#[test]
+fn in_memory_snapshot_store_passes_the_snapshot_store_tck() {
+  SnapshotStoreTck::new(InMemorySnapshotStore::new).run().expect("in-memory snapshot store conforms");
+}
+
+#[test]
+fn snapshot_store_ignoring_criteria_fails_the_selection_check() {
+  let failure = SnapshotStoreTck::new(LatestOnlySnapshotStore::default).run().expect_err("criteria are ignored");
+
+  assert_eq!(failure.check(), "selection_criteria");
+  assert!(failure.reason().starts_with("max sequence number 4"));
+}
@@ -0,0 +1,40 @@
+//! Conformance check failure.
+
+#[cfg(test)]
+#[path = "tck_failure_test.rs"]
+mod tests;
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Describes the first conformance check a plugin failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TckFailure {
+  check:  &'static str,
+  reason: String,
+}
+
+impl TckFailure {
+  /// Creates a failure for the named check.
+  #[must_use]
+  pub fn new(check: &'static str, reason: impl Into<String>) -> Self {
+    Self { check, reason: reason.into() }
+  }
+
+  /// Returns the name of the failed check.
+  #[must_use]
+  pub const fn check(&self) -> &'static str {
+    self.check
+  }
+
+  /// Returns why the check failed.
+  #[must_use]
+  pub fn reason(&self) -> &str {
+    &self.reason
+  }
+}
+
+impl Display for TckFailure {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+    write!(formatter, "{}: {}", self.check, self.reason)
+  }
+}
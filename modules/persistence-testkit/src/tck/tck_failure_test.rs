@@ -0,0 +1,10 @@
+use super::TckFailure;
+
+#[test]
+fn tck_failure_exposes_check_and_reason() {
+  let failure = TckFailure::new("replay_order", "expected [1, 2], got [2, 1]");
+
+  assert_eq!(failure.check(), "replay_order");
+  assert_eq!(failure.reason(), "expected [1, 2], got [2, 1]");
+  assert_eq!(failure.to_string(), "replay_order: expected [1, 2], got [2, 1]");
+}
@@ -0,0 +1,11 @@
+//! Typed event-sourced harness package.
+
+mod command_result;
+mod event_sourced_test_kit;
+mod event_sourced_test_kit_error;
+mod test_kit_guardian;
+mod test_kit_guardian_command;
+
+pub use command_result::CommandResult;
+pub use event_sourced_test_kit::EventSourcedTestKit;
+pub use event_sourced_test_kit_error::EventSourcedTestKitError;
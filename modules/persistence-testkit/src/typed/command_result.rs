@@ -0,0 +1,39 @@
+//! Outcome of a command run through the event-sourced harness.
+
+/// Events a command persisted, the state after applying them, and the reply it produced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandResult<S, E, R = ()> {
+  events: Vec<E>,
+  state:  S,
+  reply:  R,
+}
+
+impl<S, E, R> CommandResult<S, E, R> {
+  pub(crate) const fn new(events: Vec<E>, state: S, reply: R) -> Self {
+    Self { events, state, reply }
+  }
+
+  /// Returns the events persisted while handling the command, in sequence order.
+  #[must_use]
+  pub fn events(&self) -> &[E] {
+    &self.events
+  }
+
+  /// Returns `true` when the command persisted no event.
+  #[must_use]
+  pub const fn has_no_events(&self) -> bool {
+    self.events.is_empty()
+  }
+
+  /// Returns the state after applying the persisted events.
+  #[must_use]
+  pub const fn state(&self) -> &S {
+    &self.state
+  }
+
+  /// Returns the reply the entity sent for the command.
+  #[must_use]
+  pub const fn reply(&self) -> &R {
+    &self.reply
+  }
+}
@@ -0,0 +1,280 @@
+//! Harness running one event-sourced effector against an in-memory journal.
+
+#[cfg(test)]
+#[path = "event_sourced_test_kit_test.rs"]
+mod tests;
+
+use core::time::Duration;
+use std::{thread, time::Instant};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::actor::{
+  error::ActorError, extension::ExtensionInstallers, scheduler::SchedulerConfig, setup::ActorSystemConfig,
+};
+use fraktor_actor_core_typed_rs::{Behavior, TypedActorRef, TypedActorSystem, TypedProps};
+use fraktor_persistence_core_kernel_rs::{
+  extension::PersistenceExtensionInstaller,
+  journal::{Journal, SharedInMemoryJournal, Tagged},
+  persistent::PersistentRepr,
+  snapshot::InMemorySnapshotStore,
+};
+use fraktor_persistence_core_typed_rs::{EventSourcedEffector, EventSourcedEffectorConfig, PersistenceMode};
+use fraktor_utils_core_rs::sync::{DefaultMutex, SharedLock};
+
+use crate::{
+  block_on::block_on,
+  journal::FaultyJournal,
+  typed::{
+    CommandResult, EventSourcedTestKitError, test_kit_guardian::TestKitGuardian,
+    test_kit_guardian_command::TestKitGuardianCommand,
+  },
+};
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Runs an [`EventSourcedEffector`] entity and reports what each command persisted.
+///
+/// The entity runs in persisted mode on a dedicated actor system with the inline executor, backed
+/// by a [`FaultyJournal`] around an [`SharedInMemoryJournal`] and an in-memory snapshot store. A
+/// command sent with [`run_command`](Self::run_command) is therefore handled, together with the
+/// journal writes it triggers, before the call returns. The harness reads the events the command
+/// appended from the journal and folds them into its copy of the state with the config's event
+/// handler.
+///
+/// The entity is spawned under a guardian, so [`restart`](Self::restart) stops it and recovers a
+/// new incarnation from the same journal. Dropping the harness terminates the actor system.
+pub struct EventSourcedTestKit<S, E, M>
+where
+  M: Send + Sync + 'static, {
+  system:      TypedActorSystem<TestKitGuardianCommand<M>>,
+  entity:      TypedActorRef<M>,
+  journal:     FaultyJournal<SharedInMemoryJournal>,
+  config:      EventSourcedEffectorConfig<S, E, M>,
+  recovered:   SharedLock<Option<S>>,
+  state:       S,
+  sequence_nr: u64,
+}
+
+impl<S, E, M> EventSourcedTestKit<S, E, M>
+where
+  S: Clone + Send + Sync + 'static,
+  E: Clone + Send + Sync + 'static,
+  M: Send + Sync + 'static,
+{
+  /// Starts the entity described by `config` and `on_ready` against an empty journal.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when the actor system cannot start or the entity does not recover in time.
+  pub fn new<F>(config: EventSourcedEffectorConfig<S, E, M>, on_ready: F) -> Result<Self, EventSourcedTestKitError>
+  where
+    F: Fn(S, EventSourcedEffector<S, E, M>) -> Result<Behavior<M>, ActorError> + Send + Sync + 'static, {
+    Self::with_journal(FaultyJournal::new(SharedInMemoryJournal::new()), config, on_ready)
+  }
+
+  /// Starts the entity against `journal`, recovering any events it already holds.
+  ///
+  /// Keep a clone of `journal` to seed events or inject faults; the harness shares its storage
+  /// and fault queue.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when the actor system cannot start or the entity does not recover in time.
+  pub fn with_journal<F>(
+    journal: FaultyJournal<SharedInMemoryJournal>,
+    config: EventSourcedEffectorConfig<S, E, M>,
+    on_ready: F,
+  ) -> Result<Self, EventSourcedTestKitError>
+  where
+    F: Fn(S, EventSourcedEffector<S, E, M>) -> Result<Behavior<M>, ActorError> + Send + Sync + 'static, {
+    let config = config.with_persistence_mode(PersistenceMode::Persisted);
+    let recovered = SharedLock::new_with_driver::<DefaultMutex<_>>(None);
+    let entity_props = {
+      let recovered = recovered.clone();
+      EventSourcedEffector::props(config.clone(), move |state: S, effector| {
+        recovered.with_lock(|slot| *slot = Some(state.clone()));
+        on_ready(state, effector)
+      })
+    };
+    let guardian_props = TypedProps::new(move || TestKitGuardian::new(entity_props.clone()));
+    let installer = PersistenceExtensionInstaller::new(journal.clone(), InMemorySnapshotStore::new());
+    let system_config = ActorSystemConfig::new(TestTickDriver::default())
+      .with_scheduler_config(SchedulerConfig::default().with_runner_api_enabled(true))
+      .with_extension_installers(ExtensionInstallers::default().with_extension_installer(installer));
+    let system = TypedActorSystem::create_from_props(&guardian_props, system_config)
+      .map_err(|error| EventSourcedTestKitError::SystemStartFailed(format!("{error:?}")))?;
+
+    let entity = spawn_entity(&system)?;
+    let state = await_recovery(&recovered)?;
+    let mut kit = Self { system, entity, journal, config, recovered, state, sequence_nr: 0 };
+    kit.sequence_nr = kit.highest_sequence_nr()?;
+    Ok(kit)
+  }
+
+  /// Sends `command` to the entity and returns the events it persisted.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when the journal cannot be read or holds an event of another type.
+  pub fn run_command(&mut self, command: M) -> Result<CommandResult<S, E>, EventSourcedTestKitError> {
+    self.entity.tell(command);
+    let events = self.take_new_events()?;
+    Ok(CommandResult::new(events, self.state.clone(), ()))
+  }
+
+  /// Sends the command built by `build` and waits for the reply sent to its `reply_to`.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when no reply arrives in time, the reply cannot be taken, or the journal
+  /// cannot be read.
+  pub fn run_command_with_reply<R, B>(&mut self, build: B) -> Result<CommandResult<S, E, R>, EventSourcedTestKitError>
+  where
+    R: Send + Sync + 'static,
+    B: FnOnce(TypedActorRef<R>) -> M, {
+    let response = self.entity.ask::<R, _>(build);
+    let mut future = response.future().clone();
+    if !wait_until(|| future.is_ready()) {
+      return Err(EventSourcedTestKitError::ReplyTimedOut);
+    }
+    let reply = future
+      .try_take()
+      .ok_or(EventSourcedTestKitError::ReplyTimedOut)?
+      .map_err(|error| EventSourcedTestKitError::ReplyFailed(format!("{error:?}")))?;
+    let events = self.take_new_events()?;
+    Ok(CommandResult::new(events, self.state.clone(), reply))
+  }
+
+  /// Stops the entity and recovers a new incarnation from the journal.
+  ///
+  /// The harness state is replaced by the state the new incarnation recovered.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when the entity cannot be respawned or does not recover in time.
+  pub fn restart(&mut self) -> Result<(), EventSourcedTestKitError> {
+    self.recovered.with_lock(|slot| *slot = None);
+    self.system.user_guardian_ref().tell(TestKitGuardianCommand::Stop);
+    self.entity = spawn_entity(&self.system)?;
+    self.state = await_recovery(&self.recovered)?;
+    self.sequence_nr = self.highest_sequence_nr()?;
+    Ok(())
+  }
+
+  /// Returns every event currently stored for the entity, in sequence order.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error when the journal cannot be read or holds an event of another type.
+  pub fn persisted_events(&self) -> Result<Vec<E>, EventSourcedTestKitError> {
+    let reprs = block_on(self.journal.inner().replay_messages(self.persistence_id(), 1, u64::MAX, 0))
+      .map_err(EventSourcedTestKitError::Journal)?;
+    reprs.iter().map(downcast_event).collect()
+  }
+
+  /// Returns the state after the last command or recovery.
+  #[must_use]
+  pub const fn state(&self) -> &S {
+    &self.state
+  }
+
+  /// Returns the highest sequence number the harness has observed.
+  #[must_use]
+  pub const fn sequence_nr(&self) -> u64 {
+    self.sequence_nr
+  }
+
+  /// Returns the persistence id of the entity.
+  #[must_use]
+  pub fn persistence_id(&self) -> &str {
+    self.config.persistence_id().as_str()
+  }
+
+  /// Returns a reference to the current entity incarnation.
+  #[must_use]
+  pub fn entity_ref(&self) -> TypedActorRef<M> {
+    self.entity.clone()
+  }
+
+  /// Returns the journal backing the entity, for seeding events or injecting faults.
+  #[must_use]
+  pub const fn journal(&self) -> &FaultyJournal<SharedInMemoryJournal> {
+    &self.journal
+  }
+
+  fn highest_sequence_nr(&self) -> Result<u64, EventSourcedTestKitError> {
+    block_on(self.journal.inner().highest_sequence_nr(self.persistence_id())).map_err(EventSourcedTestKitError::Journal)
+  }
+
+  fn take_new_events(&mut self) -> Result<Vec<E>, EventSourcedTestKitError> {
+    let highest = self.highest_sequence_nr()?;
+    if highest <= self.sequence_nr {
+      return Ok(Vec::new());
+    }
+    // フォールト注入の対象はエンティティの操作だけに限定するため、ラップ元のジャーナルを直接読む。
+    let reprs = block_on(self.journal.inner().replay_messages(self.persistence_id(), self.sequence_nr + 1, highest, 0))
+      .map_err(EventSourcedTestKitError::Journal)?;
+    let events = reprs.iter().map(downcast_event).collect::<Result<Vec<E>, _>>()?;
+    for event in &events {
+      self.state = self.config.apply_event(&self.state, event);
+    }
+    self.sequence_nr = highest;
+    Ok(events)
+  }
+}
+
+impl<S, E, M> Drop for EventSourcedTestKit<S, E, M>
+where
+  M: Send + Sync + 'static,
+{
+  fn drop(&mut self) {
+    // must-ignore: Drop
+    // から返す先がなく、失敗するのは既に停止処理中のシステムだけなので後続に影響しない。
+    drop(self.system.terminate());
+  }
+}
+
+fn spawn_entity<M>(
+  system: &TypedActorSystem<TestKitGuardianCommand<M>>,
+) -> Result<TypedActorRef<M>, EventSourcedTestKitError>
+where
+  M: Send + Sync + 'static, {
+  let response =
+    system.user_guardian_ref().ask::<TypedActorRef<M>, _>(|reply_to| TestKitGuardianCommand::Spawn { reply_to });
+  let mut future = response.future().clone();
+  if !wait_until(|| future.is_ready()) {
+    return Err(EventSourcedTestKitError::SystemStartFailed(String::from("entity spawn timed out")));
+  }
+  future
+    .try_take()
+    .ok_or_else(|| EventSourcedTestKitError::SystemStartFailed(String::from("entity spawn reply missing")))?
+    .map_err(|error| EventSourcedTestKitError::SystemStartFailed(format!("entity spawn failed: {error:?}")))
+}
+
+fn await_recovery<S: Send + 'static>(recovered: &SharedLock<Option<S>>) -> Result<S, EventSourcedTestKitError> {
+  let mut state = None;
+  wait_until(|| {
+    state = recovered.with_lock(Option::take);
+    state.is_some()
+  });
+  state.ok_or(EventSourcedTestKitError::RecoveryTimedOut)
+}
+
+fn wait_until(mut predicate: impl FnMut() -> bool) -> bool {
+  let deadline = Instant::now() + RESPONSE_TIMEOUT;
+  while Instant::now() < deadline {
+    if predicate() {
+      return true;
+    }
+    thread::yield_now();
+  }
+  predicate()
+}
+
+fn downcast_event<E: Clone + 'static>(repr: &PersistentRepr) -> Result<E, EventSourcedTestKitError> {
+  repr
+    .downcast_ref::<E>()
+    .or_else(|| repr.downcast_ref::<Tagged>().and_then(Tagged::downcast_ref::<E>))
+    .cloned()
+    .ok_or(EventSourcedTestKitError::UnexpectedEventType { sequence_nr: repr.sequence_nr() })
+}
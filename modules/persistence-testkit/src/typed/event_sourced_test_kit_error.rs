@@ -0,0 +1,44 @@
+//! Event-sourced harness errors.
+
+#[cfg(test)]
+#[path = "event_sourced_test_kit_error_test.rs"]
+mod tests;
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+use fraktor_persistence_core_kernel_rs::journal::JournalError;
+
+/// Errors reported by [`EventSourcedTestKit`](super::EventSourcedTestKit).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventSourcedTestKitError {
+  /// The actor system hosting the entity could not be started.
+  SystemStartFailed(String),
+  /// The entity did not finish recovery in time.
+  RecoveryTimedOut,
+  /// The entity did not reply in time.
+  ReplyTimedOut,
+  /// The reply could not be taken from the ask future.
+  ReplyFailed(String),
+  /// Reading the journal failed.
+  Journal(JournalError),
+  /// A journaled event does not have the effector's event type.
+  UnexpectedEventType {
+    /// Sequence number of the offending event.
+    sequence_nr: u64,
+  },
+}
+
+impl Display for EventSourcedTestKitError {
+  fn fmt(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      | EventSourcedTestKitError::SystemStartFailed(reason) => write!(formatter, "system start failed: {}", reason),
+      | EventSourcedTestKitError::RecoveryTimedOut => write!(formatter, "entity recovery timed out"),
+      | EventSourcedTestKitError::ReplyTimedOut => write!(formatter, "entity reply timed out"),
+      | EventSourcedTestKitError::ReplyFailed(reason) => write!(formatter, "entity reply failed: {}", reason),
+      | EventSourcedTestKitError::Journal(error) => write!(formatter, "journal read failed: {}", error),
+      | EventSourcedTestKitError::UnexpectedEventType { sequence_nr } => {
+        write!(formatter, "journaled event {} has an unexpected type", sequence_nr)
+      },
+    }
+  }
+}
@@ -0,0 +1,16 @@
+use fraktor_persistence_core_kernel_rs::journal::JournalError;
+
+use super::EventSourcedTestKitError;
+
+#[test]
+fn event_sourced_test_kit_error_display_names_the_cause() {
+  assert_eq!(EventSourcedTestKitError::ReplyTimedOut.to_string(), "entity reply timed out");
+  assert_eq!(
+    EventSourcedTestKitError::Journal(JournalError::ReadFailed(String::from("disk"))).to_string(),
+    "journal read failed: read failed: disk"
+  );
+  assert_eq!(
+    EventSourcedTestKitError::UnexpectedEventType { sequence_nr: 3 }.to_string(),
+    "journaled event 3 has an unexpected type"
+  );
+}
@@ -0,0 +1,165 @@
+use core::any::Any;
+
+use fraktor_actor_core_typed_rs::{Behavior, TypedActorRef, dsl::Behaviors};
+use fraktor_persistence_core_kernel_rs::{
+  journal::{Journal, SharedInMemoryJournal},
+  persistent::{AtomicWrite, PersistentRepr},
+};
+use fraktor_persistence_core_typed_rs::{
+  EventSourcedEffector, EventSourcedEffectorConfig, EventSourcedEffectorMessageAdapter, EventSourcedEffectorSignal,
+  PersistenceId,
+};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+use super::EventSourcedTestKit;
+use crate::{block_on::block_on, journal::FaultyJournal};
+
+const PERSISTENCE_ID: &str = "test-kit-counter";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Counter {
+  value: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum CounterEvent {
+  Added(i32),
+}
+
+enum CounterCommand {
+  Add(i32),
+  AddTwice(i32),
+  AddAndReply { delta: i32, reply_to: TypedActorRef<i32> },
+  Read { reply_to: TypedActorRef<i32> },
+  Persistence(EventSourcedEffectorSignal<Counter, CounterEvent>),
+}
+
+type Effector = EventSourcedEffector<Counter, CounterEvent, CounterCommand>;
+
+fn apply(state: &Counter, event: &CounterEvent) -> Counter {
+  let CounterEvent::Added(delta) = event;
+  Counter { value: state.value + delta }
+}
+
+fn config() -> EventSourcedEffectorConfig<Counter, CounterEvent, CounterCommand> {
+  let adapter = EventSourcedEffectorMessageAdapter::new(CounterCommand::Persistence, |message| match message {
+    | CounterCommand::Persistence(signal) => Some(signal),
+    | _ => None,
+  });
+  EventSourcedEffectorConfig::new(PersistenceId::of_unique_id(PERSISTENCE_ID), Counter { value: 0 }, apply)
+    .with_message_adapter(adapter)
+}
+
+fn counter(state: Counter, effector: Effector) -> Behavior<CounterCommand> {
+  Behaviors::receive_message(move |ctx, message| match message {
+    | CounterCommand::Add(delta) => {
+      let event = CounterEvent::Added(*delta);
+      let next = apply(&state, &event);
+      let effector_next = effector.clone();
+      effector.persist_event(ctx, event, move |_event| Ok(counter(next, effector_next)))
+    },
+    | CounterCommand::AddTwice(delta) => {
+      let events = vec![CounterEvent::Added(*delta), CounterEvent::Added(*delta)];
+      let next = events.iter().fold(state.clone(), |state, event| apply(&state, event));
+      let effector_next = effector.clone();
+      effector.persist_events(ctx, events, move |_events| Ok(counter(next, effector_next)))
+    },
+    | CounterCommand::AddAndReply { delta, reply_to } => {
+      let event = CounterEvent::Added(*delta);
+      let next = apply(&state, &event);
+      let effector_next = effector.clone();
+      let mut reply_to = reply_to.clone();
+      effector.persist_event(ctx, event, move |_event| {
+        reply_to.tell(next.value);
+        Ok(counter(next, effector_next))
+      })
+    },
+    | CounterCommand::Read { reply_to } => {
+      let mut reply_to = reply_to.clone();
+      reply_to.tell(state.value);
+      Ok(Behaviors::same())
+    },
+    | CounterCommand::Persistence(_) => Ok(Behaviors::unhandled()),
+  })
+}
+
+fn start() -> EventSourcedTestKit<Counter, CounterEvent, CounterCommand> {
+  EventSourcedTestKit::new(config(), |state, effector| Ok(counter(state, effector))).expect("start test kit")
+}
+
+#[test]
+fn run_command_reports_persisted_events_and_folded_state() {
+  let mut kit = start();
+
+  let first = kit.run_command(CounterCommand::Add(3)).expect("add");
+  let second = kit.run_command(CounterCommand::AddTwice(2)).expect("add twice");
+
+  assert_eq!(first.events(), &[CounterEvent::Added(3)]);
+  assert_eq!(first.state(), &Counter { value: 3 });
+  assert_eq!(second.events(), &[CounterEvent::Added(2), CounterEvent::Added(2)]);
+  assert_eq!(second.state(), &Counter { value: 7 });
+  assert_eq!(kit.sequence_nr(), 3);
+  assert_eq!(kit.persisted_events().expect("persisted events").len(), 3);
+}
+
+#[test]
+fn run_command_with_reply_returns_the_reply_and_read_only_commands_persist_nothing() {
+  let mut kit = start();
+
+  let added = kit.run_command_with_reply(|reply_to| CounterCommand::AddAndReply { delta: 5, reply_to }).expect("add");
+  let read = kit.run_command_with_reply(|reply_to| CounterCommand::Read { reply_to }).expect("read");
+
+  assert_eq!(*added.reply(), 5);
+  assert_eq!(added.events(), &[CounterEvent::Added(5)]);
+  assert_eq!(*read.reply(), 5);
+  assert!(read.has_no_events());
+  assert_eq!(read.state(), &Counter { value: 5 });
+}
+
+#[test]
+fn restart_recovers_the_state_from_the_journal() {
+  let mut kit = start();
+  kit.run_command(CounterCommand::Add(4)).expect("add");
+  kit.run_command(CounterCommand::Add(6)).expect("add");
+
+  kit.restart().expect("restart");
+  let read = kit.run_command_with_reply(|reply_to| CounterCommand::Read { reply_to }).expect("read");
+
+  assert_eq!(kit.state(), &Counter { value: 10 });
+  assert_eq!(*read.reply(), 10);
+  assert_eq!(kit.persistence_id(), PERSISTENCE_ID);
+}
+
+#[test]
+fn with_journal_recovers_seeded_events() {
+  let mut journal = SharedInMemoryJournal::new();
+  let event: ArcShared<dyn Any + Send + Sync> = ArcShared::new(CounterEvent::Added(8));
+  let write = AtomicWrite::new(vec![PersistentRepr::new(PERSISTENCE_ID, 1, event)]).expect("atomic write");
+  block_on(journal.write_messages(&[write])).expect("seed journal");
+
+  let mut kit = EventSourcedTestKit::with_journal(FaultyJournal::new(journal), config(), |state, effector| {
+    Ok(counter(state, effector))
+  })
+  .expect("start test kit");
+  let result = kit.run_command(CounterCommand::Add(1)).expect("add");
+
+  assert_eq!(result.state(), &Counter { value: 9 });
+  assert_eq!(kit.sequence_nr(), 2);
+}
+
+#[test]
+fn injected_write_failure_persists_nothing_until_the_entity_restarts() {
+  let mut kit = start();
+  kit.run_command(CounterCommand::Add(2)).expect("add");
+  // ジャーナルアクタの既定リトライ (1 回) を使い切るまで失敗させる。
+  kit.journal().fail_next_writes(2);
+
+  let failed = kit.run_command(CounterCommand::Add(5)).expect("failed add");
+  kit.restart().expect("restart");
+  let recovered = kit.run_command(CounterCommand::Add(1)).expect("add after restart");
+
+  assert!(failed.has_no_events());
+  assert_eq!(failed.state(), &Counter { value: 2 });
+  assert_eq!(recovered.events(), &[CounterEvent::Added(1)]);
+  assert_eq!(recovered.state(), &Counter { value: 3 });
+}
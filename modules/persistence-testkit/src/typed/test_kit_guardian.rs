@@ -0,0 +1,56 @@
+//! Guardian that owns the entity under test.
+
+use fraktor_actor_core_kernel_rs::actor::error::ActorError;
+use fraktor_actor_core_typed_rs::{
+  TypedProps,
+  actor::{TypedActor, TypedActorContext, TypedChildRef},
+};
+
+use crate::typed::test_kit_guardian_command::TestKitGuardianCommand;
+
+/// Spawns and stops the entity as a child so the harness can restart it inside one system.
+pub(crate) struct TestKitGuardian<M>
+where
+  M: Send + Sync + 'static, {
+  entity_props: TypedProps<M>,
+  entity:       Option<TypedChildRef<M>>,
+}
+
+impl<M> TestKitGuardian<M>
+where
+  M: Send + Sync + 'static,
+{
+  pub(crate) const fn new(entity_props: TypedProps<M>) -> Self {
+    Self { entity_props, entity: None }
+  }
+}
+
+impl<M> TypedActor<TestKitGuardianCommand<M>> for TestKitGuardian<M>
+where
+  M: Send + Sync + 'static,
+{
+  fn receive(
+    &mut self,
+    ctx: &mut TypedActorContext<'_, TestKitGuardianCommand<M>>,
+    message: &TestKitGuardianCommand<M>,
+  ) -> Result<(), ActorError> {
+    match message {
+      | TestKitGuardianCommand::Spawn { reply_to } => {
+        let entity = ctx
+          .spawn_child(&self.entity_props)
+          .map_err(|error| ActorError::recoverable(format!("entity spawn failed: {error:?}")))?;
+        let mut reply_to = reply_to.clone();
+        reply_to.tell(entity.actor_ref());
+        self.entity = Some(entity);
+      },
+      | TestKitGuardianCommand::Stop => {
+        if let Some(entity) = self.entity.take() {
+          // must-ignore:
+          // 永続化失敗などで既に停止したエンティティだけが停止要求を拒否し、再起動には影響しない。
+          drop(entity.stop());
+        }
+      },
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,13 @@
+//! Commands understood by the harness guardian.
+
+use fraktor_actor_core_typed_rs::TypedActorRef;
+
+/// Messages the harness sends to its guardian to manage the entity under test.
+pub(crate) enum TestKitGuardianCommand<M>
+where
+  M: Send + Sync + 'static, {
+  /// Spawns a fresh entity and replies with its reference.
+  Spawn { reply_to: TypedActorRef<TypedActorRef<M>> },
+  /// Stops the current entity, if any.
+  Stop,
+}
@@ -0,0 +1,100 @@
+use std::{
+  env, fs,
+  io::ErrorKind,
+  path::{Path, PathBuf},
+  process,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use fraktor_actor_core_kernel_rs::serialization::{
+  builtin, default_serialization_setup, serialization_registry::SerializationRegistry,
+};
+use fraktor_persistence_adaptor_std_rs::{
+  journal::{LocalJournal, LocalJournalConfig},
+  snapshot::{LocalSnapshotStore, LocalSnapshotStoreConfig},
+  state::{LocalDurableStateStore, LocalDurableStateStoreConfig},
+};
+use fraktor_persistence_core_kernel_rs::serialization::register_persistence_serializers;
+use fraktor_persistence_testkit_rs::tck::{DurableStateStoreTck, JournalTck, SnapshotStoreTck};
+use fraktor_utils_core_rs::sync::ArcShared;
+
+fn serialization_registry() -> ArcShared<SerializationRegistry> {
+  let setup = default_serialization_setup();
+  let registry = ArcShared::new(SerializationRegistry::from_setup(&setup));
+  builtin::register_defaults(&registry, |name, id| panic!("unexpected serializer collision for {name}: {id:?}"))
+    .expect("register builtin serializers");
+  register_persistence_serializers(&registry).expect("register persistence serializers");
+  registry
+}
+
+fn unique_root(name: &str) -> PathBuf {
+  let timestamp = match SystemTime::now().duration_since(UNIX_EPOCH) {
+    | Ok(duration) => duration.as_nanos(),
+    | Err(error) => panic!("system clock should be after unix epoch: {error}"),
+  };
+  env::temp_dir().join(format!("fraktor-persistence-tck-{name}-{}-{timestamp}", process::id()))
+}
+
+fn remove_dir_if_exists(path: &Path) {
+  match fs::remove_dir_all(path) {
+    | Ok(()) => (),
+    | Err(error) if error.kind() == ErrorKind::NotFound => (),
+    | Err(error) => panic!("tck directory should be removable: {error}"),
+  }
+}
+
+/// Returns a factory that opens each instance in a fresh sub-directory of `root`.
+fn fresh_directories(root: &Path) -> impl FnMut() -> PathBuf + '_ {
+  let mut next = 0;
+  move || {
+    next += 1;
+    root.join(format!("instance-{next}"))
+  }
+}
+
+#[test]
+fn local_journal_passes_the_journal_tck() {
+  let root = unique_root("journal");
+  let registry = serialization_registry();
+  let mut directories = fresh_directories(&root);
+
+  let result = JournalTck::new(|| {
+    LocalJournal::open(LocalJournalConfig::new(directories(), registry.clone())).expect("open local journal")
+  })
+  .run();
+
+  remove_dir_if_exists(&root);
+  result.expect("local journal conforms");
+}
+
+#[test]
+fn local_snapshot_store_passes_the_snapshot_store_tck() {
+  let root = unique_root("snapshot");
+  let registry = serialization_registry();
+  let mut directories = fresh_directories(&root);
+
+  let result = SnapshotStoreTck::new(|| {
+    LocalSnapshotStore::open(LocalSnapshotStoreConfig::new(directories(), registry.clone()))
+      .expect("open local snapshot store")
+  })
+  .run();
+
+  remove_dir_if_exists(&root);
+  result.expect("local snapshot store conforms");
+}
+
+#[test]
+fn local_durable_state_store_passes_the_durable_state_store_tck() {
+  let root = unique_root("state");
+  let registry = serialization_registry();
+  let mut directories = fresh_directories(&root);
+
+  let result = DurableStateStoreTck::new(|| {
+    LocalDurableStateStore::<String>::open(LocalDurableStateStoreConfig::new(directories(), registry.clone()))
+      .expect("open local durable state store")
+  })
+  .run_with_changes();
+
+  remove_dir_if_exists(&root);
+  result.expect("local durable state store conforms");
+}
@@ -4,14 +4,18 @@ extern crate std;
 #[path = "file_io_test.rs"]
 mod tests;
 
+use core::time::Duration;
 use std::{
-  fs::{self, File, OpenOptions},
+  boxed::Box,
+  fs::{File, OpenOptions},
   io::{BufWriter, Error, ErrorKind, Read, Seek, SeekFrom, Write},
   path::{Path, PathBuf},
+  time::Instant,
+  vec::Vec,
 };
 
 use fraktor_stream_core_kernel_rs::{
-  DemandTracker, DynValue, IOResult, SinkDecision, SinkLogic, StreamError,
+  DemandTracker, DynValue, IOResult, SinkDecision, SinkLogic, SourceLogic, StreamError,
   dsl::{Sink, Source},
   materialization::StreamFuture,
   stage::StageKind,
@@ -19,38 +23,39 @@ use fraktor_stream_core_kernel_rs::{
 
 use super::super::io_error_to_stream_error;
 
+/// Chunk size used by [`FileIO::from_path`].
+///
+/// Mirrors Pekko's default `chunkSize` of `FileIO.fromPath`.
+const DEFAULT_CHUNK_SIZE: usize = 8192;
+
 /// File IO utilities for reading and writing byte streams from/to files.
 ///
 /// Corresponds to Pekko's `FileIO` object. Provides `from_path` and `to_path`
-/// factory methods that produce sources and sinks with `IOResult` materialized
-/// values.
+/// factory methods that produce sources and sinks of `Vec<u8>` chunks
+/// (mirroring Pekko's `ByteString`) with [`StreamFuture<IOResult>`]
+/// materialized values.
 pub struct FileIO;
 
 impl FileIO {
-  /// Creates a source that reads all bytes from a file at the given path.
-  ///
-  /// The file is read eagerly when the source is constructed. Bytes are emitted
-  /// as individual `u8` elements. The materialized value is an [`IOResult`]
-  /// containing the number of bytes read and the completion status.
+  /// Creates a source that reads a file at the given path in chunks.
   ///
-  /// On read failure, the source emits zero elements and the materialized
-  /// `IOResult` records the error.
+  /// Equivalent to [`from_path_with_options`](Self::from_path_with_options)
+  /// with an 8 KiB chunk size starting at the beginning of the file.
   #[must_use]
-  pub fn from_path<P: AsRef<Path>>(path: P) -> Source<u8, IOResult> {
-    match fs::read(path.as_ref()) {
-      | Ok(bytes) => {
-        let count = bytes.len() as u64;
-        Source::from_iterator(bytes).map_materialized_value(move |_| IOResult::successful(count))
-      },
-      | Err(e) => Source::empty().map_materialized_value(move |_| IOResult::failed(0, io_error_to_stream_error(&e))),
-    }
+  pub fn from_path<P: AsRef<Path>>(path: P) -> Source<Vec<u8>, StreamFuture<IOResult>> {
+    Self::from_path_with_options(path, DEFAULT_CHUNK_SIZE, 0)
   }
 
-  /// Creates a source that reads bytes from a file with configurable options.
+  /// Creates a source that reads a file in chunks starting at `start_position`.
   ///
-  /// Reads all bytes starting from `start_position`, using `chunk_size` as the
-  /// internal read buffer size. If the start position is past the end of the
-  /// file, the source emits zero elements.
+  /// The file is opened on the first pull and read lazily, one chunk of at
+  /// most `chunk_size` bytes per downstream demand, so only one chunk is held
+  /// in memory at a time. If the start position is past the end of the file,
+  /// the source emits zero elements.
+  ///
+  /// The materialized value is a [`StreamFuture<IOResult>`] that completes
+  /// with the number of bytes read when the source reaches the end of the
+  /// file, fails, or is cancelled.
   ///
   /// Corresponds to Pekko's `FileIO.fromPath(f, chunkSize, startPosition)`.
   #[must_use]
@@ -58,82 +63,59 @@ impl FileIO {
     path: P,
     chunk_size: usize,
     start_position: u64,
-  ) -> Source<u8, IOResult> {
-    if chunk_size == 0 {
-      let error = Error::new(ErrorKind::InvalidInput, "chunk_size must be greater than 0");
-      return Source::empty().map_materialized_value(move |_| IOResult::failed(0, io_error_to_stream_error(&error)));
-    }
-
-    let result = (|| -> Result<Vec<u8>, Error> {
-      let mut file = File::open(path.as_ref())?;
-      file.seek(SeekFrom::Start(start_position))?;
-      let mut all_bytes = Vec::new();
-      let mut buf = vec![0u8; chunk_size];
-      loop {
-        let n = file.read(&mut buf)?;
-        if n == 0 {
-          break;
-        }
-        all_bytes.extend_from_slice(&buf[..n]);
-      }
-      Ok(all_bytes)
-    })();
+  ) -> Source<Vec<u8>, StreamFuture<IOResult>> {
+    Self::read_source(path.as_ref().to_path_buf(), chunk_size, start_position, None)
+  }
 
-    match result {
-      | Ok(bytes) => {
-        let count = bytes.len() as u64;
-        Source::from_iterator(bytes).map_materialized_value(move |_| IOResult::successful(count))
-      },
-      | Err(e) => Source::empty().map_materialized_value(move |_| IOResult::failed(0, io_error_to_stream_error(&e))),
-    }
+  /// Creates a source that follows a growing file, emitting appended bytes.
+  ///
+  /// Reads like [`from_path_with_options`](Self::from_path_with_options), but
+  /// reaching the end of the file does not complete the stream. Instead the
+  /// source checks the file for new bytes at most once per `poll_interval`
+  /// and emits them as they are appended, like `tail -f`. The stream only
+  /// ends when it is cancelled or a read fails; the materialized
+  /// [`IOResult`] then reports every byte read so far.
+  ///
+  /// Corresponds to Alpakka's `FileTailSource(path, maxChunkSize,
+  /// startingPosition, pollingInterval)`.
+  #[must_use]
+  pub fn tail_path<P: AsRef<Path>>(
+    path: P,
+    chunk_size: usize,
+    start_position: u64,
+    poll_interval: Duration,
+  ) -> Source<Vec<u8>, StreamFuture<IOResult>> {
+    Self::read_source(path.as_ref().to_path_buf(), chunk_size, start_position, Some(poll_interval))
   }
 
-  /// Creates a sink that writes received bytes to a file at the given path.
+  /// Creates a sink that writes received byte chunks to a file at the given
+  /// path.
   ///
-  /// The file is opened when the stream starts. Each byte is written through a
-  /// buffered writer so that intermediate data is flushed to disk incrementally
-  /// rather than accumulated entirely in memory. The materialized value is a
-  /// [`StreamFuture<IOResult>`] that completes with the number of bytes
-  /// written and the completion status.
+  /// The file is opened when the stream starts. Each received `Vec<u8>` chunk
+  /// is written through a buffered writer so that intermediate data is
+  /// flushed to disk incrementally rather than accumulated entirely in memory.
+  /// The materialized value is a [`StreamFuture<IOResult>`] that completes
+  /// with the number of bytes written and the completion status.
   ///
-  /// The file is created (or truncated) at the given path. On write failure the
-  /// `IOResult` records the error with a byte count of zero.
+  /// The file is created (or truncated) at the given path. If the file cannot
+  /// be opened the `IOResult` records the error with a byte count of zero.
   #[must_use]
-  pub fn to_path<P: AsRef<Path>>(path: P) -> Sink<u8, StreamFuture<IOResult>> {
-    let path_buf = path.as_ref().to_path_buf();
-    let completion = StreamFuture::new();
-    let logic = WriteToPathSinkLogic {
-      path:           path_buf,
-      options:        None,
-      start_position: None,
-      writer:         None,
-      count:          0,
-      completion:     completion.clone(),
-    };
-    Sink::from_definition(StageKind::Custom, logic, completion)
+  pub fn to_path<P: AsRef<Path>>(path: P) -> Sink<Vec<u8>, StreamFuture<IOResult>> {
+    Self::write_sink(path.as_ref().to_path_buf(), None, None)
   }
 
-  /// Creates a sink that writes received bytes to a file with the given open
-  /// options.
+  /// Creates a sink that writes received byte chunks to a file with the given
+  /// open options.
   ///
   /// The `options` parameter controls how the file is opened (e.g. append,
   /// create, truncate). Corresponds to Pekko's `FileIO.toPath(f, options)`.
   #[must_use]
-  pub fn to_path_with_options<P: AsRef<Path>>(path: P, options: OpenOptions) -> Sink<u8, StreamFuture<IOResult>> {
-    let path_buf = path.as_ref().to_path_buf();
-    let completion = StreamFuture::new();
-    let logic = WriteToPathSinkLogic {
-      path:           path_buf,
-      options:        Some(options),
-      start_position: None,
-      writer:         None,
-      count:          0,
-      completion:     completion.clone(),
-    };
-    Sink::from_definition(StageKind::Custom, logic, completion)
+  pub fn to_path_with_options<P: AsRef<Path>>(path: P, options: OpenOptions) -> Sink<Vec<u8>, StreamFuture<IOResult>> {
+    Self::write_sink(path.as_ref().to_path_buf(), Some(options), None)
   }
 
-  /// Creates a sink that writes received bytes to a file at the given position.
+  /// Creates a sink that writes received byte chunks to a file at the given
+  /// position.
   ///
   /// The `start_position` parameter specifies the byte offset at which writing
   /// begins. Corresponds to Pekko's `FileIO.toPath(f, options, startPosition)`.
@@ -142,21 +124,139 @@ impl FileIO {
     path: P,
     options: OpenOptions,
     start_position: u64,
-  ) -> Sink<u8, StreamFuture<IOResult>> {
-    let path_buf = path.as_ref().to_path_buf();
+  ) -> Sink<Vec<u8>, StreamFuture<IOResult>> {
+    Self::write_sink(path.as_ref().to_path_buf(), Some(options), Some(start_position))
+  }
+
+  fn read_source(
+    path: PathBuf,
+    chunk_size: usize,
+    start_position: u64,
+    poll_interval: Option<Duration>,
+  ) -> Source<Vec<u8>, StreamFuture<IOResult>> {
     let completion = StreamFuture::new();
-    let logic = WriteToPathSinkLogic {
-      path:           path_buf,
-      options:        Some(options),
-      start_position: Some(start_position),
-      writer:         None,
-      count:          0,
-      completion:     completion.clone(),
-    };
+    if chunk_size == 0 {
+      let error = Error::new(ErrorKind::InvalidInput, "chunk_size must be greater than 0");
+      completion.complete(Ok(IOResult::failed(0, io_error_to_stream_error(&error))));
+      return Source::empty().map_materialized_value(move |_| completion);
+    }
+
+    Source::from_logic(StageKind::Custom, ReadFromPathSourceLogic {
+      path,
+      chunk_size,
+      start_position,
+      poll_interval,
+      next_poll: None,
+      file: None,
+      total_bytes: 0,
+      completion: completion.clone(),
+      done: false,
+    })
+    .map_materialized_value(move |_| completion)
+  }
+
+  fn write_sink(
+    path: PathBuf,
+    options: Option<OpenOptions>,
+    start_position: Option<u64>,
+  ) -> Sink<Vec<u8>, StreamFuture<IOResult>> {
+    let completion = StreamFuture::new();
+    let logic =
+      WriteToPathSinkLogic { path, options, start_position, writer: None, count: 0, completion: completion.clone() };
     Sink::from_definition(StageKind::Custom, logic, completion)
   }
 }
 
+struct ReadFromPathSourceLogic {
+  path:           PathBuf,
+  chunk_size:     usize,
+  start_position: u64,
+  /// `Some` のときは追従モードで、ファイル末尾に達しても完了せずに再読み込みを待つ。
+  poll_interval:  Option<Duration>,
+  next_poll:      Option<Instant>,
+  file:           Option<File>,
+  total_bytes:    u64,
+  completion:     StreamFuture<IOResult>,
+  done:           bool,
+}
+
+impl ReadFromPathSourceLogic {
+  fn open(&mut self) -> Result<(), Error> {
+    let mut file = File::open(&self.path)?;
+    file.seek(SeekFrom::Start(self.start_position))?;
+    self.file = Some(file);
+    Ok(())
+  }
+
+  fn finish(&mut self, io_result: IOResult) {
+    self.done = true;
+    self.file = None;
+    self.completion.complete(Ok(io_result));
+  }
+
+  fn fail(&mut self, error: &Error) -> StreamError {
+    let error = io_error_to_stream_error(error);
+    self.finish(IOResult::failed(self.total_bytes, error.clone()));
+    error
+  }
+}
+
+impl SourceLogic for ReadFromPathSourceLogic {
+  fn pull(&mut self) -> Result<Option<DynValue>, StreamError> {
+    if self.done {
+      return Ok(None);
+    }
+    if let Some(next_poll) = self.next_poll {
+      if Instant::now() < next_poll {
+        return Err(StreamError::WouldBlock);
+      }
+      self.next_poll = None;
+    }
+    if self.file.is_none()
+      && let Err(e) = self.open()
+    {
+      return Err(self.fail(&e));
+    }
+    let Some(file) = &mut self.file else {
+      return Ok(None);
+    };
+
+    let mut buf = vec![0u8; self.chunk_size];
+    match file.read(&mut buf) {
+      | Ok(0) => match self.poll_interval {
+        | Some(poll_interval) => {
+          // 追記を待つ。次回の確認までは読み込みを行わない。
+          self.next_poll = Some(Instant::now() + poll_interval);
+          Err(StreamError::WouldBlock)
+        },
+        | None => {
+          self.finish(IOResult::successful(self.total_bytes));
+          Ok(None)
+        },
+      },
+      | Ok(n) => {
+        self.total_bytes += n as u64;
+        buf.truncate(n);
+        Ok(Some(Box::new(buf)))
+      },
+      | Err(e) if e.kind() == ErrorKind::Interrupted => Err(StreamError::WouldBlock),
+      | Err(e) => Err(self.fail(&e)),
+    }
+  }
+
+  fn on_cancel(&mut self) -> Result<(), StreamError> {
+    if !self.done {
+      self.finish(IOResult::successful(self.total_bytes));
+    }
+    Ok(())
+  }
+
+  fn should_drain_on_shutdown(&self) -> bool {
+    // 追従モードのソースは終端しないため、シャットダウン時は即座にキャンセルする。
+    self.poll_interval.is_none()
+  }
+}
+
 struct WriteToPathSinkLogic {
   path:           PathBuf,
   options:        Option<OpenOptions>,
@@ -166,31 +266,43 @@ struct WriteToPathSinkLogic {
   completion:     StreamFuture<IOResult>,
 }
 
-impl SinkLogic for WriteToPathSinkLogic {
-  fn on_start(&mut self, demand: &mut DemandTracker) -> Result<(), StreamError> {
-    let mut file = if let Some(options) = self.options.take() {
-      options.open(&self.path).map_err(|e| io_error_to_stream_error(&e))?
-    } else {
-      File::create(&self.path).map_err(|e| io_error_to_stream_error(&e))?
+impl WriteToPathSinkLogic {
+  fn open(&mut self) -> Result<File, Error> {
+    let mut file = match self.options.take() {
+      | Some(options) => options.open(&self.path)?,
+      | None => File::create(&self.path)?,
     };
     if let Some(pos) = self.start_position {
-      file.seek(SeekFrom::Start(pos)).map_err(|e| io_error_to_stream_error(&e))?;
+      file.seek(SeekFrom::Start(pos))?;
+    }
+    Ok(file)
+  }
+}
+
+impl SinkLogic for WriteToPathSinkLogic {
+  fn on_start(&mut self, demand: &mut DemandTracker) -> Result<(), StreamError> {
+    match self.open() {
+      | Ok(file) => self.writer = Some(BufWriter::new(file)),
+      | Err(e) => {
+        let error = io_error_to_stream_error(&e);
+        self.completion.complete(Ok(IOResult::failed(0, error.clone())));
+        return Err(error);
+      },
     }
-    self.writer = Some(BufWriter::new(file));
     demand.request(1)
   }
 
   fn on_push(&mut self, input: DynValue, demand: &mut DemandTracker) -> Result<SinkDecision, StreamError> {
-    let byte = *input.downcast::<u8>().map_err(|_| StreamError::TypeMismatch)?;
+    let chunk = *input.downcast::<Vec<u8>>().map_err(|_| StreamError::TypeMismatch)?;
     let Some(writer) = &mut self.writer else {
       // writer が既に破棄されている場合は即完了。
       return Ok(SinkDecision::Complete);
     };
-    if let Err(e) = writer.write_all(&[byte]) {
+    if let Err(e) = writer.write_all(&chunk) {
       self.writer = None;
       return Err(io_error_to_stream_error(&e));
     }
-    self.count += 1;
+    self.count += chunk.len() as u64;
     demand.request(1)?;
     Ok(SinkDecision::Continue)
   }
@@ -1,8 +1,172 @@
 extern crate std;
 
-use std::{env, fs::OpenOptions};
+use core::time::Duration;
+use std::{
+  env,
+  fs::{self, OpenOptions},
+  io::{Read, Write},
+  path::PathBuf,
+  process,
+  time::Instant,
+  vec::Vec,
+};
+
+use fraktor_actor_adaptor_std_rs::tick_driver::TestTickDriver;
+use fraktor_actor_core_kernel_rs::{
+  actor::{
+    Actor, ActorContext, error::ActorError, messaging::AnyMessageView, props::Props, scheduler::SchedulerConfig,
+    setup::ActorSystemConfig,
+  },
+  system::ActorSystem,
+};
+use fraktor_stream_core_kernel_rs::{
+  IOResult, KillSwitches,
+  dsl::{Sink, Source},
+  materialization::{ActorMaterializer, ActorMaterializerConfig, Completion, KeepBoth, KeepRight, StreamFuture},
+};
 
 use super::FileIO;
+use crate::io::StreamConverters;
+
+// ---------------------------------------------------------------------------
+// テストハーネス
+// ---------------------------------------------------------------------------
+
+struct GuardianActor;
+
+impl Actor for GuardianActor {
+  fn receive(&mut self, _ctx: &mut ActorContext<'_>, _message: AnyMessageView<'_>) -> Result<(), ActorError> {
+    Ok(())
+  }
+}
+
+fn build_materializer() -> ActorMaterializer {
+  let props = Props::from_fn(|| GuardianActor);
+  let scheduler = SchedulerConfig::default().with_runner_api_enabled(true);
+  let config = ActorSystemConfig::new(TestTickDriver::default()).with_scheduler_config(scheduler);
+  let system = ActorSystem::create_from_props(&props, config).expect("system should build");
+  let mut materializer =
+    ActorMaterializer::new(system, ActorMaterializerConfig::default().with_drive_interval(Duration::from_millis(1)));
+  materializer.start().expect("materializer start");
+  materializer
+}
+
+fn await_completion<T: Clone + Send + 'static>(completion: &StreamFuture<T>) -> T {
+  let deadline = Instant::now() + Duration::from_secs(5);
+  loop {
+    match completion.value() {
+      | Completion::Ready(Ok(value)) => return value,
+      | Completion::Ready(Err(error)) => panic!("completion failed: {error:?}"),
+      | Completion::Pending => {},
+    }
+    assert!(Instant::now() < deadline, "completion did not complete within timeout");
+    std::thread::yield_now();
+  }
+}
+
+fn temp_path(name: &str) -> PathBuf {
+  env::temp_dir().join(format!("fraktor_file_io_{name}_{}.bin", process::id()))
+}
+
+fn read_all(path: &PathBuf, chunk_size: usize, start_position: u64) -> (IOResult, Vec<Vec<u8>>) {
+  let mut materializer = build_materializer();
+  let graph = FileIO::from_path_with_options(path, chunk_size, start_position).into_mat(Sink::collect(), KeepBoth);
+  let materialized = graph.run(&mut materializer).expect("materialize");
+  let (io_completion, chunks_completion) = materialized.materialized();
+  (await_completion(io_completion), await_completion(chunks_completion))
+}
+
+// ---------------------------------------------------------------------------
+// ソース
+// ---------------------------------------------------------------------------
+
+#[test]
+fn from_path_with_options_emits_chunks_and_reports_byte_count() {
+  let path = temp_path("chunks");
+  fs::write(&path, b"0123456789").expect("write fixture");
+
+  let (io_result, chunks) = read_all(&path, 4, 0);
+
+  assert!(io_result.was_successful());
+  assert_eq!(io_result.count(), 10);
+  assert_eq!(chunks, vec![b"0123".to_vec(), b"4567".to_vec(), b"89".to_vec()]);
+  fs::remove_file(&path).ok();
+}
+
+#[test]
+fn from_path_with_options_starts_at_start_position() {
+  let path = temp_path("start_position");
+  fs::write(&path, b"0123456789").expect("write fixture");
+
+  let (io_result, chunks) = read_all(&path, 8, 6);
+  let (past_end, past_end_chunks) = read_all(&path, 8, 20);
+
+  assert_eq!(io_result.count(), 4);
+  assert_eq!(chunks, vec![b"6789".to_vec()]);
+  assert!(past_end.was_successful());
+  assert_eq!(past_end.count(), 0);
+  assert!(past_end_chunks.is_empty());
+  fs::remove_file(&path).ok();
+}
+
+#[test]
+fn from_path_reports_failed_io_result_for_missing_file() {
+  let path = temp_path("missing");
+  fs::remove_file(&path).ok();
+  let mut materializer = build_materializer();
+
+  let graph = FileIO::from_path(&path).into_mat(Sink::ignore(), KeepBoth);
+  let materialized = graph.run(&mut materializer).expect("materialize");
+  let io_result = await_completion(&materialized.materialized().0);
+
+  assert!(!io_result.was_successful());
+  assert_eq!(io_result.count(), 0);
+}
+
+#[test]
+fn from_path_with_options_rejects_zero_chunk_size() {
+  let path = temp_path("zero_chunk");
+  fs::write(&path, b"data").expect("write fixture");
+
+  let (io_result, chunks) = read_all(&path, 0, 0);
+
+  assert!(!io_result.was_successful());
+  assert!(chunks.is_empty());
+  fs::remove_file(&path).ok();
+}
+
+#[test]
+fn tail_path_emits_appended_bytes_until_shut_down() {
+  let path = temp_path("tail");
+  fs::write(&path, b"head").expect("write fixture");
+  let mut materializer = build_materializer();
+
+  let source = FileIO::tail_path(&path, 16, 0, Duration::from_millis(5)).via_mat(KillSwitches::single(), KeepBoth);
+  let graph = source.into_mat(StreamConverters::as_input_stream(Duration::from_secs(5)), KeepBoth);
+  let materialized = graph.run(&mut materializer).expect("materialize");
+  let ((io_completion, switch), mut reader) = materialized.into_materialized();
+
+  let mut head = [0u8; 4];
+  reader.read_exact(&mut head).expect("read head");
+  let mut file = OpenOptions::new().append(true).open(&path).expect("open for append");
+  file.write_all(b"-tail").expect("append");
+  let mut tail = [0u8; 5];
+  reader.read_exact(&mut tail).expect("read appended bytes");
+  // ファイル末尾に達しても追従中のソースは完了しない。
+  assert!(matches!(io_completion.value(), Completion::Pending));
+  switch.shutdown();
+  let io_result = await_completion(&io_completion);
+
+  assert_eq!(&head, b"head");
+  assert_eq!(&tail, b"-tail");
+  assert!(io_result.was_successful());
+  assert_eq!(io_result.count(), 9);
+  fs::remove_file(&path).ok();
+}
+
+// ---------------------------------------------------------------------------
+// シンク
+// ---------------------------------------------------------------------------
 
 #[test]
 fn to_path_constructs_sink_without_panicking() {
@@ -28,3 +192,56 @@ fn to_path_with_position_constructs_sink_without_panicking() {
   options.create(true).write(true);
   let _sink = FileIO::to_path_with_position(&path, options, 0);
 }
+
+#[test]
+fn to_path_writes_chunks_and_reports_byte_count() {
+  let path = temp_path("to_path");
+  let mut materializer = build_materializer();
+
+  let chunks = vec![b"abc".to_vec(), Vec::new(), b"defg".to_vec()];
+  let graph = Source::from_iterator(chunks).into_mat(FileIO::to_path(&path), KeepRight);
+  let materialized = graph.run(&mut materializer).expect("materialize");
+  let io_result = await_completion(materialized.materialized());
+
+  assert!(io_result.was_successful());
+  assert_eq!(io_result.count(), 7);
+  assert_eq!(fs::read(&path).expect("read written file"), b"abcdefg");
+  fs::remove_file(&path).ok();
+}
+
+#[test]
+fn to_path_with_position_overwrites_from_offset() {
+  let path = temp_path("to_path_position");
+  fs::write(&path, b"0123456789").expect("write fixture");
+  let mut materializer = build_materializer();
+  let mut options = OpenOptions::new();
+  options.write(true);
+
+  let sink = FileIO::to_path_with_position(&path, options, 3);
+  let graph = Source::single(b"xyz".to_vec()).into_mat(sink, KeepRight);
+  let materialized = graph.run(&mut materializer).expect("materialize");
+  let io_result = await_completion(materialized.materialized());
+
+  assert_eq!(io_result.count(), 3);
+  assert_eq!(fs::read(&path).expect("read written file"), b"012xyz6789");
+  fs::remove_file(&path).ok();
+}
+
+#[test]
+fn file_round_trip_preserves_contents() {
+  let source_path = temp_path("round_trip_source");
+  let target_path = temp_path("round_trip_target");
+  let payload: Vec<u8> = (0..=255u8).cycle().take(10_000).collect();
+  fs::write(&source_path, &payload).expect("write fixture");
+  let mut materializer = build_materializer();
+
+  let graph = FileIO::from_path_with_options(&source_path, 1024, 0).into_mat(FileIO::to_path(&target_path), KeepBoth);
+  let materialized = graph.run(&mut materializer).expect("materialize");
+  let (read, written) = materialized.materialized();
+
+  assert_eq!(await_completion(read).count(), 10_000);
+  assert_eq!(await_completion(written).count(), 10_000);
+  assert_eq!(fs::read(&target_path).expect("read copy"), payload);
+  fs::remove_file(&source_path).ok();
+  fs::remove_file(&target_path).ok();
+}